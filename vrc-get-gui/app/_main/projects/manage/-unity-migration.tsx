@@ -129,12 +129,16 @@ export async function unityVersionChange({
 		}
 		dialog.replace(<MigrationMigratingDialog header={header} />);
 
-		if (
-			data.isVRC &&
-			data.kind === "upgradeMajor" &&
-			targetUnityVersion.startsWith("2022.")
-		) {
-			await commands.projectMigrateProjectTo2022(migrateProjectPath);
+		if (data.isVRC && data.kind === "upgradeMajor") {
+			if (targetUnityVersion.startsWith("2022.")) {
+				await commands.projectMigrateProjectTo2022(migrateProjectPath);
+			} else {
+				// Unity 6 and later upgrades the SDK packages for the new Unity
+				await commands.projectMigrateUnityVersion(
+					migrateProjectPath,
+					targetUnityVersion,
+				);
+			}
 		}
 
 		const finalizeResult = await dialog.askClosing(
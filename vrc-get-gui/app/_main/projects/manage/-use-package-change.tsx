@@ -42,6 +42,9 @@ export type RequestedOperation =
 	| {
 			type: "reinstallAll";
 	  }
+	| {
+			type: "addVpmResolver";
+	  }
 	| {
 			type: "remove";
 			displayName: string;
@@ -120,10 +123,23 @@ export async function applyChanges(
 			// close window
 			return;
 		}
-		await commands.projectApplyPendingChanges(
-			projectPath,
-			changes.changes_version,
-		);
+		try {
+			await commands.projectApplyPendingChanges(
+				projectPath,
+				changes.changes_version,
+				changes.classification,
+				false,
+			);
+		} catch (e) {
+			if (!(isHandleable(e) && e.body.type === "ProjectOpenInUnity")) throw e;
+			if (!(await openSingleDialog(ProjectOpenInUnityDialog, {}))) return;
+			await commands.projectApplyPendingChanges(
+				projectPath,
+				changes.changes_version,
+				changes.classification,
+				true,
+			);
+		}
 		showToast(operation);
 	} catch (e) {
 		if (isHandleable(e) && e.body.type === "MissingDependencies") {
@@ -155,6 +171,8 @@ function createChanges(
 		case "resolve":
 		case "reinstallAll":
 			return commands.projectResolve(projectPath);
+		case "addVpmResolver":
+			return commands.projectAddVpmResolver(projectPath);
 		case "remove":
 			return commands.projectRemovePackages(projectPath, [operation.packageId]);
 		case "bulkInstalled":
@@ -222,6 +240,9 @@ function showToast(requested: RequestedOperation) {
 		case "reinstallAll":
 			toastSuccess(tt("projects:manage:toast:all packages reinstalled"));
 			break;
+		case "addVpmResolver":
+			toastSuccess(tt("projects:manage:toast:vpm resolver added"));
+			break;
 		case "upgradeAll":
 			toastSuccess(tt("projects:manage:toast:all packages upgraded"));
 			if (requested.hasUnityIncompatibleLatest) {
@@ -269,6 +290,8 @@ function ProjectChangesDialog({
 	const unlockedConflicts = changes.conflicts.flatMap(
 		([_, c]) => c.unlocked_names,
 	);
+	const { removals, legacy_deletions, downgrades } = changes.classification;
+	const isAdditive = !removals && !legacy_deletions && !downgrades;
 
 	const TypographyItem = ({ children }: { children: React.ReactNode }) => (
 		<div className={"p-3"}>
@@ -493,6 +516,26 @@ function ProjectChangesDialog({
 						</div>
 					</>
 				) : null}
+				{changes.downgrades.length > 0 ? (
+					<>
+						<p className={"text-destructive"}>
+							{tc("projects:manage:dialog:packages are downgraded")}
+						</p>
+						<div className={"flex flex-col gap-1 p-2"}>
+							{changes.downgrades.map((downgrade) => (
+								<TypographyItem key={downgrade.name}>
+									{tc("projects:manage:dialog:downgrade package", {
+										name:
+											installingPackageById.get(downgrade.name)?.InstallNew
+												?.display_name ?? downgrade.name,
+										previous: toVersionString(downgrade.previous_version),
+										version: toVersionString(downgrade.version),
+									})}
+								</TypographyItem>
+							))}
+						</div>
+					</>
+				) : null}
 				{unlockedConflicts.length > 0 ? (
 					<>
 						<p className={"text-destructive"}>
@@ -512,7 +555,10 @@ function ProjectChangesDialog({
 				<Button onClick={() => dialog.close(false)} className="mr-1">
 					{tc("general:button:cancel")}
 				</Button>
-				<Button onClick={() => dialog.close(true)} variant={"destructive"}>
+				<Button
+					onClick={() => dialog.close(true)}
+					variant={isAdditive ? "default" : "destructive"}
+				>
 					{tc("projects:manage:button:apply")}
 				</Button>
 			</DialogFooter>
@@ -555,3 +601,31 @@ function MissingDependenciesDialog({
 		</div>
 	);
 }
+
+function ProjectOpenInUnityDialog({
+	dialog,
+}: {
+	dialog: DialogContext<boolean>;
+}) {
+	return (
+		<div>
+			<DialogTitle className={"text-destructive"}>
+				<CircleAlert className="size-6 inline" />{" "}
+				{tc("projects:manage:dialog:project open in unity")}
+			</DialogTitle>
+			<DialogDescription>
+				<p className={"whitespace-normal"}>
+					{tc("projects:manage:dialog:project open in unity description")}
+				</p>
+			</DialogDescription>
+			<DialogFooter>
+				<Button onClick={() => dialog.close(false)} className="mr-1">
+					{tc("general:button:cancel")}
+				</Button>
+				<Button onClick={() => dialog.close(true)} variant={"destructive"}>
+					{tc("projects:manage:button:apply anyway")}
+				</Button>
+			</DialogFooter>
+		</div>
+	);
+}
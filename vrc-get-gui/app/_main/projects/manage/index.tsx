@@ -208,6 +208,9 @@ function PageBody() {
 				{detailsResult?.data?.should_resolve && (
 					<SuggestResolveProjectCard disabled={isLoading} />
 				)}
+				{detailsResult?.data?.vpm_resolver_missing && (
+					<SuggestVpmResolverCard disabled={isLoading} />
+				)}
 				<MigrationCards
 					isLoading={isLoading}
 					detailsResult={detailsResult.data}
@@ -345,6 +348,52 @@ function SuggestResolveProjectCard({
 	);
 }
 
+function SuggestVpmResolverCard({
+	disabled,
+}: {
+	disabled?: boolean;
+}) {
+	const { projectPath } = Route.useSearch();
+	const queryClient = useQueryClient();
+	const packageChange = useMutation(applyChangesMutation(projectPath));
+	const excludeVpmResolver = useMutation({
+		mutationFn: async () =>
+			await commands.projectSetVpmResolverExcluded(projectPath, true),
+		onError: (e) => {
+			console.error(e);
+			toastThrownError(e);
+		},
+		onSettled: async () => {
+			await queryClient.invalidateQueries({
+				queryKey: ["projectDetails", projectPath],
+			});
+		},
+	});
+
+	return (
+		<Card className={"shrink-0 p-2 flex flex-row items-center"}>
+			<p className="cursor-pointer py-1.5 font-bold grow-0 shrink overflow-hidden whitespace-normal text-sm">
+				{tc("projects:manage:suggest vpm resolver")}
+			</p>
+			<div className={"grow shrink-0 w-2"} />
+			<Button
+				variant={"ghost"}
+				onClick={() => excludeVpmResolver.mutate()}
+				disabled={disabled || excludeVpmResolver.isPending}
+			>
+				{tc("projects:manage:button:exclude vpm resolver")}
+			</Button>
+			<Button
+				variant={"ghost-destructive"}
+				onClick={() => packageChange.mutate({ type: "addVpmResolver" })}
+				disabled={disabled}
+			>
+				{tc("projects:manage:button:add vpm resolver")}
+			</Button>
+		</Card>
+	);
+}
+
 function MigrationCards({
 	isLoading,
 	detailsResult,
@@ -182,11 +182,17 @@ async projectReinstallPackages(projectPath: string, packageIds: string[]) : Prom
 async projectResolve(projectPath: string) : Promise<TauriPendingProjectChanges> {
     return await TAURI_INVOKE("project_resolve", { projectPath });
 },
+async projectAddVpmResolver(projectPath: string) : Promise<TauriPendingProjectChanges> {
+    return await TAURI_INVOKE("project_add_vpm_resolver", { projectPath });
+},
+async projectSetVpmResolverExcluded(projectPath: string, excluded: boolean) : Promise<null> {
+    return await TAURI_INVOKE("project_set_vpm_resolver_excluded", { projectPath, excluded });
+},
 async projectRemovePackages(projectPath: string, names: string[]) : Promise<TauriPendingProjectChanges> {
     return await TAURI_INVOKE("project_remove_packages", { projectPath, names });
 },
-async projectApplyPendingChanges(projectPath: string, changesVersion: number) : Promise<null> {
-    return await TAURI_INVOKE("project_apply_pending_changes", { projectPath, changesVersion });
+async projectApplyPendingChanges(projectPath: string, changesVersion: number, confirmed: TauriChangeClassification, ignoreUnityLock: boolean) : Promise<null> {
+    return await TAURI_INVOKE("project_apply_pending_changes", { projectPath, changesVersion, confirmed, ignoreUnityLock });
 },
 async projectClearPendingChanges() : Promise<null> {
     return await TAURI_INVOKE("project_clear_pending_changes");
@@ -194,6 +200,9 @@ async projectClearPendingChanges() : Promise<null> {
 async projectMigrateProjectTo2022(projectPath: string) : Promise<null> {
     return await TAURI_INVOKE("project_migrate_project_to_2022", { projectPath });
 },
+async projectMigrateUnityVersion(projectPath: string, unityVersion: string) : Promise<null> {
+    return await TAURI_INVOKE("project_migrate_unity_version", { projectPath, unityVersion });
+},
 async projectCallUnityForMigration(channel: string, projectPath: string, unityPath: string) : Promise<AsyncCallResult<string, TauriCallUnityForMigrationResult>> {
     return await TAURI_INVOKE("project_call_unity_for_migration", { channel, projectPath, unityPath });
 },
@@ -269,7 +278,7 @@ export type CheckForUpdateResponse = { version: number; current_version: string;
 /**
  * Errors that is expected to be handled on the GUI side
  */
-export type HandleableRustError = { type: "MissingDependencies"; dependencies: string[] }
+export type HandleableRustError = { type: "MissingDependencies"; dependencies: string[] } | { type: "ProjectOpenInUnity" }
 export type InstallUpgradeProgress = { type: "DownloadProgress"; received: number; total: number | null } | { type: "DownloadComplete" }
 export type LocalizableRustError = { id: string; args: { [key in string]: string } }
 export type LogEntry = { time: string; level: LogLevel; target: string; message: string; gui_toast: boolean }
@@ -282,10 +291,12 @@ export type TauriAddRepositoryResult = "BadUrl" | "Success"
 export type TauriAddUserPackageWithPickerResult = "NoFolderSelected" | "InvalidSelection" | "AlreadyAdded" | "Successful"
 export type TauriBasePackageInfo = { name: string; display_name: string | null; description: string | null; aliases: string[]; version: TauriVersion; unity: [number, number] | null; changelog_url: string | null; vpm_dependencies: string[]; legacy_packages: string[]; is_yanked: boolean }
 export type TauriCallUnityForMigrationResult = { type: "ExistsWithNonZero"; status: string } | { type: "FinishedSuccessfully" }
+export type TauriChangeClassification = { removals: boolean; legacy_deletions: boolean; downgrades: boolean }
 export type TauriConflictInfo = { packages: string[]; unity_conflict: boolean; unlocked_names: string[] }
 export type TauriCopyProjectForMigrationProgress = { total: number; proceed: number; last_proceed: string }
 export type TauriCreateBackupProgress = { total: number; proceed: number; last_proceed: string }
 export type TauriCreateProjectResult = "AlreadyExists" | "TemplateNotFound" | "Successful"
+export type TauriDowngrade = { name: string; version: TauriVersion; previous_version: TauriVersion }
 export type TauriDownloadRepository = { type: "BadUrl" } | { type: "Duplicated"; reason: TauriDuplicatedReason; duplicated_name: string } | { type: "DownloadError"; message: string } | { type: "Success"; value: TauriRemoteRepositoryInfo }
 export type TauriDuplicatedReason = "URLDuplicated" | "IDDuplicated"
 export type TauriEnvironmentSettings = { default_project_path: string; project_backup_path: string; unity_hub: string; unity_paths: ([string, string, boolean])[]; show_prerelease_packages: boolean; backup_format: string; release_channel: string; use_alcom_for_vcc_protocol: boolean; default_unity_arguments: string[] | null; gui_animation: boolean; unity_hub_access_method: UnityHubAccessMethod }
@@ -293,14 +304,14 @@ export type TauriImportRepositoryPickResult = { type: "NoFilePicked" } | { type:
 export type TauriPackage = ({ name: string; display_name: string | null; description: string | null; aliases: string[]; version: TauriVersion; unity: [number, number] | null; changelog_url: string | null; vpm_dependencies: string[]; legacy_packages: string[]; is_yanked: boolean }) & { env_version: number; index: number; source: TauriPackageSource }
 export type TauriPackageChange = { InstallNew: TauriBasePackageInfo } | { Remove: TauriRemoveReason }
 export type TauriPackageSource = "LocalUser" | { Remote: { id: string; display_name: string } }
-export type TauriPendingProjectChanges = { changes_version: number; package_changes: ([string, TauriPackageChange])[]; remove_legacy_files: string[]; remove_legacy_folders: string[]; conflicts: ([string, TauriConflictInfo])[] }
+export type TauriPendingProjectChanges = { changes_version: number; package_changes: ([string, TauriPackageChange])[]; remove_legacy_files: string[]; remove_legacy_folders: string[]; conflicts: ([string, TauriConflictInfo])[]; classification: TauriChangeClassification; downgrades: TauriDowngrade[] }
 export type TauriPickProjectBackupPathResult = { type: "NoFolderSelected" } | { type: "InvalidSelection" } | { type: "Successful" }
 export type TauriPickProjectDefaultPathResult = { type: "NoFolderSelected" } | { type: "InvalidSelection" } | { type: "Successful"; new_path: string }
 export type TauriPickUnityHubResult = { type: "NoFolderSelected" } | { type: "InvalidSelection" } | { type: "Successful" }
 export type TauriPickUnityResult = "NoFolderSelected" | "InvalidSelection" | "AlreadyAdded" | "Successful"
 export type TauriProject = { list_version: number; index: number; name: string; path: string; project_type: TauriProjectType; unity: string; unity_revision: string | null; last_modified: number; created_at: number; favorite: boolean; is_exists: boolean }
 export type TauriProjectCreationInformation = { templates: TauriProjectTemplate[]; default_path: string }
-export type TauriProjectDetails = { unity: [number, number] | null; unity_str: string | null; unity_revision: string | null; installed_packages: ([string, TauriBasePackageInfo])[]; should_resolve: boolean }
+export type TauriProjectDetails = { unity: [number, number] | null; unity_str: string | null; unity_revision: string | null; installed_packages: ([string, TauriBasePackageInfo])[]; should_resolve: boolean; vpm_resolver_missing: boolean }
 export type TauriProjectDirCheckResult = "InvalidNameForFolderName" | "MayCompatibilityProblem" | "WideChar" | "AlreadyExists" | "Ok"
 export type TauriProjectTemplate = { type: "Builtin"; id: string; name: string } | { type: "Custom"; name: string }
 export type TauriProjectType = "Unknown" | "LegacySdk2" | "LegacyWorlds" | "LegacyAvatars" | "UpmWorlds" | "UpmAvatars" | "UpmStarter" | "Worlds" | "Avatars" | "VpmStarter"
@@ -13,7 +13,7 @@ use vrc_get_vpm::PackageManifest;
 use vrc_get_vpm::environment::VccDatabaseConnection;
 use vrc_get_vpm::io::{DefaultEnvironmentIo, DefaultProjectIo};
 use vrc_get_vpm::unity_project::{
-    AddPackageErr, MigrateUnity2022Error, MigrateVpmError, ReinstalPackagesError, ResolvePackageErr,
+    AddPackageErr, ProjectOpenInUnityError, ReinstalPackagesError, ResolvePackageErr,
 };
 use vrc_get_vpm::version::Version;
 
@@ -47,7 +47,7 @@ mod util;
 mod prelude {
     pub(super) use super::{
         IntoPathBuf as _, RustError, TauriBasePackageInfo, UnityProject, load_project,
-        update_project_last_modified,
+        update_project_last_modified, update_project_type,
     };
     pub use crate::state::*;
 }
@@ -118,10 +118,13 @@ pub(crate) fn handlers() -> impl Fn(Invoke) -> bool + Send + Sync + 'static {
         project::project_install_packages,
         project::project_reinstall_packages,
         project::project_resolve,
+        project::project_add_vpm_resolver,
+        project::project_set_vpm_resolver_excluded,
         project::project_remove_packages,
         project::project_apply_pending_changes,
         project::project_clear_pending_changes,
         project::project_migrate_project_to_2022,
+        project::project_migrate_unity_version,
         project::project_call_unity_for_migration,
         project::project_migrate_project_to_vpm,
         project::project_open_unity,
@@ -209,10 +212,13 @@ pub(crate) fn export_ts() {
             project::project_install_packages,
             project::project_reinstall_packages,
             project::project_resolve,
+            project::project_add_vpm_resolver,
+            project::project_set_vpm_resolver_excluded,
             project::project_remove_packages,
             project::project_apply_pending_changes,
             project::project_clear_pending_changes,
             project::project_migrate_project_to_2022,
+            project::project_migrate_unity_version,
             project::project_call_unity_for_migration,
             project::project_migrate_project_to_vpm,
             project::project_open_unity,
@@ -243,7 +249,10 @@ pub(crate) fn export_ts() {
 }
 
 async fn update_project_last_modified(io: &DefaultEnvironmentIo, project_dir: &Path) {
-    async fn inner(io: &DefaultEnvironmentIo, project_dir: &Path) -> Result<(), io::Error> {
+    async fn inner(
+        io: &DefaultEnvironmentIo,
+        project_dir: &Path,
+    ) -> Result<(), vrc_get_vpm::Error> {
         let mut connection = VccDatabaseConnection::connect(io).await?;
         connection.update_project_last_modified(&project_dir.to_string_lossy())?;
         connection.save(io).await?;
@@ -255,6 +264,23 @@ async fn update_project_last_modified(io: &DefaultEnvironmentIo, project_dir: &P
     }
 }
 
+/// Updates the project type in the VCC database after migrating the project.
+async fn update_project_type(io: &DefaultEnvironmentIo, project: &UnityProject) {
+    async fn inner(
+        io: &DefaultEnvironmentIo,
+        project: &UnityProject,
+    ) -> Result<(), vrc_get_vpm::Error> {
+        let mut connection = VccDatabaseConnection::connect(io).await?;
+        connection.update_project_type(project).await?;
+        connection.save(io).await?;
+        Ok(())
+    }
+
+    if let Err(err) = inner(io, project).await {
+        eprintln!("error updating project type on vcc: {err}");
+    }
+}
+
 #[derive(Debug, Clone, Serialize, specta::Type)]
 #[specta(export)]
 #[serde(tag = "type")]
@@ -280,7 +306,11 @@ struct LocalizableRustError {
 #[derive(Debug, Clone, Serialize, specta::Type)]
 #[serde(tag = "type")]
 enum HandleableRustError {
-    MissingDependencies { dependencies: Vec<Box<str>> },
+    MissingDependencies {
+        dependencies: Vec<Box<str>>,
+    },
+    /// The project is open in Unity. The GUI can ask the user to proceed anyway.
+    ProjectOpenInUnity,
 }
 
 impl RustError {
@@ -304,6 +334,10 @@ impl RustError {
     }
 }
 
+fn is_open_in_unity(error: &io::Error) -> bool {
+    (error.get_ref()).is_some_and(|inner| inner.is::<ProjectOpenInUnityError>())
+}
+
 macro_rules! impl_from_error {
     ($($error:ty),* $(,)?) => {
         $(
@@ -317,7 +351,6 @@ macro_rules! impl_from_error {
 }
 
 impl_from_error!(
-    io::Error,
     String,
     async_zip::error::ZipError,
     vrc_get_vpm::environment::AddRepositoryErr,
@@ -325,28 +358,20 @@ impl_from_error!(
     fs_extra::error::Error,
 );
 
-impl From<tauri_plugin_updater::Error> for RustError {
-    fn from(value: tauri_plugin_updater::Error) -> Self {
-        log::error!(gui_toast = false; "failed to load latest release: {value}");
-        Self::unrecoverable("failed to load the latest release")
-    }
-}
-
-impl From<MigrateVpmError> for RustError {
-    fn from(value: MigrateVpmError) -> Self {
-        match value {
-            MigrateVpmError::AddPackageErr(add_err) => add_err.into(),
-            value => RustError::unrecoverable(value),
+impl From<io::Error> for RustError {
+    fn from(value: io::Error) -> Self {
+        if is_open_in_unity(&value) {
+            RustError::handleable(value.to_string(), HandleableRustError::ProjectOpenInUnity)
+        } else {
+            RustError::unrecoverable(value)
         }
     }
 }
 
-impl From<MigrateUnity2022Error> for RustError {
-    fn from(value: MigrateUnity2022Error) -> Self {
-        match value {
-            MigrateUnity2022Error::AddPackageErr(add_err) => add_err.into(),
-            value => RustError::unrecoverable(value),
-        }
+impl From<tauri_plugin_updater::Error> for RustError {
+    fn from(value: tauri_plugin_updater::Error) -> Self {
+        log::error!(gui_toast = false; "failed to load latest release: {value}");
+        Self::unrecoverable("failed to load the latest release")
     }
 }
 
@@ -374,6 +399,37 @@ impl From<AddPackageErr> for RustError {
     }
 }
 
+impl From<vrc_get_vpm::Error> for RustError {
+    fn from(value: vrc_get_vpm::Error) -> Self {
+        let dependencies = match (
+            value.downcast_ref::<AddPackageErr>(),
+            value.downcast_ref::<ResolvePackageErr>(),
+            value.downcast_ref::<ReinstalPackagesError>(),
+        ) {
+            (Some(AddPackageErr::DependenciesNotFound { dependencies }), _, _)
+            | (_, Some(ResolvePackageErr::DependenciesNotFound { dependencies }), _)
+            | (_, _, Some(ReinstalPackagesError::DependenciesNotFound { dependencies })) => {
+                Some(dependencies.clone())
+            }
+            _ => None,
+        };
+
+        if let Some(dependencies) = dependencies {
+            return RustError::handleable_missing_dependencies(value.to_string(), dependencies);
+        }
+        if value
+            .downcast_ref::<io::Error>()
+            .is_some_and(is_open_in_unity)
+        {
+            return RustError::handleable(
+                value.to_string(),
+                HandleableRustError::ProjectOpenInUnity,
+            );
+        }
+        RustError::unrecoverable(value)
+    }
+}
+
 impl From<ResolvePackageErr> for RustError {
     fn from(value: ResolvePackageErr) -> Self {
         let message = value.to_string();
@@ -13,10 +13,13 @@ use std::path::{Component, Path, PathBuf, Prefix};
 use std::sync::atomic::AtomicUsize;
 use tauri::{State, Window};
 use tauri_plugin_dialog::DialogExt;
-use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
-use vrc_get_vpm::ProjectType;
-use vrc_get_vpm::environment::{PackageInstaller, Settings, UserProject, VccDatabaseConnection};
-use vrc_get_vpm::io::{DefaultEnvironmentIo, DefaultProjectIo, DirEntry, EnvironmentIo, IoTrait};
+use vrc_get_vpm::environment::{
+    CreateProjectOptions, EmbeddedTemplate, PackageInstaller, ProjectTemplate, Settings,
+    UserProject, VccDatabaseConnection, list_project_templates,
+};
+use vrc_get_vpm::io::{DefaultEnvironmentIo, DefaultProjectIo};
+use vrc_get_vpm::unity_project::check_not_open_in_unity;
+use vrc_get_vpm::{ProjectType, WINDOWS_RESERVED_CHARS, WINDOWS_RESERVED_NAMES};
 
 #[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct TauriProject {
@@ -235,6 +238,13 @@ pub async fn environment_remove_project(
         return Err(RustError::unrecoverable("project not found"));
     };
 
+    if directory {
+        check_not_open_in_unity(&DefaultProjectIo::new(
+            Path::new(project.path().unwrap()).into(),
+        ))
+        .await?;
+    }
+
     let mut settings = settings.load_mut(io.inner()).await?;
     let mut connection = VccDatabaseConnection::connect(io.inner()).await?;
     migrate_sanitize_projects(&mut connection, io.inner(), &settings).await?;
@@ -266,6 +276,10 @@ pub async fn environment_remove_project_by_path(
     directory: bool,
 ) -> Result<(), RustError> {
     {
+        if directory {
+            check_not_open_in_unity(&DefaultProjectIo::new(Path::new(&path).into())).await?;
+        }
+
         let mut settings = settings.load_mut(io.inner()).await?;
         let mut connection = VccDatabaseConnection::connect(io.inner()).await?;
         migrate_sanitize_projects(&mut connection, io.inner(), &settings).await?;
@@ -294,19 +308,6 @@ pub async fn environment_remove_project_by_path(
     }
 }
 
-async fn copy_recursively(from: PathBuf, to: PathBuf) -> fs_extra::error::Result<u64> {
-    let mut options = fs_extra::dir::CopyOptions::new();
-    options.copy_inside = false;
-    options.content_only = true;
-    match tokio::runtime::Handle::current()
-        .spawn_blocking(move || fs_extra::dir::copy(from, to, &options))
-        .await
-    {
-        Ok(r) => Ok(r?),
-        Err(_) => Err(io::Error::new(io::ErrorKind::Other, "background task failed").into()),
-    }
-}
-
 #[derive(Serialize, specta::Type, Clone)]
 pub struct TauriCopyProjectForMigrationProgress {
     total: usize,
@@ -477,46 +478,6 @@ pub struct TauriProjectCreationInformation {
     default_path: String,
 }
 
-async fn load_user_templates(io: &DefaultEnvironmentIo) -> io::Result<Vec<String>> {
-    let mut templates = Vec::<String>::new();
-
-    let path = io.resolve("Templates".as_ref());
-    let mut dir = io.read_dir("Templates".as_ref()).await?;
-    while let Some(dir) = dir.try_next().await? {
-        if !dir.file_type().await?.is_dir() {
-            continue;
-        }
-
-        let Ok(name) = dir.file_name().into_string() else {
-            continue;
-        };
-
-        let path = path.join(&name);
-
-        // check package.json
-        let Ok(pkg_json) = tokio::fs::metadata(path.join("package.json")).await else {
-            continue;
-        };
-        if !pkg_json.is_file() {
-            continue;
-        }
-
-        match UnityProject::load(DefaultProjectIo::new(path.into())).await {
-            Err(e) => {
-                warn!("failed to load user template {name}: {e}");
-            }
-            Ok(ref p) if !p.is_valid().await => {
-                warn!("failed to load user template {name}: invalid project");
-            }
-            Ok(_) => {}
-        }
-
-        templates.push(name)
-    }
-
-    Ok(templates)
-}
-
 #[tauri::command]
 #[specta::specta]
 pub async fn environment_project_creation_information(
@@ -532,16 +493,24 @@ pub async fn environment_project_creation_information(
             })
             .collect::<Vec<_>>();
 
-        templates.extend(
-            load_user_templates(&io)
-                .await
-                .ok()
-                .into_iter()
-                .flatten()
-                .map(|name| TauriProjectTemplate::Custom { name }),
-        );
-
         let mut settings = settings.load_mut(io.inner()).await?;
+
+        let user_templates = match list_project_templates(&settings, io.inner()).await {
+            Ok(templates) => templates,
+            Err(e) => {
+                warn!("failed to load user templates: {e}");
+                vec![]
+            }
+        };
+        templates.extend(user_templates.iter().filter_map(|template| match template {
+            ProjectTemplate::User(template) if template.unusable_reason().is_none() => {
+                Some(TauriProjectTemplate::Custom {
+                    name: template.name().into(),
+                })
+            }
+            _ => None,
+        }));
+
         let default_path = default_project_path(&mut settings).to_string();
         settings.maybe_save().await?;
 
@@ -563,13 +532,6 @@ pub enum TauriProjectDirCheckResult {
     Ok,
 }
 
-static WINDOWS_RESERVED_NAMES: &[&str] = &[
-    "CON", "PRN", "AUX", "NUL", "COM0", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7",
-    "COM8", "COM9", "LPT0", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
-];
-
-static WINDOWS_RESERVED_CHARS: &[char] = &['/', '\\', '<', '>', ':', '"', '|', '?', '*'];
-
 #[tauri::command]
 #[specta::specta]
 pub async fn environment_check_project_name(
@@ -577,11 +539,12 @@ pub async fn environment_check_project_name(
     project_name: String,
 ) -> Result<TauriProjectDirCheckResult, RustError> {
     let project_name = project_name.trim();
-    let project_name_upper = project_name.to_ascii_uppercase();
+    // Windows treats `nul.foo` as `nul`
+    let project_stem = project_name.split('.').next().unwrap().to_ascii_lowercase();
 
     if project_name.is_empty()
         || project_name.len() > 255
-        || WINDOWS_RESERVED_NAMES.contains(&project_name_upper.as_str())
+        || WINDOWS_RESERVED_NAMES.contains(&project_stem.as_str())
         || project_name.contains(WINDOWS_RESERVED_CHARS)
     {
         return Ok(TauriProjectDirCheckResult::InvalidNameForFolderName);
@@ -623,40 +586,30 @@ pub async fn environment_create_project(
     project_name: String,
     template: TauriProjectTemplate,
 ) -> Result<TauriCreateProjectResult, RustError> {
-    enum Template {
-        Builtin(&'static [u8]),
-        Custom(PathBuf),
-    }
-
     // first, check the template.
     let template = match template {
         TauriProjectTemplate::Builtin { id, .. } => {
-            let Some((_, _, template)) = crate::templates::TEMPLATES.iter().find(|x| x.0 == id)
+            let Some(&(id, name, tgz)) = crate::templates::TEMPLATES.iter().find(|x| x.0 == id)
             else {
                 return Ok(TauriCreateProjectResult::TemplateNotFound);
             };
-            Template::Builtin(template)
+            ProjectTemplate::Embedded(embedded_template(id, name, tgz)?)
         }
         TauriProjectTemplate::Custom { name } => {
-            let template_path = io.resolve(format!("Templates/{name}").as_ref());
-            match tokio::fs::metadata(&template_path).await {
-                Err(ref e) if e.kind() == io::ErrorKind::NotFound => {
-                    return Ok(TauriCreateProjectResult::TemplateNotFound);
-                }
-                Err(e) => {
-                    return Err(e.into());
-                }
-                Ok(ref meta) if !meta.is_dir() => {
-                    return Ok(TauriCreateProjectResult::TemplateNotFound);
-                }
-                Ok(_) => {}
-            }
-            Template::Custom(template_path)
+            let settings = settings.load(io.inner()).await?;
+            let templates = list_project_templates(&settings, io.inner()).await?;
+            let found = templates.into_iter().find(|template| {
+                matches!(template, ProjectTemplate::User(template) if template.name() == name)
+            });
+            let Some(template) = found else {
+                return Ok(TauriCreateProjectResult::TemplateNotFound);
+            };
+            template
         }
     };
 
     let base_path = Path::new(&base_path);
-    let path = {
+    let base_path = {
         let mut path;
         if !base_path.has_root() {
             let mut components = base_path.components().collect::<Vec<_>>();
@@ -683,124 +636,66 @@ pub async fn environment_create_project(
                 _ => {}
             }
 
-            path = components.iter().collect();
+            path = components.iter().collect::<PathBuf>();
         } else {
             path = base_path.to_path_buf();
         }
-        path.push(&project_name);
         path
     };
-    let path_str = path.to_str().unwrap();
-
-    // we split creating folder into two phases
-    // because we want to fail if the project folder already exists.
-
-    // create parent directory if not exists (unlikely to happen)
-    super::super::create_dir_all_with_err(base_path).await?;
-
-    // create project directory
-    match tokio::fs::create_dir(&path).await {
-        Ok(()) => {}
-        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
-            return Ok(TauriCreateProjectResult::AlreadyExists);
-        }
-        Err(e) => {
-            return Err(e.into());
-        }
-    }
-
-    // copy template contents to the project directory
-    match template {
-        Template::Builtin(tgz) => {
-            let tar = flate2::read::GzDecoder::new(std::io::Cursor::new(tgz));
-            let mut archive = tar::Archive::new(tar);
-            archive.unpack(&path)?;
-        }
-        Template::Custom(template) => {
-            copy_recursively(template, path.clone()).await?;
-            // remove unnecessary package.json and README.md
-            tokio::fs::remove_file(path.join("package.json")).await.ok();
-            tokio::fs::remove_file(path.join("README.md")).await.ok();
-        }
-    }
-
-    // update ProjectSettings.asset
-    {
-        let settings_path = path.join("ProjectSettings/ProjectSettings.asset");
-        let mut settings_file = tokio::fs::File::options()
-            .read(true)
-            .write(true)
-            .open(&settings_path)
-            .await?;
-
-        let mut settings = String::new();
-        settings_file.read_to_string(&mut settings).await?;
-
-        fn set_value(buffer: &mut String, finder: &str, value: &str) {
-            if let Some(pos) = buffer.find(finder) {
-                let before_ws = buffer[..pos]
-                    .chars()
-                    .last()
-                    .map(|x| x.is_ascii_whitespace())
-                    .unwrap_or(true);
-                if before_ws {
-                    if let Some(eol) = buffer[pos..].find('\n') {
-                        let eol = eol + pos;
-                        buffer.replace_range((pos + finder.len())..eol, value);
-                    }
-                }
-            }
-        }
-
-        fn yaml_quote(value: &str) -> String {
-            let s = value
-                .replace('"', "\\\"")
-                .replace('\n', "\\n")
-                .replace('\r', "\\r");
-            format!("\"{}\"", s)
-        }
 
-        set_value(
-            &mut settings,
-            "productGUID: ",
-            &uuid::Uuid::new_v4().simple().to_string(),
-        );
-        set_value(&mut settings, "productName: ", &yaml_quote(&project_name));
-
-        settings_file.seek(std::io::SeekFrom::Start(0)).await?;
-        settings_file.set_len(0).await?;
-        settings_file.write_all(settings.as_bytes()).await?;
-        settings_file.flush().await?;
-        settings_file.sync_data().await?;
-        drop(settings_file);
+    // the library proposes another folder name if exists, but we want to fail here
+    match tokio::fs::metadata(base_path.join(&project_name)).await {
+        Ok(_) => return Ok(TauriCreateProjectResult::AlreadyExists),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
     }
 
-    let mut unity_project = load_project(path_str.into()).await?;
+    // create parent directory if not exists (unlikely to happen)
+    super::super::create_dir_all_with_err(&base_path).await?;
 
-    let packages;
     {
         let mut settings = settings.load_mut(io.inner()).await?;
-        packages = packages_state
+        let packages = packages_state
             .load(&settings, io.inner(), http.inner())
             .await?;
+        let installer = PackageInstaller::new(io.inner(), Some(http.inner()))
+            .use_package_store(settings.use_package_store())
+            .use_hard_links(settings.use_hard_links());
 
-        // add the project to listing
+        // instantiate the template, resolve the project, and add it to the listing
         let mut connection = VccDatabaseConnection::connect(io.inner()).await?;
         migrate_sanitize_projects(&mut connection, io.inner(), &settings).await?;
-        connection.add_project(&unity_project).await?;
+        connection
+            .create_project(
+                &settings,
+                packages.collection(),
+                &installer,
+                &template,
+                &project_name,
+                &CreateProjectOptions::new().location(&base_path),
+            )
+            .await?;
         connection.save(io.inner()).await?;
         settings.load_from_db(&connection)?;
         settings.save().await?;
     }
 
-    {
-        let installer = PackageInstaller::new(io.inner(), Some(http.inner()));
+    Ok(TauriCreateProjectResult::Successful)
+}
 
-        // finally, resolve the project folder
-        let request = unity_project.resolve_request(packages.collection()).await?;
-        unity_project
-            .apply_pending_changes(&installer, request)
-            .await?;
+/// Decodes the built-in template embedded as gzipped tar.
+fn embedded_template(id: &str, name: &str, tgz: &[u8]) -> io::Result<EmbeddedTemplate> {
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(tgz));
+    let mut files = Vec::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry.path()?.to_string_lossy().replace('\\', "/");
+        let mut contents = Vec::new();
+        std::io::Read::read_to_end(&mut entry, &mut contents)?;
+        files.push((path.into(), contents.into()));
     }
-    Ok(TauriCreateProjectResult::Successful)
+    Ok(EmbeddedTemplate::new(id, name, files))
 }
@@ -282,7 +282,10 @@ pub async fn environment_pick_unity(
         }
 
         match connection.add_unity_installation(&path, unity_version) {
-            Err(ref e) if e.kind() == io::ErrorKind::InvalidInput => {
+            Err(ref e)
+                if (e.downcast_ref::<io::Error>())
+                    .is_some_and(|e| e.kind() == io::ErrorKind::InvalidInput) =>
+            {
                 return Ok(TauriPickUnityResult::InvalidSelection);
             }
             Err(e) => return Err(e.into()),
@@ -8,7 +8,7 @@ use tokio::sync::Mutex;
 use tokio::sync::oneshot;
 use vrc_get_vpm::environment::{VccDatabaseConnection, find_unity_hub};
 use vrc_get_vpm::io::DefaultEnvironmentIo;
-use vrc_get_vpm::unity_hub;
+use vrc_get_vpm::{AbortCheck, unity_hub};
 
 use crate::commands::prelude::*;
 use crate::config::UnityHubAccessMethod;
@@ -55,7 +55,7 @@ pub async fn update_unity_paths_from_unity_hub(
     settings: &SettingsState,
     config: &GuiConfigState,
     io: &DefaultEnvironmentIo,
-) -> io::Result<bool> {
+) -> Result<bool, vrc_get_vpm::Error> {
     loop {
         let mut guard = UPDATE_UNITY_PATH_SHARED_STATE.lock().await;
         if let Some(state) = guard.as_mut() {
@@ -67,7 +67,7 @@ pub async fn update_unity_paths_from_unity_hub(
                 Ok(UpdateUnityResultTiny::Success) => return Ok(true),
                 Ok(UpdateUnityResultTiny::NoUnityHub) => return Ok(false),
                 Ok(UpdateUnityResultTiny::IoError) => {
-                    return Err(io::Error::new(io::ErrorKind::Other, "io error"));
+                    return Err(io::Error::new(io::ErrorKind::Other, "io error").into());
                 }
                 Err(_) => {
                     debug!("previous update failed with panic or was canceled, retrying");
@@ -123,7 +123,7 @@ async fn update_unity_paths_from_unity_hub_impl(
     settings: &SettingsState,
     config: &GuiConfigState,
     io: &DefaultEnvironmentIo,
-) -> io::Result<bool> {
+) -> Result<bool, vrc_get_vpm::Error> {
     let paths_from_hub = match config.get().unity_hub_access_method {
         UnityHubAccessMethod::ReadConfig => unity_hub::load_unity_by_loading_unity_hub_files()
             .await?
@@ -149,7 +149,7 @@ async fn update_unity_paths_from_unity_hub_impl(
         let mut connection = VccDatabaseConnection::connect(io).await?;
 
         connection
-            .update_unity_from_unity_hub_and_fs(&paths_from_hub, io)
+            .update_unity_from_unity_hub_and_fs(&paths_from_hub, io, &AbortCheck::new())
             .await?;
 
         connection.save(io).await?;
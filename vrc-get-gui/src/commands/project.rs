@@ -2,21 +2,29 @@ use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
 
+use futures::future::{BoxFuture, ready};
 use log::{error, info, warn};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tauri::{State, Window};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::Command;
-use vrc_get_vpm::environment::{PackageInstaller, VccDatabaseConnection};
+use vrc_get_vpm::AbortCheck;
+use vrc_get_vpm::environment::{
+    AutoBackupOptions, PackageInstaller, VccDatabaseConnection, apply_pending_changes_with_backup,
+};
 use vrc_get_vpm::io::DefaultEnvironmentIo;
 use vrc_get_vpm::unity_project::pending_project_changes::{
     ConflictInfo, PackageChange, RemoveReason,
 };
-use vrc_get_vpm::unity_project::{AddPackageOperation, PendingProjectChanges};
+use vrc_get_vpm::unity_project::{
+    AddPackageOperation, ChangeClassification, ChangesReport, ConfirmationHandler,
+    ConfirmationPolicy, PendingProjectChanges,
+};
+use vrc_get_vpm::version::UnityVersion;
 
-use crate::commands::DEFAULT_UNITY_ARGUMENTS;
 use crate::commands::async_command::*;
 use crate::commands::prelude::*;
+use crate::commands::{DEFAULT_UNITY_ARGUMENTS, TauriVersion};
 use crate::utils::{PathExt, collect_notable_project_files_tree, project_backup_path};
 
 #[derive(Serialize, specta::Type)]
@@ -26,6 +34,7 @@ pub struct TauriProjectDetails {
     unity_revision: Option<String>,
     installed_packages: Vec<(String, TauriBasePackageInfo)>,
     should_resolve: bool,
+    vpm_resolver_missing: bool,
 }
 
 #[tauri::command]
@@ -44,6 +53,7 @@ pub async fn project_details(project_path: String) -> Result<TauriProjectDetails
             .map(|(k, p)| (k.to_string(), TauriBasePackageInfo::new(p)))
             .collect(),
         should_resolve: unity_project.should_resolve(),
+        vpm_resolver_missing: unity_project.is_vpm_resolver_missing(),
     })
 }
 
@@ -56,10 +66,14 @@ pub struct TauriPendingProjectChanges {
     remove_legacy_folders: Vec<String>,
 
     conflicts: Vec<(String, TauriConflictInfo)>,
+
+    classification: TauriChangeClassification,
+    downgrades: Vec<TauriDowngrade>,
 }
 
 impl TauriPendingProjectChanges {
-    pub fn new(version: u32, changes: &PendingProjectChanges) -> Self {
+    pub fn new(version: u32, changes: &PendingProjectChanges, project: &UnityProject) -> Self {
+        let report = changes.report(project);
         TauriPendingProjectChanges {
             changes_version: version,
             package_changes: changes
@@ -82,10 +96,47 @@ impl TauriPendingProjectChanges {
                 .iter()
                 .map(|(name, info)| (name.to_string(), info.into()))
                 .collect(),
+            classification: report.classification.into(),
+            downgrades: report
+                .installs
+                .iter()
+                .filter_map(|install| {
+                    let previous = install.previous_version.as_ref()?;
+                    (&install.version < previous).then(|| TauriDowngrade {
+                        name: install.name.to_string(),
+                        version: (&install.version).into(),
+                        previous_version: previous.into(),
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, specta::Type, Clone, Copy)]
+pub struct TauriChangeClassification {
+    removals: bool,
+    legacy_deletions: bool,
+    downgrades: bool,
+}
+
+impl From<ChangeClassification> for TauriChangeClassification {
+    fn from(value: ChangeClassification) -> Self {
+        Self {
+            removals: value.removals,
+            legacy_deletions: value.legacy_deletions,
+            downgrades: value.downgrades,
         }
     }
 }
 
+#[derive(Serialize, specta::Type)]
+struct TauriDowngrade {
+    name: String,
+    version: TauriVersion,
+    previous_version: TauriVersion,
+}
+
 #[derive(Serialize, specta::Type)]
 enum TauriPackageChange {
     InstallNew(Box<TauriBasePackageInfo>),
@@ -149,21 +200,21 @@ impl From<&ConflictInfo> for TauriConflictInfo {
 }
 
 macro_rules! changes {
-    ($packages_ref: ident, $changes: ident, |$collection: pat_param, $packages: pat_param| $body: expr) => {{
+    ($packages_ref: ident, $changes: ident, $project: ident, |$collection: pat_param, $packages: pat_param| $body: expr) => {{
         $changes
             .build_changes(
                 &$packages_ref,
                 |$collection, $packages| async { Ok($body) },
-                TauriPendingProjectChanges::new,
+                |version, changes| TauriPendingProjectChanges::new(version, changes, &$project),
             )
             .await
     }};
-    ($packages_ref: ident, $changes: ident, |$collection: pat_param| $body: expr) => {{
+    ($packages_ref: ident, $changes: ident, $project: ident, |$collection: pat_param| $body: expr) => {{
         $changes
             .build_changes_no_list(
                 &$packages_ref,
                 |$collection| async { Ok($body) },
-                TauriPendingProjectChanges::new,
+                |version, changes| TauriPendingProjectChanges::new(version, changes, &$project),
             )
             .await
     }};
@@ -187,14 +238,14 @@ pub async fn project_install_packages(
         ));
     };
 
-    changes!(packages, changes, |collection, packages| {
+    let unity_project = load_project(project_path).await?;
+
+    changes!(packages, changes, unity_project, |collection, packages| {
         let installing_packages = package_indices
             .iter()
             .map(|&index| packages[index])
             .collect::<Vec<_>>();
 
-        let unity_project = load_project(project_path).await?;
-
         let allow_prerelease = settings.show_prerelease_packages();
 
         unity_project
@@ -222,9 +273,9 @@ pub async fn project_reinstall_packages(
     let settings = settings.load(io.inner()).await?;
     let packages = packages.load(&settings, io.inner(), http.inner()).await?;
 
-    changes!(packages, changes, |collection| {
-        let unity_project = load_project(project_path).await?;
+    let unity_project = load_project(project_path).await?;
 
+    changes!(packages, changes, unity_project, |collection| {
         let package_ids = package_ids.iter().map(|x| x.as_str()).collect::<Vec<_>>();
 
         unity_project
@@ -245,13 +296,51 @@ pub async fn project_resolve(
 ) -> Result<TauriPendingProjectChanges, RustError> {
     let settings = settings.load(io.inner()).await?;
     let packages = packages.load(&settings, io.inner(), http.inner()).await?;
-    changes!(packages, changes, |collection| {
-        let unity_project = load_project(project_path).await?;
+    let unity_project = load_project(project_path).await?;
 
+    changes!(packages, changes, unity_project, |collection| {
         unity_project.resolve_request(collection).await?
     })
 }
 
+#[tauri::command]
+#[specta::specta]
+pub async fn project_add_vpm_resolver(
+    settings: State<'_, SettingsState>,
+    packages: State<'_, PackagesState>,
+    changes: State<'_, ChangesState>,
+    io: State<'_, DefaultEnvironmentIo>,
+    http: State<'_, reqwest::Client>,
+    project_path: String,
+) -> Result<TauriPendingProjectChanges, RustError> {
+    let settings = settings.load(io.inner()).await?;
+    let packages = packages.load(&settings, io.inner(), http.inner()).await?;
+    let unity_project = load_project(project_path).await?;
+
+    changes!(packages, changes, unity_project, |collection| {
+        match unity_project.vpm_resolver_request(collection).await? {
+            Some(changes) => changes,
+            None => {
+                return Err(RustError::unrecoverable(
+                    "com.vrchat.core.vpm-resolver is not found in the repositories",
+                ));
+            }
+        }
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn project_set_vpm_resolver_excluded(
+    project_path: String,
+    excluded: bool,
+) -> Result<(), RustError> {
+    let mut unity_project = load_project(project_path).await?;
+    unity_project.set_vpm_resolver_excluded(excluded);
+    unity_project.save().await?;
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn project_remove_packages(
@@ -265,36 +354,83 @@ pub async fn project_remove_packages(
 
     let changes = unity_project.remove_request(&names).await?;
 
-    Ok(changes_state.set(changes, TauriPendingProjectChanges::new))
+    Ok(changes_state.set(changes, |version, changes| {
+        TauriPendingProjectChanges::new(version, changes, &unity_project)
+    }))
 }
 
 #[tauri::command]
 #[specta::specta]
 pub async fn project_apply_pending_changes(
     changes: State<'_, ChangesState>,
+    settings: State<'_, SettingsState>,
     io: State<'_, DefaultEnvironmentIo>,
     http: State<'_, reqwest::Client>,
     project_path: String,
     changes_version: u32,
+    confirmed: TauriChangeClassification,
+    ignore_unity_lock: bool,
 ) -> Result<(), RustError> {
     let Some(mut changes) = changes.get_versioned(changes_version) else {
         return Err(RustError::unrecoverable("changes version mismatch"));
     };
 
+    let mut unity_project = load_project(project_path).await?;
+    unity_project.set_ignore_unity_lock(ignore_unity_lock);
+    // checked before taking the changes so the user can retry with ignore_unity_lock
+    unity_project.check_not_open_in_unity().await?;
+
     let changes = changes.take_changes();
 
-    let installer = PackageInstaller::new(io.inner(), Some(http.inner()));
+    let settings = settings.load(io.inner()).await?;
+    let installer = PackageInstaller::new(io.inner(), Some(http.inner()))
+        .use_package_store(settings.use_package_store())
+        .use_hard_links(settings.use_hard_links());
 
-    let mut unity_project = load_project(project_path).await?;
+    let _lock = unity_project.lock_project().await?;
 
-    unity_project
-        .apply_pending_changes(&installer, changes)
-        .await?;
+    let confirmation = ConfirmedWithDialog(confirmed);
+    let policy = ConfirmationPolicy::RequireCallback(&confirmation);
+    if !policy.allows(&unity_project, &changes).await {
+        return Err(RustError::unrecoverable(
+            "the project is changed after confirming the changes",
+        ));
+    }
+
+    apply_pending_changes_with_backup(
+        &settings,
+        io.inner(),
+        &mut unity_project,
+        &installer,
+        changes,
+        &AutoBackupOptions::new(),
+        &AbortCheck::new(),
+    )
+    .await
+    .map_err(vrc_get_vpm::Error::from)?;
 
     update_project_last_modified(&io, unity_project.project_dir()).await;
     Ok(())
 }
 
+/// Proceeds if the changes are not destructive in a way not confirmed with the dialog.
+///
+/// The project is loaded again to apply the changes, so the changes can be more destructive
+/// than the dialog shows if the project is modified in the meantime.
+struct ConfirmedWithDialog(TauriChangeClassification);
+
+impl ConfirmationHandler for ConfirmedWithDialog {
+    fn confirm<'a>(&'a self, report: &'a ChangesReport) -> BoxFuture<'a, bool> {
+        let confirmed = self.0;
+        let classification = report.classification;
+        Box::pin(ready(
+            (!classification.removals || confirmed.removals)
+                && (!classification.legacy_deletions || confirmed.legacy_deletions)
+                && (!classification.downgrades || confirmed.downgrades),
+        ))
+    }
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn project_clear_pending_changes(
@@ -317,8 +453,11 @@ pub async fn project_migrate_project_to_2022(
         let settings = settings.load(io.inner()).await?;
         let packages = packages.load(&settings, io.inner(), http.inner()).await?;
         let mut unity_project = load_project(project_path).await?;
+        let _lock = unity_project.lock_project().await?;
 
-        let installer = PackageInstaller::new(io.inner(), Some(http.inner()));
+        let installer = PackageInstaller::new(io.inner(), Some(http.inner()))
+            .use_package_store(settings.use_package_store())
+            .use_hard_links(settings.use_hard_links());
 
         unity_project
             .migrate_unity_2022(packages.collection(), &installer)
@@ -330,6 +469,51 @@ pub async fn project_migrate_project_to_2022(
     }
 }
 
+#[tauri::command]
+#[specta::specta]
+pub async fn project_migrate_unity_version(
+    settings: State<'_, SettingsState>,
+    packages: State<'_, PackagesState>,
+    io: State<'_, DefaultEnvironmentIo>,
+    http: State<'_, reqwest::Client>,
+    project_path: String,
+    unity_version: String,
+) -> Result<(), RustError> {
+    let Some(target) = UnityVersion::parse(&unity_version) else {
+        return Err(RustError::unrecoverable(format!(
+            "invalid Unity version: {unity_version}"
+        )));
+    };
+
+    let settings = settings.load(io.inner()).await?;
+    let packages = packages.load(&settings, io.inner(), http.inner()).await?;
+    let mut unity_project = load_project(project_path).await?;
+    let _lock = unity_project.lock_project().await?;
+
+    let installer = PackageInstaller::new(io.inner(), Some(http.inner()))
+        .use_package_store(settings.use_package_store())
+        .use_hard_links(settings.use_hard_links());
+
+    let changes = unity_project
+        .migrate_unity_version_request(packages.collection(), target)
+        .await?;
+    apply_pending_changes_with_backup(
+        &settings,
+        io.inner(),
+        &mut unity_project,
+        &installer,
+        changes,
+        &AutoBackupOptions::new(),
+        &AbortCheck::new(),
+    )
+    .await
+    .map_err(vrc_get_vpm::Error::from)?;
+
+    update_project_last_modified(&io, unity_project.project_dir()).await;
+
+    Ok(())
+}
+
 #[derive(Serialize, specta::Type, Clone)]
 #[serde(tag = "type")]
 #[allow(dead_code)]
@@ -423,7 +607,10 @@ pub async fn project_migrate_project_to_vpm(
     let packages = packages.load(&settings, io.inner(), http.inner()).await?;
 
     let mut unity_project = load_project(project_path).await?;
-    let installer = PackageInstaller::new(io.inner(), Some(http.inner()));
+    let _lock = unity_project.lock_project().await?;
+    let installer = PackageInstaller::new(io.inner(), Some(http.inner()))
+        .use_package_store(settings.use_package_store())
+        .use_hard_links(settings.use_hard_links());
 
     unity_project
         .migrate_vpm(
@@ -434,6 +621,7 @@ pub async fn project_migrate_project_to_vpm(
         .await?;
 
     update_project_last_modified(&io, unity_project.project_dir()).await;
+    update_project_type(&io, &unity_project).await;
 
     Ok(())
 }
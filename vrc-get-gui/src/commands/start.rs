@@ -2,7 +2,6 @@ use crate::commands::prelude::*;
 
 use crate::commands::environment::unity_hub::update_unity_paths_from_unity_hub;
 use log::{error, info};
-use std::io;
 use tauri::async_runtime::spawn;
 use tauri::{App, AppHandle, LogicalSize, Manager, State, WebviewWindow, WindowEvent};
 use vrc_get_vpm::io::DefaultEnvironmentIo;
@@ -41,7 +40,7 @@ pub fn startup(app: &mut App) {
         settings: State<'_, SettingsState>,
         config: State<'_, GuiConfigState>,
         io: State<'_, DefaultEnvironmentIo>,
-    ) -> Result<(), io::Error> {
+    ) -> Result<(), vrc_get_vpm::Error> {
         tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
 
         if update_unity_paths_from_unity_hub(&settings, &config, &io).await? {
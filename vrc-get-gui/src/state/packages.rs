@@ -1,7 +1,7 @@
 use crate::utils::YokeExt;
 use arc_swap::ArcSwapOption;
+use log::warn;
 use std::future::Future;
-use std::io;
 use std::marker::PhantomData;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU32, Ordering};
@@ -69,7 +69,7 @@ impl PackagesState {
         settings: &Settings,
         io: &DefaultEnvironmentIo,
         http: &reqwest::Client,
-    ) -> io::Result<PackagesStateRef<'_>> {
+    ) -> Result<PackagesStateRef<'_>, vrc_get_vpm::Error> {
         let inner = self.inner.load_full();
 
         // If the data is new enough, we can use it.
@@ -88,7 +88,7 @@ impl PackagesState {
         settings: &Settings,
         io: &DefaultEnvironmentIo,
         http: &reqwest::Client,
-    ) -> io::Result<PackagesStateRef> {
+    ) -> Result<PackagesStateRef, vrc_get_vpm::Error> {
         self.load_impl(settings, io, http, true).await
     }
 
@@ -98,7 +98,7 @@ impl PackagesState {
         io: &DefaultEnvironmentIo,
         http: &reqwest::Client,
         force: bool,
-    ) -> io::Result<PackagesStateRef> {
+    ) -> Result<PackagesStateRef, vrc_get_vpm::Error> {
         // We won't allow multiple threads to load the data at the same time.
         let guard = self.load_lock.lock().await;
 
@@ -114,7 +114,11 @@ impl PackagesState {
             }
         }
 
-        let collection = PackageCollection::load(settings, io, Some(http)).await?;
+        let mut collection = PackageCollection::load(settings, io, Some(http)).await?;
+        if let Err(e) = collection.save(io).await {
+            // the loaded repositories are usable even if the caches cannot be saved
+            warn!("failed to save repository caches: {e}");
+        }
 
         let yoke = Yoke::<YokeData<'static>, _>::attach_to_cart(Arc::new(collection), |x| {
             YokeData::new(x.get_all_packages().collect())
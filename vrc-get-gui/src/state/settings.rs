@@ -11,7 +11,6 @@ use tokio::sync::{Mutex, MutexGuard};
 use vrc_get_vpm::environment::Settings;
 use vrc_get_vpm::io::DefaultEnvironmentIo;
 
-#[derive(Clone)]
 struct SettingsInner {
     settings: Settings,
     loaded_at: Instant,
@@ -80,10 +79,14 @@ impl SettingsState {
         if let Some(inner) = inner.filter(|x| x.is_new()) {
             self.inner.store(None); // remove the old one
 
-            let settings = Arc::try_unwrap(inner).unwrap_or_else(|arc| {
-                log::info!("Unwrapping settings arc failed, cloning...");
-                SettingsInner::clone(&arc)
-            });
+            // the cached settings are always saved, so loading again is the same as the cached one
+            let settings = match Arc::try_unwrap(inner) {
+                Ok(settings) => settings,
+                Err(_) => {
+                    log::info!("Unwrapping settings arc failed, loading again...");
+                    SettingsInner::new(Settings::load(io).await?)
+                }
+            };
 
             return Ok(SettingMutRef::new(settings, &self.inner, io, guard));
         }
@@ -143,7 +146,7 @@ impl<'s> SettingMutRef<'s> {
         }
     }
 
-    pub async fn save(self) -> io::Result<()> {
+    pub async fn save(mut self) -> io::Result<()> {
         // We're doing the save, so we don't need to check for it
         forget(self.save_checker);
         // first, save the settings
@@ -161,8 +164,9 @@ impl<'s> SettingMutRef<'s> {
         self.save_checker.require_save();
     }
 
+    /// Saves the settings if required or changed, and returns the unchanged settings to the cache otherwise.
     pub async fn maybe_save(self) -> io::Result<()> {
-        if self.save_checker.should_save {
+        if self.save_checker.should_save || self.owned.settings.is_changed() {
             self.save().await
         } else {
             // skip should_save in drop
@@ -0,0 +1,254 @@
+//! The synchronous wrappers of the main operations, for simple tools and scripts.
+//!
+//! The operations run on the tokio runtime driven by one dedicated thread shared by all callers,
+//! like the blocking client of reqwest, so the connections of the [`reqwest::Client`]
+//! stay usable across calls from different threads.
+//! The functions in this module must not be called in async contexts, and panic if called.
+//!
+//! ```no_run
+//! use vrc_get_vpm::blocking::{Environment, UnityProject};
+//!
+//! let env = Environment::new_default(Some(reqwest::Client::new()))?;
+//! let mut project = UnityProject::load("path/to/project")?;
+//! let changes = project.resolve_request(&env)?;
+//! project.apply_pending_changes(&env, changes)?;
+//! project.save()?;
+//! # Ok::<(), vrc_get_vpm::Error>(())
+//! ```
+
+use crate::environment::{PackageCollection, PackageInstaller, Settings};
+use crate::io::{DefaultEnvironmentIo, DefaultProjectIo};
+use crate::unity_project::{AddPackageOperation, PendingProjectChanges, ProjectLockGuard};
+use crate::{PackageCollection as _, PackageInfo, PackageInfoSnapshot, VersionSelector, io};
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    if tokio::runtime::Handle::try_current().is_ok() {
+        panic!(
+            "vrc_get_vpm::blocking cannot be used in async contexts. use the async functions instead."
+        );
+    }
+
+    static RUNTIME: OnceLock<tokio::runtime::Handle> = OnceLock::new();
+
+    let handle = RUNTIME.get_or_init(|| {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to create the runtime");
+        let handle = runtime.handle().clone();
+        // the io and timer drivers of the current-thread runtime only run while
+        // Runtime::block_on is called, so the dedicated thread keeps calling it.
+        std::thread::Builder::new()
+            .name("vrc-get-vpm-blocking".into())
+            .spawn(move || runtime.block_on(std::future::pending::<()>()))
+            .expect("failed to spawn the runtime thread");
+        handle
+    });
+
+    handle.block_on(future)
+}
+
+/// The environment with the settings and the loaded repositories.
+pub struct Environment {
+    io: DefaultEnvironmentIo,
+    http: Option<reqwest::Client>,
+    settings: Settings,
+    collection: PackageCollection,
+}
+
+impl Environment {
+    /// Loads the environment in the default folder.
+    ///
+    /// See [`new`](Self::new) for details.
+    pub fn new_default(http: Option<reqwest::Client>) -> Result<Self, crate::Error> {
+        Self::new(DefaultEnvironmentIo::new_default(), http)
+    }
+
+    /// Loads the settings and the repositories from the cache.
+    ///
+    /// The repositories are not refreshed. Use [`refresh_repositories`](Self::refresh_repositories) to download them.
+    /// `http` is used to refresh the repositories and to download the packages.
+    pub fn new(
+        io: DefaultEnvironmentIo,
+        http: Option<reqwest::Client>,
+    ) -> Result<Self, crate::Error> {
+        block_on(async move {
+            let settings = Settings::load(&io).await?;
+            let collection =
+                PackageCollection::load(&settings, &io, None::<&reqwest::Client>).await?;
+            Ok(Self {
+                io,
+                http,
+                settings,
+                collection,
+            })
+        })
+    }
+
+    /// Downloads the repositories again, and saves the settings and the repository caches.
+    ///
+    /// This does nothing without the HTTP client.
+    pub fn refresh_repositories(&mut self) -> Result<(), crate::Error> {
+        let Self {
+            io,
+            http,
+            settings,
+            collection,
+        } = self;
+        let Some(http) = http else {
+            return Ok(());
+        };
+
+        block_on(async {
+            *collection = PackageCollection::load(settings, io, Some(http)).await?;
+            settings.update_id(collection);
+            let removed = settings.remove_id_duplication();
+            collection.remove_repositories(&removed, io).await;
+            collection.save(io).await?;
+            Ok(settings.save(io).await?)
+        })
+    }
+
+    pub fn io(&self) -> &DefaultEnvironmentIo {
+        &self.io
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// The settings to change. Call [`save_settings`](Self::save_settings) to save the changes.
+    pub fn settings_mut(&mut self) -> &mut Settings {
+        &mut self.settings
+    }
+
+    pub fn save_settings(&mut self) -> Result<(), crate::Error> {
+        Ok(block_on(self.settings.save(&self.io))?)
+    }
+
+    pub fn collection(&self) -> &PackageCollection {
+        &self.collection
+    }
+
+    /// Returns all versions of the package.
+    pub fn find_packages(&self, package: &str) -> Vec<PackageInfo<'_>> {
+        self.collection.find_packages(package).collect()
+    }
+
+    /// Returns the version of the package matching `selector`.
+    pub fn find_package_by_name(
+        &self,
+        package: &str,
+        selector: VersionSelector,
+    ) -> Option<PackageInfo<'_>> {
+        self.collection.find_package_by_name(package, selector)
+    }
+
+    /// Finds the package the snapshot is taken from.
+    ///
+    /// See [`crate::PackageCollection::resolve_snapshot`].
+    pub fn resolve_snapshot(&self, snapshot: &PackageInfoSnapshot) -> Option<PackageInfo<'_>> {
+        self.collection.resolve_snapshot(snapshot)
+    }
+
+    /// The installer downloading the packages to the cache of this environment.
+    pub fn installer(&self) -> PackageInstaller<'_, reqwest::Client, DefaultEnvironmentIo> {
+        PackageInstaller::new(&self.io, self.http.as_ref())
+    }
+}
+
+/// The unity project on the file system.
+///
+/// The read-only methods of [`crate::UnityProject`] are available through [`Deref`].
+pub struct UnityProject {
+    inner: crate::UnityProject<DefaultProjectIo>,
+}
+
+impl UnityProject {
+    /// Loads the project at `path`.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let io = DefaultProjectIo::new(path.as_ref().into());
+        block_on(crate::UnityProject::load(io)).map(|inner| Self { inner })
+    }
+
+    /// Loads the project containing `path`.
+    pub fn find(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let io = DefaultProjectIo::find_project_parent(path.into())?;
+        block_on(crate::UnityProject::load(io)).map(|inner| Self { inner })
+    }
+
+    pub fn into_inner(self) -> crate::UnityProject<DefaultProjectIo> {
+        self.inner
+    }
+
+    /// See [`crate::UnityProject::add_package_request`].
+    pub fn add_package_request<'env>(
+        &self,
+        env: &'env Environment,
+        packages: &[PackageInfo<'env>],
+        operation: AddPackageOperation,
+        allow_prerelease: bool,
+    ) -> Result<PendingProjectChanges<'env>, crate::Error> {
+        block_on(self.inner.add_package_request(
+            &env.collection,
+            packages,
+            operation,
+            allow_prerelease,
+        ))
+    }
+
+    /// See [`crate::UnityProject::remove_request`].
+    pub fn remove_request(
+        &self,
+        remove: &[&str],
+    ) -> Result<PendingProjectChanges<'static>, crate::Error> {
+        block_on(self.inner.remove_request(remove))
+    }
+
+    /// See [`crate::UnityProject::resolve_request`].
+    pub fn resolve_request<'env>(
+        &self,
+        env: &'env Environment,
+    ) -> Result<PendingProjectChanges<'env>, crate::Error> {
+        block_on(self.inner.resolve_request(&env.collection))
+    }
+
+    /// Applies the changes with [`Environment::installer`].
+    ///
+    /// See [`crate::UnityProject::apply_pending_changes`].
+    pub fn apply_pending_changes<'env>(
+        &mut self,
+        env: &'env Environment,
+        changes: PendingProjectChanges<'env>,
+    ) -> Result<(), crate::Error> {
+        let installer = env.installer();
+        block_on(self.inner.apply_pending_changes(&installer, changes))
+    }
+
+    /// See [`crate::UnityProject::lock_project`].
+    pub fn lock_project(&self) -> io::Result<ProjectLockGuard> {
+        block_on(self.inner.lock_project())
+    }
+
+    pub fn save(&mut self) -> io::Result<()> {
+        block_on(self.inner.save())
+    }
+}
+
+impl Deref for UnityProject {
+    type Target = crate::UnityProject<DefaultProjectIo>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DerefMut for UnityProject {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
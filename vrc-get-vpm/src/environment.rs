@@ -1,3 +1,6 @@
+mod auto_backup;
+mod config_export;
+mod health_check;
 mod repo_holder;
 mod repo_source;
 mod uesr_package_collection;
@@ -6,14 +9,23 @@ mod vrc_get_settings;
 
 #[cfg(feature = "vrc-get-litedb")]
 mod litedb;
+mod package_cache;
 mod package_collection;
+mod package_index;
 mod package_installer;
+mod package_store;
+mod profile;
+mod project_backup;
+#[cfg(feature = "experimental-project-management")]
+mod project_creation;
 #[cfg(feature = "experimental-project-management")]
 mod project_management;
+mod repos_cleanup;
 mod settings;
 #[cfg(feature = "experimental-unity-management")]
 mod unity_management;
 
+use crate::error::{ErrorCode, ErrorParam};
 use crate::io;
 use crate::repository::RemoteRepository;
 use crate::repository::local::LocalCachedRepository;
@@ -21,13 +33,20 @@ use crate::traits::HttpClient;
 use crate::utils::to_vec_pretty_os_eol;
 use futures::prelude::*;
 use indexmap::IndexMap;
-use std::collections::HashSet;
-use std::ffi::{OsStr, OsString};
 use std::fmt;
 use std::path::Path;
 use url::Url;
 
 use crate::io::{DirEntry, EnvironmentIo};
+pub use auto_backup::{
+    ApplyWithBackupError, AutoBackup, AutoBackupOptions, apply_pending_changes_with_backup,
+};
+pub use config_export::{
+    EnvironmentExport, ImportReport, ImportStrategy, SectionImport, export_config, import_config,
+};
+pub use health_check::{HealthCheck, HealthReport, HealthStatus, health_check};
+#[cfg(feature = "experimental-project-management")]
+pub use project_creation::*;
 #[cfg(feature = "experimental-project-management")]
 pub use project_management::*;
 pub(crate) use repo_holder::RepoHolder;
@@ -37,8 +56,19 @@ pub use unity_management::*;
 
 #[cfg(feature = "vrc-get-litedb")]
 pub use litedb::VccDatabaseConnection;
-pub use package_collection::PackageCollection;
+pub use package_cache::{
+    CachedPackage, PackageCacheOverview, package_cache_overview, prune_unused_package_cache,
+    remove_cached_package,
+};
+pub use package_collection::{PackageAuthor, PackageCollection, PackageListingOptions};
+pub use package_index::{IndexedPackage, PackageIndex, quick_package_index, update_package_index};
 pub use package_installer::PackageInstaller;
+pub use package_store::cleanup_package_store;
+pub use profile::{
+    DEFAULT_PROFILE, Profile, create_profile, delete_profile, list_profiles, load_profile,
+};
+pub use project_backup::{RestoreOptions, backup_project, restore_project_backup};
+pub use repos_cleanup::{RemovedRepoFile, ReposCleanupResult, cleanup_repos_folder};
 pub use settings::Settings;
 pub use uesr_package_collection::UserPackageCollection;
 
@@ -55,6 +85,17 @@ pub async fn add_remote_repo(
     headers: IndexMap<Box<str>, Box<str>>,
     io: &impl EnvironmentIo,
     http: &impl HttpClient,
+) -> Result<(), crate::Error> {
+    Ok(add_remote_repo_impl(settings, url, name, headers, io, http).await?)
+}
+
+async fn add_remote_repo_impl(
+    settings: &mut Settings,
+    url: Url,
+    name: Option<&str>,
+    headers: IndexMap<Box<str>, Box<str>>,
+    io: &impl EnvironmentIo,
+    http: &impl HttpClient,
 ) -> Result<(), AddRepositoryErr> {
     let (remote_repo, etag) = RemoteRepository::download(http, &url, &headers).await?;
 
@@ -82,48 +123,6 @@ pub async fn add_remote_repo(
     Ok(())
 }
 
-pub async fn cleanup_repos_folder(settings: &Settings, io: &impl EnvironmentIo) -> io::Result<()> {
-    let mut uesr_repo_file_names = HashSet::<OsString>::from_iter([
-        OsString::from("vrc-official.json"),
-        OsString::from("vrc-curated.json"),
-        // package cache management file used by VCC but not used by vrc-get
-        OsString::from("package-cache.json"),
-    ]);
-    let repos_base = io.resolve(REPO_CACHE_FOLDER.as_ref());
-
-    for x in settings.get_user_repos() {
-        if let Ok(relative) = x.local_path().strip_prefix(&repos_base) {
-            if let Some(file_name) = relative.file_name() {
-                if relative
-                    .parent()
-                    .map(|x| x.as_os_str().is_empty())
-                    .unwrap_or(true)
-                {
-                    // the file must be in direct child of
-                    uesr_repo_file_names.insert(file_name.to_owned());
-                }
-            }
-        }
-    }
-
-    let mut entry = io.read_dir(REPO_CACHE_FOLDER.as_ref()).await?;
-    while let Some(entry) = entry.try_next().await? {
-        let file_name: OsString = entry.file_name();
-        if file_name.as_encoded_bytes().ends_with(b".json")
-            && !uesr_repo_file_names.contains(&file_name)
-            && entry.metadata().await.map(|x| x.is_file()).unwrap_or(false)
-        {
-            let mut path = OsString::with_capacity(REPO_CACHE_FOLDER.len() + 1 + file_name.len());
-            path.push(REPO_CACHE_FOLDER);
-            path.push(OsStr::new("/"));
-            path.push(file_name);
-            io.remove_file(path.as_ref()).await?;
-        }
-    }
-
-    Ok(())
-}
-
 async fn write_new_repo(
     local_cache: &LocalCachedRepository,
     io: &impl EnvironmentIo,
@@ -245,6 +244,23 @@ impl fmt::Display for AddRepositoryErr {
 
 impl std::error::Error for AddRepositoryErr {}
 
+impl ErrorCode for AddRepositoryErr {
+    fn code(&self) -> &'static str {
+        match self {
+            AddRepositoryErr::Io(error) => error.code(),
+            AddRepositoryErr::AlreadyAdded => "repository.already-added",
+            AddRepositoryErr::OfflineMode => "repository.offline-mode",
+        }
+    }
+
+    fn params(&self) -> IndexMap<&'static str, ErrorParam> {
+        match self {
+            AddRepositoryErr::Io(error) => error.params(),
+            AddRepositoryErr::AlreadyAdded | AddRepositoryErr::OfflineMode => IndexMap::new(),
+        }
+    }
+}
+
 impl From<io::Error> for AddRepositoryErr {
     fn from(value: io::Error) -> Self {
         Self::Io(value)
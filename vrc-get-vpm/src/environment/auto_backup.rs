@@ -0,0 +1,370 @@
+use crate::environment::Settings;
+use crate::environment::project_backup::{create_backup, project_name};
+use crate::error::{ErrorCode, ErrorParam};
+use crate::io::{DirEntry, EnvironmentIo, FileSystemProjectIo, ProjectIo};
+use crate::unity_project::{BackupOptions, PendingProjectChanges};
+use crate::{AbortCheck, PackageInstaller, UnityProject, io};
+use futures::prelude::*;
+use indexmap::IndexMap;
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+
+/// The files always stored in the partial backups
+const PARTIAL_BACKUP_FILES: &[&str] = &[
+    "Packages/vpm-manifest.json",
+    "Packages/manifest.json",
+    "Packages/packages-lock.json",
+];
+
+/// The folder in the backup folder the automatic backups are created in
+const AUTO_BACKUP_FOLDER: &str = "vrc-get-auto-backups";
+
+/// The options for [`apply_pending_changes_with_backup`].
+#[derive(Debug, Clone)]
+pub struct AutoBackupOptions {
+    enabled: Option<bool>,
+    keep: Option<usize>,
+    full_backup_limit: u64,
+    allow_backup_failure: bool,
+    backup: BackupOptions,
+}
+
+impl Default for AutoBackupOptions {
+    fn default() -> Self {
+        Self {
+            enabled: None,
+            keep: None,
+            full_backup_limit: 1024 * 1024 * 1024,
+            allow_backup_failure: false,
+            backup: BackupOptions::default(),
+        }
+    }
+}
+
+impl AutoBackupOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables or disables the backup for this call regardless of [`Settings::auto_backup`].
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = Some(enabled);
+        self
+    }
+
+    /// The number of automatic backups kept for the project, instead of [`Settings::auto_backup_count`].
+    pub fn keep(mut self, keep: usize) -> Self {
+        self.keep = Some(keep);
+        self
+    }
+
+    /// If the project is larger than `bytes` without `Library` and `Temp`, only the manifests
+    /// and the files removed by the changes are backed up. The default is 1 GiB.
+    pub fn full_backup_limit(mut self, bytes: u64) -> Self {
+        self.full_backup_limit = bytes;
+        self
+    }
+
+    /// Applies the changes even if creating the backup is failed.
+    pub fn allow_backup_failure(mut self, allow: bool) -> Self {
+        self.allow_backup_failure = allow;
+        self
+    }
+
+    /// The options for the full backup.
+    pub fn backup_options(mut self, options: BackupOptions) -> Self {
+        self.backup = options;
+        self
+    }
+}
+
+/// The backup created by [`apply_pending_changes_with_backup`].
+#[derive(Debug, Clone)]
+pub struct AutoBackup {
+    path: PathBuf,
+    partial: bool,
+}
+
+impl AutoBackup {
+    /// The absolute path to the backup archive.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns true if the backup only contains the manifests and the removed files.
+    ///
+    /// Partial backups cannot be restored with [`restore_project_backup`](crate::environment::restore_project_backup)
+    /// so they should be extracted over the project.
+    pub fn is_partial(&self) -> bool {
+        self.partial
+    }
+}
+
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum ApplyWithBackupError {
+    /// Creating the backup is failed and the changes are not applied.
+    Backup(io::Error),
+    /// Applying the changes is failed.
+    Apply {
+        backup: Option<AutoBackup>,
+        error: io::Error,
+    },
+}
+
+impl std::error::Error for ApplyWithBackupError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ApplyWithBackupError::Backup(err) => Some(err),
+            ApplyWithBackupError::Apply { error, .. } => Some(error),
+        }
+    }
+}
+
+impl ErrorCode for ApplyWithBackupError {
+    fn code(&self) -> &'static str {
+        match self {
+            ApplyWithBackupError::Backup(_) => "backup.create-failed",
+            ApplyWithBackupError::Apply { .. } => "backup.apply-failed",
+        }
+    }
+
+    fn params(&self) -> IndexMap<&'static str, ErrorParam> {
+        match self {
+            ApplyWithBackupError::Backup(error) => {
+                IndexMap::from([("message", ErrorParam::text(error))])
+            }
+            ApplyWithBackupError::Apply { backup, error } => {
+                let mut params = IndexMap::from([("message", ErrorParam::text(error))]);
+                if let Some(backup) = backup {
+                    params.insert("backup_path", ErrorParam::text(backup.path().display()));
+                }
+                params
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ApplyWithBackupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApplyWithBackupError::Backup(err) => write!(f, "failed to create backup: {err}"),
+            ApplyWithBackupError::Apply {
+                backup: Some(backup),
+                error,
+            } => write!(f, "{error} (the backup is at {})", backup.path().display()),
+            ApplyWithBackupError::Apply {
+                backup: None,
+                error,
+            } => write!(f, "{error}"),
+        }
+    }
+}
+
+/// Applies the changes to the project like [`UnityProject::apply_pending_changes`], with creating a backup
+/// if the changes remove packages or legacy assets.
+///
+/// The backups are created in the `vrc-get-auto-backups` folder in the backup folder configured in the settings,
+/// and only the last [`keep`](AutoBackupOptions::keep) backups are kept for each project.
+/// If the project is too large, only the manifests and the removed files are backed up.
+/// See [`AutoBackupOptions::full_backup_limit`].
+///
+/// The backup and the changes are done while holding the project lock, and both are aborted by `abort`.
+///
+/// Returns the created backup if any.
+pub async fn apply_pending_changes_with_backup<'env>(
+    settings: &Settings,
+    io: &impl EnvironmentIo,
+    project: &mut UnityProject<impl ProjectIo + FileSystemProjectIo>,
+    installer: &'env impl PackageInstaller,
+    changes: PendingProjectChanges<'env>,
+    options: &AutoBackupOptions,
+    abort: &AbortCheck,
+) -> Result<Option<AutoBackup>, ApplyWithBackupError> {
+    // lock first so the project is not modified between the backup and the changes
+    let lock = match project.lock().await {
+        Ok(lock) => lock,
+        Err(error) => {
+            return Err(ApplyWithBackupError::Apply {
+                backup: None,
+                error,
+            });
+        }
+    };
+    let result =
+        apply_with_backup_locked(settings, io, project, installer, changes, options, abort).await;
+    project.unlock(lock).await;
+    result
+}
+
+async fn apply_with_backup_locked<'env>(
+    settings: &Settings,
+    io: &impl EnvironmentIo,
+    project: &mut UnityProject<impl ProjectIo + FileSystemProjectIo>,
+    installer: &'env impl PackageInstaller,
+    changes: PendingProjectChanges<'env>,
+    options: &AutoBackupOptions,
+    abort: &AbortCheck,
+) -> Result<Option<AutoBackup>, ApplyWithBackupError> {
+    let enabled = options.enabled.unwrap_or_else(|| settings.auto_backup());
+
+    let backup = if enabled && changes.is_destructive() {
+        match create_auto_backup(settings, io, project, &changes, options, abort).await {
+            Ok(backup) => Some(backup),
+            Err(e) if options.allow_backup_failure => {
+                warn!("failed to create backup, applying changes without backup: {e}");
+                None
+            }
+            Err(e) => return Err(ApplyWithBackupError::Backup(e)),
+        }
+    } else {
+        None
+    };
+
+    if let Err(error) = project
+        .apply_pending_changes_locked(installer, changes, abort)
+        .await
+    {
+        return Err(ApplyWithBackupError::Apply { backup, error });
+    }
+
+    Ok(backup)
+}
+
+async fn create_auto_backup(
+    settings: &Settings,
+    io: &impl EnvironmentIo,
+    project: &UnityProject<impl ProjectIo + FileSystemProjectIo>,
+    changes: &PendingProjectChanges<'_>,
+    options: &AutoBackupOptions,
+    abort: &AbortCheck,
+) -> io::Result<AutoBackup> {
+    let backup_dir = settings
+        .project_backup_path()
+        .map(|x| Path::new(x).join(AUTO_BACKUP_FOLDER))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "backup path is not configured"))?;
+
+    let size = project.size_report(abort).await?;
+    let partial = size.total() - size.clearable() > options.full_backup_limit;
+
+    let project_name = project_name(project);
+    let (name_prefix, backup_options) = if partial {
+        // ProjectVersion.txt is not included so that restore_project_backup rejects the partial backup
+        let backup_options = (PARTIAL_BACKUP_FILES.iter().map(|&x| x.to_owned()))
+            .chain(
+                changes
+                    .removed_paths()
+                    .map(|x| x.to_string_lossy().into_owned()),
+            )
+            .fold(BackupOptions::new(), BackupOptions::include);
+        (format!("{project_name}-auto-partial"), backup_options)
+    } else {
+        (format!("{project_name}-auto"), options.backup.clone())
+    };
+
+    let path = create_backup(
+        io,
+        project,
+        &backup_dir,
+        &name_prefix,
+        &backup_options,
+        abort,
+        |_| {},
+    )
+    .await?;
+
+    info!("created backup at {}", path.display());
+
+    let keep = options.keep.unwrap_or_else(|| settings.auto_backup_count());
+    if let Err(e) = rotate_backups(io, &backup_dir, project_name, keep).await {
+        warn!("failed to remove old backups: {e}");
+    }
+
+    Ok(AutoBackup { path, partial })
+}
+
+/// Removes automatic backups of the project except for the last `keep` backups.
+async fn rotate_backups(
+    io: &impl EnvironmentIo,
+    backup_dir: &Path,
+    project_name: &str,
+    keep: usize,
+) -> io::Result<()> {
+    let mut backups = vec![];
+
+    let mut iter = io.read_dir(backup_dir).await?;
+    while let Some(entry) = iter.try_next().await? {
+        let Ok(file_name) = entry.file_name().into_string() else {
+            continue;
+        };
+        if let Some((timestamp, index)) = backup_timestamp(&file_name, project_name) {
+            backups.push((timestamp.to_owned(), index, file_name));
+        }
+    }
+
+    // the timestamps are sortable as strings
+    backups.sort();
+
+    let remove_count = backups.len().saturating_sub(keep);
+    for (_, _, file_name) in backups.into_iter().take(remove_count) {
+        info!("removing old backup {file_name}");
+        io.remove_file(&backup_dir.join(file_name)).await?;
+    }
+
+    Ok(())
+}
+
+/// Returns the timestamp and the index of the automatic backup name, or None if it's not a backup of the project.
+fn backup_timestamp<'a>(file_name: &'a str, project_name: &str) -> Option<(&'a str, u32)> {
+    let rest = file_name
+        .strip_prefix(project_name)?
+        .strip_prefix("-auto-")?
+        .strip_suffix(".zip")?;
+    let rest = rest.strip_prefix("partial-").unwrap_or(rest);
+
+    // YYYY-MM-DDTHH-MM-SS optionally followed by -index
+    let (timestamp, index) = rest.split_at_checked(19)?;
+    let is_timestamp = timestamp.bytes().enumerate().all(|(i, b)| match i {
+        4 | 7 | 13 | 16 => b == b'-',
+        10 => b == b'T',
+        _ => b.is_ascii_digit(),
+    });
+    if !is_timestamp {
+        return None;
+    }
+    let index = match index {
+        "" => 0,
+        index => index.strip_prefix('-')?.parse().ok()?,
+    };
+
+    Some((timestamp, index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamp_of_backup() {
+        assert_eq!(
+            backup_timestamp("Project-auto-2024-01-02T03-04-05.zip", "Project"),
+            Some(("2024-01-02T03-04-05", 0))
+        );
+        assert_eq!(
+            backup_timestamp("Project-auto-partial-2024-01-02T03-04-05-1.zip", "Project"),
+            Some(("2024-01-02T03-04-05", 1))
+        );
+        assert_eq!(
+            backup_timestamp("Project-2024-01-02T03-04-05.zip", "Project"),
+            None
+        );
+        assert_eq!(
+            backup_timestamp("Project-auto-2024-01-02T03-04-05.zip", "Proj"),
+            None
+        );
+        assert_eq!(
+            backup_timestamp("Project-auto-other-2024-01-02T03-04-05.zip", "Project"),
+            None
+        );
+    }
+}
@@ -0,0 +1,258 @@
+use crate::UserRepoSetting;
+use crate::environment::{REPO_CACHE_FOLDER, Settings};
+use crate::io::EnvironmentIo;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use url::Url;
+
+/// The configuration of the environment exported by [`export_config`].
+///
+/// This is serialized as a single JSON document to be imported on another machine with [`import_config`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentExport {
+    #[serde(default)]
+    repositories: Vec<ExportedRepository>,
+    #[serde(default)]
+    settings: ExportedSettings,
+    #[serde(default)]
+    user_package_folders: Vec<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    projects: Option<Vec<Box<str>>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedRepository {
+    url: Url,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<Box<str>>,
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    headers: IndexMap<Box<str>, Box<str>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    show_prerelease_packages: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    default_project_path: Option<Box<str>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    project_backup_path: Option<Box<str>>,
+}
+
+impl EnvironmentExport {
+    /// Adds the paths of the projects to the export.
+    pub fn with_projects<'a>(mut self, projects: impl IntoIterator<Item = &'a str>) -> Self {
+        self.projects = Some(projects.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// The paths of the projects, if exported.
+    pub fn projects(&self) -> Option<&[Box<str>]> {
+        self.projects.as_deref()
+    }
+}
+
+/// Exports the configuration of the environment.
+///
+/// The export contains the remote user repositories, settings not specific to the machine,
+/// and user package folders.
+/// Use [`EnvironmentExport::with_projects`] to include the project list.
+///
+/// The headers of the repositories and the password in the repository urls may contain secrets,
+/// so they are excluded unless `include_secrets` is true.
+pub fn export_config(settings: &Settings, include_secrets: bool) -> EnvironmentExport {
+    let repositories = (settings.get_user_repos().iter())
+        .filter_map(|repo| {
+            let mut url = repo.url()?.clone();
+            if !include_secrets {
+                url.set_password(None).ok();
+            }
+            Some(ExportedRepository {
+                url,
+                name: repo.name().map(Into::into),
+                headers: if include_secrets {
+                    repo.headers().clone()
+                } else {
+                    IndexMap::new()
+                },
+            })
+        })
+        .collect();
+
+    EnvironmentExport {
+        repositories,
+        settings: ExportedSettings {
+            show_prerelease_packages: Some(settings.show_prerelease_packages()),
+            default_project_path: settings.default_project_path().map(Into::into),
+            project_backup_path: settings.project_backup_path().map(Into::into),
+        },
+        user_package_folders: settings.user_package_folders().to_vec(),
+        projects: None,
+    }
+}
+
+/// How [`import_config`] treats the existing configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportStrategy {
+    /// Keeps the existing entries and adds the entries not exist yet.
+    ///
+    /// The settings already set are kept as well.
+    Merge,
+    /// Removes the existing entries and adds all entries in the export.
+    /// The settings are overwritten with the exported values.
+    ///
+    /// The local user repositories are kept since they are not exported.
+    Replace,
+}
+
+/// The outcome of importing a section of [`EnvironmentExport`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct SectionImport {
+    /// The number of entries added or values changed.
+    pub imported: usize,
+    /// The number of entries skipped since they already exist or not supported.
+    pub skipped: usize,
+    /// The number of existing entries removed by [`ImportStrategy::Replace`].
+    pub removed: usize,
+}
+
+/// The result of [`import_config`].
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ImportReport {
+    pub repositories: SectionImport,
+    pub settings: SectionImport,
+    pub user_package_folders: SectionImport,
+    /// The projects in the export.
+    ///
+    /// Without `experimental-project-management` feature, the project list cannot be changed
+    /// so all exported projects are counted as `skipped`.
+    pub projects: SectionImport,
+    /// The exported projects not exist on this machine.
+    ///
+    /// They are imported as is so the user can fix the paths later.
+    /// This is reported even if the projects are skipped.
+    pub missing_projects: Vec<Box<str>>,
+}
+
+/// Imports the configuration exported by [`export_config`].
+///
+/// The repositories are added without the cache, so they will be downloaded on the next load.
+/// The user package folders and the projects are imported even if they don't exist on this machine.
+///
+/// The projects are imported only if `experimental-project-management` feature is enabled.
+/// Without the feature, the exported projects are not imported and counted as skipped in
+/// [`ImportReport::projects`].
+///
+/// The settings are changed in memory, so you have to save the settings after this.
+pub async fn import_config(
+    settings: &mut Settings,
+    io: &impl EnvironmentIo,
+    export: &EnvironmentExport,
+    strategy: ImportStrategy,
+) -> ImportReport {
+    let mut report = ImportReport::default();
+
+    // repositories
+    if strategy == ImportStrategy::Replace {
+        // the local repositories are not exported so they are kept
+        report.repositories.removed = settings.remove_repo(|x| x.url().is_some()).len();
+    }
+    for repo in &export.repositories {
+        if (settings.get_user_repos().iter()).any(|x| x.url() == Some(&repo.url)) {
+            report.repositories.skipped += 1;
+            continue;
+        }
+        let local_path =
+            io.resolve(format!("{}/{}.json", REPO_CACHE_FOLDER, uuid::Uuid::new_v4()).as_ref());
+        let mut setting = UserRepoSetting::new(
+            local_path.into_boxed_path(),
+            repo.name.clone(),
+            Some(repo.url.clone()),
+            None,
+        );
+        setting.headers = repo.headers.clone();
+        settings.add_user_repo_setting(setting);
+        report.repositories.imported += 1;
+    }
+
+    // settings; merging only fills the values not set yet
+    let exported = &export.settings;
+    let replace = strategy == ImportStrategy::Replace;
+    if let Some(value) = exported.show_prerelease_packages {
+        // false is the default value so it's treated as not set
+        if replace || !settings.show_prerelease_packages() {
+            settings.set_show_prerelease_packages(value);
+            report.settings.imported += 1;
+        } else {
+            report.settings.skipped += 1;
+        }
+    }
+    if let Some(value) = &exported.default_project_path {
+        if replace || settings.default_project_path().is_none() {
+            settings.set_default_project_path(value);
+            report.settings.imported += 1;
+        } else {
+            report.settings.skipped += 1;
+        }
+    }
+    if let Some(value) = &exported.project_backup_path {
+        if replace || settings.project_backup_path().is_none() {
+            settings.set_project_backup_path(value);
+            report.settings.imported += 1;
+        } else {
+            report.settings.skipped += 1;
+        }
+    }
+
+    // user package folders
+    if strategy == ImportStrategy::Replace {
+        let existing = settings.user_package_folders().to_vec();
+        for path in &existing {
+            settings.remove_user_package(path);
+        }
+        report.user_package_folders.removed = existing.len();
+    }
+    for path in &export.user_package_folders {
+        if settings.add_user_package_unchecked(path.clone()) {
+            report.user_package_folders.imported += 1;
+        } else {
+            report.user_package_folders.skipped += 1;
+        }
+    }
+
+    // projects
+    for path in export.projects().unwrap_or_default() {
+        if !io.is_dir(path.as_ref()).await {
+            report.missing_projects.push(path.clone());
+        }
+    }
+    #[cfg(feature = "experimental-project-management")]
+    if let Some(projects) = export.projects() {
+        if strategy == ImportStrategy::Replace {
+            report.projects.removed = settings.retain_user_projects(|_| false).len();
+        }
+        for path in projects {
+            if settings.user_projects().contains(path) {
+                report.projects.skipped += 1;
+            } else {
+                settings.add_user_project(path);
+                report.projects.imported += 1;
+            }
+        }
+    }
+    // the project list is not available without the feature
+    #[cfg(not(feature = "experimental-project-management"))]
+    {
+        report.projects.skipped = export.projects().map_or(0, <[_]>::len);
+    }
+
+    report
+}
@@ -0,0 +1,300 @@
+use crate::environment::{
+    CURATED_URL_STR, LOCAL_CURATED_PATH, LOCAL_OFFICIAL_PATH, OFFICIAL_URL_STR, REPO_CACHE_FOLDER,
+    Settings,
+};
+use crate::io;
+use crate::io::EnvironmentIo;
+use crate::traits::{HttpClient, http_get};
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::path::Path;
+use std::time::SystemTime;
+use url::Url;
+
+/// The status of a check in [`HealthReport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HealthStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// A check in [`HealthReport`].
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct HealthCheck {
+    /// The short name of the check, like `settings` or `repository`.
+    pub name: &'static str,
+    pub status: HealthStatus,
+    pub message: String,
+}
+
+/// The result of [`health_check`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HealthReport {
+    checks: Vec<HealthCheck>,
+}
+
+impl HealthReport {
+    pub fn checks(&self) -> &[HealthCheck] {
+        &self.checks
+    }
+
+    /// The worst status of the checks.
+    pub fn status(&self) -> HealthStatus {
+        (self.checks.iter().map(|x| x.status))
+            .max()
+            .unwrap_or(HealthStatus::Ok)
+    }
+
+    fn ok(&mut self, name: &'static str, message: impl Into<String>) {
+        self.push(name, HealthStatus::Ok, message.into());
+    }
+
+    fn warning(&mut self, name: &'static str, message: impl Into<String>) {
+        self.push(name, HealthStatus::Warning, message.into());
+    }
+
+    fn error(&mut self, name: &'static str, message: impl Into<String>) {
+        self.push(name, HealthStatus::Error, message.into());
+    }
+
+    fn push(&mut self, name: &'static str, status: HealthStatus, message: String) {
+        self.checks.push(HealthCheck {
+            name,
+            status,
+            message,
+        });
+    }
+}
+
+/// Checks the environment and reports the problems found.
+///
+/// This checks the environment folder and the cache folders are writable, the settings can be loaded,
+/// the repositories are cached, the Unity Hub is found, and the projects and Unity installations
+/// registered in the VCC database exist.
+/// The repositories are downloaded to check the network only if `http` is specified.
+///
+/// Nothing in the environment is changed except for the temporary files for checking permissions.
+pub async fn health_check(io: &impl EnvironmentIo, http: Option<&impl HttpClient>) -> HealthReport {
+    let mut report = HealthReport::default();
+
+    check_writable(&mut report, io, "".as_ref(), "environment folder").await;
+    check_writable(
+        &mut report,
+        io,
+        REPO_CACHE_FOLDER.as_ref(),
+        "repository cache folder",
+    )
+    .await;
+
+    let settings = match Settings::load(io).await {
+        Ok(settings) => {
+            report.ok("settings", "settings are loaded");
+            Some(settings)
+        }
+        Err(e) => {
+            report.error("settings", format!("failed to load settings: {e}"));
+            None
+        }
+    };
+
+    if let Some(settings) = &settings {
+        check_repositories(&mut report, io, http, settings).await;
+        check_unity_hub(&mut report, io, settings).await;
+        check_backup_folder(&mut report, io, settings).await;
+    }
+
+    #[cfg(feature = "vrc-get-litedb")]
+    check_database(&mut report, io).await;
+
+    report
+}
+
+async fn check_writable(
+    report: &mut HealthReport,
+    io: &impl EnvironmentIo,
+    folder: &Path,
+    name: &'static str,
+) {
+    let display = io.resolve(folder);
+    if !io.is_dir(folder).await {
+        report.error(name, format!("{} does not exist", display.display()));
+        return;
+    }
+
+    let probe = folder.join(format!(".vrc-get-health-check-{}", uuid::Uuid::new_v4()));
+    match io.create_new(&probe).await {
+        Ok(file) => {
+            drop(file);
+            io.remove_file(&probe).await.ok();
+            report.ok(name, format!("{} is writable", display.display()));
+        }
+        Err(e) => report.error(name, format!("{} is not writable: {e}", display.display())),
+    }
+}
+
+async fn check_repositories(
+    report: &mut HealthReport,
+    io: &impl EnvironmentIo,
+    http: Option<&impl HttpClient>,
+    settings: &Settings,
+) {
+    let mut repositories = vec![];
+    if !settings.ignore_official_repository() {
+        repositories.push((
+            "VRChat Official",
+            Path::new(LOCAL_OFFICIAL_PATH),
+            Url::parse(OFFICIAL_URL_STR).ok(),
+        ));
+    }
+    if !settings.ignore_curated_repository() {
+        repositories.push((
+            "VRChat Curated",
+            Path::new(LOCAL_CURATED_PATH),
+            Url::parse(CURATED_URL_STR).ok(),
+        ));
+    }
+    for repo in settings.get_user_repos() {
+        repositories.push((
+            repo.name().unwrap_or("(unnamed)"),
+            repo.local_path(),
+            repo.url().cloned(),
+        ));
+    }
+
+    for (name, path, url) in repositories {
+        match io.metadata(path).await {
+            Ok(metadata) => {
+                let fetched = metadata
+                    .modified()
+                    .and_then(|x| SystemTime::now().duration_since(x).ok())
+                    .map(|x| format!("last fetched {} ago", format_duration(x.as_secs())))
+                    .unwrap_or_else(|| "last fetched time is unknown".to_owned());
+                report.ok("repository", format!("{name} is cached, {fetched}"));
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                report.warning(
+                    "repository",
+                    format!("{name} is not cached at {}", path.display()),
+                );
+            }
+            Err(e) => report.error("repository", format!("failed to read cache of {name}: {e}")),
+        }
+
+        if let (Some(http), Some(url)) = (http, &url) {
+            match http_get(http, url, &IndexMap::new()).await {
+                Ok(_) => report.ok("network", format!("{url} is reachable")),
+                Err(e) => report.error("network", format!("failed to fetch {url}: {e}")),
+            }
+        }
+    }
+}
+
+async fn check_unity_hub(report: &mut HealthReport, io: &impl EnvironmentIo, settings: &Settings) {
+    let path = settings.unity_hub_path();
+    if path.is_empty() {
+        report.warning("unity hub", "Unity Hub path is not configured");
+    } else if io.is_file(path.as_ref()).await {
+        report.ok("unity hub", format!("Unity Hub is found at {path}"));
+    } else {
+        report.error("unity hub", format!("Unity Hub is not found at {path}"));
+    }
+}
+
+async fn check_backup_folder(
+    report: &mut HealthReport,
+    io: &impl EnvironmentIo,
+    settings: &Settings,
+) {
+    match settings.project_backup_path() {
+        None => report.warning("backup folder", "backup folder is not configured"),
+        Some(path) if io.is_dir(path.as_ref()).await => {
+            report.ok("backup folder", format!("backup folder is {path}"))
+        }
+        Some(path) => report.warning("backup folder", format!("{path} does not exist")),
+    }
+}
+
+#[cfg(feature = "vrc-get-litedb")]
+async fn check_database(report: &mut HealthReport, io: &impl EnvironmentIo) {
+    #[cfg_attr(
+        not(any(
+            feature = "experimental-project-management",
+            feature = "experimental-unity-management"
+        )),
+        allow(unused_variables)
+    )]
+    let connection = match crate::environment::VccDatabaseConnection::connect(io).await {
+        Ok(connection) => {
+            report.ok("database", "VCC database is opened");
+            connection
+        }
+        Err(e) => {
+            report.error("database", format!("failed to open VCC database: {e}"));
+            return;
+        }
+    };
+
+    #[cfg(feature = "experimental-project-management")]
+    {
+        let projects = connection.get_projects();
+        let mut missing = 0;
+        for project in &projects {
+            match project.path() {
+                Some(path) if io.is_dir(path.as_ref()).await => {}
+                _ => missing += 1,
+            }
+        }
+        report_missing(report, "projects", projects.len(), missing);
+    }
+
+    #[cfg(feature = "experimental-unity-management")]
+    {
+        let installations = connection.get_unity_installations();
+        let mut missing = 0;
+        for unity in &installations {
+            match unity.path() {
+                Some(path) if io.is_file(path.as_ref()).await => {}
+                _ => missing += 1,
+            }
+        }
+        report_missing(report, "unity", installations.len(), missing);
+    }
+}
+
+#[cfg(any(
+    feature = "experimental-project-management",
+    feature = "experimental-unity-management"
+))]
+fn report_missing(report: &mut HealthReport, name: &'static str, count: usize, missing: usize) {
+    let message = format!("{count} {name} are registered, {missing} of them are missing on disk");
+    if missing == 0 {
+        report.ok(name, message);
+    } else {
+        report.warning(name, message);
+    }
+}
+
+fn format_duration(secs: u64) -> String {
+    match secs {
+        ..120 => format!("{secs} seconds"),
+        ..7200 => format!("{} minutes", secs / 60),
+        ..172800 => format!("{} hours", secs / 3600),
+        _ => format!("{} days", secs / 86400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duration() {
+        assert_eq!(format_duration(30), "30 seconds");
+        assert_eq!(format_duration(600), "10 minutes");
+        assert_eq!(format_duration(3 * 3600), "3 hours");
+        assert_eq!(format_duration(3 * 86400), "3 days");
+    }
+}
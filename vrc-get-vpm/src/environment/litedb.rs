@@ -1,20 +1,27 @@
 #![allow(unsafe_code)]
 
+use crate::error::{IoCategory, categorized};
 use crate::io;
 use crate::io::EnvironmentIo;
+use crate::utils::SaveController;
 use futures::prelude::*;
+use log::warn;
 use vrc_get_litedb::expression::BsonExpression;
 use vrc_get_litedb::file_io::LiteDBFile;
 
+/// The connection to the VCC database, `vcc.liteDb`.
+///
+/// The methods changing the database never write to the file.
+/// Call [`VccDatabaseConnection::save`] to write the changes, which writes nothing if nothing is changed.
 pub struct VccDatabaseConnection {
-    pub(crate) db: LiteDBFile,
+    pub(crate) db: SaveController<LiteDBFile>,
     _guard: has_drop::MutexGuard,
 }
 
 static FILE_NAME: &str = "vcc.liteDb";
 
 impl VccDatabaseConnection {
-    pub async fn connect(io: &impl EnvironmentIo) -> io::Result<Self> {
+    pub async fn connect(io: &impl EnvironmentIo) -> Result<Self, crate::Error> {
         let path = io.resolve(FILE_NAME.as_ref());
 
         let lock = {
@@ -36,10 +43,10 @@ impl VccDatabaseConnection {
             Ok(mut file) => {
                 let mut buffer = vec![];
                 file.read_to_end(&mut buffer).await?;
-                LiteDBFile::parse(&buffer)?
+                LiteDBFile::parse(&buffer).map_err(|e| categorized(IoCategory::Database, e))?
             }
             Err(ref e) if e.kind() == io::ErrorKind::NotFound => LiteDBFile::new(),
-            Err(e) => return Err(e),
+            Err(e) => return Err(e.into()),
         };
 
         litedb
@@ -55,15 +62,32 @@ impl VccDatabaseConnection {
     }
 
     pub(crate) fn new(db: LiteDBFile, _guard: has_drop::MutexGuard) -> Self {
-        Self { db, _guard }
+        Self {
+            db: SaveController::new(db),
+            _guard,
+        }
     }
 
-    pub async fn save(&self, io: &impl EnvironmentIo) -> io::Result<()> {
-        // nop for now but might have to do something in the future
-        io.write_sync(FILE_NAME.as_ref(), &self.db.serialize())
-            .await?;
+    pub async fn save(&mut self, io: &impl EnvironmentIo) -> Result<(), crate::Error> {
+        self.db
+            .save(|db| async move { io.write_sync(FILE_NAME.as_ref(), &db.serialize()).await })
+            .await
+            .map_err(|e| categorized(IoCategory::Database, e))?;
         Ok(())
     }
+
+    /// Whether the database has changes not saved yet.
+    pub fn is_changed(&self) -> bool {
+        self.db.is_changed()
+    }
+}
+
+impl Drop for VccDatabaseConnection {
+    fn drop(&mut self) {
+        if self.is_changed() {
+            warn!("the changes to the VCC database are dropped without saving");
+        }
+    }
 }
 
 mod has_drop {
@@ -0,0 +1,288 @@
+use crate::environment::REPO_CACHE_FOLDER;
+use crate::io;
+use crate::io::{DirEntry, EnvironmentIo};
+use crate::package_name::package_path;
+use crate::unity_project::read_locked_packages;
+use crate::version::Version;
+use futures::prelude::*;
+use log::{debug, warn};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// The zip file of a package version in the package cache.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct CachedPackage {
+    pub name: Box<str>,
+    pub version: Version,
+    /// The size of the zip file and the hash file in bytes.
+    pub size: u64,
+    /// Whether any of the projects may use this version.
+    pub used: bool,
+}
+
+/// The result of [`package_cache_overview`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageCacheOverview {
+    packages: Vec<CachedPackage>,
+    total_size: u64,
+    unused_size: u64,
+    unreadable_projects: Vec<PathBuf>,
+}
+
+impl PackageCacheOverview {
+    /// The cached packages sorted by the name and the version.
+    pub fn packages(&self) -> &[CachedPackage] {
+        &self.packages
+    }
+
+    pub fn total_size(&self) -> u64 {
+        self.total_size
+    }
+
+    /// The size of the packages not used by any of the projects.
+    pub fn unused_size(&self) -> u64 {
+        self.unused_size
+    }
+
+    /// The projects whose `vpm-manifest.json` cannot be read, or which are not found.
+    ///
+    /// All versions of the packages in `Packages` folder of these projects are treated as used,
+    /// and all packages are treated as used if the project or the folder is not found.
+    pub fn unreadable_projects(&self) -> &[PathBuf] {
+        &self.unreadable_projects
+    }
+}
+
+/// The packages used by the projects
+struct UsedPackages {
+    /// The `name@version` of the locked packages
+    versions: HashSet<String>,
+    /// The packages all versions are treated as used
+    names: HashSet<Box<str>>,
+    /// If true, all packages are treated as used
+    all: bool,
+    unreadable_projects: Vec<PathBuf>,
+}
+
+impl UsedPackages {
+    async fn collect<'a>(
+        io: &impl EnvironmentIo,
+        projects: impl IntoIterator<Item = &'a Path>,
+    ) -> Self {
+        let mut used = Self {
+            versions: HashSet::new(),
+            names: HashSet::new(),
+            all: false,
+            unreadable_projects: vec![],
+        };
+
+        for project in projects {
+            match read_locked_packages(io, project).await {
+                Ok(Some(locked)) => used.versions.extend(
+                    (locked.into_iter()).map(|(name, version)| format!("{name}@{version}")),
+                ),
+                Ok(None) if !io.is_dir(project).await => {
+                    warn!("project {} not found", project.display());
+                    used.unreadable_projects.push(project.to_owned());
+                    // the project may be on the drive not mounted, we cannot know the packages used
+                    used.all = true;
+                }
+                Ok(None) => {
+                    // the packages may be installed without vpm-manifest.json
+                    match package_folder_names(io, project).await {
+                        Ok(names) => used.names.extend(names),
+                        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                        Err(e) => {
+                            warn!("failed to read packages of {}: {e}", project.display());
+                            used.unreadable_projects.push(project.to_owned());
+                            used.all = true;
+                        }
+                    }
+                }
+                Err(e) => {
+                    warn!("failed to read manifest of {}: {e}", project.display());
+                    used.unreadable_projects.push(project.to_owned());
+                    // we cannot know the versions, so keep all versions of the packages in the project
+                    match package_folder_names(io, project).await {
+                        Ok(names) => used.names.extend(names),
+                        Err(_) => used.all = true,
+                    }
+                }
+            }
+        }
+
+        used
+    }
+
+    fn is_used(&self, name: &str, version: &Version) -> bool {
+        self.all
+            || self.names.contains(name)
+            || self.versions.contains(&format!("{name}@{version}"))
+    }
+}
+
+async fn package_folder_names(
+    io: &impl EnvironmentIo,
+    project: &Path,
+) -> io::Result<Vec<Box<str>>> {
+    let mut names = vec![];
+    let mut iter = io.read_dir(&project.join("Packages")).await?;
+    while let Some(entry) = iter.try_next().await? {
+        if let Ok(name) = entry.file_name().into_string() {
+            names.push(name.into());
+        }
+    }
+    Ok(names)
+}
+
+/// The path to the zip file, the same as the one used by the package installer
+fn zip_path(name: &str, version: &Version) -> io::Result<PathBuf> {
+    Ok(package_path(REPO_CACHE_FOLDER, name)?.join(format!("vrc-get-{name}-{version}.zip")))
+}
+
+/// Lists the packages in the package cache.
+async fn list_cached_packages(
+    io: &impl EnvironmentIo,
+) -> io::Result<Vec<(Box<str>, Version, u64)>> {
+    let mut packages = vec![];
+
+    let mut iter = match io.read_dir(REPO_CACHE_FOLDER.as_ref()).await {
+        Ok(iter) => iter,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(packages),
+        Err(e) => return Err(e),
+    };
+
+    while let Some(entry) = iter.try_next().await? {
+        // the repository cache files are in the same folder
+        if !entry.file_type().await?.is_dir() {
+            continue;
+        }
+        let Ok(name) = entry.file_name().into_string() else {
+            continue;
+        };
+        let package_dir = Path::new(REPO_CACHE_FOLDER).join(&name);
+
+        let mut files = io.read_dir(&package_dir).await?;
+        while let Some(file) = files.try_next().await? {
+            let Ok(file_name) = file.file_name().into_string() else {
+                continue;
+            };
+            let Some(version) = file_name
+                .strip_prefix("vrc-get-")
+                .and_then(|x| x.strip_prefix(name.as_str()))
+                .and_then(|x| x.strip_prefix('-'))
+                .and_then(|x| x.strip_suffix(".zip"))
+                .and_then(|x| x.parse::<Version>().ok())
+            else {
+                continue;
+            };
+
+            let sha_path = package_dir.join(format!("{file_name}.sha256"));
+            let sha_size = match io.metadata(&sha_path).await {
+                Ok(metadata) => metadata.len(),
+                Err(_) => 0,
+            };
+            let size = file.metadata().await?.len() + sha_size;
+
+            packages.push((name.as_str().into(), version, size));
+        }
+    }
+
+    packages.sort_by(|(a_name, a_version, _), (b_name, b_version, _)| {
+        a_name.cmp(b_name).then_with(|| a_version.cmp(b_version))
+    });
+
+    Ok(packages)
+}
+
+/// Collects the packages in the package cache with whether they are used by any of `projects`.
+///
+/// The packages are matched with the name and version of the packages locked in the projects.
+/// You should pass all projects known to the environment,
+/// like the paths of [`VccDatabaseConnection::get_projects`](crate::environment::VccDatabaseConnection::get_projects).
+/// If the manifest of the project cannot be read or does not exist, all versions of the packages
+/// in `Packages` folder of the project are treated as used.
+/// If the project is not found, all packages are treated as used.
+pub async fn package_cache_overview<'a>(
+    io: &impl EnvironmentIo,
+    projects: impl IntoIterator<Item = &'a Path>,
+) -> io::Result<PackageCacheOverview> {
+    let used = UsedPackages::collect(io, projects).await;
+
+    let packages = list_cached_packages(io)
+        .await?
+        .into_iter()
+        .map(|(name, version, size)| CachedPackage {
+            used: used.is_used(&name, &version),
+            name,
+            version,
+            size,
+        })
+        .collect::<Vec<_>>();
+
+    let total_size = packages.iter().map(|x| x.size).sum();
+    let unused_size = packages.iter().filter(|x| !x.used).map(|x| x.size).sum();
+
+    Ok(PackageCacheOverview {
+        packages,
+        total_size,
+        unused_size,
+        unreadable_projects: used.unreadable_projects,
+    })
+}
+
+/// Removes the packages in the package cache that are not used by any of `projects`.
+///
+/// See [`package_cache_overview`] for how the packages are matched.
+/// If `dry_run` is true, nothing is removed.
+///
+/// Returns the removed packages, or the packages would be removed if `dry_run` is true.
+pub async fn prune_unused_package_cache<'a>(
+    io: &impl EnvironmentIo,
+    projects: impl IntoIterator<Item = &'a Path>,
+    dry_run: bool,
+) -> io::Result<Vec<CachedPackage>> {
+    let overview = package_cache_overview(io, projects).await?;
+    let unused = (overview.packages.into_iter())
+        .filter(|x| !x.used)
+        .collect::<Vec<_>>();
+
+    if !dry_run {
+        for package in &unused {
+            remove_cached_package(io, &package.name, &package.version).await?;
+        }
+    }
+
+    Ok(unused)
+}
+
+/// Removes the zip file of the package version from the package cache.
+///
+/// Returns false if the package is not cached.
+pub async fn remove_cached_package(
+    io: &impl EnvironmentIo,
+    name: &str,
+    version: &Version,
+) -> io::Result<bool> {
+    let zip_path = zip_path(name, version)?;
+    match io.remove_file(&zip_path).await {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e),
+    }
+    debug!("removed {name}@{version} from package cache");
+
+    match io.remove_file(&zip_path.with_extension("zip.sha256")).await {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+    // remove the folder if this was the last version
+    io.remove_dir(zip_path.parent().unwrap()).await.ok();
+
+    Ok(true)
+}
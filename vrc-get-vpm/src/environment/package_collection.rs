@@ -1,20 +1,31 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 use crate::PackageCollection as _;
+use crate::environment::repo_holder::save_repo_cache;
 use crate::environment::{RepoHolder, Settings, UserPackageCollection};
 use crate::io::EnvironmentIo;
 use crate::repository::LocalCachedRepository;
-use crate::{HttpClient, PackageInfo, PackageManifest, UserRepoSetting, VersionSelector, io};
+use crate::{
+    AbortCheck, HttpClient, KeywordCategories, PackageCategory, PackageInfo, PackageManifest,
+    UserRepoSetting, VersionSelector, io,
+};
 use futures::prelude::*;
 use itertools::Itertools;
 use log::error;
+use serde::Serialize;
+
+const CURATED_REPOSITORY_ID: &str = "com.vrchat.repos.curated";
 
 /// A immutable structure that holds information about all the packages.
-#[derive(Debug, Clone)]
+///
+/// The repositories downloaded while loading are not written to the caches until
+/// [`save`](Self::save) is called.
+#[derive(Debug)]
 pub struct PackageCollection {
     pub(super) repositories: HashMap<Box<Path>, LocalCachedRepository>,
     pub(super) user_packages: Vec<(PathBuf, PackageManifest)>,
+    unsaved_caches: HashSet<Box<Path>>,
 }
 
 impl PackageCollection {
@@ -22,6 +33,7 @@ impl PackageCollection {
         Self {
             repositories: HashMap::new(),
             user_packages: Vec::new(),
+            unsaved_caches: HashSet::new(),
         }
     }
 
@@ -29,18 +41,60 @@ impl PackageCollection {
         settings: &Settings,
         io: &impl EnvironmentIo,
         http: Option<&impl HttpClient>,
+    ) -> Result<Self, crate::Error> {
+        Ok(Self::load_impl(settings, io, http, &AbortCheck::new()).await?)
+    }
+
+    /// Loads the collection like [`load`](Self::load), refreshing the repositories until aborted.
+    ///
+    /// The HTTP requests are cancelled as soon as `abort` is aborted.
+    pub async fn load_with_abort(
+        settings: &Settings,
+        io: &impl EnvironmentIo,
+        http: Option<&impl HttpClient>,
+        abort: &AbortCheck,
+    ) -> Result<Self, crate::Error> {
+        Ok(Self::load_impl(settings, io, http, abort).await?)
+    }
+
+    pub(crate) async fn load_impl(
+        settings: &Settings,
+        io: &impl EnvironmentIo,
+        http: Option<&impl HttpClient>,
+        abort: &AbortCheck,
     ) -> io::Result<Self> {
         let (repositories, user_packages) = futures::try_join!(
-            RepoHolder::load(settings, io, http),
+            RepoHolder::load(settings, io, http, abort),
             UserPackageCollection::load(settings, io).map(Ok)
         )?;
 
+        let (repositories, unsaved_caches) = repositories.into_repos();
+
         Ok(Self {
-            repositories: repositories.into_repos(),
+            repositories,
             user_packages: user_packages.into_packages(),
+            unsaved_caches,
         })
     }
 
+    /// Writes the caches of the repositories downloaded while loading.
+    ///
+    /// This writes nothing if no repository is downloaded.
+    pub async fn save(&mut self, io: &impl EnvironmentIo) -> io::Result<()> {
+        for path in self.unsaved_caches.iter().sorted() {
+            if let Some(repo) = self.repositories.get(path) {
+                save_repo_cache(io, path, repo).await?;
+            }
+        }
+        self.unsaved_caches.clear();
+        Ok(())
+    }
+
+    /// Whether the collection has downloaded repositories not saved to the caches yet.
+    pub fn is_changed(&self) -> bool {
+        !self.unsaved_caches.is_empty()
+    }
+
     pub async fn remove_repositories(
         &mut self,
         remove_repos: &[UserRepoSetting],
@@ -53,6 +107,7 @@ impl PackageCollection {
             );
             io.remove_file(duplicated_repo.local_path()).await.ok();
             self.repositories.remove(duplicated_repo.local_path());
+            self.unsaved_caches.remove(duplicated_repo.local_path());
         }
     }
 }
@@ -66,39 +121,200 @@ impl PackageCollection {
         &self.user_packages
     }
 
+    /// Returns the newest version of each package matching `version_selector` and `filter`.
+    ///
+    /// The yanked versions and the deprecated packages are not listed.
+    /// Use [`find_whole_all_packages_with`](Self::find_whole_all_packages_with) to list them.
     pub fn find_whole_all_packages(
         &self,
         version_selector: VersionSelector,
         filter: impl Fn(&PackageManifest) -> bool,
+    ) -> Vec<PackageInfo> {
+        self.find_whole_all_packages_with(version_selector, &PackageListingOptions::new(), filter)
+    }
+
+    /// Returns the newest version of each package matching `version_selector` and `filter`.
+    ///
+    /// The package is deprecated if the newest version is deprecated, and the deprecated packages
+    /// are listed only if allowed by `options`.
+    pub fn find_whole_all_packages_with(
+        &self,
+        version_selector: VersionSelector,
+        options: &PackageListingOptions,
+        filter: impl Fn(&PackageManifest) -> bool,
     ) -> Vec<PackageInfo> {
         self.get_all_packages()
             .filter(|x| version_selector.satisfies(x.package_json()))
             .into_group_map_by(|x| x.name())
             .values()
             .map(|versions| versions.iter().max_by_key(|x| x.version()).unwrap())
+            .filter(|x| options.is_listed(**x))
             .filter(|x| filter(x.package_json()))
             .copied()
             .collect()
     }
-}
 
-impl crate::PackageCollection for PackageCollection {
-    fn get_curated_packages(
-        &self,
-        version_selector: VersionSelector,
-    ) -> impl Iterator<Item = PackageInfo> {
+    /// Returns the newest version of each package in the curated repository matching `version_selector`.
+    ///
+    /// The packages are in the order of the curated repository.
+    /// This only reads the cached repository, so this returns an empty list if the curated repository
+    /// is not loaded, e.g. not fetched yet or ignored by the settings.
+    pub fn curated_packages(&self, version_selector: VersionSelector) -> Vec<PackageInfo> {
+        self.get_curated_packages(version_selector).collect()
+    }
+
+    /// Returns whether the package is in the curated repository.
+    pub fn is_curated(&self, package: &str) -> bool {
+        self.curated_repository()
+            .is_some_and(|repo| repo.repo().get_package(package).is_some())
+    }
+
+    /// Returns the authors of the packages in the repositories with the number of their packages.
+    ///
+    /// The author of the package is the one of the newest version.
+    /// The packages without the author name are counted in the entry with `None` name, which is the last entry.
+    /// Other entries are sorted by the name.
+    pub fn package_authors(&self) -> Vec<PackageAuthor> {
+        let mut authors = HashMap::<Option<&str>, PackageAuthor>::new();
+
+        let newest = self.repositories.values().flat_map(|repo| {
+            (repo.repo().get_packages())
+                .filter_map(|x| x.all_versions().max_by_key(|x| x.version()))
+        });
+        for package in newest {
+            let author = package.author();
+            let name = author.and_then(|x| x.name());
+            let entry = authors.entry(name).or_insert_with(|| PackageAuthor {
+                name: name.map(Into::into),
+                url: None,
+                packages: 0,
+            });
+            entry.packages += 1;
+            if entry.url.is_none() {
+                entry.url = author.and_then(|x| x.url()).map(Into::into);
+            }
+        }
+
+        let mut authors = authors.into_values().collect::<Vec<_>>();
+        // None is the last
+        authors.sort_by(|a, b| match (&a.name, &b.name) {
+            (Some(a), Some(b)) => a.cmp(b),
+            (a, b) => b.is_some().cmp(&a.is_some()),
+        });
+        authors
+    }
+
+    fn curated_repository(&self) -> Option<&LocalCachedRepository> {
         self.repositories
             .values()
-            .filter(|x| x.repo.id() == Some("com.vrchat.repos.curated"))
-            .flat_map(move |repo| {
-                repo.repo()
-                    .get_packages()
-                    .filter_map(move |x| x.get_latest(version_selector))
-                    .map(|json| PackageInfo::remote(json, repo))
-            })
+            .find(|x| x.id() == Some(CURATED_REPOSITORY_ID))
+    }
+}
+
+/// An entry of [`PackageCollection::package_authors`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct PackageAuthor {
+    /// The name of the author, or `None` for the packages without the author.
+    pub name: Option<Box<str>>,
+    /// The url of the author, if any of the packages has it.
+    pub url: Option<Box<str>>,
+    /// The number of packages by this author.
+    pub packages: usize,
+}
+
+/// The options for [`PackageCollection::find_whole_all_packages_with`].
+#[derive(Debug, Clone, Default)]
+pub struct PackageListingOptions<'a> {
+    include_deprecated: bool,
+    installed: HashSet<&'a str>,
+    author: Option<Option<&'a str>>,
+    category: Option<PackageCategory>,
+    keyword_categories: Option<&'a KeywordCategories>,
+}
+
+impl<'a> PackageListingOptions<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lists the deprecated packages. This is false by default.
+    pub fn include_deprecated(mut self, include_deprecated: bool) -> Self {
+        self.include_deprecated = include_deprecated;
+        self
+    }
+
+    /// Lists the deprecated packages if installed in the project.
+    ///
+    /// Pass the packages installed in the project being viewed so that the user can see
+    /// [`PackageInfo::deprecation_message`] of them.
+    pub fn installed(mut self, packages: impl IntoIterator<Item = &'a str>) -> Self {
+        self.installed.extend(packages);
+        self
+    }
+
+    /// Lists only the packages by the author with the name.
+    ///
+    /// `None` lists the packages without the author name,
+    /// which are grouped as unknown in [`PackageCollection::package_authors`].
+    pub fn author(mut self, author: Option<&'a str>) -> Self {
+        self.author = Some(author);
+        self
+    }
+
+    /// Lists only the packages in the category.
+    pub fn category(mut self, category: PackageCategory) -> Self {
+        self.category = Some(category);
+        self
+    }
+
+    /// Uses the mapping to derive the category of the packages instead of the well-known one.
+    pub fn keyword_categories(mut self, keyword_categories: &'a KeywordCategories) -> Self {
+        self.keyword_categories = Some(keyword_categories);
+        self
+    }
+
+    fn is_listed(&self, package: PackageInfo) -> bool {
+        if let Some(author) = self.author {
+            if package.author().and_then(|x| x.name()) != author {
+                return false;
+            }
+        }
+
+        if let Some(category) = self.category {
+            let actual = match self.keyword_categories {
+                Some(mapping) => mapping.category_of(package.keywords()),
+                None => package.category(),
+            };
+            if actual != category {
+                return false;
+            }
+        }
+
+        self.include_deprecated
+            || !package.is_deprecated()
+            || self.installed.contains(package.name())
+    }
+}
+
+impl crate::PackageCollection for PackageCollection {
+    fn get_curated_packages<'a, 'iter>(
+        &'a self,
+        version_selector: VersionSelector<'iter>,
+    ) -> Box<dyn Iterator<Item = PackageInfo<'a>> + 'iter>
+    where
+        'a: 'iter,
+    {
+        Box::new(self.curated_repository().into_iter().flat_map(move |repo| {
+            repo.repo()
+                .get_packages()
+                .filter_map(move |x| x.get_latest(version_selector))
+                .map(|json| PackageInfo::remote(json, repo))
+        }))
     }
 
-    fn get_all_packages(&self) -> impl Iterator<Item = PackageInfo> {
+    fn get_all_packages(&self) -> Box<dyn Iterator<Item = PackageInfo<'_>> + '_> {
         let remote = self.repositories.values().flat_map(|repo| {
             repo.repo
                 .get_packages()
@@ -110,10 +326,16 @@ impl crate::PackageCollection for PackageCollection {
             .iter()
             .map(|(path, json)| PackageInfo::local(json, path));
 
-        remote.chain(local)
+        Box::new(remote.chain(local))
     }
 
-    fn find_packages(&self, package: &str) -> impl Iterator<Item = PackageInfo> {
+    fn find_packages<'a, 'iter>(
+        &'a self,
+        package: &'iter str,
+    ) -> Box<dyn Iterator<Item = PackageInfo<'a>> + 'iter>
+    where
+        'a: 'iter,
+    {
         let remote = self.repositories.values().flat_map(|repo| {
             repo.repo
                 .get_package(package)
@@ -126,14 +348,14 @@ impl crate::PackageCollection for PackageCollection {
             .filter(move |(_, json)| json.name() == package)
             .map(|(path, json)| PackageInfo::local(json, path));
 
-        remote.chain(local)
+        Box::new(remote.chain(local))
     }
 
     fn find_package_by_name(
         &self,
         package: &str,
         package_selector: VersionSelector,
-    ) -> Option<PackageInfo> {
+    ) -> Option<PackageInfo<'_>> {
         let remote = self.repositories.values().flat_map(|repo| {
             repo.repo
                 .get_package(package)
@@ -0,0 +1,153 @@
+use crate::environment::{
+    LOCAL_CURATED_PATH, LOCAL_OFFICIAL_PATH, PackageCollection, PackageListingOptions, Settings,
+};
+use crate::io;
+use crate::io::EnvironmentIo;
+use crate::utils::{save_json, try_load_json};
+use crate::version::Version;
+use crate::{AbortCheck, VersionSelector};
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// The index file in the environment folder
+const INDEX_PATH: &str = "vrc-get/package-index.json";
+/// The format version of the index file. The index files with other versions are rebuilt.
+const INDEX_FORMAT_VERSION: u32 = 1;
+
+/// A package in [`PackageIndex`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct IndexedPackage {
+    pub name: Box<str>,
+    /// The newest version of the package including prereleases
+    pub version: Version,
+    pub display_name: Option<Box<str>>,
+}
+
+/// The names of all packages in the environment, returned by [`quick_package_index`].
+#[derive(Debug, Clone)]
+pub struct PackageIndex {
+    packages: Vec<IndexedPackage>,
+}
+
+impl PackageIndex {
+    /// The packages sorted by the name.
+    pub fn packages(&self) -> &[IndexedPackage] {
+        &self.packages
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexFile {
+    format_version: u32,
+    /// The repository caches the index is built from
+    sources: Vec<IndexSource>,
+    packages: Vec<IndexedPackage>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+struct IndexSource {
+    path: PathBuf,
+    /// The modified time of the cache in milliseconds since unix epoch
+    modified: Option<u64>,
+}
+
+/// Returns the package index without loading the repositories.
+///
+/// The index is read from the index file written by [`update_package_index`].
+/// If the index file is missing, is written by another version, or the repository caches are changed
+/// since the index is built, the repositories are loaded without updating and the index is rebuilt.
+///
+/// This is intended to be used for shell completions and quick lookups, so the changes are detected
+/// only by the modified time of the repository caches and the index may lag a refresh by one invocation.
+/// Use [`PackageCollection`] for anything requires correctness.
+pub async fn quick_package_index(io: &impl EnvironmentIo) -> io::Result<PackageIndex> {
+    let settings = Settings::load(io).await?;
+    let sources = index_sources(&settings, io).await;
+
+    if let Ok(Some(index)) = try_load_json::<IndexFile>(io, INDEX_PATH.as_ref()).await {
+        if index.format_version == INDEX_FORMAT_VERSION && index.sources == sources {
+            return Ok(PackageIndex {
+                packages: index.packages,
+            });
+        }
+    }
+
+    let collection =
+        PackageCollection::load_impl(&settings, io, None::<&Infallible>, &AbortCheck::new())
+            .await?;
+    // loading may rewrite the caches so the modified times are read again
+    let sources = index_sources(&settings, io).await;
+    let packages = write_index(&collection, sources, io).await?;
+    Ok(PackageIndex { packages })
+}
+
+/// Rebuilds the index for [`quick_package_index`] from the loaded collection.
+///
+/// Call this after refreshing the repositories to keep the index up to date.
+pub async fn update_package_index(
+    settings: &Settings,
+    collection: &PackageCollection,
+    io: &impl EnvironmentIo,
+) -> io::Result<()> {
+    let sources = index_sources(settings, io).await;
+    write_index(collection, sources, io).await?;
+    Ok(())
+}
+
+async fn write_index(
+    collection: &PackageCollection,
+    sources: Vec<IndexSource>,
+    io: &impl EnvironmentIo,
+) -> io::Result<Vec<IndexedPackage>> {
+    let options = PackageListingOptions::new().include_deprecated(true);
+    let mut packages = collection
+        .find_whole_all_packages_with(VersionSelector::latest_for(None, true), &options, |_| true)
+        .into_iter()
+        .map(|x| IndexedPackage {
+            name: x.name().into(),
+            version: x.version().clone(),
+            display_name: x.display_name().map(Into::into),
+        })
+        .collect::<Vec<_>>();
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let index = IndexFile {
+        format_version: INDEX_FORMAT_VERSION,
+        sources,
+        packages,
+    };
+    save_json(io, INDEX_PATH.as_ref(), &index).await?;
+
+    Ok(index.packages)
+}
+
+/// Lists the repository caches with the modified time.
+async fn index_sources(settings: &Settings, io: &impl EnvironmentIo) -> Vec<IndexSource> {
+    let mut paths = vec![];
+    if !settings.ignore_official_repository() {
+        paths.push(Path::new(LOCAL_OFFICIAL_PATH));
+    }
+    if !settings.ignore_curated_repository() {
+        paths.push(Path::new(LOCAL_CURATED_PATH));
+    }
+    paths.extend(settings.get_user_repos().iter().map(|x| x.local_path()));
+
+    let mut sources = Vec::with_capacity(paths.len());
+    for path in paths {
+        let modified = (io.metadata(path).await.ok())
+            .and_then(|x| x.modified())
+            .and_then(|x| x.duration_since(UNIX_EPOCH).ok())
+            .map(|x| x.as_millis() as u64);
+        sources.push(IndexSource {
+            path: path.to_owned(),
+            modified,
+        });
+    }
+    sources
+}
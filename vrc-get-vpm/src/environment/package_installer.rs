@@ -1,7 +1,10 @@
 use crate::environment::REPO_CACHE_FOLDER;
-use crate::io::{EnvironmentIo, ProjectIo};
+use crate::environment::package_store::{install_hard_linked, install_linked};
+use crate::event::{Event, EventObserver};
+use crate::io::{EnvironmentIo, FileOperation, ProjectIo};
+use crate::package_name::package_path;
 use crate::repository::LocalCachedRepository;
-use crate::traits::AbortCheck;
+use crate::traits::{AbortCheck, http_get};
 use crate::utils::Sha256AsyncWrite;
 use crate::{HttpClient, PackageInfo, PackageManifest, io};
 use futures::prelude::*;
@@ -16,11 +19,43 @@ use url::Url;
 pub struct PackageInstaller<'a, T: HttpClient, IO: EnvironmentIo> {
     pub(super) io: &'a IO,
     pub(super) http: Option<&'a T>,
+    use_package_store: bool,
+    use_hard_links: bool,
 }
 
 impl<'a, T: HttpClient, IO: EnvironmentIo> PackageInstaller<'a, T, IO> {
     pub fn new(io: &'a IO, http: Option<&'a T>) -> Self {
-        Self { io, http }
+        Self {
+            io,
+            http,
+            use_package_store: false,
+            use_hard_links: false,
+        }
+    }
+
+    /// Installs remote packages as links to the package store in the environment folder.
+    ///
+    /// Packages are extracted once to the store and `Packages/<name>` of each project links to it.
+    /// If links cannot be created in the project, packages are copied as usual.
+    /// Use [`cleanup_package_store`](crate::environment::cleanup_package_store) to remove unused entries.
+    ///
+    /// Usually this should be [`Settings::use_package_store`](crate::environment::Settings::use_package_store).
+    pub fn use_package_store(mut self, use_package_store: bool) -> Self {
+        self.use_package_store = use_package_store;
+        self
+    }
+
+    /// Installs remote packages by hard linking each file from the package store.
+    ///
+    /// Unlike [`use_package_store`](Self::use_package_store), `Packages/<name>` is a normal folder so
+    /// tools that don't work with linked folders work as usual.
+    /// Files are copied if hard links cannot be created, like the project is on another volume.
+    /// This is ignored if `use_package_store` is enabled.
+    ///
+    /// Usually this should be [`Settings::use_hard_links`](crate::environment::Settings::use_hard_links).
+    pub fn use_hard_links(mut self, use_hard_links: bool) -> Self {
+        self.use_hard_links = use_hard_links;
+        self
     }
 }
 
@@ -34,10 +69,11 @@ impl<T: HttpClient, IO: EnvironmentIo> crate::PackageInstaller for PackageInstal
         abort.check()?;
         use crate::PackageInfoInner;
         log::debug!("adding package {}", package.name());
-        let dest_folder = PathBuf::from(format!("Packages/{}", package.name()));
+        let dest_folder = package_path("Packages", package.name())?;
         match package.inner {
             PackageInfoInner::Remote(package, user_repo) => {
-                let zip_file = get_package(self.io, self.http, user_repo, package).await?;
+                let (zip_file, zip_hash) =
+                    get_package(self.io, self.http, user_repo, package, abort).await?;
 
                 // downloading may take a long time, so check abort again
                 abort.check()?;
@@ -49,8 +85,34 @@ impl<T: HttpClient, IO: EnvironmentIo> crate::PackageInstaller for PackageInstal
                     package.name(),
                     package.version()
                 );
+                let result = if self.use_package_store {
+                    install_linked(
+                        self.io,
+                        io,
+                        package,
+                        zip_file,
+                        &zip_hash,
+                        &dest_folder,
+                        abort,
+                    )
+                    .await
+                } else if self.use_hard_links {
+                    install_hard_linked(
+                        self.io,
+                        io,
+                        package,
+                        zip_file,
+                        &zip_hash,
+                        &dest_folder,
+                        abort,
+                    )
+                    .await
+                } else {
+                    crate::utils::extract_zip(zip_file, io, &dest_folder, abort).await
+                };
+
                 // remove dest folder before extract if exists
-                if let Err(e) = crate::utils::extract_zip(zip_file, io, &dest_folder).await {
+                if let Err(e) = result {
                     // if an error occurs, try to remove the dest folder
                     log::debug!(
                         "Error occurred while extracting zip file for {}@{}: {}",
@@ -61,11 +123,10 @@ impl<T: HttpClient, IO: EnvironmentIo> crate::PackageInstaller for PackageInstal
                     let _ = io.remove_dir_all(&dest_folder).await;
                     return Err(e);
                 }
-                debug!(
-                    "Extracted zip file for {}@{}",
-                    package.name(),
-                    package.version()
-                );
+                self.io.observer().on_event(&Event::PackageExtracted {
+                    name: package.name(),
+                    version: package.version(),
+                });
 
                 Ok(())
             }
@@ -75,6 +136,61 @@ impl<T: HttpClient, IO: EnvironmentIo> crate::PackageInstaller for PackageInstal
             }
         }
     }
+
+    async fn simulate_install_package(
+        &self,
+        io: &impl ProjectIo,
+        package: PackageInfo<'_>,
+    ) -> io::Result<Vec<FileOperation>> {
+        use crate::PackageInfoInner;
+        let dest_folder = package_path("Packages", package.name())?;
+        match package.inner {
+            PackageInfoInner::Remote(package, _) => {
+                let (_, zip_path, sha_path) = package_cache_paths(package)?;
+                let cache =
+                    try_load_package_cache(self.io, &zip_path, &sha_path, package.zip_sha_256())
+                        .await;
+                if let Some((zip_file, _)) = cache {
+                    // the package store is in the environment folder, so the package is
+                    // extracted as without the package store to record the files in the package
+                    let zip_file = io::BufReader::new(zip_file);
+                    crate::utils::extract_zip(zip_file, io, &dest_folder, &AbortCheck::new())
+                        .await?;
+                    Ok(vec![])
+                } else {
+                    // we cannot know the files in the package without downloading
+                    let url = package.url().ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            "URL field of the package.json in the repository empty",
+                        )
+                    })?;
+                    Ok(vec![FileOperation::Download {
+                        name: package.name().into(),
+                        version: package.version().clone(),
+                        url: url.clone(),
+                    }])
+                }
+            }
+            PackageInfoInner::Local(_, path) => {
+                crate::utils::copy_recursive(self.io, path.into(), io, dest_folder).await?;
+                Ok(vec![])
+            }
+        }
+    }
+
+    fn observer(&self) -> &dyn EventObserver {
+        self.io.observer()
+    }
+}
+
+/// Returns the name of the zip file, the path to the zip file, and the path to the sha256 file
+/// of the package in the cache.
+fn package_cache_paths(package: &PackageManifest) -> io::Result<(String, PathBuf, PathBuf)> {
+    let zip_file_name = format!("vrc-get-{}-{}.zip", &package.name(), package.version());
+    let zip_path = package_path(REPO_CACHE_FOLDER, package.name())?.join(&zip_file_name);
+    let sha_path = zip_path.with_extension("zip.sha256");
+    Ok((zip_file_name, zip_path, sha_path))
 }
 
 async fn get_package<T: HttpClient, IO: EnvironmentIo>(
@@ -82,15 +198,9 @@ async fn get_package<T: HttpClient, IO: EnvironmentIo>(
     http: Option<&T>,
     repository: &LocalCachedRepository,
     package: &PackageManifest,
-) -> io::Result<IO::FileStream> {
-    let zip_file_name = format!("vrc-get-{}-{}.zip", &package.name(), package.version());
-    let zip_path = PathBuf::from(format!(
-        "{}/{}/{}",
-        REPO_CACHE_FOLDER,
-        package.name(),
-        &zip_file_name
-    ));
-    let sha_path = zip_path.with_extension("zip.sha256");
+    abort: &AbortCheck,
+) -> io::Result<(IO::FileStream, [u8; 256 / 8])> {
+    let (zip_file_name, zip_path, sha_path) = package_cache_paths(package)?;
 
     if let Some(cache_file) =
         try_load_package_cache(io, &zip_path, &sha_path, package.zip_sha_256()).await
@@ -116,6 +226,7 @@ async fn get_package<T: HttpClient, IO: EnvironmentIo>(
         let (zip_file, zip_hash) = download_package_zip(
             http,
             io,
+            package,
             &new_headers,
             &zip_path,
             &sha_path,
@@ -126,6 +237,7 @@ async fn get_package<T: HttpClient, IO: EnvironmentIo>(
                     "URL field of the package.json in the repository empty",
                 )
             })?,
+            abort,
         )
         .await?;
 
@@ -143,7 +255,7 @@ async fn get_package<T: HttpClient, IO: EnvironmentIo>(
             }
         }
 
-        Ok(zip_file)
+        Ok((zip_file, zip_hash))
     }
 }
 
@@ -155,13 +267,13 @@ async fn get_package<T: HttpClient, IO: EnvironmentIo>(
 /// * `sha_path`: the path to sha256 file
 /// * `sha256`: sha256 hash if specified
 ///
-/// returns: Option<File> readable zip file or None
+/// returns: Option<(File, hash)> readable zip file and its sha256 or None
 async fn try_load_package_cache<IO: EnvironmentIo>(
     io: &IO,
     zip_path: &Path,
     sha_path: &Path,
     sha256: Option<&str>,
-) -> Option<IO::FileStream> {
+) -> Option<(IO::FileStream, [u8; 256 / 8])> {
     let mut cache_file = io.open(zip_path).await.ok()?;
 
     let mut buf = [0u8; 256 / 4];
@@ -192,7 +304,7 @@ async fn try_load_package_cache<IO: EnvironmentIo>(
 
     cache_file.seek(SeekFrom::Start(0)).await.ok()?;
 
-    Some(cache_file)
+    Some((cache_file, hex))
 }
 
 /// downloads the zip file from the url to the specified path
@@ -200,37 +312,46 @@ async fn try_load_package_cache<IO: EnvironmentIo>(
 /// # Arguments
 ///
 /// * `http`: http client. returns error if none
+/// * `package`: the package to download, used for the events
 /// * `zip_path`: the path to zip file
 /// * `sha_path`: the path to sha256 file
 /// * `zip_file_name`: the name of zip file. will be used in the sha file
 /// * `url`: url to zip file
+/// * `abort`: aborts downloading. the partially downloaded file is removed
 ///
 /// returns: Result<File, Error> the readable zip file.
 async fn download_package_zip<IO: EnvironmentIo>(
     http: Option<&impl HttpClient>,
     io: &IO,
+    package: &PackageManifest,
     headers: &IndexMap<&str, &str>,
     zip_path: &Path,
     sha_path: &Path,
     zip_file_name: &str,
     url: &Url,
+    abort: &AbortCheck,
 ) -> io::Result<(IO::FileStream, [u8; 256 / 8])> {
     let Some(http) = http else {
         return Err(io::Error::new(io::ErrorKind::NotFound, "Offline mode"));
     };
 
+    abort.check()?;
+
     // file not found: err
     let cache_file = io.create(zip_path).await?;
 
-    debug!("Download started for {}", url);
-    let mut response = pin!(http.get(url, headers).await?);
-
-    let mut writer = Sha256AsyncWrite::new(cache_file);
-    io::copy(&mut response, &mut writer).await?;
-    debug!("finished downloading {}", url);
-
-    let (mut cache_file, hash) = writer.finalize();
-    let hash: [u8; 256 / 8] = hash.into();
+    // the download is dropped as soon as aborted, and the partial file is removed.
+    // we don't have to care the partial writes since the file is removed anyway.
+    let result = abort
+        .run(download_to(http, io, package, headers, url, cache_file))
+        .await;
+    let (mut cache_file, hash) = match result {
+        Ok(result) => result,
+        Err(e) => {
+            io.remove_file(zip_path).await.ok();
+            return Err(e);
+        }
+    };
 
     cache_file.flush().await?;
     cache_file.seek(SeekFrom::Start(0)).await?;
@@ -244,3 +365,39 @@ async fn download_package_zip<IO: EnvironmentIo>(
 
     Ok((cache_file, hash))
 }
+
+async fn download_to<IO: EnvironmentIo>(
+    http: &impl HttpClient,
+    io: &IO,
+    package: &PackageManifest,
+    headers: &IndexMap<&str, &str>,
+    url: &Url,
+    cache_file: IO::FileStream,
+) -> io::Result<(IO::FileStream, [u8; 256 / 8])> {
+    let (name, version) = (package.name(), package.version());
+    io.observer()
+        .on_event(&Event::PackageDownloadStarted { name, version, url });
+    let mut response = pin!(http_get(http, url, headers).await?);
+
+    let mut writer = Sha256AsyncWrite::new(cache_file);
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut downloaded = 0;
+    loop {
+        let read = response.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        writer.write_all(&buffer[..read]).await?;
+        downloaded += read as u64;
+        io.observer().on_event(&Event::PackageDownloadProgress {
+            name,
+            version,
+            downloaded,
+        });
+    }
+    io.observer()
+        .on_event(&Event::PackageDownloadFinished { name, version, url });
+
+    let (cache_file, hash) = writer.finalize();
+    Ok((cache_file, hash.into()))
+}
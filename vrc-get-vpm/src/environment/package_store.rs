@@ -0,0 +1,203 @@
+use crate::io::{DirEntry, EnvironmentIo, ProjectIo};
+use crate::package_name::check_package_name;
+use crate::utils::extract_zip;
+use crate::{AbortCheck, PackageManifest, UnityProject, io};
+use futures::prelude::*;
+use log::{debug, warn};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// The folder in the environment folder the packages are extracted to
+const PACKAGE_STORE_FOLDER: &str = "PackageStore";
+const TEMP_PREFIX: &str = ".tmp-";
+
+/// The name of the store entry. The entry is identified by the hash of the zip file.
+fn entry_name(package: &PackageManifest, zip_hash: &[u8; 256 / 8]) -> String {
+    format!(
+        "{}@{}-{}",
+        package.name(),
+        package.version(),
+        &hex::encode(zip_hash)[..16]
+    )
+}
+
+/// Extracts the package to the store if not extracted yet.
+///
+/// Returns the path to the store entry, relative to the environment folder.
+async fn extract_to_store(
+    env_io: &impl EnvironmentIo,
+    package: &PackageManifest,
+    zip_file: impl AsyncBufRead + AsyncSeek + Unpin,
+    zip_hash: &[u8; 256 / 8],
+    abort: &AbortCheck,
+) -> io::Result<PathBuf> {
+    check_package_name(package.name())?;
+    let entry_path = PathBuf::from(format!(
+        "{PACKAGE_STORE_FOLDER}/{}",
+        entry_name(package, zip_hash)
+    ));
+
+    if !env_io.is_dir(&entry_path).await {
+        debug!(
+            "extracting {}@{} to store",
+            package.name(),
+            package.version()
+        );
+        let temp_path = PathBuf::from(format!(
+            "{PACKAGE_STORE_FOLDER}/{TEMP_PREFIX}{}",
+            uuid::Uuid::new_v4()
+        ));
+        env_io.create_dir_all(&temp_path).await?;
+        if let Err(e) = extract_zip(zip_file, env_io, &temp_path, abort).await {
+            env_io.remove_dir_all(&temp_path).await.ok();
+            return Err(e);
+        }
+        if let Err(e) = env_io.rename(&temp_path, &entry_path).await {
+            env_io.remove_dir_all(&temp_path).await.ok();
+            // another process may extract the same package at the same time
+            if !env_io.is_dir(&entry_path).await {
+                return Err(e);
+            }
+        }
+    }
+
+    Ok(entry_path)
+}
+
+/// Extracts the package to the store if not extracted yet, and links `dest_folder` to the store entry.
+///
+/// If creating the link is failed, the package is extracted to `dest_folder` instead.
+pub(super) async fn install_linked(
+    env_io: &impl EnvironmentIo,
+    project_io: &impl ProjectIo,
+    package: &PackageManifest,
+    mut zip_file: impl AsyncBufRead + AsyncSeek + Unpin,
+    zip_hash: &[u8; 256 / 8],
+    dest_folder: &Path,
+    abort: &AbortCheck,
+) -> io::Result<()> {
+    let entry_path = extract_to_store(env_io, package, &mut zip_file, zip_hash, abort).await?;
+
+    if let Some(parent) = dest_folder.parent() {
+        project_io.create_dir_all(parent).await?;
+    }
+
+    match project_io
+        .create_dir_link(&env_io.resolve(&entry_path), dest_folder)
+        .await
+    {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            warn!(
+                "failed to link {}, copying the package instead: {e}",
+                dest_folder.display()
+            );
+            extract_zip(zip_file, project_io, dest_folder, abort).await
+        }
+    }
+}
+
+/// Extracts the package to the store if not extracted yet, and hard links each file to `dest_folder`.
+///
+/// If creating hard links is failed, like the store and the project are on different volumes,
+/// the remaining files are copied instead.
+pub(super) async fn install_hard_linked(
+    env_io: &impl EnvironmentIo,
+    project_io: &impl ProjectIo,
+    package: &PackageManifest,
+    zip_file: impl AsyncBufRead + AsyncSeek + Unpin,
+    zip_hash: &[u8; 256 / 8],
+    dest_folder: &Path,
+    abort: &AbortCheck,
+) -> io::Result<()> {
+    let entry_path = extract_to_store(env_io, package, zip_file, zip_hash, abort).await?;
+
+    let mut can_link = true;
+    let mut queue = vec![PathBuf::new()];
+    while let Some(relative_dir) = queue.pop() {
+        project_io
+            .create_dir_all(&dest_folder.join(&relative_dir))
+            .await?;
+
+        let mut iter = env_io.read_dir(&entry_path.join(&relative_dir)).await?;
+        while let Some(entry) = iter.try_next().await? {
+            abort.check()?;
+            let relative = relative_dir.join(entry.file_name());
+            if entry.file_type().await?.is_dir() {
+                queue.push(relative);
+                continue;
+            }
+
+            let source = entry_path.join(&relative);
+            let dest = dest_folder.join(&relative);
+
+            if can_link {
+                match project_io.hard_link(&env_io.resolve(&source), &dest).await {
+                    Ok(()) => continue,
+                    Err(e) => {
+                        warn!(
+                            "failed to create hard links in {}, copying files instead: {e}",
+                            dest_folder.display()
+                        );
+                        can_link = false;
+                    }
+                }
+            }
+
+            let mut src_file = env_io.open(&source).await?;
+            let mut dst_file = project_io.create(&dest).await?;
+            io::copy(&mut src_file, &mut dst_file).await?;
+            dst_file.flush().await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Removes the entries in the package store that are not used by any of `projects`.
+///
+/// Files hard linked to projects are kept in the projects even if the store entry is removed.
+///
+/// The entries are matched with the name and version of the packages locked in the projects.
+/// Packages linked from projects not in `projects` will be broken, so you should pass all
+/// projects known to the environment.
+///
+/// Returns the number of removed entries.
+pub async fn cleanup_package_store<'a, ProjectIO: ProjectIo + 'a>(
+    io: &impl EnvironmentIo,
+    projects: impl IntoIterator<Item = &'a UnityProject<ProjectIO>>,
+) -> io::Result<usize> {
+    let used = projects
+        .into_iter()
+        .flat_map(|project| project.locked_packages())
+        .map(|locked| format!("{}@{}", locked.name(), locked.version()))
+        .collect::<HashSet<_>>();
+
+    let mut iter = match io.read_dir(PACKAGE_STORE_FOLDER.as_ref()).await {
+        Ok(iter) => iter,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+
+    let mut removed = 0;
+    while let Some(entry) = iter.try_next().await? {
+        let Ok(file_name) = entry.file_name().into_string() else {
+            continue;
+        };
+        let is_used = match file_name.rsplit_once('-') {
+            _ if file_name.starts_with(TEMP_PREFIX) => false,
+            Some((package, _hash)) => used.contains(package),
+            None => true, // not created by us
+        };
+        if is_used {
+            continue;
+        }
+
+        debug!("removing {file_name} from package store");
+        io.remove_dir_all(&Path::new(PACKAGE_STORE_FOLDER).join(&file_name))
+            .await?;
+        removed += 1;
+    }
+
+    Ok(removed)
+}
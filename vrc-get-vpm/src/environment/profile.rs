@@ -0,0 +1,144 @@
+use crate::WINDOWS_RESERVED_NAMES;
+use crate::io;
+use crate::io::{DirEntry, EnvironmentIo};
+use crate::utils::{save_json, try_load_json};
+use futures::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// The folder in the environment folder the profiles are in
+const PROFILES_FOLDER: &str = "vrc-get/profiles";
+const PROFILE_JSON: &str = "profile.json";
+/// The name of the profile uses the environment folder itself
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// A named environment isolated from the default one.
+///
+/// The settings, repository caches, and the database of the profile are in the profile folder.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Profile {
+    #[serde(skip)]
+    pub name: Box<str>,
+    /// Whether to read the VCC settings of the default environment
+    /// until the profile has its own settings.
+    #[serde(default)]
+    pub share_vcc_settings: bool,
+}
+
+impl Profile {
+    /// The folder of the profile, relative to the default environment folder.
+    pub fn folder(&self) -> PathBuf {
+        profile_folder(&self.name)
+    }
+}
+
+fn profile_folder(name: &str) -> PathBuf {
+    PathBuf::from(format!("{PROFILES_FOLDER}/{name}"))
+}
+
+/// Returns error if the name cannot be used as the name of the profile.
+///
+/// The name must be non-empty `[0-9a-zA-Z._-]+`, not starting or ending with `.`, and not [`DEFAULT_PROFILE`].
+/// Since the name is used as a folder name, names reserved on Windows like `con` or `nul.txt` are rejected.
+fn check_profile_name(name: &str) -> io::Result<()> {
+    // Windows treats `nul.foo` as `nul`, case-insensitively
+    let stem = name.split('.').next().unwrap().to_ascii_lowercase();
+    let valid = !name.is_empty()
+        && !matches!(name, "." | "..")
+        && !name.starts_with('.')
+        // windows does not allow trailing dots and spaces
+        && !name.ends_with(['.', ' '])
+        && name != DEFAULT_PROFILE
+        && !WINDOWS_RESERVED_NAMES.contains(&stem.as_str())
+        && (name.bytes()).all(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_' | b'-'));
+    if valid {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid profile name: {name}"),
+        ))
+    }
+}
+
+/// Lists the profiles in the default environment `io`, sorted by the name.
+///
+/// This does not include [`DEFAULT_PROFILE`].
+pub async fn list_profiles(io: &impl EnvironmentIo) -> io::Result<Vec<Profile>> {
+    let mut profiles = vec![];
+
+    let mut entries = match io.read_dir(PROFILES_FOLDER.as_ref()).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(profiles),
+        Err(e) => return Err(e),
+    };
+
+    while let Some(entry) = entries.try_next().await? {
+        let Ok(name) = entry.file_name().into_string() else {
+            continue;
+        };
+        if check_profile_name(&name).is_err() {
+            continue;
+        }
+        if let Some(profile) = load_profile(io, &name).await? {
+            profiles.push(profile);
+        }
+    }
+
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(profiles)
+}
+
+/// Loads the profile from the default environment `io`.
+///
+/// Returns `None` if the profile does not exist.
+pub async fn load_profile(io: &impl EnvironmentIo, name: &str) -> io::Result<Option<Profile>> {
+    check_profile_name(name)?;
+    let path = profile_folder(name).join(PROFILE_JSON);
+    Ok(try_load_json::<Profile>(io, &path)
+        .await?
+        .map(|profile| Profile {
+            name: name.into(),
+            ..profile
+        }))
+}
+
+/// Creates a new profile in the default environment `io`.
+///
+/// Returns [`io::ErrorKind::AlreadyExists`] error if the profile already exists.
+pub async fn create_profile(
+    io: &impl EnvironmentIo,
+    name: &str,
+    share_vcc_settings: bool,
+) -> io::Result<Profile> {
+    if load_profile(io, name).await?.is_some() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("profile {name} already exists"),
+        ));
+    }
+
+    let profile = Profile {
+        name: name.into(),
+        share_vcc_settings,
+    };
+    let folder = profile.folder();
+    io.create_dir_all(&folder).await?;
+    save_json(io, &folder.join(PROFILE_JSON), &profile).await?;
+
+    Ok(profile)
+}
+
+/// Removes the profile and all the files in the profile folder from the default environment `io`.
+///
+/// Returns false if the profile does not exist.
+pub async fn delete_profile(io: &impl EnvironmentIo, name: &str) -> io::Result<bool> {
+    if load_profile(io, name).await?.is_none() {
+        return Ok(false);
+    }
+    io.remove_dir_all(&profile_folder(name)).await?;
+    Ok(true)
+}
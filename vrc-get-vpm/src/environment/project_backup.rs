@@ -0,0 +1,450 @@
+use crate::environment::Settings;
+use crate::io::{EnvironmentIo, FileSystemProjectIo, IoTrait, ProjectIo};
+use crate::unity_project::{BackupOptions, BackupProgress};
+use crate::utils::{MapResultExt, is_complete_relative};
+use crate::{AbortCheck, UnityProject, io};
+use async_zip::base::read::seek::ZipFileReader;
+use futures::prelude::*;
+use log::warn;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Creates the zip backup of the project.
+///
+/// The backup is created in `destination_dir` or the backup folder configured in the settings
+/// with the name like `ProjectName-2024-01-01T00-00-00.zip` (in UTC).
+///
+/// The created archive is verified by reading it again.
+/// If the backup is failed or aborted with `abort`, the partially created archive will be removed.
+///
+/// Returns the path to the created archive.
+pub async fn backup_project(
+    settings: &Settings,
+    io: &impl EnvironmentIo,
+    project: &UnityProject<impl ProjectIo + FileSystemProjectIo>,
+    destination_dir: Option<&Path>,
+    options: &BackupOptions,
+    abort: &AbortCheck,
+    on_progress: impl FnMut(BackupProgress<'_>),
+) -> Result<PathBuf, crate::Error> {
+    let destination_dir = match destination_dir {
+        Some(dir) => dir,
+        None => settings
+            .project_backup_path()
+            .map(Path::new)
+            .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::NotFound, "backup path is not configured")
+            })?,
+    };
+
+    Ok(create_backup(
+        io,
+        project,
+        destination_dir,
+        project_name(project),
+        options,
+        abort,
+        on_progress,
+    )
+    .await?)
+}
+
+pub(super) fn project_name(project: &UnityProject<impl ProjectIo + FileSystemProjectIo>) -> &str {
+    project
+        .project_dir()
+        .file_name()
+        .and_then(|x| x.to_str())
+        .unwrap_or("project")
+}
+
+/// Creates the zip backup of the project in `destination_dir` with the name like `{name_prefix}-{timestamp}.zip`.
+pub(super) async fn create_backup(
+    io: &impl EnvironmentIo,
+    project: &UnityProject<impl ProjectIo + FileSystemProjectIo>,
+    destination_dir: &Path,
+    name_prefix: &str,
+    options: &BackupOptions,
+    abort: &AbortCheck,
+    on_progress: impl FnMut(BackupProgress<'_>),
+) -> io::Result<PathBuf> {
+    io.create_dir_all(destination_dir).await?;
+
+    let timestamp = format_timestamp(SystemTime::now());
+    let mut index = 0;
+    let (backup_path, mut file) = loop {
+        let name = match index {
+            0 => format!("{name_prefix}-{timestamp}.zip"),
+            index => format!("{name_prefix}-{timestamp}-{index}.zip"),
+        };
+        let path = destination_dir.join(name);
+        match io.create_new(&path).await {
+            Ok(file) => break (path, file),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => index += 1,
+            Err(e) => return Err(e),
+        }
+    };
+
+    let result = async {
+        let entries = project
+            .create_backup_archive(&mut file, options, abort, on_progress)
+            .await?;
+        drop(file);
+
+        verify_archive(io, &backup_path, entries).await
+    }
+    .await;
+
+    if let Err(e) = result {
+        io.remove_file(&backup_path).await.ok();
+        return Err(e);
+    }
+
+    Ok(io.resolve(&backup_path))
+}
+
+async fn verify_archive(io: &impl IoTrait, path: &Path, entries: usize) -> io::Result<()> {
+    let file = io::BufReader::new(io.open(path).await?);
+    let reader = ZipFileReader::new(file).await.err_mapped()?;
+    if reader.file().entries().len() != entries {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "created backup archive is broken",
+        ));
+    }
+    Ok(())
+}
+
+/// The options for [`restore_project_backup`].
+#[derive(Debug, Clone, Default)]
+pub struct RestoreOptions {
+    overwrite: bool,
+    #[cfg(feature = "experimental-project-management")]
+    pub(super) register: bool,
+}
+
+impl RestoreOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows restoring to non-empty destination folder.
+    ///
+    /// The existing contents of the destination folder will be removed.
+    pub fn overwrite(mut self, overwrite: bool) -> Self {
+        self.overwrite = overwrite;
+        self
+    }
+
+    /// Adds the restored project to the VCC project list.
+    ///
+    /// This is used by [`VccDatabaseConnection::restore_project_backup`](crate::environment::VccDatabaseConnection::restore_project_backup)
+    /// since [`restore_project_backup`] has no access to the database.
+    #[cfg(feature = "experimental-project-management")]
+    pub fn register(mut self, register: bool) -> Self {
+        self.register = register;
+        self
+    }
+}
+
+/// Restores the project from the zip backup to `destination`.
+///
+/// The archive must contain `ProjectSettings/ProjectVersion.txt` at the root or in a single top-level folder.
+/// If the project is in a top-level folder, the folder is stripped.
+///
+/// The project is extracted to the temporary folder next to the `destination` and then renamed to `destination`
+/// so failed restore will not leave a half project.
+///
+/// The restored project is not added to the VCC project list even if [`RestoreOptions::register`] is set.
+/// Use [`VccDatabaseConnection::restore_project_backup`](crate::environment::VccDatabaseConnection::restore_project_backup)
+/// if you want to register it.
+pub async fn restore_project_backup(
+    io: &impl EnvironmentIo,
+    archive: &Path,
+    destination: &Path,
+    options: &RestoreOptions,
+    abort: &AbortCheck,
+    on_progress: impl FnMut(BackupProgress<'_>),
+) -> Result<PathBuf, crate::Error> {
+    Ok(restore_project_backup_impl(io, archive, destination, options, abort, on_progress).await?)
+}
+
+async fn restore_project_backup_impl(
+    io: &impl EnvironmentIo,
+    archive: &Path,
+    destination: &Path,
+    options: &RestoreOptions,
+    abort: &AbortCheck,
+    on_progress: impl FnMut(BackupProgress<'_>),
+) -> io::Result<PathBuf> {
+    let (Some(parent), Some(dest_name)) = (destination.parent(), destination.file_name()) else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "invalid destination path",
+        ));
+    };
+
+    let destination_exists = io.is_dir(destination).await;
+    if destination_exists
+        && !options.overwrite
+        && io.read_dir(destination).await?.try_next().await?.is_some()
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "destination folder is not empty",
+        ));
+    }
+
+    let mut archive = ProjectArchive::open(io::BufReader::new(io.open(archive).await?)).await?;
+
+    let sibling = |kind: &str| {
+        let mut name = std::ffi::OsString::from(".");
+        name.push(dest_name);
+        name.push(format!(".{kind}-{}", uuid::Uuid::new_v4()));
+        parent.join(name)
+    };
+    let temp_dir = sibling("restoring");
+
+    let extract = async {
+        io.create_dir_all(&temp_dir).await?;
+
+        archive
+            .extract(io, &temp_dir, |_| false, abort, on_progress)
+            .await?;
+
+        abort.check()?;
+
+        if destination_exists {
+            // keep the old project until the new project is placed
+            let old_dir = sibling("old");
+            io.rename(destination, &old_dir).await?;
+            if let Err(e) = io.rename(&temp_dir, destination).await {
+                io.rename(&old_dir, destination).await.ok();
+                return Err(e);
+            }
+            // the project is already restored, so failing to remove the old one is not an error
+            if let Err(e) = io.remove_dir_all(&old_dir).await {
+                warn!(
+                    "failed to remove the old project at {}: {e}",
+                    io.resolve(&old_dir).display()
+                );
+            }
+            Ok(())
+        } else {
+            io.rename(&temp_dir, destination).await
+        }
+    }
+    .await;
+
+    if let Err(e) = extract {
+        io.remove_dir_all(&temp_dir).await.ok();
+        return Err(e);
+    }
+
+    Ok(io.resolve(destination))
+}
+
+/// The zip archive containing a Unity project at the root or in a single top-level folder.
+pub(super) struct ProjectArchive<R> {
+    reader: ZipFileReader<R>,
+    names: Vec<String>,
+    prefix: String,
+}
+
+impl<R: AsyncBufRead + AsyncSeek + Unpin> ProjectArchive<R> {
+    pub(super) async fn open(reader: R) -> io::Result<Self> {
+        let reader = ZipFileReader::new(reader).await.err_mapped()?;
+
+        let mut names = Vec::with_capacity(reader.file().entries().len());
+        for entry in reader.file().entries() {
+            let Ok(name) = entry.filename().as_str() else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "path in zip file is not utf8",
+                ));
+            };
+            let name = name.replace('\\', "/");
+            if !is_complete_relative(name.as_ref()) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("directory traversal detected: {name}"),
+                ));
+            }
+            names.push(name);
+        }
+
+        let prefix = find_project_prefix(&names)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "the archive does not contain Unity project",
+                )
+            })?
+            .to_owned();
+
+        Ok(Self {
+            reader,
+            names,
+            prefix,
+        })
+    }
+
+    /// Returns the entries in the project with the path relative to the project root
+    fn entries(&self) -> impl Iterator<Item = (usize, &str)> {
+        self.names.iter().enumerate().filter_map(|(index, name)| {
+            let relative = name.strip_prefix(&self.prefix)?;
+            (!relative.is_empty()).then_some((index, relative))
+        })
+    }
+
+    /// Reads the file in the project. Returns `None` if the file does not exist.
+    pub(super) async fn read_file(&mut self, relative: &str) -> io::Result<Option<Vec<u8>>> {
+        let Some((index, _)) = self.entries().find(|&(_, name)| name == relative) else {
+            return Ok(None);
+        };
+        let mut buffer = Vec::new();
+        let mut reader = self.reader.reader_without_entry(index).await.err_mapped()?;
+        reader.read_to_end(&mut buffer).await?;
+        Ok(Some(buffer))
+    }
+
+    /// Extracts the project to `dest` except for the entries `excluded` returns true.
+    pub(super) async fn extract(
+        &mut self,
+        io: &impl IoTrait,
+        dest: &Path,
+        excluded: impl Fn(&str) -> bool,
+        abort: &AbortCheck,
+        mut on_progress: impl FnMut(BackupProgress<'_>),
+    ) -> io::Result<()> {
+        let targets = self
+            .entries()
+            .filter(|&(_, relative)| !excluded(relative))
+            .map(|(index, relative)| (index, relative.to_owned()))
+            .collect::<Vec<_>>();
+
+        let total_files = targets.len();
+        let total_bytes = targets
+            .iter()
+            .map(|&(index, _)| self.reader.file().entries()[index].uncompressed_size())
+            .sum();
+        let mut proceed_bytes = 0;
+
+        on_progress(BackupProgress {
+            total_files,
+            proceed_files: 0,
+            total_bytes,
+            proceed_bytes,
+            last_proceed: "",
+        });
+
+        for (proceed, (index, relative)) in targets.iter().enumerate() {
+            abort.check()?;
+
+            let path = dest.join(relative);
+            if relative.ends_with('/') {
+                io.create_dir_all(&path).await?;
+            } else {
+                io.create_dir_all(path.parent().unwrap()).await?;
+                let mut entry_reader = self
+                    .reader
+                    .reader_without_entry(*index)
+                    .await
+                    .err_mapped()?;
+                let mut dest_file = io.create_new(&path).await?;
+                proceed_bytes += io::copy(&mut entry_reader, &mut dest_file).await?;
+                dest_file.flush().await?;
+            }
+
+            on_progress(BackupProgress {
+                total_files,
+                proceed_files: proceed + 1,
+                total_bytes,
+                proceed_bytes,
+                last_proceed: relative,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+fn find_project_prefix(names: &[String]) -> Option<&str> {
+    const PROJECT_VERSION: &str = "ProjectSettings/ProjectVersion.txt";
+
+    names
+        .iter()
+        .filter_map(|name| name.strip_suffix(PROJECT_VERSION))
+        .filter(|prefix| {
+            prefix.is_empty()
+                || (prefix.ends_with('/') && !prefix[..prefix.len() - 1].contains('/'))
+        })
+        .min_by_key(|prefix| prefix.len())
+}
+
+/// Formats the time as `YYYY-MM-DDTHH-MM-SS` in UTC
+fn format_timestamp(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|x| x.as_secs())
+        .unwrap_or(0);
+
+    let days = (secs / 86400) as i64;
+    let secs_of_day = secs % 86400;
+
+    // https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}-{:02}-{:02}",
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn project_prefix() {
+        fn names(names: &[&str]) -> Vec<String> {
+            names.iter().map(|&x| x.to_owned()).collect()
+        }
+
+        assert_eq!(
+            find_project_prefix(&names(&["Assets/", "ProjectSettings/ProjectVersion.txt"])),
+            Some("")
+        );
+        assert_eq!(
+            find_project_prefix(&names(&[
+                "__MACOSX/Project/ProjectSettings/ProjectVersion.txt",
+                "Project/ProjectSettings/ProjectVersion.txt",
+            ])),
+            Some("Project/")
+        );
+        assert_eq!(
+            find_project_prefix(&names(&["MyProjectSettings/ProjectVersion.txt"])),
+            None
+        );
+        assert_eq!(find_project_prefix(&names(&["Assets/Scene.unity"])), None);
+    }
+
+    #[test]
+    fn timestamp() {
+        let time = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+        assert_eq!(format_timestamp(time(0)), "1970-01-01T00-00-00");
+        assert_eq!(format_timestamp(time(951782400)), "2000-02-29T00-00-00");
+        assert_eq!(format_timestamp(time(1704067199)), "2023-12-31T23-59-59");
+        assert_eq!(format_timestamp(time(1718454896)), "2024-06-15T12-34-56");
+    }
+}
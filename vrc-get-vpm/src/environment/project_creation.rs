@@ -0,0 +1,744 @@
+use crate::environment::project_backup::ProjectArchive;
+use crate::environment::{PackageInstaller, Settings, VccDatabaseConnection};
+use crate::error::{ErrorCode, ErrorParam};
+use crate::io::{DirEntry, EnvironmentIo, FileSystemProjectIo, IoTrait, ProjectIo};
+use crate::unity_project::{AddPackageErr, ResolvePackageErr};
+use crate::utils::is_complete_relative;
+use crate::version::UnityVersion;
+use crate::{
+    AbortCheck, HttpClient, PackageCollection, UnityProject, VRCHAT_RECOMMENDED_2022_UNITY,
+    WINDOWS_RESERVED_CHARS, WINDOWS_RESERVED_NAMES, io,
+};
+use futures::prelude::*;
+use indexmap::IndexMap;
+use serde_json::json;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+const VRCHAT_RECOMMENDED_2022_UNITY_REVISION: &str = "887be4894c44";
+
+/// The last Unity supported by the Worlds SDK without UdonSharp bundled.
+const LEGACY_WORLDS_UNITY: UnityVersion = UnityVersion::new_f1(2019, 4, 31);
+const LEGACY_WORLDS_UNITY_REVISION: &str = "bd5abf232a62";
+
+const VPM_MANIFEST_PATH: &str = "Packages/vpm-manifest.json";
+const PROJECT_VERSION_PATH: &str = "ProjectSettings/ProjectVersion.txt";
+const PROJECT_SETTINGS_PATH: &str = "ProjectSettings/ProjectSettings.asset";
+
+/// The templates for creating new projects
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ProjectTemplate {
+    /// The project with VRChat Avatars SDK
+    Avatars,
+    /// The project with VRChat Worlds SDK, which bundles UdonSharp since 3.4.0.
+    Worlds,
+    /// The Unity 2019 project with VRChat Worlds SDK 3.3, the last version without UdonSharp.
+    WorldsWithoutUdonSharp,
+    /// The VPM project without any packages.
+    Blank,
+    /// The template embedded in the application, like the built-in templates of the GUI.
+    Embedded(EmbeddedTemplate),
+    /// The user-defined template in the templates folder.
+    User(UserTemplate),
+}
+
+/// The template embedded in the application as the list of the files in the project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddedTemplate {
+    id: Box<str>,
+    name: Box<str>,
+    files: Vec<(Box<str>, Box<[u8]>)>,
+}
+
+impl EmbeddedTemplate {
+    /// Creates the template from the pairs of the `/`-separated path relative to the project and the contents.
+    pub fn new(
+        id: impl Into<Box<str>>,
+        name: impl Into<Box<str>>,
+        files: Vec<(Box<str>, Box<[u8]>)>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            files,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn write_files(&self, io: &impl ProjectIo) -> io::Result<()> {
+        io.create_dir_all("Assets".as_ref()).await?;
+        for (path, contents) in &self.files {
+            let path = Path::new(path.as_ref());
+            if !is_complete_relative(path) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "template {} has invalid path: {}",
+                        self.name,
+                        path.display()
+                    ),
+                ));
+            }
+            if let Some(parent) = path.parent() {
+                io.create_dir_all(parent).await?;
+            }
+            io.write(path, contents).await?;
+        }
+        Ok(())
+    }
+}
+
+/// The user-defined template, which is a folder or zip file with a Unity project in the templates folder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserTemplate {
+    name: Box<str>,
+    path: PathBuf,
+    is_zip: bool,
+    unusable_reason: Option<Box<str>>,
+}
+
+impl UserTemplate {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The reason why this template cannot be used to create projects, if any.
+    pub fn unusable_reason(&self) -> Option<&str> {
+        self.unusable_reason.as_deref()
+    }
+}
+
+impl ProjectTemplate {
+    pub const BUILTIN: &'static [ProjectTemplate] = &[
+        ProjectTemplate::Avatars,
+        ProjectTemplate::Worlds,
+        ProjectTemplate::WorldsWithoutUdonSharp,
+        ProjectTemplate::Blank,
+    ];
+
+    pub fn name(&self) -> &str {
+        match self {
+            ProjectTemplate::Avatars => "Avatars",
+            ProjectTemplate::Worlds => "Worlds",
+            ProjectTemplate::WorldsWithoutUdonSharp => "Worlds without UdonSharp",
+            ProjectTemplate::Blank => "Blank",
+            ProjectTemplate::Embedded(template) => template.name(),
+            ProjectTemplate::User(template) => template.name(),
+        }
+    }
+
+    /// The reason why this template cannot be used to create projects, if any.
+    pub fn unusable_reason(&self) -> Option<&str> {
+        match self {
+            ProjectTemplate::User(template) => template.unusable_reason(),
+            _ => None,
+        }
+    }
+
+    /// The dependencies in `vpm-manifest.json` of the skeleton with the version ranges.
+    fn dependencies(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            ProjectTemplate::Avatars => &[("com.vrchat.avatars", "3.x")],
+            ProjectTemplate::Worlds => &[("com.vrchat.worlds", "3.x")],
+            ProjectTemplate::WorldsWithoutUdonSharp => &[("com.vrchat.worlds", "3.3.x")],
+            ProjectTemplate::Blank | ProjectTemplate::Embedded(_) | ProjectTemplate::User(_) => &[],
+        }
+    }
+
+    /// The Unity version and revision of the skeleton.
+    fn unity(&self) -> (UnityVersion, &'static str) {
+        match self {
+            ProjectTemplate::WorldsWithoutUdonSharp => {
+                (LEGACY_WORLDS_UNITY, LEGACY_WORLDS_UNITY_REVISION)
+            }
+            _ => (
+                VRCHAT_RECOMMENDED_2022_UNITY,
+                VRCHAT_RECOMMENDED_2022_UNITY_REVISION,
+            ),
+        }
+    }
+
+    /// Writes the contents of the template to `io`.
+    ///
+    /// For built-in templates, this creates `Assets`, `Packages/vpm-manifest.json`, and
+    /// `ProjectSettings/ProjectVersion.txt`, and other files will be created by Unity when the project is opened.
+    ///
+    /// For embedded templates, the files are written and the product name is replaced with `project_name`.
+    ///
+    /// For user templates, the contents of the template excluding `Library` and `Temp` are copied from `env_io`,
+    /// and the product name in `ProjectSettings/ProjectSettings.asset` is replaced with `project_name`.
+    pub async fn instantiate(
+        &self,
+        env_io: &impl EnvironmentIo,
+        io: &impl ProjectIo,
+        project_name: &str,
+    ) -> io::Result<()> {
+        let template = match self {
+            ProjectTemplate::User(template) => template,
+            ProjectTemplate::Embedded(template) => {
+                template.write_files(io).await?;
+                return rewrite_product_name(io, project_name).await;
+            }
+            _ => return self.write_skeleton(io).await,
+        };
+
+        if let Some(reason) = template.unusable_reason() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("template {} is not usable: {reason}", template.name()),
+            ));
+        }
+
+        let excluded = |relative: &str| match relative.split_once('/') {
+            Some((root, _)) => {
+                root.eq_ignore_ascii_case("Library") || root.eq_ignore_ascii_case("Temp")
+            }
+            // package.json and README.md are metadata of the template
+            None => relative == "package.json" || relative == "README.md",
+        };
+
+        if template.is_zip {
+            let file = io::BufReader::new(env_io.open(&template.path).await?);
+            let mut archive = ProjectArchive::open(file).await?;
+            archive
+                .extract(io, Path::new(""), excluded, &AbortCheck::new(), |_| {})
+                .await?;
+        } else {
+            copy_template_folder(env_io, &template.path, io, excluded).await?;
+        }
+
+        rewrite_product_name(io, project_name).await
+    }
+
+    async fn write_skeleton(&self, io: &impl ProjectIo) -> io::Result<()> {
+        let mut dependencies = serde_json::Map::new();
+        for &(dependency, version) in self.dependencies() {
+            dependencies.insert(dependency.into(), json!({ "version": version }));
+        }
+        let vpm_manifest = json!({
+            "dependencies": dependencies,
+            "locked": {},
+        });
+
+        io.create_dir_all("Assets".as_ref()).await?;
+        io.create_dir_all("Packages".as_ref()).await?;
+        io.create_dir_all("ProjectSettings".as_ref()).await?;
+
+        io.write(
+            VPM_MANIFEST_PATH.as_ref(),
+            serde_json::to_string_pretty(&vpm_manifest)
+                .unwrap()
+                .as_bytes(),
+        )
+        .await?;
+
+        let (unity, revision) = self.unity();
+        let project_version = format!(
+            "m_EditorVersion: {unity}\nm_EditorVersionWithRevision: {unity} ({revision})\n",
+        );
+        io.write(PROJECT_VERSION_PATH.as_ref(), project_version.as_bytes())
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Lists the built-in templates and the user templates in the templates folder.
+///
+/// Each folder or zip file in the templates folder is a user template.
+/// Templates without valid `Packages/vpm-manifest.json` are listed with [`ProjectTemplate::unusable_reason`].
+pub async fn list_project_templates(
+    settings: &Settings,
+    io: &impl EnvironmentIo,
+) -> io::Result<Vec<ProjectTemplate>> {
+    let mut templates = ProjectTemplate::BUILTIN.to_vec();
+
+    let templates_path = settings.user_templates_path();
+    let mut read_dir = match io.read_dir(templates_path).await {
+        Ok(read_dir) => read_dir,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(templates),
+        Err(e) => return Err(e),
+    };
+
+    let mut user_templates = Vec::new();
+    while let Some(entry) = read_dir.try_next().await? {
+        let Ok(file_name) = entry.file_name().into_string() else {
+            continue;
+        };
+        let path = templates_path.join(&file_name);
+
+        let template = if entry.file_type().await?.is_dir() {
+            let unusable_reason = check_template_folder(io, &path).await;
+            UserTemplate {
+                name: file_name.into(),
+                path,
+                is_zip: false,
+                unusable_reason,
+            }
+        } else if let Some(name) = strip_zip_extension(&file_name) {
+            let unusable_reason = check_template_zip(io, &path).await;
+            UserTemplate {
+                name: name.into(),
+                path,
+                is_zip: true,
+                unusable_reason,
+            }
+        } else {
+            continue;
+        };
+
+        if let Some(reason) = &template.unusable_reason {
+            log::warn!("template {} is not usable: {reason}", template.name);
+        }
+
+        user_templates.push(template);
+    }
+
+    user_templates.sort_by(|a, b| a.name.cmp(&b.name));
+    templates.extend(user_templates.into_iter().map(ProjectTemplate::User));
+
+    Ok(templates)
+}
+
+fn strip_zip_extension(file_name: &str) -> Option<&str> {
+    let (name, extension) = file_name.rsplit_once('.')?;
+    extension.eq_ignore_ascii_case("zip").then_some(name)
+}
+
+async fn check_template_folder(io: &impl EnvironmentIo, path: &Path) -> Option<Box<str>> {
+    if !io.is_file(&path.join(PROJECT_VERSION_PATH)).await {
+        return Some(format!("{PROJECT_VERSION_PATH} not found").into());
+    }
+
+    let mut buffer = Vec::new();
+    match io.open(&path.join(VPM_MANIFEST_PATH)).await {
+        Ok(mut file) => {
+            if let Err(e) = file.read_to_end(&mut buffer).await {
+                return Some(e.to_string().into());
+            }
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return Some(format!("{VPM_MANIFEST_PATH} not found").into());
+        }
+        Err(e) => return Some(e.to_string().into()),
+    }
+
+    check_vpm_manifest(&buffer)
+}
+
+async fn check_template_zip(io: &impl EnvironmentIo, path: &Path) -> Option<Box<str>> {
+    let result = async {
+        let file = io::BufReader::new(io.open(path).await?);
+        let mut archive = ProjectArchive::open(file).await?;
+        archive.read_file(VPM_MANIFEST_PATH).await
+    };
+
+    match result.await {
+        Ok(Some(manifest)) => check_vpm_manifest(&manifest),
+        Ok(None) => Some(format!("{VPM_MANIFEST_PATH} not found").into()),
+        Err(e) => Some(e.to_string().into()),
+    }
+}
+
+fn check_vpm_manifest(json: &[u8]) -> Option<Box<str>> {
+    match serde_json::from_slice::<serde_json::Value>(json) {
+        Ok(serde_json::Value::Object(manifest))
+            if manifest
+                .get("dependencies")
+                .is_none_or(serde_json::Value::is_object) =>
+        {
+            None
+        }
+        Ok(_) => Some(format!("{VPM_MANIFEST_PATH} is not a valid manifest").into()),
+        Err(e) => Some(format!("{VPM_MANIFEST_PATH} is not a valid json: {e}").into()),
+    }
+}
+
+async fn copy_template_folder(
+    env_io: &impl EnvironmentIo,
+    template: &Path,
+    io: &impl ProjectIo,
+    excluded: impl Fn(&str) -> bool,
+) -> io::Result<()> {
+    let mut queue = vec![String::new()];
+
+    while let Some(relative_dir) = queue.pop() {
+        io.create_dir_all(relative_dir.as_ref()).await?;
+
+        let mut read_dir = env_io.read_dir(&template.join(&relative_dir)).await?;
+        while let Some(entry) = read_dir.try_next().await? {
+            let Ok(file_name) = entry.file_name().into_string() else {
+                log::warn!("skipping non-utf8 file name in template");
+                continue;
+            };
+
+            if entry.file_type().await?.is_dir() {
+                let relative = format!("{relative_dir}{file_name}/");
+                if !excluded(&relative) {
+                    queue.push(relative);
+                }
+            } else {
+                let relative = format!("{relative_dir}{file_name}");
+                if !excluded(&relative) {
+                    let mut src = env_io.open(&template.join(&relative)).await?;
+                    let mut dest = io.create_new(relative.as_ref()).await?;
+                    io::copy(&mut src, &mut dest).await?;
+                    dest.flush().await?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Replaces `productName` and `productGUID` in `ProjectSettings/ProjectSettings.asset`
+async fn rewrite_product_name(io: &impl ProjectIo, project_name: &str) -> io::Result<()> {
+    let mut buffer = String::new();
+    match io.open(PROJECT_SETTINGS_PATH.as_ref()).await {
+        Ok(mut file) => file.read_to_string(&mut buffer).await?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+
+    set_yaml_value(&mut buffer, "productName: ", &yaml_quote(project_name));
+    set_yaml_value(
+        &mut buffer,
+        "productGUID: ",
+        &uuid::Uuid::new_v4().simple().to_string(),
+    );
+
+    io.write(PROJECT_SETTINGS_PATH.as_ref(), buffer.as_bytes())
+        .await
+}
+
+fn set_yaml_value(buffer: &mut String, finder: &str, value: &str) {
+    let mut search_from = 0;
+    while let Some(pos) = buffer[search_from..].find(finder).map(|x| x + search_from) {
+        let line_start = buffer[..pos].rfind('\n').map(|x| x + 1).unwrap_or(0);
+        if buffer[line_start..pos].bytes().all(|x| x == b' ') {
+            let eol = buffer[pos..]
+                .find(['\r', '\n'])
+                .map(|x| x + pos)
+                .unwrap_or(buffer.len());
+            buffer.replace_range((pos + finder.len())..eol, value);
+            return;
+        }
+        search_from = pos + finder.len();
+    }
+}
+
+fn yaml_quote(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r");
+    format!("\"{escaped}\"")
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CreateProjectOptions<'a> {
+    location: Option<&'a Path>,
+    keep_on_failure: bool,
+    skip_gitignore: bool,
+}
+
+impl<'a> CreateProjectOptions<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The folder to create the project in.
+    ///
+    /// Defaults to the default project path in the settings.
+    pub fn location(mut self, location: &'a Path) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    /// Keeps the partially created project if resolving packages failed.
+    pub fn keep_on_failure(mut self, keep: bool) -> Self {
+        self.keep_on_failure = keep;
+        self
+    }
+
+    /// Doesn't write `.gitignore` to the project.
+    ///
+    /// By default, [`DEFAULT_GITIGNORE`](crate::unity_project::DEFAULT_GITIGNORE) is written unless the template has one.
+    pub fn skip_gitignore(mut self, skip: bool) -> Self {
+        self.skip_gitignore = skip;
+        self
+    }
+}
+
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum CreateProjectError {
+    DefaultProjectPathNotSet,
+    TemplateUnusable { reason: Box<str> },
+    ResolvePackageErr(ResolvePackageErr),
+    AddPackageErr(AddPackageErr),
+    Io(io::Error),
+}
+
+impl fmt::Display for CreateProjectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CreateProjectError::DefaultProjectPathNotSet => {
+                f.write_str("default project path is not set")
+            }
+            CreateProjectError::TemplateUnusable { reason } => {
+                write!(f, "the template is not usable: {reason}")
+            }
+            CreateProjectError::ResolvePackageErr(err) => fmt::Display::fmt(err, f),
+            CreateProjectError::AddPackageErr(err) => fmt::Display::fmt(err, f),
+            CreateProjectError::Io(err) => fmt::Display::fmt(err, f),
+        }
+    }
+}
+
+impl ErrorCode for CreateProjectError {
+    fn code(&self) -> &'static str {
+        match self {
+            CreateProjectError::DefaultProjectPathNotSet => "create-project.default-path-not-set",
+            CreateProjectError::TemplateUnusable { .. } => "create-project.template-unusable",
+            CreateProjectError::ResolvePackageErr(err) => err.code(),
+            CreateProjectError::AddPackageErr(err) => err.code(),
+            CreateProjectError::Io(err) => err.code(),
+        }
+    }
+
+    fn params(&self) -> IndexMap<&'static str, ErrorParam> {
+        match self {
+            CreateProjectError::DefaultProjectPathNotSet => IndexMap::new(),
+            CreateProjectError::TemplateUnusable { reason } => {
+                IndexMap::from([("reason", ErrorParam::text(reason))])
+            }
+            CreateProjectError::ResolvePackageErr(err) => err.params(),
+            CreateProjectError::AddPackageErr(err) => err.params(),
+            CreateProjectError::Io(err) => err.params(),
+        }
+    }
+}
+
+impl std::error::Error for CreateProjectError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CreateProjectError::DefaultProjectPathNotSet => None,
+            CreateProjectError::TemplateUnusable { .. } => None,
+            CreateProjectError::ResolvePackageErr(err) => Some(err),
+            CreateProjectError::AddPackageErr(err) => Some(err),
+            CreateProjectError::Io(err) => Some(err),
+        }
+    }
+}
+
+impl From<ResolvePackageErr> for CreateProjectError {
+    fn from(err: ResolvePackageErr) -> Self {
+        CreateProjectError::ResolvePackageErr(err)
+    }
+}
+
+impl From<AddPackageErr> for CreateProjectError {
+    fn from(err: AddPackageErr) -> Self {
+        CreateProjectError::AddPackageErr(err)
+    }
+}
+
+impl From<io::Error> for CreateProjectError {
+    fn from(err: io::Error) -> Self {
+        CreateProjectError::Io(err)
+    }
+}
+
+type Result<T = (), E = CreateProjectError> = std::result::Result<T, E>;
+
+impl VccDatabaseConnection {
+    /// Creates a new project from the template, resolves packages, and adds the project to the database.
+    ///
+    /// `com.vrchat.core.vpm-resolver` is added if the template has VPM packages without it.
+    ///
+    /// The project folder name is proposed with [`propose_project_path`] so existing folders are never reused.
+    pub async fn create_project<T: HttpClient, IO: EnvironmentIo>(
+        &mut self,
+        settings: &Settings,
+        collection: &(impl PackageCollection + ?Sized),
+        installer: &PackageInstaller<'_, T, IO>,
+        template: &ProjectTemplate,
+        name: &str,
+        options: &CreateProjectOptions<'_>,
+    ) -> Result<UnityProject<IO::ProjectIo>, crate::Error>
+    where
+        IO::ProjectIo: FileSystemProjectIo,
+    {
+        Ok(self
+            .create_project_impl(settings, collection, installer, template, name, options)
+            .await?)
+    }
+
+    async fn create_project_impl<T: HttpClient, IO: EnvironmentIo>(
+        &mut self,
+        settings: &Settings,
+        collection: &(impl PackageCollection + ?Sized),
+        installer: &PackageInstaller<'_, T, IO>,
+        template: &ProjectTemplate,
+        name: &str,
+        options: &CreateProjectOptions<'_>,
+    ) -> Result<UnityProject<IO::ProjectIo>>
+    where
+        IO::ProjectIo: FileSystemProjectIo,
+    {
+        let io = installer.io;
+
+        if let Some(reason) = template.unusable_reason() {
+            return Err(CreateProjectError::TemplateUnusable {
+                reason: reason.into(),
+            });
+        }
+
+        let location = match options.location {
+            Some(location) => location,
+            None => settings
+                .default_project_path()
+                .map(Path::new)
+                .ok_or(CreateProjectError::DefaultProjectPathNotSet)?,
+        };
+
+        io.create_dir_all(location).await?;
+        let path = propose_project_path(io, location, name).await?;
+        io.create_dir_all(&path).await?;
+
+        let result = async {
+            let project_io = io.new_project_io(&io.resolve(&path));
+            template.instantiate(io, &project_io, name.trim()).await?;
+
+            let mut project = UnityProject::load(project_io).await?;
+            let request = project.resolve_request_impl(collection).await?;
+            project
+                .apply_pending_changes_impl(installer, request, &AbortCheck::new())
+                .await?;
+            if let Some(request) = project.vpm_resolver_request_impl(collection).await? {
+                project
+                    .apply_pending_changes_impl(installer, request, &AbortCheck::new())
+                    .await?;
+            }
+
+            if !options.skip_gitignore {
+                project.write_default_gitignore(false).await?;
+            }
+
+            Ok::<_, CreateProjectError>(project)
+        }
+        .await;
+
+        let project = match result {
+            Ok(project) => project,
+            Err(e) => {
+                if !options.keep_on_failure {
+                    io.remove_dir_all(&path).await.ok();
+                }
+                return Err(e);
+            }
+        };
+
+        self.add_project(&project).await?;
+
+        Ok(project)
+    }
+}
+
+/// Sanitizes the project name to be used as a folder name on all platforms.
+///
+/// The characters not allowed on windows are replaced with `_`.
+pub fn sanitize_project_name(name: &str) -> String {
+    let name = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if WINDOWS_RESERVED_CHARS.contains(&c) || c.is_control() {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect::<String>();
+
+    // windows does not allow trailing dots and spaces
+    let mut name = name.trim_end_matches(['.', ' ']).to_owned();
+
+    if name.is_empty() {
+        name.push_str("New Project");
+    }
+
+    let stem = name.split('.').next().unwrap().to_ascii_lowercase();
+    if WINDOWS_RESERVED_NAMES.contains(&stem.as_str()) {
+        name.insert(stem.len(), '_');
+    }
+
+    name
+}
+
+/// Proposes the path for the new project in `location`.
+///
+/// The name is sanitized with [`sanitize_project_name`] and a number is appended if the folder already exists.
+pub async fn propose_project_path(
+    io: &impl IoTrait,
+    location: &Path,
+    name: &str,
+) -> io::Result<PathBuf> {
+    let name = sanitize_project_name(name);
+
+    let mut path = location.join(&name);
+    let mut index = 1;
+    while io.metadata(&path).await.is_ok() {
+        path = location.join(format!("{name}-{index}"));
+        index += 1;
+    }
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn product_name() {
+        let mut settings = "PlayerSettings:\n  companyName: DefaultCompany\n  productName: Template\r\n  productGUID: 0\n".to_owned();
+        set_yaml_value(
+            &mut settings,
+            "productName: ",
+            &yaml_quote("My \"Project\""),
+        );
+        assert_eq!(
+            settings,
+            "PlayerSettings:\n  companyName: DefaultCompany\n  productName: \"My \\\"Project\\\"\"\r\n  productGUID: 0\n"
+        );
+    }
+
+    #[test]
+    fn sanitize() {
+        assert_eq!(sanitize_project_name("My Avatar"), "My Avatar");
+        assert_eq!(sanitize_project_name("  My Avatar  "), "My Avatar");
+        assert_eq!(sanitize_project_name("a/b\\c:d*e?"), "a_b_c_d_e_");
+        assert_eq!(sanitize_project_name("project..."), "project");
+        assert_eq!(sanitize_project_name("   "), "New Project");
+        assert_eq!(sanitize_project_name("con"), "con_");
+        assert_eq!(sanitize_project_name("COM1.project"), "COM1_.project");
+        assert_eq!(sanitize_project_name("Console"), "Console");
+        assert_eq!(sanitize_project_name("アバター"), "アバター");
+    }
+}
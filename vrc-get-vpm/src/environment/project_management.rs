@@ -1,15 +1,16 @@
-use crate::environment::VccDatabaseConnection;
 use crate::environment::settings::Settings;
+use crate::environment::{RestoreOptions, VccDatabaseConnection, propose_project_path};
 use crate::io::{EnvironmentIo, FileSystemProjectIo, ProjectIo};
+use crate::unity_project::BackupProgress;
 use crate::utils::{check_absolute_path, normalize_path};
 use crate::version::UnityVersion;
-use crate::{ProjectType, UnityProject, io};
+use crate::{AbortCheck, ProjectType, UnityProject, io};
 use futures::future::join_all;
 use log::error;
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::ffi::OsStr;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use vrc_get_litedb::bson::{Array, DateTime, Document, Value};
 use vrc_get_litedb::document;
 use vrc_get_litedb::file_io::BsonAutoId;
@@ -68,10 +69,11 @@ impl VccDatabaseConnection {
                 {
                     let project = UnityProject::load(io.new_project_io(path)).await?;
                     let detected_type = project.detect_project_type().await?;
+                    let version = project.project_version().ok();
                     Ok((
                         detected_type,
-                        project.unity_version(),
-                        project.unity_revision().map(|x| x.to_owned()),
+                        version.map(|x| x.version()),
+                        version.and_then(|x| x.revision()).map(|x| x.to_owned()),
                     ))
                 }
                 let (project_type, unity_version, unity_revision) = get_project_type(
@@ -90,6 +92,7 @@ impl VccDatabaseConnection {
 
         if !to_insert.is_empty() {
             self.db
+                .as_mut()
                 .insert(
                     COLLECTION,
                     to_insert.iter().map(|x| x.to_bson()).collect(),
@@ -111,7 +114,9 @@ impl VccDatabaseConnection {
             }
         }
 
-        self.db.delete(COLLECTION, &ids_to_delete);
+        if !ids_to_delete.is_empty() {
+            self.db.as_mut().delete(COLLECTION, &ids_to_delete);
+        }
 
         Ok(())
     }
@@ -132,9 +137,13 @@ impl VccDatabaseConnection {
         )
         .await;
 
-        self.db
-            .update(COLLECTION, changed_projects.into_iter().flatten().collect())
-            .expect("updating project");
+        let changed_projects = changed_projects.into_iter().flatten().collect::<Vec<_>>();
+        if !changed_projects.is_empty() {
+            self.db
+                .as_mut()
+                .update(COLLECTION, changed_projects)
+                .expect("updating project");
+        }
 
         async fn update_project_with_actual_data(
             io: &impl EnvironmentIo,
@@ -178,9 +187,9 @@ impl VccDatabaseConnection {
             };
 
             let loaded_project = UnityProject::load(io.new_project_io(path)).await?;
-            if let Some(unity_version) = loaded_project.unity_version() {
-                let unity_version = unity_version.to_string();
-                if let Some(revision) = loaded_project.unity_revision() {
+            if let Ok(project_version) = loaded_project.project_version() {
+                let unity_version = project_version.version().to_string();
+                if let Some(revision) = project_version.revision() {
                     if Some(unity_version.as_str()) != project[UNITY_VERSION].as_str()
                         || Some(revision)
                             != project[VRC_GET]
@@ -284,8 +293,15 @@ impl VccDatabaseConnection {
             }
         }
 
-        self.db.update(COLLECTION, updates).expect("update");
-        self.db.delete(COLLECTION, &deletes);
+        if !updates.is_empty() {
+            self.db
+                .as_mut()
+                .update(COLLECTION, updates)
+                .expect("update");
+        }
+        if !deletes.is_empty() {
+            self.db.as_mut().delete(COLLECTION, &deletes);
+        }
     }
 
     pub fn get_projects(&self) -> Vec<UserProject> {
@@ -320,18 +336,49 @@ impl VccDatabaseConnection {
         };
 
         project.insert(LAST_MODIFIED, DateTime::now());
-        self.db.update(COLLECTION, vec![project]).expect("update");
+        self.db
+            .as_mut()
+            .update(COLLECTION, vec![project])
+            .expect("update");
         Ok(())
     }
 
+    /// Updates the project type in the database with the type detected from the project.
+    ///
+    /// This should be called after changes that can change the project type, like migrating legacy projects.
+    /// Returns false if the project is not in the database.
+    pub async fn update_project_type<ProjectIO: ProjectIo + FileSystemProjectIo>(
+        &mut self,
+        project: &UnityProject<ProjectIO>,
+    ) -> io::Result<bool> {
+        let path = project.project_dir().to_str().ok_or(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "project path is not utf8",
+        ))?;
+        let Some(mut document) = self.find_project_bson(path)?.cloned() else {
+            return Ok(false);
+        };
+
+        let project_type = project.detect_project_type().await?;
+        document.insert(TYPE, project_type as i32);
+        self.db
+            .as_mut()
+            .update(COLLECTION, vec![document])
+            .expect("update");
+        Ok(true)
+    }
+
     pub fn update_project(&mut self, project: &UserProject) {
         self.db
+            .as_mut()
             .update(COLLECTION, vec![project.to_bson()])
             .expect("update");
     }
 
     pub fn remove_project(&mut self, project: &UserProject) {
-        self.db.delete(COLLECTION, &[project.bson[ID].clone()]);
+        self.db
+            .as_mut()
+            .delete(COLLECTION, &[project.bson[ID].clone()]);
     }
 
     pub async fn add_project<ProjectIO: ProjectIo + FileSystemProjectIo>(
@@ -344,11 +391,11 @@ impl VccDatabaseConnection {
             io::ErrorKind::InvalidData,
             "project path is not utf8",
         ))?;
-        let unity_version = project.unity_version().ok_or(io::Error::new(
-            io::ErrorKind::InvalidData,
-            "project has no unity version",
-        ))?;
-        let unity_revision = project.unity_revision().ok_or(io::Error::new(
+        let project_version = project
+            .project_version()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        let unity_version = project_version.version();
+        let unity_revision = project_version.revision().ok_or(io::Error::new(
             io::ErrorKind::InvalidData,
             "project has no unity revision",
         ))?;
@@ -359,6 +406,7 @@ impl VccDatabaseConnection {
         new_project.set_unity_revision(unity_version, unity_revision.to_owned());
 
         self.db
+            .as_mut()
             .insert(
                 COLLECTION,
                 vec![new_project.to_bson()],
@@ -368,6 +416,153 @@ impl VccDatabaseConnection {
 
         Ok(())
     }
+
+    /// Copies the project at `source` to a new folder and adds the copy to the database.
+    ///
+    /// The copy is created in `location` or next to the source project, with the name proposed by
+    /// [`propose_project_path`]. `Library`, `Temp`, `obj`, and `Logs` are not copied, and the
+    /// modification times are preserved so that Unity doesn't reimport the assets.
+    ///
+    /// The database entry is copied from the source project if it exists, with new id and timestamps
+    /// and not marked as favorite.
+    /// If the copy is failed or aborted with `abort`, the partially copied folder will be removed.
+    ///
+    /// Returns the path to the copied project.
+    pub async fn clone_project<IO: EnvironmentIo>(
+        &mut self,
+        io: &IO,
+        source: &str,
+        new_name: &str,
+        location: Option<&Path>,
+        abort: &AbortCheck,
+    ) -> Result<PathBuf, crate::Error> {
+        Ok(self
+            .clone_project_impl(io, source, new_name, location, abort)
+            .await?)
+    }
+
+    async fn clone_project_impl<IO: EnvironmentIo>(
+        &mut self,
+        io: &IO,
+        source: &str,
+        new_name: &str,
+        location: Option<&Path>,
+        abort: &AbortCheck,
+    ) -> io::Result<PathBuf> {
+        check_absolute_path(source)?;
+        let source_path = normalize_path(source.as_ref());
+        let location = match location {
+            Some(location) => location,
+            None => source_path.parent().ok_or(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "project path has no parent",
+            ))?,
+        };
+
+        let source_project = UnityProject::load(io.new_project_io(&source_path)).await?;
+
+        let new_path = io.resolve(&propose_project_path(io, location, new_name).await?);
+        let new_path_str = new_path.to_str().ok_or(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "project path is not utf8",
+        ))?;
+        io.create_dir_all(&new_path).await?;
+
+        let result = async {
+            let new_io = io.new_project_io(&new_path);
+            source_project.copy_files_to(&new_io, false, abort).await?;
+            UnityProject::load(new_io).await
+        }
+        .await;
+
+        let new_project = match result {
+            Ok(project) => project,
+            Err(e) => {
+                if let Err(e) = io.remove_dir_all(&new_path).await {
+                    error!("failed to remove partially cloned project: {e}");
+                }
+                return Err(e);
+            }
+        };
+
+        let source_document = self
+            .find_project_bson(source_path.to_str().unwrap_or(source))?
+            .cloned();
+        let document = match source_document {
+            Some(mut document) => {
+                let now = DateTime::now();
+                document.remove(ID);
+                document.insert(PATH, new_path_str);
+                document.insert(CREATED_AT, now);
+                document.insert(LAST_MODIFIED, now);
+                document.insert(FAVORITE, false);
+                document
+            }
+            None => {
+                let project_type = new_project.detect_project_type().await?;
+                let unity_version = new_project.unity_version();
+                UserProject::new(new_path_str.into(), unity_version, project_type).to_bson()
+            }
+        };
+
+        self.db
+            .as_mut()
+            .insert(COLLECTION, vec![document], BsonAutoId::ObjectId)
+            .expect("insert");
+
+        Ok(new_path)
+    }
+
+    /// Restores the project from the zip backup like [`restore_project_backup`](crate::environment::restore_project_backup),
+    /// and adds the restored project to the database if [`RestoreOptions::register`] is set.
+    ///
+    /// The project already in the database is not added again.
+    /// If adding the project is failed, the restored project is left as is.
+    ///
+    /// Returns the path to the restored project.
+    pub async fn restore_project_backup<IO: EnvironmentIo>(
+        &mut self,
+        io: &IO,
+        archive: &Path,
+        destination: &Path,
+        options: &RestoreOptions,
+        abort: &AbortCheck,
+        on_progress: impl FnMut(BackupProgress<'_>),
+    ) -> Result<PathBuf, crate::Error> {
+        let path = crate::environment::restore_project_backup(
+            io,
+            archive,
+            destination,
+            options,
+            abort,
+            on_progress,
+        )
+        .await?;
+
+        if !options.register {
+            return Ok(path);
+        }
+
+        let path_str = path.to_str().ok_or(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "project path is not utf8",
+        ))?;
+        if self.find_project_bson(path_str)?.is_some() {
+            return Ok(path);
+        }
+
+        let project = UnityProject::load(io.new_project_io(&path)).await?;
+        let project_type = project.detect_project_type().await?;
+        let unity_version = project.unity_version();
+        let document = UserProject::new(path_str.into(), unity_version, project_type).to_bson();
+
+        self.db
+            .as_mut()
+            .insert(COLLECTION, vec![document], BsonAutoId::ObjectId)
+            .expect("insert");
+
+        Ok(path)
+    }
 }
 
 pub struct UserProject {
@@ -2,29 +2,33 @@ use crate::environment::repo_source::RepoSource;
 use crate::environment::{
     CURATED_URL_STR, LOCAL_CURATED_PATH, LOCAL_OFFICIAL_PATH, OFFICIAL_URL_STR, Settings,
 };
+use crate::event::Event;
 use crate::io::EnvironmentIo;
 use crate::repository::RemoteRepository;
 use crate::repository::local::LocalCachedRepository;
 use crate::traits::HttpClient;
 use crate::utils::{read_json_file, to_vec_pretty_os_eol, try_load_json};
-use crate::{UserRepoSetting, io};
+use crate::{AbortCheck, UserRepoSetting, io};
 use futures::future::join_all;
 use indexmap::IndexMap;
 use lazy_static::lazy_static;
 use log::{error, warn};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use url::Url;
 
 #[derive(Debug)]
 pub(crate) struct RepoHolder {
     cached_repos_new: HashMap<Box<Path>, LocalCachedRepository>,
+    /// The caches downloaded but not written to the file yet.
+    unsaved_caches: HashSet<Box<Path>>,
 }
 
 impl RepoHolder {
     pub(crate) fn new() -> Self {
         RepoHolder {
             cached_repos_new: HashMap::new(),
+            unsaved_caches: HashSet::new(),
         }
     }
 }
@@ -35,6 +39,7 @@ impl RepoHolder {
         settings: &Settings,
         io: &impl EnvironmentIo,
         http: Option<&impl HttpClient>,
+        abort: &AbortCheck,
     ) -> io::Result<Self> {
         let predefined_repos = Self::get_predefined_repos(settings).into_iter();
         let user_repos = settings
@@ -44,7 +49,7 @@ impl RepoHolder {
         io.create_dir_all("Repos".as_ref()).await?;
         let mut repo_cache = Self::new();
         repo_cache
-            .load_repos(http, io, predefined_repos.chain(user_repos))
+            .load_repos(http, io, predefined_repos.chain(user_repos), abort)
             .await?;
 
         Ok(repo_cache)
@@ -87,23 +92,35 @@ impl RepoHolder {
         http: Option<&impl HttpClient>,
         io: &IO,
         sources: impl Iterator<Item = RepoSource<'a>>,
+        abort: &AbortCheck,
     ) -> io::Result<()> {
         let start = std::time::Instant::now();
         let repos = join_all(sources.map(|src| async move {
-            match Self::load_repo_from_source(http, io, &src).await {
-                Ok(Some(v)) => Some((v, src.cache_path().into())),
-                Ok(None) => None,
-                Err(e) => {
-                    error!("loading repo '{}': {}", src.cache_path().display(), e);
-                    None
-                }
-            }
+            io.observer().on_event(&Event::RepositoryRefreshStarted {
+                cache_path: src.cache_path(),
+                url: src.url(),
+            });
+            let result = Self::load_repo_from_source(http, io, &src, abort).await;
+            io.observer().on_event(&Event::RepositoryRefreshFinished {
+                cache_path: src.cache_path(),
+                url: src.url(),
+                error: result.as_ref().err(),
+            });
+            result
+                .ok()
+                .map(|(v, downloaded)| (v, downloaded, src.cache_path().into()))
         }))
         .await;
         let duration = std::time::Instant::now() - start;
         log::info!("downloading repos took {:?}", duration);
 
-        for (repo, path) in repos.into_iter().flatten() {
+        // nothing is written until saved, so the caches are consistent even if aborted
+        abort.check()?;
+
+        for (repo, downloaded, path) in repos.into_iter().flatten() {
+            if downloaded {
+                self.unsaved_caches.insert(Box::clone(&path));
+            }
             self.cached_repos_new.insert(path, repo);
         }
 
@@ -114,56 +131,64 @@ impl RepoHolder {
         client: Option<&impl HttpClient>,
         io: &IO,
         source: &RepoSource<'_>,
-    ) -> io::Result<Option<LocalCachedRepository>> {
+        abort: &AbortCheck,
+    ) -> io::Result<(LocalCachedRepository, bool)> {
         if let Some(url) = &source.url() {
-            RepoHolder::load_remote_repo(client, io, source.headers(), source.cache_path(), url)
-                .await
-                .map(Some)
+            RepoHolder::load_remote_repo(
+                client,
+                io,
+                source.headers(),
+                source.cache_path(),
+                url,
+                abort,
+            )
+            .await
         } else {
             RepoHolder::load_local_repo(io, source.cache_path())
                 .await
-                .map(Some)
+                .map(|repo| (repo, false))
         }
     }
 
+    /// Loads the cache of the remote repository, downloading the newer one if possible.
+    ///
+    /// Returns true with the repository if it's downloaded and should be saved.
     async fn load_remote_repo(
         client: Option<&impl HttpClient>,
         io: &impl EnvironmentIo,
         headers: &IndexMap<Box<str>, Box<str>>,
         path: &Path,
         remote_url: &Url,
-    ) -> io::Result<LocalCachedRepository> {
+        abort: &AbortCheck,
+    ) -> io::Result<(LocalCachedRepository, bool)> {
         if let Some(mut loaded) = try_load_json::<LocalCachedRepository>(io, path).await? {
             if let Some(client) = client {
                 // if it's possible to download remote repo, try to update with that
                 log::debug!("downloading remote repo '{}' with local cache", remote_url);
-                match RemoteRepository::download_with_etag(
-                    client,
-                    remote_url,
-                    loaded.headers(),
-                    loaded.vrc_get.as_ref().map(|x| x.etag.as_ref()),
-                )
-                .await
+                match abort
+                    .run(RemoteRepository::download_with_etag(
+                        client,
+                        remote_url,
+                        loaded.headers(),
+                        loaded.vrc_get.as_ref().map(|x| x.etag.as_ref()),
+                    ))
+                    .await
                 {
                     Ok(None) => log::debug!("cache matched downloading '{}'", remote_url),
                     Ok(Some((remote_repo, etag))) => {
                         log::debug!("downloaded finished '{}'", remote_url);
                         loaded.set_repo(remote_repo);
                         loaded.set_etag(etag);
-
-                        io.write(path, &to_vec_pretty_os_eol(&loaded)?)
-                            .await
-                            .unwrap_or_else(|e| {
-                                error!("writing local repo cache '{}': {}", path.display(), e)
-                            });
+                        return Ok((loaded, true));
                     }
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => return Err(e),
                     Err(e) => {
                         error!("fetching remote repo '{}': {}", remote_url, e);
                     }
                 }
             }
 
-            Ok(loaded)
+            Ok((loaded, false))
         } else {
             // if local repository not found: try downloading remote one
             let Some(client) = client else {
@@ -173,21 +198,16 @@ impl RepoHolder {
                 ));
             };
             log::debug!("downloading remote repo '{}'", remote_url);
-            let (remote_repo, etag) =
-                RemoteRepository::download(client, remote_url, headers).await?;
+            let (remote_repo, etag) = abort
+                .run(RemoteRepository::download(client, remote_url, headers))
+                .await?;
             log::debug!("downloaded finished '{}'", remote_url);
 
             let mut local_cache = LocalCachedRepository::new(remote_repo, headers.clone());
 
             local_cache.set_etag(etag);
 
-            io.write(path, &to_vec_pretty_os_eol(&local_cache)?)
-                .await
-                .unwrap_or_else(|e| {
-                    error!("writing local repo cache '{}': {}", path.display(), e);
-                });
-
-            Ok(local_cache)
+            Ok((local_cache, true))
         }
     }
 
@@ -198,7 +218,22 @@ impl RepoHolder {
         read_json_file::<LocalCachedRepository>(io.open(path).await?, path).await
     }
 
-    pub(crate) fn into_repos(self) -> HashMap<Box<Path>, LocalCachedRepository> {
-        self.cached_repos_new
+    /// Returns the loaded repositories and the paths of the caches not saved yet.
+    pub(crate) fn into_repos(
+        self,
+    ) -> (
+        HashMap<Box<Path>, LocalCachedRepository>,
+        HashSet<Box<Path>>,
+    ) {
+        (self.cached_repos_new, self.unsaved_caches)
     }
 }
+
+/// Writes the cache of the repository at `path`.
+pub(crate) async fn save_repo_cache(
+    io: &impl EnvironmentIo,
+    path: &Path,
+    repo: &LocalCachedRepository,
+) -> io::Result<()> {
+    io.write(path, &to_vec_pretty_os_eol(repo)?).await
+}
@@ -0,0 +1,164 @@
+use crate::environment::{LOCAL_CURATED_PATH, LOCAL_OFFICIAL_PATH, REPO_CACHE_FOLDER, Settings};
+use crate::io;
+use crate::io::{DirEntry, EnvironmentIo};
+use futures::prelude::*;
+use log::debug;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// The files modified recently may be being written by another process, so they are kept.
+const RECENT_FILE_THRESHOLD: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The package cache management file used by VCC but not used by vrc-get
+const VCC_PACKAGE_CACHE_FILE: &str = "package-cache.json";
+
+/// What owns a file directly in the `Repos` folder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RepoCacheOwner {
+    /// The cache of the official or curated repository.
+    ///
+    /// They are kept even if ignored by the settings since VCC may use them.
+    PredefinedRepository,
+    /// The cache of the repository registered in the settings
+    UserRepository,
+    /// The folder of the zip files of a package. See `package_cache` module.
+    PackageCache,
+    /// The package cache management file of VCC
+    Vcc,
+}
+
+/// The mapping from the names of files in the `Repos` folder to their owners.
+struct RepoCacheOwnership {
+    files: HashMap<OsString, RepoCacheOwner>,
+}
+
+impl RepoCacheOwnership {
+    fn new(settings: &Settings, io: &impl EnvironmentIo) -> Self {
+        let mut files = HashMap::new();
+
+        for path in [LOCAL_OFFICIAL_PATH, LOCAL_CURATED_PATH] {
+            let file_name = Path::new(path).file_name().unwrap();
+            files.insert(file_name.to_owned(), RepoCacheOwner::PredefinedRepository);
+        }
+        files.insert(VCC_PACKAGE_CACHE_FILE.into(), RepoCacheOwner::Vcc);
+
+        // the local path of user repositories are absolute paths
+        let repos_base = io.resolve(REPO_CACHE_FOLDER.as_ref());
+        for repo in settings.get_user_repos() {
+            let Ok(relative) = repo.local_path().strip_prefix(&repos_base) else {
+                continue;
+            };
+            // the file must be a direct child of the Repos folder
+            if let (Some(file_name), true) = (
+                relative.file_name(),
+                relative.parent().is_none_or(|x| x.as_os_str().is_empty()),
+            ) {
+                files.insert(file_name.to_owned(), RepoCacheOwner::UserRepository);
+            }
+        }
+
+        Self { files }
+    }
+
+    fn owner(&self, file_name: &OsStr, is_dir: bool) -> Option<RepoCacheOwner> {
+        if is_dir {
+            // folders are named with the package name so we cannot tell from the name
+            return Some(RepoCacheOwner::PackageCache);
+        }
+        self.files.get(file_name).copied()
+    }
+}
+
+/// A file removed by [`cleanup_repos_folder`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct RemovedRepoFile {
+    /// The path relative to the environment folder.
+    pub path: PathBuf,
+    /// The size of the file in bytes.
+    pub size: u64,
+}
+
+/// The result of [`cleanup_repos_folder`].
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReposCleanupResult {
+    removed: Vec<RemovedRepoFile>,
+    total_size: u64,
+    skipped: Vec<PathBuf>,
+}
+
+impl ReposCleanupResult {
+    /// The removed files, or the files would be removed if it's a dry run.
+    pub fn removed(&self) -> &[RemovedRepoFile] {
+        &self.removed
+    }
+
+    /// The total size of the removed files in bytes.
+    pub fn total_size(&self) -> u64 {
+        self.total_size
+    }
+
+    /// The unknown files kept since they are modified recently.
+    pub fn skipped(&self) -> &[PathBuf] {
+        &self.skipped
+    }
+}
+
+/// Removes the files in the `Repos` folder not owned by any of the repositories.
+///
+/// The caches of the official, curated, and user repositories, and the package cache folders are kept.
+/// Other files, like caches of removed repositories or `<uuid>.json` left by the official VPM CLI on errors,
+/// are removed.
+/// The unknown files modified in a day are kept since another process may be writing them.
+/// If `dry_run` is true, nothing is removed.
+pub async fn cleanup_repos_folder(
+    settings: &Settings,
+    io: &impl EnvironmentIo,
+    dry_run: bool,
+) -> io::Result<ReposCleanupResult> {
+    let ownership = RepoCacheOwnership::new(settings, io);
+    let mut result = ReposCleanupResult::default();
+
+    let mut entries = match io.read_dir(REPO_CACHE_FOLDER.as_ref()).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(result),
+        Err(e) => return Err(e),
+    };
+
+    let now = SystemTime::now();
+    while let Some(entry) = entries.try_next().await? {
+        let file_name = entry.file_name();
+        let metadata = entry.metadata().await?;
+        if ownership.owner(&file_name, metadata.is_dir()).is_some() {
+            continue;
+        }
+
+        let path = Path::new(REPO_CACHE_FOLDER).join(&file_name);
+        let recent = metadata
+            .modified()
+            .and_then(|x| now.duration_since(x).ok())
+            .is_some_and(|x| x < RECENT_FILE_THRESHOLD);
+        if recent {
+            debug!("keeping recently modified file {}", path.display());
+            result.skipped.push(path);
+            continue;
+        }
+
+        if !dry_run {
+            io.remove_file(&path).await?;
+            debug!("removed {}", path.display());
+        }
+        result.total_size += metadata.len();
+        result.removed.push(RemovedRepoFile {
+            path,
+            size: metadata.len(),
+        });
+    }
+
+    Ok(result)
+}
@@ -3,6 +3,7 @@ use std::fmt::Write;
 use std::path::{Path, PathBuf};
 
 use indexmap::IndexMap;
+use log::warn;
 use url::Url;
 
 use crate::environment::vpm_settings::VpmSettings;
@@ -12,9 +13,15 @@ use crate::io::EnvironmentIo;
 use crate::package_manifest::LooseManifest;
 use crate::repository::RemoteRepository;
 use crate::utils::{normalize_path, try_load_json};
-use crate::{UserRepoSetting, io};
-
-#[derive(Debug, Clone)]
+use crate::{UserRepoSetting, io, validate_package_name};
+
+/// The settings of the environment.
+///
+/// The methods changing the settings never write to the file.
+/// Call [`Settings::save`] to write the changes, which writes nothing if nothing is changed.
+///
+/// This is not [`Clone`] so that the unsaved changes have only one owner to save them.
+#[derive(Debug)]
 pub struct Settings {
     /// parsed settings
     vpm: VpmSettings,
@@ -32,11 +39,25 @@ impl Settings {
         })
     }
 
-    pub async fn save(&self, io: &impl EnvironmentIo) -> io::Result<()> {
+    pub async fn save(&mut self, io: &impl EnvironmentIo) -> io::Result<()> {
         self.vpm.save(io).await?;
+        self.vrc_get.save(io).await?;
 
         Ok(())
     }
+
+    /// Whether the settings have changes not saved yet.
+    pub fn is_changed(&self) -> bool {
+        self.vpm.is_changed() || self.vrc_get.is_changed()
+    }
+}
+
+impl Drop for Settings {
+    fn drop(&mut self) {
+        if self.is_changed() {
+            warn!("the changes to the settings are dropped without saving");
+        }
+    }
 }
 
 /// VCC Settings / Stores
@@ -72,6 +93,11 @@ impl Settings {
     pub fn set_unity_hub_path(&mut self, value: &str) {
         self.vpm.set_unity_hub(value);
     }
+
+    /// The `pathToUnityExe` saved by older VCC, or empty if not set.
+    pub fn legacy_unity_path(&self) -> &str {
+        self.vpm.legacy_unity_exe()
+    }
 }
 
 #[cfg(feature = "experimental-project-management")]
@@ -125,6 +151,47 @@ impl Settings {
     pub fn ignore_official_repository(&self) -> bool {
         self.vrc_get.ignore_official_repository()
     }
+
+    /// The folder for user-defined project templates.
+    ///
+    /// Relative paths are relative to the environment folder.
+    pub fn user_templates_path(&self) -> &Path {
+        self.vrc_get.templates_path()
+    }
+
+    /// Whether packages should be installed as links to the shared package store.
+    ///
+    /// See [`PackageInstaller::use_package_store`](crate::environment::PackageInstaller::use_package_store).
+    pub fn use_package_store(&self) -> bool {
+        self.vrc_get.use_package_store()
+    }
+
+    pub fn set_use_package_store(&mut self, value: bool) {
+        self.vrc_get.set_use_package_store(value);
+    }
+
+    /// Whether package files should be hard linked from the shared package store.
+    ///
+    /// See [`PackageInstaller::use_hard_links`](crate::environment::PackageInstaller::use_hard_links).
+    pub fn use_hard_links(&self) -> bool {
+        self.vrc_get.use_hard_links()
+    }
+
+    pub fn set_use_hard_links(&mut self, value: bool) {
+        self.vrc_get.set_use_hard_links(value);
+    }
+
+    /// Whether the project should be backed up before applying destructive changes.
+    ///
+    /// See [`apply_pending_changes_with_backup`](crate::environment::apply_pending_changes_with_backup).
+    pub fn auto_backup(&self) -> bool {
+        self.vrc_get.auto_backup()
+    }
+
+    /// The number of automatic backups kept for each project.
+    pub fn auto_backup_count(&self) -> usize {
+        self.vrc_get.auto_backup_count().unwrap_or(5)
+    }
 }
 
 /// User Package Managements
@@ -153,7 +220,8 @@ impl Settings {
         }
 
         match try_load_json::<LooseManifest>(io, &pkg_path.join("package.json")).await {
-            Ok(Some(LooseManifest(package_json))) => package_json,
+            Ok(Some(LooseManifest(package_json)))
+                if validate_package_name(package_json.name()).is_ok() => {}
             _ => {
                 return AddUserPackageResult::BadPackage;
             }
@@ -163,6 +231,15 @@ impl Settings {
 
         AddUserPackageResult::Success
     }
+
+    /// Adds the user package folder without checking the folder, for importing configuration from another machine.
+    pub(crate) fn add_user_package_unchecked(&mut self, pkg_path: PathBuf) -> bool {
+        if self.vpm.user_package_folders().contains(&pkg_path) {
+            return false;
+        }
+        self.vpm.add_user_package_folder(pkg_path);
+        true
+    }
 }
 
 /// Repository Managements
@@ -232,6 +309,11 @@ impl Settings {
         true
     }
 
+    /// Adds the repository setting without the cache. The cache will be created on the next load.
+    pub(crate) fn add_user_repo_setting(&mut self, setting: UserRepoSetting) {
+        self.vpm.add_user_repo(setting);
+    }
+
     pub fn add_local_repo(&mut self, path: &Path, name: Option<&str>) -> bool {
         let path = normalize_path(path);
 
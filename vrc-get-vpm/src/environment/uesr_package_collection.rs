@@ -1,8 +1,8 @@
 use super::Settings;
-use crate::PackageManifest;
 use crate::io::EnvironmentIo;
 use crate::package_manifest::LooseManifest;
 use crate::utils::try_load_json;
+use crate::{PackageManifest, validate_package_name};
 use std::path::{Path, PathBuf};
 
 #[derive(Debug)]
@@ -32,6 +32,10 @@ impl UserPackageCollection {
     pub(crate) async fn try_add_package(&mut self, io: &impl EnvironmentIo, folder: &Path) {
         match try_load_json::<LooseManifest>(io, &folder.join("package.json")).await {
             Ok(Some(LooseManifest(package_json))) => {
+                if let Err(e) = validate_package_name(package_json.name()) {
+                    log::warn!("Skipping user package in {}: {e}", folder.display());
+                    return;
+                }
                 self.user_packages.push((folder.to_owned(), package_json));
             }
             Ok(None) => {
@@ -1,11 +1,14 @@
+use crate::AbortCheck;
 use crate::environment::{Settings, VccDatabaseConnection};
-use crate::io;
+use crate::error::{IoCategory, categorized};
+use crate::event::{Event, UnityProbeFailure};
 use crate::io::EnvironmentIo;
-use crate::unity_hub::get_executable_path;
+use crate::unity_hub::{get_app_path, get_executable_path, load_unity_version};
 use crate::utils::{check_absolute_path, normalize_path};
-use crate::version::UnityVersion;
+use crate::version::{ReleaseType, UnityVersion};
 use log::info;
 use std::borrow::Cow;
+use std::cmp::Reverse;
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use vrc_get_litedb::bson::Document;
@@ -26,9 +29,13 @@ impl VccDatabaseConnection {
             .collect()
     }
 
-    pub fn add_unity_installation(&mut self, path: &str, version: UnityVersion) -> io::Result<()> {
-        check_absolute_path(path)?;
-        Self::add_unity_installation_internal(&mut self.db, path, version, false);
+    pub fn add_unity_installation(
+        &mut self,
+        path: &str,
+        version: UnityVersion,
+    ) -> Result<(), crate::Error> {
+        check_absolute_path(path).map_err(|e| categorized(IoCategory::UnityManagement, e))?;
+        Self::add_unity_installation_internal(self.db.as_mut(), path, version, false);
         Ok(())
     }
 
@@ -45,48 +52,41 @@ impl VccDatabaseConnection {
     }
 
     pub fn remove_unity_installation(&mut self, unity: &UnityInstallation) {
-        self.db.delete(COLLECTION, &[unity.bson["_id"].clone()]);
+        self.db
+            .as_mut()
+            .delete(COLLECTION, &[unity.bson["_id"].clone()]);
     }
 
+    /// Finds the installed Unity to open the project made with `expected`.
+    ///
+    /// The exact version is preferred, then the same `major.minor.revision`, the same `major.minor`,
+    /// and the same major like `2022` or `6000`. The versions with different major are never returned,
+    /// since opening a project with those upgrades or downgrades the project.
+    ///
+    /// Among the versions with the same `major.minor`, the newest release is preferred.
+    /// Among the versions with the same major only, the closest minor is preferred.
+    /// The alpha and beta releases are used only if no other releases are found in the same rank.
     pub fn find_most_suitable_unity(&self, expected: UnityVersion) -> Option<UnityInstallation> {
-        let mut revision_match = None;
-        let mut minor_match = None;
-        let mut major_match = None;
-
-        for unity in self.db.get_all(COLLECTION) {
-            let unity = UnityInstallation::from_document(unity.clone());
-            if unity.path().is_none() {
-                continue;
-            }
-            if let Some(version) = unity.version() {
-                if version == expected {
-                    return Some(unity);
-                }
-
-                if version.major() == expected.major() {
-                    if version.minor() == expected.minor() {
-                        if version.revision() == expected.revision() {
-                            revision_match = Some(unity);
-                        } else {
-                            minor_match = Some(unity);
-                        }
-                    } else {
-                        major_match = Some(unity);
-                    }
-                } else {
-                    continue;
-                }
-            }
-        }
-
-        revision_match.or(minor_match).or(major_match)
+        (self.db.get_all(COLLECTION))
+            .map(|unity| UnityInstallation::from_document(unity.clone()))
+            .filter(|unity| unity.path().is_some())
+            .filter_map(|unity| Some((suitability(unity.version()?, expected)?, unity)))
+            .max_by_key(|(suitability, _)| *suitability)
+            .map(|(_, unity)| unity)
     }
 
+    /// Updates the Unity installations in the database with the ones found by Unity Hub,
+    /// and removes the installations no longer exist.
+    ///
+    /// `abort` is checked between each installation.
+    /// The database is changed only after all installations are checked,
+    /// so the database is not changed if aborted.
     pub async fn update_unity_from_unity_hub_and_fs(
         &mut self,
         path_and_version_from_hub: &[(UnityVersion, PathBuf)],
         io: &impl EnvironmentIo,
-    ) -> io::Result<()> {
+        abort: &AbortCheck,
+    ) -> Result<(), crate::Error> {
         let path_and_version_from_hub = path_and_version_from_hub
             .iter()
             .map(|(version, path)| (version, get_executable_path(path)))
@@ -98,10 +98,12 @@ impl VccDatabaseConnection {
 
         let mut update = Vec::new();
         let mut delete = Vec::new();
+        let mut add = Vec::new();
 
         let mut registered = HashSet::new();
 
         for in_db in self.db.get_all(COLLECTION) {
+            abort.check()?;
             let Some(path) = in_db[PATH].as_str() else {
                 // if the unity editor not found, remove it from the db
                 info!("Removed Unity has no path: {:?}", in_db["_id"]);
@@ -110,20 +112,33 @@ impl VccDatabaseConnection {
             };
 
             let path_path = Path::new(path);
+            io.observer()
+                .on_event(&Event::UnityProbeStarted { path: path_path });
             if !io.is_file(path_path).await {
                 // if the unity editor not found, remove it from the db
-                info!("Removed Unity that is not exists: {}", path);
+                io.observer().on_event(&Event::UnityProbeFailed {
+                    path: path_path,
+                    reason: UnityProbeFailure::NotFound,
+                });
                 delete.push(in_db["_id"].clone());
                 continue;
             }
 
             if registered.contains(path) {
                 // if the unity editor is already installed, remove it from the db
-                info!("Removed duplicated Unity: {}", path);
+                io.observer().on_event(&Event::UnityProbeFailed {
+                    path: path_path,
+                    reason: UnityProbeFailure::Duplicated,
+                });
                 delete.push(in_db["_id"].clone());
                 continue;
             }
 
+            io.observer().on_event(&Event::UnityProbeSucceeded {
+                path: path_path,
+                version: in_db[VERSION].as_str().and_then(UnityVersion::parse),
+            });
+
             registered.insert(path.to_string());
 
             let normalized = normalize_path(path.as_ref())
@@ -147,35 +162,200 @@ impl VccDatabaseConnection {
             }
         }
 
-        self.db.delete(COLLECTION, &delete);
-        self.db.update(COLLECTION, update).expect("update");
-
         for &(&version, ref path) in &path_and_version_from_hub {
+            abort.check()?;
             let Some(path) = path.as_os_str().to_str() else {
-                info!(
-                    "Ignoring Unity from Unity Hub since non-utf8 path: {}",
-                    path.display()
-                );
+                io.observer().on_event(&Event::UnityProbeFailed {
+                    path,
+                    reason: UnityProbeFailure::NonUtf8Path,
+                });
                 continue;
             };
             if !registered.contains(path) {
                 if version < UnityVersion::new_f1(2019, 4, 0) {
-                    info!("Ignoring Unity from Unity Hub since old: {}", path);
+                    io.observer().on_event(&Event::UnityProbeFailed {
+                        path: path.as_ref(),
+                        reason: UnityProbeFailure::UnsupportedVersion(version),
+                    });
                     continue;
                 }
-                info!("Adding Unity from Unity Hub: {}", path);
-                Self::add_unity_installation_internal(&mut self.db, path, version, true);
+                io.observer().on_event(&Event::UnityAdded {
+                    path: path.as_ref(),
+                    version,
+                    from_hub: true,
+                });
+                add.push((path, version));
             }
         }
 
+        if !delete.is_empty() {
+            self.db.as_mut().delete(COLLECTION, &delete);
+        }
+        if !update.is_empty() {
+            self.db.as_mut().update(COLLECTION, update).expect("update");
+        }
+        for (path, version) in add {
+            Self::add_unity_installation_internal(self.db.as_mut(), path, version, true);
+        }
+
         Ok(())
     }
 }
 
+/// The rank of `version` to open the project made with `expected`, greater is better.
+///
+/// Returns `None` if the major is different.
+fn suitability(
+    version: UnityVersion,
+    expected: UnityVersion,
+) -> Option<(u8, Reverse<u8>, bool, UnityVersion)> {
+    if version.major() != expected.major() {
+        return None;
+    }
+
+    let rank = if version == expected {
+        3
+    } else if version.minor() != expected.minor() {
+        0
+    } else if version.revision() == expected.revision() {
+        2
+    } else {
+        1
+    };
+    let minor_distance = version.minor().abs_diff(expected.minor());
+    let is_release = !matches!(version.type_(), ReleaseType::Alpha | ReleaseType::Beta);
+
+    Some((rank, Reverse(minor_distance), is_release, version))
+}
+
+/// The outcome of importing a legacy setting in [`VccDatabaseConnection::import_legacy_unity_settings`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LegacySettingImport {
+    /// The setting is valid and imported.
+    Imported,
+    /// The setting is not in `settings.json`.
+    NotConfigured,
+    /// The setting is not imported since Unity installations are already registered.
+    Skipped,
+    /// The setting points to a file that does not exist or is not a Unity.
+    InvalidPath,
+}
+
+/// The result of [`VccDatabaseConnection::import_legacy_unity_settings`].
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct LegacyUnitySettingsImport {
+    /// The outcome of `pathToUnityExe`
+    pub unity: LegacySettingImport,
+    /// The version of the imported Unity, if `unity` is [`LegacySettingImport::Imported`].
+    pub unity_version: Option<UnityVersion>,
+    /// The outcome of `pathToUnityHub`
+    pub unity_hub: LegacySettingImport,
+}
+
+impl VccDatabaseConnection {
+    /// Imports `pathToUnityExe` and `pathToUnityHub` saved by older VCC.
+    ///
+    /// The Unity at `pathToUnityExe` is registered only if no Unity installations are registered,
+    /// or `force` is true. The version is detected without launching Unity.
+    /// `pathToUnityHub` is the setting [`Settings::unity_hub_path`] reads, so this only checks the path.
+    ///
+    /// The legacy settings are kept as is for VCC.
+    pub async fn import_legacy_unity_settings(
+        &mut self,
+        settings: &Settings,
+        io: &impl EnvironmentIo,
+        force: bool,
+    ) -> Result<LegacyUnitySettingsImport, crate::Error> {
+        let (unity, unity_version) = self
+            .import_legacy_unity(settings.legacy_unity_path(), io, force)
+            .await?;
+
+        let unity_hub = match settings.unity_hub_path() {
+            "" => LegacySettingImport::NotConfigured,
+            path if io.is_file(path.as_ref()).await => LegacySettingImport::Imported,
+            _ => LegacySettingImport::InvalidPath,
+        };
+
+        Ok(LegacyUnitySettingsImport {
+            unity,
+            unity_version,
+            unity_hub,
+        })
+    }
+
+    async fn import_legacy_unity(
+        &mut self,
+        path: &str,
+        io: &impl EnvironmentIo,
+        force: bool,
+    ) -> Result<(LegacySettingImport, Option<UnityVersion>), crate::Error> {
+        if path.is_empty() {
+            return Ok((LegacySettingImport::NotConfigured, None));
+        }
+
+        if !force && self.db.get_all(COLLECTION).next().is_some() {
+            return Ok((LegacySettingImport::Skipped, None));
+        }
+
+        let path_path = Path::new(path);
+        io.observer()
+            .on_event(&Event::UnityProbeStarted { path: path_path });
+
+        if check_absolute_path(path).is_err() || !io.is_file(path_path).await {
+            io.observer().on_event(&Event::UnityProbeFailed {
+                path: path_path,
+                reason: UnityProbeFailure::NotFound,
+            });
+            return Ok((LegacySettingImport::InvalidPath, None));
+        }
+
+        let normalized = normalize_path(path_path);
+        if (self.db.get_all(COLLECTION))
+            .any(|x| (x[PATH].as_str()).is_some_and(|x| normalize_path(x.as_ref()) == normalized))
+        {
+            io.observer().on_event(&Event::UnityProbeFailed {
+                path: path_path,
+                reason: UnityProbeFailure::Duplicated,
+            });
+            return Ok((LegacySettingImport::Skipped, None));
+        }
+
+        // the fast probe reads the version from the files without launching Unity
+        let app_path = get_app_path(path_path).unwrap_or(path_path);
+        let Ok(version) = load_unity_version(app_path).await else {
+            return Ok((LegacySettingImport::InvalidPath, None));
+        };
+        io.observer().on_event(&Event::UnityProbeSucceeded {
+            path: path_path,
+            version: Some(version),
+        });
+
+        if version < UnityVersion::new_f1(2019, 4, 0) {
+            io.observer().on_event(&Event::UnityProbeFailed {
+                path: path_path,
+                reason: UnityProbeFailure::UnsupportedVersion(version),
+            });
+            return Ok((LegacySettingImport::InvalidPath, None));
+        }
+
+        let normalized = normalized.into_os_string().into_string().unwrap();
+        self.add_unity_installation(&normalized, version)?;
+        io.observer().on_event(&Event::UnityAdded {
+            path: path_path,
+            version,
+            from_hub: false,
+        });
+
+        Ok((LegacySettingImport::Imported, Some(version)))
+    }
+}
+
 pub async fn find_unity_hub(
     settings: &mut Settings,
     io: &impl EnvironmentIo,
-) -> io::Result<Option<String>> {
+) -> Result<Option<String>, crate::Error> {
     let path = settings.unity_hub_path();
     if !path.is_empty() && io.is_file(path.as_ref()).await {
         // if configured one is valid path to file, return it
@@ -1,8 +1,8 @@
 use crate::UserRepoSetting;
-use crate::environment::PackageCollection;
+use crate::environment::{PackageCollection, REPO_CACHE_FOLDER};
 use crate::io;
 use crate::io::EnvironmentIo;
-use crate::utils::{load_json_or_default, save_json};
+use crate::utils::{SaveController, load_json_or_default, save_json};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use std::path::{Path, PathBuf};
@@ -60,50 +60,93 @@ struct AsJson {
     rest: JsonObject,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub(crate) struct VpmSettings {
-    parsed: AsJson,
+    controller: SaveController<AsJson>,
 }
 
 const JSON_PATH: &str = "settings.json";
 
 impl VpmSettings {
     pub async fn load(io: &impl EnvironmentIo) -> io::Result<Self> {
+        if let Some(shared) = io.shared_settings_path() {
+            if !io.is_file(JSON_PATH.as_ref()).await {
+                return Self::load_shared(io, shared).await;
+            }
+        }
+
         let parsed: AsJson = load_json_or_default(io, JSON_PATH.as_ref()).await?;
 
-        Ok(Self { parsed })
+        Ok(Self {
+            controller: SaveController::new(parsed),
+        })
+    }
+
+    async fn load_shared(io: &impl EnvironmentIo, shared: &Path) -> io::Result<Self> {
+        let mut parsed: AsJson = load_json_or_default(io, shared).await?;
+
+        // the caches of the repositories are in the shared Repos folder,
+        // so move them to our Repos folder not to update the shared caches.
+        if let Some(shared_repos) = shared.parent().map(|x| x.join(REPO_CACHE_FOLDER)) {
+            let repos = io.resolve(REPO_CACHE_FOLDER.as_ref());
+            for repo in &mut parsed.user_repos {
+                if let Ok(relative) = repo.local_path().strip_prefix(&shared_repos) {
+                    repo.local_path = repos.join(relative).into_boxed_path();
+                }
+            }
+        }
+
+        Ok(Self {
+            controller: SaveController::new(parsed),
+        })
     }
 
     pub(crate) fn user_repos(&self) -> &[UserRepoSetting] {
-        &self.parsed.user_repos
+        &self.controller.user_repos
     }
 
     pub(crate) fn user_package_folders(&self) -> &[PathBuf] {
-        &self.parsed.user_package_folders
+        &self.controller.user_package_folders
     }
 
     pub fn remove_user_package_folder(&mut self, path: &Path) {
-        self.parsed.user_package_folders.retain(|x| x != path);
+        if self
+            .controller
+            .user_package_folders
+            .iter()
+            .any(|x| x == path)
+        {
+            (self.controller.as_mut().user_package_folders).retain(|x| x != path);
+        }
     }
 
     pub(crate) fn add_user_package_folder(&mut self, path: PathBuf) {
-        self.parsed.user_package_folders.push(path);
+        self.controller.as_mut().user_package_folders.push(path);
     }
 
     pub(crate) fn update_id(&mut self, collection: &PackageCollection) -> bool {
-        let json = &mut self.parsed;
-        let mut changed = false;
-
-        for repo in &mut json.user_repos {
-            if let Some(cache) = collection.repositories.get(repo.local_path()) {
-                if cache.repo.id() != repo.id() {
-                    repo.id = cache.repo.id().map(|x| x.into());
-                    changed = true;
-                }
+        let new_id = |repo: &UserRepoSetting| {
+            (collection.repositories.get(repo.local_path()))
+                .map(|cache| cache.repo.id())
+                .filter(|&id| id != repo.id())
+        };
+
+        if !self
+            .controller
+            .user_repos
+            .iter()
+            .any(|x| new_id(x).is_some())
+        {
+            return false;
+        }
+
+        for repo in &mut self.controller.as_mut().user_repos {
+            if let Some(id) = new_id(repo) {
+                repo.id = id.map(|x| x.into());
             }
         }
 
-        changed
+        true
     }
 
     pub fn retain_user_repos(
@@ -113,66 +156,79 @@ impl VpmSettings {
         let mut removed = Vec::new();
 
         // awaiting extract_if but not stable yet so use cloned method
-        let json = &mut self.parsed;
-        let cloned = json.user_repos.to_vec();
-        json.user_repos.clear();
+        let mut retained = Vec::new();
 
-        for element in cloned {
+        for element in self.controller.user_repos.iter().cloned() {
             if f(&element) {
-                json.user_repos.push(element);
+                retained.push(element);
             } else {
                 removed.push(element);
             }
         }
 
+        if !removed.is_empty() {
+            self.controller.as_mut().user_repos = retained;
+        }
+
         removed
     }
 
     pub(crate) fn add_user_repo(&mut self, repo: UserRepoSetting) {
-        self.parsed.user_repos.push(repo);
+        self.controller.as_mut().user_repos.push(repo);
     }
 
     pub(crate) fn show_prerelease_packages(&self) -> bool {
-        self.parsed.show_prerelease_packages
+        self.controller.show_prerelease_packages
     }
 
     pub(crate) fn set_show_prerelease_packages(&mut self, value: bool) {
-        self.parsed.show_prerelease_packages = value;
+        self.controller.as_mut().show_prerelease_packages = value;
     }
 
     pub(crate) fn default_project_path(&self) -> Option<&str> {
-        self.parsed.default_project_path.as_deref()
+        self.controller.default_project_path.as_deref()
     }
 
     pub(crate) fn set_default_project_path(&mut self, value: &str) {
-        self.parsed.default_project_path = Some(value.into());
+        self.controller.as_mut().default_project_path = Some(value.into());
     }
 
     pub(crate) fn project_backup_path(&self) -> Option<&str> {
-        self.parsed.project_backup_path.as_deref()
+        self.controller.project_backup_path.as_deref()
     }
 
     pub(crate) fn set_project_backup_path(&mut self, value: &str) {
-        self.parsed.project_backup_path = Some(value.into());
+        self.controller.as_mut().project_backup_path = Some(value.into());
+    }
+
+    /// The Unity path used by older VCC. This is not updated by vrc-get.
+    pub(crate) fn legacy_unity_exe(&self) -> &str {
+        &self.controller.path_to_unity_exe
     }
 
     pub(crate) fn unity_hub(&self) -> &str {
-        &self.parsed.path_to_unity_hub
+        &self.controller.path_to_unity_hub
     }
 
     pub(crate) fn set_unity_hub(&mut self, path: &str) {
-        self.parsed.path_to_unity_hub = path.into();
+        self.controller.as_mut().path_to_unity_hub = path.into();
     }
 
-    pub async fn save(&self, io: &impl EnvironmentIo) -> io::Result<()> {
-        save_json(io, JSON_PATH.as_ref(), &self.parsed).await
+    pub(crate) fn is_changed(&self) -> bool {
+        self.controller.is_changed()
+    }
+
+    pub async fn save(&mut self, io: &impl EnvironmentIo) -> io::Result<()> {
+        self.controller
+            .save(|json| save_json(io, JSON_PATH.as_ref(), json))
+            .await
     }
 }
 
 #[cfg(feature = "experimental-project-management")]
 impl VpmSettings {
     pub(crate) fn user_projects(&self) -> &[Box<str>] {
-        &self.parsed.user_projects
+        &self.controller.user_projects
     }
 
     pub(crate) fn retain_user_projects(
@@ -182,26 +238,38 @@ impl VpmSettings {
         let mut removed = Vec::new();
 
         // awaiting extract_if but not stable yet so use cloned method
+        let mut retained = Vec::new();
 
-        let cloned = self.parsed.user_projects.to_vec();
-        self.parsed.user_projects.clear();
-
-        for element in cloned {
+        for element in self.controller.user_projects.iter().cloned() {
             if f(element.as_ref()) {
-                self.parsed.user_projects.push(element);
+                retained.push(element);
             } else {
                 removed.push(element);
             }
         }
 
+        if !removed.is_empty() {
+            self.controller.as_mut().user_projects = retained;
+        }
+
         removed
     }
 
     pub(crate) fn remove_user_project(&mut self, path: &str) {
-        self.parsed.user_projects.retain(|x| x.as_ref() != path);
+        if self
+            .controller
+            .user_projects
+            .iter()
+            .any(|x| x.as_ref() == path)
+        {
+            (self.controller.as_mut().user_projects).retain(|x| x.as_ref() != path);
+        }
     }
 
     pub(crate) fn add_user_project(&mut self, path: &str) {
-        self.parsed.user_projects.insert(0, path.into());
+        self.controller
+            .as_mut()
+            .user_projects
+            .insert(0, path.into());
     }
 }
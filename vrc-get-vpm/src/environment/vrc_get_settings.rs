@@ -1,7 +1,8 @@
 use crate::io;
 use crate::io::EnvironmentIo;
-use crate::utils::read_json_file;
+use crate::utils::{SaveController, read_json_file, save_json};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 
 /// since this file is vrc-get specific, additional keys can be removed
 #[derive(Debug, Default, Serialize, Deserialize, Clone)]
@@ -11,11 +12,21 @@ struct AsJson {
     ignore_official_repository: bool,
     #[serde(default)]
     ignore_curated_repository: bool,
+    #[serde(default)]
+    templates_path: Option<Box<str>>,
+    #[serde(default)]
+    use_package_store: bool,
+    #[serde(default)]
+    use_hard_links: bool,
+    #[serde(default)]
+    auto_backup: bool,
+    #[serde(default)]
+    auto_backup_count: Option<usize>,
 }
 
 #[derive(Debug, Clone)]
 pub(crate) struct VrcGetSettings {
-    parsed: AsJson,
+    controller: SaveController<AsJson>,
 }
 
 const JSON_PATH: &str = "vrc-get/settings.json";
@@ -33,14 +44,58 @@ impl VrcGetSettings {
             Err(e) => return Err(e),
         };
 
-        Ok(Self { parsed })
+        Ok(Self {
+            controller: SaveController::new(parsed),
+        })
     }
 
     pub fn ignore_official_repository(&self) -> bool {
-        self.parsed.ignore_official_repository
+        self.controller.ignore_official_repository
     }
 
     pub fn ignore_curated_repository(&self) -> bool {
-        self.parsed.ignore_curated_repository
+        self.controller.ignore_curated_repository
+    }
+
+    pub fn templates_path(&self) -> &Path {
+        self.controller
+            .templates_path
+            .as_deref()
+            .unwrap_or("Templates")
+            .as_ref()
+    }
+
+    pub fn use_package_store(&self) -> bool {
+        self.controller.use_package_store
+    }
+
+    pub fn set_use_package_store(&mut self, value: bool) {
+        self.controller.as_mut().use_package_store = value;
+    }
+
+    pub fn use_hard_links(&self) -> bool {
+        self.controller.use_hard_links
+    }
+
+    pub fn set_use_hard_links(&mut self, value: bool) {
+        self.controller.as_mut().use_hard_links = value;
+    }
+
+    pub fn auto_backup(&self) -> bool {
+        self.controller.auto_backup
+    }
+
+    pub fn auto_backup_count(&self) -> Option<usize> {
+        self.controller.auto_backup_count
+    }
+
+    pub(crate) fn is_changed(&self) -> bool {
+        self.controller.is_changed()
+    }
+
+    pub async fn save(&mut self, io: &impl EnvironmentIo) -> io::Result<()> {
+        self.controller
+            .save(|json| save_json(io, JSON_PATH.as_ref(), json))
+            .await
     }
 }
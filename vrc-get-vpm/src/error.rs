@@ -0,0 +1,715 @@
+//! The crate-level error type.
+//!
+//! The functions in this crate return specific error types like [`AddPackageErr`] internally,
+//! and [`Error`] groups them into stable categories at the public API boundary
+//! so frontends can map errors to exit codes or dialogs without matching on messages.
+//!
+//! # Error codes
+//!
+//! The errors implement [`ErrorCode`] to expose the machine-readable code and the parameters of the message,
+//! so frontends can show the messages in their own language.
+//! The [`Display`](fmt::Display) messages are in English, and should be used as the fallback.
+//!
+//! The codes and the names of the parameters are a stability surface:
+//! once released, a code keeps its meaning and parameters, and is never reused for another error.
+//! New codes may be added in minor releases, so frontends should fall back to the message for unknown codes.
+//!
+//! The variants wrapping other errors, like `AddPackageErr` in the migration errors or `Io`,
+//! have the code and the parameters of the wrapped error.
+//!
+//! | Code | Error | Parameters |
+//! |------|-------|------------|
+//! | `package.dependency-not-found` | [`AddPackageErr::DependenciesNotFound`] | `dependencies` |
+//! | `package.upgrade-not-locked` | [`AddPackageErr::UpgradingNonLockedPackage`] | `package_name` |
+//! | `package.downgrade-not-locked` | [`AddPackageErr::DowngradingNonLockedPackage`] | `package_name` |
+//! | `package.upgrade-with-downgrade` | [`AddPackageErr::UpgradingWithDowngrade`] | `package_name` |
+//! | `package.incompatible-sdk-unity` | [`AddPackageErr::IncompatibleSdkUnity`] | `package_name`, `package_version`, `unity_version` |
+//! | `package.not-found` | [`AddPackageErr::PackageNotFound`] | `package_name`, `package_version` |
+//! | `resolve.dependency-not-found` | [`ResolvePackageErr::DependenciesNotFound`] | `dependencies` |
+//! | `reinstall.not-installed` | [`ReinstalPackagesError::NotInstalled`] | `package_name` |
+//! | `reinstall.dependency-not-found` | [`ReinstalPackagesError::DependenciesNotFound`] | `dependencies` |
+//! | `remove.not-installed` | [`RemovePackageErr::NotInstalled`] | `package_names` |
+//! | `remove.conflicts` | [`RemovePackageErr::ConflictsWith`] | `package_names` |
+//! | `repository.already-added` | [`AddRepositoryErr::AlreadyAdded`] | |
+//! | `repository.offline-mode` | [`AddRepositoryErr::OfflineMode`] | |
+//! | `project.open-in-unity` | [`ProjectOpenInUnityError`] | |
+//! | `project.possibly-open-in-unity` | [`ProjectOpenInUnityError`] if [possibly stale](ProjectOpenInUnityError::is_possibly_stale) | |
+//! | `project.operation-in-progress` | [`ProjectLockedError`] | |
+//! | `project.modified-externally` | [`ProjectModifiedError`] | |
+//! | `project-version.not-found` | [`ProjectVersionError::NotFound`] | |
+//! | `project-version.read-failed` | [`ProjectVersionError::Io`] | `message` |
+//! | `project-version.missing-editor-version` | [`ProjectVersionError::MissingEditorVersion`] | |
+//! | `project-version.invalid-editor-version` | [`ProjectVersionError::InvalidEditorVersion`] | `raw` |
+//! | `backup.create-failed` | [`ApplyWithBackupError::Backup`] | `message` |
+//! | `backup.apply-failed` | [`ApplyWithBackupError::Apply`] | `message`, `backup_path` if backed up |
+//! | `create-project.default-path-not-set` | `CreateProjectError::DefaultProjectPathNotSet` | |
+//! | `create-project.template-unusable` | `CreateProjectError::TemplateUnusable` | `reason` |
+//! | `migrate-vpm.project-type-mismatch` | [`MigrateVpmError::ProjectTypeMismatch`] | `project_type` |
+//! | `migrate-vpm.unity-version-mismatch` | [`MigrateVpmError::UnityVersionMismatch`] | |
+//! | `migrate-vpm.package-not-found` | [`MigrateVpmError::VpmPackageNotFound`] | `package_name` |
+//! | `migrate-unity-2022.unity-version-mismatch` | [`MigrateUnity2022Error::UnityVersionMismatch`] | |
+//! | `migrate-unity-2022.package-not-found` | [`MigrateUnity2022Error::VpmPackageNotFound`] | `package_name` |
+//! | `migrate-unity.downgrading-unity` | [`MigrateUnityVersionError::DowngradingUnity`] | `current`, `target` |
+//! | `migrate-unity.unlocked-sdk-package` | [`MigrateUnityVersionError::UnlockedSdkPackage`] | `package_name` |
+//! | `migrate-unity.package-not-found` | [`MigrateUnityVersionError::VpmPackageNotFound`] | `package_name` |
+//! | `migrate-legacy-sdk.not-legacy-project` | [`MigrateLegacySdkError::NotLegacyProject`] | |
+//! | `migrate-legacy-sdk.sdk2-not-supported` | [`MigrateLegacySdkError::Sdk2NotSupported`] | |
+//! | `migrate-legacy-sdk.package-not-found` | [`MigrateLegacySdkError::VpmPackageNotFound`] | `package_name` |
+//! | `version.segment-too-big` | the error parsing [`Version`](crate::version::Version) | |
+//! | `version.unexpected-end` | the error parsing [`Version`](crate::version::Version) | |
+//! | `version.invalid` | the error parsing [`Version`](crate::version::Version) | |
+//! | `cancelled` | [`io::Error`] with [`io::ErrorKind::Interrupted`] | `message` |
+//! | `network.request-failed` | [`io::Error`] by failed requests | `message` |
+//! | `io.not-found` | [`io::Error`] with [`io::ErrorKind::NotFound`] | `message` |
+//! | `io.permission-denied` | [`io::Error`] with [`io::ErrorKind::PermissionDenied`] | `message` |
+//! | `io.already-exists` | [`io::Error`] with [`io::ErrorKind::AlreadyExists`] | `message` |
+//! | `io.resource-busy` | [`io::Error`] with [`io::ErrorKind::ResourceBusy`] | `message` |
+//! | `io.invalid-data` | [`io::Error`] with [`io::ErrorKind::InvalidData`] | `message` |
+//! | `io.storage-full` | [`io::Error`] with [`io::ErrorKind::StorageFull`] | `message` |
+//! | `io.other` | other [`io::Error`]s | `message` |
+//!
+//! `message` is the English message of the error, like the message from the OS,
+//! which cannot be localized by frontends.
+
+#[cfg(feature = "experimental-project-management")]
+use crate::environment::CreateProjectError;
+use crate::environment::{AddRepositoryErr, ApplyWithBackupError};
+use crate::io;
+use crate::traits::HttpStatusError;
+use crate::unity_project::{
+    AddPackageErr, MigrateLegacySdkError, MigrateUnity2022Error, MigrateUnityVersionError,
+    MigrateVpmError, ProjectLockedError, ProjectModifiedError, ProjectOpenInUnityError,
+    ProjectVersionError, ReinstalPackagesError, RemovePackageErr, ResolvePackageErr,
+};
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::fmt;
+
+type Source = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// The error of the operations in this crate, grouped by the category.
+///
+/// Each variant wraps the specific error, which can be retrieved with [`Error::downcast_ref`].
+/// [`Display`](fmt::Display) shows the message of the specific error as is.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Failed to communicate with remote servers.
+    ///
+    /// The suggested exit code is 3.
+    Network(Source),
+    /// The repository cannot be added, loaded, or is in bad state.
+    ///
+    /// The suggested exit code is 4.
+    Repository(Source),
+    /// The requested packages or their dependencies cannot be resolved.
+    ///
+    /// The suggested exit code is 5.
+    PackageResolution(Source),
+    /// The project is not in the state the operation requires,
+    /// like open in Unity, or not a supported project type.
+    ///
+    /// The suggested exit code is 6.
+    ProjectState(Source),
+    /// Failed to manage the Unity installations.
+    ///
+    /// The suggested exit code is 7.
+    UnityManagement(Source),
+    /// Failed to read or write the VCC database.
+    ///
+    /// The suggested exit code is 8.
+    Database(Source),
+    /// Other I/O errors.
+    ///
+    /// The suggested exit code is 1.
+    Io(Source),
+    /// The operation is aborted with [`AbortCheck`](crate::AbortCheck).
+    ///
+    /// The suggested exit code is 130, the same as interrupted by Ctrl-C.
+    Cancelled(Source),
+}
+
+impl Error {
+    fn source_ref(&self) -> &Source {
+        match self {
+            Error::Network(source)
+            | Error::Repository(source)
+            | Error::PackageResolution(source)
+            | Error::ProjectState(source)
+            | Error::UnityManagement(source)
+            | Error::Database(source)
+            | Error::Io(source)
+            | Error::Cancelled(source) => source,
+        }
+    }
+
+    /// The suggested exit code for the CLI.
+    ///
+    /// The codes are stable so scripts can rely on them.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Network(_) => 3,
+            Error::Repository(_) => 4,
+            Error::PackageResolution(_) => 5,
+            Error::ProjectState(_) => 6,
+            Error::UnityManagement(_) => 7,
+            Error::Database(_) => 8,
+            Error::Io(_) => 1,
+            Error::Cancelled(_) => 130,
+        }
+    }
+
+    /// Returns the specific error if it's `E`.
+    pub fn downcast_ref<E: std::error::Error + 'static>(&self) -> Option<&E> {
+        self.source_ref().downcast_ref()
+    }
+
+    /// Returns the specific error.
+    pub fn into_inner(self) -> Source {
+        match self {
+            Error::Network(source)
+            | Error::Repository(source)
+            | Error::PackageResolution(source)
+            | Error::ProjectState(source)
+            | Error::UnityManagement(source)
+            | Error::Database(source)
+            | Error::Io(source)
+            | Error::Cancelled(source) => source,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.source_ref(), f)
+    }
+}
+
+impl Error {
+    /// The specific error with the code. All specific errors in this crate have the code.
+    fn specific_code(&self) -> Option<&dyn ErrorCode> {
+        let source = self.source_ref();
+
+        macro_rules! downcast {
+            ($($error:ty),* $(,)?) => {
+                $(
+                    if let Some(error) = source.downcast_ref::<$error>() {
+                        return Some(error);
+                    }
+                )*
+            };
+        }
+
+        downcast!(
+            io::Error,
+            AddPackageErr,
+            ResolvePackageErr,
+            ReinstalPackagesError,
+            RemovePackageErr,
+            ProjectOpenInUnityError,
+            ProjectLockedError,
+            ProjectModifiedError,
+            ApplyWithBackupError,
+            AddRepositoryErr,
+            ProjectVersionError,
+            MigrateLegacySdkError,
+            MigrateUnity2022Error,
+            MigrateUnityVersionError,
+            MigrateVpmError,
+        );
+        #[cfg(feature = "experimental-project-management")]
+        downcast!(CreateProjectError);
+
+        None
+    }
+}
+
+impl ErrorCode for Error {
+    fn code(&self) -> &'static str {
+        if let Some(specific) = self.specific_code() {
+            return specific.code();
+        }
+        match self {
+            Error::Network(_) => "network.request-failed",
+            Error::Cancelled(_) => "cancelled",
+            _ => "io.other",
+        }
+    }
+
+    fn params(&self) -> IndexMap<&'static str, ErrorParam> {
+        match self.specific_code() {
+            Some(specific) => specific.params(),
+            None => IndexMap::from([("message", ErrorParam::text(self))]),
+        }
+    }
+}
+
+/// The machine-readable code and the parameters of the error, to localize the message.
+///
+/// See [the module documentation](self#error-codes) for the list of the codes.
+pub trait ErrorCode {
+    /// The stable code of the error, like `package.dependency-not-found`.
+    fn code(&self) -> &'static str;
+
+    /// The parameters of the message, like the name of the package.
+    ///
+    /// The names of the parameters are stable as the code.
+    fn params(&self) -> IndexMap<&'static str, ErrorParam> {
+        IndexMap::new()
+    }
+}
+
+/// The parameter of the error message, returned by [`ErrorCode::params`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+#[non_exhaustive]
+pub enum ErrorParam {
+    Text(Box<str>),
+    /// The list of the values, like the names of the packages.
+    List(Vec<Box<str>>),
+}
+
+impl ErrorParam {
+    pub(crate) fn text(value: impl fmt::Display) -> Self {
+        ErrorParam::Text(value.to_string().into())
+    }
+
+    pub(crate) fn list(values: &[Box<str>]) -> Self {
+        ErrorParam::List(values.to_vec())
+    }
+}
+
+/// Shows the text as is, and the list separated with commas.
+impl fmt::Display for ErrorParam {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorParam::Text(text) => f.write_str(text),
+            ErrorParam::List(values) => {
+                let mut iter = values.iter();
+                if let Some(first) = iter.next() {
+                    f.write_str(first)?;
+                }
+                for value in iter {
+                    f.write_str(", ")?;
+                    f.write_str(value)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl ErrorCode for io::Error {
+    fn code(&self) -> &'static str {
+        if let Some(inner) = self.get_ref() {
+            if let Some(inner) = inner.downcast_ref::<CategorizedIoError>() {
+                return inner.error.code();
+            }
+            if let Some(inner) = inner.downcast_ref::<ProjectOpenInUnityError>() {
+                return inner.code();
+            }
+            if let Some(inner) = inner.downcast_ref::<ProjectLockedError>() {
+                return inner.code();
+            }
+            if let Some(inner) = inner.downcast_ref::<ProjectModifiedError>() {
+                return inner.code();
+            }
+            if is_request_error(inner) {
+                return "network.request-failed";
+            }
+        }
+
+        match self.kind() {
+            io::ErrorKind::Interrupted => "cancelled",
+            io::ErrorKind::NotFound => "io.not-found",
+            io::ErrorKind::PermissionDenied => "io.permission-denied",
+            io::ErrorKind::AlreadyExists => "io.already-exists",
+            io::ErrorKind::ResourceBusy => "io.resource-busy",
+            io::ErrorKind::InvalidData => "io.invalid-data",
+            io::ErrorKind::StorageFull => "io.storage-full",
+            _ => "io.other",
+        }
+    }
+
+    fn params(&self) -> IndexMap<&'static str, ErrorParam> {
+        if let Some(inner) = self.get_ref() {
+            if let Some(inner) = inner.downcast_ref::<CategorizedIoError>() {
+                return inner.error.params();
+            }
+            if let Some(inner) = inner.downcast_ref::<ProjectOpenInUnityError>() {
+                return inner.params();
+            }
+            if let Some(inner) = inner.downcast_ref::<ProjectLockedError>() {
+                return inner.params();
+            }
+            if let Some(inner) = inner.downcast_ref::<ProjectModifiedError>() {
+                return inner.params();
+            }
+        }
+        IndexMap::from([("message", ErrorParam::text(self))])
+    }
+}
+
+impl std::error::Error for Error {
+    // returns the specific error so the callers walking the chain can downcast to it
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source_ref().as_ref())
+    }
+}
+
+/// The category for io errors, set by [`categorized`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum IoCategory {
+    #[cfg_attr(not(feature = "experimental-unity-management"), allow(dead_code))]
+    UnityManagement,
+    #[cfg_attr(not(feature = "vrc-get-litedb"), allow(dead_code))]
+    Database,
+}
+
+/// The io error with the category, to keep the category through functions returning [`io::Result`].
+#[derive(Debug)]
+struct CategorizedIoError {
+    category: IoCategory,
+    error: io::Error,
+}
+
+impl fmt::Display for CategorizedIoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+impl std::error::Error for CategorizedIoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.error.source()
+    }
+}
+
+/// Marks the io error to be converted to the `category` of [`Error`].
+///
+/// The kind and the message of the error are kept.
+pub(crate) fn categorized(category: IoCategory, error: impl Into<io::Error>) -> io::Error {
+    let error = error.into();
+    io::Error::new(error.kind(), CategorizedIoError { category, error })
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        if error.kind() == io::ErrorKind::Interrupted {
+            return Error::Cancelled(error.into());
+        }
+
+        let Some(inner) = error.get_ref() else {
+            return Error::Io(error.into());
+        };
+
+        if inner.is::<CategorizedIoError>() {
+            let inner = *(error.into_inner().unwrap())
+                .downcast::<CategorizedIoError>()
+                .unwrap();
+            let source = inner.error.into();
+            return match inner.category {
+                IoCategory::UnityManagement => Error::UnityManagement(source),
+                IoCategory::Database => Error::Database(source),
+            };
+        }
+
+        if is_request_error(inner) {
+            Error::Network(error.into())
+        } else if inner.is::<ProjectOpenInUnityError>()
+            || inner.is::<ProjectLockedError>()
+            || inner.is::<ProjectModifiedError>()
+        {
+            Error::ProjectState(error.into())
+        } else {
+            Error::Io(error.into())
+        }
+    }
+}
+
+/// Whether the error is of the HTTP requests.
+fn is_request_error(inner: &(dyn std::error::Error + Send + Sync + 'static)) -> bool {
+    if inner.is::<HttpStatusError>() {
+        return true;
+    }
+    // the errors of reqwest are wrapped to show the source
+    #[cfg(feature = "reqwest")]
+    if inner.is::<reqwest::Error>() || inner.source().is_some_and(|x| x.is::<reqwest::Error>()) {
+        return true;
+    }
+    false
+}
+
+macro_rules! from_specific {
+    ($($category:ident: $error:ty),* $(,)?) => {
+        $(
+            impl From<$error> for Error {
+                fn from(error: $error) -> Self {
+                    Error::$category(error.into())
+                }
+            }
+        )*
+    };
+}
+
+from_specific!(
+    PackageResolution: AddPackageErr,
+    PackageResolution: ResolvePackageErr,
+    PackageResolution: ReinstalPackagesError,
+    ProjectState: ProjectOpenInUnityError,
+    ProjectState: ProjectLockedError,
+    ProjectState: ProjectModifiedError,
+    ProjectState: ApplyWithBackupError,
+);
+
+impl From<RemovePackageErr> for Error {
+    fn from(error: RemovePackageErr) -> Self {
+        match error {
+            RemovePackageErr::Io(error) => error.into(),
+            error => Error::PackageResolution(error.into()),
+        }
+    }
+}
+
+impl From<AddRepositoryErr> for Error {
+    fn from(error: AddRepositoryErr) -> Self {
+        match error {
+            AddRepositoryErr::Io(error) => error.into(),
+            error => Error::Repository(error.into()),
+        }
+    }
+}
+
+impl From<ProjectVersionError> for Error {
+    fn from(error: ProjectVersionError) -> Self {
+        match error {
+            ProjectVersionError::Io(error) => error.into(),
+            error => Error::ProjectState(error.into()),
+        }
+    }
+}
+
+#[cfg(feature = "experimental-project-management")]
+impl From<CreateProjectError> for Error {
+    fn from(error: CreateProjectError) -> Self {
+        match error {
+            CreateProjectError::ResolvePackageErr(error) => error.into(),
+            CreateProjectError::AddPackageErr(error) => error.into(),
+            CreateProjectError::Io(error) => error.into(),
+            error => Error::ProjectState(error.into()),
+        }
+    }
+}
+
+macro_rules! from_migrate {
+    ($($error:ident),* $(,)?) => {
+        $(
+            impl From<$error> for Error {
+                fn from(error: $error) -> Self {
+                    match error {
+                        $error::AddPackageErr(error) => error.into(),
+                        $error::Io(error) => error.into(),
+                        error => Error::ProjectState(error.into()),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+from_migrate!(
+    MigrateLegacySdkError,
+    MigrateUnity2022Error,
+    MigrateUnityVersionError,
+    MigrateVpmError,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn io_category() {
+        let error = io::Error::new(io::ErrorKind::NotFound, "not found");
+        assert!(matches!(Error::from(error), Error::Io(_)));
+
+        let error = io::Error::new(io::ErrorKind::Interrupted, "Aborted");
+        assert!(matches!(Error::from(error), Error::Cancelled(_)));
+
+        let error = categorized(
+            IoCategory::Database,
+            io::Error::new(io::ErrorKind::InvalidData, "bad database"),
+        );
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), "bad database");
+        let error = Error::from(error);
+        assert!(matches!(error, Error::Database(_)));
+        assert_eq!(error.to_string(), "bad database");
+        assert!(error.downcast_ref::<io::Error>().is_some());
+
+        let source = std::error::Error::source(&error).unwrap();
+        assert!(source.is::<io::Error>());
+    }
+
+    #[test]
+    fn error_codes() {
+        fn assert_error_code<E: ErrorCode + std::error::Error>() {}
+
+        // every public error has the code
+        assert_error_code::<Error>();
+        assert_error_code::<io::Error>();
+        assert_error_code::<AddPackageErr>();
+        assert_error_code::<ResolvePackageErr>();
+        assert_error_code::<ReinstalPackagesError>();
+        assert_error_code::<RemovePackageErr>();
+        assert_error_code::<ProjectOpenInUnityError>();
+        assert_error_code::<ProjectLockedError>();
+        assert_error_code::<ProjectModifiedError>();
+        assert_error_code::<ApplyWithBackupError>();
+        assert_error_code::<AddRepositoryErr>();
+        assert_error_code::<ProjectVersionError>();
+        assert_error_code::<MigrateLegacySdkError>();
+        assert_error_code::<MigrateUnity2022Error>();
+        assert_error_code::<MigrateUnityVersionError>();
+        assert_error_code::<MigrateVpmError>();
+        assert_error_code::<ParseVersionError>();
+        #[cfg(feature = "experimental-project-management")]
+        assert_error_code::<CreateProjectError>();
+
+        use crate::ProjectType;
+        use crate::unity_project::UnityLockState;
+        use crate::version::{ParseVersionError, ReleaseType, UnityVersion, Version};
+
+        let name = || Box::<str>::from("com.vrchat.base");
+        let kind = |kind| io::Error::new(kind, "error");
+
+        // the variants not wrapping other errors
+        #[allow(unused_mut)]
+        let mut errors: Vec<Box<dyn ErrorCode>> = vec![
+            Box::new(AddPackageErr::DependenciesNotFound {
+                dependencies: vec![name()],
+            }),
+            Box::new(AddPackageErr::UpgradingNonLockedPackage {
+                package_name: name(),
+            }),
+            Box::new(AddPackageErr::DowngradingNonLockedPackage {
+                package_name: name(),
+            }),
+            Box::new(AddPackageErr::UpgradingWithDowngrade {
+                package_name: name(),
+            }),
+            Box::new(AddPackageErr::IncompatibleSdkUnity {
+                package_name: name(),
+                package_version: Version::new(3, 7, 0),
+                unity_version: UnityVersion::new_f1(2019, 4, 31),
+            }),
+            Box::new(AddPackageErr::PackageNotFound {
+                package_name: name(),
+                package_version: Version::new(1, 0, 0),
+            }),
+            Box::new(ResolvePackageErr::DependenciesNotFound {
+                dependencies: vec![name()],
+            }),
+            Box::new(ReinstalPackagesError::NotInstalled {
+                package_name: name(),
+            }),
+            Box::new(ReinstalPackagesError::DependenciesNotFound {
+                dependencies: vec![name()],
+            }),
+            Box::new(RemovePackageErr::NotInstalled(vec![name()])),
+            Box::new(RemovePackageErr::ConflictsWith(vec![name()])),
+            Box::new(AddRepositoryErr::AlreadyAdded),
+            Box::new(AddRepositoryErr::OfflineMode),
+            Box::new(ProjectOpenInUnityError::new(UnityLockState::Open)),
+            Box::new(ProjectOpenInUnityError::new(UnityLockState::PossiblyStale)),
+            Box::new(ProjectLockedError::new(Some(1234))),
+            Box::new(ProjectModifiedError::new()),
+            Box::new(ProjectVersionError::NotFound),
+            Box::new(ProjectVersionError::Io(kind(io::ErrorKind::Other))),
+            Box::new(ProjectVersionError::MissingEditorVersion),
+            Box::new(ProjectVersionError::InvalidEditorVersion { raw: "2019".into() }),
+            Box::new(ApplyWithBackupError::Backup(kind(io::ErrorKind::Other))),
+            Box::new(ApplyWithBackupError::Apply {
+                backup: None,
+                error: kind(io::ErrorKind::Other),
+            }),
+            Box::new(MigrateVpmError::ProjectTypeMismatch(ProjectType::Unknown)),
+            Box::new(MigrateVpmError::UnityVersionMismatch),
+            Box::new(MigrateVpmError::VpmPackageNotFound("com.vrchat.base")),
+            Box::new(MigrateUnity2022Error::UnityVersionMismatch),
+            Box::new(MigrateUnity2022Error::VpmPackageNotFound("com.vrchat.base")),
+            Box::new(MigrateUnityVersionError::DowngradingUnity {
+                current: UnityVersion::new_f1(2022, 3, 22),
+                target: UnityVersion::new(2019, 4, 31, ReleaseType::Normal, 1),
+            }),
+            Box::new(MigrateUnityVersionError::UnlockedSdkPackage {
+                package_name: name(),
+            }),
+            Box::new(MigrateUnityVersionError::VpmPackageNotFound {
+                package_name: name(),
+            }),
+            Box::new(MigrateLegacySdkError::NotLegacyProject),
+            Box::new(MigrateLegacySdkError::Sdk2NotSupported),
+            Box::new(MigrateLegacySdkError::VpmPackageNotFound("com.vrchat.base")),
+            Box::new(ParseVersionError::too_big()),
+            Box::new(ParseVersionError::unexpected_end()),
+            Box::new(ParseVersionError::invalid()),
+            Box::new(kind(io::ErrorKind::Interrupted)),
+            Box::new(kind(io::ErrorKind::NotFound)),
+            Box::new(kind(io::ErrorKind::PermissionDenied)),
+            Box::new(kind(io::ErrorKind::AlreadyExists)),
+            Box::new(kind(io::ErrorKind::ResourceBusy)),
+            Box::new(kind(io::ErrorKind::InvalidData)),
+            Box::new(kind(io::ErrorKind::StorageFull)),
+            Box::new(kind(io::ErrorKind::Other)),
+        ];
+        #[cfg(feature = "experimental-project-management")]
+        errors.extend([
+            Box::new(CreateProjectError::DefaultProjectPathNotSet) as Box<dyn ErrorCode>,
+            Box::new(CreateProjectError::TemplateUnusable {
+                reason: "reason".into(),
+            }),
+        ]);
+
+        let documentation = include_str!("error.rs");
+        let mut codes = std::collections::HashSet::new();
+        for error in &errors {
+            let code = error.code();
+            assert!(codes.insert(code), "duplicated code: {code}");
+            assert!(
+                documentation.contains(&format!("//! | `{code}` |")),
+                "{code} is not documented"
+            );
+        }
+
+        // the wrapping variants have the code of the wrapped error
+        let error = MigrateVpmError::AddPackageErr(AddPackageErr::UpgradingWithDowngrade {
+            package_name: name(),
+        });
+        assert_eq!(error.code(), "package.upgrade-with-downgrade");
+        assert_eq!(
+            error.params(),
+            IndexMap::from([("package_name", ErrorParam::text("com.vrchat.base"))])
+        );
+        let error = Error::from(RemovePackageErr::Io(io::Error::new(
+            io::ErrorKind::ResourceBusy,
+            ProjectOpenInUnityError::new(UnityLockState::Open),
+        )));
+        assert_eq!(error.code(), "project.open-in-unity");
+        let error = Error::from(io::Error::new(
+            io::ErrorKind::ResourceBusy,
+            ProjectLockedError::new(None),
+        ));
+        assert!(matches!(error, Error::ProjectState(_)));
+        assert_eq!(error.code(), "project.operation-in-progress");
+        let error = Error::from(categorized(
+            IoCategory::Database,
+            kind(io::ErrorKind::InvalidData),
+        ));
+        assert_eq!(error.code(), "io.invalid-data");
+        assert_eq!(
+            error.params(),
+            IndexMap::from([("message", ErrorParam::text("error"))])
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! The events emitted by long-running operations.
+//!
+//! Frontends can show the progress of the operations by observing the events
+//! instead of parsing the log messages.
+//! The observer is read from [`EnvironmentIo::observer`](crate::io::EnvironmentIo::observer)
+//! and [`PackageInstaller::observer`](crate::PackageInstaller::observer), and the default
+//! observer, [`LogObserver`], forwards the events to the `log` crate.
+
+use crate::io;
+use crate::version::{UnityVersion, Version};
+use log::{debug, error, info};
+use std::path::Path;
+use url::Url;
+
+/// The event emitted by operations in the environment and projects.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum Event<'a> {
+    /// Started loading the repository, with downloading if it's a remote repository.
+    RepositoryRefreshStarted {
+        cache_path: &'a Path,
+        url: Option<&'a Url>,
+    },
+    /// Finished loading the repository. `error` is set if loading is failed.
+    RepositoryRefreshFinished {
+        cache_path: &'a Path,
+        url: Option<&'a Url>,
+        error: Option<&'a io::Error>,
+    },
+    /// Started checking the Unity installation registered in the environment.
+    UnityProbeStarted { path: &'a Path },
+    /// The Unity installation is valid.
+    UnityProbeSucceeded {
+        path: &'a Path,
+        version: Option<UnityVersion>,
+    },
+    /// The Unity installation is not valid and is removed or ignored.
+    UnityProbeFailed {
+        path: &'a Path,
+        reason: UnityProbeFailure,
+    },
+    /// The Unity installation is added to the environment.
+    UnityAdded {
+        path: &'a Path,
+        version: UnityVersion,
+        from_hub: bool,
+    },
+    /// Started downloading the zip file of the package.
+    PackageDownloadStarted {
+        name: &'a str,
+        version: &'a Version,
+        url: &'a Url,
+    },
+    /// Downloaded `downloaded` bytes of the zip file of the package.
+    PackageDownloadProgress {
+        name: &'a str,
+        version: &'a Version,
+        downloaded: u64,
+    },
+    /// Finished downloading the zip file of the package.
+    PackageDownloadFinished {
+        name: &'a str,
+        version: &'a Version,
+        url: &'a Url,
+    },
+    /// The package is extracted to the project.
+    PackageExtracted { name: &'a str, version: &'a Version },
+    /// The changes are applied to the project.
+    ProjectChangesApplied {
+        installed: &'a [&'a str],
+        removed: &'a [&'a str],
+    },
+    /// A line of the log written by Unity launched to finalize the migration.
+    UnityMigrationLog { line: &'a str },
+}
+
+/// The reason of [`Event::UnityProbeFailed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum UnityProbeFailure {
+    /// The executable does not exist.
+    NotFound,
+    /// The same installation is already registered.
+    Duplicated,
+    /// The path cannot be saved since it's not valid UTF-8.
+    NonUtf8Path,
+    /// The version is too old to be used with VPM.
+    UnsupportedVersion(UnityVersion),
+}
+
+/// The observer of [`Event`]s.
+pub trait EventObserver: Send + Sync {
+    fn on_event(&self, event: &Event<'_>);
+}
+
+/// The observer forwards the events to the `log` crate.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LogObserver;
+
+impl EventObserver for LogObserver {
+    fn on_event(&self, event: &Event<'_>) {
+        match *event {
+            Event::RepositoryRefreshStarted { .. } => {}
+            Event::RepositoryRefreshFinished {
+                cache_path,
+                error: Some(e),
+                ..
+            } => error!("loading repo '{}': {}", cache_path.display(), e),
+            Event::RepositoryRefreshFinished { error: None, .. } => {}
+            Event::UnityProbeStarted { .. } => {}
+            Event::UnityProbeSucceeded { .. } => {}
+            Event::UnityProbeFailed { path, reason } => match reason {
+                UnityProbeFailure::NotFound => {
+                    info!("Removed Unity that is not exists: {}", path.display())
+                }
+                UnityProbeFailure::Duplicated => {
+                    info!("Removed duplicated Unity: {}", path.display())
+                }
+                UnityProbeFailure::NonUtf8Path => info!(
+                    "Ignoring Unity from Unity Hub since non-utf8 path: {}",
+                    path.display()
+                ),
+                UnityProbeFailure::UnsupportedVersion(_) => {
+                    info!(
+                        "Ignoring Unity from Unity Hub since old: {}",
+                        path.display()
+                    )
+                }
+            },
+            Event::UnityAdded {
+                path,
+                from_hub: true,
+                ..
+            } => info!("Adding Unity from Unity Hub: {}", path.display()),
+            Event::UnityAdded {
+                path,
+                from_hub: false,
+                ..
+            } => info!("Adding Unity: {}", path.display()),
+            Event::PackageDownloadStarted { url, .. } => debug!("Download started for {}", url),
+            Event::PackageDownloadProgress { .. } => {}
+            Event::PackageDownloadFinished { url, .. } => debug!("finished downloading {}", url),
+            Event::PackageExtracted { name, version } => {
+                debug!("Extracted zip file for {}@{}", name, version)
+            }
+            Event::ProjectChangesApplied { .. } => {}
+            Event::UnityMigrationLog { line } => debug!("Unity: {}", line),
+        }
+    }
+}
@@ -0,0 +1,783 @@
+//! The in-memory implementations of [`EnvironmentIo`] and [`ProjectIo`] for testing.
+//!
+//! The file systems are fast and deterministic, and the failures can be injected
+//! to test the error handling.
+//! Cloned values share the same files, so the clone can be used to check the files
+//! after passing the io to [`UnityProject`](crate::UnityProject) or other functions.
+//!
+//! ```
+//! # use vrc_get_vpm::UnityProject;
+//! # use vrc_get_vpm::io::memory::MemoryProjectIo;
+//! # use vrc_get_vpm::version::UnityVersion;
+//! # use std::io::ErrorKind;
+//! # futures::executor::block_on(async {
+//! let io = MemoryProjectIo::new()
+//!     .with_vpm_manifest(r#"{"dependencies": {}, "locked": {}}"#)
+//!     .with_unity_version("2022.3.22f1", "887be4894c44")
+//!     .with_package("com.anatawa12.package", "1.0.0");
+//!
+//! let project = UnityProject::load(io.clone()).await?;
+//! assert_eq!(project.unity_version(), Some(UnityVersion::new_f1(2022, 3, 22)));
+//! assert!(project.get_installed_package("com.anatawa12.package").is_some());
+//!
+//! io.fail_on("Packages/vpm-manifest.json", ErrorKind::PermissionDenied);
+//! assert!(UnityProject::load(io).await.is_err());
+//! # Ok::<(), std::io::Error>(())
+//! # }).unwrap();
+//! ```
+//!
+//! This module is available with the `test-util` feature.
+
+use crate::event::{EventObserver, LogObserver};
+use crate::io::{
+    AsyncRead, AsyncSeek, AsyncWrite, DirEntry, EnvironmentIo, Error, ErrorKind, FileStream,
+    FileType, IoTrait, Metadata, ProjectIo, Result, SeekFrom, Stream,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::{OsStr, OsString};
+use std::path::{Component, Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime};
+
+/// The in-memory [`EnvironmentIo`].
+///
+/// Relative paths are resolved from the root of the file system, and absolute paths are rejected.
+#[derive(Clone, Default)]
+pub struct MemoryEnvironmentIo {
+    fs: MemoryFileSystem,
+    observer: Option<Arc<dyn EventObserver>>,
+    shared_settings: Option<PathBuf>,
+    #[cfg(feature = "experimental-project-management")]
+    projects: Arc<Mutex<HashMap<PathBuf, MemoryProjectIo>>>,
+}
+
+impl MemoryEnvironmentIo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_observer(mut self, observer: Arc<dyn EventObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    pub fn with_shared_settings(mut self, path: impl Into<PathBuf>) -> Self {
+        self.shared_settings = Some(path.into());
+        self
+    }
+
+    /// Adds the file with the parent directories.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the file cannot be added, see [`add_file`](Self::add_file).
+    pub fn with_file(self, path: impl AsRef<Path>, content: impl AsRef<[u8]>) -> Self {
+        (self.fs.add_file(path.as_ref(), content.as_ref())).expect("adding file");
+        self
+    }
+
+    /// Adds the `settings.json` of VCC.
+    pub fn with_settings(self, json: &str) -> Self {
+        self.with_file("settings.json", json)
+    }
+
+    /// Adds the cache of the repository in the `Repos` folder.
+    ///
+    /// The repository is loaded if the local path in the settings is `Repos/{file_name}`.
+    pub fn with_repo_cache(self, file_name: &str, json: &str) -> Self {
+        self.with_file(Path::new("Repos").join(file_name), json)
+    }
+
+    /// Registers the project returned by [`EnvironmentIo::new_project_io`] for `path`.
+    ///
+    /// Unregistered paths get new empty projects, which are kept for the later calls.
+    #[cfg(feature = "experimental-project-management")]
+    pub fn with_project(self, path: impl Into<PathBuf>, project: MemoryProjectIo) -> Self {
+        self.projects.lock().unwrap().insert(path.into(), project);
+        self
+    }
+
+    /// See [`MemoryProjectIo::add_file`].
+    pub fn add_file(&self, path: impl AsRef<Path>, content: impl AsRef<[u8]>) -> Result<()> {
+        self.fs.add_file(path.as_ref(), content.as_ref())
+    }
+
+    /// See [`MemoryProjectIo::deny_deletion`].
+    pub fn deny_deletion(&self, path: impl AsRef<Path>) -> Result<()> {
+        self.fs.deny_deletion(path.as_ref())
+    }
+
+    /// See [`MemoryProjectIo::fail_on`].
+    pub fn fail_on(&self, path: impl AsRef<Path>, kind: ErrorKind) {
+        self.fs.fail_on(path.as_ref(), kind)
+    }
+
+    pub fn clear_failures(&self) {
+        self.fs.clear_failures()
+    }
+
+    /// The number of times files are written with [`IoTrait::write`] or [`IoTrait::write_sync`].
+    pub fn write_count(&self) -> usize {
+        self.fs.write_count()
+    }
+}
+
+/// The in-memory [`ProjectIo`].
+///
+/// Relative paths are resolved from the root of the project, and absolute paths are rejected.
+#[derive(Clone, Default)]
+pub struct MemoryProjectIo {
+    fs: MemoryFileSystem,
+}
+
+impl MemoryProjectIo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the file with the parent directories.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the file cannot be added, see [`add_file`](Self::add_file).
+    pub fn with_file(self, path: impl AsRef<Path>, content: impl AsRef<[u8]>) -> Self {
+        (self.fs.add_file(path.as_ref(), content.as_ref())).expect("adding file");
+        self
+    }
+
+    /// Adds `Packages/vpm-manifest.json`.
+    pub fn with_vpm_manifest(self, json: &str) -> Self {
+        self.with_file("Packages/vpm-manifest.json", json)
+    }
+
+    /// Adds the package folder with `package.json` in `Packages`.
+    ///
+    /// The package is not added to `vpm-manifest.json`.
+    pub fn with_package(self, name: &str, version: &str) -> Self {
+        let json = serde_json::json!({ "name": name, "version": version });
+        self.with_file(
+            Path::new("Packages").join(name).join("package.json"),
+            json.to_string(),
+        )
+    }
+
+    /// Adds `ProjectSettings/ProjectVersion.txt`.
+    pub fn with_unity_version(self, version: &str, revision: &str) -> Self {
+        self.with_file(
+            "ProjectSettings/ProjectVersion.txt",
+            format!(
+                "m_EditorVersion: {version}\n\
+                m_EditorVersionWithRevision: {version} ({revision})\n"
+            ),
+        )
+    }
+
+    /// Adds the file with the parent directories, replacing the existing file.
+    ///
+    /// Unlike the [`IoTrait`] methods, the injected failures are not applied.
+    pub fn add_file(&self, path: impl AsRef<Path>, content: impl AsRef<[u8]>) -> Result<()> {
+        self.fs.add_file(path.as_ref(), content.as_ref())
+    }
+
+    /// Makes the file unable to be removed, moved, or replaced, like the files opened by other processes on windows.
+    pub fn deny_deletion(&self, path: impl AsRef<Path>) -> Result<()> {
+        self.fs.deny_deletion(path.as_ref())
+    }
+
+    /// Makes the operations on `path` fail with `kind`.
+    ///
+    /// The failure is applied to the operations with exactly the path,
+    /// not to the operations on the files in the directory.
+    pub fn fail_on(&self, path: impl AsRef<Path>, kind: ErrorKind) {
+        self.fs.fail_on(path.as_ref(), kind)
+    }
+
+    pub fn clear_failures(&self) {
+        self.fs.clear_failures()
+    }
+
+    /// The number of times files are written with [`IoTrait::write`] or [`IoTrait::write_sync`].
+    pub fn write_count(&self) -> usize {
+        self.fs.write_count()
+    }
+}
+
+macro_rules! delegate_io_trait {
+    ($ty: ty) => {
+        impl IoTrait for $ty {
+            async fn create_dir_all(&self, path: &Path) -> Result<()> {
+                self.fs.create_dir_all(path)
+            }
+
+            async fn write(&self, path: &Path, content: &[u8]) -> Result<()> {
+                self.fs.write(path, content)
+            }
+
+            async fn write_sync(&self, path: &Path, content: &[u8]) -> Result<()> {
+                self.fs.write(path, content)
+            }
+
+            async fn remove_file(&self, path: &Path) -> Result<()> {
+                self.fs.remove_file(path)
+            }
+
+            async fn remove_dir(&self, path: &Path) -> Result<()> {
+                self.fs.remove_dir(path, false)
+            }
+
+            async fn remove_dir_all(&self, path: &Path) -> Result<()> {
+                self.fs.remove_dir(path, true)
+            }
+
+            async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+                self.fs.rename(from, to)
+            }
+
+            async fn metadata(&self, path: &Path) -> Result<Metadata> {
+                self.fs.metadata(path)
+            }
+
+            async fn set_modified(&self, path: &Path, time: SystemTime) -> Result<()> {
+                self.fs.set_modified(path, time)
+            }
+
+            type DirEntry = MemoryDirEntry;
+            type ReadDirStream = MemoryReadDir;
+
+            async fn read_dir(&self, path: &Path) -> Result<Self::ReadDirStream> {
+                self.fs.read_dir(path)
+            }
+
+            type FileStream = MemoryFileStream;
+
+            async fn create_new(&self, path: &Path) -> Result<Self::FileStream> {
+                self.fs.create(path, true)
+            }
+
+            async fn create(&self, path: &Path) -> Result<Self::FileStream> {
+                self.fs.create(path, false)
+            }
+
+            async fn open(&self, path: &Path) -> Result<Self::FileStream> {
+                self.fs.open(path)
+            }
+        }
+    };
+}
+
+delegate_io_trait!(MemoryEnvironmentIo);
+delegate_io_trait!(MemoryProjectIo);
+
+impl EnvironmentIo for MemoryEnvironmentIo {
+    fn resolve(&self, path: &Path) -> PathBuf {
+        match components(path) {
+            Ok(components) => components.iter().collect(),
+            Err(_) => path.to_owned(),
+        }
+    }
+
+    fn observer(&self) -> &dyn EventObserver {
+        match &self.observer {
+            Some(observer) => observer.as_ref(),
+            None => &LogObserver,
+        }
+    }
+
+    fn shared_settings_path(&self) -> Option<&Path> {
+        self.shared_settings.as_deref()
+    }
+
+    // there are no other processes sharing the memory
+    #[cfg(feature = "vrc-get-litedb")]
+    type MutexGuard = ();
+
+    #[cfg(feature = "vrc-get-litedb")]
+    async fn new_mutex(&self, _: &OsStr) -> Result<Self::MutexGuard> {
+        Ok(())
+    }
+
+    #[cfg(feature = "experimental-project-management")]
+    type ProjectIo = MemoryProjectIo;
+
+    #[cfg(feature = "experimental-project-management")]
+    fn new_project_io(&self, path: &Path) -> Self::ProjectIo {
+        let mut projects = self.projects.lock().unwrap();
+        projects.entry(path.to_owned()).or_default().clone()
+    }
+}
+
+impl ProjectIo for MemoryProjectIo {
+    /// Yields to the other tasks once instead of waiting, so the operations waiting for
+    /// the project lock run concurrently with the operation holding the lock.
+    async fn sleep(&self, _: Duration) -> bool {
+        let mut yielded = false;
+        futures::future::poll_fn(|cx| {
+            if yielded {
+                Poll::Ready(true)
+            } else {
+                yielded = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    fn blocking_remover(&self, path: &Path) -> Option<Box<dyn FnOnce() + Send + Sync>> {
+        let (fs, path) = (self.fs.clone(), path.to_owned());
+        Some(Box::new(move || {
+            fs.remove_file(&path).ok();
+        }))
+    }
+}
+
+fn err<T>(kind: ErrorKind, message: &str) -> Result<T> {
+    Err(Error::new(kind, message))
+}
+
+/// Normalizes the relative path to the components from the root.
+fn components(path: &Path) -> Result<Vec<OsString>> {
+    let mut result = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => {
+                return err(ErrorKind::InvalidInput, "absolute paths are not supported");
+            }
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if result.pop().is_none() {
+                    return err(ErrorKind::InvalidInput, "the path is outside of the root");
+                }
+            }
+            Component::Normal(component) => result.push(component.to_owned()),
+        }
+    }
+    Ok(result)
+}
+
+type Directory = BTreeMap<OsString, Node>;
+
+enum Node {
+    File(Arc<Mutex<FileContent>>),
+    Directory(Directory),
+}
+
+impl Node {
+    fn new_file(data: Vec<u8>) -> Self {
+        Node::File(Arc::new(Mutex::new(FileContent {
+            data,
+            modified: None,
+            locked: false,
+        })))
+    }
+
+    fn metadata(&self) -> Metadata {
+        match self {
+            Node::File(file) => {
+                let file = file.lock().unwrap();
+                let metadata = Metadata::file().with_len(file.data.len() as u64);
+                match file.modified {
+                    Some(modified) => metadata.with_modified(modified),
+                    None => metadata,
+                }
+            }
+            Node::Directory(_) => Metadata::dir(),
+        }
+    }
+
+    /// Whether the node can be removed, checking the files in the directory.
+    fn can_remove(&self) -> bool {
+        match self {
+            Node::File(file) => !file.lock().unwrap().locked,
+            Node::Directory(directory) => directory.values().all(Node::can_remove),
+        }
+    }
+}
+
+struct FileContent {
+    data: Vec<u8>,
+    /// `None` until set with [`IoTrait::set_modified`], for deterministic tests
+    modified: Option<SystemTime>,
+    locked: bool,
+}
+
+#[derive(Clone, Default)]
+struct MemoryFileSystem {
+    root: Arc<Mutex<Directory>>,
+    failures: Arc<Mutex<HashMap<Vec<OsString>, ErrorKind>>>,
+    write_count: Arc<AtomicUsize>,
+}
+
+impl MemoryFileSystem {
+    fn fail_on(&self, path: &Path, kind: ErrorKind) {
+        let path = components(path).expect("bad path");
+        self.failures.lock().unwrap().insert(path, kind);
+    }
+
+    fn clear_failures(&self) {
+        self.failures.lock().unwrap().clear();
+    }
+
+    fn write_count(&self) -> usize {
+        self.write_count.load(Ordering::SeqCst)
+    }
+
+    /// Resolves the path, and returns the injected failure for the path.
+    fn resolve(&self, path: &Path) -> Result<Vec<OsString>> {
+        let path = components(path)?;
+        match self.failures.lock().unwrap().get(&path) {
+            Some(&kind) => err(kind, "injected failure"),
+            None => Ok(path),
+        }
+    }
+
+    fn add_file(&self, path: &Path, content: &[u8]) -> Result<()> {
+        let path = components(path)?;
+        let Some((name, parent)) = path.split_last() else {
+            return err(ErrorKind::IsADirectory, "the root is a directory");
+        };
+        let mut root = self.root.lock().unwrap();
+        let parent = create_dir_all(&mut root, parent)?;
+        insert_file(parent, name, content.to_vec())
+    }
+
+    fn deny_deletion(&self, path: &Path) -> Result<()> {
+        let path = components(path)?;
+        let mut root = self.root.lock().unwrap();
+        match get(&mut root, &path)? {
+            Node::File(file) => {
+                file.lock().unwrap().locked = true;
+                Ok(())
+            }
+            Node::Directory(_) => err(ErrorKind::IsADirectory, "is a directory"),
+        }
+    }
+
+    fn create_dir_all(&self, path: &Path) -> Result<()> {
+        let path = self.resolve(path)?;
+        create_dir_all(&mut self.root.lock().unwrap(), &path)?;
+        Ok(())
+    }
+
+    fn write(&self, path: &Path, content: &[u8]) -> Result<()> {
+        let path = self.resolve(path)?;
+        let Some((name, parent)) = path.split_last() else {
+            return err(ErrorKind::IsADirectory, "the root is a directory");
+        };
+        let mut root = self.root.lock().unwrap();
+        insert_file(get_dir(&mut root, parent)?, name, content.to_vec())?;
+        self.write_count.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn remove_file(&self, path: &Path) -> Result<()> {
+        let path = self.resolve(path)?;
+        let Some((name, parent)) = path.split_last() else {
+            return err(ErrorKind::IsADirectory, "the root is a directory");
+        };
+        let mut root = self.root.lock().unwrap();
+        let parent = get_dir(&mut root, parent)?;
+        match parent.get(name) {
+            None => err(ErrorKind::NotFound, "file not found"),
+            Some(Node::Directory(_)) => err(ErrorKind::IsADirectory, "is a directory"),
+            Some(node) if !node.can_remove() => err(ErrorKind::PermissionDenied, "file is locked"),
+            Some(Node::File(_)) => {
+                parent.remove(name);
+                Ok(())
+            }
+        }
+    }
+
+    fn remove_dir(&self, path: &Path, recursive: bool) -> Result<()> {
+        let path = self.resolve(path)?;
+        let Some((name, parent)) = path.split_last() else {
+            return err(ErrorKind::PermissionDenied, "removing the root");
+        };
+        let mut root = self.root.lock().unwrap();
+        let parent = get_dir(&mut root, parent)?;
+        match parent.get(name) {
+            None => err(ErrorKind::NotFound, "directory not found"),
+            Some(Node::File(_)) => err(ErrorKind::NotADirectory, "is a file"),
+            Some(Node::Directory(dir)) if !recursive && !dir.is_empty() => {
+                err(ErrorKind::DirectoryNotEmpty, "directory not empty")
+            }
+            Some(node) if !node.can_remove() => err(
+                ErrorKind::PermissionDenied,
+                "the directory has locked files",
+            ),
+            Some(Node::Directory(_)) => {
+                parent.remove(name);
+                Ok(())
+            }
+        }
+    }
+
+    /// Moves the file or directory without copying, like renaming on the same volume.
+    ///
+    /// Existing files are replaced, and existing directories are not.
+    fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        let from = self.resolve(from)?;
+        let to = self.resolve(to)?;
+        let (Some((from_name, from_parent)), Some((to_name, to_parent))) =
+            (from.split_last(), to.split_last())
+        else {
+            return err(ErrorKind::PermissionDenied, "moving the root");
+        };
+
+        let mut root = self.root.lock().unwrap();
+
+        // check the source and the destination before moving
+        let source = get(&mut root, &from)?;
+        if from == to {
+            return Ok(());
+        }
+        if to.starts_with(&from) {
+            return err(
+                ErrorKind::InvalidInput,
+                "moving to a subdirectory of itself",
+            );
+        }
+        if !source.can_remove() {
+            return err(ErrorKind::PermissionDenied, "file is locked");
+        }
+        let source_is_dir = matches!(source, Node::Directory(_));
+        match get_dir(&mut root, to_parent)?.get(to_name) {
+            None => {}
+            Some(Node::Directory(_)) => return err(ErrorKind::AlreadyExists, "directory exists"),
+            Some(_) if source_is_dir => return err(ErrorKind::AlreadyExists, "file exists"),
+            Some(node) if !node.can_remove() => {
+                return err(ErrorKind::PermissionDenied, "file is locked");
+            }
+            Some(Node::File(_)) => {}
+        }
+
+        let node = get_dir(&mut root, from_parent)?.remove(from_name).unwrap();
+        get_dir(&mut root, to_parent)?.insert(to_name.clone(), node);
+        Ok(())
+    }
+
+    fn metadata(&self, path: &Path) -> Result<Metadata> {
+        let path = self.resolve(path)?;
+        if path.is_empty() {
+            return Ok(Metadata::dir());
+        }
+        Ok(get(&mut self.root.lock().unwrap(), &path)?.metadata())
+    }
+
+    fn set_modified(&self, path: &Path, time: SystemTime) -> Result<()> {
+        let path = self.resolve(path)?;
+        match get(&mut self.root.lock().unwrap(), &path)? {
+            Node::File(file) => {
+                file.lock().unwrap().modified = Some(time);
+                Ok(())
+            }
+            Node::Directory(_) => Ok(()),
+        }
+    }
+
+    fn read_dir(&self, path: &Path) -> Result<MemoryReadDir> {
+        let path = self.resolve(path)?;
+        let mut root = self.root.lock().unwrap();
+        let entries = (get_dir(&mut root, &path)?.iter())
+            .map(|(name, node)| MemoryDirEntry {
+                name: name.clone(),
+                metadata: node.metadata(),
+            })
+            .collect::<Vec<_>>();
+        Ok(MemoryReadDir {
+            entries: entries.into_iter(),
+        })
+    }
+
+    fn create(&self, path: &Path, new: bool) -> Result<MemoryFileStream> {
+        let path = self.resolve(path)?;
+        let Some((name, parent)) = path.split_last() else {
+            return err(ErrorKind::IsADirectory, "the root is a directory");
+        };
+        let mut root = self.root.lock().unwrap();
+        let parent = get_dir(&mut root, parent)?;
+        match parent.get(name) {
+            Some(_) if new => return err(ErrorKind::AlreadyExists, "file exists"),
+            Some(Node::Directory(_)) => return err(ErrorKind::IsADirectory, "is a directory"),
+            Some(Node::File(file)) => file.lock().unwrap().data.clear(),
+            None => {
+                parent.insert(name.clone(), Node::new_file(Vec::new()));
+            }
+        }
+        let Some(Node::File(file)) = parent.get(name) else {
+            unreachable!()
+        };
+        Ok(MemoryFileStream::new(file.clone()))
+    }
+
+    fn open(&self, path: &Path) -> Result<MemoryFileStream> {
+        let path = self.resolve(path)?;
+        match get(&mut self.root.lock().unwrap(), &path)? {
+            Node::File(file) => Ok(MemoryFileStream::new(file.clone())),
+            Node::Directory(_) => err(ErrorKind::IsADirectory, "is a directory"),
+        }
+    }
+}
+
+fn get<'a>(root: &'a mut Directory, path: &[OsString]) -> Result<&'a mut Node> {
+    let Some((name, parent)) = path.split_last() else {
+        return err(ErrorKind::InvalidInput, "the root is not a node");
+    };
+    (get_dir(root, parent)?.get_mut(name))
+        .ok_or_else(|| Error::new(ErrorKind::NotFound, "not found"))
+}
+
+fn get_dir<'a>(root: &'a mut Directory, path: &[OsString]) -> Result<&'a mut Directory> {
+    let mut current = root;
+    for name in path {
+        current = match current.get_mut(name) {
+            None => return err(ErrorKind::NotFound, "directory not found"),
+            Some(Node::File(_)) => return err(ErrorKind::NotADirectory, "is a file"),
+            Some(Node::Directory(directory)) => directory,
+        };
+    }
+    Ok(current)
+}
+
+fn create_dir_all<'a>(root: &'a mut Directory, path: &[OsString]) -> Result<&'a mut Directory> {
+    let mut current = root;
+    for name in path {
+        let node =
+            (current.entry(name.clone())).or_insert_with(|| Node::Directory(Directory::new()));
+        current = match node {
+            Node::File(_) => return err(ErrorKind::NotADirectory, "is a file"),
+            Node::Directory(directory) => directory,
+        };
+    }
+    Ok(current)
+}
+
+fn insert_file(parent: &mut Directory, name: &OsStr, data: Vec<u8>) -> Result<()> {
+    match parent.get(name) {
+        Some(Node::Directory(_)) => err(ErrorKind::IsADirectory, "is a directory"),
+        Some(Node::File(file)) => {
+            file.lock().unwrap().data = data;
+            Ok(())
+        }
+        None => {
+            parent.insert(name.to_owned(), Node::new_file(data));
+            Ok(())
+        }
+    }
+}
+
+/// The entries of the directory when [`IoTrait::read_dir`] is called, in the order of the names.
+pub struct MemoryReadDir {
+    entries: std::vec::IntoIter<MemoryDirEntry>,
+}
+
+impl Stream for MemoryReadDir {
+    type Item = Result<MemoryDirEntry>;
+
+    fn poll_next(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Poll::Ready(self.entries.next().map(Ok))
+    }
+}
+
+pub struct MemoryDirEntry {
+    name: OsString,
+    metadata: Metadata,
+}
+
+impl DirEntry for MemoryDirEntry {
+    fn file_name(&self) -> OsString {
+        self.name.clone()
+    }
+
+    async fn file_type(&self) -> Result<FileType> {
+        Ok(self.metadata.file_type())
+    }
+
+    async fn metadata(&self) -> Result<Metadata> {
+        Ok(self.metadata.clone())
+    }
+}
+
+/// The opened file, sharing the content with the file system.
+pub struct MemoryFileStream {
+    content: Arc<Mutex<FileContent>>,
+    position: u64,
+}
+
+impl MemoryFileStream {
+    fn new(content: Arc<Mutex<FileContent>>) -> Self {
+        Self {
+            content,
+            position: 0,
+        }
+    }
+}
+
+impl AsyncRead for MemoryFileStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        let content = self.content.clone();
+        let content = content.lock().unwrap();
+        let start = (self.position as usize).min(content.data.len());
+        let read = buf.len().min(content.data.len() - start);
+        buf[..read].copy_from_slice(&content.data[start..][..read]);
+        self.position += read as u64;
+        Poll::Ready(Ok(read))
+    }
+}
+
+impl AsyncWrite for MemoryFileStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<Result<usize>> {
+        let content = self.content.clone();
+        let mut content = content.lock().unwrap();
+        let start = self.position as usize;
+        if content.data.len() < start + buf.len() {
+            content.data.resize(start + buf.len(), 0);
+        }
+        content.data[start..][..buf.len()].copy_from_slice(buf);
+        self.position += buf.len() as u64;
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncSeek for MemoryFileStream {
+    fn poll_seek(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        pos: SeekFrom,
+    ) -> Poll<Result<u64>> {
+        let position = match pos {
+            SeekFrom::Start(position) => Some(position),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+            SeekFrom::End(offset) => {
+                let len = self.content.lock().unwrap().data.len() as u64;
+                len.checked_add_signed(offset)
+            }
+        };
+        let Some(position) = position else {
+            return Poll::Ready(err(ErrorKind::InvalidInput, "invalid position"));
+        };
+        self.position = position;
+        Poll::Ready(Ok(position))
+    }
+}
+
+impl FileStream for MemoryFileStream {}
@@ -1,6 +1,8 @@
+use crate::event::{EventObserver, LogObserver};
 use std::ffi::{OsStr, OsString};
 use std::future::Future;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
 pub(crate) use futures::Stream;
 pub(crate) use futures::io::{
@@ -8,8 +10,14 @@ pub(crate) use futures::io::{
 };
 pub(crate) use std::io::SeekFrom;
 
+#[cfg(feature = "test-util")]
+pub mod memory;
+mod recording;
 #[cfg(feature = "tokio")]
 mod tokio;
+
+pub(crate) use recording::OperationRecorder;
+pub use recording::{FileOperation, RecordingDirEntry, RecordingFileStream, RecordingIo};
 #[cfg(feature = "tokio")]
 pub use tokio::DefaultEnvironmentIo;
 #[cfg(feature = "tokio")]
@@ -22,7 +30,26 @@ pub use tokio::DefaultProjectIo;
 pub trait EnvironmentIo: Sync + IoTrait {
     /// We may need to resolve a relative path to an absolute path for some reason.
     /// For example, to get the absolute path of the Repos folder for creating local cache and cleanup repos folder.
+    ///
+    /// The returned path is not an extended-length path on windows even if the file system operations use it,
+    /// so it can be shown to the user.
     fn resolve(&self, path: &Path) -> PathBuf;
+
+    /// The observer receives the events of the operations in this environment.
+    ///
+    /// This is [`LogObserver`] by default.
+    fn observer(&self) -> &dyn EventObserver {
+        &LogObserver
+    }
+
+    /// The `settings.json` of VCC to read if the environment folder doesn't have its own one.
+    ///
+    /// This is set for the profiles sharing the VCC settings.
+    /// The file is only read, and the settings are saved to the environment folder.
+    fn shared_settings_path(&self) -> Option<&Path> {
+        None
+    }
+
     #[cfg(feature = "vrc-get-litedb")]
     type MutexGuard: Send + Sync + 'static;
     #[cfg(feature = "vrc-get-litedb")]
@@ -39,7 +66,78 @@ pub trait EnvironmentIo: Sync + IoTrait {
 /// Absolute paths are not allowed and relative paths should be resolved as a relative path from the project folder.
 ///
 /// [UnityProject]: crate::unity_project::UnityProject
-pub trait ProjectIo: Sync + IoTrait {}
+pub trait ProjectIo: Sync + IoTrait {
+    /// Checks if the file is exclusively locked by another process.
+    ///
+    /// Returns `None` if the lock cannot be checked on this platform or file system.
+    fn is_exclusively_locked(
+        &self,
+        path: &Path,
+    ) -> impl Future<Output = Result<Option<bool>>> + Send {
+        let _ = path;
+        async { Ok(None) }
+    }
+
+    /// Creates a hard link at `link` to the file at `original`.
+    ///
+    /// `original` is an absolute path.
+    /// Returns [`ErrorKind::Unsupported`] by default for file systems without hard links.
+    fn hard_link(&self, original: &Path, link: &Path) -> impl Future<Output = Result<()>> + Send {
+        let _ = (original, link);
+        async {
+            Err(Error::new(
+                ErrorKind::Unsupported,
+                "hard links are not supported",
+            ))
+        }
+    }
+
+    /// Creates a link at `link` to the directory at `target`.
+    ///
+    /// This creates a symbolic link, or a junction on windows so that developer mode is not required.
+    /// `target` is an absolute path.
+    /// Returns [`ErrorKind::Unsupported`] by default for file systems without links.
+    fn create_dir_link(
+        &self,
+        target: &Path,
+        link: &Path,
+    ) -> impl Future<Output = Result<()>> + Send {
+        let _ = (target, link);
+        async {
+            Err(Error::new(
+                ErrorKind::Unsupported,
+                "links are not supported",
+            ))
+        }
+    }
+
+    /// Checks if the process with `pid` is running, to find the project locks left by crashed processes.
+    ///
+    /// Returns `None` if the process cannot be checked on this platform.
+    fn is_process_running(&self, pid: u32) -> Option<bool> {
+        let _ = pid;
+        None
+    }
+
+    /// Waits for `duration` before retrying to acquire the project lock.
+    ///
+    /// Returns false without waiting by default for platforms without timers,
+    /// and acquiring the project lock fails immediately if it's held.
+    fn sleep(&self, duration: Duration) -> impl Future<Output = bool> + Send {
+        let _ = duration;
+        async { false }
+    }
+
+    /// Returns the function removing the file at `path` synchronously, used to remove the project lock
+    /// when the operation holding the lock is dropped before completing.
+    ///
+    /// Returns `None` by default, and the lock left by the dropped operation is broken by the next
+    /// operation in this process, or when it gets stale in other processes.
+    fn blocking_remover(&self, path: &Path) -> Option<Box<dyn FnOnce() + Send + Sync>> {
+        let _ = path;
+        None
+    }
+}
 
 pub trait FileSystemProjectIo {
     fn location(&self) -> &Path;
@@ -55,6 +153,18 @@ pub trait IoTrait: Sync {
     fn rename(&self, from: &Path, to: &Path) -> impl Future<Output = Result<()>> + Send;
     fn metadata(&self, path: &Path) -> impl Future<Output = Result<Metadata>> + Send;
 
+    /// Sets the modification time of the file.
+    ///
+    /// This does nothing by default for file systems without modification times.
+    fn set_modified(
+        &self,
+        path: &Path,
+        time: SystemTime,
+    ) -> impl Future<Output = Result<()>> + Send {
+        let _ = (path, time);
+        async { Ok(()) }
+    }
+
     type DirEntry: DirEntry;
     type ReadDirStream: Stream<Item = Result<Self::DirEntry>> + Unpin + Send;
 
@@ -91,6 +201,7 @@ pub trait FileStream: AsyncRead + AsyncWrite + AsyncSeek + Unpin + Send {}
 pub struct FileType {
     is_file: bool,
     is_dir: bool,
+    is_symlink: bool,
 }
 
 impl FileType {
@@ -98,6 +209,7 @@ impl FileType {
         Self {
             is_file: true,
             is_dir: false,
+            is_symlink: false,
         }
     }
 
@@ -105,6 +217,7 @@ impl FileType {
         Self {
             is_file: false,
             is_dir: true,
+            is_symlink: false,
         }
     }
 
@@ -115,6 +228,13 @@ impl FileType {
     pub fn is_dir(&self) -> bool {
         self.is_dir
     }
+
+    /// Returns true if this is a symbolic link (or junction on windows).
+    ///
+    /// Only file types that are not following symbolic links, like [`DirEntry::file_type`], can be symlinks.
+    pub fn is_symlink(&self) -> bool {
+        self.is_symlink
+    }
 }
 
 impl From<std::fs::FileType> for FileType {
@@ -122,6 +242,7 @@ impl From<std::fs::FileType> for FileType {
         Self {
             is_dir: value.is_dir(),
             is_file: value.is_file(),
+            is_symlink: value.is_symlink(),
         }
     }
 }
@@ -129,18 +250,24 @@ impl From<std::fs::FileType> for FileType {
 #[derive(Debug, Clone)]
 pub struct Metadata {
     file_type: FileType,
+    len: u64,
+    modified: Option<SystemTime>,
 }
 
 impl Metadata {
     pub fn file() -> Self {
         Self {
             file_type: FileType::file(),
+            len: 0,
+            modified: None,
         }
     }
 
     pub fn dir() -> Self {
         Self {
             file_type: FileType::dir(),
+            len: 0,
+            modified: None,
         }
     }
 
@@ -155,12 +282,36 @@ impl Metadata {
     pub fn is_dir(&self) -> bool {
         self.file_type.is_dir
     }
+
+    /// The size of the file in bytes.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn with_len(self, len: u64) -> Self {
+        Self { len, ..self }
+    }
+
+    /// The last modification time, if the file system supports it.
+    pub fn modified(&self) -> Option<SystemTime> {
+        self.modified
+    }
+
+    pub fn with_modified(self, modified: SystemTime) -> Self {
+        Self {
+            modified: Some(modified),
+            ..self
+        }
+    }
 }
 
 impl From<std::fs::Metadata> for Metadata {
     fn from(value: std::fs::Metadata) -> Self {
         Self {
             file_type: value.file_type().into(),
+            len: value.len(),
+            modified: value.modified().ok(),
         }
     }
 }
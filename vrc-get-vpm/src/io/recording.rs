@@ -0,0 +1,598 @@
+use crate::io;
+use crate::io::{DirEntry, FileStream, FileType, IoTrait, Metadata, ProjectIo};
+use crate::version::Version;
+use futures::io::Cursor;
+use futures::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime};
+use url::Url;
+
+/// The file system operation recorded by [`RecordingIo`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum FileOperation {
+    CreateDir {
+        path: PathBuf,
+    },
+    /// `size` is `None` if the file is written as a stream, like extracting packages.
+    WriteFile {
+        path: PathBuf,
+        size: Option<u64>,
+    },
+    RemoveFile {
+        path: PathBuf,
+    },
+    /// Removes the directory and its contents if `recursive`.
+    RemoveDir {
+        path: PathBuf,
+        recursive: bool,
+    },
+    Rename {
+        from: PathBuf,
+        to: PathBuf,
+    },
+    SetModified {
+        path: PathBuf,
+    },
+    HardLink {
+        original: PathBuf,
+        link: PathBuf,
+    },
+    CreateDirLink {
+        target: PathBuf,
+        link: PathBuf,
+    },
+    /// Downloads the package not in the cache.
+    ///
+    /// The repositories don't have the size of the zip files, so this only has the url.
+    Download {
+        name: Box<str>,
+        version: Version,
+        url: Url,
+    },
+}
+
+impl fmt::Display for FileOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileOperation::CreateDir { path } => {
+                write!(f, "create directory {}", path.display())
+            }
+            FileOperation::WriteFile {
+                path,
+                size: Some(size),
+            } => write!(f, "write {size} bytes to {}", path.display()),
+            FileOperation::WriteFile { path, size: None } => {
+                write!(f, "write {}", path.display())
+            }
+            FileOperation::RemoveFile { path } => write!(f, "remove file {}", path.display()),
+            FileOperation::RemoveDir {
+                path,
+                recursive: false,
+            } => write!(f, "remove empty directory {}", path.display()),
+            FileOperation::RemoveDir {
+                path,
+                recursive: true,
+            } => write!(f, "remove directory {}", path.display()),
+            FileOperation::Rename { from, to } => {
+                write!(f, "move {} to {}", from.display(), to.display())
+            }
+            FileOperation::SetModified { path } => {
+                write!(f, "update modification time of {}", path.display())
+            }
+            FileOperation::HardLink { original, link } => {
+                write!(f, "hard link {} to {}", link.display(), original.display())
+            }
+            FileOperation::CreateDirLink { target, link } => {
+                write!(f, "link {} to {}", link.display(), target.display())
+            }
+            FileOperation::Download { name, version, url } => {
+                write!(f, "download {name}@{version} from {url}")
+            }
+        }
+    }
+}
+
+/// The [`ProjectIo`] records the operations changing the file system instead of doing them.
+///
+/// Reading operations are done with the wrapped io, reflecting the recorded operations:
+/// removed files are not found, moved files are found at the new path,
+/// and files written with [`IoTrait::write`] can be read again.
+/// The contents of files written as streams are not recorded.
+pub struct RecordingIo<'a, IO> {
+    inner: &'a IO,
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    operations: Vec<FileOperation>,
+    /// the paths changed by the operations. the descendants of the changed paths are
+    /// not found unless they're also in this map, except for [`Entry::Moved`]
+    entries: HashMap<PathBuf, Entry>,
+}
+
+#[derive(Clone)]
+enum Entry {
+    Dir,
+    File(Option<Arc<[u8]>>),
+    /// moved from the path in the wrapped io
+    Moved(PathBuf),
+    Removed,
+}
+
+enum Resolved {
+    Inner(PathBuf),
+    Missing,
+    Dir,
+    File(Option<Arc<[u8]>>),
+}
+
+impl State {
+    fn resolve(&self, path: &Path) -> Resolved {
+        for ancestor in path.ancestors() {
+            let Some(entry) = self.entries.get(ancestor) else {
+                continue;
+            };
+            let rest = path.strip_prefix(ancestor).unwrap();
+            return match entry {
+                Entry::Moved(from) => Resolved::Inner(from.join(rest)),
+                _ if !rest.as_os_str().is_empty() => Resolved::Missing,
+                Entry::Dir => Resolved::Dir,
+                Entry::File(content) => Resolved::File(content.clone()),
+                Entry::Removed => Resolved::Missing,
+            };
+        }
+        Resolved::Inner(path.to_owned())
+    }
+
+    /// Sets the entry at `path`, replacing the entries in `path`.
+    fn set(&mut self, path: &Path, entry: Entry) {
+        self.entries.retain(|x, _| !x.starts_with(path));
+        self.entries.insert(path.to_owned(), entry);
+    }
+}
+
+/// The handle to record operations done without [`RecordingIo`], like downloading.
+#[derive(Clone)]
+pub(crate) struct OperationRecorder(Arc<Mutex<State>>);
+
+impl OperationRecorder {
+    pub(crate) fn record(&self, operation: FileOperation) {
+        self.0.lock().unwrap().operations.push(operation);
+    }
+}
+
+impl<'a, IO: IoTrait> RecordingIo<'a, IO> {
+    pub fn new(inner: &'a IO) -> Self {
+        Self {
+            inner,
+            state: Default::default(),
+        }
+    }
+
+    /// The operations recorded so far, in the order.
+    pub fn operations(&self) -> Vec<FileOperation> {
+        self.state.lock().unwrap().operations.clone()
+    }
+
+    pub(crate) fn recorder(&self) -> OperationRecorder {
+        OperationRecorder(self.state.clone())
+    }
+
+    fn record(&self, operation: FileOperation, path: &Path, entry: Entry) {
+        let mut state = self.state.lock().unwrap();
+        state.operations.push(operation);
+        state.set(path, entry);
+    }
+
+    fn resolve(&self, path: &Path) -> Resolved {
+        self.state.lock().unwrap().resolve(path)
+    }
+
+    async fn exists(&self, path: &Path) -> bool {
+        match self.resolve(path) {
+            Resolved::Inner(path) => self.inner.metadata(&path).await.is_ok(),
+            Resolved::Missing => false,
+            Resolved::Dir | Resolved::File(_) => true,
+        }
+    }
+
+    async fn check_exists(&self, path: &Path) -> io::Result<()> {
+        if self.exists(path).await {
+            Ok(())
+        } else {
+            Err(not_found(path))
+        }
+    }
+
+    async fn read_dir_entries(
+        &self,
+        path: &Path,
+    ) -> io::Result<Vec<io::Result<RecordingDirEntry<IO::DirEntry>>>> {
+        let mut entries = vec![];
+
+        match self.resolve(path) {
+            Resolved::Inner(inner_path) => {
+                let mut iter = self.inner.read_dir(&inner_path).await?;
+                while let Some(entry) = iter.try_next().await? {
+                    // skip the entries removed, moved, or replaced by the operations
+                    let name = entry.file_name();
+                    let unchanged = matches!(
+                        self.resolve(&path.join(&name)),
+                        Resolved::Inner(resolved) if resolved == inner_path.join(&name)
+                    );
+                    if unchanged {
+                        entries.push(Ok(RecordingDirEntry::Inner(entry)));
+                    }
+                }
+            }
+            Resolved::Missing => return Err(not_found(path)),
+            Resolved::File(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotADirectory,
+                    format!("{} is not a directory", path.display()),
+                ));
+            }
+            Resolved::Dir => {}
+        }
+
+        // the entries created or moved in this directory
+        let children = {
+            let state = self.state.lock().unwrap();
+            (state.entries.iter())
+                .filter(|(x, entry)| x.parent() == Some(path) && !matches!(entry, Entry::Removed))
+                .map(|(x, entry)| (x.clone(), entry.clone()))
+                .collect::<Vec<_>>()
+        };
+        for (child, entry) in children {
+            let metadata = match entry {
+                Entry::Dir => Metadata::dir(),
+                Entry::File(content) => {
+                    Metadata::file().with_len(content.map_or(0, |x| x.len() as u64))
+                }
+                Entry::Moved(from) => match self.inner.metadata(&from).await {
+                    Ok(metadata) => metadata,
+                    Err(_) => continue,
+                },
+                Entry::Removed => unreachable!(),
+            };
+            entries.push(Ok(RecordingDirEntry::Recorded {
+                name: child.file_name().unwrap().to_owned(),
+                metadata,
+            }));
+        }
+
+        Ok(entries)
+    }
+}
+
+fn not_found(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{} not found", path.display()),
+    )
+}
+
+impl<IO: IoTrait> IoTrait for RecordingIo<'_, IO>
+where
+    IO::DirEntry: Send + Sync,
+{
+    async fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        if self.metadata(path).await.is_ok_and(|x| x.is_dir()) {
+            return Ok(());
+        }
+
+        let mut created = vec![path];
+        for ancestor in path.ancestors().skip(1) {
+            if ancestor.as_os_str().is_empty() || self.exists(ancestor).await {
+                break;
+            }
+            created.push(ancestor);
+        }
+
+        let mut state = self.state.lock().unwrap();
+        state.operations.push(FileOperation::CreateDir {
+            path: path.to_owned(),
+        });
+        for path in created.into_iter().rev() {
+            state.set(path, Entry::Dir);
+        }
+        Ok(())
+    }
+
+    async fn write(&self, path: &Path, content: &[u8]) -> io::Result<()> {
+        self.record(
+            FileOperation::WriteFile {
+                path: path.to_owned(),
+                size: Some(content.len() as u64),
+            },
+            path,
+            Entry::File(Some(content.into())),
+        );
+        Ok(())
+    }
+
+    async fn write_sync(&self, path: &Path, content: &[u8]) -> io::Result<()> {
+        self.write(path, content).await
+    }
+
+    async fn remove_file(&self, path: &Path) -> io::Result<()> {
+        self.check_exists(path).await?;
+        self.record(
+            FileOperation::RemoveFile {
+                path: path.to_owned(),
+            },
+            path,
+            Entry::Removed,
+        );
+        Ok(())
+    }
+
+    async fn remove_dir(&self, path: &Path) -> io::Result<()> {
+        if !self.read_dir_entries(path).await?.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::DirectoryNotEmpty,
+                format!("{} is not empty", path.display()),
+            ));
+        }
+        self.record(
+            FileOperation::RemoveDir {
+                path: path.to_owned(),
+                recursive: false,
+            },
+            path,
+            Entry::Removed,
+        );
+        Ok(())
+    }
+
+    async fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        self.check_exists(path).await?;
+        self.record(
+            FileOperation::RemoveDir {
+                path: path.to_owned(),
+                recursive: true,
+            },
+            path,
+            Entry::Removed,
+        );
+        Ok(())
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.check_exists(from).await?;
+
+        let mut state = self.state.lock().unwrap();
+        state.operations.push(FileOperation::Rename {
+            from: from.to_owned(),
+            to: to.to_owned(),
+        });
+        let moved = match state.resolve(from) {
+            Resolved::Inner(inner) => vec![(to.to_owned(), Entry::Moved(inner))],
+            Resolved::Missing => return Err(not_found(from)),
+            // the entries of the recorded directory are moved with the directory
+            _ => (state.entries.iter())
+                .filter(|(x, _)| x.starts_with(from))
+                .map(|(x, entry)| (to.join(x.strip_prefix(from).unwrap()), entry.clone()))
+                .collect(),
+        };
+        state.set(from, Entry::Removed);
+        state.set(to, Entry::Removed);
+        state.entries.extend(moved);
+        Ok(())
+    }
+
+    async fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        match self.resolve(path) {
+            Resolved::Inner(path) => self.inner.metadata(&path).await,
+            Resolved::Missing => Err(not_found(path)),
+            Resolved::Dir => Ok(Metadata::dir()),
+            Resolved::File(content) => {
+                Ok(Metadata::file().with_len(content.map_or(0, |x| x.len() as u64)))
+            }
+        }
+    }
+
+    async fn set_modified(&self, path: &Path, _: SystemTime) -> io::Result<()> {
+        self.check_exists(path).await?;
+        let operation = FileOperation::SetModified {
+            path: path.to_owned(),
+        };
+        self.state.lock().unwrap().operations.push(operation);
+        Ok(())
+    }
+
+    type DirEntry = RecordingDirEntry<IO::DirEntry>;
+    type ReadDirStream = stream::Iter<std::vec::IntoIter<io::Result<Self::DirEntry>>>;
+
+    async fn read_dir(&self, path: &Path) -> io::Result<Self::ReadDirStream> {
+        Ok(stream::iter(self.read_dir_entries(path).await?))
+    }
+
+    type FileStream = RecordingFileStream<IO::FileStream>;
+
+    async fn create_new(&self, path: &Path) -> io::Result<Self::FileStream> {
+        if self.exists(path).await {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} already exists", path.display()),
+            ));
+        }
+        self.create(path).await
+    }
+
+    async fn create(&self, path: &Path) -> io::Result<Self::FileStream> {
+        self.record(
+            FileOperation::WriteFile {
+                path: path.to_owned(),
+                size: None,
+            },
+            path,
+            Entry::File(None),
+        );
+        Ok(RecordingFileStream::Recorded(Cursor::new(vec![])))
+    }
+
+    async fn open(&self, path: &Path) -> io::Result<Self::FileStream> {
+        match self.resolve(path) {
+            Resolved::Inner(path) => Ok(RecordingFileStream::Inner(self.inner.open(&path).await?)),
+            Resolved::Missing | Resolved::Dir => Err(not_found(path)),
+            Resolved::File(Some(content)) => {
+                Ok(RecordingFileStream::Recorded(Cursor::new(content.to_vec())))
+            }
+            Resolved::File(None) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("the content of {} is not recorded", path.display()),
+            )),
+        }
+    }
+}
+
+impl<IO: ProjectIo> ProjectIo for RecordingIo<'_, IO>
+where
+    IO::DirEntry: Send + Sync,
+{
+    async fn is_exclusively_locked(&self, path: &Path) -> io::Result<Option<bool>> {
+        match self.resolve(path) {
+            Resolved::Inner(path) => self.inner.is_exclusively_locked(&path).await,
+            _ => Ok(Some(false)),
+        }
+    }
+
+    fn is_process_running(&self, pid: u32) -> Option<bool> {
+        self.inner.is_process_running(pid)
+    }
+
+    async fn sleep(&self, duration: Duration) -> bool {
+        self.inner.sleep(duration).await
+    }
+
+    fn blocking_remover(&self, path: &Path) -> Option<Box<dyn FnOnce() + Send + Sync>> {
+        self.inner.blocking_remover(path)
+    }
+
+    async fn hard_link(&self, original: &Path, link: &Path) -> io::Result<()> {
+        self.record(
+            FileOperation::HardLink {
+                original: original.to_owned(),
+                link: link.to_owned(),
+            },
+            link,
+            Entry::File(None),
+        );
+        Ok(())
+    }
+
+    async fn create_dir_link(&self, target: &Path, link: &Path) -> io::Result<()> {
+        self.record(
+            FileOperation::CreateDirLink {
+                target: target.to_owned(),
+                link: link.to_owned(),
+            },
+            link,
+            Entry::Dir,
+        );
+        Ok(())
+    }
+}
+
+pub enum RecordingDirEntry<E> {
+    Inner(E),
+    Recorded { name: OsString, metadata: Metadata },
+}
+
+impl<E: DirEntry + Send + Sync> DirEntry for RecordingDirEntry<E> {
+    fn file_name(&self) -> OsString {
+        match self {
+            RecordingDirEntry::Inner(entry) => entry.file_name(),
+            RecordingDirEntry::Recorded { name, .. } => name.clone(),
+        }
+    }
+
+    async fn file_type(&self) -> io::Result<FileType> {
+        match self {
+            RecordingDirEntry::Inner(entry) => entry.file_type().await,
+            RecordingDirEntry::Recorded { metadata, .. } => Ok(metadata.file_type()),
+        }
+    }
+
+    async fn metadata(&self) -> io::Result<Metadata> {
+        match self {
+            RecordingDirEntry::Inner(entry) => entry.metadata().await,
+            RecordingDirEntry::Recorded { metadata, .. } => Ok(metadata.clone()),
+        }
+    }
+}
+
+pub enum RecordingFileStream<S> {
+    Inner(S),
+    /// the file created by [`RecordingIo`]. the written content is discarded
+    Recorded(Cursor<Vec<u8>>),
+}
+
+impl<S: FileStream> AsyncRead for RecordingFileStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            RecordingFileStream::Inner(stream) => Pin::new(stream).poll_read(cx, buf),
+            RecordingFileStream::Recorded(cursor) => Pin::new(cursor).poll_read(cx, buf),
+        }
+    }
+}
+
+impl<S: FileStream> AsyncWrite for RecordingFileStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            RecordingFileStream::Inner(_) => Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "the file opened with RecordingIo is read only",
+            ))),
+            RecordingFileStream::Recorded(cursor) => Pin::new(cursor).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            RecordingFileStream::Inner(_) => Poll::Ready(Ok(())),
+            RecordingFileStream::Recorded(cursor) => Pin::new(cursor).poll_flush(cx),
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            RecordingFileStream::Inner(_) => Poll::Ready(Ok(())),
+            RecordingFileStream::Recorded(cursor) => Pin::new(cursor).poll_close(cx),
+        }
+    }
+}
+
+impl<S: FileStream> AsyncSeek for RecordingFileStream<S> {
+    fn poll_seek(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        pos: io::SeekFrom,
+    ) -> Poll<io::Result<u64>> {
+        match self.get_mut() {
+            RecordingFileStream::Inner(stream) => Pin::new(stream).poll_seek(cx, pos),
+            RecordingFileStream::Recorded(cursor) => Pin::new(cursor).poll_seek(cx, pos),
+        }
+    }
+}
+
+impl<S: FileStream> FileStream for RecordingFileStream<S> {}
@@ -1,3 +1,4 @@
+use crate::event::{EventObserver, LogObserver};
 use crate::io;
 use crate::io::{
     EnvironmentIo, FileStream, FileSystemProjectIo, FileType, IoTrait, Metadata, ProjectIo,
@@ -8,19 +9,44 @@ use std::ffi::{OsStr, OsString};
 use std::path::Path;
 use std::path::PathBuf;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime};
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
 use tokio_util::compat::TokioAsyncReadCompatExt;
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct DefaultEnvironmentIo {
     root: Box<Path>,
+    observer: Option<Arc<dyn EventObserver>>,
+    profile: Option<Box<str>>,
+    shared_settings: Option<Box<Path>>,
+}
+
+impl std::fmt::Debug for DefaultEnvironmentIo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DefaultEnvironmentIo")
+            .field("root", &self.root)
+            .field("profile", &self.profile)
+            .finish_non_exhaustive()
+    }
 }
 
 impl DefaultEnvironmentIo {
     pub fn new(root: Box<Path>) -> Self {
-        Self { root }
+        Self {
+            root,
+            observer: None,
+            profile: None,
+            shared_settings: None,
+        }
+    }
+
+    /// Sets the observer of the events instead of [`LogObserver`].
+    pub fn with_observer(mut self, observer: Arc<dyn EventObserver>) -> Self {
+        self.observer = Some(observer);
+        self
     }
 
     pub fn new_default() -> Self {
@@ -36,6 +62,50 @@ impl DefaultEnvironmentIo {
         DefaultEnvironmentIo::new(folder.clone().into_boxed_path())
     }
 
+    /// Creates the io for the profile created with [`create_profile`].
+    ///
+    /// All files of the profile are in the profile folder in the default environment folder.
+    /// [`DEFAULT_PROFILE`] is the same as [`new_default`](Self::new_default).
+    ///
+    /// [`create_profile`]: crate::environment::create_profile
+    /// [`DEFAULT_PROFILE`]: crate::environment::DEFAULT_PROFILE
+    pub async fn new_profile(name: &str) -> io::Result<Self> {
+        let default = Self::new_default();
+        if name == crate::environment::DEFAULT_PROFILE {
+            return Ok(default);
+        }
+
+        let Some(profile) = crate::environment::load_profile(&default, name).await? else {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("profile {name} not found"),
+            ));
+        };
+
+        let root = default.root.join(profile.folder());
+        debug!(
+            "initializing EnvironmentIo for profile {name} at {}",
+            root.display()
+        );
+
+        Ok(Self {
+            shared_settings: (profile.share_vcc_settings)
+                .then(|| default.root.join("settings.json").into_boxed_path()),
+            profile: Some(profile.name),
+            ..Self::new(root.into_boxed_path())
+        })
+    }
+
+    /// The environment folder. All files of this environment are in this folder.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The name of the profile, or `None` for the default environment.
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
     #[cfg(windows)]
     fn get_local_config_folder() -> PathBuf {
         dirs_sys::known_folder_local_app_data().expect("LocalAppData not found")
@@ -66,6 +136,17 @@ impl EnvironmentIo for DefaultEnvironmentIo {
         self.root.join(path)
     }
 
+    fn observer(&self) -> &dyn EventObserver {
+        match &self.observer {
+            Some(observer) => observer.as_ref(),
+            None => &LogObserver,
+        }
+    }
+
+    fn shared_settings_path(&self) -> Option<&Path> {
+        self.shared_settings.as_deref()
+    }
+
     #[cfg(feature = "vrc-get-litedb")]
     #[cfg(windows)]
     type MutexGuard = vrc_get_litedb::shared_mutex::SharedMutexOwnedGuard;
@@ -161,7 +242,110 @@ impl DefaultProjectIo {
     }
 }
 
-impl ProjectIo for DefaultProjectIo {}
+impl ProjectIo for DefaultProjectIo {
+    #[cfg(windows)]
+    async fn is_exclusively_locked(&self, path: &Path) -> io::Result<Option<bool>> {
+        use std::os::windows::fs::OpenOptionsExt;
+        const ERROR_SHARING_VIOLATION: i32 = 32;
+
+        // Unity opens the lockfile without sharing so opening it without sharing fails while Unity is running
+        let path = self.resolve_fs(path)?;
+        match std::fs::OpenOptions::new()
+            .read(true)
+            .share_mode(0)
+            .open(path)
+        {
+            Ok(_) => Ok(Some(false)),
+            Err(e) if e.raw_os_error() == Some(ERROR_SHARING_VIOLATION) => Ok(Some(true)),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn hard_link(&self, original: &Path, link: &Path) -> io::Result<()> {
+        fs::hard_link(
+            extended_length_path(original.to_owned())?,
+            self.resolve_fs(link)?,
+        )
+        .await
+    }
+
+    #[cfg(unix)]
+    async fn create_dir_link(&self, target: &Path, link: &Path) -> io::Result<()> {
+        fs::symlink(target, self.resolve(link)?).await
+    }
+
+    #[cfg(windows)]
+    async fn create_dir_link(&self, target: &Path, link: &Path) -> io::Result<()> {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+        // creating symbolic links requires developer mode or administrator, but junctions don't.
+        // std doesn't provide a way to create junctions, so we use mklink of cmd.
+        // cmd does not support extended-length paths so the paths are passed as is.
+        let status = tokio::process::Command::new("cmd")
+            .arg("/C")
+            .arg("mklink")
+            .arg("/J")
+            .arg(self.resolve(link)?)
+            .arg(target)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .creation_flags(CREATE_NO_WINDOW)
+            .status()
+            .await?;
+        if !status.success() {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("failed to create junction: mklink exited with {status}"),
+            ));
+        }
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    fn is_process_running(&self, pid: u32) -> Option<bool> {
+        Some(Path::new("/proc").join(pid.to_string()).exists())
+    }
+
+    #[cfg(windows)]
+    #[allow(unsafe_code)]
+    fn is_process_running(&self, pid: u32) -> Option<bool> {
+        use windows::Win32::Foundation::{CloseHandle, ERROR_INVALID_PARAMETER, STILL_ACTIVE};
+        use windows::Win32::System::Threading::{
+            GetExitCodeProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+        };
+
+        // SAFETY: the handle is closed after use
+        unsafe {
+            let handle = match OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) {
+                Ok(handle) => handle,
+                // no process has the id
+                Err(e) if e.code() == ERROR_INVALID_PARAMETER.to_hresult() => return Some(false),
+                // like the process of another user
+                Err(_) => return None,
+            };
+            let mut exit_code = 0;
+            let result = GetExitCodeProcess(handle, &mut exit_code);
+            let _ = CloseHandle(handle);
+            result.ok()?;
+            Some(exit_code == STILL_ACTIVE.0 as u32)
+        }
+    }
+
+    async fn sleep(&self, duration: Duration) -> bool {
+        tokio::time::sleep(duration).await;
+        true
+    }
+
+    fn blocking_remover(&self, path: &Path) -> Option<Box<dyn FnOnce() + Send + Sync>> {
+        let path = self.resolve_fs(path).ok()?;
+        Some(Box::new(move || {
+            if let Err(e) = std::fs::remove_file(&path) {
+                log::warn!("failed to remove {}: {e}", path.display());
+            }
+        }))
+    }
+}
 
 impl FileSystemProjectIo for DefaultProjectIo {
     #[inline]
@@ -185,19 +369,65 @@ impl TokioIoTraitImpl for DefaultProjectIo {
 
 trait TokioIoTraitImpl {
     fn resolve(&self, path: &Path) -> io::Result<PathBuf>;
+
+    /// Resolves the path for the file system operations.
+    ///
+    /// The returned path may be an extended-length path so it should not be shown to the user.
+    fn resolve_fs(&self, path: &Path) -> io::Result<PathBuf> {
+        extended_length_path(self.resolve(path)?)
+    }
+}
+
+/// Converts the path to the extended-length path (`\\?\C:\path`) so that
+/// paths longer than `MAX_PATH` can be used on windows.
+///
+/// Windows does not normalize extended-length paths so the path is normalized before prefixing.
+#[cfg(windows)]
+fn extended_length_path(path: PathBuf) -> io::Result<PathBuf> {
+    use std::path::{Component, Prefix};
+
+    let path = std::path::absolute(path)?;
+    let mut components = path.components();
+    let Some(Component::Prefix(prefix)) = components.next() else {
+        return Ok(path);
+    };
+    let mut result = match prefix.kind() {
+        Prefix::Disk(_) => {
+            let mut result = OsString::from(r"\\?\");
+            result.push(prefix.as_os_str());
+            result
+        }
+        Prefix::UNC(server, share) => {
+            let mut result = OsString::from(r"\\?\UNC\");
+            result.push(server);
+            result.push(r"\");
+            result.push(share);
+            result
+        }
+        // already verbatim or device paths
+        _ => return Ok(path),
+    };
+    result.push(components.as_path());
+    Ok(result.into())
+}
+
+#[cfg(not(windows))]
+#[inline]
+fn extended_length_path(path: PathBuf) -> io::Result<PathBuf> {
+    Ok(path)
 }
 
 impl<T: TokioIoTraitImpl + Sync> IoTrait for T {
     async fn create_dir_all(&self, path: &Path) -> io::Result<()> {
-        fs::create_dir_all(self.resolve(path)?).await
+        fs::create_dir_all(self.resolve_fs(path)?).await
     }
 
     async fn write(&self, path: &Path, content: &[u8]) -> io::Result<()> {
-        tokio::fs::write(self.resolve(path)?, content).await
+        tokio::fs::write(self.resolve_fs(path)?, content).await
     }
 
     async fn write_sync(&self, path: &Path, content: &[u8]) -> io::Result<()> {
-        let path = self.resolve(path)?;
+        let path = self.resolve_fs(path)?;
         let mut file = fs::File::create(&path).await?;
         file.write_all(content).await?;
         file.flush().await?;
@@ -206,30 +436,39 @@ impl<T: TokioIoTraitImpl + Sync> IoTrait for T {
     }
 
     async fn remove_file(&self, path: &Path) -> io::Result<()> {
-        fs::remove_file(self.resolve(path)?).await
+        fs::remove_file(self.resolve_fs(path)?).await
     }
 
     async fn remove_dir(&self, path: &Path) -> io::Result<()> {
-        fs::remove_dir(self.resolve(path)?).await
+        fs::remove_dir(self.resolve_fs(path)?).await
     }
 
     async fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
-        fs::remove_dir_all(self.resolve(path)?).await
+        fs::remove_dir_all(self.resolve_fs(path)?).await
     }
 
     async fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
-        fs::rename(self.resolve(from)?, self.resolve(to)?).await
+        fs::rename(self.resolve_fs(from)?, self.resolve_fs(to)?).await
     }
 
     async fn metadata(&self, path: &Path) -> io::Result<Metadata> {
-        fs::metadata(self.resolve(path)?).await.map(Into::into)
+        fs::metadata(self.resolve_fs(path)?).await.map(Into::into)
+    }
+
+    async fn set_modified(&self, path: &Path, time: SystemTime) -> io::Result<()> {
+        // write access is required to change times on windows
+        let file = fs::OpenOptions::new()
+            .write(true)
+            .open(self.resolve_fs(path)?)
+            .await?;
+        file.into_std().await.set_modified(time)
     }
 
     type DirEntry = DirEntry;
     type ReadDirStream = ReadDir;
 
     async fn read_dir(&self, path: &Path) -> io::Result<Self::ReadDirStream> {
-        Ok(ReadDir::new(fs::read_dir(self.resolve(path)?).await?))
+        Ok(ReadDir::new(fs::read_dir(self.resolve_fs(path)?).await?))
     }
 
     type FileStream = tokio_util::compat::Compat<fs::File>;
@@ -239,7 +478,7 @@ impl<T: TokioIoTraitImpl + Sync> IoTrait for T {
             .create_new(true)
             .write(true)
             .read(true)
-            .open(self.resolve(path)?)
+            .open(self.resolve_fs(path)?)
             .map_ok(|file| file.compat())
             .await
     }
@@ -250,13 +489,13 @@ impl<T: TokioIoTraitImpl + Sync> IoTrait for T {
             .truncate(true)
             .write(true)
             .read(true)
-            .open(self.resolve(path)?)
+            .open(self.resolve_fs(path)?)
             .and_then(|file| async { Ok(file.compat()) })
             .await
     }
 
     async fn open(&self, path: &Path) -> io::Result<Self::FileStream> {
-        Ok(fs::File::open(self.resolve(path)?).await?.compat())
+        Ok(fs::File::open(self.resolve_fs(path)?).await?.compat())
     }
 }
 
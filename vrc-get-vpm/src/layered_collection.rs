@@ -0,0 +1,124 @@
+use crate::{PackageCollection, PackageInfo, VersionSelector};
+
+/// The [`PackageCollection`] combining multiple collections in the priority order.
+///
+/// The layers added first have higher priority.
+/// A package version in a layer shadows the same version of the package in the lower layers,
+/// and the latest version among all layers is selected by [`find_package_by_name`],
+/// preferring the higher layer for the same version.
+///
+/// ```
+/// # use vrc_get_vpm::{LayeredCollection, PackageCollection};
+/// # fn compose(dev_folder: &dyn PackageCollection, environment: &dyn PackageCollection) {
+/// let collection = LayeredCollection::new()
+///     .with_layer(dev_folder)
+///     .with_layer(environment);
+/// # }
+/// ```
+///
+/// [`find_package_by_name`]: PackageCollection::find_package_by_name
+#[derive(Default, Clone)]
+pub struct LayeredCollection<'a> {
+    layers: Vec<&'a dyn PackageCollection>,
+}
+
+impl<'a> LayeredCollection<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the layer with lower priority than the layers already added.
+    pub fn with_layer(mut self, layer: &'a dyn PackageCollection) -> Self {
+        self.layers.push(layer);
+        self
+    }
+
+    /// Adds the layer with lower priority than the layers already added.
+    pub fn push_layer(&mut self, layer: &'a dyn PackageCollection) {
+        self.layers.push(layer);
+    }
+
+    pub fn layers(&self) -> &[&'a dyn PackageCollection] {
+        &self.layers
+    }
+
+    /// Whether the package is in the layers with higher priority than `index`.
+    fn is_shadowed(&self, index: usize, package: PackageInfo) -> bool {
+        self.layers[..index].iter().any(|upper| {
+            let selector = VersionSelector::specific_version(package.version());
+            upper
+                .find_package_by_name(package.name(), selector)
+                .is_some()
+        })
+    }
+}
+
+impl PackageCollection for LayeredCollection<'_> {
+    fn get_curated_packages<'a, 'iter>(
+        &'a self,
+        version_selector: VersionSelector<'iter>,
+    ) -> Box<dyn Iterator<Item = PackageInfo<'a>> + 'iter>
+    where
+        'a: 'iter,
+    {
+        Box::new(
+            self.layers
+                .iter()
+                .enumerate()
+                .flat_map(move |(index, layer)| {
+                    layer
+                        .get_curated_packages(version_selector)
+                        .filter(move |x| !self.is_shadowed(index, *x))
+                }),
+        )
+    }
+
+    fn get_all_packages(&self) -> Box<dyn Iterator<Item = PackageInfo<'_>> + '_> {
+        Box::new(
+            self.layers
+                .iter()
+                .enumerate()
+                .flat_map(move |(index, layer)| {
+                    layer
+                        .get_all_packages()
+                        .filter(move |x| !self.is_shadowed(index, *x))
+                }),
+        )
+    }
+
+    fn find_packages<'a, 'iter>(
+        &'a self,
+        package: &'iter str,
+    ) -> Box<dyn Iterator<Item = PackageInfo<'a>> + 'iter>
+    where
+        'a: 'iter,
+    {
+        Box::new(
+            self.layers
+                .iter()
+                .enumerate()
+                .flat_map(move |(index, layer)| {
+                    layer
+                        .find_packages(package)
+                        .filter(move |x| !self.is_shadowed(index, *x))
+                }),
+        )
+    }
+
+    fn find_package_by_name(
+        &self,
+        package: &str,
+        package_selector: VersionSelector,
+    ) -> Option<PackageInfo<'_>> {
+        let mut found: Option<PackageInfo> = None;
+        for layer in &self.layers {
+            if let Some(candidate) = layer.find_package_by_name(package, package_selector) {
+                // the higher layer is kept for the same version
+                if found.is_none_or(|found| candidate.version() > found.version()) {
+                    found = Some(candidate);
+                }
+            }
+        }
+        found
+    }
+}
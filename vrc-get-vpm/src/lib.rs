@@ -1,6 +1,21 @@
 //! The vpm client library.
 //!
 //! TODO: documentation
+//!
+//! # Features
+//!
+//! - `rustls` (default) and `native-tls`: implements [`HttpClient`] for `reqwest::Client` with the TLS backend.
+//! - `tokio`: provides `DefaultEnvironmentIo` and `DefaultProjectIo` on the real file system.
+//! - `unity` and `unity-hub`: launches Unity and Unity Hub, and reads their installations.
+//! - `blocking`: provides the synchronous wrappers in `blocking`, with the tokio runtime.
+//! - `test-util`: provides the in-memory io in `io::memory` for testing.
+//! - `experimental-project-management` and `experimental-unity-management`:
+//!   reads and writes the VCC database, with the native LiteDB.
+//!
+//! Without these features, the crate does not spawn processes nor depend on native libraries,
+//! so the versions, the manifests, the package resolution, and planning the changes
+//! over [`PackageCollection`] and [`ProjectIo`](io::ProjectIo) builds for wasm targets
+//! like `wasm32-wasip1` with `default-features = false`.
 
 #![deny(unsafe_code)]
 
@@ -13,8 +28,13 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 use version::{ReleaseType, UnityVersion, Version, VersionRange};
 
 pub mod environment;
+mod error;
+pub mod event;
 pub mod io;
+mod layered_collection;
+mod package_info_snapshot;
 mod package_manifest;
+mod package_name;
 pub mod repository;
 mod structs;
 mod traits;
@@ -25,6 +45,8 @@ mod version_selector;
 
 pub mod repositories_file;
 
+#[cfg(feature = "blocking")]
+pub mod blocking;
 #[cfg(feature = "unity")]
 pub mod unity;
 #[cfg(feature = "unity-hub")]
@@ -32,11 +54,26 @@ pub mod unity_hub;
 
 use crate::repository::local::LocalCachedRepository;
 
+pub use error::{Error, ErrorCode, ErrorParam};
+pub use layered_collection::LayeredCollection;
+pub use package_info_snapshot::{PackageInfoSnapshot, PackageSource};
+pub use package_manifest::Author;
+pub use package_manifest::KeywordCategories;
+pub use package_manifest::PackageCategory;
 pub use package_manifest::PackageManifest;
 pub use package_manifest::PartialUnityVersion;
+pub use package_manifest::Sample;
+pub use package_manifest::ScopedRegistry;
+pub use package_name::{
+    InvalidPackageName, MAX_PACKAGE_NAME_LENGTH, WINDOWS_RESERVED_CHARS, WINDOWS_RESERVED_NAMES,
+    validate_package_name,
+};
 pub use structs::setting::UserRepoSetting;
 pub use traits::AbortCheck;
 pub use traits::HttpClient;
+pub use traits::HttpMethod;
+pub use traits::HttpRequest;
+pub use traits::HttpResponse;
 pub use traits::PackageCollection;
 pub use traits::PackageInstaller;
 pub use unity_project::UnityProject;
@@ -143,10 +180,46 @@ impl<'a> PackageInfo<'a> {
         self.package_json().is_yanked()
     }
 
+    pub fn is_deprecated(self) -> bool {
+        self.package_json().is_deprecated()
+    }
+
+    pub fn deprecation_message(self) -> Option<&'a str> {
+        self.package_json().deprecation_message()
+    }
+
     pub fn display_name(self) -> Option<&'a str> {
         self.package_json().display_name()
     }
 
+    pub fn author(self) -> Option<&'a Author> {
+        self.package_json().author()
+    }
+
+    pub fn documentation_url(self) -> Option<&'a url::Url> {
+        self.package_json().documentation_url()
+    }
+
+    pub fn licenses_url(self) -> Option<&'a url::Url> {
+        self.package_json().licenses_url()
+    }
+
+    pub fn license(self) -> Option<&'a str> {
+        self.package_json().license()
+    }
+
+    pub fn keywords(self) -> &'a [Box<str>] {
+        self.package_json().keywords()
+    }
+
+    pub fn category(self) -> PackageCategory {
+        self.package_json().category()
+    }
+
+    pub fn samples(self) -> &'a [Sample] {
+        self.package_json().samples()
+    }
+
     pub fn aliases(self) -> &'a [Box<str>] {
         self.package_json().aliases()
     }
@@ -0,0 +1,157 @@
+use crate::version::{Version, VersionRange};
+use crate::{
+    Author, KeywordCategories, PackageCategory, PackageInfo, PackageInfoInner, PartialUnityVersion,
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use url::Url;
+
+/// The owned copy of the information of [`PackageInfo`].
+///
+/// Unlike [`PackageInfo`], this doesn't borrow the collection, so this can be held across await points
+/// or sent to other threads and processes.
+/// Use [`PackageCollection::resolve_snapshot`](crate::PackageCollection::resolve_snapshot)
+/// to get the [`PackageInfo`] again for the operations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageInfoSnapshot {
+    name: Box<str>,
+    version: Version,
+    display_name: Option<Box<str>>,
+    description: Option<Box<str>>,
+    #[serde(default)]
+    keywords: Vec<Box<str>>,
+    #[serde(default)]
+    author: Option<Author>,
+    unity: Option<PartialUnityVersion>,
+    vpm_dependencies: IndexMap<Box<str>, VersionRange>,
+    url: Option<Url>,
+    zip_sha_256: Option<Box<str>>,
+    source: PackageSource,
+    is_yanked: bool,
+    is_deprecated: bool,
+}
+
+/// Where the package of [`PackageInfoSnapshot`] is from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum PackageSource {
+    /// The package in the remote repository, with the id or the url of the repository.
+    Remote { repository_id: Option<Box<str>> },
+    /// The user package at the path.
+    Local { path: PathBuf },
+}
+
+impl PackageInfoSnapshot {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> &Version {
+        &self.version
+    }
+
+    pub fn display_name(&self) -> Option<&str> {
+        self.display_name.as_deref()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn keywords(&self) -> &[Box<str>] {
+        &self.keywords
+    }
+
+    /// The category derived from the keywords with the well-known mapping.
+    ///
+    /// Use [`KeywordCategories::category_of`] to use another mapping.
+    pub fn category(&self) -> PackageCategory {
+        KeywordCategories::new().category_of(self.keywords())
+    }
+
+    pub fn author(&self) -> Option<&Author> {
+        self.author.as_ref()
+    }
+
+    pub fn unity(&self) -> Option<&PartialUnityVersion> {
+        self.unity.as_ref()
+    }
+
+    pub fn vpm_dependencies(&self) -> &IndexMap<Box<str>, VersionRange> {
+        &self.vpm_dependencies
+    }
+
+    /// The url of the zip file of the package.
+    pub fn url(&self) -> Option<&Url> {
+        self.url.as_ref()
+    }
+
+    pub fn zip_sha_256(&self) -> Option<&str> {
+        self.zip_sha_256.as_deref()
+    }
+
+    pub fn source(&self) -> &PackageSource {
+        &self.source
+    }
+
+    pub fn is_yanked(&self) -> bool {
+        self.is_yanked
+    }
+
+    pub fn is_deprecated(&self) -> bool {
+        self.is_deprecated
+    }
+
+    /// Whether the snapshot is taken from the package, with the same name, version, and source.
+    pub fn is_snapshot_of(&self, package: PackageInfo) -> bool {
+        if package.name() != self.name() || package.version() != self.version() {
+            return false;
+        }
+        match (&self.source, package.inner) {
+            (PackageSource::Remote { repository_id }, PackageInfoInner::Remote(_, repo)) => {
+                repository_id.as_deref() == repository_id_of(repo)
+            }
+            (PackageSource::Local { path }, PackageInfoInner::Local(_, package_path)) => {
+                path.as_path() == package_path
+            }
+            _ => false,
+        }
+    }
+}
+
+fn repository_id_of(repo: &crate::repository::LocalCachedRepository) -> Option<&str> {
+    repo.id().or(repo.url().map(Url::as_str))
+}
+
+impl PackageInfo<'_> {
+    /// Copies the information of the package to the owned [`PackageInfoSnapshot`].
+    pub fn to_snapshot(self) -> PackageInfoSnapshot {
+        let json = self.package_json();
+        let source = match self.inner {
+            PackageInfoInner::Remote(_, repo) => PackageSource::Remote {
+                repository_id: repository_id_of(repo).map(Into::into),
+            },
+            PackageInfoInner::Local(_, path) => PackageSource::Local {
+                path: path.to_path_buf(),
+            },
+        };
+        PackageInfoSnapshot {
+            name: json.name().into(),
+            version: json.version().clone(),
+            display_name: json.display_name().map(Into::into),
+            description: json.description().map(Into::into),
+            keywords: json.keywords().to_vec(),
+            author: json.author().cloned(),
+            unity: json.unity().cloned(),
+            vpm_dependencies: json.vpm_dependencies().clone(),
+            url: json.url().cloned(),
+            zip_sha_256: json.zip_sha_256().map(Into::into),
+            source,
+            is_yanked: json.is_yanked(),
+            is_deprecated: json.is_deprecated(),
+        }
+    }
+}
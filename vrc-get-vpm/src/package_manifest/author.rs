@@ -0,0 +1,195 @@
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// The `author` of the package.
+///
+/// The author can be written as an object with `name`, `email`, and `url`,
+/// or a string like `Name <email> (url)` as npm allows.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize)]
+pub struct Author {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<Box<str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    email: Option<Box<str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<Box<str>>,
+}
+
+impl Author {
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn email(&self) -> Option<&str> {
+        self.email.as_deref()
+    }
+
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    /// Parses the string form of the author, `Name <email> (url)`.
+    ///
+    /// All parts are optional and the name is the text before `<` or `(`.
+    pub fn parse(author: &str) -> Self {
+        fn enclosed<'a>(author: &'a str, open: char, close: char) -> Option<(usize, &'a str)> {
+            let start = author.find(open)?;
+            let rest = &author[start + open.len_utf8()..];
+            let value = match rest.find(close) {
+                Some(end) => &rest[..end],
+                None => rest,
+            };
+            Some((start, value))
+        }
+
+        let email = enclosed(author, '<', '>');
+        let url = enclosed(author, '(', ')');
+
+        let name_end = [email.map(|x| x.0), url.map(|x| x.0)]
+            .into_iter()
+            .flatten()
+            .min()
+            .unwrap_or(author.len());
+
+        Self {
+            name: non_empty(&author[..name_end]),
+            email: email.and_then(|x| non_empty(x.1)),
+            url: url.and_then(|x| non_empty(x.1)),
+        }
+    }
+}
+
+fn non_empty(value: &str) -> Option<Box<str>> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.into())
+}
+
+impl<'de> Deserialize<'de> for Author {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum AuthorJson {
+            String(Box<str>),
+            Object {
+                #[serde(default)]
+                name: Option<Box<str>>,
+                #[serde(default)]
+                email: Option<Box<str>>,
+                #[serde(default)]
+                url: Option<Box<str>>,
+            },
+            Other(IgnoredAny),
+        }
+
+        Ok(match AuthorJson::deserialize(deserializer)? {
+            AuthorJson::String(author) => Author::parse(&author),
+            AuthorJson::Object { name, email, url } => Author {
+                name: name.as_deref().and_then(non_empty),
+                email: email.as_deref().and_then(non_empty),
+                url: url.as_deref().and_then(non_empty),
+            },
+            AuthorJson::Other(_) => Author::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn author(name: Option<&str>, email: Option<&str>, url: Option<&str>) -> Author {
+        Author {
+            name: name.map(Into::into),
+            email: email.map(Into::into),
+            url: url.map(Into::into),
+        }
+    }
+
+    #[test]
+    fn parse_full() {
+        assert_eq!(
+            Author::parse("anatawa12 <anatawa12@example.com> (https://anatawa12.com)"),
+            author(
+                Some("anatawa12"),
+                Some("anatawa12@example.com"),
+                Some("https://anatawa12.com")
+            )
+        );
+    }
+
+    #[test]
+    fn parse_name_only() {
+        assert_eq!(
+            Author::parse("  VRChat Inc.  "),
+            author(Some("VRChat Inc."), None, None)
+        );
+    }
+
+    #[test]
+    fn parse_partial() {
+        assert_eq!(
+            Author::parse("anatawa12 (https://anatawa12.com)"),
+            author(Some("anatawa12"), None, Some("https://anatawa12.com"))
+        );
+        assert_eq!(
+            Author::parse("anatawa12 <anatawa12@example.com>"),
+            author(Some("anatawa12"), Some("anatawa12@example.com"), None)
+        );
+        // the order of email and url is loose
+        assert_eq!(
+            Author::parse("anatawa12 (https://anatawa12.com) <anatawa12@example.com>"),
+            author(
+                Some("anatawa12"),
+                Some("anatawa12@example.com"),
+                Some("https://anatawa12.com")
+            )
+        );
+    }
+
+    #[test]
+    fn parse_without_name() {
+        assert_eq!(
+            Author::parse("<anatawa12@example.com>"),
+            author(None, Some("anatawa12@example.com"), None)
+        );
+        assert_eq!(Author::parse(""), author(None, None, None));
+    }
+
+    #[test]
+    fn parse_unclosed() {
+        assert_eq!(
+            Author::parse("anatawa12 <anatawa12@example.com"),
+            author(Some("anatawa12"), Some("anatawa12@example.com"), None)
+        );
+    }
+
+    #[test]
+    fn deserialize() {
+        let from_string: Author =
+            serde_json::from_str(r#""anatawa12 <anatawa12@example.com>""#).unwrap();
+        assert_eq!(
+            from_string,
+            author(Some("anatawa12"), Some("anatawa12@example.com"), None)
+        );
+
+        let from_object: Author =
+            serde_json::from_str(r#"{"name": "anatawa12", "url": "https://anatawa12.com"}"#)
+                .unwrap();
+        assert_eq!(
+            from_object,
+            author(Some("anatawa12"), None, Some("https://anatawa12.com"))
+        );
+    }
+
+    #[test]
+    fn deserialize_malformed() {
+        // the malformed authors are ignored instead of failing the whole manifest
+        for json in ["42", "true", r#"["anatawa12"]"#, r#"{"name": 12}"#] {
+            let author: Author = serde_json::from_str(json).unwrap();
+            assert_eq!(author, Author::default(), "{json}");
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use serde::Serialize;
+
+/// The category of the package derived from the `keywords` in `package.json`.
+///
+/// The variants are declared in the priority order. If the keywords match multiple categories,
+/// the one declared first is used, so a tool for avatars is a [`Tool`](Self::Tool).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[non_exhaustive]
+pub enum PackageCategory {
+    /// The editor tools or extensions
+    Tool,
+    /// The components used in avatars, including the NDMF plugins
+    AvatarComponent,
+    /// The components used in worlds, including the Udon programs
+    WorldComponent,
+    /// The libraries used by other packages
+    Library,
+    /// No keywords match any category
+    Unknown,
+}
+
+/// The well-known keywords and their categories.
+const DEFAULT_KEYWORD_CATEGORIES: &[(&str, PackageCategory)] = &[
+    ("editor-tool", PackageCategory::Tool),
+    ("editor-extension", PackageCategory::Tool),
+    ("tool", PackageCategory::Tool),
+    ("tools", PackageCategory::Tool),
+    ("avatar", PackageCategory::AvatarComponent),
+    ("avatars", PackageCategory::AvatarComponent),
+    ("avatar-component", PackageCategory::AvatarComponent),
+    ("ndmf", PackageCategory::AvatarComponent),
+    ("world", PackageCategory::WorldComponent),
+    ("worlds", PackageCategory::WorldComponent),
+    ("world-component", PackageCategory::WorldComponent),
+    ("udon", PackageCategory::WorldComponent),
+    ("udonsharp", PackageCategory::WorldComponent),
+    ("library", PackageCategory::Library),
+    ("lib", PackageCategory::Library),
+    ("api", PackageCategory::Library),
+];
+
+/// The mapping from the keywords to [`PackageCategory`].
+///
+/// [`KeywordCategories::new`] has the mapping for the well-known keywords,
+/// and frontends can add or override the mapping with [`KeywordCategories::map`].
+/// The keywords are compared case-insensitively.
+#[derive(Debug, Clone, Default)]
+pub struct KeywordCategories {
+    overrides: Vec<(Box<str>, PackageCategory)>,
+}
+
+impl KeywordCategories {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maps the keyword to the category, overriding the well-known mapping.
+    ///
+    /// Mapping to [`PackageCategory::Unknown`] makes the keyword ignored.
+    pub fn map(mut self, keyword: impl Into<Box<str>>, category: PackageCategory) -> Self {
+        let keyword = keyword.into();
+        self.overrides
+            .retain(|(x, _)| !x.eq_ignore_ascii_case(&keyword));
+        self.overrides.push((keyword, category));
+        self
+    }
+
+    /// Returns the category of the package with the keywords.
+    pub fn category_of(&self, keywords: &[Box<str>]) -> PackageCategory {
+        (keywords.iter())
+            .filter_map(|x| self.lookup(x))
+            .min()
+            .unwrap_or(PackageCategory::Unknown)
+    }
+
+    fn lookup(&self, keyword: &str) -> Option<PackageCategory> {
+        let user = self.overrides.iter().map(|(k, c)| (k.as_ref(), *c));
+        let default = DEFAULT_KEYWORD_CATEGORIES.iter().copied();
+        (user.chain(default))
+            .find(|(k, _)| k.eq_ignore_ascii_case(keyword))
+            .map(|(_, c)| c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keywords(keywords: &[&str]) -> Vec<Box<str>> {
+        keywords.iter().map(|&x| x.into()).collect()
+    }
+
+    #[test]
+    fn default_mapping() {
+        let categories = KeywordCategories::new();
+        assert_eq!(
+            categories.category_of(&keywords(&["vrchat", "NDMF"])),
+            PackageCategory::AvatarComponent
+        );
+        assert_eq!(
+            categories.category_of(&keywords(&["udon"])),
+            PackageCategory::WorldComponent
+        );
+        assert_eq!(
+            categories.category_of(&keywords(&["vrchat"])),
+            PackageCategory::Unknown
+        );
+        assert_eq!(categories.category_of(&[]), PackageCategory::Unknown);
+    }
+
+    #[test]
+    fn priority() {
+        let categories = KeywordCategories::new();
+        assert_eq!(
+            categories.category_of(&keywords(&["avatar", "editor-tool"])),
+            PackageCategory::Tool
+        );
+        assert_eq!(
+            categories.category_of(&keywords(&["library", "world"])),
+            PackageCategory::WorldComponent
+        );
+    }
+
+    #[test]
+    fn overrides() {
+        let categories = KeywordCategories::new()
+            .map("gimmick", PackageCategory::AvatarComponent)
+            .map("Tool", PackageCategory::Unknown);
+        assert_eq!(
+            categories.category_of(&keywords(&["gimmick"])),
+            PackageCategory::AvatarComponent
+        );
+        assert_eq!(
+            categories.category_of(&keywords(&["tool", "library"])),
+            PackageCategory::Library
+        );
+    }
+}
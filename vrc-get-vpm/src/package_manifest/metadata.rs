@@ -0,0 +1,114 @@
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Deserializer, Serialize};
+use url::Url;
+
+/// A sample in `samples` of the package, which can be imported to the project.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Sample {
+    #[serde(default)]
+    display_name: Option<Box<str>>,
+    #[serde(default)]
+    description: Option<Box<str>>,
+    /// The path to the sample folder, relative to the package folder
+    path: Box<str>,
+}
+
+impl Sample {
+    pub fn display_name(&self) -> Option<&str> {
+        self.display_name.as_deref()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+// The metadata fields are only for showing to the user, so the values with unexpected types
+// are ignored instead of failing to load the package.
+
+/// The list field that is sometimes written as a single value instead of an array.
+#[derive(Debug, Clone)]
+pub(super) struct OneOrMany<T>(pub(super) Vec<T>);
+
+impl<T> Default for OneOrMany<T> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for OneOrMany<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Json<T> {
+            Many(Vec<T>),
+            One(T),
+            Other(IgnoredAny),
+        }
+
+        Ok(match Json::deserialize(deserializer)? {
+            Json::Many(values) => Self(values),
+            Json::One(value) => Self(vec![value]),
+            Json::Other(_) => Self::default(),
+        })
+    }
+}
+
+/// The `license` field, the SPDX expression or the legacy `{ "type": "MIT" }` object.
+#[derive(Debug, Clone, Default)]
+pub(super) struct License(pub(super) Option<Box<str>>);
+
+impl<'de> Deserialize<'de> for License {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Json {
+            String(Box<str>),
+            Object {
+                #[serde(rename = "type")]
+                license_type: Box<str>,
+            },
+            Other(IgnoredAny),
+        }
+
+        Ok(match Json::deserialize(deserializer)? {
+            Json::String(license) => Self(Some(license)),
+            Json::Object { license_type } => Self(Some(license_type)),
+            Json::Other(_) => Self(None),
+        })
+    }
+}
+
+/// The url field, which is often an empty string if not specified.
+#[derive(Debug, Clone, Default)]
+pub(super) struct MetadataUrl(pub(super) Option<Url>);
+
+impl<'de> Deserialize<'de> for MetadataUrl {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Json {
+            Url(Url),
+            Other(IgnoredAny),
+        }
+
+        Ok(match Json::deserialize(deserializer)? {
+            Json::Url(url) => Self(Some(url)),
+            Json::Other(_) => Self(None),
+        })
+    }
+}
@@ -1,15 +1,26 @@
+mod author;
+mod category;
+mod metadata;
 mod partial_unity_version;
+mod scoped_registry;
 mod yank_state;
 
 use crate::utils::DedupForwarder;
+use crate::utils::Interner;
 use crate::version::{Version, VersionRange};
 use indexmap::IndexMap;
 use serde::{Deserialize, Deserializer};
 use std::collections::HashMap;
+use std::sync::Arc;
 use url::Url;
 
-use crate::package_manifest::yank_state::YankState;
+use crate::package_manifest::metadata::{License, MetadataUrl, OneOrMany};
+use crate::package_manifest::yank_state::{DeprecationState, YankState};
+pub use author::Author;
+pub use category::{KeywordCategories, PackageCategory};
+pub use metadata::Sample;
 pub use partial_unity_version::PartialUnityVersion;
+pub use scoped_registry::ScopedRegistry;
 
 macro_rules! initialize_from_package_json_like {
     ($source: expr) => {
@@ -18,6 +29,7 @@ macro_rules! initialize_from_package_json_like {
             version: $source.version,
             display_name: $source.display_name,
             description: $source.description,
+            author: $source.author,
             unity: $source.unity,
             url: $source.url,
             zip_sha_256: $source.zip_sha_256,
@@ -27,9 +39,18 @@ macro_rules! initialize_from_package_json_like {
             legacy_packages: $source.legacy_packages,
             headers: $source.headers,
             changelog_url: $source.changelog_url,
+            documentation_url: $source.documentation_url,
+            licenses_url: $source.licenses_url,
+            license: $source.license,
+            keywords: $source.keywords,
+            samples: $source.samples,
             vrc_get: VrcGetMeta {
                 yanked: $source.vrc_get.yanked,
+                deprecated: $source.vrc_get.deprecated,
                 aliases: $source.vrc_get.aliases,
+                upm_dependencies: $source.vrc_get.upm_dependencies,
+                scoped_registries: $source.vrc_get.scoped_registries,
+                original_version: $source.vrc_get.original_version,
             },
         }
     };
@@ -52,14 +73,16 @@ macro_rules! package_json_struct {
         $(#[$meta])*
         $vis struct $name {
             $(#[$required])?
-            $required_vis name: Box<str>,
+            $required_vis name: Arc<str>,
             $(#[$required])?
             $required_vis version: Version,
 
             $(#[$optional])?
-            $optional_vis display_name: Option<Box<str>>,
+            $optional_vis display_name: Option<Arc<str>>,
             $(#[$optional])?
-            $optional_vis description: Option<Box<str>>,
+            $optional_vis description: Option<Arc<str>>,
+            $(#[$optional])?
+            $optional_vis author: Option<Arc<Author>>,
             $(#[$optional])?
             $optional_vis unity: Option<PartialUnityVersion>,
 
@@ -84,6 +107,16 @@ macro_rules! package_json_struct {
 
             $(#[$optional])?
             $optional_vis changelog_url: Option<Url>,
+            $(#[$optional])?
+            $optional_vis documentation_url: MetadataUrl,
+            $(#[$optional])?
+            $optional_vis licenses_url: MetadataUrl,
+            $(#[$optional])?
+            $optional_vis license: License,
+            $(#[$optional])?
+            $optional_vis keywords: OneOrMany<Box<str>>,
+            $(#[$optional])?
+            $optional_vis samples: OneOrMany<Sample>,
 
             $(#[$optional])?
             #[serde(rename = "vrc-get")]
@@ -97,9 +130,22 @@ macro_rules! package_json_struct {
         $vrc_get_struct_vis struct $vrc_get_meta_name {
             $(#[$vrc_get_optional])?
             $vrc_get_optional_vis yanked: YankState,
+            /// the package is deprecated, with the message if specified.
+            $(#[$vrc_get_optional])?
+            $vrc_get_optional_vis deprecated: DeprecationState,
             /// aliases for `vrc-get i --name <name> <version>` command.
             $(#[$vrc_get_optional])?
             $vrc_get_optional_vis aliases: Vec<Box<str>>,
+            /// dependencies added to `Packages/manifest.json` when the package is installed.
+            $(#[$vrc_get_optional])?
+            $vrc_get_optional_vis upm_dependencies: IndexMap<Box<str>, Version>,
+            /// scoped registries added to `Packages/manifest.json` when the package is installed.
+            $(#[$vrc_get_optional])?
+            $vrc_get_optional_vis scoped_registries: Vec<ScopedRegistry>,
+            /// the original version string if the version is parsed leniently.
+            /// This is not a part of package.json, and set when loading repositories.
+            #[serde(skip)]
+            $vrc_get_optional_vis original_version: Option<Box<str>>,
         }
     };
 }
@@ -146,6 +192,9 @@ impl PackageManifest {
     pub fn description(&self) -> Option<&str> {
         self.description.as_deref()
     }
+    pub fn author(&self) -> Option<&Author> {
+        self.author.as_deref()
+    }
     pub fn url(&self) -> Option<&Url> {
         self.url.as_ref()
     }
@@ -155,25 +204,123 @@ impl PackageManifest {
     pub fn changelog_url(&self) -> Option<&Url> {
         self.changelog_url.as_ref()
     }
+    pub fn documentation_url(&self) -> Option<&Url> {
+        self.documentation_url.0.as_ref()
+    }
+    pub fn licenses_url(&self) -> Option<&Url> {
+        self.licenses_url.0.as_ref()
+    }
+    /// The `license` of the package, usually the SPDX license expression.
+    pub fn license(&self) -> Option<&str> {
+        self.license.0.as_deref()
+    }
+    pub fn keywords(&self) -> &[Box<str>] {
+        self.keywords.0.as_slice()
+    }
+    /// The category derived from the keywords with the well-known mapping.
+    ///
+    /// Use [`KeywordCategories::category_of`] to use another mapping.
+    pub fn category(&self) -> PackageCategory {
+        KeywordCategories::new().category_of(self.keywords())
+    }
+    pub fn samples(&self) -> &[Sample] {
+        self.samples.0.as_slice()
+    }
     pub fn unity(&self) -> Option<&PartialUnityVersion> {
         self.unity.as_ref()
     }
     pub fn is_yanked(&self) -> bool {
         self.vrc_get.yanked.is_yanked()
     }
+    /// Whether the package is marked as deprecated in `vrc-get.deprecated`.
+    pub fn is_deprecated(&self) -> bool {
+        self.vrc_get.deprecated.is_yanked()
+    }
+    /// The message of the deprecation, like the replacement package.
+    pub fn deprecation_message(&self) -> Option<&str> {
+        self.vrc_get.deprecated.reason()
+    }
     pub fn aliases(&self) -> &[Box<str>] {
         self.vrc_get.aliases.as_slice()
     }
+    /// The UPM dependencies the package requires in `Packages/manifest.json`.
+    pub fn upm_dependencies(&self) -> &IndexMap<Box<str>, Version> {
+        &self.vrc_get.upm_dependencies
+    }
+    /// The scoped registries the package requires in `Packages/manifest.json`.
+    pub fn scoped_registries(&self) -> &[ScopedRegistry] {
+        self.vrc_get.scoped_registries.as_slice()
+    }
+    /// Returns the version string written in the repository
+    /// if the version is not valid in strict syntax and was parsed leniently.
+    pub fn original_version(&self) -> Option<&str> {
+        self.vrc_get.original_version.as_deref()
+    }
+}
+
+impl PackageManifest {
+    /// Deserializes the manifest from third-party data like remote repositories.
+    ///
+    /// Unlike the strict deserialization, the `version` is parsed with [`Version::parse_lenient`]
+    /// and the original string is kept for [`PackageManifest::original_version`].
+    pub(crate) fn deserialize_lenient(
+        mut value: serde_value::Value,
+    ) -> Result<Self, serde_value::DeserializerError> {
+        let mut original_version = None;
+
+        if let serde_value::Value::Map(map) = &mut value {
+            let key = serde_value::Value::String("version".to_owned());
+            if let Some(serde_value::Value::String(version)) = map.get_mut(&key) {
+                if !Version::is_strict(version) {
+                    if let Ok(parsed) = Version::parse_lenient(version) {
+                        let original = std::mem::replace(version, parsed.to_string());
+                        original_version = Some(original.into_boxed_str());
+                    }
+                }
+            }
+        }
+
+        let mut manifest = PackageManifest::deserialize(value)?;
+        manifest.vrc_get.original_version = original_version;
+        Ok(manifest)
+    }
+}
+
+/// The interners to share the values repeated in the manifests of a repository.
+#[derive(Default)]
+pub(crate) struct ManifestInterner {
+    strings: Interner<str>,
+    authors: Interner<Author>,
+}
+
+impl ManifestInterner {
+    pub fn intern(&mut self, value: &str) -> Arc<str> {
+        self.strings.intern(value)
+    }
+}
+
+impl PackageManifest {
+    /// Shares the name and the metadata with the other manifests interned with `interner`.
+    ///
+    /// The versions of a package usually have the same name, description, and author,
+    /// so those are kept only once for the repository.
+    pub(crate) fn share_values(&mut self, interner: &mut ManifestInterner) {
+        interner.strings.share(&mut self.name);
+        interner.strings.share_option(&mut self.display_name);
+        interner.strings.share_option(&mut self.description);
+        interner.authors.share_option(&mut self.author);
+    }
 }
 
 /// Constructing PackageJson. Especially for testing.
 impl PackageManifest {
     pub fn new(name: impl Into<Box<str>>, version: Version) -> Self {
         Self {
-            name: name.into(),
+            name: Arc::from(name.into()),
             version,
             display_name: None,
             description: None,
+            author: None,
             vpm_dependencies: IndexMap::new(),
             url: None,
             unity: None,
@@ -184,6 +331,11 @@ impl PackageManifest {
             vrc_get: VrcGetMeta::default(),
             zip_sha_256: None,
             changelog_url: None,
+            documentation_url: MetadataUrl::default(),
+            licenses_url: MetadataUrl::default(),
+            license: License::default(),
+            keywords: OneOrMany::default(),
+            samples: OneOrMany::default(),
         }
     }
 
@@ -211,6 +363,21 @@ impl PackageManifest {
         self.legacy_files.insert(path.into(), Some(guid.into()));
         self
     }
+
+    pub fn add_upm_dependency(mut self, name: impl Into<Box<str>>, version: Version) -> Self {
+        self.vrc_get.upm_dependencies.insert(name.into(), version);
+        self
+    }
+
+    pub fn add_scoped_registry(mut self, registry: ScopedRegistry) -> Self {
+        self.vrc_get.scoped_registries.push(registry);
+        self
+    }
+
+    pub fn with_unity(mut self, major: u16, minor: u8) -> Self {
+        self.unity = Some(PartialUnityVersion::new(major, minor));
+        self
+    }
 }
 
 pub(crate) struct LooseManifest(pub PackageManifest);
@@ -1,10 +1,14 @@
 use serde::de::Error;
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 #[derive(Debug, Clone)]
 pub struct PartialUnityVersion(u16, u8);
 
 impl PartialUnityVersion {
+    pub(crate) fn new(major: u16, minor: u8) -> Self {
+        Self(major, minor)
+    }
+
     pub fn major(&self) -> u16 {
         self.0
     }
@@ -30,3 +34,12 @@ impl<'de> Deserialize<'de> for PartialUnityVersion {
         }
     }
 }
+
+impl Serialize for PartialUnityVersion {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(&format_args!("{}.{}", self.0, self.1))
+    }
+}
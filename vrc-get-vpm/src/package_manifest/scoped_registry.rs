@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+/// A scoped registry in `scopedRegistries` of `Packages/manifest.json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScopedRegistry {
+    name: Box<str>,
+    url: Box<str>,
+    #[serde(default)]
+    scopes: Vec<Box<str>>,
+}
+
+impl ScopedRegistry {
+    pub fn new(
+        name: impl Into<Box<str>>,
+        url: impl Into<Box<str>>,
+        scopes: impl IntoIterator<Item = impl Into<Box<str>>>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            url: url.into(),
+            scopes: scopes.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn scopes(&self) -> &[Box<str>] {
+        &self.scopes
+    }
+}
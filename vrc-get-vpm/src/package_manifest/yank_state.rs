@@ -2,6 +2,9 @@ use serde::de::Error;
 use serde::{Deserialize, Deserializer};
 use std::fmt::Formatter;
 
+/// The deprecation is written in the same format as the yank, `true` or the message.
+pub(crate) type DeprecationState = YankState;
+
 #[derive(Debug, Clone, Default)]
 pub(crate) enum YankState {
     #[default]
@@ -19,7 +22,6 @@ impl YankState {
         }
     }
 
-    #[allow(dead_code)]
     pub fn reason(&self) -> Option<&str> {
         match self {
             YankState::Reason(s) => Some(s),
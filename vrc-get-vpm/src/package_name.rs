@@ -0,0 +1,184 @@
+use crate::io;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// The longest package name accepted, the same as npm.
+pub const MAX_PACKAGE_NAME_LENGTH: usize = 214;
+
+/// The names reserved by Windows, which cannot be used as the file names even with extensions.
+///
+/// Windows matches the names case-insensitively, so compare them with lowercased names.
+pub const WINDOWS_RESERVED_NAMES: &[&str] = &[
+    "con", "prn", "aux", "nul", "com0", "com1", "com2", "com3", "com4", "com5", "com6", "com7",
+    "com8", "com9", "lpt0", "lpt1", "lpt2", "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8", "lpt9",
+];
+
+/// The characters not allowed in the file names on Windows.
+pub const WINDOWS_RESERVED_CHARS: &[char] = &['/', '\\', '<', '>', ':', '"', '|', '?', '*'];
+
+/// The reason the package name is rejected by [`validate_package_name`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InvalidPackageName {
+    Empty,
+    TooLong,
+    /// The name contains the character other than lowercase alphanumerics, `.`, `-`, and `_`,
+    /// like the path separators.
+    InvalidCharacter(char),
+    /// The name starts with `.`, like `..`.
+    LeadingDot,
+    /// The name ends with `.`, which is removed on Windows.
+    TrailingDot,
+    /// The name is reserved by Windows, like `con` or `nul.foo`.
+    ReservedName,
+}
+
+impl fmt::Display for InvalidPackageName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidPackageName::Empty => f.write_str("package name is empty"),
+            InvalidPackageName::TooLong => write!(
+                f,
+                "package name is longer than {MAX_PACKAGE_NAME_LENGTH} characters"
+            ),
+            InvalidPackageName::InvalidCharacter(c) => {
+                write!(f, "package name contains invalid character {c:?}")
+            }
+            InvalidPackageName::LeadingDot => f.write_str("package name starts with '.'"),
+            InvalidPackageName::TrailingDot => f.write_str("package name ends with '.'"),
+            InvalidPackageName::ReservedName => f.write_str("package name is reserved by Windows"),
+        }
+    }
+}
+
+impl std::error::Error for InvalidPackageName {}
+
+/// Checks the package name can be used as a folder name in `Packages` and the package cache.
+///
+/// The name must be in reverse domain name notation like `com.vrchat.base`, consisting of
+/// lowercase alphanumerics, `.`, `-`, and `_`, up to [`MAX_PACKAGE_NAME_LENGTH`] characters.
+/// The names starting or ending with `.`, and the names reserved by Windows are rejected.
+pub fn validate_package_name(name: &str) -> Result<(), InvalidPackageName> {
+    if name.is_empty() {
+        return Err(InvalidPackageName::Empty);
+    }
+    if name.len() > MAX_PACKAGE_NAME_LENGTH {
+        return Err(InvalidPackageName::TooLong);
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| !matches!(c, 'a'..='z' | '0'..='9' | '.' | '-' | '_'))
+    {
+        return Err(InvalidPackageName::InvalidCharacter(c));
+    }
+    if name.starts_with('.') {
+        return Err(InvalidPackageName::LeadingDot);
+    }
+    if name.ends_with('.') {
+        return Err(InvalidPackageName::TrailingDot);
+    }
+    // Windows treats `nul.foo` as `nul`
+    let stem = name.split('.').next().unwrap();
+    if WINDOWS_RESERVED_NAMES.contains(&stem) {
+        return Err(InvalidPackageName::ReservedName);
+    }
+    Ok(())
+}
+
+/// Validates the package name before using it in a path, like the file names in the package cache.
+pub(crate) fn check_package_name(name: &str) -> io::Result<()> {
+    validate_package_name(name).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid package name {name:?}: {e}"),
+        )
+    })
+}
+
+/// Returns the folder of the package in `parent`, like `Packages/<name>`.
+///
+/// All paths from the package names are created with this function or [`check_package_name`],
+/// so the names from repositories cannot point outside the folder.
+pub(crate) fn package_path(parent: impl AsRef<Path>, name: &str) -> io::Result<PathBuf> {
+    check_package_name(name)?;
+    Ok(parent.as_ref().join(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_names() {
+        for name in [
+            "com.vrchat.base",
+            "nadena.dev.modular-avatar",
+            "jp.lilxyzw.liltoon",
+            "com.anatawa12.avatar_optimizer",
+            "con.example",
+            "console",
+            "com10",
+        ] {
+            assert_eq!(validate_package_name(name), Ok(()), "{name}");
+        }
+    }
+
+    #[test]
+    fn path_traversal() {
+        use InvalidPackageName::*;
+        for (name, error) in [
+            ("..", LeadingDot),
+            ("../../evil", InvalidCharacter('/')),
+            ("com.foo/..\\bar", InvalidCharacter('/')),
+            ("..\\evil", InvalidCharacter('\\')),
+            ("/etc/passwd", InvalidCharacter('/')),
+            ("C:evil", InvalidCharacter('C')),
+            ("c:evil", InvalidCharacter(':')),
+            (".hidden", LeadingDot),
+            ("com.foo.", TrailingDot),
+            ("com.foo\0", InvalidCharacter('\0')),
+        ] {
+            assert_eq!(validate_package_name(name), Err(error), "{name:?}");
+        }
+    }
+
+    #[test]
+    fn windows_reserved_names() {
+        for name in ["con", "nul", "aux.package", "com1", "lpt9.foo.bar", "prn"] {
+            assert_eq!(
+                validate_package_name(name),
+                Err(InvalidPackageName::ReservedName),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn charset_and_length() {
+        assert_eq!(validate_package_name(""), Err(InvalidPackageName::Empty));
+        assert_eq!(
+            validate_package_name("com.Example"),
+            Err(InvalidPackageName::InvalidCharacter('E'))
+        );
+        assert_eq!(
+            validate_package_name("com example"),
+            Err(InvalidPackageName::InvalidCharacter(' '))
+        );
+        let longest = "a".repeat(MAX_PACKAGE_NAME_LENGTH);
+        assert_eq!(validate_package_name(&longest), Ok(()));
+        assert_eq!(
+            validate_package_name(&format!("{longest}a")),
+            Err(InvalidPackageName::TooLong)
+        );
+    }
+
+    #[test]
+    fn paths() {
+        assert_eq!(
+            package_path("Packages", "com.vrchat.base").unwrap(),
+            Path::new("Packages/com.vrchat.base")
+        );
+        let error = package_path("Packages", "../evil").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+}
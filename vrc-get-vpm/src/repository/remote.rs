@@ -1,22 +1,26 @@
 use crate::PackageManifest;
-use crate::traits::HttpClient;
+use crate::package_manifest::ManifestInterner;
+use crate::traits::{HttpClient, http_get_with_etag};
 use crate::utils::{deserialize_json, deserialize_json_slice};
 use crate::version::Version;
-use crate::{VersionSelector, io};
+use crate::{VersionSelector, io, validate_package_name};
 use futures::prelude::*;
 use indexmap::IndexMap;
-use serde::de::{DeserializeSeed, Visitor};
+use serde::de::{DeserializeSeed, IgnoredAny, Visitor};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::{Map, Value};
 use std::collections::HashMap;
 use std::pin::pin;
+use std::sync::Arc;
 use url::Url;
 
 type JsonMap = Map<String, Value>;
 
 #[derive(Debug, Clone)]
 pub struct RemoteRepository {
-    actual: JsonMap,
+    /// The JSON text of the loaded repository, kept to save the cache as it is loaded.
+    /// This is much smaller than keeping the [`JsonMap`] resident.
+    actual: Box<str>,
     parsed: ParsedRepository,
 }
 
@@ -30,14 +34,15 @@ struct ParsedRepository {
     id: Option<Box<str>>,
     #[serde(default)]
     #[serde(deserialize_with = "deserialize_packages")]
-    packages: IndexMap<Box<str>, RemotePackages>,
+    packages: IndexMap<Arc<str>, RemotePackages>,
 }
 
 impl RemoteRepository {
     pub fn parse(cache: JsonMap) -> io::Result<Self> {
+        let actual = serde_json::to_string(&cache)?.into_boxed_str();
         Ok(Self {
-            parsed: deserialize_json(Value::Object(cache.clone()))?,
-            actual: cache,
+            parsed: deserialize_json(Value::Object(cache))?,
+            actual,
         })
     }
 
@@ -59,7 +64,8 @@ impl RemoteRepository {
         headers: &IndexMap<Box<str>, Box<str>>,
         current_etag: Option<&str>,
     ) -> io::Result<Option<(RemoteRepository, Option<Box<str>>)>> {
-        let Some((mut stream, etag)) = client.get_with_etag(url, headers, current_etag).await?
+        let Some((mut stream, etag)) =
+            http_get_with_etag(client, url, headers, current_etag).await?
         else {
             return Ok(None);
         };
@@ -80,18 +86,14 @@ impl RemoteRepository {
     pub(crate) fn set_id_if_none(&mut self, f: impl FnOnce() -> Box<str>) {
         if self.parsed.id.is_none() {
             let id = f();
-            self.parsed.id = Some(id.clone());
-            self.actual
-                .insert("id".to_owned(), Value::String(id.into()));
+            self.parsed.id = Some(id);
         }
     }
 
     pub(crate) fn set_url_if_none(&mut self, f: impl FnOnce() -> Url) {
         if self.parsed.url.is_none() {
             let url = f();
-            self.parsed.url = Some(url.clone());
-            self.actual
-                .insert("url".to_owned(), Value::String(url.to_string()));
+            self.parsed.url = Some(url);
         }
         if self.parsed.id.is_none() {
             let url = self.parsed.url.as_ref().unwrap().as_str().into();
@@ -141,7 +143,22 @@ impl Serialize for RemoteRepository {
     where
         S: Serializer,
     {
-        self.actual.serialize(serializer)
+        use serde::ser::Error;
+        let mut actual = serde_json::from_str::<JsonMap>(&self.actual).map_err(Error::custom)?;
+        // the url and id set after loading are saved with the cache
+        if let Some(url) = &self.parsed.url {
+            insert_if_null(&mut actual, "url", || Value::String(url.to_string()));
+        }
+        if let Some(id) = &self.parsed.id {
+            insert_if_null(&mut actual, "id", || Value::String(id.as_ref().into()));
+        }
+        actual.serialize(serializer)
+    }
+}
+
+fn insert_if_null(map: &mut JsonMap, key: &str, value: impl FnOnce() -> Value) {
+    if map.get(key).is_none_or(Value::is_null) {
+        map.insert(key.to_owned(), value());
     }
 }
 
@@ -158,14 +175,14 @@ impl<'de> Deserialize<'de> for RemoteRepository {
 
 fn deserialize_packages<'de, D>(
     deserializer: D,
-) -> Result<IndexMap<Box<str>, RemotePackages>, D::Error>
+) -> Result<IndexMap<Arc<str>, RemotePackages>, D::Error>
 where
     D: Deserializer<'de>,
 {
     struct VisitorImpl;
 
     impl<'de> Visitor<'de> for VisitorImpl {
-        type Value = IndexMap<Box<str>, RemotePackages>;
+        type Value = IndexMap<Arc<str>, RemotePackages>;
 
         fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
             formatter.write_str("a map of package names to package versions")
@@ -175,9 +192,18 @@ where
         where
             A: serde::de::MapAccess<'de>,
         {
+            // the names and the metadata are shared in the repository
+            let mut interner = ManifestInterner::default();
             let mut packages = IndexMap::new();
             while let Some(name) = map.next_key::<Box<str>>()? {
-                let versions = map.next_value_seed(PackageNameToRemotePackages(&name))?;
+                if let Err(e) = validate_package_name(&name) {
+                    log::warn!("Skipping package {name:?} with invalid name: {e}");
+                    map.next_value::<IgnoredAny>()?;
+                    continue;
+                }
+                let name = interner.intern(&name);
+                let versions =
+                    map.next_value_seed(PackageNameToRemotePackages(&name, &mut interner))?;
                 packages.insert(name, versions);
             }
             Ok(packages)
@@ -224,7 +250,7 @@ impl RemotePackages {
     }
 }
 
-struct PackageNameToRemotePackages<'a>(&'a str);
+struct PackageNameToRemotePackages<'a>(&'a str, &'a mut ManifestInterner);
 
 impl<'de> DeserializeSeed<'de> for PackageNameToRemotePackages<'_> {
     type Value = RemotePackages;
@@ -233,7 +259,7 @@ impl<'de> DeserializeSeed<'de> for PackageNameToRemotePackages<'_> {
     where
         D: Deserializer<'de>,
     {
-        struct VisitorImpl<'a>(&'a str);
+        struct VisitorImpl<'a>(&'a str, &'a mut ManifestInterner);
 
         impl<'de> Visitor<'de> for VisitorImpl<'_> {
             type Value = RemotePackages;
@@ -249,18 +275,22 @@ impl<'de> DeserializeSeed<'de> for PackageNameToRemotePackages<'_> {
                 let mut versions = HashMap::new();
                 while let Some(key) = map.next_key::<&'de str>()? {
                     if key == "versions" {
-                        versions = map.next_value_seed(PackageNameToVersions(self.0))?;
+                        versions = map.next_value_seed(PackageNameToVersions(self.0, self.1))?;
                     }
                 }
                 Ok(RemotePackages { versions })
             }
         }
 
-        deserializer.deserialize_struct("RemotePackages", &["versions"], VisitorImpl(self.0))
+        deserializer.deserialize_struct(
+            "RemotePackages",
+            &["versions"],
+            VisitorImpl(self.0, self.1),
+        )
     }
 }
 
-struct PackageNameToVersions<'a>(&'a str);
+struct PackageNameToVersions<'a>(&'a str, &'a mut ManifestInterner);
 
 impl<'de> DeserializeSeed<'de> for PackageNameToVersions<'_> {
     type Value = HashMap<Version, PackageManifest>;
@@ -269,7 +299,7 @@ impl<'de> DeserializeSeed<'de> for PackageNameToVersions<'_> {
     where
         D: Deserializer<'de>,
     {
-        struct VisitorImpl<'a>(&'a str);
+        struct VisitorImpl<'a>(&'a str, &'a mut ManifestInterner);
 
         impl<'de> Visitor<'de> for VisitorImpl<'_> {
             type Value = HashMap<Version, PackageManifest>;
@@ -283,9 +313,19 @@ impl<'de> DeserializeSeed<'de> for PackageNameToVersions<'_> {
                 A: serde::de::MapAccess<'de>,
             {
                 let mut versions = HashMap::new();
-                while let Some(version) = map.next_key::<Version>()? {
+                while let Some(version_str) = map.next_key::<Box<str>>()? {
+                    let Ok(version) = Version::parse_lenient(&version_str) else {
+                        log::warn!(
+                            "Error parsing version {} of package {}, skipping",
+                            version_str,
+                            self.0
+                        );
+                        map.next_value::<IgnoredAny>()?;
+                        continue;
+                    };
                     let manifest = map.next_value_seed(ErrorProofManifest(self.0, &version))?;
-                    if let Some(manifest) = manifest {
+                    if let Some(mut manifest) = manifest {
+                        manifest.share_values(self.1);
                         versions.insert(version, manifest);
                     }
                 }
@@ -293,7 +333,7 @@ impl<'de> DeserializeSeed<'de> for PackageNameToVersions<'_> {
             }
         }
 
-        deserializer.deserialize_map(VisitorImpl(self.0))
+        deserializer.deserialize_map(VisitorImpl(self.0, self.1))
     }
 }
 
@@ -307,8 +347,27 @@ impl<'de> DeserializeSeed<'de> for ErrorProofManifest<'_> {
         D: Deserializer<'de>,
     {
         let value = serde_value::Value::deserialize(deserializer)?;
-        match PackageManifest::deserialize(value) {
-            Ok(manifest) => Ok(Some(manifest)),
+        match PackageManifest::deserialize_lenient(value) {
+            Ok(manifest) if validate_package_name(manifest.name()).is_err() => {
+                log::warn!(
+                    "Skipping {}@{} with invalid package name {:?}",
+                    self.0,
+                    self.1,
+                    manifest.name()
+                );
+                Ok(None)
+            }
+            Ok(manifest) => {
+                if let Some(original) = manifest.original_version() {
+                    log::debug!(
+                        "version {} of {} is not valid semver and parsed as {}",
+                        original,
+                        self.0,
+                        manifest.version()
+                    );
+                }
+                Ok(Some(manifest))
+            }
             Err(err) => {
                 log::warn!(
                     "Error deserializing package manifest for {}@{}: {}",
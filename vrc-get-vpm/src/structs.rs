@@ -8,7 +8,7 @@ pub mod setting {
     #[derive(Serialize, Deserialize, Debug, Clone)]
     #[serde(rename_all = "camelCase")]
     pub struct UserRepoSetting {
-        local_path: Box<Path>,
+        pub(crate) local_path: Box<Path>,
         #[serde(default, skip_serializing_if = "Option::is_none")]
         name: Option<Box<str>>,
         // must be non-relative url.
@@ -1,36 +1,61 @@
+use crate::event::{EventObserver, LogObserver};
 use crate::io;
-use crate::io::ProjectIo;
+use crate::io::{FileOperation, ProjectIo};
+#[cfg(feature = "reqwest")]
 use crate::utils::MapResultExt;
-use crate::{PackageInfo, VersionSelector};
+use crate::{PackageInfo, PackageInfoSnapshot, VersionSelector};
 use core::iter::Iterator;
 use core::option::Option;
 use futures::prelude::*;
 use indexmap::IndexMap;
 use std::convert::Infallible;
+use std::pin::pin;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Poll, Waker};
 use url::Url;
 
+/// The collection of the packages to install.
+///
+/// This trait is dyn compatible, so the collections can be combined as `&dyn PackageCollection`
+/// like [`LayeredCollection`](crate::LayeredCollection).
 pub trait PackageCollection {
     /// get curated packages
-    fn get_curated_packages(
-        &self,
-        _version_selector: VersionSelector,
-    ) -> impl Iterator<Item = PackageInfo> {
-        [].into_iter()
+    fn get_curated_packages<'a, 'iter>(
+        &'a self,
+        _version_selector: VersionSelector<'iter>,
+    ) -> Box<dyn Iterator<Item = PackageInfo<'a>> + 'iter>
+    where
+        'a: 'iter,
+    {
+        Box::new(std::iter::empty())
     }
 
     /// get all packages in the collection
-    fn get_all_packages(&self) -> impl Iterator<Item = PackageInfo>;
+    fn get_all_packages(&self) -> Box<dyn Iterator<Item = PackageInfo<'_>> + '_>;
 
     /// get all package versions of the specified package
-    fn find_packages(&self, package: &str) -> impl Iterator<Item = PackageInfo>;
+    fn find_packages<'a, 'iter>(
+        &'a self,
+        package: &'iter str,
+    ) -> Box<dyn Iterator<Item = PackageInfo<'a>> + 'iter>
+    where
+        'a: 'iter;
 
     /// get specified version of specified package
     fn find_package_by_name(
         &self,
         package: &str,
         package_selector: VersionSelector,
-    ) -> Option<PackageInfo>;
+    ) -> Option<PackageInfo<'_>>;
+
+    /// Finds the package the snapshot is taken from, to use the package for the operations again.
+    ///
+    /// Returns `None` if the package is no longer in the collection at the version and the source.
+    fn resolve_snapshot(&self, snapshot: &PackageInfoSnapshot) -> Option<PackageInfo<'_>> {
+        self.find_packages(snapshot.name())
+            .find(|&package| snapshot.is_snapshot_of(package))
+    }
 }
 
 /// The trait for installing package
@@ -44,125 +69,374 @@ pub trait PackageInstaller {
         package: PackageInfo<'_>,
         abort: &AbortCheck,
     ) -> impl Future<Output = io::Result<()>>;
+
+    /// Installs the specified package for [`UnityProject::simulate_pending_changes`].
+    ///
+    /// `io` records the operations instead of changing the project, so this should do
+    /// the same operations to `io` as [`install_package`](Self::install_package),
+    /// but must not download packages or change anything outside `io`.
+    /// The operations not done through `io`, like downloading the package, should be returned.
+    ///
+    /// This fails with [`io::ErrorKind::Unsupported`] by default since [`install_package`](Self::install_package)
+    /// may change things outside `io`, so the installers not implementing this cannot be used for simulation.
+    ///
+    /// [`UnityProject::simulate_pending_changes`]: crate::UnityProject::simulate_pending_changes
+    fn simulate_install_package(
+        &self,
+        io: &impl ProjectIo,
+        package: PackageInfo<'_>,
+    ) -> impl Future<Output = io::Result<Vec<FileOperation>>> {
+        let _ = (io, package);
+        std::future::ready(Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the installer does not support simulation",
+        )))
+    }
+
+    /// The observer receives the events of the operations using this installer.
+    ///
+    /// This is [`LogObserver`] by default.
+    fn observer(&self) -> &dyn EventObserver {
+        &LogObserver
+    }
 }
 
+/// The flag to abort long-running operations.
+///
+/// Operations check this flag at safe points and fail with [`io::ErrorKind::Interrupted`] if aborted,
+/// which is [`Error::Cancelled`](crate::Error::Cancelled) in the crate-level error.
+/// When an operation is aborted, the changes made by the operation are reverted as possible,
+/// like removing partially downloaded files.
+///
+/// This is a cheap handle to the shared flag; clone this to abort the operation from another task.
+#[derive(Clone, Default)]
 pub struct AbortCheck {
+    inner: Arc<AbortInner>,
+}
+
+#[derive(Default)]
+struct AbortInner {
     abort: AtomicBool,
+    /// this check is aborted if the parent is aborted
+    parent: Option<AbortCheck>,
+    /// the tasks waiting for abort in [`AbortCheck::aborted`]
+    wakers: Mutex<Vec<Waker>>,
 }
 
 impl AbortCheck {
-    pub(crate) fn new() -> Self {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a check aborted together with this check, which can also be aborted separately.
+    ///
+    /// This is useful to abort the rest of parallel operations if one of them failed.
+    pub fn child(&self) -> Self {
         Self {
-            abort: AtomicBool::new(false),
+            inner: Arc::new(AbortInner {
+                parent: Some(self.clone()),
+                ..Default::default()
+            }),
         }
     }
 
+    pub fn is_aborted(&self) -> bool {
+        self.inner.abort.load(Ordering::Acquire)
+            || (self.inner.parent.as_ref()).is_some_and(AbortCheck::is_aborted)
+    }
+
     pub fn check(&self) -> io::Result<()> {
-        if self.abort.load(Ordering::Relaxed) {
-            return Err(io::Error::new(io::ErrorKind::Interrupted, "Aborted"));
+        if self.is_aborted() {
+            return Err(aborted_error());
         }
         Ok(())
     }
 
-    pub(crate) fn abort(&self) {
-        self.abort.store(true, Ordering::Relaxed);
+    pub fn abort(&self) {
+        self.inner.abort.store(true, Ordering::Release);
+        let wakers = std::mem::take(&mut *self.inner.wakers.lock().unwrap());
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    /// Waits until this check is aborted.
+    pub fn aborted(&self) -> impl Future<Output = ()> + Send + '_ {
+        future::poll_fn(move |cx| {
+            if self.is_aborted() {
+                return Poll::Ready(());
+            }
+            self.register(cx.waker());
+            // abort may be called while registering the waker
+            if self.is_aborted() {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+    }
+
+    fn register(&self, waker: &Waker) {
+        // the wakers are kept until aborted since the checks are usually short-lived
+        {
+            let mut wakers = self.inner.wakers.lock().unwrap();
+            if !wakers.iter().any(|x| x.will_wake(waker)) {
+                wakers.push(waker.clone());
+            }
+        }
+        if let Some(parent) = &self.inner.parent {
+            parent.register(waker);
+        }
+    }
+
+    /// Runs `future` until it completes, or fails with [`io::ErrorKind::Interrupted`] as soon as aborted.
+    ///
+    /// The future is dropped when aborted, so this should be used for the futures safe to be dropped
+    /// like HTTP requests, not for writing files.
+    pub async fn run<T>(&self, future: impl Future<Output = io::Result<T>>) -> io::Result<T> {
+        self.check()?;
+        match future::select(pin!(future), pin!(self.aborted())).await {
+            future::Either::Left((result, _)) => result,
+            future::Either::Right(((), _)) => Err(aborted_error()),
+        }
     }
 }
 
+fn aborted_error() -> io::Error {
+    io::Error::new(io::ErrorKind::Interrupted, "Aborted")
+}
+
 /// The HTTP Client.
+///
+/// Implement this trait to use other HTTP stacks than reqwest, which is implemented with the `rustls` or `native-tls` feature.
+/// The implementations should follow the redirects, and decode the body with `Content-Encoding`.
+///
+/// # Migration from `get` and `get_with_etag`
+///
+/// Prior to this version, the implementations provided `get` and `get_with_etag`,
+/// which returned error for the error status codes and handled `ETag`s.
+/// Now the implementations only send the [`HttpRequest`] as is, and return the [`HttpResponse`]
+/// with the status code and the headers, including the error statuses and `304 Not Modified`.
+/// The status codes and the conditional requests are handled by this crate.
 pub trait HttpClient: Sync {
-    /// Get resource from the URL with specified headers
+    /// Sends the request and returns the response.
     ///
-    /// Note: If remote server returns error status code, this function should return error.
-    fn get(
+    /// The responses with error status codes must be returned as `Ok`.
+    /// `Err` should be returned only when the request cannot be sent or the response cannot be received.
+    /// The future may be dropped to abort the request.
+    fn send(
         &self,
-        url: &Url,
-        headers: &IndexMap<&str, &str>,
-    ) -> impl Future<Output = io::Result<impl AsyncRead + Send>> + Send;
-
-    /// Get resource from the URL with specified headers and etag
-    ///
-    /// Returning `Ok(None)` means cache matched.
-    /// Returning `Ok(Some((stream, etag)))` means cache not matched and get from remote server.
-    /// Returning `Err(_)` means error.
-    ///
-    /// Note: If remote server returns error status code, this function should return error.
-    fn get_with_etag(
-        &self,
-        url: &Url,
-        headers: &IndexMap<Box<str>, Box<str>>,
-        current_etag: Option<&str>,
-    ) -> impl Future<Output = io::Result<Option<(impl AsyncRead + Send, Option<Box<str>>)>>> + Send;
+        request: HttpRequest<'_>,
+    ) -> impl Future<Output = io::Result<HttpResponse<impl AsyncRead + Send>>> + Send;
 }
 
-impl HttpClient for reqwest::Client {
-    async fn get(&self, url: &Url, headers: &IndexMap<&str, &str>) -> io::Result<impl AsyncRead> {
-        // file not found: err
+/// The HTTP method of [`HttpRequest`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HttpMethod {
+    Get,
+    Head,
+}
 
-        let mut request = self.get(url.to_owned());
+/// The HTTP request for [`HttpClient::send`].
+#[derive(Debug, Clone)]
+pub struct HttpRequest<'a> {
+    method: HttpMethod,
+    url: &'a Url,
+    headers: Vec<(&'a str, &'a str)>,
+}
 
-        for (&name, &header) in headers {
-            request = request.header(name, header);
+impl<'a> HttpRequest<'a> {
+    pub fn new(method: HttpMethod, url: &'a Url) -> Self {
+        Self {
+            method,
+            url,
+            headers: Vec::new(),
         }
+    }
 
-        Ok(request
-            .send()
-            .await
-            .and_then(reqwest::Response::error_for_status)
-            .err_mapped()?
-            .bytes_stream()
-            .map(|x| x.err_mapped())
-            .into_async_read())
+    pub fn get(url: &'a Url) -> Self {
+        Self::new(HttpMethod::Get, url)
     }
 
-    async fn get_with_etag(
-        &self,
-        url: &Url,
-        headers: &IndexMap<Box<str>, Box<str>>,
-        current_etag: Option<&str>,
-    ) -> io::Result<Option<(impl AsyncRead, Option<Box<str>>)>> {
-        let mut request = self.get(url.to_owned());
-        for (name, value) in headers {
-            request = request.header(name.as_ref(), value.as_ref());
-        }
-        if let Some(etag) = current_etag {
-            request = request.header("If-None-Match", etag.to_owned())
-        }
-        let response = request.send().await.err_mapped()?;
-        let response = response.error_for_status().err_mapped()?;
+    pub fn head(url: &'a Url) -> Self {
+        Self::new(HttpMethod::Head, url)
+    }
+
+    /// Adds the header to the request. The headers with the same name are sent multiple times.
+    pub fn header(mut self, name: &'a str, value: &'a str) -> Self {
+        self.headers.push((name, value));
+        self
+    }
+
+    pub fn method(&self) -> HttpMethod {
+        self.method
+    }
+
+    pub fn url(&self) -> &'a Url {
+        self.url
+    }
+
+    pub fn headers(&self) -> &[(&'a str, &'a str)] {
+        &self.headers
+    }
+}
 
-        if current_etag.is_some() && response.status() == 304 {
-            // for requests with etag, 304 means cache matched
-            return Ok(None);
+/// The HTTP response returned by [`HttpClient::send`], with the body stream `B`.
+#[derive(Debug)]
+pub struct HttpResponse<B> {
+    status: u16,
+    headers: Vec<(Box<str>, Box<str>)>,
+    body: B,
+}
+
+impl<B> HttpResponse<B> {
+    pub fn new(status: u16, body: B) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body,
         }
+    }
+
+    /// Adds the header to the response.
+    pub fn with_header(mut self, name: impl Into<Box<str>>, value: impl Into<Box<str>>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    /// Whether the status code is `2xx`.
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    pub fn headers(&self) -> &[(Box<str>, Box<str>)] {
+        &self.headers
+    }
+
+    /// Returns the first value of the header. The name is case-insensitive.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_ref())
+    }
+
+    pub fn into_body(self) -> B {
+        self.body
+    }
+}
+
+/// The error for the responses with error status codes.
+#[derive(Debug)]
+pub(crate) struct HttpStatusError {
+    status: u16,
+    url: Url,
+}
+
+impl std::fmt::Display for HttpStatusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HTTP status {} for url ({})", self.status, self.url)
+    }
+}
+
+impl std::error::Error for HttpStatusError {}
 
-        let etag = response
-            .headers()
-            .get("Etag")
-            .and_then(|x| x.to_str().ok())
-            .map(Into::into);
+fn error_for_status<B>(url: &Url, response: HttpResponse<B>) -> io::Result<HttpResponse<B>> {
+    if response.status >= 400 {
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            HttpStatusError {
+                status: response.status,
+                url: url.clone(),
+            },
+        ))
+    } else {
+        Ok(response)
+    }
+}
 
-        // response.json() doesn't support BOM
-        let response_stream = response
+/// Gets the resource from the URL with the headers, and fails with the error status codes.
+pub(crate) async fn http_get(
+    http: &impl HttpClient,
+    url: &Url,
+    headers: &IndexMap<&str, &str>,
+) -> io::Result<impl AsyncRead + Send> {
+    let mut request = HttpRequest::get(url);
+    for (&name, &value) in headers {
+        request = request.header(name, value);
+    }
+    let response = error_for_status(url, http.send(request).await?)?;
+    Ok(response.into_body())
+}
+
+/// Gets the resource from the URL with the headers and the etag.
+///
+/// Returns `Ok(None)` if the cache matched,
+/// and `Ok(Some((stream, etag)))` with the new etag if the resource is fetched.
+pub(crate) async fn http_get_with_etag(
+    http: &impl HttpClient,
+    url: &Url,
+    headers: &IndexMap<Box<str>, Box<str>>,
+    current_etag: Option<&str>,
+) -> io::Result<Option<(impl AsyncRead + Send, Option<Box<str>>)>> {
+    let mut request = HttpRequest::get(url);
+    for (name, value) in headers {
+        request = request.header(name.as_ref(), value.as_ref());
+    }
+    if let Some(etag) = current_etag {
+        request = request.header("If-None-Match", etag);
+    }
+    let response = error_for_status(url, http.send(request).await?)?;
+
+    if current_etag.is_some() && response.status() == 304 {
+        // for requests with etag, 304 means cache matched
+        return Ok(None);
+    }
+
+    let etag = response.header("Etag").map(Into::into);
+    Ok(Some((response.into_body(), etag)))
+}
+
+#[cfg(feature = "reqwest")]
+impl HttpClient for reqwest::Client {
+    async fn send(
+        &self,
+        request: HttpRequest<'_>,
+    ) -> io::Result<HttpResponse<impl AsyncRead + Send>> {
+        let method = match request.method() {
+            HttpMethod::Get => reqwest::Method::GET,
+            HttpMethod::Head => reqwest::Method::HEAD,
+        };
+        let mut builder = self.request(method, request.url().to_owned());
+        for &(name, value) in request.headers() {
+            builder = builder.header(name, value);
+        }
+        let response = builder.send().await.err_mapped()?;
+
+        let status = response.status().as_u16();
+        let headers = (response.headers().iter())
+            .filter_map(|(name, value)| Some((name.as_str().into(), value.to_str().ok()?.into())))
+            .collect();
+        let body = response
             .bytes_stream()
             .map(|x| x.err_mapped())
             .into_async_read();
 
-        Ok(Some((response_stream, etag)))
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
     }
 }
 
 impl HttpClient for Infallible {
-    async fn get(&self, _: &Url, _: &IndexMap<&str, &str>) -> io::Result<impl AsyncRead> {
-        Ok(io::empty())
-    }
-
-    async fn get_with_etag(
-        &self,
-        _: &Url,
-        _: &IndexMap<Box<str>, Box<str>>,
-        _: Option<&str>,
-    ) -> io::Result<Option<(impl AsyncRead, Option<Box<str>>)>> {
-        Ok(Some((io::empty(), None)))
+    async fn send(&self, _: HttpRequest<'_>) -> io::Result<HttpResponse<impl AsyncRead + Send>> {
+        Ok(HttpResponse::new(200, io::empty()))
     }
 }
@@ -1,7 +1,15 @@
-use crate::io;
+#[cfg(feature = "experimental-unity-management")]
+use crate::environment::UnityInstallation;
+#[cfg(feature = "experimental-unity-management")]
+use crate::error::{IoCategory, categorized};
+use crate::event::{Event, EventObserver};
 use crate::version::UnityVersion;
+use crate::{AbortCheck, io};
 use std::path::Path;
+use std::process::{ExitStatus, Stdio};
 use std::str::from_utf8;
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
 use tokio::process::Command;
 
 pub async fn call_unity_for_version(path: &Path) -> io::Result<UnityVersion> {
@@ -45,3 +53,245 @@ pub async fn call_unity_for_version(path: &Path) -> io::Result<UnityVersion> {
 
     Ok(version)
 }
+
+/// The result of launching Unity to finalize the migration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum UnityMigrationResult {
+    /// Unity exited successfully.
+    Success,
+    /// Unity reported compile errors in the scripts of the project.
+    CompileError,
+    /// Unity crashed or was killed by a signal.
+    Crashed { exit_code: Option<i32> },
+    /// Unity exited with non-zero exit code without known reason.
+    Failed { exit_code: i32 },
+    /// Unity did not exit within the timeout and has been killed.
+    TimedOut,
+}
+
+/// Launches the Unity installation in batch mode to let Unity upgrade the assets of the project.
+///
+/// Each line of the Unity log is sent to `observer` as [`Event::UnityMigrationLog`]
+/// while Unity is running.
+/// If `timeout` is passed, Unity will be killed when it's not exited within the duration.
+/// Unity is also killed when `abort` is aborted, and this fails with [`Error::Cancelled`](crate::Error::Cancelled).
+///
+/// Dropping the returned future will kill the Unity process.
+#[cfg(feature = "experimental-unity-management")]
+pub async fn run_unity_migration(
+    installation: &UnityInstallation,
+    project: &Path,
+    timeout: Option<Duration>,
+    observer: &dyn EventObserver,
+    abort: &AbortCheck,
+) -> Result<UnityMigrationResult, crate::Error> {
+    let Some(unity) = installation.path() else {
+        return Err(categorized(
+            IoCategory::UnityManagement,
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "the Unity installation has no path",
+            ),
+        )
+        .into());
+    };
+    Ok(run_unity_migration_impl(Path::new(unity), project, timeout, observer, abort).await?)
+}
+
+/// Launches Unity at `unity` in batch mode to let Unity upgrade the assets of the project.
+///
+/// This is the same as `run_unity_migration` but for the Unity not registered to the VCC database.
+pub async fn run_unity_migration_with_path(
+    unity: &Path,
+    project: &Path,
+    timeout: Option<Duration>,
+    observer: &dyn EventObserver,
+    abort: &AbortCheck,
+) -> Result<UnityMigrationResult, crate::Error> {
+    Ok(run_unity_migration_impl(unity, project, timeout, observer, abort).await?)
+}
+
+async fn run_unity_migration_impl(
+    unity: &Path,
+    project: &Path,
+    timeout: Option<Duration>,
+    observer: &dyn EventObserver,
+    abort: &AbortCheck,
+) -> io::Result<UnityMigrationResult> {
+    abort.check()?;
+
+    // On windows, Unity does not support writing log to stdout so we use temporary file
+    #[cfg(windows)]
+    let log_file = TemporaryLogFile(
+        std::env::temp_dir().join(format!("vrc-get-unity-{}.log", uuid::Uuid::new_v4())),
+    );
+
+    let mut command = Command::new(unity);
+    command
+        .args([
+            "-batchmode".as_ref(),
+            "-quit".as_ref(),
+            "-projectPath".as_ref(),
+            project.as_os_str(),
+        ])
+        .arg("-logFile")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true);
+
+    #[cfg(not(windows))]
+    command.arg("-").stdout(Stdio::piped());
+    #[cfg(windows)]
+    command.arg(&log_file.0).stdout(Stdio::null());
+
+    let mut child = command.spawn()?;
+    let mut analyzer = LogAnalyzer::default();
+
+    let run = async {
+        let mut buffer = vec![0u8; 8192];
+        let mut lines = LineBuffer::default();
+        let mut on_line = |line: &str| {
+            analyzer.analyze(line);
+            observer.on_event(&Event::UnityMigrationLog { line });
+        };
+
+        #[cfg(not(windows))]
+        let status = {
+            let mut stdout = child.stdout.take().expect("stdout is piped");
+            loop {
+                let read = stdout.read(&mut buffer).await?;
+                if read == 0 {
+                    break;
+                }
+                lines.push(&buffer[..read], &mut on_line);
+            }
+            child.wait().await?
+        };
+
+        #[cfg(windows)]
+        let status = {
+            // unity may not create the log file immediately so we wait for the file
+            let mut file = None;
+            loop {
+                let exited = child.try_wait()?;
+                if file.is_none() {
+                    file = tokio::fs::File::open(&log_file.0).await.ok();
+                }
+                if let Some(file) = file.as_mut() {
+                    loop {
+                        let read = file.read(&mut buffer).await?;
+                        if read == 0 {
+                            break;
+                        }
+                        lines.push(&buffer[..read], &mut on_line);
+                    }
+                }
+                if let Some(status) = exited {
+                    break status;
+                }
+                tokio::time::sleep(Duration::from_millis(200)).await;
+            }
+        };
+
+        lines.finish(&mut on_line);
+
+        Ok::<ExitStatus, io::Error>(status)
+    };
+
+    // None if timed out
+    let run = async {
+        match timeout {
+            None => run.await.map(Some),
+            Some(timeout) => match tokio::time::timeout(timeout, run).await {
+                Ok(status) => status.map(Some),
+                Err(_) => Ok(None),
+            },
+        }
+    };
+
+    let status = match abort.run(run).await {
+        Ok(Some(status)) => status,
+        Ok(None) => {
+            child.kill().await?;
+            return Ok(UnityMigrationResult::TimedOut);
+        }
+        Err(e) => {
+            // kill_on_drop does not wait for the process, so kill here not to leave Unity running
+            child.kill().await.ok();
+            return Err(e);
+        }
+    };
+
+    Ok(analyzer.result(status))
+}
+
+#[cfg(windows)]
+struct TemporaryLogFile(std::path::PathBuf);
+
+#[cfg(windows)]
+impl Drop for TemporaryLogFile {
+    fn drop(&mut self) {
+        std::fs::remove_file(&self.0).ok();
+    }
+}
+
+#[derive(Default)]
+struct LineBuffer {
+    buffer: Vec<u8>,
+}
+
+impl LineBuffer {
+    fn push(&mut self, data: &[u8], on_line: &mut impl FnMut(&str)) {
+        self.buffer.extend_from_slice(data);
+        let mut start = 0;
+        while let Some(end) = self.buffer[start..].iter().position(|&x| x == b'\n') {
+            Self::emit(&self.buffer[start..start + end], on_line);
+            start += end + 1;
+        }
+        self.buffer.drain(..start);
+    }
+
+    fn finish(&mut self, on_line: &mut impl FnMut(&str)) {
+        if !self.buffer.is_empty() {
+            Self::emit(&self.buffer, on_line);
+            self.buffer.clear();
+        }
+    }
+
+    fn emit(line: &[u8], on_line: &mut impl FnMut(&str)) {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        on_line(&String::from_utf8_lossy(line));
+    }
+}
+
+#[derive(Default)]
+struct LogAnalyzer {
+    compile_error: bool,
+    crashed: bool,
+}
+
+impl LogAnalyzer {
+    fn analyze(&mut self, line: &str) {
+        if line.contains("Scripts have compiler errors.") || line.contains(": error CS") {
+            self.compile_error = true;
+        }
+        if line.starts_with("Crash!!!") || line.contains("Received signal SIGSEGV") {
+            self.crashed = true;
+        }
+    }
+
+    fn result(&self, status: ExitStatus) -> UnityMigrationResult {
+        if status.success() {
+            return UnityMigrationResult::Success;
+        }
+        match status.code() {
+            _ if self.crashed => UnityMigrationResult::Crashed {
+                exit_code: status.code(),
+            },
+            None => UnityMigrationResult::Crashed { exit_code: None },
+            Some(_) if self.compile_error => UnityMigrationResult::CompileError,
+            Some(exit_code) => UnityMigrationResult::Failed { exit_code },
+        }
+    }
+}
@@ -1,27 +1,46 @@
 mod add_package;
+mod backup;
+mod confirmation;
+mod diagnostics;
 mod find_legacy_assets;
+mod gitignore;
+mod migrate_legacy_sdk;
 mod migrate_unity_2022;
+mod migrate_unity_version;
 mod migrate_vpm;
+mod outdated;
 mod package_resolution;
+mod packages_overview;
 pub mod pending_project_changes;
+mod project_lock;
+mod project_settings;
+mod project_size;
 mod project_type;
+mod project_version;
 mod reinstall;
 mod remove_package;
 mod resolve;
+mod simulation;
+mod unity_lock;
 mod upm_manifest;
 mod vpm_manifest;
+mod vpm_resolver;
 
+use crate::unity_project::project_lock::ProjectLock;
+use crate::unity_project::project_settings::ProjectSettings;
 use crate::unity_project::upm_manifest::UpmManifest;
 use crate::unity_project::vpm_manifest::VpmManifest;
 use crate::utils::{PathBufExt, try_load_json};
 use crate::version::{UnityVersion, Version, VersionRange};
 use crate::{PackageManifest, io};
-use futures::future::try_join;
+use futures::future::try_join3;
 use futures::prelude::*;
 use indexmap::IndexMap;
 use lazy_static::lazy_static;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::{Mutex, Weak};
+use std::time::Duration;
 
 // note: this module only declares basic small operations.
 // there are module for each complex operations.
@@ -30,24 +49,61 @@ use crate::io::{DirEntry, FileSystemProjectIo, ProjectIo};
 use crate::package_manifest::LooseManifest;
 pub use add_package::AddPackageErr;
 pub use add_package::AddPackageOperation;
+pub use backup::{
+    BackupCompression, BackupOptions, BackupProgress, BackupSymlinks, DEFAULT_BACKUP_EXCLUDES,
+};
+pub use confirmation::{
+    ChangeClassification, ChangesReport, ConfirmationHandler, ConfirmationPolicy, InstallReport,
+    RemovalReport,
+};
+pub use diagnostics::ProjectDiagnostic;
+pub use gitignore::DEFAULT_GITIGNORE;
+pub use migrate_legacy_sdk::MigrateLegacySdkError;
 pub use migrate_unity_2022::MigrateUnity2022Error;
+pub use migrate_unity_version::MigrateUnityVersionError;
 pub use migrate_vpm::MigrateVpmError;
+pub use outdated::{HeldBack, OutdatedPackage, OutdatedReport};
+pub use packages_overview::{InstalledPackageKind, PackageOverview};
 pub use pending_project_changes::PendingProjectChanges;
+pub use project_lock::{ProjectLockGuard, ProjectLockedError, ProjectModifiedError};
+pub use project_size::ProjectSizeReport;
+pub use project_version::{ProjectVersion, ProjectVersionError};
 pub use reinstall::ReinstalPackagesError;
 pub use remove_package::RemovePackageErr;
 pub use resolve::ResolvePackageErr;
+pub use unity_lock::{
+    ProjectOpenInUnityError, UnityLockState, check_not_open_in_unity, unity_lock_state,
+};
+pub(crate) use vpm_manifest::read_locked_packages;
+
+const PROJECT_VERSION_PATH: &str = "ProjectSettings/ProjectVersion.txt";
+const VPM_MANIFEST_PATH: &str = "Packages/vpm-manifest.json";
 
 #[derive(Debug)]
 pub struct UnityProject<IO: ProjectIo> {
     io: IO,
     /// vpm-manifest.json
     manifest: VpmManifest,
+    /// vpm-manifest.json as loaded or saved, to find the modifications by other processes
+    manifest_snapshot: Option<Box<[u8]>>,
     // manifest.json
     upm_manifest: UpmManifest,
-    /// unity version parsed
-    unity_version: Option<UnityVersion>,
-    /// unity revision parsed
-    unity_revision: Option<String>,
+    /// vrc-get-settings.json
+    settings: ProjectSettings,
+    /// ProjectVersion.txt
+    project_version: Result<ProjectVersion, ProjectVersionError>,
+    /// problems found while loading the project
+    diagnostics: Vec<ProjectDiagnostic>,
+    /// allow modifying the project while it's open in Unity
+    ignore_unity_lock: bool,
+    /// allow upgrading VRChat SDK not compatible with the Unity version
+    allow_incompatible_sdk: bool,
+    /// how long to wait for other operations to release `.vrc-get.lock`
+    lock_timeout: Duration,
+    /// the project lock held by the running operation, shared with the nested operations
+    held_lock: Mutex<Weak<ProjectLock>>,
+    /// operate without `.vrc-get.lock`, for simulating operations
+    ignore_project_lock: bool,
     /// packages installed in the directory but not locked in vpm-manifest.json
     unlocked_packages: Vec<(Box<str>, Option<PackageManifest>)>,
     /// packages installed in the directory and licked in vpm-manifest.json
@@ -56,51 +112,147 @@ pub struct UnityProject<IO: ProjectIo> {
 
 // basic lifecycle
 impl<IO: ProjectIo> UnityProject<IO> {
+    /// Loads the project.
+    ///
+    /// Loading succeeds as long as the project folder can be read, and problems are reported with
+    /// [`diagnostics`](Self::diagnostics).
     pub async fn load(io: IO) -> io::Result<Self> {
-        let manifest = VpmManifest::load(&io).await?;
-        let upm_manifest = UpmManifest::load(&io).await?;
-
-        let mut installed_packages = HashMap::new();
-        let mut unlocked_packages = vec![];
-
-        match io.read_dir("Packages".as_ref()).await {
+        let mut diagnostics = vec![];
+
+        let packages_is_dir = match io.metadata("Packages".as_ref()).await {
+            Ok(metadata) if metadata.is_dir() => true,
+            Ok(_) => {
+                log::error!("Packages is not a directory");
+                diagnostics.push(ProjectDiagnostic::PackagesIsFile);
+                false
+            }
             Err(ref e) if e.kind() == io::ErrorKind::NotFound => {
                 log::error!("Packages directory not found");
+                diagnostics.push(ProjectDiagnostic::PackagesFolderMissing);
+                false
             }
-            Err(e) => {
-                return Err(e);
-            }
-            Ok(mut dir_reading) => {
-                while let Some(dir_entry) = dir_reading.try_next().await? {
-                    if !dir_entry.file_type().await?.is_dir() {
-                        continue;
+            Err(e) => return Err(e),
+        };
+
+        let manifest = if !packages_is_dir {
+            VpmManifest::empty()
+        } else {
+            match VpmManifest::load(&io).await {
+                Ok(manifest) => {
+                    if !io.is_file(VPM_MANIFEST_PATH.as_ref()).await {
+                        diagnostics.push(ProjectDiagnostic::VpmManifestMissing);
                     }
-                    let read = Self::try_read_unlocked_package(&io, dir_entry).await;
-                    let mut is_installed = false;
-                    if let Some(parsed) = &read.1 {
-                        if parsed.name() == read.0.as_ref()
-                            && manifest.get_locked(parsed.name()).is_some()
-                        {
-                            is_installed = true;
-                        }
+                    manifest
+                }
+                Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                    log::error!("{e}");
+                    diagnostics.push(ProjectDiagnostic::VpmManifestInvalid {
+                        message: e.to_string().into(),
+                    });
+                    VpmManifest::empty()
+                }
+                Err(e) => return Err(e),
+            }
+        };
+
+        let manifest_snapshot = if packages_is_dir {
+            project_lock::read_manifest_snapshot(&io).await?
+        } else {
+            None
+        };
+
+        for name in manifest.invalid_package_names() {
+            log::error!("Packages/vpm-manifest.json has invalid package name {name:?}");
+            diagnostics.push(ProjectDiagnostic::InvalidPackageName { name: name.into() });
+        }
+
+        let upm_manifest = if !packages_is_dir {
+            UpmManifest::empty()
+        } else {
+            match UpmManifest::load(&io).await {
+                Ok(manifest) => manifest,
+                Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                    log::error!("{e}");
+                    diagnostics.push(ProjectDiagnostic::UpmManifestInvalid {
+                        message: e.to_string().into(),
+                    });
+                    UpmManifest::empty()
+                }
+                Err(e) => return Err(e),
+            }
+        };
+
+        let settings = if !packages_is_dir {
+            ProjectSettings::empty()
+        } else {
+            match ProjectSettings::load(&io).await {
+                Ok(settings) => settings,
+                Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                    log::error!("{e}");
+                    ProjectSettings::empty()
+                }
+                Err(e) => return Err(e),
+            }
+        };
+
+        let mut installed_packages = HashMap::new();
+        let mut unlocked_packages = vec![];
+
+        if packages_is_dir {
+            let mut dir_reading = io.read_dir("Packages".as_ref()).await?;
+            while let Some(dir_entry) = dir_reading.try_next().await? {
+                let file_type = dir_entry.file_type().await?;
+                let is_dir = if file_type.is_symlink() {
+                    // packages linked to the package store
+                    let path = Path::new("Packages").join(dir_entry.file_name());
+                    io.is_dir(&path).await
+                } else {
+                    file_type.is_dir()
+                };
+                if !is_dir {
+                    continue;
+                }
+                let read = Self::try_read_unlocked_package(&io, dir_entry).await;
+                let mut is_installed = false;
+                if let Some(parsed) = &read.1 {
+                    if parsed.name() == read.0.as_ref()
+                        && manifest.get_locked(parsed.name()).is_some()
+                    {
+                        is_installed = true;
                     }
-                    if is_installed {
-                        installed_packages.insert(read.0, read.1.unwrap());
-                    } else {
-                        unlocked_packages.push(read);
+                }
+                if is_installed {
+                    installed_packages.insert(read.0, read.1.unwrap());
+                } else {
+                    if manifest.get_locked(&read.0).is_some() {
+                        log::warn!("Packages/{} is locked but not a VPM package", read.0);
+                        diagnostics.push(ProjectDiagnostic::NonVpmPackage {
+                            name: read.0.clone(),
+                        });
                     }
+                    unlocked_packages.push(read);
                 }
             }
         }
 
-        let (unity_version, unity_revision) = Self::try_read_unity_version(&io).await;
+        let project_version = Self::read_project_version(&io).await;
+        if let Err(e) = &project_version {
+            log::error!("{e}");
+        }
 
         Ok(Self {
             io,
             manifest,
+            manifest_snapshot,
             upm_manifest,
-            unity_version,
-            unity_revision,
+            settings,
+            project_version,
+            diagnostics,
+            ignore_unity_lock: false,
+            allow_incompatible_sdk: false,
+            lock_timeout: project_lock::DEFAULT_LOCK_TIMEOUT,
+            held_lock: Mutex::new(Weak::new()),
+            ignore_project_lock: false,
             unlocked_packages,
             installed_packages,
         })
@@ -123,73 +275,60 @@ impl<IO: ProjectIo> UnityProject<IO> {
         (name, parsed.map(|x| x.0))
     }
 
-    async fn try_read_unity_version(io: &IO) -> (Option<UnityVersion>, Option<String>) {
-        let mut project_version_file =
-            match io.open("ProjectSettings/ProjectVersion.txt".as_ref()).await {
-                Ok(file) => file,
-                Err(ref e) if e.kind() == io::ErrorKind::NotFound => {
-                    log::error!("ProjectVersion.txt not found");
-                    return (None, None);
-                }
-                Err(e) => {
-                    log::error!("opening ProjectVersion.txt failed with error: {e}");
-                    return (None, None);
-                }
-            };
+    /// Rewrites `m_EditorVersion` in `ProjectSettings/ProjectVersion.txt`.
+    ///
+    /// Since we don't know the revision of the new version, `m_EditorVersionWithRevision` is removed.
+    /// Unity will write it again when the project is opened.
+    pub(crate) async fn update_unity_version(&mut self, version: UnityVersion) -> io::Result<()> {
+        let path: &Path = PROJECT_VERSION_PATH.as_ref();
 
         let mut buffer = String::new();
+        match self.io.open(path).await {
+            Ok(mut file) => {
+                file.read_to_string(&mut buffer).await?;
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
 
-        if let Err(e) = project_version_file.read_to_string(&mut buffer).await {
-            log::error!("reading ProjectVersion.txt failed with error: {e}");
-            return (None, None);
+        let eol = if buffer.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
         };
+        let mut updated = String::with_capacity(buffer.len());
+        let mut written = false;
 
-        let unity_version = match Self::find_attribute(buffer.as_str(), "m_EditorVersion:") {
-            None => None,
-            Some(version_info) => {
-                let parsed = UnityVersion::parse(version_info);
-                if parsed.is_none() {
-                    log::error!("failed to parse m_EditorVersion in ProjectVersion.txt");
-                }
-                parsed
+        for line in buffer.lines() {
+            if line.starts_with("m_EditorVersionWithRevision:") {
+                continue;
             }
-        };
-
-        let revision = match Self::find_attribute(buffer.as_str(), "m_EditorVersionWithRevision:") {
-            None => None,
-            Some(version_info) => {
-                let parsed = Self::parse_version_with_revision(version_info);
-                if parsed.is_none() {
-                    log::error!(
-                        "failed to parse m_EditorVersionWithRevision in ProjectVersion.txt"
-                    );
+            if line.starts_with("m_EditorVersion:") {
+                if written {
+                    continue;
                 }
-                parsed
+                updated.push_str(&format!("m_EditorVersion: {version}"));
+                written = true;
+            } else {
+                updated.push_str(line);
             }
-        };
+            updated.push_str(eol);
+        }
 
-        (unity_version, revision.map(|x| x.to_string()))
-    }
+        if !written {
+            updated.push_str(&format!("m_EditorVersion: {version}{eol}"));
+        }
 
-    fn find_attribute<'a>(buffer: &'a str, attribute: &str) -> Option<&'a str> {
-        let (_, version_info) = buffer.split_once(attribute)?;
-        let version_info_end = version_info
-            .find(['\r', '\n'])
-            .unwrap_or(version_info.len());
-        let version_info = &version_info[..version_info_end];
-        let version_info = version_info.trim();
-        Some(version_info)
-    }
+        self.io.create_dir_all("ProjectSettings".as_ref()).await?;
+        self.io.write_sync(path, updated.as_bytes()).await?;
 
-    fn parse_version_with_revision(version_info: &str) -> Option<&str> {
-        let (_version, revision) = version_info.split_once('(')?;
-        let (revision, _) = revision.split_once(')')?;
+        self.project_version = Ok(ProjectVersion::new(version, None));
 
-        Some(revision)
+        Ok(())
     }
 
     pub async fn is_valid(&self) -> bool {
-        self.unity_version.is_some()
+        self.project_version.is_ok()
     }
 
     pub fn io(&self) -> &IO {
@@ -197,11 +336,21 @@ impl<IO: ProjectIo> UnityProject<IO> {
     }
 
     pub async fn save(&mut self) -> io::Result<()> {
-        try_join(
+        let lock = self.lock().await?;
+        let result = self.save_locked().await;
+        self.unlock(lock).await;
+        result
+    }
+
+    async fn save_locked(&mut self) -> io::Result<()> {
+        self.check_saveable()?;
+        try_join3(
             self.manifest.save(&self.io),
             self.upm_manifest.save(&self.io),
+            self.settings.save(&self.io),
         )
         .await?;
+        self.manifest_snapshot = project_lock::read_manifest_snapshot(&self.io).await?;
         Ok(())
     }
 }
@@ -258,17 +407,78 @@ impl<IO: ProjectIo> UnityProject<IO> {
         )
     }
 
+    /// The parsed `ProjectSettings/ProjectVersion.txt`, or why it's not available.
+    pub fn project_version(&self) -> Result<&ProjectVersion, &ProjectVersionError> {
+        self.project_version.as_ref()
+    }
+
     pub fn unity_version(&self) -> Option<UnityVersion> {
-        self.unity_version
+        self.project_version
+            .as_ref()
+            .ok()
+            .map(ProjectVersion::version)
     }
 
+    /// The changeset of the Unity editor the project was last saved with, if known.
     pub fn unity_revision(&self) -> Option<&str> {
-        self.unity_revision.as_deref()
+        self.project_version
+            .as_ref()
+            .ok()
+            .and_then(ProjectVersion::revision)
     }
 
     pub fn has_upm_package(&self, name: &str) -> bool {
         self.upm_manifest.get_dependency(name).is_some()
     }
+
+    /// The paths never removed as legacy assets in addition to the built-in ones.
+    ///
+    /// The folders are protected including their contents.
+    pub fn protected_paths(&self) -> &[Box<str>] {
+        self.settings.protected_paths()
+    }
+}
+
+// settings
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Adds the path relative to the project never removed as legacy assets.
+    ///
+    /// Returns false if the path is already protected. Call [`save`](Self::save) to persist the change.
+    pub fn add_protected_path(&mut self, path: &str) -> bool {
+        self.settings.add_protected_path(path)
+    }
+
+    /// Removes the path from [`protected_paths`](Self::protected_paths).
+    ///
+    /// Returns false if the path is not protected. Call [`save`](Self::save) to persist the change.
+    pub fn remove_protected_path(&mut self, path: &str) -> bool {
+        self.settings.remove_protected_path(path)
+    }
+
+    /// Whether the user opted out of `com.vrchat.core.vpm-resolver` for this project.
+    ///
+    /// If true, the resolver is never added automatically, and not reported as missing.
+    pub fn is_vpm_resolver_excluded(&self) -> bool {
+        self.settings.exclude_vpm_resolver()
+    }
+
+    /// Sets whether `com.vrchat.core.vpm-resolver` is excluded from this project.
+    ///
+    /// Call [`save`](Self::save) to persist the change.
+    pub fn set_vpm_resolver_excluded(&mut self, excluded: bool) {
+        self.settings.set_exclude_vpm_resolver(excluded)
+    }
+}
+
+/// Returns true if the unlocked package in `Packages/{dir_name}` is the package `name`.
+///
+/// Installing `name` with VPM removes the folder.
+pub(crate) fn is_unlocked_package_of(
+    dir_name: &str,
+    manifest: Option<&PackageManifest>,
+    name: &str,
+) -> bool {
+    dir_name == name || manifest.is_some_and(|x| x.name() == name)
 }
 
 impl<IO: FileSystemProjectIo + ProjectIo> UnityProject<IO> {
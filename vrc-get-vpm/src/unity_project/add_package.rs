@@ -1,20 +1,43 @@
+use crate::error::{ErrorCode, ErrorParam};
 use crate::io::ProjectIo;
 use crate::unity_project::package_resolution::MissingDependencies;
 use crate::unity_project::pending_project_changes::RemoveReason;
 use crate::unity_project::vpm_manifest::VpmManifest;
-use crate::unity_project::{PendingProjectChanges, package_resolution};
-use crate::version::DependencyRange;
-use crate::{PackageCollection, PackageInfo, UnityProject};
+use crate::unity_project::{PendingProjectChanges, is_unlocked_package_of, package_resolution};
+use crate::version::{DependencyRange, UnityVersion, Version};
+use crate::{PackageCollection, PackageInfo, PackageInfoSnapshot, UnityProject, unity_compatible};
+use indexmap::IndexMap;
 use log::debug;
 use std::fmt;
 
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum AddPackageErr {
-    DependenciesNotFound { dependencies: Vec<Box<str>> },
-    UpgradingNonLockedPackage { package_name: Box<str> },
-    DowngradingNonLockedPackage { package_name: Box<str> },
-    UpgradingWithDowngrade { package_name: Box<str> },
+    DependenciesNotFound {
+        dependencies: Vec<Box<str>>,
+    },
+    UpgradingNonLockedPackage {
+        package_name: Box<str>,
+    },
+    DowngradingNonLockedPackage {
+        package_name: Box<str>,
+    },
+    UpgradingWithDowngrade {
+        package_name: Box<str>,
+    },
+    /// The requested version of VRChat SDK is not compatible with the Unity version of the project.
+    ///
+    /// Use [`UnityProject::set_allow_incompatible_sdk`] to upgrade anyway.
+    IncompatibleSdkUnity {
+        package_name: Box<str>,
+        package_version: Version,
+        unity_version: UnityVersion,
+    },
+    /// The package of the [`PackageInfoSnapshot`] is no longer in the collection.
+    PackageNotFound {
+        package_name: Box<str>,
+        package_version: Version,
+    },
 }
 
 impl fmt::Display for AddPackageErr {
@@ -44,12 +67,67 @@ impl fmt::Display for AddPackageErr {
                 f,
                 "Package {package_name} is locked, so it cannot be downgraded"
             ),
+            AddPackageErr::IncompatibleSdkUnity {
+                package_name,
+                package_version,
+                unity_version,
+            } => write!(
+                f,
+                "{package_name}@{package_version} is not compatible with Unity {unity_version} used by the project. \
+                Please migrate the project to newer Unity first"
+            ),
+            AddPackageErr::PackageNotFound {
+                package_name,
+                package_version,
+            } => write!(f, "{package_name}@{package_version} is not found"),
         }
     }
 }
 
 impl std::error::Error for AddPackageErr {}
 
+impl ErrorCode for AddPackageErr {
+    fn code(&self) -> &'static str {
+        match self {
+            AddPackageErr::DependenciesNotFound { .. } => "package.dependency-not-found",
+            AddPackageErr::UpgradingNonLockedPackage { .. } => "package.upgrade-not-locked",
+            AddPackageErr::DowngradingNonLockedPackage { .. } => "package.downgrade-not-locked",
+            AddPackageErr::UpgradingWithDowngrade { .. } => "package.upgrade-with-downgrade",
+            AddPackageErr::IncompatibleSdkUnity { .. } => "package.incompatible-sdk-unity",
+            AddPackageErr::PackageNotFound { .. } => "package.not-found",
+        }
+    }
+
+    fn params(&self) -> IndexMap<&'static str, ErrorParam> {
+        match self {
+            AddPackageErr::DependenciesNotFound { dependencies } => {
+                IndexMap::from([("dependencies", ErrorParam::list(dependencies))])
+            }
+            AddPackageErr::UpgradingNonLockedPackage { package_name }
+            | AddPackageErr::DowngradingNonLockedPackage { package_name }
+            | AddPackageErr::UpgradingWithDowngrade { package_name } => {
+                IndexMap::from([("package_name", ErrorParam::text(package_name))])
+            }
+            AddPackageErr::IncompatibleSdkUnity {
+                package_name,
+                package_version,
+                unity_version,
+            } => IndexMap::from([
+                ("package_name", ErrorParam::text(package_name)),
+                ("package_version", ErrorParam::text(package_version)),
+                ("unity_version", ErrorParam::text(unity_version)),
+            ]),
+            AddPackageErr::PackageNotFound {
+                package_name,
+                package_version,
+            } => IndexMap::from([
+                ("package_name", ErrorParam::text(package_name)),
+                ("package_version", ErrorParam::text(package_version)),
+            ]),
+        }
+    }
+}
+
 #[non_exhaustive]
 #[derive(Debug)]
 pub enum AddPackageOperation {
@@ -59,17 +137,97 @@ pub enum AddPackageOperation {
     AutoDetected,
 }
 
+/// The VRChat SDK packages, which are upgraded together with the Unity version.
+pub(crate) const SDK_PACKAGES: &[&str] =
+    &["com.vrchat.base", "com.vrchat.avatars", "com.vrchat.worlds"];
+
 // adding package
 impl<IO: ProjectIo> UnityProject<IO> {
+    /// Allows upgrading VRChat SDK to versions not compatible with the Unity version of the project.
+    ///
+    /// This is for upgrading SDK just before migrating the project to newer Unity.
+    pub fn set_allow_incompatible_sdk(&mut self, allow: bool) {
+        self.allow_incompatible_sdk = allow;
+    }
+
+    fn check_sdk_compatible(
+        &self,
+        request: PackageInfo,
+        unity_version: Option<UnityVersion>,
+    ) -> Result<(), AddPackageErr> {
+        if self.allow_incompatible_sdk || !SDK_PACKAGES.contains(&request.name()) {
+            return Ok(());
+        }
+        let Some(unity_version) = unity_version else {
+            return Ok(());
+        };
+        if unity_compatible(request.package_json(), unity_version) {
+            return Ok(());
+        }
+        Err(AddPackageErr::IncompatibleSdkUnity {
+            package_name: request.name().into(),
+            package_version: request.version().clone(),
+            unity_version,
+        })
+    }
+
     /// Creates a new `AddPackageRequest` to add the specified packages.
     ///
     /// You should call `apply_pending_changes` to apply the changes after confirming to the user.
+    ///
+    /// The error is [`AddPackageErr`] in [`Error::PackageResolution`](crate::Error::PackageResolution).
     pub async fn add_package_request<'env>(
         &self,
-        env: &'env impl PackageCollection,
+        env: &'env (impl PackageCollection + ?Sized),
+        packages: &[PackageInfo<'env>],
+        operation: AddPackageOperation,
+        allow_prerelease: bool,
+    ) -> Result<PendingProjectChanges<'env>, crate::Error> {
+        Ok(self
+            .add_package_request_for_unity(
+                env,
+                packages,
+                operation,
+                allow_prerelease,
+                self.unity_version(),
+            )
+            .await?)
+    }
+
+    /// Same as [`add_package_request`](Self::add_package_request) but with the snapshots of the packages.
+    ///
+    /// The packages are resolved with [`PackageCollection::resolve_snapshot`],
+    /// and [`AddPackageErr::PackageNotFound`] is returned if the package is no longer in `env`.
+    pub async fn add_package_request_from_snapshots<'env>(
+        &self,
+        env: &'env (impl PackageCollection + ?Sized),
+        packages: &[PackageInfoSnapshot],
+        operation: AddPackageOperation,
+        allow_prerelease: bool,
+    ) -> Result<PendingProjectChanges<'env>, crate::Error> {
+        let packages = packages
+            .iter()
+            .map(|snapshot| {
+                env.resolve_snapshot(snapshot)
+                    .ok_or_else(|| AddPackageErr::PackageNotFound {
+                        package_name: snapshot.name().into(),
+                        package_version: snapshot.version().clone(),
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.add_package_request(env, &packages, operation, allow_prerelease)
+            .await
+    }
+
+    /// Same as `add_package_request` but selects packages for the specified Unity version
+    /// instead of the current version of the project.
+    pub(crate) async fn add_package_request_for_unity<'env>(
+        &self,
+        env: &'env (impl PackageCollection + ?Sized),
         packages: &[PackageInfo<'env>],
         operation: AddPackageOperation,
         allow_prerelease: bool,
+        unity_version: Option<UnityVersion>,
     ) -> Result<PendingProjectChanges<'env>, AddPackageErr> {
         // if same or newer requested package is in locked dependencies,
         // just add requested version into dependencies
@@ -165,6 +323,7 @@ impl<IO: ProjectIo> UnityProject<IO> {
                             });
                         }
 
+                        self.check_sdk_compatible(request, unity_version)?;
                         upgrade_locked(request, self, &mut adding_packages, &mut changes)?;
                     }
                     AddPackageOperation::Downgrade => {
@@ -208,6 +367,7 @@ impl<IO: ProjectIo> UnityProject<IO> {
                                         request.name(),
                                         request.version()
                                     );
+                                    self.check_sdk_compatible(request, unity_version)?;
                                     upgrade_locked(
                                         request,
                                         self,
@@ -288,7 +448,7 @@ impl<IO: ProjectIo> UnityProject<IO> {
             self.manifest.all_locked(),
             self.unlocked_packages.iter(),
             |pkg| self.manifest.get_locked(pkg),
-            self.unity_version(),
+            unity_version,
             env,
             adding_packages,
             allow_prerelease,
@@ -307,11 +467,7 @@ impl<IO: ProjectIo> UnityProject<IO> {
             changes.install_to_locked(pkg);
 
             for (dir, _) in self.unlocked_packages.iter().filter(|(dir, unlocked)| {
-                dir.as_ref() == pkg.name()
-                    || unlocked
-                        .as_ref()
-                        .map(|x| x.name() == pkg.name())
-                        .unwrap_or(false)
+                is_unlocked_package_of(dir, unlocked.as_ref(), pkg.name())
             }) {
                 changes.unlocked_installation_conflict(pkg.name().into(), dir.clone());
             }
@@ -333,7 +489,7 @@ impl<IO: ProjectIo> UnityProject<IO> {
 
         debug!("Building changes (finding legacy assets, checking conflicts)");
 
-        let changes = changes.build_resolve(self).await;
+        let changes = changes.build_resolve_for_unity(self, unity_version).await;
 
         debug!("Resolving finished");
 
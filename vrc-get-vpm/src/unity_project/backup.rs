@@ -0,0 +1,274 @@
+use crate::io::{DirEntry, IoTrait, ProjectIo};
+use crate::unity_project::project_lock::PROJECT_LOCK_PATH;
+use crate::utils::MapResultExt;
+use crate::{AbortCheck, UnityProject, io};
+use async_zip::base::write::ZipFileWriter;
+use async_zip::{Compression, DeflateOption, ZipEntryBuilder};
+use futures::prelude::*;
+use log::warn;
+use std::path::PathBuf;
+
+/// The paths excluded from backups by default.
+///
+/// Those folders are generated by Unity or IDEs and will be regenerated when opening the project.
+/// The project lock of vrc-get is also excluded since it's held only while modifying the project.
+pub const DEFAULT_BACKUP_EXCLUDES: &[&str] =
+    &["Library", "Temp", "obj", "Logs", ".vs", PROJECT_LOCK_PATH];
+
+/// The compression level of the backup archive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum BackupCompression {
+    /// No compression. This is the fastest.
+    #[default]
+    Stored,
+    /// Deflate with the fastest compression level.
+    Fast,
+    /// Deflate with the best compression level.
+    Best,
+}
+
+/// How to handle symbolic links (and junctions on windows) in the project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum BackupSymlinks {
+    /// Symbolic links are not included in the backup.
+    #[default]
+    Skip,
+    /// The contents of the symbolic links are stored in the backup.
+    ///
+    /// Symbolic links inside the linked folders are skipped to avoid infinite loops.
+    StoreContents,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BackupOptions {
+    includes: Vec<Box<str>>,
+    excludes: Vec<Box<str>>,
+    compression: BackupCompression,
+    symlinks: BackupSymlinks,
+}
+
+impl BackupOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores only the included paths and their contents.
+    ///
+    /// The path is relative to the project root and compared case-insensitively.
+    /// If no path is included, the whole project is stored.
+    pub fn include(mut self, path: impl Into<Box<str>>) -> Self {
+        self.includes.push(path.into());
+        self
+    }
+
+    /// Excludes the path from the backup in addition to [`DEFAULT_BACKUP_EXCLUDES`].
+    ///
+    /// The path is relative to the project root and compared case-insensitively.
+    pub fn exclude(mut self, path: impl Into<Box<str>>) -> Self {
+        self.excludes.push(path.into());
+        self
+    }
+
+    pub fn compression(mut self, compression: BackupCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    pub fn symlinks(mut self, symlinks: BackupSymlinks) -> Self {
+        self.symlinks = symlinks;
+        self
+    }
+
+    /// Returns true if `relative` is included or a parent of included paths.
+    fn is_on_included_path(&self, relative: &str) -> bool {
+        if self.includes.is_empty() {
+            return true;
+        }
+
+        let relative = relative.to_ascii_lowercase();
+        self.includes.iter().any(|include| {
+            let include = normalize(include).to_ascii_lowercase();
+            let is_parent = |parent: &str, child: &str| {
+                child
+                    .strip_prefix(parent)
+                    .is_some_and(|x| x.starts_with('/'))
+            };
+            include == relative || is_parent(&include, &relative) || is_parent(&relative, &include)
+        })
+    }
+
+    fn is_excluded(&self, relative: &str) -> bool {
+        DEFAULT_BACKUP_EXCLUDES
+            .iter()
+            .copied()
+            .chain(self.excludes.iter().map(AsRef::as_ref))
+            .any(|exclude| normalize(exclude).eq_ignore_ascii_case(relative))
+    }
+}
+
+fn normalize(path: &str) -> String {
+    path.replace('\\', "/").trim_matches('/').to_owned()
+}
+
+/// The progress of creating a backup.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct BackupProgress<'a> {
+    pub total_files: usize,
+    pub proceed_files: usize,
+    pub total_bytes: u64,
+    pub proceed_bytes: u64,
+    pub last_proceed: &'a str,
+}
+
+struct BackupEntry {
+    path: PathBuf,
+    // relative path with '/' separator. ends with '/' for directories
+    name: String,
+    len: u64,
+}
+
+impl BackupEntry {
+    fn is_dir(&self) -> bool {
+        self.name.ends_with('/')
+    }
+}
+
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Writes the zip archive of the project to `writer`.
+    ///
+    /// Returns the number of entries in the archive.
+    pub async fn create_backup_archive(
+        &self,
+        writer: impl AsyncWrite + Unpin,
+        options: &BackupOptions,
+        abort: &AbortCheck,
+        mut on_progress: impl FnMut(BackupProgress<'_>),
+    ) -> io::Result<usize> {
+        let entries = collect_entries(&self.io, options, abort).await?;
+
+        let total_files = entries.len();
+        let total_bytes = entries.iter().map(|x| x.len).sum();
+        let mut proceed_bytes = 0;
+
+        let (compression, deflate_option) = match options.compression {
+            BackupCompression::Stored => (Compression::Stored, DeflateOption::Normal),
+            BackupCompression::Fast => (Compression::Deflate, DeflateOption::Other(1)),
+            BackupCompression::Best => (Compression::Deflate, DeflateOption::Other(9)),
+        };
+
+        on_progress(BackupProgress {
+            total_files,
+            proceed_files: 0,
+            total_bytes,
+            proceed_bytes,
+            last_proceed: "",
+        });
+
+        let mut writer = ZipFileWriter::new(writer);
+
+        for (index, entry) in entries.iter().enumerate() {
+            abort.check()?;
+
+            if entry.is_dir() {
+                let builder = ZipEntryBuilder::new(entry.name.clone().into(), Compression::Stored);
+                writer.write_entry_whole(builder, b"").await.err_mapped()?;
+            } else {
+                // stream the file not to load large assets into the memory
+                let mut file = self.io.open(&entry.path).await?;
+                let builder = ZipEntryBuilder::new(entry.name.clone().into(), compression)
+                    .deflate_option(deflate_option);
+                let mut entry_writer = writer.write_entry_stream(builder).await.err_mapped()?;
+                proceed_bytes += io::copy(&mut file, &mut entry_writer).await?;
+                entry_writer.close().await.err_mapped()?;
+            }
+
+            on_progress(BackupProgress {
+                total_files,
+                proceed_files: index + 1,
+                total_bytes,
+                proceed_bytes,
+                last_proceed: &entry.name,
+            });
+        }
+
+        let mut writer = writer.close().await.err_mapped()?;
+        writer.flush().await?;
+
+        Ok(total_files)
+    }
+}
+
+async fn collect_entries(
+    io: &impl IoTrait,
+    options: &BackupOptions,
+    abort: &AbortCheck,
+) -> io::Result<Vec<BackupEntry>> {
+    let mut result = Vec::new();
+    // (path, name prefix, inside symlink)
+    let mut queue = vec![(PathBuf::new(), String::new(), false)];
+
+    while let Some((dir, prefix, in_symlink)) = queue.pop() {
+        abort.check()?;
+
+        let mut iter = io.read_dir(&dir).await?;
+        let mut children = Vec::new();
+        while let Some(entry) = iter.try_next().await? {
+            let Ok(file_name) = entry.file_name().into_string() else {
+                warn!("skipping non-utf8 file name in {}", dir.display());
+                continue;
+            };
+            let path = dir.join(&file_name);
+            let relative = format!("{prefix}{file_name}");
+
+            if options.is_excluded(&relative) || !options.is_on_included_path(&relative) {
+                continue;
+            }
+
+            let mut file_type = entry.file_type().await?;
+            let mut len = None;
+            let mut is_symlink = in_symlink;
+
+            if file_type.is_symlink() {
+                match options.symlinks {
+                    BackupSymlinks::StoreContents if !in_symlink => {
+                        let metadata = io.metadata(&path).await?;
+                        file_type = metadata.file_type();
+                        len = Some(metadata.len());
+                        is_symlink = true;
+                    }
+                    _ => {
+                        warn!("skipping symlink: {relative}");
+                        continue;
+                    }
+                }
+            }
+
+            if file_type.is_dir() {
+                children.push((path.clone(), format!("{relative}/"), is_symlink));
+                result.push(BackupEntry {
+                    path,
+                    name: format!("{relative}/"),
+                    len: 0,
+                });
+            } else {
+                let len = match len {
+                    Some(len) => len,
+                    None => entry.metadata().await?.len(),
+                };
+                result.push(BackupEntry {
+                    path,
+                    name: relative,
+                    len,
+                });
+            }
+        }
+
+        // reverse to process children in order
+        queue.extend(children.into_iter().rev());
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,206 @@
+use crate::io::ProjectIo;
+use crate::unity_project::PendingProjectChanges;
+use crate::unity_project::pending_project_changes::{PackageChange, RemoveReason, UpmChange};
+use crate::version::{UnityVersion, Version};
+use crate::{PackageInstaller, UnityProject, io};
+use futures::future::BoxFuture;
+use serde::Serialize;
+use std::path::Path;
+
+/// How the changes are destructive, returned by [`PendingProjectChanges::classify`].
+///
+/// This is the single definition of "destructive" shared by the frontends.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ChangeClassification {
+    /// The changes remove packages, unlocked packages, or dependencies in `Packages/manifest.json`.
+    pub removals: bool,
+    /// The changes remove legacy assets.
+    pub legacy_deletions: bool,
+    /// The changes install older version of the package than the locked or installed one.
+    pub downgrades: bool,
+}
+
+impl ChangeClassification {
+    /// Returns true if the changes only add or upgrade packages.
+    pub fn is_additive(&self) -> bool {
+        !self.removals && !self.legacy_deletions && !self.downgrades
+    }
+}
+
+/// The package installed by the changes in [`ChangesReport`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct InstallReport {
+    pub name: Box<str>,
+    pub version: Version,
+    /// The version locked or installed before the changes
+    pub previous_version: Option<Version>,
+    pub is_yanked: bool,
+}
+
+/// The package removed by the changes in [`ChangesReport`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct RemovalReport {
+    pub name: Box<str>,
+    pub reason: RemoveReason,
+}
+
+/// The summary of [`PendingProjectChanges`] passed to [`ConfirmationHandler`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ChangesReport {
+    pub classification: ChangeClassification,
+    /// The packages installed, sorted by the name
+    pub installs: Vec<InstallReport>,
+    /// The packages removed, sorted by the name
+    pub removals: Vec<RemovalReport>,
+    /// The unlocked packages removed since they conflict with the installed packages
+    pub removed_unlocked_packages: Vec<Box<str>>,
+    pub removed_legacy_assets: Vec<Box<Path>>,
+    pub unity_version: Option<UnityVersion>,
+}
+
+/// The handler asked by [`ConfirmationPolicy::RequireCallback`].
+pub trait ConfirmationHandler: Sync {
+    /// Returns true to apply the changes, or false to cancel.
+    fn confirm<'a>(&'a self, report: &'a ChangesReport) -> BoxFuture<'a, bool>;
+}
+
+/// Whether to apply the changes, used with [`UnityProject::apply_pending_changes_with_confirmation`].
+#[derive(Clone, Copy)]
+pub enum ConfirmationPolicy<'a> {
+    /// Applies the changes without asking, like `--yes` of the CLI.
+    AlwaysProceed,
+    /// Applies the changes only if they are additive, and cancels otherwise without asking.
+    ProceedIfAdditive,
+    /// Asks the handler with the report of the changes.
+    RequireCallback(&'a dyn ConfirmationHandler),
+}
+
+impl ConfirmationPolicy<'_> {
+    /// Returns true if the changes to `project` can be applied with this policy.
+    ///
+    /// The handler is asked for [`RequireCallback`](Self::RequireCallback).
+    pub async fn allows(
+        &self,
+        project: &UnityProject<impl ProjectIo>,
+        changes: &PendingProjectChanges<'_>,
+    ) -> bool {
+        match self {
+            ConfirmationPolicy::AlwaysProceed => true,
+            ConfirmationPolicy::ProceedIfAdditive => changes.classify(project).is_additive(),
+            ConfirmationPolicy::RequireCallback(handler) => {
+                let report = changes.report(project);
+                handler.confirm(&report).await
+            }
+        }
+    }
+}
+
+impl PendingProjectChanges<'_> {
+    /// Classifies the changes to the project.
+    ///
+    /// The locked and installed packages in `project` are used to detect downgrades,
+    /// so `project` should be the project the changes are created for.
+    pub fn classify(&self, project: &UnityProject<impl ProjectIo>) -> ChangeClassification {
+        let removals = (self.package_changes.values()).any(|x| x.as_remove().is_some())
+            || (self.conflicts.values()).any(|x| !x.unlocked_names().is_empty())
+            || (self.upm_changes.iter()).any(|x| matches!(x, UpmChange::RemoveDependency { .. }));
+
+        let legacy_deletions =
+            !self.remove_legacy_files.is_empty() || !self.remove_legacy_folders.is_empty();
+
+        let downgrades = (self.package_changes.values())
+            .filter_map(|x| x.as_install()?.install_package())
+            .any(|package| {
+                previous_version(project, package.name())
+                    .is_some_and(|previous| package.version() < &previous)
+            });
+
+        ChangeClassification {
+            removals,
+            legacy_deletions,
+            downgrades,
+        }
+    }
+
+    /// Creates the serializable report of the changes to the project.
+    pub fn report(&self, project: &UnityProject<impl ProjectIo>) -> ChangesReport {
+        let mut installs = vec![];
+        let mut removals = vec![];
+
+        for (name, change) in &self.package_changes {
+            match change {
+                PackageChange::Install(install) => {
+                    if let Some(package) = install.install_package() {
+                        installs.push(InstallReport {
+                            name: name.clone(),
+                            version: package.version().clone(),
+                            previous_version: previous_version(project, name),
+                            is_yanked: package.is_yanked(),
+                        });
+                    }
+                }
+                PackageChange::Remove(remove) => {
+                    removals.push(RemovalReport {
+                        name: name.clone(),
+                        reason: remove.reason(),
+                    });
+                }
+            }
+        }
+
+        installs.sort_by(|a, b| a.name.cmp(&b.name));
+        removals.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut removed_unlocked_packages = (self.conflicts.values())
+            .flat_map(|x| x.unlocked_names().iter().cloned())
+            .collect::<Vec<_>>();
+        removed_unlocked_packages.sort();
+
+        let removed_legacy_assets = (self.remove_legacy_folders.iter())
+            .chain(&self.remove_legacy_files)
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        ChangesReport {
+            classification: self.classify(project),
+            installs,
+            removals,
+            removed_unlocked_packages,
+            removed_legacy_assets,
+            unity_version: self.unity_version,
+        }
+    }
+}
+
+fn previous_version(project: &UnityProject<impl ProjectIo>, name: &str) -> Option<Version> {
+    (project.get_locked(name).map(|x| x.version().clone()))
+        .or_else(|| (project.get_installed_package(name)).map(|x| x.version().clone()))
+}
+
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Applies the changes like [`apply_pending_changes`](Self::apply_pending_changes)
+    /// if the `policy` allows.
+    ///
+    /// Returns false without changing the project if the changes are cancelled.
+    pub async fn apply_pending_changes_with_confirmation<'env, Env: PackageInstaller>(
+        &mut self,
+        env: &'env Env,
+        changes: PendingProjectChanges<'env>,
+        policy: ConfirmationPolicy<'_>,
+    ) -> Result<bool, crate::Error> {
+        if !policy.allows(self, &changes).await {
+            return Ok(false);
+        }
+
+        self.apply_pending_changes(env, changes).await?;
+        Ok(true)
+    }
+}
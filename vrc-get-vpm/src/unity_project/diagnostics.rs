@@ -0,0 +1,128 @@
+use crate::io;
+use crate::io::ProjectIo;
+use crate::unity_project::{ProjectVersionError, UnityProject};
+use serde::Serialize;
+use std::fmt;
+
+/// The problem found in the project.
+///
+/// The project can be loaded with those problems, but some operations may not work.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+#[non_exhaustive]
+pub enum ProjectDiagnostic {
+    /// `Packages` folder does not exist.
+    PackagesFolderMissing,
+    /// `Packages` is a file instead of a folder. No packages can be installed.
+    PackagesIsFile,
+    /// `Packages/vpm-manifest.json` does not exist. This is normal for projects not using VPM yet.
+    VpmManifestMissing,
+    /// `Packages/vpm-manifest.json` cannot be parsed. The project is treated as having no VPM packages,
+    /// and changes to VPM packages are refused to avoid overwriting the manifest.
+    VpmManifestInvalid { message: Box<str> },
+    /// `Packages/manifest.json` cannot be parsed. Changes to UPM packages are refused.
+    UpmManifestInvalid { message: Box<str> },
+    /// `ProjectSettings/ProjectVersion.txt` does not exist. The Unity version is unknown.
+    ProjectVersionMissing,
+    /// `ProjectSettings/ProjectVersion.txt` exists but cannot be read. The Unity version is unknown.
+    ProjectVersionInvalid { message: Box<str> },
+    /// The package locked in `vpm-manifest.json` is installed but the folder is not a VPM package.
+    NonVpmPackage { name: Box<str> },
+    /// The package name in `vpm-manifest.json` cannot be used as a folder name, like `../evil`.
+    /// The package cannot be installed nor removed.
+    InvalidPackageName { name: Box<str> },
+}
+
+impl fmt::Display for ProjectDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProjectDiagnostic::PackagesFolderMissing => f.write_str("Packages folder not found"),
+            ProjectDiagnostic::PackagesIsFile => f.write_str("Packages is not a folder"),
+            ProjectDiagnostic::VpmManifestMissing => {
+                f.write_str("Packages/vpm-manifest.json not found")
+            }
+            ProjectDiagnostic::VpmManifestInvalid { message } => {
+                write!(f, "Packages/vpm-manifest.json is invalid: {message}")
+            }
+            ProjectDiagnostic::UpmManifestInvalid { message } => {
+                write!(f, "Packages/manifest.json is invalid: {message}")
+            }
+            ProjectDiagnostic::ProjectVersionMissing => {
+                f.write_str("ProjectSettings/ProjectVersion.txt not found")
+            }
+            ProjectDiagnostic::ProjectVersionInvalid { message } => f.write_str(message),
+            ProjectDiagnostic::NonVpmPackage { name } => {
+                write!(f, "Packages/{name} is locked but not a VPM package")
+            }
+            ProjectDiagnostic::InvalidPackageName { name } => {
+                write!(
+                    f,
+                    "Packages/vpm-manifest.json has invalid package name {name:?}"
+                )
+            }
+        }
+    }
+}
+
+impl ProjectDiagnostic {
+    fn blocks_vpm_manifest(&self) -> bool {
+        matches!(
+            self,
+            ProjectDiagnostic::PackagesIsFile | ProjectDiagnostic::VpmManifestInvalid { .. }
+        )
+    }
+
+    fn blocks_upm_manifest(&self) -> bool {
+        matches!(
+            self,
+            ProjectDiagnostic::PackagesIsFile | ProjectDiagnostic::UpmManifestInvalid { .. }
+        )
+    }
+
+    fn to_save_error(&self) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("cannot save project: {self}"),
+        )
+    }
+}
+
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Returns the problems found in the project.
+    pub fn diagnostics(&self) -> Vec<ProjectDiagnostic> {
+        let mut diagnostics = self.diagnostics.clone();
+
+        match &self.project_version {
+            Ok(_) => {}
+            Err(ProjectVersionError::NotFound) => {
+                diagnostics.push(ProjectDiagnostic::ProjectVersionMissing)
+            }
+            Err(e) => diagnostics.push(ProjectDiagnostic::ProjectVersionInvalid {
+                message: e.to_string().into(),
+            }),
+        }
+
+        diagnostics
+    }
+
+    /// Checks the manifests can be saved without losing the contents we could not parse.
+    pub(super) fn check_saveable(&self) -> io::Result<()> {
+        if self.manifest.is_changed() {
+            if let Some(diagnostic) = self.diagnostics.iter().find(|x| x.blocks_vpm_manifest()) {
+                return Err(diagnostic.to_save_error());
+            }
+        }
+
+        if self.upm_manifest.is_changed() {
+            if let Some(diagnostic) = self.diagnostics.iter().find(|x| x.blocks_upm_manifest()) {
+                return Err(diagnostic.to_save_error());
+            }
+        }
+
+        Ok(())
+    }
+}
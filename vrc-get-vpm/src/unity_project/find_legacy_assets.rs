@@ -6,16 +6,23 @@ use futures::StreamExt;
 use futures::prelude::*;
 use futures::stream::FuturesUnordered;
 use hex::FromHex;
-use log::debug;
+use log::{debug, warn};
 use std::collections::HashMap;
 use std::ffi::{OsStr, OsString};
 use std::path::{Path, PathBuf};
 use std::pin::pin;
 
+/// The paths never removed as legacy assets, including their contents.
+const PROTECTED_PATHS: &[&str] = &["Assets/Scenes"];
+/// The folders never removed as legacy assets, but their contents can be.
+const PROTECTED_FOLDERS: &[&str] = &["Assets", "Packages"];
+
 #[derive(Default)]
 pub(crate) struct LegacyAssets<'a> {
     pub(crate) files: Vec<(Box<Path>, &'a str)>,
     pub(crate) folders: Vec<(Box<Path>, &'a str)>,
+    /// legacy assets not removed because they're protected or outside Assets and Packages
+    pub(crate) protected: Vec<(Box<Path>, &'a str)>,
 }
 
 pub(crate) async fn collect_legacy_assets<'a>(
@@ -70,26 +77,111 @@ pub(crate) async fn collect_legacy_assets<'a>(
     // but the compiler fails so collect it here.
     let assets = folders.chain(files).collect::<Vec<_>>();
 
+    find_legacy_assets(io, assets, false, unity_project.protected_paths()).await
+}
+
+/// Collects legacy assets of `packages` whose GUID are confirmed by `.meta` files.
+///
+/// Unlike [`collect_legacy_assets`], this does not skip assets defined in installed packages,
+/// and never finds assets without GUID, so this can be used for half-migrated projects.
+pub(crate) async fn collect_legacy_assets_verified<'a>(
+    io: &impl ProjectIo,
+    packages: &[PackageInfo<'a>],
+    protected_paths: &[Box<str>],
+) -> LegacyAssets<'a> {
+    fn collect_legacy<'a>(
+        pkg: &PackageInfo<'a>,
+        get_assets: impl Fn(&'a PackageManifest) -> &'a HashMap<Box<str>, Option<Box<str>>>,
+        new_legacy_info: impl Fn(&'a str, &'a str, Option<Guid>) -> DefinedLegacyInfo<'a>,
+    ) -> Vec<DefinedLegacyInfo<'a>> {
+        get_assets(pkg.package_json())
+            .iter()
+            .filter_map(|(path, guid)| {
+                let guid = guid.as_deref().and_then(Guid::parse)?;
+                Some(new_legacy_info(pkg.name(), path, Some(guid)))
+            })
+            .collect()
+    }
+
+    let assets = packages
+        .iter()
+        .flat_map(|pkg| {
+            let folders = collect_legacy(
+                pkg,
+                PackageManifest::legacy_folders,
+                DefinedLegacyInfo::new_dir,
+            );
+            let files = collect_legacy(
+                pkg,
+                PackageManifest::legacy_files,
+                DefinedLegacyInfo::new_file,
+            );
+            folders.into_iter().chain(files)
+        })
+        .collect::<Vec<_>>();
+
+    find_legacy_assets(io, assets, true, protected_paths).await
+}
+
+async fn find_legacy_assets<'a>(
+    io: &impl ProjectIo,
+    assets: Vec<DefinedLegacyInfo<'a>>,
+    verify_guid: bool,
+    protected_paths: &[Box<str>],
+) -> LegacyAssets<'a> {
     if assets.is_empty() {
         debug!("There are no legacy assets");
         return LegacyAssets::default();
     }
 
     debug!("Collecting legacy assets by Path notation");
-    let (mut found_files, mut found_folders, find_guids) =
-        find_legacy_assets_by_path(io, assets.into_iter()).await;
+    let (mut found_files, mut found_folders, find_guids, mut protected) =
+        find_legacy_assets_by_path(io, assets.into_iter(), verify_guid).await;
 
     if !find_guids.is_empty() {
         debug!("Collecting legacy assets with GUID");
         find_legacy_assets_by_guid(io, find_guids, &mut found_files, &mut found_folders).await;
     }
 
+    let mut files = vec![];
+    let mut folders = vec![];
+
+    for (found, is_file) in (found_files.into_iter().map(|x| (x, true)))
+        .chain(found_folders.into_iter().map(|x| (x, false)))
+    {
+        if is_protected(&found.0, protected_paths) {
+            warn!(
+                "legacy asset {} of {} is protected so it will not be removed. please remove manually if needed",
+                found.0.display(),
+                found.1
+            );
+            protected.push(found);
+        } else if is_file {
+            files.push(found);
+        } else {
+            folders.push(found);
+        }
+    }
+
     LegacyAssets {
-        files: found_files.into_iter().collect(),
-        folders: found_folders.into_iter().collect(),
+        files,
+        folders,
+        protected,
     }
 }
 
+fn is_protected(path: &Path, protected_paths: &[Box<str>]) -> bool {
+    if PROTECTED_FOLDERS.iter().any(|x| path == Path::new(x)) {
+        return true;
+    }
+
+    // removing parent of the protected path also removes the protected path
+    (PROTECTED_PATHS.iter().copied())
+        .chain(protected_paths.iter().map(AsRef::as_ref))
+        .map(Path::new)
+        .any(|protected| path.starts_with(protected) || protected.starts_with(path))
+}
+
 fn valid_path(path: &Path) -> bool {
     // removing folders other than Assets and Packages are not allowed.
     if !path.starts_with("Assets") && !path.starts_with("Packages") {
@@ -109,10 +201,12 @@ fn valid_path(path: &Path) -> bool {
 async fn find_legacy_assets_by_path<'a>(
     io: &impl ProjectIo,
     assets: impl Iterator<Item = DefinedLegacyInfo<'a>>,
+    verify_guid: bool,
 ) -> (
     HashMap<Box<Path>, &'a str>,
     HashMap<Box<Path>, &'a str>,
     HashMap<Guid, (&'a str, bool)>,
+    Vec<(Box<Path>, &'a str)>,
 ) {
     use LegacySearchResult::*;
 
@@ -121,18 +215,26 @@ async fn find_legacy_assets_by_path<'a>(
             .map(|info| async move {
                 // some packages uses '/' as path separator.
                 let relative_path = PathBuf::from(info.path.replace('\\', "/")).into_boxed_path();
-                // for security, deny absolute path.
-                if relative_path.is_absolute() {
-                    return None;
+                // for security, deny absolute path and paths outside Assets and Packages.
+                if relative_path.is_absolute() || !valid_path(&relative_path) {
+                    return Some(InvalidPath(
+                        info.package_name,
+                        relative_path,
+                        info.guid,
+                        info.is_file,
+                    ));
                 }
                 #[allow(clippy::manual_map)] // it's parallel, not just a if-else
-                if valid_path(&relative_path)
-                    && io
-                        .metadata(&relative_path)
-                        .await
-                        .map(|x| x.is_file() == info.is_file)
-                        .unwrap_or(false)
-                    && check_guid(io, relative_path.as_ref(), info.guid).await
+                if io
+                    .metadata(&relative_path)
+                    .await
+                    .map(|x| x.is_file() == info.is_file)
+                    .unwrap_or(false)
+                    && if verify_guid {
+                        verify_meta_guid(io, relative_path.as_ref(), info.guid).await
+                    } else {
+                        check_guid(io, relative_path.as_ref(), info.guid).await
+                    }
                 {
                     Some(FoundWithPath(
                         info.package_name,
@@ -151,6 +253,7 @@ async fn find_legacy_assets_by_path<'a>(
     let mut found_files = HashMap::new();
     let mut found_folders = HashMap::new();
     let mut find_guids = HashMap::new();
+    let mut protected = vec![];
 
     while let Some(info) = futures.next().await {
         match info {
@@ -163,11 +266,22 @@ async fn find_legacy_assets_by_path<'a>(
             Some(SearchWithGuid(package_name, guid, is_file)) => {
                 find_guids.insert(guid, (package_name, is_file));
             }
+            Some(InvalidPath(package_name, relative_path, guid, is_file)) => {
+                warn!(
+                    "legacy asset {} of {package_name} is outside Assets and Packages so it will not be removed",
+                    relative_path.display(),
+                );
+                protected.push((relative_path, package_name));
+                // the asset might be moved to Assets, so search with GUID
+                if let Some(guid) = guid {
+                    find_guids.insert(guid, (package_name, is_file));
+                }
+            }
             None => (),
         }
     }
 
-    (found_files, found_folders, find_guids)
+    (found_files, found_folders, find_guids, protected)
 }
 
 async fn check_guid(io: &impl ProjectIo, path: &Path, guid: Option<Guid>) -> bool {
@@ -185,6 +299,15 @@ async fn check_guid(io: &impl ProjectIo, path: &Path, guid: Option<Guid>) -> boo
     true
 }
 
+async fn verify_meta_guid(io: &impl ProjectIo, path: &Path, guid: Option<Guid>) -> bool {
+    let Some(guid) = guid else {
+        return false;
+    };
+    let mut path = OsString::from(path);
+    path.push(".meta");
+    try_parse_meta(io, path.as_ref()).await == Some(guid)
+}
+
 async fn try_parse_meta(io: &impl ProjectIo, path: &Path) -> Option<Guid> {
     let mut file = BufReader::new(io.open(path).await.ok()?);
     let mut buffer = String::new();
@@ -268,6 +391,7 @@ impl<'a> DefinedLegacyInfo<'a> {
 enum LegacySearchResult<'a> {
     FoundWithPath(&'a str, Box<Path>, bool),
     SearchWithGuid(&'a str, Guid, bool),
+    InvalidPath(&'a str, Box<Path>, Option<Guid>, bool),
 }
 
 #[derive(Copy, Clone, Hash, Eq, PartialEq)]
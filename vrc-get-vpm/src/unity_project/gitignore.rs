@@ -0,0 +1,123 @@
+use crate::io::ProjectIo;
+use crate::{UnityProject, io};
+use std::fmt::Write;
+
+const GITIGNORE_PATH: &str = ".gitignore";
+const GITIGNORE_BACKUP_PATH: &str = ".gitignore.bak";
+
+/// The `.gitignore` for Unity projects for VRChat.
+///
+/// The VPM packages locked in the project are appended by [`UnityProject::write_default_gitignore`].
+pub const DEFAULT_GITIGNORE: &str = "\
+# Unity generated folders
+/[Ll]ibrary/
+/[Tt]emp/
+/[Oo]bj/
+/[Bb]uild/
+/[Bb]uilds/
+/[Ll]ogs/
+/[Uu]ser[Ss]ettings/
+/[Mm]emoryCaptures/
+/[Rr]ecordings/
+
+# IDE generated files
+.vs/
+.vscode/
+.idea/
+*.csproj
+*.unityproj
+*.sln
+*.suo
+*.tmp
+*.user
+*.userprefs
+*.pidb
+*.booproj
+*.svd
+*.pdb
+*.mdb
+*.opendb
+*.VC.db
+
+# Unity crash dumps and build artifacts
+sysinfo.txt
+crashlytics-build.properties
+*.apk
+*.aab
+*.unitypackage
+mono_crash.*
+
+# Backup of this file
+/.gitignore.bak
+";
+
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Writes the `.gitignore` for Unity projects for VRChat.
+    ///
+    /// The packages installed with VPM are ignored since they're restored by resolving packages.
+    /// Existing `.gitignore` is kept unless `overwrite` is true, in which case it's saved as `.gitignore.bak`.
+    ///
+    /// Returns false if `.gitignore` already exists and is not overwritten.
+    pub async fn write_default_gitignore(&self, overwrite: bool) -> io::Result<bool> {
+        let lock = self.lock().await?;
+        let result = self.write_default_gitignore_locked(overwrite).await;
+        self.unlock(lock).await;
+        result
+    }
+
+    async fn write_default_gitignore_locked(&self, overwrite: bool) -> io::Result<bool> {
+        if self.io.metadata(GITIGNORE_PATH.as_ref()).await.is_ok() {
+            if !overwrite {
+                return Ok(false);
+            }
+            self.io
+                .rename(GITIGNORE_PATH.as_ref(), GITIGNORE_BACKUP_PATH.as_ref())
+                .await?;
+        }
+
+        self.io
+            .write(GITIGNORE_PATH.as_ref(), self.default_gitignore().as_bytes())
+            .await?;
+
+        Ok(true)
+    }
+
+    fn default_gitignore(&self) -> String {
+        let mut gitignore = DEFAULT_GITIGNORE.to_owned();
+
+        let mut locked = self.locked_packages().map(|x| x.name()).collect::<Vec<_>>();
+        if !locked.is_empty() {
+            locked.sort();
+            gitignore.push_str("\n# VPM packages (restored by resolving packages)\n");
+            for name in locked {
+                writeln!(gitignore, "/Packages/{name}/").unwrap();
+            }
+        }
+
+        gitignore
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_gitignore_entries() {
+        let lines = DEFAULT_GITIGNORE.lines().collect::<Vec<_>>();
+        for entry in [
+            "/[Ll]ibrary/",
+            "/[Tt]emp/",
+            "/[Ll]ogs/",
+            "/[Oo]bj/",
+            "/[Uu]ser[Ss]ettings/",
+            "mono_crash.*",
+            "sysinfo.txt",
+        ] {
+            assert!(
+                lines.contains(&entry),
+                "{entry} is not in DEFAULT_GITIGNORE"
+            );
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use crate::error::{ErrorCode, ErrorParam};
+use crate::io::ProjectIo;
+use crate::unity_project::find_legacy_assets::collect_legacy_assets_verified;
+use crate::unity_project::pending_project_changes::Builder;
+use crate::unity_project::vpm_resolver::find_vpm_resolver;
+use crate::unity_project::{AddPackageErr, AddPackageOperation, PendingProjectChanges};
+use crate::{PackageCollection, ProjectType, UnityProject, VersionSelector, io};
+use indexmap::IndexMap;
+use log::info;
+use std::fmt;
+
+const WORLDS_SDK: &str = "com.vrchat.worlds";
+const AVATARS_SDK: &str = "com.vrchat.avatars";
+const BASE_SDK: &str = "com.vrchat.base";
+
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum MigrateLegacySdkError {
+    /// The project does not have legacy VRChat SDK in `Assets`.
+    NotLegacyProject,
+    /// The project has VRCSDK2, which has no VPM replacement.
+    Sdk2NotSupported,
+    VpmPackageNotFound(&'static str),
+    AddPackageErr(AddPackageErr),
+    Io(io::Error),
+}
+
+impl fmt::Display for MigrateLegacySdkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrateLegacySdkError::NotLegacyProject => {
+                f.write_str("the project does not have legacy VRChat SDK")
+            }
+            MigrateLegacySdkError::Sdk2NotSupported => {
+                f.write_str("VRCSDK2 projects cannot be migrated to VPM")
+            }
+            MigrateLegacySdkError::VpmPackageNotFound(name) => {
+                write!(f, "VPM package {name} not found")
+            }
+            MigrateLegacySdkError::AddPackageErr(err) => fmt::Display::fmt(err, f),
+            MigrateLegacySdkError::Io(err) => fmt::Display::fmt(err, f),
+        }
+    }
+}
+
+impl ErrorCode for MigrateLegacySdkError {
+    fn code(&self) -> &'static str {
+        match self {
+            MigrateLegacySdkError::NotLegacyProject => "migrate-legacy-sdk.not-legacy-project",
+            MigrateLegacySdkError::Sdk2NotSupported => "migrate-legacy-sdk.sdk2-not-supported",
+            MigrateLegacySdkError::VpmPackageNotFound(_) => "migrate-legacy-sdk.package-not-found",
+            MigrateLegacySdkError::AddPackageErr(err) => err.code(),
+            MigrateLegacySdkError::Io(err) => err.code(),
+        }
+    }
+
+    fn params(&self) -> IndexMap<&'static str, ErrorParam> {
+        match self {
+            MigrateLegacySdkError::NotLegacyProject | MigrateLegacySdkError::Sdk2NotSupported => {
+                IndexMap::new()
+            }
+            MigrateLegacySdkError::VpmPackageNotFound(name) => {
+                IndexMap::from([("package_name", ErrorParam::text(name))])
+            }
+            MigrateLegacySdkError::AddPackageErr(err) => err.params(),
+            MigrateLegacySdkError::Io(err) => err.params(),
+        }
+    }
+}
+
+impl std::error::Error for MigrateLegacySdkError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MigrateLegacySdkError::AddPackageErr(err) => Some(err),
+            MigrateLegacySdkError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<AddPackageErr> for MigrateLegacySdkError {
+    fn from(err: AddPackageErr) -> Self {
+        MigrateLegacySdkError::AddPackageErr(err)
+    }
+}
+
+impl From<io::Error> for MigrateLegacySdkError {
+    fn from(err: io::Error) -> Self {
+        MigrateLegacySdkError::Io(err)
+    }
+}
+
+type Result<T = (), E = MigrateLegacySdkError> = std::result::Result<T, E>;
+
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Creates the changes to migrate the project with legacy (unitypackage) VRChat SDK to VPM.
+    ///
+    /// The SDK package for the detected variant is added to `vpm-manifest.json` with
+    /// `com.vrchat.core.vpm-resolver` unless [excluded](Self::set_vpm_resolver_excluded),
+    /// and legacy SDK folders are removed by the `legacyFolders` of the packages.
+    ///
+    /// If the SDK package is already installed with VPM (half-migrated projects), this only removes
+    /// the legacy assets whose GUID match the ones defined in the packages.
+    ///
+    /// You should call `apply_pending_changes` to apply the changes after confirming to the user.
+    pub async fn migrate_legacy_sdk_request<'env>(
+        &self,
+        env: &'env (impl PackageCollection + ?Sized),
+    ) -> Result<PendingProjectChanges<'env>, crate::Error> {
+        Ok(self.migrate_legacy_sdk_request_impl(env).await?)
+    }
+
+    async fn migrate_legacy_sdk_request_impl<'env>(
+        &self,
+        env: &'env (impl PackageCollection + ?Sized),
+    ) -> Result<PendingProjectChanges<'env>> {
+        let sdk = self.detect_legacy_sdk().await?;
+
+        if !self.is_locked(sdk) && !self.is_locked(BASE_SDK) {
+            info!("Migrating legacy project to {sdk}");
+
+            let version_selector = VersionSelector::latest_for(self.unity_version(), false);
+            let package = env
+                .find_package_by_name(sdk, version_selector)
+                .ok_or(MigrateLegacySdkError::VpmPackageNotFound(sdk))?;
+            let mut packages = vec![package];
+            if self.is_vpm_resolver_missing_after(true) {
+                packages.extend(find_vpm_resolver(env, self.unity_version()));
+            }
+
+            return Ok(self
+                .add_package_request_for_unity(
+                    env,
+                    &packages,
+                    AddPackageOperation::InstallToDependencies,
+                    false,
+                    self.unity_version(),
+                )
+                .await?);
+        }
+
+        info!("{sdk} is already installed. removing legacy assets verified with GUID");
+
+        let mut packages = vec![];
+        for name in [sdk, BASE_SDK] {
+            let Some(locked) = self.get_locked(name) else {
+                continue;
+            };
+            let package = env
+                .find_package_by_name(name, VersionSelector::specific_version(locked.version()))
+                .or_else(|| {
+                    let selector = VersionSelector::latest_for(self.unity_version(), false);
+                    env.find_package_by_name(name, selector)
+                })
+                .ok_or(MigrateLegacySdkError::VpmPackageNotFound(name))?;
+            packages.push(package);
+        }
+
+        let legacy_assets =
+            collect_legacy_assets_verified(&self.io, &packages, self.protected_paths()).await;
+        Ok(Builder::new().build_remove_legacy_assets(legacy_assets))
+    }
+
+    async fn detect_legacy_sdk(&self) -> Result<&'static str> {
+        match self.detect_legacy_sdk_type().await {
+            Some(ProjectType::LegacySdk2) => Err(MigrateLegacySdkError::Sdk2NotSupported),
+            Some(ProjectType::LegacyWorlds) => Ok(WORLDS_SDK),
+            Some(ProjectType::LegacyAvatars) => Ok(AVATARS_SDK),
+            _ => Err(MigrateLegacySdkError::NotLegacyProject),
+        }
+    }
+}
@@ -1,8 +1,11 @@
+use crate::error::{ErrorCode, ErrorParam};
 use crate::io::ProjectIo;
+use crate::unity_project::vpm_resolver::find_vpm_resolver;
 use crate::unity_project::{AddPackageErr, AddPackageOperation};
 use crate::version::UnityVersion;
-use crate::{PackageCollection, UnityProject, VersionSelector};
+use crate::{AbortCheck, PackageCollection, UnityProject, VersionSelector};
 use crate::{PackageInstaller, VRCHAT_RECOMMENDED_2022_UNITY, io};
+use indexmap::IndexMap;
 use log::warn;
 
 #[non_exhaustive]
@@ -24,6 +27,30 @@ impl std::error::Error for MigrateUnity2022Error {
     }
 }
 
+impl ErrorCode for MigrateUnity2022Error {
+    fn code(&self) -> &'static str {
+        match self {
+            MigrateUnity2022Error::UnityVersionMismatch => {
+                "migrate-unity-2022.unity-version-mismatch"
+            }
+            MigrateUnity2022Error::VpmPackageNotFound(_) => "migrate-unity-2022.package-not-found",
+            MigrateUnity2022Error::AddPackageErr(err) => err.code(),
+            MigrateUnity2022Error::Io(err) => err.code(),
+        }
+    }
+
+    fn params(&self) -> IndexMap<&'static str, ErrorParam> {
+        match self {
+            MigrateUnity2022Error::UnityVersionMismatch => IndexMap::new(),
+            MigrateUnity2022Error::VpmPackageNotFound(name) => {
+                IndexMap::from([("package_name", ErrorParam::text(name))])
+            }
+            MigrateUnity2022Error::AddPackageErr(err) => err.params(),
+            MigrateUnity2022Error::Io(err) => err.params(),
+        }
+    }
+}
+
 impl std::fmt::Display for MigrateUnity2022Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -54,18 +81,23 @@ type Result<T = (), E = MigrateUnity2022Error> = std::result::Result<T, E>;
 impl<IO: ProjectIo> UnityProject<IO> {
     pub async fn migrate_unity_2022(
         &mut self,
-        collection: &impl PackageCollection,
+        collection: &(impl PackageCollection + ?Sized),
         installer: &impl PackageInstaller,
-    ) -> Result {
-        migrate_unity_2022(self, collection, installer).await
+    ) -> Result<(), crate::Error> {
+        let lock = self.lock().await?;
+        let result = migrate_unity_2022(self, collection, installer).await;
+        self.unlock(lock).await;
+        Ok(result?)
     }
 }
 
 async fn migrate_unity_2022(
     project: &mut UnityProject<impl ProjectIo>,
-    collection: &impl PackageCollection,
+    collection: &(impl PackageCollection + ?Sized),
     installer: &impl PackageInstaller,
 ) -> Result {
+    project.check_not_open_in_unity().await?;
+
     // See https://misskey.niri.la/notes/9nod7sk4sr for migration process
     if project.unity_version().map(UnityVersion::major) != Some(2019) {
         return Err(MigrateUnity2022Error::UnityVersionMismatch);
@@ -76,14 +108,6 @@ async fn migrate_unity_2022(
         warn!("It looks migrating projects without vpm VRCSDK. this may not intended");
     }
 
-    // remove legacy XR packages
-    project
-        .upm_manifest
-        .remove_dependency("com.unity.xr.oculus.standalone");
-    project
-        .upm_manifest
-        .remove_dependency("com.unity.xr.openvr.standalone");
-
     // upgrade VRCSDK if installed
     let mut packages = vec![];
     let migrating_packages = [
@@ -103,20 +127,41 @@ async fn migrate_unity_2022(
             packages.push(vrcsdk);
         }
     }
+    if project.is_vpm_resolver_missing_after(!packages.is_empty()) {
+        packages.extend(find_vpm_resolver(
+            collection,
+            Some(VRCHAT_RECOMMENDED_2022_UNITY),
+        ));
+    }
 
-    if !packages.is_empty() {
-        // install packages
-        let request = project
-            .add_package_request(
+    let mut request = if packages.is_empty() {
+        super::pending_project_changes::Builder::new().build_no_resolve()
+    } else {
+        project
+            .add_package_request_for_unity(
                 collection,
                 &packages,
                 AddPackageOperation::InstallToDependencies,
                 false,
+                project.unity_version(),
             )
-            .await?;
-        project.apply_pending_changes(installer, request).await?;
+            .await?
+    };
+
+    // remove legacy XR packages
+    for name in [
+        "com.unity.xr.oculus.standalone",
+        "com.unity.xr.openvr.standalone",
+    ] {
+        if project.has_upm_package(name) {
+            request.remove_upm_dependency(name.into());
+        }
     }
 
+    project
+        .apply_pending_changes_impl(installer, request, &AbortCheck::new())
+        .await?;
+
     Ok(())
 }
 
@@ -0,0 +1,294 @@
+use crate::error::{ErrorCode, ErrorParam};
+use crate::io::ProjectIo;
+use crate::unity_project::add_package::SDK_PACKAGES;
+use crate::unity_project::project_lock::PROJECT_LOCK_PATH;
+use crate::unity_project::vpm_resolver::{VPM_RESOLVER, find_vpm_resolver};
+use crate::unity_project::{AddPackageErr, AddPackageOperation, PendingProjectChanges};
+use crate::utils::copy_recursive_filtered;
+use crate::version::{UnityVersion, Version};
+use crate::{AbortCheck, PackageCollection, UnityProject, VersionSelector, io, unity_compatible};
+use indexmap::IndexMap;
+use log::debug;
+use std::path::PathBuf;
+
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum MigrateUnityVersionError {
+    DowngradingUnity {
+        current: UnityVersion,
+        target: UnityVersion,
+    },
+    UnlockedSdkPackage {
+        package_name: Box<str>,
+    },
+    VpmPackageNotFound {
+        package_name: Box<str>,
+    },
+    AddPackageErr(AddPackageErr),
+    Io(io::Error),
+}
+
+impl std::error::Error for MigrateUnityVersionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MigrateUnityVersionError::AddPackageErr(err) => Some(err),
+            MigrateUnityVersionError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl ErrorCode for MigrateUnityVersionError {
+    fn code(&self) -> &'static str {
+        match self {
+            MigrateUnityVersionError::DowngradingUnity { .. } => "migrate-unity.downgrading-unity",
+            MigrateUnityVersionError::UnlockedSdkPackage { .. } => {
+                "migrate-unity.unlocked-sdk-package"
+            }
+            MigrateUnityVersionError::VpmPackageNotFound { .. } => {
+                "migrate-unity.package-not-found"
+            }
+            MigrateUnityVersionError::AddPackageErr(err) => err.code(),
+            MigrateUnityVersionError::Io(err) => err.code(),
+        }
+    }
+
+    fn params(&self) -> IndexMap<&'static str, ErrorParam> {
+        match self {
+            MigrateUnityVersionError::DowngradingUnity { current, target } => IndexMap::from([
+                ("current", ErrorParam::text(current)),
+                ("target", ErrorParam::text(target)),
+            ]),
+            MigrateUnityVersionError::UnlockedSdkPackage { package_name }
+            | MigrateUnityVersionError::VpmPackageNotFound { package_name } => {
+                IndexMap::from([("package_name", ErrorParam::text(package_name))])
+            }
+            MigrateUnityVersionError::AddPackageErr(err) => err.params(),
+            MigrateUnityVersionError::Io(err) => err.params(),
+        }
+    }
+}
+
+impl std::fmt::Display for MigrateUnityVersionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MigrateUnityVersionError::DowngradingUnity { current, target } => write!(
+                f,
+                "Migrating to older Unity is not supported: {current} -> {target}"
+            ),
+            MigrateUnityVersionError::UnlockedSdkPackage { package_name } => write!(
+                f,
+                "Package {package_name} is not managed by VPM so it cannot be upgraded automatically"
+            ),
+            MigrateUnityVersionError::VpmPackageNotFound { package_name } => {
+                write!(f, "VPM package {package_name} for the Unity not found")
+            }
+            MigrateUnityVersionError::AddPackageErr(err) => write!(f, "{}", err),
+            MigrateUnityVersionError::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<AddPackageErr> for MigrateUnityVersionError {
+    fn from(err: AddPackageErr) -> Self {
+        MigrateUnityVersionError::AddPackageErr(err)
+    }
+}
+
+impl From<io::Error> for MigrateUnityVersionError {
+    fn from(err: io::Error) -> Self {
+        MigrateUnityVersionError::Io(err)
+    }
+}
+
+type Result<T = (), E = MigrateUnityVersionError> = std::result::Result<T, E>;
+
+/// The UPM packages pinned to the versions that come with the Unity 2022 projects made by VCC.
+///
+/// The versions in the Unity 2019 projects fail to compile on Unity 2022.
+const UNITY_2022_UPM_PACKAGES: &[(&str, (u64, u64, u64))] = &[
+    ("com.unity.textmeshpro", (3, 0, 6)),
+    ("com.unity.timeline", (1, 7, 5)),
+];
+
+/// The legacy XR packages that is not supported on Unity 2020 or later
+const LEGACY_XR_PACKAGES: &[&str] = &[
+    "com.unity.xr.oculus.standalone",
+    "com.unity.xr.openvr.standalone",
+];
+
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Creates a new `PendingProjectChanges` to migrate the project to the specified Unity version.
+    ///
+    /// The request will
+    /// - upgrade VRChat SDK packages to the latest version for the new Unity unless newer versions are installed,
+    /// - upgrade other locked packages that are not compatible with the new Unity if possible,
+    /// - add `com.vrchat.core.vpm-resolver` if [missing](Self::is_vpm_resolver_missing),
+    /// - remove legacy XR packages from `Packages/manifest.json` and pin the UPM packages for Unity 2022, and
+    /// - rewrite `ProjectSettings/ProjectVersion.txt`.
+    ///
+    /// You should call `apply_pending_changes` to apply the changes after confirming to the user.
+    /// If you want to keep the original project, [`copy_project`](Self::copy_project) the project and
+    /// apply the changes to the copied project.
+    ///
+    /// Unity have to open the project after applying the changes to upgrade assets.
+    pub async fn migrate_unity_version_request<'env>(
+        &self,
+        env: &'env (impl PackageCollection + ?Sized),
+        target: UnityVersion,
+    ) -> Result<PendingProjectChanges<'env>, crate::Error> {
+        Ok(self.migrate_unity_version_request_impl(env, target).await?)
+    }
+
+    async fn migrate_unity_version_request_impl<'env>(
+        &self,
+        env: &'env (impl PackageCollection + ?Sized),
+        target: UnityVersion,
+    ) -> Result<PendingProjectChanges<'env>> {
+        if let Some(current) = self.unity_version() {
+            if target < current {
+                return Err(MigrateUnityVersionError::DowngradingUnity { current, target });
+            }
+        }
+
+        // The packages always upgraded to the latest version for the new Unity
+        let is_sdk_package = |name: &str| SDK_PACKAGES.contains(&name) || name == VPM_RESOLVER;
+
+        // We cannot upgrade SDK packages that is not managed by VPM
+        for (dir_name, manifest) in self.unlocked_packages() {
+            let name = manifest.as_ref().map(|x| x.name()).unwrap_or(dir_name);
+            if is_sdk_package(name) {
+                return Err(MigrateUnityVersionError::UnlockedSdkPackage {
+                    package_name: name.into(),
+                });
+            }
+        }
+
+        let mut packages = vec![];
+
+        for locked in self.locked_packages() {
+            let name = locked.name();
+            let selector = VersionSelector::latest_for(Some(target), false);
+
+            if is_sdk_package(name) {
+                let Some(package) = env.find_package_by_name(name, selector) else {
+                    return Err(MigrateUnityVersionError::VpmPackageNotFound {
+                        package_name: name.into(),
+                    });
+                };
+                // the installed version may be a prerelease or newer than the repositories
+                if package.version() <= locked.version() {
+                    debug!("Keeping {} {}", name, locked.version());
+                    continue;
+                }
+                debug!("Upgrading {} to {}", name, package.version());
+                packages.push(package);
+                continue;
+            }
+
+            let compatible = env
+                .find_package_by_name(name, VersionSelector::specific_version(locked.version()))
+                .map(|x| x.package_json())
+                .or_else(|| self.get_installed_package(name))
+                .map(|x| unity_compatible(x, target))
+                .unwrap_or(true);
+
+            if !compatible {
+                if let Some(package) = env
+                    .find_package_by_name(name, selector)
+                    .filter(|x| x.version() > locked.version())
+                {
+                    debug!("Upgrading incompatible {} to {}", name, package.version());
+                    packages.push(package);
+                } else {
+                    log::warn!("No version of {name} compatible with {target} found");
+                }
+            }
+        }
+
+        if self.is_vpm_resolver_missing_after(!packages.is_empty()) {
+            packages.extend(find_vpm_resolver(env, Some(target)));
+        }
+
+        let mut request = if packages.is_empty() {
+            super::pending_project_changes::Builder::new().build_no_resolve()
+        } else {
+            self.add_package_request_for_unity(
+                env,
+                &packages,
+                AddPackageOperation::InstallToDependencies,
+                false,
+                Some(target),
+            )
+            .await?
+        };
+
+        if target.major() >= 2020 {
+            for &name in LEGACY_XR_PACKAGES {
+                if self.has_upm_package(name) {
+                    request.remove_upm_dependency(name.into());
+                }
+            }
+        }
+
+        if target.major() == 2022 {
+            for &(name, (major, minor, patch)) in UNITY_2022_UPM_PACKAGES {
+                if self.has_upm_package(name) {
+                    request.ensure_upm_dependency(name.into(), Version::new(major, minor, patch));
+                }
+            }
+        }
+
+        request.unity_version = Some(target);
+
+        Ok(request)
+    }
+
+    /// Copies the project to the location specified by `io` and loads the copied project.
+    ///
+    /// The folders generated by Unity (`Library`, `Logs`, `obj`, and `Temp`), `.git` folders,
+    /// and the project lock (`.vrc-get.lock`) are not copied.
+    pub async fn copy_project<DestIO: ProjectIo>(
+        &self,
+        io: DestIO,
+    ) -> Result<UnityProject<DestIO>, crate::Error> {
+        self.copy_files_to(&io, true, &AbortCheck::new()).await?;
+        Ok(UnityProject::load(io).await?)
+    }
+
+    /// Copies the files of the project to `io` except for the folders generated by Unity.
+    ///
+    /// The modification time of the files are preserved so that Unity doesn't reimport the assets.
+    pub(crate) async fn copy_files_to(
+        &self,
+        io: &impl ProjectIo,
+        skip_git: bool,
+        abort: &AbortCheck,
+    ) -> io::Result<()> {
+        return copy_recursive_filtered(
+            &self.io,
+            PathBuf::new(),
+            io,
+            PathBuf::new(),
+            |path| {
+                let Some(file_name) = path.file_name() else {
+                    return false;
+                };
+                let lower_name = file_name.to_string_lossy().to_ascii_lowercase();
+                let is_root = path.parent() == Some("".as_ref());
+                (skip_git && lower_name == ".git")
+                    || (is_root && is_generated_folder(&lower_name))
+                    // the lock of this project is not the lock of the copied project
+                    || (is_root && lower_name == PROJECT_LOCK_PATH)
+            },
+            abort,
+        )
+        .await;
+
+        fn is_generated_folder(lower_name: &str) -> bool {
+            matches!(lower_name, "library" | "logs" | "obj" | "temp")
+                // some people use multiple library folder to speed up switching platforms
+                || lower_name.starts_with("library")
+        }
+    }
+}
@@ -1,10 +1,13 @@
 use futures::prelude::*;
+use indexmap::IndexMap;
 use log::{debug, info};
 use std::collections::HashSet;
 
+use crate::error::{ErrorCode, ErrorParam};
 use crate::io::ProjectIo;
+use crate::unity_project::vpm_resolver::find_vpm_resolver;
 use crate::unity_project::{AddPackageErr, AddPackageOperation};
-use crate::{PackageCollection, UnityProject, VersionSelector};
+use crate::{AbortCheck, PackageCollection, UnityProject, VersionSelector};
 use crate::{PackageInstaller, ProjectType, io};
 
 #[non_exhaustive]
@@ -27,6 +30,33 @@ impl std::error::Error for MigrateVpmError {
     }
 }
 
+impl ErrorCode for MigrateVpmError {
+    fn code(&self) -> &'static str {
+        match self {
+            MigrateVpmError::ProjectTypeMismatch(_) => "migrate-vpm.project-type-mismatch",
+            MigrateVpmError::UnityVersionMismatch => "migrate-vpm.unity-version-mismatch",
+            MigrateVpmError::VpmPackageNotFound(_) => "migrate-vpm.package-not-found",
+            MigrateVpmError::AddPackageErr(err) => err.code(),
+            MigrateVpmError::Io(err) => err.code(),
+        }
+    }
+
+    fn params(&self) -> IndexMap<&'static str, ErrorParam> {
+        match self {
+            MigrateVpmError::ProjectTypeMismatch(project_type) => IndexMap::from([(
+                "project_type",
+                ErrorParam::text(format_args!("{project_type:?}")),
+            )]),
+            MigrateVpmError::UnityVersionMismatch => IndexMap::new(),
+            MigrateVpmError::VpmPackageNotFound(name) => {
+                IndexMap::from([("package_name", ErrorParam::text(name))])
+            }
+            MigrateVpmError::AddPackageErr(err) => err.params(),
+            MigrateVpmError::Io(err) => err.params(),
+        }
+    }
+}
+
 impl std::fmt::Display for MigrateVpmError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -60,20 +90,25 @@ type Result<T = (), E = MigrateVpmError> = std::result::Result<T, E>;
 impl<IO: ProjectIo> UnityProject<IO> {
     pub async fn migrate_vpm(
         &mut self,
-        collection: &impl PackageCollection,
+        collection: &(impl PackageCollection + ?Sized),
         installer: &impl PackageInstaller,
         include_prerelease: bool,
-    ) -> Result {
-        migrate_vpm(self, collection, installer, include_prerelease).await
+    ) -> Result<(), crate::Error> {
+        let lock = self.lock().await?;
+        let result = migrate_vpm(self, collection, installer, include_prerelease).await;
+        self.unlock(lock).await;
+        Ok(result?)
     }
 }
 
 async fn migrate_vpm(
     project: &mut UnityProject<impl ProjectIo>,
-    collection: &impl PackageCollection,
+    collection: &(impl PackageCollection + ?Sized),
     installer: &impl PackageInstaller,
     include_prerelease: bool,
 ) -> Result {
+    project.check_not_open_in_unity().await?;
+
     let is_worlds = match project.detect_project_type().await? {
         // we only can migrate legacy VRCSDK3 projects
         ProjectType::LegacyWorlds => true,
@@ -118,11 +153,12 @@ async fn migrate_vpm(
         );
 
         let packages = project
-            .add_package_request(
+            .add_package_request_for_unity(
                 collection,
                 &curated_packages,
                 AddPackageOperation::InstallToDependencies,
                 include_prerelease,
+                project.unity_version(),
             )
             .await?;
 
@@ -144,18 +180,25 @@ async fn migrate_vpm(
         adding_packages.extend(curated_packages.into_iter());
     }
 
+    if project.is_vpm_resolver_missing_after(!adding_packages.is_empty()) {
+        adding_packages.extend(find_vpm_resolver(collection, project.unity_version()));
+    }
+
     // install packages. this also removes legacy VRCSDK and curated packages
 
     let request = project
-        .add_package_request(
+        .add_package_request_for_unity(
             collection,
             &adding_packages,
             AddPackageOperation::InstallToDependencies,
             include_prerelease,
+            project.unity_version(),
         )
         .await?;
 
-    project.apply_pending_changes(installer, request).await?;
+    project
+        .apply_pending_changes_impl(installer, request, &AbortCheck::new())
+        .await?;
 
     // update project settings
     let project_settings_path = "ProjectSettings/ProjectSettings.asset".as_ref();
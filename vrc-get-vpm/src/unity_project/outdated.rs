@@ -0,0 +1,158 @@
+use crate::io::ProjectIo;
+use crate::version::{PrereleaseAcceptance, Version, VersionRange};
+use crate::{PackageCollection, PackageInfo, UnityProject, VersionSelector, unity_compatible};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Why [`OutdatedPackage::upgradable`] is older than [`OutdatedPackage::latest`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub enum HeldBack {
+    /// The newer versions do not match the dependency ranges declared by other packages.
+    DependencyRange,
+    /// The newer versions require newer Unity than the project uses.
+    Unity,
+}
+
+/// A locked package in [`OutdatedReport`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct OutdatedPackage {
+    pub name: Box<str>,
+    /// The version locked in `vpm-manifest.json`
+    pub locked: Version,
+    /// The newest version in the collection, regardless of the dependency ranges and the Unity version.
+    pub latest: Option<Version>,
+    /// The newest version matching the dependency ranges declared by other packages in the project.
+    pub latest_in_range: Option<Version>,
+    /// The newest version matching the dependency ranges and compatible with the Unity of the project.
+    ///
+    /// The ranges are the ones of the locked versions, so upgrading the package with the dependants
+    /// may get the newer version.
+    pub upgradable: Option<Version>,
+    /// Whether [`latest`](Self::latest) requires newer Unity than the project uses.
+    pub requires_newer_unity: bool,
+    /// Why the package cannot be upgraded to [`latest`](Self::latest), if it's held back.
+    pub held_back: Option<HeldBack>,
+    /// The package is not found in the collection, like the repository is removed or disabled.
+    ///
+    /// Other versions are `None` for such packages.
+    pub source_unavailable: bool,
+}
+
+impl OutdatedPackage {
+    /// Whether the package can be upgraded to a newer version.
+    pub fn is_outdated(&self) -> bool {
+        self.upgradable.as_ref().is_some_and(|x| x > &self.locked)
+    }
+}
+
+/// The result of [`UnityProject::outdated_report`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutdatedReport {
+    packages: Vec<OutdatedPackage>,
+}
+
+impl OutdatedReport {
+    /// All locked packages, sorted by the name.
+    pub fn packages(&self) -> &[OutdatedPackage] {
+        &self.packages
+    }
+
+    /// The packages that can be upgraded to a newer version.
+    pub fn outdated(&self) -> impl Iterator<Item = &OutdatedPackage> {
+        self.packages.iter().filter(|x| x.is_outdated())
+    }
+
+    /// The name and the version to upgrade for each of the outdated packages.
+    pub fn upgrades(&self) -> impl Iterator<Item = (&str, &Version)> {
+        (self.outdated()).filter_map(|x| Some((x.name.as_ref(), x.upgradable.as_ref()?)))
+    }
+}
+
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Reports the newer versions of the locked packages in the collection.
+    ///
+    /// This does not access the network and the files, so the collection should be updated beforehand
+    /// to get the newest information.
+    pub fn outdated_report(
+        &self,
+        collection: &(impl PackageCollection + ?Sized),
+        allow_prerelease: bool,
+    ) -> OutdatedReport {
+        let unity = self.unity_version();
+
+        let mut ranges = HashMap::<&str, Vec<&VersionRange>>::new();
+        for package in self.all_packages() {
+            for (dependency, range) in package.dependencies() {
+                if dependency.as_ref() != package.name() {
+                    ranges.entry(dependency.as_ref()).or_default().push(range);
+                }
+            }
+        }
+
+        let mut packages = vec![];
+
+        for locked in self.locked_packages() {
+            let versions = collection.find_packages(locked.name()).collect::<Vec<_>>();
+            let package_ranges = ranges.remove(locked.name()).unwrap_or_default();
+
+            let newest = |selector: VersionSelector| {
+                (versions.iter())
+                    .filter(|x| selector.satisfies(x.package_json()))
+                    .max_by_key(|x| x.version())
+                    .copied()
+            };
+            let newest_in_range = |unity| {
+                if package_ranges.is_empty() {
+                    newest(VersionSelector::latest_for(unity, allow_prerelease))
+                } else {
+                    newest(VersionSelector::ranges_for(
+                        unity,
+                        &package_ranges,
+                        PrereleaseAcceptance::allow_or_minimum(allow_prerelease),
+                    ))
+                }
+            };
+
+            let latest = newest(VersionSelector::latest_for(None, allow_prerelease));
+            let latest_in_range = newest_in_range(None);
+            let upgradable = newest_in_range(unity);
+
+            let requires_newer_unity = unity
+                .zip(latest)
+                .is_some_and(|(unity, latest)| !unity_compatible(latest.package_json(), unity));
+
+            let version = |x: Option<PackageInfo>| x.map(|x| x.version().clone());
+            let held_back = match (latest, upgradable) {
+                (Some(latest), upgradable)
+                    if upgradable.is_none_or(|x| x.version() < latest.version()) =>
+                {
+                    if latest_in_range.is_none_or(|x| x.version() < latest.version()) {
+                        Some(HeldBack::DependencyRange)
+                    } else {
+                        Some(HeldBack::Unity)
+                    }
+                }
+                _ => None,
+            };
+
+            packages.push(OutdatedPackage {
+                name: locked.name().into(),
+                locked: locked.version().clone(),
+                latest: version(latest),
+                latest_in_range: version(latest_in_range),
+                upgradable: version(upgradable),
+                requires_newer_unity,
+                held_back,
+                source_unavailable: versions.is_empty(),
+            });
+        }
+
+        packages.sort_by(|a, b| a.name.cmp(&b.name));
+
+        OutdatedReport { packages }
+    }
+}
@@ -193,7 +193,7 @@ where
     pub(crate) fn add_locked_dependency(
         &mut self,
         locked: LockedDependencyInfo<'a>,
-        env: &'env impl PackageCollection,
+        env: &'env (impl PackageCollection + ?Sized),
     ) {
         let info = self.dependencies.entry(locked.name()).or_default();
         info.set_using_info(
@@ -429,7 +429,7 @@ pub(crate) fn collect_adding_packages<'a, 'env>(
     unlocked_packages: impl Iterator<Item = &'a (Box<str>, Option<PackageManifest>)>,
     get_locked: impl Fn(&str) -> Option<LockedDependencyInfo<'a>>,
     unity_version: Option<UnityVersion>,
-    env: &'env impl PackageCollection,
+    env: &'env (impl PackageCollection + ?Sized),
     packages: Vec<PackageInfo<'env>>,
     allow_prerelease: bool,
     missing_dependencies: &mut MissingDependencies,
@@ -490,7 +490,7 @@ pub(crate) fn collect_adding_packages<'a, 'env>(
 
                 if context.should_add_package(dependency, range) {
                     fn get_package<'env>(
-                        env: &'env impl PackageCollection,
+                        env: &'env (impl PackageCollection + ?Sized),
                         dependency: &str,
                         unity_version: Option<UnityVersion>,
                         range: &VersionRange,
@@ -502,13 +502,13 @@ pub(crate) fn collect_adding_packages<'a, 'env>(
                         )
                     }
 
-                    struct PackageFinder<'env, 'a, C: PackageCollection> {
+                    struct PackageFinder<'env, 'a, C: PackageCollection + ?Sized> {
                         dependency: &'a str,
                         env: &'env C,
                         range: &'env VersionRange,
                     }
 
-                    impl<'env, C: PackageCollection> PackageFinder<'env, '_, C> {
+                    impl<'env, C: PackageCollection + ?Sized> PackageFinder<'env, '_, C> {
                         fn find(
                             &self,
                             unity_version: Option<UnityVersion>,
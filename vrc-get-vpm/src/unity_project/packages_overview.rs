@@ -0,0 +1,117 @@
+use crate::io::ProjectIo;
+use crate::unity_project::is_unlocked_package_of;
+use crate::unity_project::upm_manifest::UpmDependency;
+use crate::{PackageManifest, UnityProject};
+use serde::Serialize;
+
+/// How the package in the project is installed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[non_exhaustive]
+pub enum InstalledPackageKind {
+    /// The VPM package locked in `vpm-manifest.json`, managed by vrc-get.
+    VpmLocked,
+    /// The folder for the package in `vpm-manifest.json` but not installed as a VPM package.
+    ///
+    /// Installing or resolving the package will replace the folder.
+    VpmUnlocked,
+    /// The package folder in `Packages` not related to VPM.
+    Embedded,
+    /// The package from the registry or a git repository specified in `manifest.json`.
+    Upm,
+    /// The built-in module of Unity specified in `manifest.json`, like `com.unity.modules.audio`.
+    BuiltIn,
+}
+
+/// A package in [`UnityProject::installed_packages_overview`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct PackageOverview {
+    /// The name of the folder in `Packages`. None for packages only specified in `manifest.json`.
+    pub folder_name: Option<Box<str>>,
+    /// The name of the package. None if `package.json` cannot be read.
+    pub name: Option<Box<str>>,
+    /// The version in `package.json`, or the version or url in `manifest.json`.
+    pub version: Option<Box<str>>,
+    pub category: InstalledPackageKind,
+    /// Whether the package is managed by vrc-get.
+    pub managed: bool,
+}
+
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Lists all the packages in the project including the ones not managed by vrc-get.
+    ///
+    /// This uses the information read while loading the project and does not access the files.
+    /// The packages are sorted by the category and then the folder name or the package name.
+    pub fn installed_packages_overview(&self) -> Vec<PackageOverview> {
+        let mut result = vec![];
+
+        for (folder_name, manifest) in &self.installed_packages {
+            result.push(folder_entry(folder_name, Some(manifest), true));
+        }
+
+        for (folder_name, manifest) in &self.unlocked_packages {
+            let mut entry = folder_entry(folder_name, manifest.as_ref(), false);
+            let mut vpm_packages = (self.manifest.all_locked().map(|x| x.name()))
+                .chain(self.manifest.dependencies().map(|(name, _)| name));
+            if vpm_packages.any(|name| is_unlocked_package_of(folder_name, manifest.as_ref(), name))
+            {
+                entry.category = InstalledPackageKind::VpmUnlocked;
+            }
+            result.push(entry);
+        }
+
+        for (name, dependency) in self.upm_manifest.dependencies() {
+            // Unity uses embedded packages instead of the ones in manifest.json
+            if result.iter().any(|x| x.name.as_deref() == Some(name)) {
+                continue;
+            }
+
+            let version = match dependency {
+                UpmDependency::Version(version) => version.to_string().into(),
+                UpmDependency::OtherNotation(notation) => notation.clone(),
+            };
+            let category = if name.starts_with("com.unity.modules.") {
+                InstalledPackageKind::BuiltIn
+            } else {
+                InstalledPackageKind::Upm
+            };
+
+            result.push(PackageOverview {
+                folder_name: None,
+                name: Some(name.into()),
+                version: Some(version),
+                category,
+                managed: false,
+            });
+        }
+
+        result.sort_by(|a, b| {
+            (a.category.cmp(&b.category)).then_with(|| sort_key(a).cmp(&sort_key(b)))
+        });
+
+        result
+    }
+}
+
+fn folder_entry(
+    folder_name: &str,
+    manifest: Option<&PackageManifest>,
+    managed: bool,
+) -> PackageOverview {
+    PackageOverview {
+        folder_name: Some(folder_name.into()),
+        name: manifest.map(|x| x.name().into()),
+        version: manifest.map(|x| x.version().to_string().into()),
+        category: if managed {
+            InstalledPackageKind::VpmLocked
+        } else {
+            InstalledPackageKind::Embedded
+        },
+        managed,
+    }
+}
+
+fn sort_key(entry: &PackageOverview) -> Option<&str> {
+    entry.folder_name.as_deref().or(entry.name.as_deref())
+}
@@ -1,15 +1,20 @@
+use crate::event::Event;
 use crate::io::{DirEntry, ProjectIo};
+use crate::package_name::package_path;
 use crate::traits::AbortCheck;
-use crate::unity_project::find_legacy_assets::collect_legacy_assets;
-use crate::utils::walk_dir_relative;
-use crate::version::DependencyRange;
-use crate::{PackageInfo, UnityProject, unity_compatible};
+use crate::unity_project::find_legacy_assets::{LegacyAssets, collect_legacy_assets};
+use crate::unity_project::upm_manifest::UpmManifest;
+use crate::unity_project::vpm_manifest::VpmManifest;
+use crate::utils::{is_symlink, walk_dir_relative};
+use crate::version::{DependencyRange, UnityVersion, Version};
+use crate::{PackageInfo, ScopedRegistry, UnityProject, unity_compatible};
 use crate::{PackageInstaller, io};
 use either::Either;
 use futures::future::{join, join_all};
 use futures::prelude::*;
 use indexmap::IndexSet;
 use log::debug;
+use serde::Serialize;
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::future::ready;
@@ -31,8 +36,12 @@ pub struct PendingProjectChanges<'env> {
 
     pub(crate) remove_legacy_files: Vec<(Box<Path>, &'env str)>,
     pub(crate) remove_legacy_folders: Vec<(Box<Path>, &'env str)>,
+    pub(crate) protected_legacy_assets: Vec<(Box<Path>, &'env str)>,
 
     pub(crate) conflicts: HashMap<Box<str>, ConflictInfo>,
+
+    pub(crate) upm_changes: Vec<UpmChange>,
+    pub(crate) unity_version: Option<UnityVersion>,
 }
 
 #[derive(Debug)]
@@ -90,13 +99,30 @@ impl Remove<'_> {
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub enum RemoveReason {
     Requested,
     Legacy,
     Unused,
 }
 
+/// A change to `Packages/manifest.json`, the manifest of Unity Package Manager
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum UpmChange {
+    /// Adds the dependency or updates the version of the dependency
+    SetDependency { name: Box<str>, version: Version },
+    /// Removes the dependency
+    RemoveDependency { name: Box<str> },
+    /// Adds the dependency unless the same or newer version is already specified.
+    ///
+    /// Dependencies specified with non-version notation like git urls are kept.
+    EnsureDependency { name: Box<str>, version: Version },
+    /// Adds the scoped registry, or adds missing scopes if the registry with the same url exists
+    EnsureScopedRegistry { registry: ScopedRegistry },
+}
+
 #[derive(Debug, Default)]
 pub struct ConflictInfo {
     conflicts_packages: Vec<Box<str>>,
@@ -335,12 +361,39 @@ impl<'env> Builder<'env> {
 
             remove_legacy_files: vec![],
             remove_legacy_folders: vec![],
+            protected_legacy_assets: vec![],
+
+            upm_changes: vec![],
+            unity_version: None,
         }
     }
 
+    /// Builds the changes only removing `legacy_assets` without any package changes.
+    pub(crate) fn build_remove_legacy_assets(
+        self,
+        legacy_assets: LegacyAssets<'env>,
+    ) -> PendingProjectChanges<'env> {
+        let mut changes = self.build_no_resolve();
+        changes.remove_legacy_files = legacy_assets.files;
+        changes.remove_legacy_folders = legacy_assets.folders;
+        changes.protected_legacy_assets = legacy_assets.protected;
+        changes
+    }
+
     pub async fn build_resolve(
+        self,
+        unity_project: &UnityProject<impl ProjectIo>,
+    ) -> PendingProjectChanges<'env> {
+        self.build_resolve_for_unity(unity_project, unity_project.unity_version())
+            .await
+    }
+
+    /// Same as `build_resolve` but checks the compatibility with specified Unity version
+    /// instead of the current version of the project.
+    pub async fn build_resolve_for_unity(
         mut self,
         unity_project: &UnityProject<impl ProjectIo>,
+        unity_version: Option<UnityVersion>,
     ) -> PendingProjectChanges<'env> {
         let installs = Vec::from_iter(
             self.package_changes
@@ -352,7 +405,7 @@ impl<'env> Builder<'env> {
 
         debug!("checking for unity compatibility");
 
-        if let Some(unity) = unity_project.unity_version {
+        if let Some(unity) = unity_version {
             for package in installs
                 .iter()
                 .filter(|pkg| !unity_compatible(pkg.package_json(), unity))
@@ -373,13 +426,29 @@ impl<'env> Builder<'env> {
 
         debug!("Building PendingProjectChanges finished!");
 
-        PendingProjectChanges {
+        let mut changes = PendingProjectChanges {
             package_changes: self.package_changes,
             conflicts: self.conflicts,
 
             remove_legacy_files: legacy_assets.files,
             remove_legacy_folders: legacy_assets.folders,
+            protected_legacy_assets: legacy_assets.protected,
+
+            upm_changes: vec![],
+            unity_version: None,
+        };
+
+        for package in &installs {
+            let package = package.package_json();
+            for registry in package.scoped_registries() {
+                changes.ensure_scoped_registry(registry.clone());
+            }
+            for (name, version) in package.upm_dependencies() {
+                changes.ensure_upm_dependency(name.clone(), version.clone());
+            }
         }
+
+        changes
     }
 
     fn mark_and_sweep_packages(&mut self, unity_project: &UnityProject<impl ProjectIo>) {
@@ -519,9 +588,73 @@ impl PendingProjectChanges<'_> {
         self.remove_legacy_folders.as_slice()
     }
 
+    /// The legacy assets found but not removed since they're protected.
+    ///
+    /// Those are protected by the built-in list, like `Assets` or `Assets/Scenes`,
+    /// [`UnityProject::protected_paths`], or outside `Assets` and `Packages`.
+    /// Users should remove them manually if needed.
+    pub fn protected_legacy_assets(&self) -> &[(Box<Path>, &str)] {
+        self.protected_legacy_assets.as_slice()
+    }
+
+    /// Returns true if the changes remove packages, unlocked packages, or legacy assets.
+    pub fn is_destructive(&self) -> bool {
+        self.removed_paths().next().is_some()
+    }
+
+    /// The paths removed by applying the changes.
+    ///
+    /// The folders of the packages replaced with another version are not included.
+    pub(crate) fn removed_paths(&self) -> impl Iterator<Item = PathBuf> + '_ {
+        let removed_packages = (self.package_changes.iter())
+            .filter(|(_, change)| change.as_remove().is_some())
+            .map(|(name, _)| name);
+        let unlocked = (self.conflicts.values()).flat_map(|x| x.unlocked_names.iter());
+
+        (removed_packages.chain(unlocked))
+            .filter_map(|name| package_path("Packages", name).ok())
+            .chain(
+                (self.remove_legacy_files.iter())
+                    .chain(self.remove_legacy_folders.iter())
+                    .map(|(path, _)| path.to_path_buf()),
+            )
+    }
+
     pub fn conflicts(&self) -> &HashMap<Box<str>, ConflictInfo> {
         &self.conflicts
     }
+
+    pub fn upm_changes(&self) -> &[UpmChange] {
+        self.upm_changes.as_slice()
+    }
+
+    /// Adds `name` to `Packages/manifest.json` unless the same or newer version is already specified.
+    pub fn ensure_upm_dependency(&mut self, name: Box<str>, version: Version) -> &mut Self {
+        self.upm_changes
+            .push(UpmChange::EnsureDependency { name, version });
+        self
+    }
+
+    /// Removes `name` from `Packages/manifest.json`.
+    pub fn remove_upm_dependency(&mut self, name: Box<str>) -> &mut Self {
+        self.upm_changes.push(UpmChange::RemoveDependency { name });
+        self
+    }
+
+    /// Adds the scoped registry to `Packages/manifest.json`.
+    ///
+    /// If the registry with the same url already exists, missing scopes are added to it.
+    pub fn ensure_scoped_registry(&mut self, registry: ScopedRegistry) -> &mut Self {
+        self.upm_changes
+            .push(UpmChange::EnsureScopedRegistry { registry });
+        self
+    }
+
+    /// Returns the Unity version to be written to `ProjectVersion.txt` if the changes
+    /// migrate the project to another Unity version.
+    pub fn unity_version(&self) -> Option<UnityVersion> {
+        self.unity_version
+    }
 }
 
 static TEMP_DIR: &str = "Temp";
@@ -535,12 +668,51 @@ impl<IO: ProjectIo> UnityProject<IO> {
         &mut self,
         env: &'env Env,
         request: PendingProjectChanges<'env>,
+    ) -> Result<(), crate::Error> {
+        Ok(self
+            .apply_pending_changes_impl(env, request, &AbortCheck::new())
+            .await?)
+    }
+
+    /// Applies the changes like [`apply_pending_changes`](Self::apply_pending_changes) until aborted.
+    ///
+    /// If aborted before the packages are installed, the removed packages and the manifests
+    /// are restored, so the project is left as before the call.
+    /// Once all packages are installed, the rest of the changes are applied regardless of `abort`.
+    pub async fn apply_pending_changes_with_abort<'env, Env: PackageInstaller>(
+        &mut self,
+        env: &'env Env,
+        request: PendingProjectChanges<'env>,
+        abort: &AbortCheck,
+    ) -> Result<(), crate::Error> {
+        Ok(self.apply_pending_changes_impl(env, request, abort).await?)
+    }
+
+    pub(crate) async fn apply_pending_changes_impl<'env, Env: PackageInstaller>(
+        &mut self,
+        env: &'env Env,
+        request: PendingProjectChanges<'env>,
+        abort: &AbortCheck,
+    ) -> io::Result<()> {
+        let lock = self.lock().await?;
+        let result = self.apply_pending_changes_locked(env, request, abort).await;
+        self.unlock(lock).await;
+        result
+    }
+
+    /// Applies the changes with the project lock already held by [`lock`](Self::lock).
+    pub(crate) async fn apply_pending_changes_locked<'env, Env: PackageInstaller>(
+        &mut self,
+        env: &'env Env,
+        request: PendingProjectChanges<'env>,
+        abort: &AbortCheck,
     ) -> io::Result<()> {
         /*
         Apply pending changes consists of following steps:
         - Move packages to temp directory (remove packages)
         - Apply changes to manifest (add packages)
         - Install packages
+        - Update ProjectVersion.txt if migrating Unity version
         - Remove legacy assets
 
         This function will do those steps in the order above.
@@ -565,6 +737,15 @@ impl<IO: ProjectIo> UnityProject<IO> {
           - Both cases are not desirable, but the latter is less harmful.
          */
 
+        self.check_not_open_in_unity().await?;
+        abort.check()?;
+
+        // fail before changing anything
+        if (request.upm_changes.iter()).any(|x| matches!(x, UpmChange::EnsureScopedRegistry { .. }))
+        {
+            self.upm_manifest.check_scoped_registries()?;
+        }
+
         let mut installs = Vec::new();
         let mut remove_names = Vec::new();
         let mut remove_unlocked_names = Vec::new();
@@ -594,7 +775,7 @@ impl<IO: ProjectIo> UnityProject<IO> {
 
         self.io.create_dir_all(remove_temp_dir).await?;
 
-        move_packages_to_temp(
+        let moved = move_packages_to_temp(
             &self.io,
             (remove_names.iter().copied())
                 .chain(installs.iter().map(|x| x.name()))
@@ -603,7 +784,13 @@ impl<IO: ProjectIo> UnityProject<IO> {
         )
         .await?;
 
+        if let Err(e) = abort.check() {
+            restore_remove(&self.io, remove_temp_dir, moved.iter().copied()).await;
+            return Err(e);
+        }
+
         // apply changes to manifest
+        let snapshot = ManifestSnapshot::take(self);
         for (name, change) in &request.package_changes {
             match change {
                 PackageChange::Install(change) => {
@@ -627,19 +814,58 @@ impl<IO: ProjectIo> UnityProject<IO> {
 
         self.manifest.remove_packages(remove_names.iter().copied());
 
+        for change in &request.upm_changes {
+            match change {
+                UpmChange::SetDependency { name, version } => {
+                    self.upm_manifest.add_dependency(name, version.clone());
+                }
+                UpmChange::RemoveDependency { name } => {
+                    self.upm_manifest.remove_dependency(name);
+                }
+                UpmChange::EnsureDependency { name, version } => {
+                    self.upm_manifest.ensure_dependency(name, version);
+                }
+                UpmChange::EnsureScopedRegistry { registry } => {
+                    self.upm_manifest.ensure_scoped_registry(registry)?;
+                }
+            }
+        }
+
         // save manifest
 
         self.save().await?;
 
         // add packages
 
-        install_packages(&self.io, env, &installs).await?;
+        let installed = install_packages(&self.io, env, &installs, abort).await;
+        match installed.and_then(|()| abort.check()) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {
+                // revert the changes to keep the project as before the call
+                for package in &installs {
+                    if let Ok(dest_folder) = package_path("Packages", package.name()) {
+                        self.io.remove_dir_all(&dest_folder).await.ok();
+                    }
+                }
+                restore_remove(&self.io, remove_temp_dir, moved.iter().copied()).await;
+                snapshot.restore(self).await?;
+                return Err(e);
+            }
+            Err(e) => return Err(e),
+        }
 
         self.io.remove_dir_all(remove_temp_dir).await.ok();
         self.io.remove_dir_all(PKG_TEMP_DIR.as_ref()).await.ok();
         // remove temp dir also if it's empty
         self.io.remove_dir(TEMP_DIR.as_ref()).await.ok();
 
+        // update unity version after installing packages to keep the project openable
+        // with the original version of Unity if installing packages failed
+
+        if let Some(unity_version) = request.unity_version {
+            self.update_unity_version(unity_version).await?;
+        }
+
         // remove legacy assets
 
         remove_assets(
@@ -652,10 +878,38 @@ impl<IO: ProjectIo> UnityProject<IO> {
         )
         .await;
 
+        env.observer().on_event(&Event::ProjectChangesApplied {
+            installed: &installs.iter().map(|x| x.name()).collect::<Vec<_>>(),
+            removed: &remove_names,
+        });
+
         Ok(())
     }
 }
 
+/// The manifests before applying the changes, to revert the changes when aborted.
+struct ManifestSnapshot {
+    manifest: VpmManifest,
+    upm_manifest: UpmManifest,
+}
+
+impl ManifestSnapshot {
+    fn take(project: &UnityProject<impl ProjectIo>) -> Self {
+        Self {
+            manifest: project.manifest.clone(),
+            upm_manifest: project.upm_manifest.clone(),
+        }
+    }
+
+    async fn restore(self, project: &mut UnityProject<impl ProjectIo>) -> io::Result<()> {
+        project.manifest = self.manifest;
+        project.manifest.mark_changed();
+        project.upm_manifest = self.upm_manifest;
+        project.upm_manifest.mark_changed();
+        project.save().await
+    }
+}
+
 static REMOVED_FILE_PREFIX: &str = ".__removed_";
 
 async fn move_packages_to_temp<'a>(
@@ -692,10 +946,16 @@ async fn move_packages_to_temp<'a>(
     return Ok(moved.into_iter().collect());
 
     async fn move_package(io: &impl ProjectIo, name: &str, temp_dir: &Path) -> io::Result<bool> {
-        let package_dir = format!("Packages/{}", name);
-        let package_dir = Path::new(&package_dir);
+        let package_dir = package_path("Packages", name)?;
+        let package_dir = package_dir.as_path();
         let copied_dir = temp_dir.join(name);
 
+        // linked packages: move the link itself not to touch the package store
+        if io.is_dir(package_dir).await && is_symlink(io, package_dir).await? {
+            io.rename(package_dir, &copied_dir).await?;
+            return Ok(true);
+        }
+
         io.create_dir_all(&copied_dir).await?;
         let mut iterator = pin!(walk_dir_relative(io, vec![package_dir.into()]));
         while let Some((original, entry)) = iterator.next().await {
@@ -742,8 +1002,10 @@ async fn move_packages_to_temp<'a>(
 
 async fn restore_remove(io: &impl ProjectIo, temp_dir: &Path, names: impl Iterator<Item = &str>) {
     for name in names {
-        let package_dir = format!("Packages/{}", name);
-        let package_dir = Path::new(&package_dir);
+        let Ok(package_dir) = package_path("Packages", name) else {
+            continue;
+        };
+        let package_dir = package_dir.as_path();
         io.rename(&temp_dir.join(name), package_dir).await.ok();
 
         let mut iterator = pin!(walk_dir_relative(io, vec![package_dir.into()]));
@@ -772,8 +1034,10 @@ async fn install_packages<Env: PackageInstaller>(
     io: &impl ProjectIo,
     env: &Env,
     packages: &[PackageInfo<'_>],
+    abort: &AbortCheck,
 ) -> io::Result<()> {
-    let abort = AbortCheck::new();
+    // aborts the rest of the packages if one of them failed
+    let abort = abort.child();
     let mut error_store = OnceLock::new();
 
     // resolve all packages
@@ -0,0 +1,331 @@
+use crate::error::ErrorCode;
+use crate::io;
+use crate::io::ProjectIo;
+use crate::unity_project::{UnityProject, VPM_MANIFEST_PATH};
+use futures::prelude::*;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+pub(crate) const PROJECT_LOCK_PATH: &str = ".vrc-get.lock";
+/// The locks older than this are treated as left by crashed processes.
+const STALE_LOCK_AGE: Duration = Duration::from_secs(60 * 60);
+const RETRY_INTERVAL: Duration = Duration::from_millis(100);
+pub(super) const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The tokens of the locks held in this process,
+/// to find the locks left by the operations dropped before releasing the lock.
+static HELD_LOCKS: Mutex<Vec<Box<str>>> = Mutex::new(Vec::new());
+
+/// The error returned when another operation is modifying the project.
+///
+/// This is returned as the inner error of [`io::Error`] with [`io::ErrorKind::ResourceBusy`]
+/// if the lock is not released within the [timeout](UnityProject::set_lock_timeout).
+#[derive(Debug)]
+pub struct ProjectLockedError {
+    pid: Option<u32>,
+}
+
+impl ProjectLockedError {
+    pub(crate) fn new(pid: Option<u32>) -> Self {
+        Self { pid }
+    }
+
+    /// The process id of the process holding the lock, if known.
+    pub fn pid(&self) -> Option<u32> {
+        self.pid
+    }
+}
+
+impl fmt::Display for ProjectLockedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.pid {
+            Some(pid) => write!(
+                f,
+                "another vrc-get operation is in progress on the project (process {pid})"
+            ),
+            None => f.write_str("another vrc-get operation is in progress on the project"),
+        }
+    }
+}
+
+impl std::error::Error for ProjectLockedError {}
+
+impl ErrorCode for ProjectLockedError {
+    fn code(&self) -> &'static str {
+        "project.operation-in-progress"
+    }
+}
+
+/// The error returned when `Packages/vpm-manifest.json` is modified by another process
+/// after the project is loaded.
+///
+/// This is returned as the inner error of [`io::Error`] when acquiring the project lock.
+/// Load the project again to operate on the latest state.
+#[derive(Debug)]
+pub struct ProjectModifiedError {
+    _private: (),
+}
+
+impl ProjectModifiedError {
+    pub(crate) fn new() -> Self {
+        Self { _private: () }
+    }
+}
+
+impl fmt::Display for ProjectModifiedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the project is modified by another process after loading")
+    }
+}
+
+impl std::error::Error for ProjectModifiedError {}
+
+impl ErrorCode for ProjectModifiedError {
+    fn code(&self) -> &'static str {
+        "project.modified-externally"
+    }
+}
+
+/// The content of the lock file.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LockFile {
+    pid: u32,
+    token: Box<str>,
+    /// The unix time the lock is acquired, in seconds.
+    created_at: u64,
+}
+
+/// The lock of the project, held while an operation modifies the project.
+///
+/// The lock is shared by the nested operations, like [`UnityProject::save`] in
+/// [`UnityProject::apply_pending_changes`], and released by the outermost one.
+pub(crate) struct ProjectLock {
+    token: Box<str>,
+    /// Removes the lock file if the lock is dropped without [`UnityProject::unlock`].
+    remover: Option<Box<dyn FnOnce() + Send + Sync>>,
+}
+
+impl fmt::Debug for ProjectLock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProjectLock")
+            .field("token", &self.token)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for ProjectLock {
+    fn drop(&mut self) {
+        // the lock file is removed by `UnityProject::unlock`, or here if the operation is dropped.
+        // if the io cannot remove files synchronously, the lock file is broken by the next operation.
+        if let Some(remover) = self.remover.take() {
+            remover();
+        }
+        let mut held = HELD_LOCKS.lock().unwrap();
+        held.retain(|token| *token != self.token);
+    }
+}
+
+/// The project lock held by [`UnityProject::lock_project`].
+///
+/// The modifying operations on the project share the lock while the guard is alive,
+/// so other processes cannot modify the project between the requests and [`UnityProject::save`].
+/// The lock is released when the guard is dropped.
+#[must_use = "the project lock is released when the guard is dropped"]
+#[derive(Debug)]
+pub struct ProjectLockGuard {
+    _lock: Option<Arc<ProjectLock>>,
+}
+
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Sets how long the modifying operations wait for another operation on the project
+    /// to release the project lock. The default is 10 seconds.
+    ///
+    /// The operations fail with [`ProjectLockedError`] if the lock is not released in time.
+    pub fn set_lock_timeout(&mut self, timeout: Duration) {
+        self.lock_timeout = timeout;
+    }
+
+    /// Acquires the project lock and holds it until the returned guard is dropped.
+    ///
+    /// Hold the guard from creating the requests to [`save`](Self::save) so other processes
+    /// cannot modify the project in the middle.
+    /// This fails with [`ProjectModifiedError`] if `Packages/vpm-manifest.json` is modified
+    /// after the project is loaded, and with [`ProjectLockedError`] if the lock is held
+    /// by another operation.
+    pub async fn lock_project(&self) -> io::Result<ProjectLockGuard> {
+        Ok(ProjectLockGuard {
+            _lock: self.lock().await?,
+        })
+    }
+
+    /// Acquires the project lock, or shares the lock already held by this project.
+    ///
+    /// When acquiring a new lock, this checks `vpm-manifest.json` is not modified after loading.
+    pub(crate) async fn lock(&self) -> io::Result<Option<Arc<ProjectLock>>> {
+        if self.ignore_project_lock {
+            return Ok(None);
+        }
+
+        let held = self.held_lock.lock().unwrap().upgrade();
+        if let Some(lock) = held {
+            return Ok(Some(lock));
+        }
+
+        let lock = Arc::new(acquire_lock(&self.io, self.lock_timeout).await?);
+
+        match read_manifest_snapshot(&self.io).await {
+            Ok(snapshot) if snapshot == self.manifest_snapshot => {}
+            Ok(_) => {
+                self.unlock(Some(lock)).await;
+                return Err(io::Error::other(ProjectModifiedError::new()));
+            }
+            Err(e) => {
+                self.unlock(Some(lock)).await;
+                return Err(e);
+            }
+        }
+
+        *self.held_lock.lock().unwrap() = Arc::downgrade(&lock);
+        Ok(Some(lock))
+    }
+
+    /// Releases the project lock if this is the outermost operation holding the lock.
+    pub(crate) async fn unlock(&self, lock: Option<Arc<ProjectLock>>) {
+        let Some(mut lock) = lock.and_then(Arc::into_inner) else {
+            return;
+        };
+        lock.remover = None;
+        if let Err(e) = self.io.remove_file(PROJECT_LOCK_PATH.as_ref()).await {
+            warn!("failed to remove {PROJECT_LOCK_PATH}: {e}");
+        }
+        drop(lock);
+    }
+}
+
+/// Reads `vpm-manifest.json` to find the modifications by other processes.
+pub(super) async fn read_manifest_snapshot(io: &impl ProjectIo) -> io::Result<Option<Box<[u8]>>> {
+    let mut file = match io.open(VPM_MANIFEST_PATH.as_ref()).await {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer).await?;
+    Ok(Some(buffer.into_boxed_slice()))
+}
+
+async fn acquire_lock(io: &impl ProjectIo, timeout: Duration) -> io::Result<ProjectLock> {
+    let path: &Path = PROJECT_LOCK_PATH.as_ref();
+    let token: Box<str> = uuid::Uuid::new_v4().to_string().into();
+    let content = serde_json::to_vec(&LockFile {
+        pid: std::process::id(),
+        token: token.clone(),
+        created_at: unix_time(SystemTime::now()),
+    })
+    .unwrap();
+
+    let mut retries = timeout.as_millis() / RETRY_INTERVAL.as_millis();
+    loop {
+        match io.create_new(path).await {
+            Ok(mut file) => {
+                // registered before writing the file, so the lock is not broken while writing
+                HELD_LOCKS.lock().unwrap().push(token.clone());
+                let lock = ProjectLock {
+                    token,
+                    remover: io.blocking_remover(path),
+                };
+
+                let written = async {
+                    file.write_all(&content).await?;
+                    file.flush().await
+                };
+                if let Err(e) = written.await {
+                    drop(file);
+                    drop(lock);
+                    io.remove_file(path).await.ok();
+                    return Err(e);
+                }
+                return Ok(lock);
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(e),
+        }
+
+        let holder = match read_lock_file(io).await {
+            Ok(holder) => holder,
+            // the lock is released after we tried to create
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+
+        if is_stale(io, holder.as_ref()).await {
+            warn!("breaking stale project lock {PROJECT_LOCK_PATH}");
+            match io.remove_file(path).await {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => continue,
+            }
+        }
+
+        if retries == 0 || !io.sleep(RETRY_INTERVAL).await {
+            return Err(io::Error::new(
+                io::ErrorKind::ResourceBusy,
+                ProjectLockedError::new(holder.map(|x| x.pid)),
+            ));
+        }
+        retries -= 1;
+        debug!("waiting for the project lock");
+    }
+}
+
+/// Reads the lock file. Returns `None` if the file is not written yet or broken.
+async fn read_lock_file(io: &impl ProjectIo) -> io::Result<Option<LockFile>> {
+    let mut file = io.open(PROJECT_LOCK_PATH.as_ref()).await?;
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer).await?;
+    Ok(serde_json::from_slice(&buffer).ok())
+}
+
+/// Whether the lock is left by a crashed process or a dropped operation.
+async fn is_stale(io: &impl ProjectIo, holder: Option<&LockFile>) -> bool {
+    let now = SystemTime::now();
+    let Some(holder) = holder else {
+        // the lock file may be being written
+        let modified = io.metadata(PROJECT_LOCK_PATH.as_ref()).await.ok();
+        let modified = modified.and_then(|x| x.modified());
+        return modified
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age > STALE_LOCK_AGE);
+    };
+
+    if holder.pid == std::process::id() {
+        if !HELD_LOCKS.lock().unwrap().contains(&holder.token) {
+            debug!("the project lock is left by a dropped operation");
+            return true;
+        }
+    } else if io.is_process_running(holder.pid) == Some(false) {
+        debug!(
+            "the process {} holding the project lock is not running",
+            holder.pid
+        );
+        return true;
+    }
+
+    let age = unix_time(now).saturating_sub(holder.created_at);
+    if age > STALE_LOCK_AGE.as_secs() {
+        debug!("the project lock is acquired {age} seconds ago");
+        return true;
+    }
+
+    false
+}
+
+fn unix_time(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map(|x| x.as_secs())
+        .unwrap_or(0)
+}
@@ -0,0 +1,83 @@
+use crate::io;
+use crate::io::ProjectIo;
+use crate::utils::{SaveController, load_json_or_default, save_json};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+const SETTINGS_PATH: &str = "Packages/vrc-get-settings.json";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AsJson {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    protected_paths: Vec<Box<str>>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    exclude_vpm_resolver: bool,
+
+    #[serde(flatten)]
+    rest: Map<String, Value>,
+}
+
+/// The vrc-get specific settings for the project, saved in `Packages/vrc-get-settings.json`
+#[derive(Debug)]
+pub(super) struct ProjectSettings {
+    controller: SaveController<AsJson>,
+}
+
+impl ProjectSettings {
+    pub(super) async fn load(io: &impl ProjectIo) -> io::Result<Self> {
+        Ok(Self {
+            controller: SaveController::new(
+                load_json_or_default(io, SETTINGS_PATH.as_ref()).await?,
+            ),
+        })
+    }
+
+    pub(super) fn empty() -> Self {
+        Self {
+            controller: SaveController::new(Default::default()),
+        }
+    }
+
+    pub(super) fn protected_paths(&self) -> &[Box<str>] {
+        &self.controller.protected_paths
+    }
+
+    pub(super) fn add_protected_path(&mut self, path: &str) -> bool {
+        let path = normalize_protected_path(path);
+        if self.controller.protected_paths.contains(&path) {
+            return false;
+        }
+        self.controller.as_mut().protected_paths.push(path);
+        true
+    }
+
+    pub(super) fn remove_protected_path(&mut self, path: &str) -> bool {
+        let path = normalize_protected_path(path);
+        let Some(index) = (self.controller.protected_paths.iter()).position(|x| x == &path) else {
+            return false;
+        };
+        self.controller.as_mut().protected_paths.remove(index);
+        true
+    }
+
+    pub(super) fn exclude_vpm_resolver(&self) -> bool {
+        self.controller.exclude_vpm_resolver
+    }
+
+    pub(super) fn set_exclude_vpm_resolver(&mut self, exclude: bool) {
+        if self.controller.exclude_vpm_resolver != exclude {
+            self.controller.as_mut().exclude_vpm_resolver = exclude;
+        }
+    }
+
+    pub(super) async fn save(&mut self, io: &impl ProjectIo) -> io::Result<()> {
+        self.controller
+            .save(|json| save_json(io, SETTINGS_PATH.as_ref(), json))
+            .await
+    }
+}
+
+fn normalize_protected_path(path: &str) -> Box<str> {
+    path.replace('\\', "/").trim_end_matches('/').into()
+}
@@ -0,0 +1,145 @@
+use crate::io::{DirEntry, IoTrait, ProjectIo};
+use crate::{AbortCheck, UnityProject, io};
+use futures::prelude::*;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// The disk usage of the project, in bytes.
+///
+/// Symbolic links (and junctions on windows) are not followed, so packages linked into
+/// `Packages` are not counted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ProjectSizeReport {
+    pub assets: u64,
+    pub packages: u64,
+    /// `Library` folder. This can be removed safely while Unity is closed and will be regenerated.
+    pub library: u64,
+    /// `Temp` folder. This is removed by Unity when the editor is closed.
+    pub temp: u64,
+    /// Everything other than the folders above, like `ProjectSettings` or `Logs`.
+    pub other: u64,
+    pub file_count: u64,
+}
+
+impl ProjectSizeReport {
+    pub fn total(&self) -> u64 {
+        self.assets + self.packages + self.library + self.temp + self.other
+    }
+
+    /// The size that can be freed with [`UnityProject::clear_library`].
+    pub fn clearable(&self) -> u64 {
+        self.library + self.temp
+    }
+}
+
+const CLEARABLE_FOLDERS: &[&str] = &["Library", "Temp"];
+
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Computes the disk usage of the project.
+    ///
+    /// This may take a long time for projects with large `Library` folder.
+    /// The computation fails with [`io::ErrorKind::Interrupted`] if aborted with `abort`.
+    pub async fn size_report(&self, abort: &AbortCheck) -> io::Result<ProjectSizeReport> {
+        let mut report = ProjectSizeReport::default();
+
+        let mut iter = self.io.read_dir("".as_ref()).await?;
+        while let Some(entry) = iter.try_next().await? {
+            abort.check()?;
+
+            let file_name = entry.file_name();
+            let file_type = entry.file_type().await?;
+            let (size, count) = if file_type.is_symlink() {
+                (0, 0)
+            } else if file_type.is_dir() {
+                dir_size(&self.io, file_name.as_ref(), abort).await?
+            } else {
+                (entry.metadata().await?.len(), 1)
+            };
+
+            let bucket = match file_name.to_str() {
+                Some("Assets") if file_type.is_dir() => &mut report.assets,
+                Some("Packages") if file_type.is_dir() => &mut report.packages,
+                Some("Library") if file_type.is_dir() => &mut report.library,
+                Some("Temp") if file_type.is_dir() => &mut report.temp,
+                _ => &mut report.other,
+            };
+            *bucket += size;
+            report.file_count += count;
+        }
+
+        Ok(report)
+    }
+
+    /// Removes `Library` and `Temp` folders of the project.
+    ///
+    /// Unity regenerates those folders when opening the project, so this is safe if the project is not
+    /// open in Unity. This fails if the project is open in Unity.
+    ///
+    /// Returns the number of bytes freed.
+    pub async fn clear_library(&self, abort: &AbortCheck) -> io::Result<u64> {
+        let lock = self.lock().await?;
+        let result = self.clear_library_locked(abort).await;
+        self.unlock(lock).await;
+        result
+    }
+
+    async fn clear_library_locked(&self, abort: &AbortCheck) -> io::Result<u64> {
+        self.check_not_open_in_unity().await?;
+
+        let mut freed = 0;
+        for folder in CLEARABLE_FOLDERS {
+            let path = Path::new(folder);
+            if !self.io.is_dir(path).await {
+                continue;
+            }
+            let (size, _) = dir_size(&self.io, path, abort).await?;
+            abort.check()?;
+            self.io.remove_dir_all(path).await?;
+            freed += size;
+        }
+
+        Ok(freed)
+    }
+}
+
+/// Returns the total size and count of the files in the directory
+async fn dir_size(io: &impl IoTrait, path: &Path, abort: &AbortCheck) -> io::Result<(u64, u64)> {
+    let mut size = 0;
+    let mut count = 0;
+    let mut queue = vec![PathBuf::from(path)];
+
+    while let Some(dir) = queue.pop() {
+        abort.check()?;
+
+        let mut iter = match io.read_dir(&dir).await {
+            Ok(iter) => iter,
+            // files in Temp or Library may be removed by Unity while we're computing
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        while let Some(entry) = iter.try_next().await? {
+            let file_type = match entry.file_type().await {
+                Ok(file_type) => file_type,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+            if file_type.is_symlink() {
+                continue;
+            }
+            if file_type.is_dir() {
+                queue.push(dir.join(entry.file_name()));
+            } else {
+                match entry.metadata().await {
+                    Ok(metadata) => size += metadata.len(),
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                    Err(e) => return Err(e),
+                }
+                count += 1;
+            }
+        }
+    }
+
+    Ok((size, count))
+}
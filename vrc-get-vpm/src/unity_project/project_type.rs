@@ -20,33 +20,44 @@ impl<IO: ProjectIo> UnityProject<IO> {
             return Ok(ProjectType::UpmStarter);
         }
 
+        Ok(self
+            .detect_legacy_sdk_type()
+            .await
+            .unwrap_or(ProjectType::Unknown))
+    }
+
+    /// Detects the legacy (unitypackage) VRChat SDK in `Assets`.
+    ///
+    /// Returns one of `LegacySdk2`, `LegacyWorlds`, or `LegacyAvatars`, or `None` if not found.
+    pub(crate) async fn detect_legacy_sdk_type(&self) -> Option<ProjectType> {
         // VRCSDK2.dll is for SDK2
         if self
             .io
             .is_file("Assets/VRCSDK/Plugins/VRCSDK2.dll".as_ref())
             .await
         {
-            return Ok(ProjectType::LegacySdk2);
+            return Some(ProjectType::LegacySdk2);
         }
 
-        // VRCSDK3.dll is for SDK3 Worlds
+        // VRCSDK3.dll is for SDK3 Worlds, and Udon is only for Worlds
         if self
             .io
             .is_file("Assets/VRCSDK/Plugins/VRCSDK3.dll".as_ref())
             .await
+            || self.io.is_dir("Assets/Udon".as_ref()).await
         {
-            return Ok(ProjectType::LegacyWorlds);
+            return Some(ProjectType::LegacyWorlds);
         }
 
-        // VRCSDK3A.dll is for SDK3 Worlds
+        // VRCSDK3A.dll is for SDK3 Avatars
         if self
             .io
             .is_file("Assets/VRCSDK/Plugins/VRCSDK3A.dll".as_ref())
             .await
         {
-            return Ok(ProjectType::LegacyAvatars);
+            return Some(ProjectType::LegacyAvatars);
         }
 
-        Ok(ProjectType::Unknown)
+        None
     }
 }
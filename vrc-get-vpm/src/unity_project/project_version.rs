@@ -0,0 +1,273 @@
+use crate::error::{ErrorCode, ErrorParam};
+use crate::io::ProjectIo;
+use crate::unity_project::PROJECT_VERSION_PATH;
+use crate::version::UnityVersion;
+use crate::{UnityProject, io};
+use futures::prelude::*;
+use indexmap::IndexMap;
+use std::fmt;
+
+/// The parsed contents of `ProjectSettings/ProjectVersion.txt`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectVersion {
+    version: UnityVersion,
+    revision: Option<Box<str>>,
+    raw: Box<str>,
+}
+
+impl ProjectVersion {
+    pub(crate) fn new(version: UnityVersion, revision: Option<&str>) -> Self {
+        Self {
+            version,
+            revision: revision.map(Into::into),
+            raw: format!("m_EditorVersion: {version}").into(),
+        }
+    }
+
+    /// Parses the contents of `ProjectVersion.txt`.
+    ///
+    /// `m_EditorVersion` is required, and the revision is read from `m_EditorVersionWithRevision` if
+    /// it's present and for the same version.
+    pub fn parse(text: &str) -> Result<Self, ProjectVersionError> {
+        let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+
+        let mut editor_version = None;
+        let mut with_revision = None;
+
+        for line in text.lines() {
+            let line = line.trim_end_matches('\r');
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = unquote(value.trim());
+            match key {
+                "m_EditorVersion" if editor_version.is_none() => {
+                    editor_version = Some((line, value))
+                }
+                "m_EditorVersionWithRevision" if with_revision.is_none() => {
+                    with_revision = Some(value)
+                }
+                _ => {}
+            }
+        }
+
+        let Some((raw, value)) = editor_version else {
+            return Err(ProjectVersionError::MissingEditorVersion);
+        };
+
+        let Some(version) = UnityVersion::parse(value) else {
+            return Err(ProjectVersionError::InvalidEditorVersion { raw: raw.into() });
+        };
+
+        let revision = with_revision.and_then(|with_revision| {
+            let Some((revision_version, revision)) = parse_version_with_revision(with_revision)
+            else {
+                log::error!("failed to parse m_EditorVersionWithRevision in ProjectVersion.txt");
+                return None;
+            };
+            if revision_version != version {
+                log::warn!(
+                    "m_EditorVersionWithRevision ({revision_version}) does not match m_EditorVersion ({version})"
+                );
+                return None;
+            }
+            Some(revision)
+        });
+
+        Ok(Self {
+            version,
+            revision: revision.map(Into::into),
+            raw: raw.into(),
+        })
+    }
+
+    pub fn version(&self) -> UnityVersion {
+        self.version
+    }
+
+    /// The changeset of the editor, like `887be4894c44`
+    pub fn revision(&self) -> Option<&str> {
+        self.revision.as_deref()
+    }
+
+    /// The `m_EditorVersion` line the version is parsed from.
+    pub fn raw_line(&self) -> &str {
+        &self.raw
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(unquoted) = value
+            .strip_prefix(quote)
+            .and_then(|x| x.strip_suffix(quote))
+        {
+            return unquoted.trim();
+        }
+    }
+    value
+}
+
+fn parse_version_with_revision(value: &str) -> Option<(UnityVersion, &str)> {
+    let (version, revision) = value.split_once('(')?;
+    let (revision, _) = revision.split_once(')')?;
+    let version = UnityVersion::parse(version.trim())?;
+    let revision = revision.trim();
+    if revision.is_empty() {
+        return None;
+    }
+
+    Some((version, revision))
+}
+
+/// The error reading `ProjectSettings/ProjectVersion.txt`
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ProjectVersionError {
+    /// `ProjectVersion.txt` does not exist. This usually means the folder is not a Unity project.
+    NotFound,
+    /// Failed to read `ProjectVersion.txt`.
+    Io(io::Error),
+    /// `ProjectVersion.txt` does not have `m_EditorVersion`.
+    MissingEditorVersion,
+    /// `m_EditorVersion` exists but cannot be parsed.
+    InvalidEditorVersion { raw: Box<str> },
+}
+
+impl fmt::Display for ProjectVersionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProjectVersionError::NotFound => f.write_str("ProjectVersion.txt not found"),
+            ProjectVersionError::Io(e) => write!(f, "reading ProjectVersion.txt failed: {e}"),
+            ProjectVersionError::MissingEditorVersion => {
+                f.write_str("m_EditorVersion not found in ProjectVersion.txt")
+            }
+            ProjectVersionError::InvalidEditorVersion { raw } => {
+                write!(
+                    f,
+                    "failed to parse m_EditorVersion in ProjectVersion.txt: {raw}"
+                )
+            }
+        }
+    }
+}
+
+impl ErrorCode for ProjectVersionError {
+    fn code(&self) -> &'static str {
+        match self {
+            ProjectVersionError::NotFound => "project-version.not-found",
+            ProjectVersionError::Io(_) => "project-version.read-failed",
+            ProjectVersionError::MissingEditorVersion => "project-version.missing-editor-version",
+            ProjectVersionError::InvalidEditorVersion { .. } => {
+                "project-version.invalid-editor-version"
+            }
+        }
+    }
+
+    fn params(&self) -> IndexMap<&'static str, ErrorParam> {
+        match self {
+            ProjectVersionError::Io(error) => {
+                IndexMap::from([("message", ErrorParam::text(error))])
+            }
+            ProjectVersionError::InvalidEditorVersion { raw } => {
+                IndexMap::from([("raw", ErrorParam::text(raw))])
+            }
+            ProjectVersionError::NotFound | ProjectVersionError::MissingEditorVersion => {
+                IndexMap::new()
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProjectVersionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ProjectVersionError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl<IO: ProjectIo> UnityProject<IO> {
+    pub(super) async fn read_project_version(
+        io: &IO,
+    ) -> Result<ProjectVersion, ProjectVersionError> {
+        let mut file = match io.open(PROJECT_VERSION_PATH.as_ref()).await {
+            Ok(file) => file,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => {
+                return Err(ProjectVersionError::NotFound);
+            }
+            Err(e) => return Err(ProjectVersionError::Io(e)),
+        };
+
+        let mut buffer = Vec::new();
+        file.read_to_end(&mut buffer)
+            .await
+            .map_err(ProjectVersionError::Io)?;
+
+        ProjectVersion::parse(&String::from_utf8_lossy(&buffer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unity_2019() {
+        let parsed = ProjectVersion::parse(
+            "m_EditorVersion: 2019.4.31f1\nm_EditorVersionWithRevision: 2019.4.31f1 (bd5abf232a62)\n",
+        )
+        .unwrap();
+        assert_eq!(parsed.version(), UnityVersion::new_f1(2019, 4, 31));
+        assert_eq!(parsed.revision(), Some("bd5abf232a62"));
+        assert_eq!(parsed.raw_line(), "m_EditorVersion: 2019.4.31f1");
+    }
+
+    #[test]
+    fn unity_2022_crlf_bom() {
+        let parsed = ProjectVersion::parse(
+            "\u{feff}m_EditorVersion: 2022.3.22f1\r\nm_EditorVersionWithRevision: 2022.3.22f1 (887be4894c44)\r\n",
+        )
+        .unwrap();
+        assert_eq!(parsed.version(), UnityVersion::new_f1(2022, 3, 22));
+        assert_eq!(parsed.revision(), Some("887be4894c44"));
+        assert_eq!(parsed.raw_line(), "m_EditorVersion: 2022.3.22f1");
+    }
+
+    #[test]
+    fn unity_6() {
+        let parsed = ProjectVersion::parse(
+            "m_EditorVersion: 6000.0.23f1\nm_EditorVersionWithRevision: \"6000.0.23f1 (1c4764c07fb4)\"\n",
+        )
+        .unwrap();
+        assert_eq!(parsed.version(), UnityVersion::new_f1(6000, 0, 23));
+        assert_eq!(parsed.revision(), Some("1c4764c07fb4"));
+    }
+
+    #[test]
+    fn without_revision() {
+        let parsed = ProjectVersion::parse("m_EditorVersion: 2022.3.6f1\n").unwrap();
+        assert_eq!(parsed.version(), UnityVersion::new_f1(2022, 3, 6));
+        assert_eq!(parsed.revision(), None);
+
+        // revision for another version is ignored
+        let parsed = ProjectVersion::parse(
+            "m_EditorVersion: 2022.3.22f1\nm_EditorVersionWithRevision: 2019.4.31f1 (bd5abf232a62)\n",
+        )
+        .unwrap();
+        assert_eq!(parsed.revision(), None);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(matches!(
+            ProjectVersion::parse(""),
+            Err(ProjectVersionError::MissingEditorVersion)
+        ));
+        assert!(matches!(
+            ProjectVersion::parse("m_EditorVersion: broken\n"),
+            Err(ProjectVersionError::InvalidEditorVersion { raw }) if &*raw == "m_EditorVersion: broken"
+        ));
+    }
+}
@@ -1,5 +1,7 @@
+use indexmap::IndexMap;
 use std::fmt;
 
+use crate::error::{ErrorCode, ErrorParam};
 use crate::io::ProjectIo;
 use crate::unity_project::package_resolution::MissingDependencies;
 use crate::unity_project::{PendingProjectChanges, pending_project_changes};
@@ -36,10 +38,41 @@ impl fmt::Display for ReinstalPackagesError {
 
 impl std::error::Error for ReinstalPackagesError {}
 
+impl ErrorCode for ReinstalPackagesError {
+    fn code(&self) -> &'static str {
+        match self {
+            ReinstalPackagesError::NotInstalled { .. } => "reinstall.not-installed",
+            ReinstalPackagesError::DependenciesNotFound { .. } => "reinstall.dependency-not-found",
+        }
+    }
+
+    fn params(&self) -> IndexMap<&'static str, ErrorParam> {
+        match self {
+            ReinstalPackagesError::NotInstalled { package_name } => {
+                IndexMap::from([("package_name", ErrorParam::text(package_name))])
+            }
+            ReinstalPackagesError::DependenciesNotFound { dependencies } => {
+                IndexMap::from([("dependencies", ErrorParam::list(dependencies))])
+            }
+        }
+    }
+}
+
 impl<IO: ProjectIo> UnityProject<IO> {
+    /// Creates the changes to reinstall the locked packages with the same version.
+    ///
+    /// The error is [`ReinstalPackagesError`] in [`Error::PackageResolution`](crate::Error::PackageResolution).
     pub async fn reinstall_request<'env>(
         &self,
-        env: &'env impl PackageCollection,
+        env: &'env (impl PackageCollection + ?Sized),
+        packages: &[&str],
+    ) -> Result<PendingProjectChanges<'env>, crate::Error> {
+        Ok(self.reinstall_request_impl(env, packages).await?)
+    }
+
+    async fn reinstall_request_impl<'env>(
+        &self,
+        env: &'env (impl PackageCollection + ?Sized),
         packages: &[&str],
     ) -> Result<PendingProjectChanges<'env>, ReinstalPackagesError> {
         let mut changes = pending_project_changes::Builder::new();
@@ -1,5 +1,7 @@
 use crate::UnityProject;
+use crate::error::{ErrorCode, ErrorParam};
 use crate::io::ProjectIo;
+use indexmap::IndexMap;
 use std::collections::HashSet;
 use std::{fmt, io};
 
@@ -11,9 +13,17 @@ impl<IO: ProjectIo> UnityProject<IO> {
     /// Remove specified package from self project.
     ///
     /// This doesn't look packages not listed in vpm-maniefst.json.
+    /// The error is [`RemovePackageErr`] in [`Error::PackageResolution`](crate::Error::PackageResolution).
     pub async fn remove_request(
         &self,
         remove: &[&str],
+    ) -> Result<PendingProjectChanges<'static>, crate::Error> {
+        Ok(self.remove_request_impl(remove).await?)
+    }
+
+    async fn remove_request_impl(
+        &self,
+        remove: &[&str],
     ) -> Result<PendingProjectChanges<'static>, RemovePackageErr> {
         use RemovePackageErr::*;
 
@@ -104,6 +114,25 @@ impl fmt::Display for RemovePackageErr {
 
 impl std::error::Error for RemovePackageErr {}
 
+impl ErrorCode for RemovePackageErr {
+    fn code(&self) -> &'static str {
+        match self {
+            RemovePackageErr::Io(error) => error.code(),
+            RemovePackageErr::NotInstalled(_) => "remove.not-installed",
+            RemovePackageErr::ConflictsWith(_) => "remove.conflicts",
+        }
+    }
+
+    fn params(&self) -> IndexMap<&'static str, ErrorParam> {
+        match self {
+            RemovePackageErr::Io(error) => error.params(),
+            RemovePackageErr::NotInstalled(names) | RemovePackageErr::ConflictsWith(names) => {
+                IndexMap::from([("package_names", ErrorParam::list(names))])
+            }
+        }
+    }
+}
+
 impl From<io::Error> for RemovePackageErr {
     fn from(value: io::Error) -> Self {
         Self::Io(value)
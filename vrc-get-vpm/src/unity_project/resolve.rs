@@ -1,8 +1,10 @@
 use std::collections::{HashMap, HashSet};
 use std::fmt;
 
+use indexmap::IndexMap;
 use itertools::Itertools;
 
+use crate::error::{ErrorCode, ErrorParam};
 use crate::io::ProjectIo;
 use crate::unity_project::package_resolution::MissingDependencies;
 use crate::unity_project::{
@@ -38,6 +40,22 @@ impl fmt::Display for ResolvePackageErr {
 
 impl std::error::Error for ResolvePackageErr {}
 
+impl ErrorCode for ResolvePackageErr {
+    fn code(&self) -> &'static str {
+        match self {
+            ResolvePackageErr::DependenciesNotFound { .. } => "resolve.dependency-not-found",
+        }
+    }
+
+    fn params(&self) -> IndexMap<&'static str, ErrorParam> {
+        match self {
+            ResolvePackageErr::DependenciesNotFound { dependencies } => {
+                IndexMap::from([("dependencies", ErrorParam::list(dependencies))])
+            }
+        }
+    }
+}
+
 impl<IO: ProjectIo> UnityProject<IO> {
     /// Returns whether the project should be resolved.
     ///
@@ -108,9 +126,19 @@ impl<IO: ProjectIo> UnityProject<IO> {
         false
     }
 
+    /// Creates the changes to install the missing packages of the project.
+    ///
+    /// The error is [`ResolvePackageErr`] in [`Error::PackageResolution`](crate::Error::PackageResolution).
     pub async fn resolve_request<'env>(
         &self,
-        env: &'env impl PackageCollection,
+        env: &'env (impl PackageCollection + ?Sized),
+    ) -> Result<PendingProjectChanges<'env>, crate::Error> {
+        Ok(self.resolve_request_impl(env).await?)
+    }
+
+    pub(crate) async fn resolve_request_impl<'env>(
+        &self,
+        env: &'env (impl PackageCollection + ?Sized),
     ) -> Result<PendingProjectChanges<'env>, ResolvePackageErr> {
         let mut changes = pending_project_changes::Builder::new();
         let mut missing_dependencies = MissingDependencies::new();
@@ -144,7 +172,7 @@ impl<IO: ProjectIo> UnityProject<IO> {
 
     fn add_just_dependency<'env>(
         &self,
-        env: &'env impl PackageCollection,
+        env: &'env (impl PackageCollection + ?Sized),
         changes: &mut pending_project_changes::Builder<'env>,
         missing_dependencies: &mut MissingDependencies,
     ) -> Result<(), ResolvePackageErr> {
@@ -206,7 +234,7 @@ impl<IO: ProjectIo> UnityProject<IO> {
 
     fn resolve_unlocked<'env>(
         &self,
-        env: &'env impl PackageCollection,
+        env: &'env (impl PackageCollection + ?Sized),
         changes: &mut pending_project_changes::Builder<'env>,
         missing_dependencies: &mut MissingDependencies,
     ) -> Result<(), ResolvePackageErr> {
@@ -276,7 +304,7 @@ impl<IO: ProjectIo> UnityProject<IO> {
                 if let Some(pkg) = env.find_package_by_name(
                     pkg_name,
                     VersionSelector::ranges_for(
-                        self.unity_version,
+                        self.unity_version(),
                         &ranges,
                         PrereleaseAcceptance::allow_or_minimum(allow_prerelease),
                     ),
@@ -0,0 +1,72 @@
+use crate::event::{Event, EventObserver};
+use crate::io::{FileOperation, OperationRecorder, ProjectIo, RecordingIo};
+use crate::traits::AbortCheck;
+use crate::unity_project::PendingProjectChanges;
+use crate::{PackageInfo, PackageInstaller, UnityProject, io};
+
+impl<IO: ProjectIo> UnityProject<IO>
+where
+    IO::DirEntry: Send + Sync,
+{
+    /// Simulates [`apply_pending_changes`](Self::apply_pending_changes) and returns
+    /// the file operations it would do, in the order.
+    ///
+    /// The changes are applied to the project loaded again with [`RecordingIo`],
+    /// so the project is not changed, and unsaved changes to this project are not reflected.
+    /// The packages are not downloaded; the packages not in the cache are listed as
+    /// [`FileOperation::Download`] without the files in the package.
+    /// The package store is not used even if enabled, to list the files in the project.
+    pub async fn simulate_pending_changes<'env, Env: PackageInstaller>(
+        &self,
+        env: &'env Env,
+        changes: PendingProjectChanges<'env>,
+    ) -> io::Result<Vec<FileOperation>> {
+        let mut project = UnityProject::load(RecordingIo::new(&self.io)).await?;
+        project.ignore_unity_lock = self.ignore_unity_lock;
+        project.allow_incompatible_sdk = self.allow_incompatible_sdk;
+        // the lock file would be recorded as the operations
+        project.ignore_project_lock = true;
+
+        let installer = SimulatedInstaller {
+            inner: env,
+            recorder: project.io.recorder(),
+        };
+        project
+            .apply_pending_changes_impl(&installer, changes, &AbortCheck::new())
+            .await?;
+
+        Ok(project.io.operations())
+    }
+}
+
+/// The installer simulating the installation with [`PackageInstaller::simulate_install_package`].
+struct SimulatedInstaller<'a, Env> {
+    inner: &'a Env,
+    recorder: OperationRecorder,
+}
+
+impl<Env: PackageInstaller> PackageInstaller for SimulatedInstaller<'_, Env> {
+    async fn install_package(
+        &self,
+        io: &impl ProjectIo,
+        package: PackageInfo<'_>,
+        abort: &AbortCheck,
+    ) -> io::Result<()> {
+        abort.check()?;
+        for operation in self.inner.simulate_install_package(io, package).await? {
+            self.recorder.record(operation);
+        }
+        Ok(())
+    }
+
+    fn observer(&self) -> &dyn EventObserver {
+        &IgnoreObserver
+    }
+}
+
+/// The simulation should not be reported as the real changes
+struct IgnoreObserver;
+
+impl EventObserver for IgnoreObserver {
+    fn on_event(&self, _: &Event<'_>) {}
+}
@@ -0,0 +1,128 @@
+use crate::error::ErrorCode;
+use crate::io;
+use crate::io::ProjectIo;
+use crate::unity_project::UnityProject;
+use log::warn;
+use std::fmt;
+
+const UNITY_LOCKFILE_PATH: &str = "Temp/UnityLockfile";
+
+/// Whether the project is open in the Unity editor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum UnityLockState {
+    /// `Temp/UnityLockfile` does not exist.
+    NotOpen,
+    /// `Temp/UnityLockfile` exists and is locked by the Unity editor.
+    Open,
+    /// `Temp/UnityLockfile` exists but we could not confirm it's locked.
+    ///
+    /// This usually means the Unity editor crashed, or the lock cannot be checked on this platform.
+    PossiblyStale,
+}
+
+/// The error returned when modifying a project that is open in Unity.
+///
+/// This is returned as the inner error of [`io::Error`] with [`io::ErrorKind::ResourceBusy`].
+#[derive(Debug)]
+pub struct ProjectOpenInUnityError {
+    state: UnityLockState,
+}
+
+impl ProjectOpenInUnityError {
+    pub(crate) fn new(state: UnityLockState) -> Self {
+        Self { state }
+    }
+
+    /// Returns true if the lockfile may be left by a crashed editor.
+    ///
+    /// Callers can offer to continue with [`UnityProject::set_ignore_unity_lock`].
+    pub fn is_possibly_stale(&self) -> bool {
+        self.state == UnityLockState::PossiblyStale
+    }
+}
+
+impl fmt::Display for ProjectOpenInUnityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_possibly_stale() {
+            f.write_str("project may be open in Unity (Temp/UnityLockfile exists but may be stale)")
+        } else {
+            f.write_str("project is open in Unity")
+        }
+    }
+}
+
+impl std::error::Error for ProjectOpenInUnityError {}
+
+impl ErrorCode for ProjectOpenInUnityError {
+    fn code(&self) -> &'static str {
+        if self.is_possibly_stale() {
+            "project.possibly-open-in-unity"
+        } else {
+            "project.open-in-unity"
+        }
+    }
+}
+
+/// Checks if the project at `io` is open in the Unity editor.
+pub async fn unity_lock_state(io: &impl ProjectIo) -> io::Result<UnityLockState> {
+    if !io.is_file(UNITY_LOCKFILE_PATH.as_ref()).await {
+        return Ok(UnityLockState::NotOpen);
+    }
+
+    match io.is_exclusively_locked(UNITY_LOCKFILE_PATH.as_ref()).await {
+        Ok(Some(true)) => Ok(UnityLockState::Open),
+        Ok(Some(false) | None) => Ok(UnityLockState::PossiblyStale),
+        // the file is removed after we checked existence
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(UnityLockState::NotOpen),
+        Err(e) => Err(e),
+    }
+}
+
+/// Fails with [`ProjectOpenInUnityError`] if the project at `io` is confirmed to be open in Unity.
+///
+/// The [possibly stale](UnityLockState::PossiblyStale) lockfile is only warned,
+/// since the lock cannot be checked on some platforms.
+pub async fn check_not_open_in_unity(io: &impl ProjectIo) -> io::Result<()> {
+    match unity_lock_state(io).await? {
+        UnityLockState::Open => Err(io::Error::new(
+            io::ErrorKind::ResourceBusy,
+            ProjectOpenInUnityError::new(UnityLockState::Open),
+        )),
+        UnityLockState::PossiblyStale => {
+            warn!(
+                "{UNITY_LOCKFILE_PATH} exists but it's not locked, the project may be open in Unity"
+            );
+            Ok(())
+        }
+        UnityLockState::NotOpen => Ok(()),
+    }
+}
+
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Returns true if the project is open, or possibly open in the Unity editor.
+    ///
+    /// Use [`unity_lock_state`](Self::unity_lock_state) to distinguish stale lockfiles.
+    pub async fn is_open_in_unity(&self) -> io::Result<bool> {
+        Ok(self.unity_lock_state().await? != UnityLockState::NotOpen)
+    }
+
+    pub async fn unity_lock_state(&self) -> io::Result<UnityLockState> {
+        unity_lock_state(&self.io).await
+    }
+
+    /// Allows modifying the project even if it's open in Unity.
+    pub fn set_ignore_unity_lock(&mut self, ignore: bool) {
+        self.ignore_unity_lock = ignore;
+    }
+
+    /// Fails with [`ProjectOpenInUnityError`] if the project is confirmed to be open in Unity.
+    ///
+    /// See [`check_not_open_in_unity`](fn@check_not_open_in_unity) for details.
+    pub async fn check_not_open_in_unity(&self) -> io::Result<()> {
+        if self.ignore_unity_lock {
+            return Ok(());
+        }
+        check_not_open_in_unity(&self.io).await
+    }
+}
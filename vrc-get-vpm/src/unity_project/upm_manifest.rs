@@ -1,3 +1,4 @@
+use crate::ScopedRegistry;
 use crate::io;
 use crate::io::ProjectIo;
 use crate::utils::{JsonMapExt, SaveController, load_json_or_default, save_json};
@@ -11,13 +12,13 @@ use std::str::FromStr;
 
 const MANIFEST_PATH: &str = "Packages/manifest.json";
 
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 struct Parsed {
     #[serde(default)]
     dependencies: HashMap<Box<str>, UpmDependency>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub(super) enum UpmDependency {
     // minimum version name. build meta is not supported by upm
@@ -67,7 +68,7 @@ impl<'de> Deserialize<'de> for UpmDependency {
     }
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 struct AsJson {
     as_json: Parsed,
     raw: Map<String, Value>,
@@ -98,7 +99,7 @@ impl Serialize for AsJson {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(super) struct UpmManifest {
     controller: SaveController<AsJson>,
 }
@@ -112,7 +113,20 @@ impl UpmManifest {
         })
     }
 
-    #[allow(dead_code)]
+    pub(super) fn empty() -> Self {
+        Self {
+            controller: SaveController::new(Default::default()),
+        }
+    }
+
+    pub(super) fn is_changed(&self) -> bool {
+        self.controller.is_changed()
+    }
+
+    pub(super) fn mark_changed(&mut self) {
+        self.controller.mark_changed()
+    }
+
     pub(super) fn dependencies(&self) -> impl Iterator<Item = (&str, &UpmDependency)> {
         self.controller
             .as_json
@@ -126,7 +140,6 @@ impl UpmManifest {
         self.controller.as_json.dependencies.get(package)
     }
 
-    #[allow(dead_code)]
     pub(super) fn add_dependency(&mut self, name: &str, version: Version) {
         self.controller
             .as_mut()
@@ -152,6 +165,76 @@ impl UpmManifest {
         self.controller.as_mut().as_json.dependencies.remove(name);
     }
 
+    /// Adds the dependency unless the same or newer version, or non-version notation like git url, is already specified.
+    pub(super) fn ensure_dependency(&mut self, name: &str, version: &Version) {
+        match self.get_dependency(name) {
+            Some(UpmDependency::Version(current)) if current >= version => {}
+            Some(UpmDependency::OtherNotation(_)) => {}
+            _ => self.add_dependency(name, version.clone()),
+        }
+    }
+
+    /// Checks `scopedRegistries` can be modified by [`ensure_scoped_registry`](Self::ensure_scoped_registry).
+    pub(super) fn check_scoped_registries(&self) -> io::Result<()> {
+        match self.controller.raw.get("scopedRegistries") {
+            None | Some(Value::Array(_)) => Ok(()),
+            Some(_) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "scopedRegistries in Packages/manifest.json is not an array",
+            )),
+        }
+    }
+
+    /// Adds the scoped registry, or adds missing scopes if the registry with the same url already exists.
+    ///
+    /// Returns [`io::ErrorKind::InvalidData`] if `scopedRegistries` is not an array.
+    pub(super) fn ensure_scoped_registry(&mut self, registry: &ScopedRegistry) -> io::Result<()> {
+        self.check_scoped_registries()?;
+        let same_url = |x: &Value| {
+            x.get("url")
+                .and_then(Value::as_str)
+                .map(|x| x.trim_end_matches('/'))
+                == Some(registry.url().trim_end_matches('/'))
+        };
+        let registries = self.controller.raw.get("scopedRegistries");
+        let existing = registries
+            .and_then(Value::as_array)
+            .and_then(|x| x.iter().position(same_url));
+
+        let Some(index) = existing else {
+            let value = serde_json::to_value(registry).expect("serializing registry");
+            self.controller
+                .as_mut()
+                .raw
+                .get_or_put_mut("scopedRegistries", Vec::<Value>::new)
+                .as_array_mut()
+                .expect("checked above")
+                .push(value);
+            return Ok(());
+        };
+
+        let has_scope = |scope: &str| {
+            (registries.unwrap()[index].get("scopes"))
+                .and_then(Value::as_array)
+                .is_some_and(|x| x.iter().any(|x| x.as_str() == Some(scope)))
+        };
+        let missing = (registry.scopes().iter())
+            .filter(|scope| !has_scope(scope))
+            .map(|scope| Value::String(scope.to_string()))
+            .collect::<Vec<_>>();
+
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let existing = &mut self.controller.as_mut().raw["scopedRegistries"][index];
+        match existing.get_mut("scopes").and_then(Value::as_array_mut) {
+            Some(scopes) => scopes.extend(missing),
+            None => existing["scopes"] = Value::Array(missing),
+        }
+        Ok(())
+    }
+
     pub(super) async fn save(&mut self, io: &impl ProjectIo) -> io::Result<()> {
         self.controller
             .save(|json| save_json(io, MANIFEST_PATH.as_ref(), json))
@@ -1,14 +1,16 @@
 use crate::io;
-use crate::io::ProjectIo;
+use crate::io::{IoTrait, ProjectIo};
 use crate::unity_project::LockedDependencyInfo;
-use crate::utils::{SaveController, load_json_or_default, save_json};
+use crate::utils::{SaveController, load_json_or_default, save_json, try_load_json};
+use crate::validate_package_name;
 use crate::version::{DependencyRange, Version, VersionRange};
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 
 const MANIFEST_PATH: &str = "Packages/vpm-manifest.json";
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct AsJson {
     #[serde(default)]
@@ -29,7 +31,7 @@ struct VpmLockedDependency {
     pub dependencies: Option<IndexMap<Box<str>, VersionRange>>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(super) struct VpmManifest {
     controller: SaveController<AsJson>,
 }
@@ -43,6 +45,20 @@ impl VpmManifest {
         })
     }
 
+    pub(super) fn empty() -> Self {
+        Self {
+            controller: SaveController::new(Default::default()),
+        }
+    }
+
+    pub(super) fn is_changed(&self) -> bool {
+        self.controller.is_changed()
+    }
+
+    pub(super) fn mark_changed(&mut self) {
+        self.controller.mark_changed()
+    }
+
     pub(super) fn dependencies(&self) -> impl Iterator<Item = (&str, &DependencyRange)> {
         self.controller
             .dependencies
@@ -94,6 +110,21 @@ impl VpmManifest {
         );
     }
 
+    /// The package names in the manifest, including the dependencies of the locked packages,
+    /// that cannot be used as folder names.
+    pub(super) fn invalid_package_names(&self) -> IndexSet<&str> {
+        let locked = &self.controller.locked;
+        let locked_dependencies =
+            (locked.values()).flat_map(|x| x.dependencies.iter().flat_map(IndexMap::keys));
+
+        (locked.keys())
+            .chain(self.controller.dependencies.keys())
+            .chain(locked_dependencies)
+            .map(|name| &**name)
+            .filter(|name| validate_package_name(name).is_err())
+            .collect()
+    }
+
     pub(crate) fn remove_packages<'a>(&mut self, names: impl Iterator<Item = &'a str>) {
         for name in names {
             self.controller.as_mut().locked.shift_remove(name);
@@ -111,3 +142,18 @@ impl VpmManifest {
             .await
     }
 }
+
+/// Reads the name and version of the locked packages of the project at `project_dir` without loading the project.
+///
+/// Returns None if the project doesn't have `vpm-manifest.json`.
+pub(crate) async fn read_locked_packages(
+    io: &impl IoTrait,
+    project_dir: &Path,
+) -> io::Result<Option<Vec<(Box<str>, Version)>>> {
+    let manifest = try_load_json::<AsJson>(io, &project_dir.join(MANIFEST_PATH)).await?;
+    Ok(manifest.map(|manifest| {
+        (manifest.locked.into_iter())
+            .map(|(name, locked)| (name, locked.version))
+            .collect()
+    }))
+}
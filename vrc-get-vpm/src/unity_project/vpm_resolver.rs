@@ -0,0 +1,85 @@
+use crate::io::ProjectIo;
+use crate::unity_project::is_unlocked_package_of;
+use crate::unity_project::{AddPackageErr, AddPackageOperation, PendingProjectChanges};
+use crate::version::UnityVersion;
+use crate::{PackageCollection, PackageInfo, UnityProject, VersionSelector};
+use log::warn;
+
+/// The package resolving VPM packages in the Unity editor, included in the projects created by VCC.
+pub(crate) const VPM_RESOLVER: &str = "com.vrchat.core.vpm-resolver";
+
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Returns true if `com.vrchat.core.vpm-resolver` is not installed in the VPM project
+    /// and the user did not [exclude](Self::set_vpm_resolver_excluded) it.
+    ///
+    /// The projects without any VPM packages are not reported.
+    pub fn is_vpm_resolver_missing(&self) -> bool {
+        self.is_vpm_resolver_missing_after(false)
+    }
+
+    /// Creates the changes to add `com.vrchat.core.vpm-resolver` to the project if it's
+    /// [missing](Self::is_vpm_resolver_missing).
+    ///
+    /// Returns `None` if nothing should be added, including when the package is not found in `env`.
+    /// You should call `apply_pending_changes` to apply the changes after confirming to the user.
+    pub async fn vpm_resolver_request<'env>(
+        &self,
+        env: &'env (impl PackageCollection + ?Sized),
+    ) -> Result<Option<PendingProjectChanges<'env>>, crate::Error> {
+        Ok(self.vpm_resolver_request_impl(env).await?)
+    }
+
+    pub(crate) async fn vpm_resolver_request_impl<'env>(
+        &self,
+        env: &'env (impl PackageCollection + ?Sized),
+    ) -> Result<Option<PendingProjectChanges<'env>>, AddPackageErr> {
+        if !self.is_vpm_resolver_missing() {
+            return Ok(None);
+        }
+        let Some(resolver) = find_vpm_resolver(env, self.unity_version()) else {
+            return Ok(None);
+        };
+
+        let request = self
+            .add_package_request_for_unity(
+                env,
+                &[resolver],
+                AddPackageOperation::InstallToDependencies,
+                false,
+                self.unity_version(),
+            )
+            .await?;
+        Ok(Some(request))
+    }
+
+    /// Returns true if the resolver is [missing](Self::is_vpm_resolver_missing) after the planner
+    /// adds VPM packages if `adding_vpm_packages` is true.
+    ///
+    /// All planners use this to decide whether to add the resolver.
+    pub(crate) fn is_vpm_resolver_missing_after(&self, adding_vpm_packages: bool) -> bool {
+        let has_vpm_packages = adding_vpm_packages
+            || self.dependencies().next().is_some()
+            || self.locked_packages().next().is_some();
+        has_vpm_packages
+            && !self.is_vpm_resolver_excluded()
+            && !self.is_locked(VPM_RESOLVER)
+            && self.manifest.get_dependency(VPM_RESOLVER).is_none()
+            && !(self.unlocked_packages.iter())
+                .any(|(dir, manifest)| is_unlocked_package_of(dir, manifest.as_ref(), VPM_RESOLVER))
+    }
+}
+
+/// Finds the newest `com.vrchat.core.vpm-resolver` compatible with the Unity version.
+pub(crate) fn find_vpm_resolver(
+    env: &(impl PackageCollection + ?Sized),
+    unity_version: Option<UnityVersion>,
+) -> Option<PackageInfo<'_>> {
+    let found = env.find_package_by_name(
+        VPM_RESOLVER,
+        VersionSelector::latest_for(unity_version, false),
+    );
+    if found.is_none() {
+        warn!("{VPM_RESOLVER} not found, the project is left without it");
+    }
+    found
+}
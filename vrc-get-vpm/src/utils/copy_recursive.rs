@@ -1,20 +1,47 @@
-use crate::io;
 use crate::io::{DirEntry, IoTrait};
+use crate::{AbortCheck, io};
 use futures::prelude::*;
 use std::collections::VecDeque;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub(crate) async fn copy_recursive(
     src_io: &impl IoTrait,
     src_dir: PathBuf,
     dst_io: &impl IoTrait,
     dst_dir: PathBuf,
+) -> io::Result<()> {
+    copy_recursive_filtered(
+        src_io,
+        src_dir,
+        dst_io,
+        dst_dir,
+        |_| false,
+        &AbortCheck::new(),
+    )
+    .await
+}
+
+/// Copies `src_dir` to `dst_dir` except for the directories `skip_dir` returns true for.
+///
+/// `skip_dir` receives the path relative to `src_dir`.
+/// The directories linked with symlinks are copied as directories,
+/// and the modification time of the files are preserved.
+pub(crate) async fn copy_recursive_filtered(
+    src_io: &impl IoTrait,
+    src_dir: PathBuf,
+    dst_io: &impl IoTrait,
+    dst_dir: PathBuf,
+    skip_dir: impl Fn(&Path) -> bool,
+    abort: &AbortCheck,
 ) -> io::Result<()> {
     // TODO: parallelize & speedup
     let mut queue = VecDeque::new();
-    queue.push_front((src_dir, dst_dir));
+    queue.push_front(PathBuf::new());
 
-    while let Some((src_dir, dst_dir)) = queue.pop_back() {
+    while let Some(relative) = queue.pop_back() {
+        abort.check()?;
+        let src_dir = src_dir.join(&relative);
+        let dst_dir = dst_dir.join(&relative);
         let mut iter = src_io.read_dir(&src_dir).await?;
         dst_io.create_dir_all(&dst_dir).await?;
         while let Some(entry) = iter.try_next().await? {
@@ -22,15 +49,27 @@ pub(crate) async fn copy_recursive(
             let src = src_dir.join(entry.file_name());
             let dst = dst_dir.join(entry.file_name());
 
-            if file_type.is_file() {
-                let mut src_file = src_io.open(src.as_ref()).await?;
-                let mut dst_file = dst_io.create_new(dst.as_ref()).await?;
-                io::copy(&mut src_file, &mut dst_file).await?;
-            } else if file_type.is_dir() {
-                //copy_recursive(&src, dst_io, &dst).await?;
-                queue.push_front((src, dst));
+            let is_dir = if file_type.is_symlink() {
+                src_io.is_dir(&src).await
             } else {
-                panic!("unknown file type: none of file, dir, symlink")
+                file_type.is_dir()
+            };
+
+            if is_dir {
+                let relative = relative.join(entry.file_name());
+                if !skip_dir(&relative) {
+                    queue.push_front(relative);
+                }
+            } else {
+                abort.check()?;
+                let mut src_file = src_io.open(&src).await?;
+                let mut dst_file = dst_io.create_new(&dst).await?;
+                io::copy(&mut src_file, &mut dst_file).await?;
+                dst_file.flush().await?;
+                drop(dst_file);
+                if let Some(modified) = entry.metadata().await?.modified() {
+                    dst_io.set_modified(&dst, modified).await?;
+                }
             }
         }
     }
@@ -1,21 +1,27 @@
+use crate::AbortCheck;
 use crate::io;
-use crate::io::ProjectIo;
+use crate::io::IoTrait;
 use crate::io::SeekFrom;
 use crate::utils::MapResultExt;
 use async_zip::base::read::seek::ZipFileReader;
 use futures::prelude::*;
 use std::path::{Component, Path};
 
+/// Extracts the zip file to `dest_folder`.
+///
+/// `abort` is checked before each entry. The caller should remove `dest_folder` on error.
 pub(crate) async fn extract_zip(
     mut zip_file: impl AsyncBufRead + AsyncSeek + Unpin,
-    io: &impl ProjectIo,
+    io: &impl IoTrait,
     dest_folder: &Path,
+    abort: &AbortCheck,
 ) -> io::Result<()> {
     // extract zip file
     zip_file.seek(SeekFrom::Start(0)).await?;
 
     let mut zip_reader = ZipFileReader::new(zip_file).await.err_mapped()?;
     for i in 0..zip_reader.file().entries().len() {
+        abort.check()?;
         let entry = &zip_reader.file().entries()[i];
         let Some(filename) = entry.filename().as_str().ok() else {
             return Err(io::Error::new(
@@ -46,7 +52,7 @@ pub(crate) async fn extract_zip(
     Ok(())
 }
 
-fn is_complete_relative(path: &Path) -> bool {
+pub(crate) fn is_complete_relative(path: &Path) -> bool {
     for x in path.components() {
         match x {
             Component::Prefix(_) => return false,
@@ -0,0 +1,48 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::sync::Arc;
+
+/// Shares the equal values with one allocation.
+///
+/// Used to keep the repeated values in the remote repositories, like the package names,
+/// only once in memory.
+pub(crate) struct Interner<T: ?Sized> {
+    values: HashSet<Arc<T>>,
+}
+
+impl<T: ?Sized> Default for Interner<T> {
+    fn default() -> Self {
+        Self {
+            values: HashSet::new(),
+        }
+    }
+}
+
+impl<T: ?Sized + Eq + Hash> Interner<T> {
+    /// Replaces `value` with the equal value shared before, or remembers `value` to share.
+    pub fn share(&mut self, value: &mut Arc<T>) {
+        if let Some(shared) = self.values.get(&**value) {
+            *value = shared.clone();
+        } else {
+            self.values.insert(value.clone());
+        }
+    }
+
+    pub fn share_option(&mut self, value: &mut Option<Arc<T>>) {
+        if let Some(value) = value {
+            self.share(value);
+        }
+    }
+}
+
+impl Interner<str> {
+    pub fn intern(&mut self, value: &str) -> Arc<str> {
+        if let Some(shared) = self.values.get(value) {
+            shared.clone()
+        } else {
+            let value = Arc::<str>::from(value);
+            self.values.insert(value.clone());
+            value
+        }
+    }
+}
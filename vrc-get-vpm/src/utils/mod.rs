@@ -2,25 +2,28 @@ mod copy_recursive;
 mod crlf_json_formatter;
 mod deup_deserializer;
 mod extract_zip;
+mod interner;
 mod save_controller;
 mod sha256_async_write;
 
 use crate::io;
 use crate::io::{DirEntry, IoTrait};
 use async_zip::error::ZipError;
-pub(crate) use copy_recursive::copy_recursive;
+pub(crate) use copy_recursive::{copy_recursive, copy_recursive_filtered};
 pub(crate) use crlf_json_formatter::to_vec_pretty_os_eol;
 pub(crate) use deup_deserializer::DedupForwarder;
 use either::Either;
-pub(crate) use extract_zip::extract_zip;
+pub(crate) use extract_zip::{extract_zip, is_complete_relative};
 use futures::prelude::*;
 use futures::stream::FuturesUnordered;
+pub(crate) use interner::Interner;
 use pin_project_lite::pin_project;
 pub(crate) use save_controller::SaveController;
 use serde::Serialize;
 use serde_json::error::Category;
 use serde_json::{Map, Value};
 pub(crate) use sha256_async_write::Sha256AsyncWrite;
+#[cfg(feature = "reqwest")]
 use std::error::Error;
 use std::path::{Component, Path, PathBuf};
 use std::pin::Pin;
@@ -43,6 +46,7 @@ pub(crate) trait MapResultExt<T> {
     fn err_mapped(self) -> Result<T, Self::Output>;
 }
 
+#[cfg(feature = "reqwest")]
 impl<T> MapResultExt<T> for Result<T, reqwest::Error> {
     type Output = io::Error;
 
@@ -337,6 +341,22 @@ pub(crate) fn normalize_path(input: &Path) -> PathBuf {
     result
 }
 
+/// Returns true if `path` is a symbolic link (or junction on windows).
+///
+/// [`IoTrait::metadata`] follows links so we look up the entry in the parent directory.
+pub(crate) async fn is_symlink(io: &impl IoTrait, path: &Path) -> io::Result<bool> {
+    let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+        return Ok(false);
+    };
+    let mut iter = io.read_dir(parent).await?;
+    while let Some(entry) = iter.try_next().await? {
+        if entry.file_name() == name {
+            return Ok(entry.file_type().await?.is_symlink());
+        }
+    }
+    Ok(false)
+}
+
 #[allow(dead_code)] // used by some features
 pub(crate) fn check_absolute_path(path: impl AsRef<Path>) -> io::Result<()> {
     if !path.as_ref().is_absolute() {
@@ -21,6 +21,15 @@ impl<T> SaveController<T> {
         self.settings_changed = true;
         &mut self.parsed
     }
+
+    /// Marks as changed to save the value even if not modified, like when the value is reverted.
+    pub(crate) fn mark_changed(&mut self) {
+        self.settings_changed = true;
+    }
+
+    pub(crate) fn is_changed(&self) -> bool {
+        self.settings_changed
+    }
 }
 
 impl<T> SaveController<T> {
@@ -85,7 +85,7 @@ use segment::Segment;
 pub use actual_identifier::BuildMetadata;
 pub use actual_identifier::Prerelease;
 use parsing_buf::FromParsingBuf;
-use parsing_buf::ParseVersionError;
+pub(crate) use parsing_buf::ParseVersionError;
 use parsing_buf::ParsingBuf;
 
 #[cfg(test)]
@@ -1,3 +1,4 @@
+use crate::error::ErrorCode;
 use std::fmt::{Display, Formatter};
 
 pub(super) trait FromParsingBuf: Sized {
@@ -71,6 +72,16 @@ impl Display for ParseVersionError {
 
 impl std::error::Error for ParseVersionError {}
 
+impl ErrorCode for ParseVersionError {
+    fn code(&self) -> &'static str {
+        match self.inner {
+            Inner::VersionSegmentTooBig => "version.segment-too-big",
+            Inner::UnexpectedEnd => "version.unexpected-end",
+            Inner::Invalid => "version.invalid",
+        }
+    }
+}
+
 #[derive(Debug)]
 enum Inner {
     VersionSegmentTooBig,
@@ -79,17 +90,17 @@ enum Inner {
 }
 
 impl ParseVersionError {
-    pub(super) fn too_big() -> Self {
+    pub(crate) fn too_big() -> Self {
         Self {
             inner: Inner::VersionSegmentTooBig,
         }
     }
-    pub(super) fn invalid() -> Self {
+    pub(crate) fn invalid() -> Self {
         Self {
             inner: Inner::Invalid,
         }
     }
-    pub(super) fn unexpected_end() -> ParseVersionError {
+    pub(crate) fn unexpected_end() -> ParseVersionError {
         Self {
             inner: Inner::UnexpectedEnd,
         }
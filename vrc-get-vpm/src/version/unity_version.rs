@@ -1,16 +1,17 @@
 use std::cmp::Ordering;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::num::NonZeroU8;
 use std::str::FromStr;
 
 use serde::de::Error as _;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct UnityVersion {
-    // major version such as 2019, 2022, and 6
-    // note: 5 < 2017 < 2023 < 6 < 7 ...
-    // this system assumes if this value is greater than 2000, it's year release
+    // major version such as 2019, 2022, and 6000
+    // note: Unity 6 and later report 6000, 7000, ... so the numeric order is the release order.
+    //   5 < 2017 < 2023 < 6000 < 7000
     major: u16,
     // minor version of unity. usually 1, 2 for tech and 3 for LTS
     minor: u8,
@@ -192,8 +193,9 @@ impl<'de> Deserialize<'de> for UnityVersion {
     where
         D: Deserializer<'de>,
     {
-        UnityVersion::parse(&String::deserialize(deserializer)?)
-            .ok_or_else(|| D::Error::custom("invalid unity version"))
+        let string = String::deserialize(deserializer)?;
+        UnityVersion::parse(&string)
+            .ok_or_else(|| D::Error::custom(format!("invalid unity version: {string}")))
     }
 }
 
@@ -242,6 +244,17 @@ impl PartialEq for ReleaseType {
     }
 }
 
+impl Hash for ReleaseType {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Normal and China are equal so they must have the same hash
+        let normalized = match self {
+            ReleaseType::China => ReleaseType::Normal,
+            other => *other,
+        };
+        std::mem::discriminant(&normalized).hash(state);
+    }
+}
+
 impl PartialOrd for ReleaseType {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(Ord::cmp(self, other))
@@ -354,6 +367,7 @@ mod tests {
         good!("2019.1.0a1", 2019, 1, 0, Alpha, 1);
         good!("2019.1.0b1", 2019, 1, 0, Beta, 1);
         good!("2019.4.31f1", 2019, 4, 31, Normal, 1);
+        good!("6000.0.23f1", 6000, 0, 23, Normal, 1);
         good!("2023.3.6f1", 2023, 3, 6, Normal, 1);
         good!("2023.3.6c1", 2023, 3, 6, China, 1);
         good!("2023.3.6p1", 2023, 3, 6, Patch, 1);
@@ -384,6 +398,66 @@ mod tests {
         test!("5.6.6f1" < "5.6.6f2");
         test!("5.6.6f1" < "2022.1.0f1");
         test!("2022.1.0a1" < "2022.1.0f1");
+        test!("2022.3.22f1" < "6000.0.0b1");
+        test!("6000.0.23f1" < "6000.0.23f2");
+        test!("6000.0.23f1" < "6000.1.0f1");
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        macro_rules! test {
+            ($string: literal) => {
+                let version = UnityVersion::parse($string).unwrap();
+                let json = serde_json::to_string(&version).unwrap();
+                assert_eq!(json, concat!("\"", $string, "\""));
+                let parsed: UnityVersion = serde_json::from_str(&json).unwrap();
+                assert_eq!(parsed, version);
+                assert_eq!(parsed.to_string(), $string);
+            };
+        }
+
+        test!("5.6.6f1");
+        test!("2019.1.0a1");
+        test!("2019.1.0b1");
+        test!("2019.4.31f1");
+        test!("2023.3.6c1");
+        test!("2023.3.6p1");
+        test!("2023.3.6x1");
+        test!("2022.3.22f1c1");
+        test!("6000.0.23f1");
+    }
+
+    #[test]
+    fn deserialize_error() {
+        let error = serde_json::from_str::<UnityVersion>("\"2023.4.6f\"").unwrap_err();
+        assert!(error.to_string().contains("2023.4.6f"));
+
+        serde_json::from_str::<UnityVersion>("2022").unwrap_err();
+    }
+
+    #[test]
+    fn hash_consistent_with_eq() {
+        use std::collections::hash_map::DefaultHasher;
+
+        fn hash_of(version: &str) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            UnityVersion::parse(version).unwrap().hash(&mut hasher);
+            hasher.finish()
+        }
+
+        assert_eq!(
+            UnityVersion::parse("2022.3.22f1"),
+            UnityVersion::parse("2022.3.22c1")
+        );
+        assert_eq!(hash_of("2022.3.22f1"), hash_of("2022.3.22c1"));
+
+        let mut map = std::collections::HashMap::new();
+        map.insert(UnityVersion::parse("2022.3.22f1").unwrap(), "normal");
+        assert_eq!(
+            map.get(&UnityVersion::parse("2022.3.22c1").unwrap()),
+            Some(&"normal")
+        );
+        assert_eq!(map.get(&UnityVersion::parse("2022.3.22p1").unwrap()), None);
     }
 
     #[test]
@@ -46,54 +46,62 @@ impl FromParsingBuf for Version {
         bytes.read('.')?;
         let patch = parse_segment(bytes)?;
 
-        let pre = if let Some(b'-') = bytes.first() {
-            bytes.skip();
-            Prerelease::parse(bytes)?
-        } else {
-            Prerelease::EMPTY
-        };
-
-        let build = if let Some(b'+') = bytes.first() {
-            bytes.skip();
-            BuildMetadata::parse(bytes)?
-        } else {
-            BuildMetadata::EMPTY
-        };
+        let (pre, build) = parse_pre_and_build(bytes)?;
 
-        return Ok(Version {
+        Ok(Version {
             major,
             minor,
             patch,
             pre,
             build,
-        });
+        })
+    }
+}
+
+fn parse_pre_and_build(
+    bytes: &mut ParsingBuf,
+) -> Result<(Prerelease, BuildMetadata), ParseVersionError> {
+    let pre = if let Some(b'-') = bytes.first() {
+        bytes.skip();
+        Prerelease::parse(bytes)?
+    } else {
+        Prerelease::EMPTY
+    };
 
-        fn parse_segment(bytes: &mut ParsingBuf) -> Result<u64, ParseVersionError> {
-            match bytes.first() {
-                Some(b'1'..=b'9') => {
-                    let mut i = 1;
-                    while let Some(b'0'..=b'9') = bytes.get(i) {
-                        i += 1;
-                    }
-                    let str = bytes.take(i);
-                    let value = Segment::from_str(str)
-                        .map_err(|_| ParseVersionError::too_big())?
-                        .as_number()
-                        .unwrap();
-                    Ok(value)
-                }
-                Some(b'0') => {
-                    bytes.skip();
-                    // if 0\d, 0 is invalid char
-                    if let Some(b'0'..=b'9') = bytes.first() {
-                        return Err(ParseVersionError::invalid());
-                    }
-                    Ok(0)
-                }
-                Some(_) => Err(ParseVersionError::invalid()),
-                None => Err(ParseVersionError::invalid()),
+    let build = if let Some(b'+') = bytes.first() {
+        bytes.skip();
+        BuildMetadata::parse(bytes)?
+    } else {
+        BuildMetadata::EMPTY
+    };
+
+    Ok((pre, build))
+}
+
+fn parse_segment(bytes: &mut ParsingBuf) -> Result<u64, ParseVersionError> {
+    match bytes.first() {
+        Some(b'1'..=b'9') => {
+            let mut i = 1;
+            while let Some(b'0'..=b'9') = bytes.get(i) {
+                i += 1;
+            }
+            let str = bytes.take(i);
+            let value = Segment::from_str(str)
+                .map_err(|_| ParseVersionError::too_big())?
+                .as_number()
+                .unwrap();
+            Ok(value)
+        }
+        Some(b'0') => {
+            bytes.skip();
+            // if 0\d, 0 is invalid char
+            if let Some(b'0'..=b'9') = bytes.first() {
+                return Err(ParseVersionError::invalid());
             }
+            Ok(0)
         }
+        Some(_) => Err(ParseVersionError::invalid()),
+        None => Err(ParseVersionError::invalid()),
     }
 }
 
@@ -154,10 +162,159 @@ impl Version {
         }
     }
 
+    /// Parses the version string leniently.
+    ///
+    /// This is intended for third-party data like repository JSON in the wild,
+    /// and for our own manifests, use strict parsing with [`FromStr`] instead.
+    ///
+    /// In addition to the strict syntax, this accepts:
+    /// - surrounding whitespaces and a leading `v` or `=`, like `v1.2.3`
+    /// - missing minor or patch component, like `1.2` or `1`, which are filled with zero
+    /// - a fourth numeric component, like `1.2.3.4`, which is truncated
+    ///
+    /// Since the result may lose some information, callers should keep the original string
+    /// if they want to show it to users.
+    /// Use [`Version::is_strict`] to check if the string is conformant to the strict syntax.
+    pub fn parse_lenient(input: &str) -> Result<Version, ParseVersionError> {
+        let input = input.trim();
+        let input = input
+            .strip_prefix(['v', 'V', '='])
+            .unwrap_or(input)
+            .trim_start();
+        let mut bytes = ParsingBuf::new(input);
+
+        let major = parse_segment(&mut bytes)?;
+        let minor = parse_optional_segment(&mut bytes)?;
+        let patch = parse_optional_segment(&mut bytes)?;
+
+        // truncate the fourth component
+        if let Some(b'.') = bytes.first() {
+            bytes.skip();
+            parse_segment(&mut bytes)?;
+        }
+
+        let (pre, build) = parse_pre_and_build(&mut bytes)?;
+
+        if !bytes.is_empty() {
+            return Err(ParseVersionError::invalid());
+        }
+
+        return Ok(Version {
+            major,
+            minor,
+            patch,
+            pre,
+            build,
+        });
+
+        fn parse_optional_segment(bytes: &mut ParsingBuf) -> Result<u64, ParseVersionError> {
+            if let Some(b'.') = bytes.first() {
+                bytes.skip();
+                parse_segment(bytes)
+            } else {
+                Ok(0)
+            }
+        }
+    }
+
+    /// Returns true if the string can be parsed with strict syntax.
+    pub fn is_strict(input: &str) -> bool {
+        Version::from_str(input).is_ok()
+    }
+
+    /// Returns the version with prerelease and build metadata stripped.
+    ///
+    /// `1.2.3-rc.1+build` becomes `1.2.3`.
     pub fn base_version(&self) -> Version {
         Version::new(self.major, self.minor, self.patch)
     }
 
+    /// Returns the next major version, with prerelease and build metadata cleared.
+    ///
+    /// This follows npm's `semver.inc` behavior: a prerelease of a major release
+    /// like `2.0.0-rc.1` is bumped to `2.0.0` instead of `3.0.0`.
+    ///
+    /// Returns `None` if the major component is the largest value we can parse.
+    pub fn bump_major(&self) -> Option<Version> {
+        if self.is_pre() && self.minor == 0 && self.patch == 0 {
+            return Some(self.base_version());
+        }
+        Some(Version::new(bump_segment(self.major)?, 0, 0))
+    }
+
+    /// Returns the next minor version, with prerelease and build metadata cleared.
+    ///
+    /// This follows npm's `semver.inc` behavior: a prerelease of a minor release
+    /// like `1.3.0-rc.1` is bumped to `1.3.0` instead of `1.4.0`.
+    ///
+    /// Returns `None` if the minor component is the largest value we can parse.
+    pub fn bump_minor(&self) -> Option<Version> {
+        if self.is_pre() && self.patch == 0 {
+            return Some(self.base_version());
+        }
+        Some(Version::new(self.major, bump_segment(self.minor)?, 0))
+    }
+
+    /// Returns the next patch version, with prerelease and build metadata cleared.
+    ///
+    /// This follows npm's `semver.inc` behavior: since `1.2.3-rc.1` is a prerelease of `1.2.3`,
+    /// bumping patch version of `1.2.3-rc.1` yields `1.2.3`, not `1.2.4`.
+    ///
+    /// Returns `None` if the patch component is the largest value we can parse.
+    pub fn bump_patch(&self) -> Option<Version> {
+        if self.is_pre() {
+            return Some(self.base_version());
+        }
+        Some(Version::new(
+            self.major,
+            self.minor,
+            bump_segment(self.patch)?,
+        ))
+    }
+
+    /// Returns the next prerelease version, with build metadata cleared.
+    ///
+    /// This follows npm's `semver.inc` with `prerelease`:
+    /// - `1.2.3-rc.1` becomes `1.2.3-rc.2` (the last numeric identifier is incremented)
+    /// - `1.2.3-beta` becomes `1.2.3-beta.0` (`.0` is appended if the last identifier is not numeric)
+    /// - `1.2.3` becomes `1.2.4-0` (a stable version starts prerelease of the next patch)
+    ///
+    /// Returns `None` if the number to be incremented would overflow.
+    pub fn bump_prerelease(&self) -> Option<Version> {
+        if self.is_stable() {
+            let next = self.bump_patch()?;
+            return Some(Version::new_pre(
+                next.major,
+                next.minor,
+                next.patch,
+                Prerelease::new("0").unwrap(),
+            ));
+        }
+
+        let pre = self.pre.as_str();
+        let (rest, last) = match pre.rsplit_once('.') {
+            Some((rest, last)) => (Some(rest), last),
+            None => (None, pre),
+        };
+
+        let new_pre = if last.bytes().all(|b| b.is_ascii_digit()) {
+            let next = u64::from_str(last).ok()?.checked_add(1)?;
+            match rest {
+                Some(rest) => format!("{rest}.{next}"),
+                None => next.to_string(),
+            }
+        } else {
+            format!("{pre}.0")
+        };
+
+        Some(Version::new_pre(
+            self.major,
+            self.minor,
+            self.patch,
+            Prerelease::new(&new_pre).unwrap(),
+        ))
+    }
+
     pub fn is_pre(&self) -> bool {
         !self.is_stable()
     }
@@ -166,3 +323,198 @@ impl Version {
         self.pre.is_empty()
     }
 }
+
+/// Increments the version segment if the result still can be parsed
+fn bump_segment(value: u64) -> Option<u64> {
+    Segment::new(value.checked_add(1)?)?.as_number()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bump() {
+        macro_rules! test {
+            ($method: ident, $from: literal, $to: literal) => {
+                let from = Version::from_str($from).unwrap();
+                let bumped = from.$method().unwrap();
+                assert_eq!(
+                    bumped.to_string(),
+                    $to,
+                    "{}: {}",
+                    stringify!($method),
+                    $from
+                );
+            };
+            ($method: ident, $from: literal, None) => {
+                let from = Version::from_str($from).unwrap();
+                assert!(
+                    from.$method().is_none(),
+                    "{}: {}",
+                    stringify!($method),
+                    $from
+                );
+            };
+        }
+
+        test!(bump_major, "1.2.3", "2.0.0");
+        test!(bump_major, "1.2.3+build", "2.0.0");
+        test!(bump_major, "1.2.3-rc.1", "2.0.0");
+        test!(bump_major, "1.2.0-rc.1", "2.0.0");
+        test!(bump_major, "2.0.0-rc.1", "2.0.0");
+        test!(bump_major, "0.0.0", "1.0.0");
+
+        test!(bump_minor, "1.2.3", "1.3.0");
+        test!(bump_minor, "1.2.3+build", "1.3.0");
+        test!(bump_minor, "1.2.3-rc.1", "1.3.0");
+        test!(bump_minor, "1.3.0-rc.1", "1.3.0");
+        test!(bump_minor, "1.0.0-rc.1", "1.0.0");
+
+        test!(bump_patch, "1.2.3", "1.2.4");
+        test!(bump_patch, "1.2.3+build", "1.2.4");
+        test!(bump_patch, "1.2.3-rc.1", "1.2.3");
+        test!(bump_patch, "1.2.3-rc.1+build", "1.2.3");
+
+        test!(base_version, "1.2.3", "1.2.3");
+        test!(base_version, "1.2.3-rc.1", "1.2.3");
+        test!(base_version, "1.2.3+build", "1.2.3");
+        test!(base_version, "1.2.3-rc.1+build", "1.2.3");
+
+        test!(bump_prerelease, "1.2.3", "1.2.4-0");
+        test!(bump_prerelease, "1.2.3-0", "1.2.3-1");
+        test!(bump_prerelease, "1.2.3-rc.1", "1.2.3-rc.2");
+        test!(bump_prerelease, "1.2.3-rc.9", "1.2.3-rc.10");
+        test!(bump_prerelease, "1.2.3-rc.1+build", "1.2.3-rc.2");
+        test!(bump_prerelease, "1.2.3-beta", "1.2.3-beta.0");
+        test!(
+            bump_prerelease,
+            "1.2.3-alpha.1.beta",
+            "1.2.3-alpha.1.beta.0"
+        );
+
+        // the largest segment we can parse
+        test!(bump_major, "9223372036854775807.0.0", None);
+        test!(bump_minor, "0.9223372036854775807.0", None);
+        test!(bump_patch, "0.0.9223372036854775807", None);
+        test!(bump_prerelease, "0.0.9223372036854775807", None);
+        test!(
+            bump_major,
+            "9223372036854775806.5.5",
+            "9223372036854775807.0.0"
+        );
+        test!(
+            bump_minor,
+            "9223372036854775807.2.3",
+            "9223372036854775807.3.0"
+        );
+        test!(
+            bump_patch,
+            "0.0.9223372036854775807-rc.1",
+            "0.0.9223372036854775807"
+        );
+
+        // prerelease identifiers are not limited in length
+        test!(
+            bump_prerelease,
+            "1.0.0-rc.18446744073709551614",
+            "1.0.0-rc.18446744073709551615"
+        );
+        test!(bump_prerelease, "1.0.0-rc.18446744073709551615", None);
+        test!(bump_prerelease, "1.0.0-rc.99999999999999999999999", None);
+    }
+
+    #[test]
+    fn parse_lenient() {
+        macro_rules! test {
+            ($input: literal, $expected: literal) => {
+                let parsed = Version::parse_lenient($input).expect($input);
+                assert_eq!(parsed.to_string(), $expected, "{}", $input);
+            };
+            ($input: literal, Err) => {
+                Version::parse_lenient($input).expect_err($input);
+            };
+        }
+
+        test!("1.2.3", "1.2.3");
+        test!("1.2.3-rc.1+build", "1.2.3-rc.1+build");
+        test!("v1.2.3", "1.2.3");
+        test!("V1.2.3", "1.2.3");
+        test!("=1.2.3", "1.2.3");
+        test!(" 1.2.3 ", "1.2.3");
+        test!("1.2", "1.2.0");
+        test!("1", "1.0.0");
+        test!("v1.2-beta", "1.2.0-beta");
+        test!("1.2.3.4", "1.2.3");
+        test!("1.2.3.4-rc.1", "1.2.3-rc.1");
+
+        test!("", Err);
+        test!("v", Err);
+        test!("1.", Err);
+        test!("1..2", Err);
+        test!("01.2.3", Err);
+        test!("1.2.3.4.5", Err);
+        test!("1.2.3.x", Err);
+        test!("1.2.3-", Err);
+        test!("vv1.2.3", Err);
+        test!("1.2.3 4", Err);
+        test!("x", Err);
+
+        assert!(Version::is_strict("1.2.3"));
+        assert!(!Version::is_strict("v1.2.3"));
+        assert!(!Version::is_strict("1.2"));
+        assert!(!Version::is_strict("1.2.3.4"));
+    }
+
+    #[test]
+    fn parse_lenient_arbitrary_input() {
+        // a tiny xorshift to generate inputs deterministically without extra dependencies
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        const CHARS: &[char] = &[
+            '0', '1', '9', '.', '-', '+', 'v', 'V', '=', ' ', 'a', 'x', '*', 'あ', '\u{FEFF}',
+        ];
+
+        for _ in 0..20000 {
+            let len = (next() % 16) as usize;
+            let input = (0..len)
+                .map(|_| CHARS[(next() % CHARS.len() as u64) as usize])
+                .collect::<String>();
+
+            let Ok(lenient) = Version::parse_lenient(&input) else {
+                continue;
+            };
+
+            // lenient parsing must be superset of strict parsing
+            if let Ok(strict) = Version::from_str(&input) {
+                assert_eq!(strict, lenient, "{input:?}");
+                assert_eq!(strict.build, lenient.build, "{input:?}");
+            }
+
+            // the result of lenient parsing must be valid in strict syntax
+            let printed = lenient.to_string();
+            assert_eq!(Version::from_str(&printed).unwrap(), lenient, "{input:?}");
+        }
+
+        // very long segment must not panic
+        Version::parse_lenient("99999999999999999999999999.0.0").unwrap_err();
+        Version::parse_lenient(&"1.".repeat(1000)).unwrap_err();
+    }
+
+    #[test]
+    fn bump_ordering() {
+        for version in ["1.2.3", "1.2.3-rc.1", "1.0.0-beta", "0.0.0", "2.0.0-0"] {
+            let version = Version::from_str(version).unwrap();
+            assert!(version.bump_major().unwrap() > version);
+            assert!(version.bump_minor().unwrap() > version);
+            assert!(version.bump_patch().unwrap() > version);
+            assert!(version.bump_prerelease().unwrap() > version);
+        }
+    }
+}
@@ -856,6 +856,119 @@ fn do_not_remove_udonsharp_folder_if_guid_mismatch() {
     })
 }
 
+#[test]
+fn protect_dangerous_legacy_folders() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dir("Assets/Scenes")
+            .add_file("Assets/Scenes/Main.unity", "# empty scene")
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(
+                PackageManifest::new("com.anatawa12.malicious", Version::new(1, 0, 0))
+                    .add_legacy_folder("..", "")
+                    .add_legacy_folder("Assets", "")
+                    .add_legacy_folder("Assets\\Scenes", "")
+                    .add_legacy_file("Assets\\Scenes\\Main.unity", ""),
+            )
+            .build();
+
+        let package = collection.get_package("com.anatawa12.malicious", Version::new(1, 0, 0));
+
+        let result = project
+            .add_package_request(
+                &collection,
+                &[package],
+                AddPackageOperation::InstallToDependencies,
+                false,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.package_changes().len(), 1);
+        assert_eq!(result.conflicts().len(), 0);
+
+        assert_eq!(result.remove_legacy_folders(), &[]);
+        assert_eq!(result.remove_legacy_files(), &[]);
+
+        assert_eq!(
+            result
+                .protected_legacy_assets()
+                .iter()
+                .collect::<HashSet<_>>(),
+            [
+                (Path::new("..").into(), "com.anatawa12.malicious"),
+                (Path::new("Assets").into(), "com.anatawa12.malicious"),
+                (Path::new("Assets/Scenes").into(), "com.anatawa12.malicious"),
+                (
+                    Path::new("Assets/Scenes/Main.unity").into(),
+                    "com.anatawa12.malicious"
+                ),
+            ]
+            .iter()
+            .collect::<HashSet<_>>()
+        );
+    })
+}
+
+#[test]
+fn protect_user_protected_paths() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dir("Assets/Scripts/Important")
+            .add_dir("Assets/LegacyFolder")
+            .add_file(
+                "Packages/vrc-get-settings.json",
+                r#"{"protectedPaths": ["Assets/Scripts/Important"]}"#,
+            )
+            .build()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            project.protected_paths(),
+            &["Assets/Scripts/Important".into()]
+        );
+
+        let collection = PackageCollectionBuilder::new()
+            .add(
+                PackageManifest::new("com.anatawa12.package", Version::new(1, 0, 0))
+                    .add_legacy_folder("Assets\\Scripts", "")
+                    .add_legacy_folder("Assets\\LegacyFolder", ""),
+            )
+            .build();
+
+        let package = collection.get_package("com.anatawa12.package", Version::new(1, 0, 0));
+
+        let result = project
+            .add_package_request(
+                &collection,
+                &[package],
+                AddPackageOperation::InstallToDependencies,
+                false,
+            )
+            .await
+            .unwrap();
+
+        // removing parent folder of protected path is also denied
+        assert_eq!(
+            result.protected_legacy_assets(),
+            &[(Path::new("Assets/Scripts").into(), "com.anatawa12.package")]
+        );
+        assert_eq!(
+            result.remove_legacy_folders(),
+            &[(
+                Path::new("Assets/LegacyFolder").into(),
+                "com.anatawa12.package"
+            )]
+        );
+        assert_eq!(result.remove_legacy_files(), &[]);
+    })
+}
+
 //endregion
 
 // region errors
@@ -884,7 +997,8 @@ fn not_found_err() {
             .await
             .expect_err("should fail");
 
-        match &err {
+        assert!(matches!(err, vrc_get_vpm::Error::PackageResolution(_)));
+        match err.downcast_ref::<AddPackageErr>().unwrap() {
             AddPackageErr::DependenciesNotFound { dependencies } => {
                 assert_eq!(dependencies.len(), 1);
                 assert_eq!(dependencies[0].as_ref(), "com.vrchat.base");
@@ -918,7 +1032,8 @@ fn updating_non_locked_package_should_cause_error() {
             .await
             .expect_err("should fail");
 
-        match &err {
+        assert!(matches!(err, vrc_get_vpm::Error::PackageResolution(_)));
+        match err.downcast_ref::<AddPackageErr>().unwrap() {
             AddPackageErr::UpgradingNonLockedPackage { package_name } => {
                 assert_eq!(package_name.as_ref(), "com.vrchat.avatars");
             }
@@ -1003,6 +1118,69 @@ fn downgrade_dependencies() {
     })
 }
 
+#[test]
+fn upgrading_sdk_incompatible_with_unity_should_cause_error() {
+    block_on(async {
+        let mut project = VirtualProjectBuilder::new()
+            .add_dependency("com.vrchat.base", Version::new(3, 4, 2))
+            .add_locked("com.vrchat.base", Version::new(3, 4, 2), &[])
+            .add_file(
+                "ProjectSettings/ProjectVersion.txt",
+                "m_EditorVersion: 2019.4.31f1\n",
+            )
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(PackageManifest::new(
+                "com.vrchat.base",
+                Version::new(3, 4, 2),
+            ))
+            .add(PackageManifest::new("com.vrchat.base", Version::new(3, 5, 0)).with_unity(2022, 3))
+            .build();
+
+        let base_package = collection.get_package("com.vrchat.base", Version::new(3, 5, 0));
+
+        let err = project
+            .add_package_request(
+                &collection,
+                &[base_package],
+                AddPackageOperation::UpgradeLocked,
+                false,
+            )
+            .await
+            .expect_err("should fail");
+
+        assert!(matches!(err, vrc_get_vpm::Error::PackageResolution(_)));
+        match err.downcast_ref::<AddPackageErr>().unwrap() {
+            AddPackageErr::IncompatibleSdkUnity {
+                package_name,
+                package_version,
+                ..
+            } => {
+                assert_eq!(package_name.as_ref(), "com.vrchat.base");
+                assert_eq!(package_version, &Version::new(3, 5, 0));
+            }
+            _ => panic!("unexpected error: {:?}", err),
+        }
+
+        // can be forced
+        project.set_allow_incompatible_sdk(true);
+        let result = project
+            .add_package_request(
+                &collection,
+                &[base_package],
+                AddPackageOperation::UpgradeLocked,
+                false,
+            )
+            .await
+            .unwrap();
+
+        assert!(result.package_changes().contains_key("com.vrchat.base"));
+    })
+}
+
 // endregion
 
 // region conflicts
@@ -0,0 +1,163 @@
+#![cfg(feature = "tokio")]
+
+use std::convert::Infallible;
+use std::fs;
+use std::path::{Path, PathBuf};
+use vrc_get_vpm::environment::{
+    ApplyWithBackupError, AutoBackupOptions, PackageInstaller, Settings,
+    apply_pending_changes_with_backup,
+};
+use vrc_get_vpm::io::{DefaultEnvironmentIo, DefaultProjectIo};
+use vrc_get_vpm::{AbortCheck, UnityProject};
+
+fn run<F: Future>(f: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(f)
+}
+
+fn temp_dir() -> PathBuf {
+    let root = std::env::temp_dir().join(format!("vrc-get-auto-backup-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&root).unwrap();
+    root
+}
+
+/// Creates the project with `com.vrchat.base` installed, and the environment with the backup path.
+async fn setup(root: &Path) -> (DefaultEnvironmentIo, Settings, PathBuf) {
+    let project = root.join("project");
+    let package = project.join("Packages/com.vrchat.base");
+    fs::create_dir_all(&package).unwrap();
+    fs::create_dir_all(project.join("ProjectSettings")).unwrap();
+    fs::write(
+        project.join("Packages/vpm-manifest.json"),
+        r#"{
+            "dependencies": { "com.vrchat.base": { "version": "1.0.0" } },
+            "locked": { "com.vrchat.base": { "version": "1.0.0", "dependencies": {} } }
+        }"#,
+    )
+    .unwrap();
+    fs::write(
+        package.join("package.json"),
+        r#"{"name":"com.vrchat.base","version":"1.0.0"}"#,
+    )
+    .unwrap();
+    fs::write(
+        project.join("ProjectSettings/ProjectVersion.txt"),
+        "m_EditorVersion: 2022.3.22f1\n",
+    )
+    .unwrap();
+
+    let io = DefaultEnvironmentIo::new(root.join("env").into_boxed_path());
+    let mut settings = Settings::load(&io).await.unwrap();
+    settings.set_project_backup_path(root.join("backups").to_str().unwrap());
+    settings.save(&io).await.unwrap();
+
+    (io, settings, project)
+}
+
+#[test]
+fn backup_before_removal() {
+    run(async {
+        let root = temp_dir();
+        let (io, settings, project_path) = setup(&root).await;
+        let installer = PackageInstaller::new(&io, None::<&Infallible>);
+
+        let mut project = UnityProject::load(DefaultProjectIo::new(project_path.clone().into()))
+            .await
+            .unwrap();
+        let changes = project.remove_request(&["com.vrchat.base"]).await.unwrap();
+
+        let backup = apply_pending_changes_with_backup(
+            &settings,
+            &io,
+            &mut project,
+            &installer,
+            changes,
+            &AutoBackupOptions::new().enabled(true),
+            &AbortCheck::new(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert!(backup.path().starts_with(root.join("backups")));
+        assert!(backup.path().is_file());
+        assert!(!project_path.join("Packages/com.vrchat.base").exists());
+
+        fs::remove_dir_all(root).ok();
+    })
+}
+
+#[test]
+fn aborted_before_backup() {
+    run(async {
+        let root = temp_dir();
+        let (io, settings, project_path) = setup(&root).await;
+        let installer = PackageInstaller::new(&io, None::<&Infallible>);
+
+        let mut project = UnityProject::load(DefaultProjectIo::new(project_path.clone().into()))
+            .await
+            .unwrap();
+        let changes = project.remove_request(&["com.vrchat.base"]).await.unwrap();
+
+        let abort = AbortCheck::new();
+        abort.abort();
+
+        let error = apply_pending_changes_with_backup(
+            &settings,
+            &io,
+            &mut project,
+            &installer,
+            changes,
+            &AutoBackupOptions::new().enabled(true),
+            &abort,
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(error, ApplyWithBackupError::Backup(_)));
+        assert!(project_path.join("Packages/com.vrchat.base").exists());
+        assert!(!root.join("backups").exists());
+
+        fs::remove_dir_all(root).ok();
+    })
+}
+
+#[test]
+fn aborted_without_backup() {
+    run(async {
+        let root = temp_dir();
+        let (io, settings, project_path) = setup(&root).await;
+        let installer = PackageInstaller::new(&io, None::<&Infallible>);
+
+        let mut project = UnityProject::load(DefaultProjectIo::new(project_path.clone().into()))
+            .await
+            .unwrap();
+        let changes = project.remove_request(&["com.vrchat.base"]).await.unwrap();
+
+        let abort = AbortCheck::new();
+        abort.abort();
+
+        let error = apply_pending_changes_with_backup(
+            &settings,
+            &io,
+            &mut project,
+            &installer,
+            changes,
+            &AutoBackupOptions::new().enabled(false),
+            &abort,
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(
+            error,
+            ApplyWithBackupError::Apply { backup: None, .. }
+        ));
+        assert!(project_path.join("Packages/com.vrchat.base").exists());
+
+        fs::remove_dir_all(root).ok();
+    })
+}
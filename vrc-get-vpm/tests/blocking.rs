@@ -0,0 +1,106 @@
+#![cfg(feature = "blocking")]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use vrc_get_vpm::blocking::{Environment, UnityProject};
+use vrc_get_vpm::io::DefaultEnvironmentIo;
+use vrc_get_vpm::version::Version;
+
+const PACKAGE_NAME: &str = "com.anatawa12.package";
+
+fn temp_dir() -> PathBuf {
+    let root = std::env::temp_dir().join(format!("vrc-get-blocking-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&root).unwrap();
+    root
+}
+
+fn create_project(root: &Path) -> PathBuf {
+    let project = root.join("project");
+    fs::create_dir_all(project.join("Packages").join(PACKAGE_NAME)).unwrap();
+    fs::write(
+        project.join("Packages/vpm-manifest.json"),
+        format!(
+            r#"{{
+                "dependencies": {{ "{PACKAGE_NAME}": {{ "version": "1.0.0" }} }},
+                "locked": {{ "{PACKAGE_NAME}": {{ "version": "1.0.0", "dependencies": {{}} }} }}
+            }}"#
+        ),
+    )
+    .unwrap();
+    fs::write(
+        project
+            .join("Packages")
+            .join(PACKAGE_NAME)
+            .join("package.json"),
+        format!(r#"{{"name":"{PACKAGE_NAME}","version":"1.0.0"}}"#),
+    )
+    .unwrap();
+    project
+}
+
+#[test]
+fn remove_package() {
+    let root = temp_dir();
+    let env = Environment::new(
+        DefaultEnvironmentIo::new(root.join("env").into_boxed_path()),
+        None,
+    )
+    .unwrap();
+    let project_path = create_project(&root);
+
+    let mut project = UnityProject::load(&project_path).unwrap();
+    assert_eq!(
+        project.get_locked(PACKAGE_NAME).unwrap().version(),
+        &Version::new(1, 0, 0)
+    );
+
+    let changes = project.remove_request(&[PACKAGE_NAME]).unwrap();
+    project.apply_pending_changes(&env, changes).unwrap();
+    project.save().unwrap();
+
+    assert!(!project_path.join("Packages").join(PACKAGE_NAME).exists());
+    let project = UnityProject::load(&project_path).unwrap();
+    assert!(!project.is_locked(PACKAGE_NAME));
+
+    fs::remove_dir_all(root).ok();
+}
+
+#[test]
+fn shared_between_threads() {
+    let root = temp_dir();
+    let env = Environment::new(
+        DefaultEnvironmentIo::new(root.join("env").into_boxed_path()),
+        None,
+    )
+    .unwrap();
+    let project_path = create_project(&root);
+
+    // the runtime is shared, so the operations can be called from any thread
+    std::thread::scope(|scope| {
+        let threads = (0..4)
+            .map(|_| scope.spawn(|| UnityProject::load(&project_path).unwrap()))
+            .collect::<Vec<_>>();
+        for thread in threads {
+            let project = thread.join().unwrap();
+            assert!(project.is_locked(PACKAGE_NAME));
+        }
+    });
+
+    let mut project = UnityProject::load(&project_path).unwrap();
+    let changes = project.remove_request(&[PACKAGE_NAME]).unwrap();
+    project.apply_pending_changes(&env, changes).unwrap();
+    assert!(!project_path.join("Packages").join(PACKAGE_NAME).exists());
+
+    fs::remove_dir_all(root).ok();
+}
+
+#[test]
+#[should_panic(expected = "cannot be used in async contexts")]
+fn panics_in_async_context() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        UnityProject::load(std::env::temp_dir()).ok();
+    });
+}
@@ -0,0 +1,228 @@
+use common::*;
+use futures::executor::block_on;
+use futures::prelude::*;
+use indexmap::IndexMap;
+use serde_json::json;
+use std::io;
+use std::path::Path;
+use vrc_get_vpm::environment::PackageInstaller;
+use vrc_get_vpm::io::{IoTrait, ProjectIo};
+use vrc_get_vpm::repository::{LocalCachedRepository, RemoteRepository};
+use vrc_get_vpm::unity_project::AddPackageOperation;
+use vrc_get_vpm::version::Version;
+use vrc_get_vpm::{
+    AbortCheck, HttpClient, HttpRequest, HttpResponse, PackageInfo, PackageInstaller as _,
+    PackageManifest,
+};
+
+mod common;
+
+const PACKAGE_NAME: &str = "com.anatawa12.package";
+const OLD_PACKAGE_JSON: &str = r#"{"name":"com.anatawa12.package","version":"1.0.0"}"#;
+const NEW_PACKAGE_JSON: &str = r#"{"name":"com.anatawa12.package","version":"1.1.0"}"#;
+
+#[test]
+fn abort_check_child() {
+    let parent = AbortCheck::new();
+    let child = parent.child();
+
+    child.abort();
+    assert!(child.check().is_err());
+    assert!(parent.check().is_ok());
+
+    let parent = AbortCheck::new();
+    let child = parent.child();
+    let clone = parent.clone();
+
+    clone.abort();
+    assert!(parent.is_aborted());
+    let error = child.check().unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::Interrupted);
+    assert!(matches!(
+        vrc_get_vpm::Error::from(error),
+        vrc_get_vpm::Error::Cancelled(_)
+    ));
+}
+
+/// The http client never responds, to test aborting requests in flight
+struct PendingHttp;
+
+impl HttpClient for PendingHttp {
+    async fn send(&self, _: HttpRequest<'_>) -> io::Result<HttpResponse<impl AsyncRead + Send>> {
+        future::pending::<()>().await;
+        Ok(HttpResponse::new(200, futures::io::empty()))
+    }
+}
+
+#[test]
+fn abort_download() {
+    block_on(async {
+        let env = VirtualFileSystem::new();
+        let json = json!({
+            "packages": {
+                PACKAGE_NAME: {
+                    "versions": {
+                        "1.0.0": {
+                            "name": PACKAGE_NAME,
+                            "version": "1.0.0",
+                            "url": "https://example.com/package.zip",
+                        }
+                    }
+                }
+            }
+        });
+        let serde_json::Value::Object(json) = json else {
+            unreachable!()
+        };
+        let repository =
+            LocalCachedRepository::new(RemoteRepository::parse(json).unwrap(), IndexMap::new());
+        let manifest = repository
+            .repo()
+            .get_package_version(PACKAGE_NAME, &Version::new(1, 0, 0))
+            .unwrap();
+
+        let project = VirtualProjectBuilder::new().build().await.unwrap();
+
+        let abort = AbortCheck::new();
+        let installer = PackageInstaller::new(&env, Some(&PendingHttp));
+        let (result, ()) = future::join(
+            installer.install_package(
+                project.io(),
+                PackageInfo::remote(manifest, &repository),
+                &abort,
+            ),
+            async { abort.abort() },
+        )
+        .await;
+
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::Interrupted);
+        let zip_path = Path::new("Repos")
+            .join(PACKAGE_NAME)
+            .join(format!("vrc-get-{PACKAGE_NAME}-1.0.0.zip"));
+        assert!(!env.is_file(&zip_path).await);
+        assert!(
+            !project
+                .io()
+                .is_dir(format!("Packages/{PACKAGE_NAME}").as_ref())
+                .await
+        );
+    })
+}
+
+/// Where [`MockInstaller`] aborts the operation
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum AbortPoint {
+    BeforeApply,
+    WhileInstalling,
+    AfterInstalled,
+}
+
+/// The installer writes `package.json` of the package, and aborts at `point`
+struct MockInstaller {
+    abort: AbortCheck,
+    point: AbortPoint,
+}
+
+impl vrc_get_vpm::PackageInstaller for MockInstaller {
+    async fn install_package(
+        &self,
+        io: &impl ProjectIo,
+        package: PackageInfo<'_>,
+        abort: &AbortCheck,
+    ) -> io::Result<()> {
+        abort.check()?;
+        let path = format!("Packages/{}/package.json", package.name());
+        io.create_dir_all(Path::new(&path).parent().unwrap())
+            .await?;
+        io.write(path.as_ref(), NEW_PACKAGE_JSON.as_bytes()).await?;
+
+        match self.point {
+            AbortPoint::BeforeApply => unreachable!(),
+            AbortPoint::WhileInstalling => {
+                self.abort.abort();
+                abort.check()
+            }
+            AbortPoint::AfterInstalled => {
+                self.abort.abort();
+                Ok(())
+            }
+        }
+    }
+}
+
+fn abort_apply(point: AbortPoint) {
+    block_on(async {
+        let mut project = VirtualProjectBuilder::new()
+            .add_dependency(PACKAGE_NAME, Version::new(1, 0, 0))
+            .add_locked(PACKAGE_NAME, Version::new(1, 0, 0), &[])
+            .add_package_json(PACKAGE_NAME, OLD_PACKAGE_JSON)
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(PackageManifest::new(PACKAGE_NAME, Version::new(1, 1, 0)))
+            .build();
+        let package = collection.get_package(PACKAGE_NAME, Version::new(1, 1, 0));
+
+        let changes = project
+            .add_package_request(
+                &collection,
+                &[package],
+                AddPackageOperation::UpgradeLocked,
+                false,
+            )
+            .await
+            .unwrap();
+
+        let manifest_before = read_file(project.io(), "Packages/vpm-manifest.json").await;
+
+        let abort = AbortCheck::new();
+        if point == AbortPoint::BeforeApply {
+            abort.abort();
+        }
+        let installer = MockInstaller {
+            abort: abort.clone(),
+            point,
+        };
+        let error = project
+            .apply_pending_changes_with_abort(&installer, changes, &abort)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, vrc_get_vpm::Error::Cancelled(_)));
+
+        // the project is untouched
+        assert_eq!(
+            read_file(
+                project.io(),
+                &format!("Packages/{PACKAGE_NAME}/package.json")
+            )
+            .await,
+            OLD_PACKAGE_JSON
+        );
+        assert!(!project.io().is_dir("Temp".as_ref()).await);
+        assert_eq!(
+            project.get_locked(PACKAGE_NAME).unwrap().version(),
+            &Version::new(1, 0, 0)
+        );
+        let manifest_after = read_file(project.io(), "Packages/vpm-manifest.json").await;
+        let before: serde_json::Value = serde_json::from_str(&manifest_before).unwrap();
+        let after: serde_json::Value = serde_json::from_str(&manifest_after).unwrap();
+        assert_eq!(before, after);
+    })
+}
+
+#[test]
+fn abort_before_apply() {
+    abort_apply(AbortPoint::BeforeApply);
+}
+
+#[test]
+fn abort_while_installing() {
+    abort_apply(AbortPoint::WhileInstalling);
+}
+
+#[test]
+fn abort_after_installed() {
+    abort_apply(AbortPoint::AfterInstalled);
+}
@@ -0,0 +1,106 @@
+use futures::io::Cursor;
+use std::collections::HashMap;
+use std::io;
+use std::sync::Mutex;
+use url::Url;
+use vrc_get_vpm::{HttpClient, HttpMethod, HttpRequest, HttpResponse};
+
+/// The http client responding with the registered responses, and recording the requests.
+///
+/// The requests to unregistered urls get `404 Not Found`.
+#[derive(Default)]
+pub struct MockHttpClient {
+    responses: HashMap<Url, MockResponse>,
+    requests: Mutex<Vec<RecordedRequest>>,
+}
+
+struct MockResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: HttpMethod,
+    pub url: Url,
+    pub headers: Vec<(String, String)>,
+}
+
+impl RecordedRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl MockHttpClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_response(mut self, url: &str, status: u16, body: impl Into<Vec<u8>>) -> Self {
+        self.responses.insert(
+            Url::parse(url).unwrap(),
+            MockResponse {
+                status,
+                headers: Vec::new(),
+                body: body.into(),
+            },
+        );
+        self
+    }
+
+    /// Responds with the `ETag` header, or `304 Not Modified` for the request with the same `If-None-Match`.
+    pub fn with_etag_response(self, url: &str, etag: &str, body: impl Into<Vec<u8>>) -> Self {
+        let mut result = self.with_response(url, 200, body);
+        let response = result.responses.get_mut(&Url::parse(url).unwrap()).unwrap();
+        response.headers.push(("ETag".into(), etag.into()));
+        result
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl HttpClient for MockHttpClient {
+    async fn send(
+        &self,
+        request: HttpRequest<'_>,
+    ) -> io::Result<HttpResponse<impl futures::AsyncRead + Send>> {
+        let recorded = RecordedRequest {
+            method: request.method(),
+            url: request.url().clone(),
+            headers: (request.headers().iter())
+                .map(|&(name, value)| (name.to_owned(), value.to_owned()))
+                .collect(),
+        };
+
+        let result = match self.responses.get(request.url()) {
+            None => HttpResponse::new(404, Cursor::new(Vec::new())),
+            Some(response) => {
+                let etag = (response.headers.iter())
+                    .find(|(name, _)| name.eq_ignore_ascii_case("ETag"))
+                    .map(|(_, value)| value.as_str());
+                if etag.is_some() && recorded.header("If-None-Match") == etag {
+                    HttpResponse::new(304, Cursor::new(Vec::new()))
+                } else {
+                    let body = match request.method() {
+                        HttpMethod::Head => Vec::new(),
+                        _ => response.body.clone(),
+                    };
+                    (response.headers.iter()).fold(
+                        HttpResponse::new(response.status, Cursor::new(body)),
+                        |result, (name, value)| result.with_header(name.as_str(), value.as_str()),
+                    )
+                }
+            }
+        };
+
+        self.requests.lock().unwrap().push(recorded);
+        Ok(result)
+    }
+}
@@ -2,22 +2,41 @@
 #![allow(dead_code)]
 #![allow(unused_imports)]
 
+mod http_client;
 mod package_collection;
+mod repositories;
 mod virtual_environment;
 mod virtual_file_system;
 mod virtual_project_builder;
 
+pub use http_client::{MockHttpClient, RecordedRequest};
 pub use package_collection::PackageCollection;
 pub use package_collection::PackageCollectionBuilder;
+pub use repositories::{list_latest_package_names, list_latest_packages, load_cached_repositories};
 pub use virtual_environment::VirtualEnvironment;
 pub use virtual_file_system::VirtualFileSystem;
 pub use virtual_project_builder::VirtualProjectBuilder;
 
+use futures::AsyncReadExt;
+use std::path::Path;
 use vrc_get_vpm::PackageInfo;
+use vrc_get_vpm::io::IoTrait;
 use vrc_get_vpm::unity_project::PendingProjectChanges;
 use vrc_get_vpm::unity_project::pending_project_changes::RemoveReason;
 use vrc_get_vpm::version::{DependencyRange, Version};
 
+/// Reads the whole file at `path` as a string.
+pub async fn read_file(io: &impl IoTrait, path: impl AsRef<Path>) -> String {
+    let mut buffer = String::new();
+    io.open(path.as_ref())
+        .await
+        .unwrap()
+        .read_to_string(&mut buffer)
+        .await
+        .unwrap();
+    buffer
+}
+
 pub fn assert_removed(result: &PendingProjectChanges, package: &str, reason: RemoveReason) {
     let package_change = result
         .package_changes()
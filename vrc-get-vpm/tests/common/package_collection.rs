@@ -17,6 +17,20 @@ impl PackageCollectionBuilder {
         self
     }
 
+    /// Adds `com.vrchat.avatars` depending on `com.vrchat.base` of the SDK `version`.
+    ///
+    /// `configure` is applied to both manifests, like adding the Unity version.
+    pub fn add_sdk(
+        self,
+        version: Version,
+        configure: impl Fn(PackageManifest) -> PackageManifest,
+    ) -> PackageCollectionBuilder {
+        let avatars = PackageManifest::new("com.vrchat.avatars", version.clone())
+            .add_vpm_dependency("com.vrchat.base", &version.to_string());
+        let base = PackageManifest::new("com.vrchat.base", version);
+        self.add(configure(avatars)).add(configure(base))
+    }
+
     pub fn build(self) -> PackageCollection {
         PackageCollection {
             packages: self.packages,
@@ -36,15 +50,25 @@ impl PackageCollection {
 }
 
 impl vrc_get_vpm::PackageCollection for PackageCollection {
-    fn get_all_packages(&self) -> impl Iterator<Item = PackageInfo> {
-        self.packages
-            .iter()
-            .map(|(json, path)| PackageInfo::local(json, path))
+    fn get_all_packages(&self) -> Box<dyn Iterator<Item = PackageInfo<'_>> + '_> {
+        Box::new(
+            self.packages
+                .iter()
+                .map(|(json, path)| PackageInfo::local(json, path)),
+        )
     }
 
-    fn find_packages(&self, package: &str) -> impl Iterator<Item = PackageInfo> {
-        self.get_all_packages()
-            .filter(move |pkg| pkg.name() == package)
+    fn find_packages<'a, 'iter>(
+        &'a self,
+        package: &'iter str,
+    ) -> Box<dyn Iterator<Item = PackageInfo<'a>> + 'iter>
+    where
+        'a: 'iter,
+    {
+        Box::new(
+            self.get_all_packages()
+                .filter(move |pkg| pkg.name() == package),
+        )
     }
 
     fn find_package_by_name(
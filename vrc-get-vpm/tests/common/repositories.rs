@@ -0,0 +1,42 @@
+use super::VirtualFileSystem;
+use std::convert::Infallible;
+use vrc_get_vpm::VersionSelector;
+use vrc_get_vpm::environment::{PackageCollection, PackageListingOptions, Settings};
+
+/// Loads the collection from the repository caches written at the paths like `Repos/vrc-official.json`.
+pub async fn load_cached_repositories(files: &[(&str, &str)]) -> PackageCollection {
+    let env = VirtualFileSystem::new();
+    for (path, content) in files {
+        env.add_file(path.as_ref(), content.as_bytes())
+            .await
+            .unwrap();
+    }
+    let settings = Settings::load(&env).await.unwrap();
+    PackageCollection::load(&settings, &env, None::<&Infallible>)
+        .await
+        .unwrap()
+}
+
+/// Lists the name and the version of the latest packages matching `options`, sorted.
+pub fn list_latest_packages(
+    collection: &PackageCollection,
+    options: &PackageListingOptions,
+) -> Vec<(String, String)> {
+    let mut packages = collection
+        .find_whole_all_packages_with(VersionSelector::latest_for(None, true), options, |_| true)
+        .into_iter()
+        .map(|x| (x.name().to_owned(), x.version().to_string()))
+        .collect::<Vec<_>>();
+    packages.sort();
+    packages
+}
+
+/// Lists the name of the latest packages matching `options`, sorted.
+pub fn list_latest_package_names(
+    collection: &PackageCollection,
+    options: &PackageListingOptions,
+) -> Vec<String> {
+    (list_latest_packages(collection, options).into_iter())
+        .map(|(name, _)| name)
+        .collect()
+}
@@ -1,14 +1,18 @@
 use futures::Stream;
 use indexmap::IndexMap;
 use indexmap::map::Entry as IndexEntry;
+use std::collections::HashSet;
 use std::ffi::{OsStr, OsString};
 use std::future::Future;
 use std::io::ErrorKind;
 use std::path::{Component, Path, PathBuf};
 use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
+use std::time::SystemTime;
 use std::{error, io};
+use vrc_get_vpm::event::{EventObserver, LogObserver};
 use vrc_get_vpm::io::{EnvironmentIo, ExitStatus, FileType, IoTrait, Metadata, ProjectIo};
 
 pub(crate) use file_stream::*;
@@ -28,15 +32,38 @@ where
 /// This struct implements All EnvironmentIo and ProjectIo methods.
 pub struct VirtualFileSystem {
     root: DirectoryEntry,
+    observer: Option<Arc<dyn EventObserver>>,
+    shared_settings: Option<PathBuf>,
+    write_count: AtomicUsize,
+    locked_files: Mutex<HashSet<PathBuf>>,
 }
 
 impl VirtualFileSystem {
     pub fn new() -> Self {
         Self {
             root: DirectoryEntry::new(),
+            observer: None,
+            shared_settings: None,
+            write_count: AtomicUsize::new(0),
+            locked_files: Mutex::new(HashSet::new()),
         }
     }
 
+    /// The number of times files are written with [`IoTrait::write`] or [`IoTrait::write_sync`].
+    pub fn write_count(&self) -> usize {
+        self.write_count.load(Ordering::SeqCst)
+    }
+
+    pub fn with_observer(mut self, observer: Arc<dyn EventObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    pub fn with_shared_settings(mut self, path: &Path) -> Self {
+        self.shared_settings = Some(path.to_owned());
+        self
+    }
+
     pub async fn add_file(&self, path: &Path, content: &[u8]) -> io::Result<()> {
         let Some((dir_path, last)) = self.resolve2(path)? else {
             return err(IS_DIRECTORY, "is directory");
@@ -51,6 +78,13 @@ impl VirtualFileSystem {
         Ok(())
     }
 
+    /// Makes [`ProjectIo::is_exclusively_locked`] return true for the file.
+    ///
+    /// The lock of the other files cannot be checked.
+    pub fn lock_exclusively(&self, path: &Path) {
+        self.locked_files.lock().unwrap().insert(path.to_owned());
+    }
+
     pub async fn deny_deletion(&self, path: &Path) -> io::Result<()> {
         let Some((dir_path, last)) = self.resolve2(path)? else {
             return err(IS_DIRECTORY, "is directory");
@@ -106,6 +140,7 @@ impl vrc_get_vpm::io::IoTrait for VirtualFileSystem {
     }
 
     async fn write(&self, path: &Path, content: &[u8]) -> io::Result<()> {
+        self.write_count.fetch_add(1, Ordering::SeqCst);
         let Some((dir_path, last)) = self.resolve2(path)? else {
             return err(IS_DIRECTORY, "is directory");
         };
@@ -146,7 +181,7 @@ impl vrc_get_vpm::io::IoTrait for VirtualFileSystem {
         self.root
             .get_folder(&dir_path)
             .await?
-            .remove_dir_all(last)
+            .remove_dir(last)
             .await?;
         Ok(())
     }
@@ -212,6 +247,23 @@ impl vrc_get_vpm::io::IoTrait for VirtualFileSystem {
             .metadata())
     }
 
+    async fn set_modified(&self, path: &Path, time: SystemTime) -> io::Result<()> {
+        let Some((dir_path, last)) = self.resolve2(path)? else {
+            return err(IS_DIRECTORY, "is directory");
+        };
+        self.root
+            .get_folder(&dir_path)
+            .await?
+            .get(last)
+            .await?
+            .as_file()?
+            .content
+            .lock()
+            .unwrap()
+            .modified = Some(time);
+        Ok(())
+    }
+
     type DirEntry = DirEntry;
     type ReadDirStream = ReadDirStream;
 
@@ -281,6 +333,17 @@ impl EnvironmentIo for VirtualFileSystem {
             .collect()
     }
 
+    fn observer(&self) -> &dyn EventObserver {
+        match &self.observer {
+            Some(observer) => observer.as_ref(),
+            None => &LogObserver,
+        }
+    }
+
+    fn shared_settings_path(&self) -> Option<&Path> {
+        self.shared_settings.as_deref()
+    }
+
     #[cfg(feature = "vrc-get-litedb")]
     type MutexGuard = ();
 
@@ -298,7 +361,15 @@ impl EnvironmentIo for VirtualFileSystem {
     }
 }
 
-impl ProjectIo for VirtualFileSystem {}
+impl ProjectIo for VirtualFileSystem {
+    async fn is_exclusively_locked(&self, path: &Path) -> io::Result<Option<bool>> {
+        if self.locked_files.lock().unwrap().contains(path) {
+            Ok(Some(true))
+        } else {
+            Ok(None)
+        }
+    }
+}
 
 #[derive(Clone)]
 enum FileSystemEntry {
@@ -309,7 +380,14 @@ enum FileSystemEntry {
 impl FileSystemEntry {
     fn metadata(&self) -> Metadata {
         match self {
-            FileSystemEntry::File(_) => Metadata::file(),
+            FileSystemEntry::File(file) => {
+                let content = file.content.lock().unwrap();
+                let metadata = Metadata::file().with_len(content.content.len() as u64);
+                match content.modified {
+                    Some(modified) => metadata.with_modified(modified),
+                    None => metadata,
+                }
+            }
             FileSystemEntry::Directory(_) => Metadata::dir(),
         }
     }
@@ -564,6 +642,7 @@ struct FileEntry {
 struct FileContent {
     content: Vec<u8>,
     locked: bool,
+    modified: Option<SystemTime>,
 }
 
 impl FileContent {
@@ -571,6 +650,7 @@ impl FileContent {
         Self {
             content: Vec::new(),
             locked: false,
+            modified: None,
         }
     }
 }
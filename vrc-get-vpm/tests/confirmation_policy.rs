@@ -0,0 +1,266 @@
+use common::*;
+use futures::executor::block_on;
+use futures::future::BoxFuture;
+use std::sync::Mutex;
+use vrc_get_vpm::PackageManifest;
+use vrc_get_vpm::unity_project::pending_project_changes::RemoveReason;
+use vrc_get_vpm::unity_project::{
+    AddPackageOperation, ChangeClassification, ChangesReport, ConfirmationHandler,
+    ConfirmationPolicy,
+};
+use vrc_get_vpm::version::Version;
+
+mod common;
+
+fn classification(
+    removals: bool,
+    legacy_deletions: bool,
+    downgrades: bool,
+) -> ChangeClassification {
+    let mut classification = ChangeClassification::default();
+    classification.removals = removals;
+    classification.legacy_deletions = legacy_deletions;
+    classification.downgrades = downgrades;
+    classification
+}
+
+#[test]
+fn classify_additive() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.vrchat.base", Version::new(1, 0, 0))
+            .add_locked("com.vrchat.base", Version::new(1, 0, 0), &[])
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(PackageManifest::new(
+                "com.vrchat.base",
+                Version::new(1, 1, 0),
+            ))
+            .add(PackageManifest::new(
+                "com.anatawa12.package",
+                Version::new(1, 0, 0),
+            ))
+            .build();
+
+        let packages = [
+            collection.get_package("com.vrchat.base", Version::new(1, 1, 0)),
+            collection.get_package("com.anatawa12.package", Version::new(1, 0, 0)),
+        ];
+
+        let changes = project
+            .add_package_request(
+                &collection,
+                &packages,
+                AddPackageOperation::InstallToDependencies,
+                false,
+            )
+            .await
+            .unwrap();
+
+        let classification = changes.classify(&project);
+        assert_eq!(classification, self::classification(false, false, false));
+        assert!(classification.is_additive());
+
+        let report = changes.report(&project);
+        assert_eq!(report.installs.len(), 2);
+        assert_eq!(report.installs[0].name.as_ref(), "com.anatawa12.package");
+        assert_eq!(report.installs[0].previous_version, None);
+        assert_eq!(report.installs[1].name.as_ref(), "com.vrchat.base");
+        assert_eq!(
+            report.installs[1].previous_version,
+            Some(Version::new(1, 0, 0))
+        );
+    })
+}
+
+#[test]
+fn classify_removals() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.anatawa12.gists", Version::new(1, 0, 0))
+            .add_locked("com.anatawa12.gists", Version::new(1, 0, 0), &[])
+            .build()
+            .await
+            .unwrap();
+
+        let changes = project
+            .remove_request(&["com.anatawa12.gists"])
+            .await
+            .unwrap();
+
+        let classification = changes.classify(&project);
+        assert_eq!(classification, self::classification(true, false, false));
+        assert!(!classification.is_additive());
+
+        let report = changes.report(&project);
+        assert_eq!(report.removals.len(), 1);
+        assert_eq!(report.removals[0].name.as_ref(), "com.anatawa12.gists");
+        assert_eq!(report.removals[0].reason, RemoveReason::Requested);
+    })
+}
+
+#[test]
+fn classify_legacy_deletions() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dir("Assets/LegacyFolder")
+            .add_file("Assets/LegacyAsset.cs", "// empty file")
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(
+                PackageManifest::new("com.anatawa12.package", Version::new(1, 0, 0))
+                    .add_legacy_folder("Assets\\LegacyFolder", "")
+                    .add_legacy_file("Assets\\LegacyAsset.cs", ""),
+            )
+            .build();
+
+        let package = collection.get_package("com.anatawa12.package", Version::new(1, 0, 0));
+
+        let changes = project
+            .add_package_request(
+                &collection,
+                &[package],
+                AddPackageOperation::InstallToDependencies,
+                false,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            changes.classify(&project),
+            classification(false, true, false)
+        );
+        assert_eq!(changes.report(&project).removed_legacy_assets.len(), 2);
+    })
+}
+
+#[test]
+fn classify_downgrades() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_locked("com.vrchat.base", Version::new(1, 1, 0), &[])
+            .add_dependency("com.vrchat.base", Version::new(1, 0, 0))
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(PackageManifest::new(
+                "com.vrchat.base",
+                Version::new(1, 0, 0),
+            ))
+            .build();
+
+        let package = collection.get_package("com.vrchat.base", Version::new(1, 0, 0));
+
+        let changes = project
+            .add_package_request(
+                &collection,
+                &[package],
+                AddPackageOperation::Downgrade,
+                false,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            changes.classify(&project),
+            classification(false, false, true)
+        );
+    })
+}
+
+struct RecordingHandler {
+    approve: bool,
+    reports: Mutex<Vec<ChangesReport>>,
+}
+
+impl ConfirmationHandler for RecordingHandler {
+    fn confirm<'a>(&'a self, report: &'a ChangesReport) -> BoxFuture<'a, bool> {
+        self.reports.lock().unwrap().push(report.clone());
+        Box::pin(async move { self.approve })
+    }
+}
+
+async fn gists_project() -> vrc_get_vpm::UnityProject<VirtualFileSystem> {
+    VirtualProjectBuilder::new()
+        .add_dependency("com.anatawa12.gists", Version::new(1, 0, 0))
+        .add_locked("com.anatawa12.gists", Version::new(1, 0, 0), &[])
+        .add_dir("Packages/com.anatawa12.gists")
+        .build()
+        .await
+        .unwrap()
+}
+
+#[test]
+fn apply_with_policy() {
+    block_on(async {
+        let env = VirtualEnvironment::new(VirtualFileSystem::new());
+
+        // ProceedIfAdditive cancels the removal
+        let mut project = gists_project().await;
+        let changes = project
+            .remove_request(&["com.anatawa12.gists"])
+            .await
+            .unwrap();
+        let applied = project
+            .apply_pending_changes_with_confirmation(
+                &env,
+                changes,
+                ConfirmationPolicy::ProceedIfAdditive,
+            )
+            .await
+            .unwrap();
+        assert!(!applied);
+        assert!(project.is_locked("com.anatawa12.gists"));
+
+        // the handler receives the report and denies
+        let handler = RecordingHandler {
+            approve: false,
+            reports: Mutex::new(vec![]),
+        };
+        let changes = project
+            .remove_request(&["com.anatawa12.gists"])
+            .await
+            .unwrap();
+        let applied = project
+            .apply_pending_changes_with_confirmation(
+                &env,
+                changes,
+                ConfirmationPolicy::RequireCallback(&handler),
+            )
+            .await
+            .unwrap();
+        assert!(!applied);
+        assert!(project.is_locked("com.anatawa12.gists"));
+        let reports = handler.reports.into_inner().unwrap();
+        assert_eq!(reports.len(), 1);
+        assert!(reports[0].classification.removals);
+
+        // the handler approves
+        let handler = RecordingHandler {
+            approve: true,
+            reports: Mutex::new(vec![]),
+        };
+        let changes = project
+            .remove_request(&["com.anatawa12.gists"])
+            .await
+            .unwrap();
+        let applied = project
+            .apply_pending_changes_with_confirmation(
+                &env,
+                changes,
+                ConfirmationPolicy::RequireCallback(&handler),
+            )
+            .await
+            .unwrap();
+        assert!(applied);
+        assert!(!project.is_locked("com.anatawa12.gists"));
+    })
+}
@@ -0,0 +1,83 @@
+use common::*;
+use futures::executor::block_on;
+use vrc_get_vpm::VersionSelector;
+
+mod common;
+
+const OFFICIAL: &str = r#"{
+    "repo": {
+        "name": "Official",
+        "id": "com.vrchat.repos.official",
+        "packages": {
+            "com.vrchat.base": {
+                "versions": {
+                    "1.0.0": {"name": "com.vrchat.base", "version": "1.0.0"}
+                }
+            }
+        }
+    }
+}"#;
+
+const CURATED: &str = r#"{
+    "repo": {
+        "name": "Curated",
+        "id": "com.vrchat.repos.curated",
+        "packages": {
+            "com.anatawa12.zeta": {
+                "versions": {
+                    "1.0.0": {"name": "com.anatawa12.zeta", "version": "1.0.0", "displayName": "Zeta"},
+                    "1.1.0": {"name": "com.anatawa12.zeta", "version": "1.1.0", "vrc-get": {"yanked": true}},
+                    "2.0.0-beta.1": {"name": "com.anatawa12.zeta", "version": "2.0.0-beta.1"}
+                }
+            },
+            "com.anatawa12.alpha": {
+                "versions": {
+                    "0.1.0": {"name": "com.anatawa12.alpha", "version": "0.1.0"}
+                }
+            }
+        }
+    }
+}"#;
+
+#[test]
+fn curated_packages() {
+    block_on(async {
+        let collection = load_cached_repositories(&[
+            ("Repos/vrc-official.json", OFFICIAL),
+            ("Repos/vrc-curated.json", CURATED),
+        ])
+        .await;
+
+        let packages = collection.curated_packages(VersionSelector::latest_for(None, false));
+        let packages = packages
+            .iter()
+            .map(|x| (x.name(), x.version().to_string(), x.display_name()))
+            .collect::<Vec<_>>();
+        // in the order of the repository, yanked and prerelease versions are skipped
+        assert_eq!(
+            packages,
+            [
+                ("com.anatawa12.zeta", "1.0.0".to_owned(), Some("Zeta")),
+                ("com.anatawa12.alpha", "0.1.0".to_owned(), None),
+            ]
+        );
+
+        let packages = collection.curated_packages(VersionSelector::latest_for(None, true));
+        assert_eq!(packages[0].version().to_string(), "2.0.0-beta.1");
+
+        assert!(collection.is_curated("com.anatawa12.alpha"));
+        assert!(!collection.is_curated("com.vrchat.base"));
+        assert!(!collection.is_curated("com.anatawa12.unknown"));
+    })
+}
+
+#[test]
+fn curated_packages_not_cached() {
+    block_on(async {
+        let collection = load_cached_repositories(&[("Repos/vrc-official.json", OFFICIAL)]).await;
+
+        let packages = collection.curated_packages(VersionSelector::latest_for(None, true));
+        assert!(packages.is_empty());
+        assert!(!collection.is_curated("com.anatawa12.alpha"));
+    })
+}
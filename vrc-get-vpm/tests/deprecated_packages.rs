@@ -0,0 +1,111 @@
+use common::*;
+use futures::executor::block_on;
+use vrc_get_vpm::VersionSelector;
+use vrc_get_vpm::environment::PackageListingOptions;
+
+mod common;
+
+const REPOSITORY: &str = r#"{
+    "repo": {
+        "name": "Official",
+        "id": "com.vrchat.repos.official",
+        "packages": {
+            "com.anatawa12.normal": {
+                "versions": {
+                    "1.0.0": {"name": "com.anatawa12.normal", "version": "1.0.0", "vrc-get": {"deprecated": true}},
+                    "1.1.0": {"name": "com.anatawa12.normal", "version": "1.1.0"}
+                }
+            },
+            "com.anatawa12.yanked": {
+                "versions": {
+                    "1.0.0": {"name": "com.anatawa12.yanked", "version": "1.0.0"},
+                    "2.0.0": {"name": "com.anatawa12.yanked", "version": "2.0.0", "vrc-get": {"yanked": "broken"}}
+                }
+            },
+            "com.anatawa12.all-yanked": {
+                "versions": {
+                    "1.0.0": {"name": "com.anatawa12.all-yanked", "version": "1.0.0", "vrc-get": {"yanked": true}}
+                }
+            },
+            "com.anatawa12.deprecated": {
+                "versions": {
+                    "1.0.0": {"name": "com.anatawa12.deprecated", "version": "1.0.0"},
+                    "1.1.0": {
+                        "name": "com.anatawa12.deprecated",
+                        "version": "1.1.0",
+                        "vrc-get": {"deprecated": "use com.anatawa12.normal instead"}
+                    }
+                }
+            }
+        }
+    }
+}"#;
+
+fn pair(name: &str, version: &str) -> (String, String) {
+    (name.to_owned(), version.to_owned())
+}
+
+#[test]
+fn hidden_by_default() {
+    block_on(async {
+        let collection = load_cached_repositories(&[("Repos/vrc-official.json", REPOSITORY)]).await;
+
+        assert_eq!(
+            list_latest_packages(&collection, &PackageListingOptions::new()),
+            [
+                pair("com.anatawa12.normal", "1.1.0"),
+                pair("com.anatawa12.yanked", "1.0.0"),
+            ]
+        );
+
+        let packages =
+            collection.find_whole_all_packages(VersionSelector::latest_for(None, true), |_| true);
+        assert_eq!(packages.len(), 2);
+    })
+}
+
+#[test]
+fn include_deprecated() {
+    block_on(async {
+        let collection = load_cached_repositories(&[("Repos/vrc-official.json", REPOSITORY)]).await;
+
+        let options = PackageListingOptions::new().include_deprecated(true);
+        assert_eq!(
+            list_latest_packages(&collection, &options),
+            [
+                pair("com.anatawa12.deprecated", "1.1.0"),
+                pair("com.anatawa12.normal", "1.1.0"),
+                pair("com.anatawa12.yanked", "1.0.0"),
+            ]
+        );
+    })
+}
+
+#[test]
+fn installed_deprecated() {
+    block_on(async {
+        let collection = load_cached_repositories(&[("Repos/vrc-official.json", REPOSITORY)]).await;
+
+        let options = PackageListingOptions::new().installed(["com.anatawa12.deprecated"]);
+        assert_eq!(
+            list_latest_packages(&collection, &options),
+            [
+                pair("com.anatawa12.deprecated", "1.1.0"),
+                pair("com.anatawa12.normal", "1.1.0"),
+                pair("com.anatawa12.yanked", "1.0.0"),
+            ]
+        );
+
+        let deprecated = collection
+            .find_whole_all_packages_with(VersionSelector::latest_for(None, true), &options, |x| {
+                x.name() == "com.anatawa12.deprecated"
+            })
+            .pop()
+            .unwrap();
+        assert!(deprecated.is_deprecated());
+        assert_eq!(
+            deprecated.deprecation_message(),
+            Some("use com.anatawa12.normal instead")
+        );
+    })
+}
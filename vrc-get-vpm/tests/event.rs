@@ -0,0 +1,62 @@
+use common::*;
+use futures::executor::block_on;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use vrc_get_vpm::environment::{PackageCollection, Settings};
+use vrc_get_vpm::event::{Event, EventObserver};
+
+mod common;
+
+#[derive(Default)]
+struct RecordingObserver {
+    events: Mutex<Vec<String>>,
+}
+
+impl EventObserver for RecordingObserver {
+    fn on_event(&self, event: &Event<'_>) {
+        let event = match *event {
+            Event::RepositoryRefreshStarted { cache_path, .. } => {
+                format!("started {}", cache_path.display())
+            }
+            Event::RepositoryRefreshFinished {
+                cache_path, error, ..
+            } => format!(
+                "finished {} {}",
+                cache_path.display(),
+                if error.is_some() { "err" } else { "ok" }
+            ),
+            _ => return,
+        };
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+#[test]
+fn repository_refresh_events() {
+    block_on(async {
+        let observer = Arc::new(RecordingObserver::default());
+        let env = VirtualFileSystem::new().with_observer(observer.clone());
+        // the official repository is cached and the curated one is not
+        env.add_file(
+            "Repos/vrc-official.json".as_ref(),
+            br#"{"repo":{"name":"Official","id":"com.vrchat.repos.official","packages":{}}}"#,
+        )
+        .await
+        .unwrap();
+
+        let settings = Settings::load(&env).await.unwrap();
+        PackageCollection::load(&settings, &env, None::<&Infallible>)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *observer.events.lock().unwrap(),
+            [
+                "started Repos/vrc-official.json",
+                "finished Repos/vrc-official.json ok",
+                "started Repos/vrc-curated.json",
+                "finished Repos/vrc-curated.json err",
+            ]
+        );
+    })
+}
@@ -0,0 +1,77 @@
+use common::*;
+use futures::executor::block_on;
+use futures::prelude::*;
+use vrc_get_vpm::io::IoTrait;
+use vrc_get_vpm::version::Version;
+
+mod common;
+
+async fn read_gitignore(
+    project: &vrc_get_vpm::UnityProject<VirtualFileSystem>,
+    path: &str,
+) -> String {
+    let mut buffer = String::new();
+    project
+        .io()
+        .open(path.as_ref())
+        .await
+        .unwrap()
+        .read_to_string(&mut buffer)
+        .await
+        .unwrap();
+    buffer
+}
+
+#[test]
+fn write_gitignore() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_locked("com.vrchat.base", Version::new(3, 7, 0), &[])
+            .add_locked("com.vrchat.avatars", Version::new(3, 7, 0), &[])
+            .build()
+            .await
+            .unwrap();
+
+        assert!(project.write_default_gitignore(false).await.unwrap());
+
+        let gitignore = read_gitignore(&project, ".gitignore").await;
+        let lines = gitignore.lines().collect::<Vec<_>>();
+        assert!(lines.contains(&"/[Ll]ibrary/"));
+        assert!(lines.contains(&"/Packages/com.vrchat.base/"));
+        assert!(lines.contains(&"/Packages/com.vrchat.avatars/"));
+        assert!(!lines.contains(&"/Packages/vpm-manifest.json"));
+    })
+}
+
+#[test]
+fn keep_existing_gitignore() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file(".gitignore", "custom\n")
+            .build()
+            .await
+            .unwrap();
+
+        assert!(!project.write_default_gitignore(false).await.unwrap());
+        assert_eq!(read_gitignore(&project, ".gitignore").await, "custom\n");
+    })
+}
+
+#[test]
+fn overwrite_gitignore() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file(".gitignore", "custom\n")
+            .build()
+            .await
+            .unwrap();
+
+        assert!(project.write_default_gitignore(true).await.unwrap());
+        assert_eq!(read_gitignore(&project, ".gitignore.bak").await, "custom\n");
+        assert!(
+            read_gitignore(&project, ".gitignore")
+                .await
+                .contains("/[Ll]ibrary/")
+        );
+    })
+}
@@ -0,0 +1,111 @@
+use async_zip::base::write::ZipFileWriter;
+use async_zip::{Compression, ZipEntryBuilder};
+use common::*;
+use futures::executor::block_on;
+use futures::prelude::*;
+use indexmap::IndexMap;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::convert::Infallible;
+use std::path::Path;
+use vrc_get_vpm::environment::PackageInstaller;
+use vrc_get_vpm::io::IoTrait;
+use vrc_get_vpm::repository::{LocalCachedRepository, RemoteRepository};
+use vrc_get_vpm::version::Version;
+use vrc_get_vpm::{AbortCheck, PackageInfo, PackageInstaller as _};
+
+mod common;
+
+const PACKAGE_NAME: &str = "com.anatawa12.package";
+
+async fn create_zip(files: &[(&str, &str)]) -> Vec<u8> {
+    let mut writer = ZipFileWriter::new(Vec::new());
+    for (name, content) in files {
+        let entry = ZipEntryBuilder::new(name.to_string().into(), Compression::Stored);
+        writer
+            .write_entry_whole(entry, content.as_bytes())
+            .await
+            .unwrap();
+    }
+    writer.close().await.unwrap()
+}
+
+fn remote_repository() -> LocalCachedRepository {
+    let json = json!({
+        "packages": {
+            PACKAGE_NAME: {
+                "versions": {
+                    "1.0.0": {
+                        "name": PACKAGE_NAME,
+                        "version": "1.0.0",
+                        "url": "https://example.com/package.zip",
+                    }
+                }
+            }
+        }
+    });
+    let serde_json::Value::Object(json) = json else {
+        unreachable!()
+    };
+    LocalCachedRepository::new(RemoteRepository::parse(json).unwrap(), IndexMap::new())
+}
+
+// VirtualFileSystem doesn't support hard links so this tests the copy fallback path
+#[test]
+fn install_with_hard_links_fallback() {
+    block_on(async {
+        let env = VirtualFileSystem::new();
+        let zip = create_zip(&[
+            (
+                "package.json",
+                r#"{"name":"com.anatawa12.package","version":"1.0.0"}"#,
+            ),
+            ("Runtime/Script.cs", "class Script {}"),
+        ])
+        .await;
+        let zip_name = format!("vrc-get-{PACKAGE_NAME}-1.0.0.zip");
+        let zip_path = Path::new("Repos").join(PACKAGE_NAME).join(&zip_name);
+        env.add_file(&zip_path, &zip).await.unwrap();
+        env.add_file(
+            &zip_path.with_extension("zip.sha256"),
+            format!("{} {zip_name}\n", hex::encode(Sha256::digest(&zip))).as_bytes(),
+        )
+        .await
+        .unwrap();
+
+        let repository = remote_repository();
+        let manifest = repository
+            .repo()
+            .get_package_version(PACKAGE_NAME, &Version::new(1, 0, 0))
+            .unwrap();
+
+        let project = VirtualProjectBuilder::new().build().await.unwrap();
+
+        let installer = PackageInstaller::new(&env, None::<&Infallible>).use_hard_links(true);
+        installer
+            .install_package(
+                project.io(),
+                PackageInfo::remote(manifest, &repository),
+                &AbortCheck::new(),
+            )
+            .await
+            .unwrap();
+
+        let target = format!("Packages/{PACKAGE_NAME}/Runtime/Script.cs");
+        assert!(project.io().is_file(target.as_ref()).await);
+        assert!(
+            project
+                .io()
+                .is_file(format!("Packages/{PACKAGE_NAME}/package.json").as_ref())
+                .await
+        );
+
+        // the package is extracted to the store once
+        let mut store = env.read_dir("PackageStore".as_ref()).await.unwrap();
+        let mut entries = 0;
+        while store.try_next().await.unwrap().is_some() {
+            entries += 1;
+        }
+        assert_eq!(entries, 1);
+    })
+}
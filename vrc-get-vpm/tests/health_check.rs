@@ -0,0 +1,78 @@
+use common::*;
+use futures::executor::block_on;
+use futures::prelude::*;
+use std::convert::Infallible;
+use vrc_get_vpm::environment::{HealthReport, HealthStatus, health_check};
+use vrc_get_vpm::io::{DirEntry, IoTrait};
+
+mod common;
+
+fn statuses(report: &HealthReport) -> Vec<(&str, HealthStatus)> {
+    (report.checks().iter())
+        .filter(|x| x.name != "database")
+        .map(|x| (x.name, x.status))
+        .collect()
+}
+
+#[test]
+fn healthy_environment() {
+    block_on(async {
+        let env = VirtualFileSystem::new();
+        env.add_file("settings.json".as_ref(), b"{}").await.unwrap();
+        env.add_file(
+            "Repos/vrc-official.json".as_ref(),
+            br#"{"repo":{"name":"Official","packages":{}}}"#,
+        )
+        .await
+        .unwrap();
+
+        let report = health_check(&env, None::<&Infallible>).await;
+
+        assert_eq!(
+            statuses(&report),
+            [
+                ("environment folder", HealthStatus::Ok),
+                ("repository cache folder", HealthStatus::Ok),
+                ("settings", HealthStatus::Ok),
+                ("repository", HealthStatus::Ok),
+                ("repository", HealthStatus::Warning),
+                ("unity hub", HealthStatus::Warning),
+                ("backup folder", HealthStatus::Warning),
+            ]
+        );
+
+        // the probe files are removed
+        let mut entries = env.read_dir("Repos".as_ref()).await.unwrap();
+        let mut names = vec![];
+        while let Some(entry) = entries.try_next().await.unwrap() {
+            names.push(entry.file_name());
+        }
+        assert_eq!(names, ["vrc-official.json"]);
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["checks"][0]["status"], "ok");
+    })
+}
+
+#[test]
+fn broken_environment() {
+    block_on(async {
+        let env = VirtualFileSystem::new();
+        env.add_file("settings.json".as_ref(), b"{ broken")
+            .await
+            .unwrap();
+
+        let report = health_check(&env, None::<&Infallible>).await;
+
+        assert_eq!(
+            statuses(&report),
+            [
+                ("environment folder", HealthStatus::Ok),
+                ("repository cache folder", HealthStatus::Error),
+                ("settings", HealthStatus::Error),
+            ]
+        );
+        assert_eq!(report.status(), HealthStatus::Error);
+        // health check does not create missing folders
+        assert!(!env.is_dir("Repos".as_ref()).await);
+    })
+}
@@ -0,0 +1,141 @@
+use common::*;
+use futures::executor::block_on;
+use indexmap::IndexMap;
+use serde_json::json;
+use std::io;
+use std::path::Path;
+use url::Url;
+use vrc_get_vpm::environment::PackageInstaller;
+use vrc_get_vpm::io::IoTrait;
+use vrc_get_vpm::repository::{LocalCachedRepository, RemoteRepository};
+use vrc_get_vpm::version::Version;
+use vrc_get_vpm::{AbortCheck, ErrorCode, HttpMethod, PackageInfo, PackageInstaller as _};
+
+mod common;
+
+const REPOSITORY_URL: &str = "https://example.com/vpm.json";
+const PACKAGE_NAME: &str = "com.anatawa12.package";
+
+fn repository_json() -> String {
+    json!({
+        "name": "Example Repository",
+        "packages": {
+            PACKAGE_NAME: {
+                "versions": {
+                    "1.0.0": {
+                        "name": PACKAGE_NAME,
+                        "version": "1.0.0",
+                        "url": "https://example.com/package.zip",
+                    }
+                }
+            }
+        }
+    })
+    .to_string()
+}
+
+#[test]
+fn download_with_etag() {
+    block_on(async {
+        let client =
+            MockHttpClient::new().with_etag_response(REPOSITORY_URL, "\"v1\"", repository_json());
+        let url = Url::parse(REPOSITORY_URL).unwrap();
+
+        let (repository, etag) =
+            RemoteRepository::download_with_etag(&client, &url, &IndexMap::new(), None)
+                .await
+                .unwrap()
+                .expect("downloading without etag must return the repository");
+        assert_eq!(etag.as_deref(), Some("\"v1\""));
+        assert_eq!(repository.name(), Some("Example Repository"));
+        assert_eq!(repository.url(), Some(&url));
+
+        let not_modified =
+            RemoteRepository::download_with_etag(&client, &url, &IndexMap::new(), Some("\"v1\""))
+                .await
+                .unwrap();
+        assert!(not_modified.is_none());
+
+        let requests = client.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, HttpMethod::Get);
+        assert_eq!(requests[0].header("If-None-Match"), None);
+        assert_eq!(requests[1].header("If-None-Match"), Some("\"v1\""));
+    })
+}
+
+#[test]
+fn download_sends_headers() {
+    block_on(async {
+        let client = MockHttpClient::new().with_response(REPOSITORY_URL, 200, repository_json());
+        let url = Url::parse(REPOSITORY_URL).unwrap();
+        let mut headers = IndexMap::<Box<str>, Box<str>>::new();
+        headers.insert("Authorization".into(), "Bearer token".into());
+
+        RemoteRepository::download(&client, &url, &headers)
+            .await
+            .unwrap();
+
+        let requests = client.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].header("authorization"), Some("Bearer token"));
+    })
+}
+
+#[test]
+fn download_error_status() {
+    block_on(async {
+        let client = MockHttpClient::new().with_response(REPOSITORY_URL, 500, "error");
+        let url = Url::parse(REPOSITORY_URL).unwrap();
+
+        let error = RemoteRepository::download(&client, &url, &IndexMap::new())
+            .await
+            .unwrap_err();
+        let error = vrc_get_vpm::Error::from(error);
+        assert!(matches!(error, vrc_get_vpm::Error::Network(_)));
+        assert_eq!(error.code(), "network.request-failed");
+    })
+}
+
+#[test]
+fn package_download_error_status() {
+    block_on(async {
+        let env = VirtualFileSystem::new();
+        let serde_json::Value::Object(json) = serde_json::from_str(&repository_json()).unwrap()
+        else {
+            unreachable!()
+        };
+        let mut headers = IndexMap::<Box<str>, Box<str>>::new();
+        headers.insert("Authorization".into(), "Bearer token".into());
+        let repository =
+            LocalCachedRepository::new(RemoteRepository::parse(json).unwrap(), headers);
+        let manifest = repository
+            .repo()
+            .get_package_version(PACKAGE_NAME, &Version::new(1, 0, 0))
+            .unwrap();
+
+        let project = VirtualProjectBuilder::new().build().await.unwrap();
+
+        let client = MockHttpClient::new();
+        let installer = PackageInstaller::new(&env, Some(&client));
+        let error = installer
+            .install_package(
+                project.io(),
+                PackageInfo::remote(manifest, &repository),
+                &AbortCheck::new(),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+
+        let requests = client.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].url.as_str(), "https://example.com/package.zip");
+        assert_eq!(requests[0].header("Authorization"), Some("Bearer token"));
+
+        let zip_path = Path::new("Repos")
+            .join(PACKAGE_NAME)
+            .join(format!("vrc-get-{PACKAGE_NAME}-1.0.0.zip"));
+        assert!(!env.is_file(&zip_path).await);
+    })
+}
@@ -0,0 +1,104 @@
+//! Runs the tests in `resolve_project.rs` with the packages split into the layers of [`LayeredCollection`].
+//!
+//! The packages are added to one of the upper layers and the lowest layer,
+//! so the packages in the lowest layer are always shadowed.
+
+use vrc_get_vpm::{LayeredCollection, PackageCollection as _, PackageInfo, VersionSelector};
+
+include!("resolve_project.rs");
+
+/// Shadows [`common::PackageCollectionBuilder`] for the tests in `resolve_project.rs`
+struct PackageCollectionBuilder {
+    layers: [common::PackageCollectionBuilder; 2],
+    lowest: common::PackageCollectionBuilder,
+    count: usize,
+}
+
+impl PackageCollectionBuilder {
+    fn new() -> Self {
+        Self {
+            layers: [
+                common::PackageCollectionBuilder::new(),
+                common::PackageCollectionBuilder::new(),
+            ],
+            lowest: common::PackageCollectionBuilder::new(),
+            count: 0,
+        }
+    }
+
+    fn add(self, json: PackageManifest) -> Self {
+        let [first, second] = self.layers;
+        let layers = if self.count % 2 == 0 {
+            [first.add(json.clone()), second]
+        } else {
+            [first, second.add(json.clone())]
+        };
+        Self {
+            layers,
+            lowest: self.lowest.add(json),
+            count: self.count + 1,
+        }
+    }
+
+    fn build(self) -> LayeredCollection<'static> {
+        let [first, second] = self.layers;
+        let layers: &'static [common::PackageCollection] =
+            Vec::leak(vec![first.build(), second.build(), self.lowest.build()]);
+        (layers.iter()).fold(LayeredCollection::new(), |collection, layer| {
+            collection.with_layer(layer)
+        })
+    }
+}
+
+trait GetPackage {
+    fn get_package(&self, name: &str, version: Version) -> PackageInfo<'_>;
+}
+
+impl GetPackage for LayeredCollection<'_> {
+    fn get_package(&self, name: &str, version: Version) -> PackageInfo<'_> {
+        self.find_package_by_name(name, VersionSelector::specific_version(&version))
+            .unwrap()
+    }
+}
+
+#[test]
+fn shadowing() {
+    let collection = PackageCollectionBuilder::new()
+        .add(PackageManifest::new(
+            "com.anatawa12.package",
+            Version::new(1, 0, 0),
+        ))
+        .add(PackageManifest::new(
+            "com.anatawa12.package",
+            Version::new(1, 1, 0),
+        ))
+        .build();
+    let upper = &collection.layers()[..2];
+
+    // the versions in the lowest layer are shadowed
+    let versions = collection
+        .find_packages("com.anatawa12.package")
+        .map(|x| x.version().clone())
+        .collect::<Vec<_>>();
+    assert_eq!(versions, [Version::new(1, 0, 0), Version::new(1, 1, 0)]);
+    assert_eq!(collection.get_all_packages().count(), 2);
+
+    // the latest version among layers, from the higher layer
+    let latest = collection
+        .find_package_by_name(
+            "com.anatawa12.package",
+            VersionSelector::latest_for(None, false),
+        )
+        .unwrap();
+    assert_eq!(latest.version(), &Version::new(1, 1, 0));
+    let from_upper = upper[1]
+        .find_package_by_name(
+            "com.anatawa12.package",
+            VersionSelector::latest_for(None, false),
+        )
+        .unwrap();
+    assert!(std::ptr::eq(
+        latest.package_json(),
+        from_upper.package_json()
+    ));
+}
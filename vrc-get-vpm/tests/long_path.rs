@@ -0,0 +1,114 @@
+#![cfg(all(feature = "tokio", windows))]
+
+use common::*;
+use futures::AsyncWriteExt;
+use futures::io::Cursor;
+use std::path::{Path, PathBuf};
+use vrc_get_vpm::environment::{RestoreOptions, restore_project_backup};
+use vrc_get_vpm::io::{DefaultEnvironmentIo, DefaultProjectIo, IoTrait};
+use vrc_get_vpm::unity_project::BackupOptions;
+use vrc_get_vpm::{AbortCheck, UnityProject};
+
+mod common;
+
+fn run<F: Future>(f: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(f)
+}
+
+/// The relative path longer than `MAX_PATH` (260) even without the project path
+fn long_relative_path() -> PathBuf {
+    let mut path = PathBuf::from("Assets");
+    for i in 0..6 {
+        path.push(format!("a-very-long-folder-name-to-exceed-max-path-{i}"));
+    }
+    path.push("Sample.txt");
+    assert!(path.as_os_str().len() > 260);
+    path
+}
+
+#[test]
+fn long_path_operations() {
+    run(async {
+        let root = std::env::temp_dir().join(format!("vrc-get-long-path-{}", uuid::Uuid::new_v4()));
+        let env = DefaultEnvironmentIo::new(root.clone().into_boxed_path());
+        let long_path = long_relative_path();
+
+        env.create_dir_all("project/Packages".as_ref())
+            .await
+            .unwrap();
+        env.write("project/Packages/vpm-manifest.json".as_ref(), b"{}")
+            .await
+            .unwrap();
+
+        let project_io = DefaultProjectIo::new(root.join("project").into_boxed_path());
+        project_io
+            .create_dir_all("ProjectSettings".as_ref())
+            .await
+            .unwrap();
+        project_io
+            .write(
+                "ProjectSettings/ProjectVersion.txt".as_ref(),
+                b"m_EditorVersion: 2022.3.22f1\n",
+            )
+            .await
+            .unwrap();
+        project_io
+            .create_dir_all(long_path.parent().unwrap())
+            .await
+            .unwrap();
+        let mut file = project_io.create_new(&long_path).await.unwrap();
+        file.write_all(b"sample").await.unwrap();
+        file.flush().await.unwrap();
+        drop(file);
+        project_io
+            .rename(&long_path, &long_path.with_extension("md"))
+            .await
+            .unwrap();
+        let long_path = long_path.with_extension("md");
+        assert!(project_io.is_file(&long_path).await);
+
+        // extract the archive to the path longer than MAX_PATH
+        let project = UnityProject::load(project_io).await.unwrap();
+        let mut archive = Cursor::new(Vec::new());
+        project
+            .create_backup_archive(
+                &mut archive,
+                &BackupOptions::new(),
+                &AbortCheck::new(),
+                |_| {},
+            )
+            .await
+            .unwrap();
+        env.write("backup.zip".as_ref(), &archive.into_inner())
+            .await
+            .unwrap();
+
+        let restored = restore_project_backup(
+            &env,
+            "backup.zip".as_ref(),
+            "restored".as_ref(),
+            &RestoreOptions::new(),
+            &AbortCheck::new(),
+            |_| {},
+        )
+        .await
+        .unwrap();
+        assert!(!restored.as_os_str().to_string_lossy().starts_with(r"\\?\"));
+        assert_eq!(
+            read_file(&env, &Path::new("restored").join(&long_path)).await,
+            "sample"
+        );
+
+        // recursive removal of the long paths
+        env.remove_dir_all("restored".as_ref()).await.unwrap();
+        env.remove_dir_all("project".as_ref()).await.unwrap();
+        assert!(!env.is_dir("restored".as_ref()).await);
+        assert!(!env.is_dir("project".as_ref()).await);
+
+        std::fs::remove_dir_all(&root).ok();
+    })
+}
@@ -0,0 +1,177 @@
+use common::*;
+use futures::executor::block_on;
+use futures::prelude::*;
+use std::io;
+use vrc_get_vpm::environment::Settings;
+use vrc_get_vpm::io::memory::{MemoryEnvironmentIo, MemoryProjectIo};
+use vrc_get_vpm::io::{DirEntry, IoTrait};
+use vrc_get_vpm::version::{UnityVersion, Version};
+use vrc_get_vpm::{PackageManifest, UnityProject};
+
+mod common;
+
+async fn read_dir(io: &impl IoTrait, path: &str) -> Vec<String> {
+    let entries = io.read_dir(path.as_ref()).await.unwrap();
+    entries
+        .map_ok(|entry| entry.file_name().into_string().unwrap())
+        .try_collect()
+        .await
+        .unwrap()
+}
+
+#[test]
+fn file_operations() {
+    block_on(async {
+        let io = MemoryProjectIo::new();
+
+        // the parent directory must exist
+        let error = io.write("dir/file.txt".as_ref(), b"").await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+
+        io.create_dir_all("dir".as_ref()).await.unwrap();
+        io.write("dir/b.txt".as_ref(), b"content").await.unwrap();
+        io.write("dir/a.txt".as_ref(), b"a").await.unwrap();
+        assert_eq!(read_file(&io, "dir/b.txt").await, "content");
+        assert_eq!(read_file(&io, "dir/../dir/./a.txt").await, "a");
+        assert_eq!(io.metadata("dir/b.txt".as_ref()).await.unwrap().len(), 7);
+        assert!(io.is_dir("dir".as_ref()).await);
+        assert_eq!(read_dir(&io, "dir").await, ["a.txt", "b.txt"]);
+
+        // create truncates the file, and create_new fails for the existing file
+        let mut file = io.create("dir/b.txt".as_ref()).await.unwrap();
+        file.write_all(b"new").await.unwrap();
+        file.write_all(b" content").await.unwrap();
+        assert_eq!(read_file(&io, "dir/b.txt").await, "new content");
+        let error = io.create_new("dir/b.txt".as_ref()).await.err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+
+        let error = io.remove_dir("dir".as_ref()).await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::DirectoryNotEmpty);
+        io.remove_file("dir/a.txt".as_ref()).await.unwrap();
+        assert_eq!(read_dir(&io, "dir").await, ["b.txt"]);
+        io.remove_dir_all("dir".as_ref()).await.unwrap();
+        assert!(!io.is_dir("dir".as_ref()).await);
+        assert_eq!(io.write_count(), 2);
+    })
+}
+
+#[test]
+fn rename() {
+    block_on(async {
+        let io = MemoryProjectIo::new()
+            .with_file("from/file.txt", "from")
+            .with_file("to/file.txt", "to")
+            .with_file("locked/file.txt", "locked");
+        io.deny_deletion("locked/file.txt").unwrap();
+
+        // files are replaced
+        io.rename("from/file.txt".as_ref(), "to/file.txt".as_ref())
+            .await
+            .unwrap();
+        assert_eq!(read_file(&io, "to/file.txt").await, "from");
+        assert!(!io.is_file("from/file.txt".as_ref()).await);
+
+        // directories are moved with the contents, but not replaced
+        io.rename("to".as_ref(), "moved".as_ref()).await.unwrap();
+        assert_eq!(read_file(&io, "moved/file.txt").await, "from");
+        let error = (io.rename("moved".as_ref(), "from".as_ref()).await).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+        let error = (io.rename("moved".as_ref(), "moved/inner".as_ref()).await).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+
+        // the locked files cannot be moved
+        let error = (io.rename("locked".as_ref(), "unlocked".as_ref()).await).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+        let error = (io.remove_dir_all("locked".as_ref()).await).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+        assert!(io.is_file("locked/file.txt".as_ref()).await);
+    })
+}
+
+#[test]
+fn injected_failures() {
+    block_on(async {
+        let env = MemoryEnvironmentIo::new().with_settings(r#"{"showPrereleasePackages": true}"#);
+        env.fail_on("settings.json", io::ErrorKind::PermissionDenied);
+
+        let error = Settings::load(&env).await.err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+        // the other paths are not affected
+        env.write("other.json".as_ref(), b"{}").await.unwrap();
+
+        env.clear_failures();
+        let settings = Settings::load(&env).await.unwrap();
+        assert!(settings.show_prerelease_packages());
+    })
+}
+
+#[test]
+fn seeded_project() {
+    block_on(async {
+        let io = MemoryProjectIo::new()
+            .with_vpm_manifest(
+                r#"{
+                    "dependencies": { "com.anatawa12.package": { "version": "1.0.0" } },
+                    "locked": { "com.anatawa12.package": { "version": "1.0.0", "dependencies": {} } }
+                }"#,
+            )
+            .with_unity_version("2022.3.22f1", "887be4894c44")
+            .with_package("com.anatawa12.package", "1.0.0");
+
+        let mut project = UnityProject::load(io.clone()).await.unwrap();
+        assert_eq!(
+            project.unity_version(),
+            Some(UnityVersion::new_f1(2022, 3, 22))
+        );
+        assert_eq!(project.unity_revision(), Some("887be4894c44"));
+        let package: &PackageManifest = project
+            .get_installed_package("com.anatawa12.package")
+            .unwrap();
+        assert_eq!(package.version(), &Version::new(1, 0, 0));
+
+        // the clone shares the files with the project
+        let changes = project
+            .remove_request(&["com.anatawa12.package"])
+            .await
+            .unwrap();
+        project
+            .apply_pending_changes(&NoInstaller, changes)
+            .await
+            .unwrap();
+        project.save().await.unwrap();
+        assert!(!io.is_dir("Packages/com.anatawa12.package".as_ref()).await);
+        assert!(
+            !read_file(&io, "Packages/vpm-manifest.json")
+                .await
+                .contains("com.anatawa12")
+        );
+    })
+}
+
+/// The installer for the changes without installing packages
+struct NoInstaller;
+
+impl vrc_get_vpm::PackageInstaller for NoInstaller {
+    async fn install_package(
+        &self,
+        _: &impl vrc_get_vpm::io::ProjectIo,
+        _: vrc_get_vpm::PackageInfo<'_>,
+        _: &vrc_get_vpm::AbortCheck,
+    ) -> io::Result<()> {
+        unreachable!("no packages are installed")
+    }
+}
+
+#[test]
+fn seeded_environment() {
+    block_on(async {
+        let env = MemoryEnvironmentIo::new()
+            .with_settings(r#"{"showPrereleasePackages": true}"#)
+            .with_repo_cache("example.json", r#"{"repo": {"packages": {}}}"#);
+
+        assert_eq!(read_dir(&env, "").await, ["Repos", "settings.json"]);
+        assert_eq!(read_dir(&env, "Repos").await, ["example.json"]);
+        let settings = Settings::load(&env).await.unwrap();
+        assert!(settings.show_prerelease_packages());
+    })
+}
@@ -0,0 +1,175 @@
+use common::*;
+use futures::executor::block_on;
+use std::path::Path;
+use vrc_get_vpm::unity_project::MigrateLegacySdkError;
+use vrc_get_vpm::version::Version;
+use vrc_get_vpm::{PackageManifest, ProjectType};
+
+mod common;
+
+const VRCSDK_GUID: &str = "0123456789abcdef0123456789abcdef";
+const EXAMPLES_GUID: &str = "fedcba9876543210fedcba9876543210";
+
+fn meta(guid: &str) -> String {
+    format!("fileFormatVersion: 2\nguid: {guid}\nfolderAsset: yes\n")
+}
+
+/// The SDK with the legacy folders of the SDK before VPM.
+fn sdk_collection() -> PackageCollection {
+    PackageCollectionBuilder::new()
+        .add_sdk(Version::new(3, 7, 0), |manifest| {
+            if manifest.name() != "com.vrchat.base" {
+                return manifest;
+            }
+            manifest
+                .add_legacy_folder("Assets\\VRCSDK", VRCSDK_GUID)
+                .add_legacy_folder("Assets/VRChat Examples", EXAMPLES_GUID)
+        })
+        .add(PackageManifest::new(
+            "com.vrchat.worlds",
+            Version::new(3, 7, 0),
+        ))
+        .build()
+}
+
+fn folders(changes: &[(Box<Path>, &str)]) -> Vec<String> {
+    let mut folders = changes
+        .iter()
+        .map(|(path, _)| path.to_str().unwrap().replace('\\', "/"))
+        .collect::<Vec<_>>();
+    folders.sort();
+    folders
+}
+
+#[test]
+fn legacy_avatars_project() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("Assets/VRCSDK/Plugins/VRCSDK3A.dll", "")
+            .add_file("Assets/VRCSDK.meta", meta(VRCSDK_GUID))
+            .add_file("Assets/VRChat Examples/Prefab.prefab", "")
+            .build()
+            .await
+            .unwrap();
+
+        let collection = sdk_collection();
+        let result = project
+            .migrate_legacy_sdk_request(&collection)
+            .await
+            .unwrap();
+
+        assert_eq!(result.package_changes().len(), 2);
+        let avatars = result.package_changes()["com.vrchat.avatars"]
+            .as_install()
+            .unwrap();
+        assert!(avatars.to_dependencies().is_some());
+        assert_eq!(
+            folders(result.remove_legacy_folders()),
+            ["Assets/VRCSDK", "Assets/VRChat Examples"]
+        );
+    })
+}
+
+#[test]
+fn half_migrated_project() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.vrchat.avatars", Version::new(3, 7, 0))
+            .add_locked(
+                "com.vrchat.avatars",
+                Version::new(3, 7, 0),
+                &[("com.vrchat.base", "3.7.0")],
+            )
+            .add_locked("com.vrchat.base", Version::new(3, 7, 0), &[])
+            .add_package_json(
+                "com.vrchat.avatars",
+                r#"{"name":"com.vrchat.avatars","version":"3.7.0"}"#,
+            )
+            .add_package_json(
+                "com.vrchat.base",
+                r#"{"name":"com.vrchat.base","version":"3.7.0"}"#,
+            )
+            .add_file("Assets/VRCSDK/Plugins/VRCSDK3A.dll", "")
+            .add_file("Assets/VRCSDK.meta", meta(VRCSDK_GUID))
+            // the folder with same name but different GUID is not a part of the legacy SDK
+            .add_file("Assets/VRChat Examples/Prefab.prefab", "")
+            .add_file("Assets/VRChat Examples.meta", meta(VRCSDK_GUID))
+            .build()
+            .await
+            .unwrap();
+
+        let collection = sdk_collection();
+        let result = project
+            .migrate_legacy_sdk_request(&collection)
+            .await
+            .unwrap();
+
+        assert_eq!(result.package_changes().len(), 0);
+        assert_eq!(folders(result.remove_legacy_folders()), ["Assets/VRCSDK"]);
+    })
+}
+
+#[test]
+fn not_legacy_project() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("Assets/Scene.unity", "")
+            .build()
+            .await
+            .unwrap();
+
+        let collection = sdk_collection();
+        let err = project
+            .migrate_legacy_sdk_request(&collection)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(MigrateLegacySdkError::NotLegacyProject)
+        ));
+    })
+}
+
+#[test]
+fn sdk2_project() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("Assets/VRCSDK/Plugins/VRCSDK2.dll", "")
+            .build()
+            .await
+            .unwrap();
+
+        let collection = sdk_collection();
+        let err = project
+            .migrate_legacy_sdk_request(&collection)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(MigrateLegacySdkError::Sdk2NotSupported)
+        ));
+    })
+}
+
+#[test]
+fn udon_only_project() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dir("Assets/Udon")
+            .build()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            project.detect_project_type().await.unwrap(),
+            ProjectType::LegacyWorlds
+        );
+
+        let collection = sdk_collection();
+        let result = project
+            .migrate_legacy_sdk_request(&collection)
+            .await
+            .unwrap();
+        assert!(result.package_changes().contains_key("com.vrchat.worlds"));
+    })
+}
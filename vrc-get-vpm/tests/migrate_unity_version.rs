@@ -0,0 +1,261 @@
+use common::*;
+use futures::executor::block_on;
+use vrc_get_vpm::PackageManifest;
+use vrc_get_vpm::io::IoTrait;
+use vrc_get_vpm::unity_project::MigrateUnityVersionError;
+use vrc_get_vpm::unity_project::pending_project_changes::UpmChange;
+use vrc_get_vpm::version::{UnityVersion, Version};
+
+mod common;
+
+const PROJECT_VERSION_2019: &str = "m_EditorVersion: 2019.4.31f1\r\n\
+    m_EditorVersionWithRevision: 2019.4.31f1 (bd5abf232a62)\r\n";
+
+#[test]
+fn upgrade_sdk_for_2022() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.vrchat.avatars", Version::new(3, 4, 2))
+            .add_locked(
+                "com.vrchat.avatars",
+                Version::new(3, 4, 2),
+                &[("com.vrchat.base", "3.4.2")],
+            )
+            .add_locked("com.vrchat.base", Version::new(3, 4, 2), &[])
+            .add_file("ProjectSettings/ProjectVersion.txt", PROJECT_VERSION_2019)
+            .add_file(
+                "Packages/manifest.json",
+                r#"{"dependencies":{"com.unity.xr.oculus.standalone":"2.38.4"}}"#,
+            )
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(
+                PackageManifest::new("com.vrchat.avatars", Version::new(3, 4, 2))
+                    .add_vpm_dependency("com.vrchat.base", "3.4.2"),
+            )
+            .add(PackageManifest::new(
+                "com.vrchat.base",
+                Version::new(3, 4, 2),
+            ))
+            .add(
+                PackageManifest::new("com.vrchat.avatars", Version::new(3, 7, 0))
+                    .add_vpm_dependency("com.vrchat.base", "3.7.0"),
+            )
+            .add(PackageManifest::new(
+                "com.vrchat.base",
+                Version::new(3, 7, 0),
+            ))
+            .build();
+
+        let target = UnityVersion::new_f1(2022, 3, 22);
+        let result = project
+            .migrate_unity_version_request(&collection, target)
+            .await
+            .unwrap();
+
+        let avatars = collection.get_package("com.vrchat.avatars", Version::new(3, 7, 0));
+        let base = collection.get_package("com.vrchat.base", Version::new(3, 7, 0));
+
+        assert_eq!(result.package_changes().len(), 2);
+        assert_eq!(result.conflicts().len(), 0);
+        assert_installing_to_both(&result, &avatars);
+        assert_installing_to_both(&result, &base);
+
+        assert_eq!(result.upm_changes().len(), 1);
+        assert!(matches!(
+            &result.upm_changes()[0],
+            UpmChange::RemoveDependency { name } if name.as_ref() == "com.unity.xr.oculus.standalone"
+        ));
+        assert_eq!(result.unity_version(), Some(target));
+    })
+}
+
+#[test]
+fn unlocked_sdk_is_error() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_package_json(
+                "com.vrchat.avatars",
+                r#"{"name":"com.vrchat.avatars","version":"3.4.2"}"#,
+            )
+            .add_file("ProjectSettings/ProjectVersion.txt", PROJECT_VERSION_2019)
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new().build();
+
+        let err = project
+            .migrate_unity_version_request(&collection, UnityVersion::new_f1(2022, 3, 22))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(MigrateUnityVersionError::UnlockedSdkPackage { package_name }) if package_name.as_ref() == "com.vrchat.avatars"
+        ));
+    })
+}
+
+#[test]
+fn downgrading_is_error() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("ProjectSettings/ProjectVersion.txt", PROJECT_VERSION_2019)
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new().build();
+
+        let err = project
+            .migrate_unity_version_request(&collection, UnityVersion::new_f1(2018, 4, 0))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(MigrateUnityVersionError::DowngradingUnity { .. })
+        ));
+    })
+}
+
+#[test]
+fn apply_to_copied_project() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("ProjectSettings/ProjectVersion.txt", PROJECT_VERSION_2019)
+            .add_file(
+                "Packages/manifest.json",
+                r#"{"dependencies":{"com.unity.xr.openvr.standalone":"1.0.5","com.unity.timeline":"1.2.18"}}"#,
+            )
+            .add_file("Assets/Scene.unity", "scene")
+            .add_file("Library/ArtifactDB", "cache")
+            .add_file("Temp/UnityLockfile", "")
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new().build();
+        let target = UnityVersion::new_f1(2022, 3, 22);
+        let request = project
+            .migrate_unity_version_request(&collection, target)
+            .await
+            .unwrap();
+
+        let mut copied = project
+            .copy_project(VirtualFileSystem::new())
+            .await
+            .unwrap();
+
+        assert!(copied.io().is_file("Assets/Scene.unity".as_ref()).await);
+        assert!(!copied.io().is_dir("Library".as_ref()).await);
+        assert!(!copied.io().is_dir("Temp".as_ref()).await);
+
+        let env = VirtualEnvironment::new(VirtualFileSystem::new());
+        copied.apply_pending_changes(&env, request).await.unwrap();
+
+        assert_eq!(copied.unity_version(), Some(target));
+        assert_eq!(copied.unity_revision(), None);
+        assert_eq!(
+            read_file(copied.io(), "ProjectSettings/ProjectVersion.txt").await,
+            "m_EditorVersion: 2022.3.22f1\r\n"
+        );
+        assert!(!copied.has_upm_package("com.unity.xr.openvr.standalone"));
+        assert!(copied.has_upm_package("com.unity.timeline"));
+
+        // original project is not modified
+        assert_eq!(
+            project.unity_version(),
+            Some(UnityVersion::new_f1(2019, 4, 31))
+        );
+        assert_eq!(
+            read_file(project.io(), "ProjectSettings/ProjectVersion.txt").await,
+            PROJECT_VERSION_2019
+        );
+    })
+}
+
+#[test]
+fn project_lock_is_not_copied() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("ProjectSettings/ProjectVersion.txt", PROJECT_VERSION_2019)
+            .add_file("Assets/Scene.unity", "scene")
+            .add_file(".vrc-get.lock", r#"{"pid":1}"#)
+            .add_file("Assets/.vrc-get.lock", "kept")
+            .build()
+            .await
+            .unwrap();
+
+        let copied = project
+            .copy_project(VirtualFileSystem::new())
+            .await
+            .unwrap();
+
+        assert!(copied.io().is_file("Assets/Scene.unity".as_ref()).await);
+        assert!(copied.io().is_file("Assets/.vrc-get.lock".as_ref()).await);
+        assert!(!copied.io().is_file(".vrc-get.lock".as_ref()).await);
+    })
+}
+
+#[test]
+fn keep_newer_sdk() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.vrchat.base", Version::new(3, 8, 0))
+            .add_locked("com.vrchat.base", Version::new(3, 8, 0), &[])
+            .add_file("ProjectSettings/ProjectVersion.txt", PROJECT_VERSION_2019)
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(PackageManifest::new(
+                "com.vrchat.base",
+                Version::new(3, 7, 0),
+            ))
+            .build();
+
+        let target = UnityVersion::new_f1(2022, 3, 22);
+        let result = project
+            .migrate_unity_version_request(&collection, target)
+            .await
+            .unwrap();
+
+        assert_eq!(result.package_changes().len(), 0);
+        assert_eq!(result.unity_version(), Some(target));
+    })
+}
+
+#[test]
+fn pin_upm_packages_for_2022() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("ProjectSettings/ProjectVersion.txt", PROJECT_VERSION_2019)
+            .add_file(
+                "Packages/manifest.json",
+                r#"{"dependencies":{"com.unity.textmeshpro":"2.1.6"}}"#,
+            )
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new().build();
+        let target = UnityVersion::new_f1(2022, 3, 22);
+        let result = project
+            .migrate_unity_version_request(&collection, target)
+            .await
+            .unwrap();
+
+        assert_eq!(result.upm_changes().len(), 1);
+        assert!(matches!(
+            &result.upm_changes()[0],
+            UpmChange::EnsureDependency { name, version }
+                if name.as_ref() == "com.unity.textmeshpro" && *version == Version::new(3, 0, 6)
+        ));
+    })
+}
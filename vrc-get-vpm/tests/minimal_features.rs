@@ -0,0 +1,105 @@
+//! The tests for the build without the features spawning processes and using native libraries.
+//!
+//! Run with `cargo test -p vrc-get-vpm --no-default-features`.
+#![cfg(not(any(feature = "reqwest", feature = "tokio", feature = "vrc-get-litedb")))]
+
+use common::*;
+use futures::executor::block_on;
+use futures::prelude::*;
+use std::io;
+use std::path::Path;
+use vrc_get_vpm::io::{IoTrait, ProjectIo};
+use vrc_get_vpm::unity_project::AddPackageOperation;
+use vrc_get_vpm::version::{Version, VersionRange};
+use vrc_get_vpm::{AbortCheck, PackageInfo, PackageManifest};
+
+mod common;
+
+/// The installer writes `package.json` of the package
+struct ManifestInstaller;
+
+impl vrc_get_vpm::PackageInstaller for ManifestInstaller {
+    async fn install_package(
+        &self,
+        io: &impl ProjectIo,
+        package: PackageInfo<'_>,
+        abort: &AbortCheck,
+    ) -> io::Result<()> {
+        abort.check()?;
+        let path = format!("Packages/{}/package.json", package.name());
+        io.create_dir_all(Path::new(&path).parent().unwrap())
+            .await?;
+        let json = format!(
+            r#"{{"name":"{}","version":"{}"}}"#,
+            package.name(),
+            package.version()
+        );
+        io.write(path.as_ref(), json.as_bytes()).await
+    }
+}
+
+#[test]
+fn parse_versions() {
+    let version: Version = "1.2.3-beta.1".parse().unwrap();
+    assert!(version.is_pre());
+    assert_eq!(version.base_version(), Version::new(1, 2, 3));
+
+    let range: VersionRange = ">=1.0.0 <2.0.0".parse().unwrap();
+    assert!(range.matches(&Version::new(1, 5, 0)));
+    assert!(!range.matches(&Version::new(2, 0, 0)));
+}
+
+#[test]
+fn plan_and_apply_changes() {
+    block_on(async {
+        let mut project = VirtualProjectBuilder::new().build().await.unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(
+                PackageManifest::new("com.anatawa12.package", Version::new(1, 0, 0))
+                    .add_vpm_dependency("com.anatawa12.library", "^1.0.0"),
+            )
+            .add(PackageManifest::new(
+                "com.anatawa12.library",
+                Version::new(1, 1, 0),
+            ))
+            .build();
+        let package = collection.get_package("com.anatawa12.package", Version::new(1, 0, 0));
+
+        let changes = project
+            .add_package_request(
+                &collection,
+                &[package],
+                AddPackageOperation::InstallToDependencies,
+                false,
+            )
+            .await
+            .unwrap();
+        assert_eq!(changes.package_changes().len(), 2);
+        assert_eq!(changes.conflicts().len(), 0);
+
+        project
+            .apply_pending_changes(&ManifestInstaller, changes)
+            .await
+            .unwrap();
+        project.save().await.unwrap();
+
+        assert_eq!(
+            project
+                .get_locked("com.anatawa12.library")
+                .unwrap()
+                .version(),
+            &Version::new(1, 1, 0)
+        );
+        let mut manifest = String::new();
+        (project
+            .io()
+            .open("Packages/vpm-manifest.json".as_ref())
+            .await)
+            .unwrap()
+            .read_to_string(&mut manifest)
+            .await
+            .unwrap();
+        assert!(manifest.contains("com.anatawa12.package"));
+    })
+}
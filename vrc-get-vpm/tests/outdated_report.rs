@@ -0,0 +1,164 @@
+use crate::common::*;
+use futures::executor::block_on;
+use vrc_get_vpm::PackageManifest;
+use vrc_get_vpm::unity_project::HeldBack;
+use vrc_get_vpm::version::Version;
+
+mod common;
+
+#[test]
+fn upgradable() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.anatawa12.package", Version::new(1, 0, 0))
+            .add_locked("com.anatawa12.package", Version::new(1, 0, 0), &[])
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(PackageManifest::new(
+                "com.anatawa12.package",
+                Version::new(1, 0, 0),
+            ))
+            .add(PackageManifest::new(
+                "com.anatawa12.package",
+                Version::new(1, 1, 0),
+            ))
+            .add(PackageManifest::new(
+                "com.anatawa12.package",
+                "2.0.0-beta.1".parse::<Version>().unwrap(),
+            ))
+            .build();
+
+        let report = project.outdated_report(&collection, false);
+        let package = &report.packages()[0];
+        assert_eq!(package.latest, Some(Version::new(1, 1, 0)));
+        assert_eq!(package.upgradable, Some(Version::new(1, 1, 0)));
+        assert_eq!(package.held_back, None);
+        assert!(package.is_outdated());
+        assert_eq!(
+            report.upgrades().collect::<Vec<_>>(),
+            [("com.anatawa12.package", &Version::new(1, 1, 0))]
+        );
+
+        let report = project.outdated_report(&collection, true);
+        let package = &report.packages()[0];
+        assert_eq!(
+            package.upgradable,
+            Some("2.0.0-beta.1".parse::<Version>().unwrap())
+        );
+    })
+}
+
+#[test]
+fn held_back_by_range() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.anatawa12.package", Version::new(1, 0, 0))
+            .add_locked(
+                "com.anatawa12.package",
+                Version::new(1, 0, 0),
+                &[("com.anatawa12.library", "^1.0.0")],
+            )
+            .add_locked("com.anatawa12.library", Version::new(1, 0, 0), &[])
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(
+                PackageManifest::new("com.anatawa12.package", Version::new(1, 0, 0))
+                    .add_vpm_dependency("com.anatawa12.library", "^1.0.0"),
+            )
+            .add(PackageManifest::new(
+                "com.anatawa12.library",
+                Version::new(1, 0, 0),
+            ))
+            .add(PackageManifest::new(
+                "com.anatawa12.library",
+                Version::new(1, 2, 0),
+            ))
+            .add(PackageManifest::new(
+                "com.anatawa12.library",
+                Version::new(2, 0, 0),
+            ))
+            .build();
+
+        let report = project.outdated_report(&collection, false);
+        let library = (report.packages().iter())
+            .find(|x| x.name.as_ref() == "com.anatawa12.library")
+            .unwrap();
+        assert_eq!(library.latest, Some(Version::new(2, 0, 0)));
+        assert_eq!(library.latest_in_range, Some(Version::new(1, 2, 0)));
+        assert_eq!(library.upgradable, Some(Version::new(1, 2, 0)));
+        assert!(!library.requires_newer_unity);
+        assert_eq!(library.held_back, Some(HeldBack::DependencyRange));
+        assert!(library.is_outdated());
+
+        let package = (report.packages().iter())
+            .find(|x| x.name.as_ref() == "com.anatawa12.package")
+            .unwrap();
+        assert!(!package.is_outdated());
+    })
+}
+
+#[test]
+fn held_back_by_unity() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file(
+                "ProjectSettings/ProjectVersion.txt",
+                "m_EditorVersion: 2019.4.31f1\n",
+            )
+            .add_dependency("com.anatawa12.package", Version::new(1, 0, 0))
+            .add_locked("com.anatawa12.package", Version::new(1, 0, 0), &[])
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(
+                PackageManifest::new("com.anatawa12.package", Version::new(1, 0, 0))
+                    .with_unity(2019, 4),
+            )
+            .add(
+                PackageManifest::new("com.anatawa12.package", Version::new(1, 1, 0))
+                    .with_unity(2019, 4),
+            )
+            .add(
+                PackageManifest::new("com.anatawa12.package", Version::new(2, 0, 0))
+                    .with_unity(2022, 3),
+            )
+            .build();
+
+        let report = project.outdated_report(&collection, false);
+        let package = &report.packages()[0];
+        assert_eq!(package.latest, Some(Version::new(2, 0, 0)));
+        assert_eq!(package.latest_in_range, Some(Version::new(2, 0, 0)));
+        assert_eq!(package.upgradable, Some(Version::new(1, 1, 0)));
+        assert!(package.requires_newer_unity);
+        assert_eq!(package.held_back, Some(HeldBack::Unity));
+    })
+}
+
+#[test]
+fn source_unavailable() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.anatawa12.package", Version::new(1, 0, 0))
+            .add_locked("com.anatawa12.package", Version::new(1, 0, 0), &[])
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new().build();
+
+        let report = project.outdated_report(&collection, false);
+        let package = &report.packages()[0];
+        assert!(package.source_unavailable);
+        assert_eq!(package.latest, None);
+        assert!(!package.is_outdated());
+        assert_eq!(report.outdated().count(), 0);
+    })
+}
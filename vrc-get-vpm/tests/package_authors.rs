@@ -0,0 +1,98 @@
+use common::*;
+use futures::executor::block_on;
+use vrc_get_vpm::VersionSelector;
+use vrc_get_vpm::environment::PackageListingOptions;
+
+mod common;
+
+const REPOSITORY: &str = r#"{
+    "repo": {
+        "name": "Official",
+        "id": "com.vrchat.repos.official",
+        "packages": {
+            "com.anatawa12.string": {
+                "versions": {
+                    "1.0.0": {
+                        "name": "com.anatawa12.string",
+                        "version": "1.0.0",
+                        "author": "anatawa12 <anatawa12@example.com> (https://anatawa12.com)"
+                    }
+                }
+            },
+            "com.anatawa12.object": {
+                "versions": {
+                    "1.0.0": {
+                        "name": "com.anatawa12.object",
+                        "version": "1.0.0",
+                        "author": {"name": "anatawa12", "email": "anatawa12@example.com"}
+                    }
+                }
+            },
+            "com.vrchat.avatars": {
+                "versions": {
+                    "1.0.0": {
+                        "name": "com.vrchat.avatars",
+                        "version": "1.0.0",
+                        "author": {"name": "VRChat", "url": "https://vrchat.com"}
+                    }
+                }
+            },
+            "com.example.no-author": {
+                "versions": {
+                    "1.0.0": {"name": "com.example.no-author", "version": "1.0.0"}
+                }
+            }
+        }
+    }
+}"#;
+
+#[test]
+fn authors() {
+    block_on(async {
+        let collection = load_cached_repositories(&[("Repos/vrc-official.json", REPOSITORY)]).await;
+        let authors = collection.package_authors();
+
+        let summary = authors
+            .iter()
+            .map(|x| (x.name.as_deref(), x.url.as_deref(), x.packages))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [
+                (Some("VRChat"), Some("https://vrchat.com"), 1),
+                (Some("anatawa12"), Some("https://anatawa12.com"), 2),
+                (None, None, 1),
+            ]
+        );
+    })
+}
+
+#[test]
+fn filter_by_author() {
+    block_on(async {
+        let collection = load_cached_repositories(&[("Repos/vrc-official.json", REPOSITORY)]).await;
+
+        assert_eq!(
+            list_latest_package_names(
+                &collection,
+                &PackageListingOptions::new().author(Some("anatawa12"))
+            ),
+            ["com.anatawa12.object", "com.anatawa12.string"]
+        );
+        assert_eq!(
+            list_latest_package_names(&collection, &PackageListingOptions::new().author(None)),
+            ["com.example.no-author"]
+        );
+
+        let package = collection
+            .find_whole_all_packages(VersionSelector::latest_for(None, true), |x| {
+                x.name() == "com.anatawa12.string"
+            })
+            .pop()
+            .unwrap();
+        let author = package.author().unwrap();
+        assert_eq!(author.name(), Some("anatawa12"));
+        assert_eq!(author.email(), Some("anatawa12@example.com"));
+        assert_eq!(author.url(), Some("https://anatawa12.com"));
+    })
+}
@@ -0,0 +1,220 @@
+use common::*;
+use futures::executor::block_on;
+use std::path::Path;
+use vrc_get_vpm::environment::{
+    package_cache_overview, prune_unused_package_cache, remove_cached_package,
+};
+use vrc_get_vpm::io::IoTrait;
+use vrc_get_vpm::version::Version;
+
+mod common;
+
+async fn build_environment() -> VirtualFileSystem {
+    let fs = VirtualFileSystem::new();
+    let files: &[(&str, &[u8])] = &[
+        ("Repos/vrc-curated.json", b"{}"),
+        (
+            "Repos/com.anatawa12.a/vrc-get-com.anatawa12.a-1.0.0.zip",
+            b"zip",
+        ),
+        (
+            "Repos/com.anatawa12.a/vrc-get-com.anatawa12.a-1.0.0.zip.sha256",
+            b"sha",
+        ),
+        (
+            "Repos/com.anatawa12.a/vrc-get-com.anatawa12.a-2.0.0.zip",
+            b"zip2",
+        ),
+        (
+            "Repos/com.anatawa12.b/vrc-get-com.anatawa12.b-1.0.0.zip",
+            b"zip",
+        ),
+        (
+            "Repos/com.anatawa12.c/vrc-get-com.anatawa12.c-1.0.0.zip",
+            b"zip",
+        ),
+        ("Repos/com.anatawa12.c/not-a-cache.txt", b"text"),
+        (
+            "Projects/Valid/Packages/vpm-manifest.json",
+            br#"{"locked":{"com.anatawa12.a":{"version":"1.0.0"}}}"#,
+        ),
+        ("Projects/Broken/Packages/vpm-manifest.json", b"{"),
+        (
+            "Projects/Broken/Packages/com.anatawa12.b/package.json",
+            b"{}",
+        ),
+        ("Projects/Legacy/Assets/Scene.unity", b"scene"),
+    ];
+    for (path, content) in files {
+        fs.add_file(path.as_ref(), content).await.unwrap();
+    }
+    fs
+}
+
+const PROJECTS: &[&str] = &["Projects/Valid", "Projects/Broken", "Projects/Legacy"];
+
+fn projects() -> impl Iterator<Item = &'static Path> {
+    PROJECTS.iter().map(Path::new)
+}
+
+#[test]
+fn cache_overview() {
+    block_on(async {
+        let env = build_environment().await;
+
+        let overview = package_cache_overview(&env, projects()).await.unwrap();
+
+        let packages = overview
+            .packages()
+            .iter()
+            .map(|x| (x.name.as_ref(), x.version.to_string(), x.size, x.used))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            packages,
+            [
+                ("com.anatawa12.a", "1.0.0".to_owned(), 6, true),
+                ("com.anatawa12.a", "2.0.0".to_owned(), 4, false),
+                ("com.anatawa12.b", "1.0.0".to_owned(), 3, true),
+                ("com.anatawa12.c", "1.0.0".to_owned(), 3, false),
+            ]
+        );
+        assert_eq!(overview.total_size(), 16);
+        assert_eq!(overview.unused_size(), 7);
+        assert_eq!(
+            overview.unreadable_projects(),
+            [Path::new("Projects/Broken")]
+        );
+    })
+}
+
+#[test]
+fn prune_unused_cache() {
+    block_on(async {
+        let env = build_environment().await;
+
+        let would_remove = prune_unused_package_cache(&env, projects(), true)
+            .await
+            .unwrap();
+        assert_eq!(would_remove.len(), 2);
+        assert!(
+            env.is_file("Repos/com.anatawa12.a/vrc-get-com.anatawa12.a-2.0.0.zip".as_ref())
+                .await
+        );
+
+        let removed = prune_unused_package_cache(&env, projects(), false)
+            .await
+            .unwrap();
+        let removed = removed
+            .iter()
+            .map(|x| (x.name.as_ref(), x.version.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            removed,
+            [
+                ("com.anatawa12.a", "2.0.0".to_owned()),
+                ("com.anatawa12.c", "1.0.0".to_owned()),
+            ]
+        );
+
+        assert!(
+            env.is_file("Repos/com.anatawa12.a/vrc-get-com.anatawa12.a-1.0.0.zip".as_ref())
+                .await
+        );
+        assert!(
+            !env.is_file("Repos/com.anatawa12.a/vrc-get-com.anatawa12.a-2.0.0.zip".as_ref())
+                .await
+        );
+        assert!(
+            !env.is_file("Repos/com.anatawa12.c/vrc-get-com.anatawa12.c-1.0.0.zip".as_ref())
+                .await
+        );
+        // other files are kept
+        assert!(
+            env.is_file("Repos/com.anatawa12.c/not-a-cache.txt".as_ref())
+                .await
+        );
+        assert!(env.is_file("Repos/vrc-curated.json".as_ref()).await);
+    })
+}
+
+#[test]
+fn remove_single_cached_package() {
+    block_on(async {
+        let env = build_environment().await;
+
+        let version = Version::new(1, 0, 0);
+        assert!(
+            remove_cached_package(&env, "com.anatawa12.b", &version)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !remove_cached_package(&env, "com.anatawa12.b", &version)
+                .await
+                .unwrap()
+        );
+        // the empty folder is removed
+        assert!(!env.is_dir("Repos/com.anatawa12.b".as_ref()).await);
+
+        assert!(
+            remove_cached_package(&env, "com.anatawa12.a", &version)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !env.is_file("Repos/com.anatawa12.a/vrc-get-com.anatawa12.a-1.0.0.zip.sha256".as_ref())
+                .await
+        );
+        assert!(
+            env.is_file("Repos/com.anatawa12.a/vrc-get-com.anatawa12.a-2.0.0.zip".as_ref())
+                .await
+        );
+    })
+}
+
+#[test]
+fn project_without_manifest() {
+    block_on(async {
+        let env = build_environment().await;
+        (env.add_file(
+            "Projects/NoManifest/Packages/com.anatawa12.c/package.json".as_ref(),
+            b"{}",
+        ))
+        .await
+        .unwrap();
+
+        let projects = projects().chain([Path::new("Projects/NoManifest")]);
+        let removed = prune_unused_package_cache(&env, projects, false)
+            .await
+            .unwrap();
+        let removed = removed
+            .iter()
+            .map(|x| (x.name.as_ref(), x.version.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(removed, [("com.anatawa12.a", "2.0.0".to_owned())]);
+        assert!(
+            env.is_file("Repos/com.anatawa12.c/vrc-get-com.anatawa12.c-1.0.0.zip".as_ref())
+                .await
+        );
+    })
+}
+
+#[test]
+fn missing_project() {
+    block_on(async {
+        let env = build_environment().await;
+
+        let with_missing = || projects().chain([Path::new("Projects/Missing")]);
+        let overview = package_cache_overview(&env, with_missing()).await.unwrap();
+        assert!(overview.packages().iter().all(|x| x.used));
+        assert_eq!(
+            overview.unreadable_projects(),
+            [Path::new("Projects/Broken"), Path::new("Projects/Missing")]
+        );
+
+        let removed = prune_unused_package_cache(&env, with_missing(), false)
+            .await
+            .unwrap();
+        assert!(removed.is_empty());
+    })
+}
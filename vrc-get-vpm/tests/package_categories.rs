@@ -0,0 +1,109 @@
+use common::*;
+use futures::executor::block_on;
+use vrc_get_vpm::environment::PackageListingOptions;
+use vrc_get_vpm::{KeywordCategories, PackageCategory, VersionSelector};
+
+mod common;
+
+const REPOSITORY: &str = r#"{
+    "repo": {
+        "name": "Official",
+        "id": "com.vrchat.repos.official",
+        "packages": {
+            "com.anatawa12.avatar-optimizer": {
+                "versions": {
+                    "1.0.0": {
+                        "name": "com.anatawa12.avatar-optimizer",
+                        "version": "1.0.0",
+                        "keywords": ["avatar", "ndmf"]
+                    }
+                }
+            },
+            "com.anatawa12.world-gimmick": {
+                "versions": {
+                    "1.0.0": {
+                        "name": "com.anatawa12.world-gimmick",
+                        "version": "1.0.0",
+                        "keywords": "udon"
+                    }
+                }
+            },
+            "com.anatawa12.editor-tool": {
+                "versions": {
+                    "1.0.0": {
+                        "name": "com.anatawa12.editor-tool",
+                        "version": "1.0.0",
+                        "keywords": ["avatar", "editor-tool", "gimmick"]
+                    }
+                }
+            },
+            "com.anatawa12.no-keywords": {
+                "versions": {
+                    "1.0.0": {"name": "com.anatawa12.no-keywords", "version": "1.0.0"}
+                }
+            }
+        }
+    }
+}"#;
+
+#[test]
+fn filter_by_category() {
+    block_on(async {
+        let collection = load_cached_repositories(&[("Repos/vrc-official.json", REPOSITORY)]).await;
+
+        let options = PackageListingOptions::new().category(PackageCategory::AvatarComponent);
+        assert_eq!(
+            list_latest_package_names(&collection, &options),
+            ["com.anatawa12.avatar-optimizer"]
+        );
+
+        let options = PackageListingOptions::new().category(PackageCategory::WorldComponent);
+        assert_eq!(
+            list_latest_package_names(&collection, &options),
+            ["com.anatawa12.world-gimmick"]
+        );
+
+        let options = PackageListingOptions::new().category(PackageCategory::Tool);
+        assert_eq!(
+            list_latest_package_names(&collection, &options),
+            ["com.anatawa12.editor-tool"]
+        );
+
+        let options = PackageListingOptions::new().category(PackageCategory::Unknown);
+        assert_eq!(
+            list_latest_package_names(&collection, &options),
+            ["com.anatawa12.no-keywords"]
+        );
+    })
+}
+
+#[test]
+fn custom_mapping() {
+    block_on(async {
+        let collection = load_cached_repositories(&[("Repos/vrc-official.json", REPOSITORY)]).await;
+
+        let mapping = KeywordCategories::new().map("editor-tool", PackageCategory::Unknown);
+        let options = PackageListingOptions::new()
+            .category(PackageCategory::AvatarComponent)
+            .keyword_categories(&mapping);
+        assert_eq!(
+            list_latest_package_names(&collection, &options),
+            [
+                "com.anatawa12.avatar-optimizer",
+                "com.anatawa12.editor-tool"
+            ]
+        );
+
+        let package = collection
+            .find_whole_all_packages(VersionSelector::latest_for(None, true), |x| {
+                x.name() == "com.anatawa12.editor-tool"
+            })
+            .pop()
+            .unwrap();
+        assert_eq!(package.category(), PackageCategory::Tool);
+        assert_eq!(
+            package.keywords(),
+            &["avatar".into(), "editor-tool".into(), "gimmick".into()]
+        );
+    })
+}
@@ -0,0 +1,131 @@
+use common::*;
+use futures::executor::block_on;
+use std::time::{Duration, SystemTime};
+use vrc_get_vpm::environment::quick_package_index;
+use vrc_get_vpm::io::IoTrait;
+
+mod common;
+
+const REPOSITORY: &str = r#"{
+    "repo": {
+        "name": "Official",
+        "id": "com.vrchat.repos.official",
+        "packages": {
+            "com.vrchat.base": {
+                "versions": {
+                    "1.0.0": {"name": "com.vrchat.base", "version": "1.0.0", "displayName": "VRChat SDK - Base"},
+                    "1.1.0": {"name": "com.vrchat.base", "version": "1.1.0", "displayName": "VRChat SDK - Base"}
+                }
+            },
+            "com.anatawa12.package": {
+                "versions": {
+                    "2.0.0-beta.1": {"name": "com.anatawa12.package", "version": "2.0.0-beta.1"}
+                }
+            }
+        }
+    }
+}"#;
+
+const UPDATED_REPOSITORY: &str = r#"{
+    "repo": {
+        "name": "Official",
+        "id": "com.vrchat.repos.official",
+        "packages": {
+            "com.vrchat.base": {
+                "versions": {
+                    "1.2.0": {"name": "com.vrchat.base", "version": "1.2.0", "displayName": "VRChat SDK - Base"}
+                }
+            }
+        }
+    }
+}"#;
+
+const INDEX_PATH: &str = "vrc-get/package-index.json";
+
+fn names(index: &vrc_get_vpm::environment::PackageIndex) -> Vec<(String, String)> {
+    (index.packages().iter())
+        .map(|x| (x.name.to_string(), x.version.to_string()))
+        .collect()
+}
+
+#[test]
+fn build_index() {
+    block_on(async {
+        let env = VirtualFileSystem::new();
+        env.add_file("Repos/vrc-official.json".as_ref(), REPOSITORY.as_bytes())
+            .await
+            .unwrap();
+
+        let index = quick_package_index(&env).await.unwrap();
+        assert_eq!(
+            names(&index),
+            vec![
+                (
+                    "com.anatawa12.package".to_owned(),
+                    "2.0.0-beta.1".to_owned()
+                ),
+                ("com.vrchat.base".to_owned(), "1.1.0".to_owned()),
+            ]
+        );
+        assert_eq!(
+            index.packages()[1].display_name.as_deref(),
+            Some("VRChat SDK - Base")
+        );
+        assert!(env.is_file(INDEX_PATH.as_ref()).await);
+
+        // the index is reused while the caches are not changed
+        let write_count = env.write_count();
+        let index = quick_package_index(&env).await.unwrap();
+        assert_eq!(index.packages().len(), 2);
+        assert_eq!(env.write_count(), write_count);
+    })
+}
+
+#[test]
+fn rebuild_on_cache_change() {
+    block_on(async {
+        let env = VirtualFileSystem::new();
+        env.add_file("Repos/vrc-official.json".as_ref(), REPOSITORY.as_bytes())
+            .await
+            .unwrap();
+        quick_package_index(&env).await.unwrap();
+
+        env.add_file(
+            "Repos/vrc-official.json".as_ref(),
+            UPDATED_REPOSITORY.as_bytes(),
+        )
+        .await
+        .unwrap();
+        env.set_modified(
+            "Repos/vrc-official.json".as_ref(),
+            SystemTime::now() + Duration::from_secs(60),
+        )
+        .await
+        .unwrap();
+
+        let index = quick_package_index(&env).await.unwrap();
+        assert_eq!(
+            names(&index),
+            vec![("com.vrchat.base".to_owned(), "1.2.0".to_owned())]
+        );
+    })
+}
+
+#[test]
+fn rebuild_old_format() {
+    block_on(async {
+        let env = VirtualFileSystem::new();
+        env.add_file("Repos/vrc-official.json".as_ref(), REPOSITORY.as_bytes())
+            .await
+            .unwrap();
+        env.add_file(
+            INDEX_PATH.as_ref(),
+            br#"{"formatVersion":0,"sources":[],"packages":[]}"#,
+        )
+        .await
+        .unwrap();
+
+        let index = quick_package_index(&env).await.unwrap();
+        assert_eq!(index.packages().len(), 2);
+    })
+}
@@ -0,0 +1,83 @@
+use vrc_get_vpm::PackageManifest;
+
+fn parse(json: &str) -> PackageManifest {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn vrcfury() {
+    let manifest = parse(include_str!("fixtures/vrcfury.package.json"));
+
+    assert_eq!(manifest.name(), "com.vrcfury.vrcfury");
+    assert_eq!(
+        manifest.documentation_url().map(|x| x.as_str()),
+        Some("https://vrcfury.com/")
+    );
+    assert_eq!(
+        manifest.licenses_url().map(|x| x.as_str()),
+        Some("https://vrcfury.com/license")
+    );
+    assert_eq!(manifest.license(), None);
+    // a bare string is accepted as a single keyword
+    assert_eq!(manifest.keywords(), &["vrchat".into()]);
+
+    let samples = manifest.samples();
+    assert_eq!(samples.len(), 1);
+    assert_eq!(samples[0].display_name(), Some("Example Props"));
+    assert_eq!(
+        samples[0].description(),
+        Some("Example toggles using VRCFury")
+    );
+    assert_eq!(samples[0].path(), "Samples~/ExampleProps");
+}
+
+#[test]
+fn gesture_manager() {
+    let manifest = parse(include_str!("fixtures/gesture-manager.package.json"));
+
+    assert_eq!(manifest.name(), "vrchat.blackstartx.gesture-manager");
+    // empty url is treated as not specified
+    assert_eq!(manifest.documentation_url(), None);
+    assert_eq!(manifest.licenses_url(), None);
+    assert_eq!(manifest.license(), Some("MIT"));
+    assert_eq!(
+        manifest.keywords(),
+        &[
+            "vrchat".into(),
+            "avatars".into(),
+            "gesture".into(),
+            "animation".into()
+        ]
+    );
+
+    // a single object is accepted as a single sample
+    let samples = manifest.samples();
+    assert_eq!(samples.len(), 1);
+    assert_eq!(samples[0].display_name(), Some("Gesture Manager Examples"));
+    assert_eq!(samples[0].description(), None);
+    assert_eq!(samples[0].path(), "Samples~/Examples");
+}
+
+#[test]
+fn missing_and_invalid_metadata() {
+    let manifest = parse(r#"{"name": "com.anatawa12.package", "version": "1.0.0"}"#);
+    assert_eq!(manifest.documentation_url(), None);
+    assert_eq!(manifest.license(), None);
+    assert!(manifest.keywords().is_empty());
+    assert!(manifest.samples().is_empty());
+
+    let manifest = parse(
+        r#"{
+            "name": "com.anatawa12.package",
+            "version": "1.0.0",
+            "license": {"type": "MIT", "url": "https://opensource.org/licenses/MIT"},
+            "licensesUrl": 1,
+            "keywords": {"vrchat": true},
+            "samples": "Samples~"
+        }"#,
+    );
+    assert_eq!(manifest.license(), Some("MIT"));
+    assert_eq!(manifest.licenses_url(), None);
+    assert!(manifest.keywords().is_empty());
+    assert!(manifest.samples().is_empty());
+}
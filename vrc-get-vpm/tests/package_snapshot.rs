@@ -0,0 +1,148 @@
+use common::*;
+use futures::executor::block_on;
+use indexmap::IndexMap;
+use serde_json::json;
+use vrc_get_vpm::repository::{LocalCachedRepository, RemoteRepository};
+use vrc_get_vpm::unity_project::AddPackageOperation;
+use vrc_get_vpm::version::{Version, VersionRange};
+use vrc_get_vpm::{
+    ErrorCode, PackageCategory, PackageCollection as _, PackageInfo, PackageInfoSnapshot,
+    PackageManifest, PackageSource,
+};
+
+mod common;
+
+const PACKAGE_NAME: &str = "com.anatawa12.package";
+
+#[test]
+fn local_snapshot() {
+    let collection = PackageCollectionBuilder::new()
+        .add(
+            PackageManifest::new(PACKAGE_NAME, Version::new(1, 0, 0))
+                .add_vpm_dependency("com.anatawa12.library", "^1.0.0")
+                .with_unity(2022, 3),
+        )
+        .build();
+    let package = collection.get_package(PACKAGE_NAME, Version::new(1, 0, 0));
+
+    let snapshot = package.to_snapshot();
+    assert_eq!(snapshot.name(), PACKAGE_NAME);
+    assert_eq!(snapshot.version(), &Version::new(1, 0, 0));
+    assert_eq!(
+        snapshot.unity().map(|x| (x.major(), x.minor())),
+        Some((2022, 3))
+    );
+    assert_eq!(
+        snapshot.vpm_dependencies().get("com.anatawa12.library"),
+        Some(&"^1.0.0".parse::<VersionRange>().unwrap())
+    );
+    assert!(matches!(snapshot.source(), PackageSource::Local { .. }));
+    assert!(!snapshot.is_yanked());
+    assert!(snapshot.keywords().is_empty());
+    assert_eq!(snapshot.category(), PackageCategory::Unknown);
+    assert!(snapshot.author().is_none());
+
+    // the snapshot survives the serialization
+    let json = serde_json::to_string(&snapshot).unwrap();
+    let snapshot: PackageInfoSnapshot = serde_json::from_str(&json).unwrap();
+
+    let resolved = collection.resolve_snapshot(&snapshot).unwrap();
+    assert!(std::ptr::eq(
+        resolved.package_json(),
+        package.package_json()
+    ));
+}
+
+#[test]
+fn remote_snapshot() {
+    let json = json!({
+        "id": "com.anatawa12.repository",
+        "packages": {
+            PACKAGE_NAME: {
+                "versions": {
+                    "1.0.0": {
+                        "name": PACKAGE_NAME,
+                        "version": "1.0.0",
+                        "url": "https://example.com/package.zip",
+                        "keywords": ["avatar", "ndmf"],
+                        "author": "anatawa12 <anatawa12@example.com>",
+                    }
+                }
+            }
+        }
+    });
+    let serde_json::Value::Object(json) = json else {
+        unreachable!()
+    };
+    let repository =
+        LocalCachedRepository::new(RemoteRepository::parse(json).unwrap(), IndexMap::new());
+    let manifest = repository
+        .repo()
+        .get_package_version(PACKAGE_NAME, &Version::new(1, 0, 0))
+        .unwrap();
+    let package = PackageInfo::remote(manifest, &repository);
+
+    let snapshot = package.to_snapshot();
+    assert_eq!(
+        snapshot.url().map(|x| x.as_str()),
+        Some("https://example.com/package.zip")
+    );
+    assert_eq!(
+        serde_json::to_value(&snapshot).unwrap()["source"],
+        json!({ "type": "remote", "repositoryId": "com.anatawa12.repository" })
+    );
+    assert!(snapshot.is_snapshot_of(package));
+
+    // the listing information is kept across the serialization
+    let json = serde_json::to_string(&snapshot).unwrap();
+    let snapshot: PackageInfoSnapshot = serde_json::from_str(&json).unwrap();
+    assert_eq!(snapshot.keywords(), &["avatar".into(), "ndmf".into()]);
+    assert_eq!(snapshot.category(), PackageCategory::AvatarComponent);
+    let author = snapshot.author().unwrap();
+    assert_eq!(author.name(), Some("anatawa12"));
+    assert_eq!(author.email(), Some("anatawa12@example.com"));
+
+    // the same package in the other source is not the package of the snapshot
+    let local = PackageInfo::local(manifest, "Packages/com.anatawa12.package".as_ref());
+    assert!(!snapshot.is_snapshot_of(local));
+}
+
+#[test]
+fn add_package_from_snapshots() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new().build().await.unwrap();
+        let collection = PackageCollectionBuilder::new()
+            .add(PackageManifest::new(PACKAGE_NAME, Version::new(1, 0, 0)))
+            .build();
+        let snapshot = collection
+            .get_package(PACKAGE_NAME, Version::new(1, 0, 0))
+            .to_snapshot();
+
+        let changes = project
+            .add_package_request_from_snapshots(
+                &collection,
+                &[snapshot.clone()],
+                AddPackageOperation::InstallToDependencies,
+                false,
+            )
+            .await
+            .unwrap();
+        assert_eq!(changes.package_changes().len(), 1);
+
+        // the package is removed from the collection after the snapshot is taken
+        let collection = PackageCollectionBuilder::new()
+            .add(PackageManifest::new(PACKAGE_NAME, Version::new(1, 1, 0)))
+            .build();
+        let error = project
+            .add_package_request_from_snapshots(
+                &collection,
+                &[snapshot],
+                AddPackageOperation::InstallToDependencies,
+                false,
+            )
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(error.code(), "package.not-found");
+    })
+}
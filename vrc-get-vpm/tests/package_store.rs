@@ -0,0 +1,60 @@
+use common::*;
+use futures::executor::block_on;
+use std::path::Path;
+use vrc_get_vpm::environment::cleanup_package_store;
+use vrc_get_vpm::io::IoTrait;
+use vrc_get_vpm::version::Version;
+
+mod common;
+
+#[test]
+fn cleanup_unused_entries() {
+    block_on(async {
+        let env = VirtualFileSystem::new();
+        for entry in [
+            "com.anatawa12.used@1.0.0-0123456789abcdef",
+            "com.anatawa12.used@0.9.0-0123456789abcdef",
+            "com.anatawa12.unused@1.0.0-0123456789abcdef",
+            ".tmp-3f2c1e6a-0000-0000-0000-000000000000",
+        ] {
+            env.add_file(
+                &Path::new("PackageStore").join(entry).join("package.json"),
+                b"{}",
+            )
+            .await
+            .unwrap();
+        }
+
+        let project = VirtualProjectBuilder::new()
+            .add_locked("com.anatawa12.used", Version::new(1, 0, 0), &[])
+            .build()
+            .await
+            .unwrap();
+
+        let removed = cleanup_package_store(&env, [&project]).await.unwrap();
+
+        assert_eq!(removed, 3);
+        assert!(
+            env.is_dir("PackageStore/com.anatawa12.used@1.0.0-0123456789abcdef".as_ref())
+                .await
+        );
+        assert!(
+            !env.is_dir("PackageStore/com.anatawa12.used@0.9.0-0123456789abcdef".as_ref())
+                .await
+        );
+        assert!(
+            !env.is_dir("PackageStore/com.anatawa12.unused@1.0.0-0123456789abcdef".as_ref())
+                .await
+        );
+    })
+}
+
+#[test]
+fn cleanup_without_store() {
+    block_on(async {
+        let env = VirtualFileSystem::new();
+        let project = VirtualProjectBuilder::new().build().await.unwrap();
+
+        assert_eq!(cleanup_package_store(&env, [&project]).await.unwrap(), 0);
+    })
+}
@@ -0,0 +1,112 @@
+use common::*;
+use futures::executor::block_on;
+use vrc_get_vpm::unity_project::InstalledPackageKind;
+use vrc_get_vpm::version::Version;
+
+mod common;
+
+#[test]
+fn lists_all_packages() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.anatawa12.locked", Version::new(1, 0, 0))
+            .add_locked("com.anatawa12.locked", Version::new(1, 0, 0), &[])
+            .add_package_json(
+                "com.anatawa12.locked",
+                r#"{"name":"com.anatawa12.locked","version":"1.0.0"}"#,
+            )
+            .add_dependency("com.anatawa12.unlocked", Version::new(1, 0, 0))
+            .add_package_json(
+                "unlocked-folder",
+                r#"{"name":"com.anatawa12.unlocked","version":"1.2.0"}"#,
+            )
+            .add_package_json(
+                "com.anatawa12.embedded",
+                r#"{"name":"com.anatawa12.embedded","version":"0.1.0"}"#,
+            )
+            .add_dir("Packages/no-package-json")
+            .add_file(
+                "Packages/manifest.json",
+                r#"{
+                    "dependencies": {
+                        "com.unity.modules.audio": "1.0.0",
+                        "com.unity.textmeshpro": "3.0.6",
+                        "com.anatawa12.git": "https://example.com/package.git",
+                        "com.anatawa12.embedded": "0.0.1"
+                    }
+                }"#,
+            )
+            .build()
+            .await
+            .unwrap();
+
+        let overview = project.installed_packages_overview();
+        let overview = overview
+            .iter()
+            .map(|x| {
+                (
+                    x.folder_name.as_deref(),
+                    x.name.as_deref(),
+                    x.version.as_deref(),
+                    x.category,
+                    x.managed,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            overview,
+            [
+                (
+                    Some("com.anatawa12.locked"),
+                    Some("com.anatawa12.locked"),
+                    Some("1.0.0"),
+                    InstalledPackageKind::VpmLocked,
+                    true
+                ),
+                (
+                    Some("unlocked-folder"),
+                    Some("com.anatawa12.unlocked"),
+                    Some("1.2.0"),
+                    InstalledPackageKind::VpmUnlocked,
+                    false
+                ),
+                (
+                    Some("com.anatawa12.embedded"),
+                    Some("com.anatawa12.embedded"),
+                    Some("0.1.0"),
+                    InstalledPackageKind::Embedded,
+                    false
+                ),
+                (
+                    Some("no-package-json"),
+                    None,
+                    None,
+                    InstalledPackageKind::Embedded,
+                    false
+                ),
+                (
+                    None,
+                    Some("com.anatawa12.git"),
+                    Some("https://example.com/package.git"),
+                    InstalledPackageKind::Upm,
+                    false
+                ),
+                (
+                    None,
+                    Some("com.unity.textmeshpro"),
+                    Some("3.0.6"),
+                    InstalledPackageKind::Upm,
+                    false
+                ),
+                (
+                    None,
+                    Some("com.unity.modules.audio"),
+                    Some("1.0.0"),
+                    InstalledPackageKind::BuiltIn,
+                    false
+                ),
+            ]
+        );
+    })
+}
@@ -0,0 +1,101 @@
+use common::*;
+use futures::AsyncReadExt;
+use futures::executor::block_on;
+use std::path::Path;
+use vrc_get_vpm::environment::{
+    Settings, create_profile, delete_profile, list_profiles, load_profile,
+};
+use vrc_get_vpm::io::IoTrait;
+
+mod common;
+
+#[test]
+fn manage_profiles() {
+    block_on(async {
+        let env = VirtualFileSystem::new();
+        assert!(list_profiles(&env).await.unwrap().is_empty());
+
+        create_profile(&env, "sandbox", false).await.unwrap();
+        create_profile(&env, "beta", true).await.unwrap();
+        assert!(create_profile(&env, "beta", false).await.is_err());
+
+        let profiles = list_profiles(&env).await.unwrap();
+        let profiles = profiles
+            .iter()
+            .map(|x| (x.name.as_ref(), x.share_vcc_settings))
+            .collect::<Vec<_>>();
+        assert_eq!(profiles, [("beta", true), ("sandbox", false)]);
+        assert!(
+            env.is_file("vrc-get/profiles/beta/profile.json".as_ref())
+                .await
+        );
+
+        assert!(delete_profile(&env, "sandbox").await.unwrap());
+        assert!(!delete_profile(&env, "sandbox").await.unwrap());
+        assert!(load_profile(&env, "sandbox").await.unwrap().is_none());
+        assert!(!env.is_dir("vrc-get/profiles/sandbox".as_ref()).await);
+    })
+}
+
+#[test]
+fn invalid_profile_names() {
+    block_on(async {
+        let env = VirtualFileSystem::new();
+        for name in [
+            "", "default", ".", "..", ".hidden", "a/b", "a\\b", "con", "NUL", "aux.dev", "Com1",
+            "lpt9.txt", "beta.", "beta ",
+        ] {
+            assert!(
+                create_profile(&env, name, false).await.is_err(),
+                "{name} is accepted"
+            );
+        }
+    })
+}
+
+#[test]
+fn shared_vcc_settings() {
+    block_on(async {
+        let env = VirtualFileSystem::new().with_shared_settings("shared/settings.json".as_ref());
+        env.add_file(
+            "shared/settings.json".as_ref(),
+            br#"{
+                "showPrereleasePackages": true,
+                "userRepos": [
+                    {"localPath": "shared/Repos/example.json", "url": "https://example.com/vpm.json"}
+                ]
+            }"#,
+        )
+        .await
+        .unwrap();
+
+        let mut settings = Settings::load(&env).await.unwrap();
+        assert!(settings.show_prerelease_packages());
+        // the cache is in the folder of the profile
+        assert_eq!(
+            settings.get_user_repos()[0].local_path(),
+            Path::new("Repos/example.json")
+        );
+
+        // the shared settings are not updated
+        settings.set_show_prerelease_packages(false);
+        settings.save(&env).await.unwrap();
+        assert!(env.is_file("settings.json".as_ref()).await);
+        let mut shared = String::new();
+        (env.open("shared/settings.json".as_ref()).await.unwrap())
+            .read_to_string(&mut shared)
+            .await
+            .unwrap();
+        assert!(shared.contains("shared/Repos"));
+
+        // the own settings are used after saving
+        env.write(
+            "settings.json".as_ref(),
+            br#"{"showPrereleasePackages": false}"#,
+        )
+        .await
+        .unwrap();
+        let settings = Settings::load(&env).await.unwrap();
+        assert!(!settings.show_prerelease_packages());
+    })
+}
@@ -0,0 +1,338 @@
+use common::*;
+use futures::AsyncWriteExt;
+use futures::executor::block_on;
+use futures::io::Cursor;
+use std::io;
+use std::path::Path;
+use vrc_get_vpm::AbortCheck;
+use vrc_get_vpm::environment::{RestoreOptions, restore_project_backup};
+use vrc_get_vpm::io::IoTrait;
+use vrc_get_vpm::unity_project::{BackupCompression, BackupOptions};
+
+mod common;
+
+async fn read_entries(archive: Vec<u8>) -> Vec<String> {
+    let reader = async_zip::base::read::seek::ZipFileReader::new(Cursor::new(archive))
+        .await
+        .unwrap();
+    let mut entries = reader
+        .file()
+        .entries()
+        .iter()
+        .map(|x| x.filename().as_str().unwrap().to_owned())
+        .collect::<Vec<_>>();
+    entries.sort();
+    entries
+}
+
+#[test]
+fn backup_excludes_generated_folders() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("Assets/Scene.unity", "scene")
+            .add_file("Assets/Private/Secret.txt", "secret")
+            .add_file(
+                "ProjectSettings/ProjectVersion.txt",
+                "m_EditorVersion: 2022.3.22f1\n",
+            )
+            .add_file("Library/ArtifactDB", "cache")
+            .add_file("Temp/UnityLockfile", "")
+            .add_file("logs/Upm.log", "log")
+            .add_file("Assets/Library/Kept.txt", "kept")
+            .build()
+            .await
+            .unwrap();
+
+        let options = BackupOptions::new()
+            .exclude("Assets/Private")
+            .compression(BackupCompression::Fast);
+        let mut archive = Cursor::new(Vec::new());
+        let mut last_progress = None;
+        let entries = project
+            .create_backup_archive(&mut archive, &options, &AbortCheck::new(), |progress| {
+                last_progress = Some((progress.proceed_files, progress.total_files));
+            })
+            .await
+            .unwrap();
+
+        let expected = [
+            "Assets/",
+            "Assets/Library/",
+            "Assets/Library/Kept.txt",
+            "Assets/Scene.unity",
+            "Packages/",
+            "Packages/vpm-manifest.json",
+            "ProjectSettings/",
+            "ProjectSettings/ProjectVersion.txt",
+        ];
+
+        assert_eq!(entries, expected.len());
+        assert_eq!(last_progress, Some((expected.len(), expected.len())));
+        assert_eq!(read_entries(archive.into_inner()).await, expected);
+    })
+}
+
+#[test]
+fn backup_excludes_project_lock() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("Assets/Scene.unity", "scene")
+            .add_file(".vrc-get.lock", r#"{"pid":1}"#)
+            .build()
+            .await
+            .unwrap();
+
+        let mut archive = Cursor::new(Vec::new());
+        project
+            .create_backup_archive(
+                &mut archive,
+                &BackupOptions::new(),
+                &AbortCheck::new(),
+                |_| {},
+            )
+            .await
+            .unwrap();
+
+        let entries = read_entries(archive.into_inner()).await;
+        assert!(entries.iter().any(|x| x == "Assets/Scene.unity"));
+        assert!(!entries.iter().any(|x| x == ".vrc-get.lock"));
+    })
+}
+
+#[test]
+fn backup_includes_only_specified_paths() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("Assets/Scene.unity", "scene")
+            .add_file("Assets/LegacyFolder/Script.cs", "script")
+            .add_file("Packages/com.anatawa12.package/package.json", "{}")
+            .build()
+            .await
+            .unwrap();
+
+        let options = BackupOptions::new()
+            .include("Packages/vpm-manifest.json")
+            .include("Packages\\com.anatawa12.package")
+            .include("Assets/LegacyFolder/");
+        let mut archive = Cursor::new(Vec::new());
+        project
+            .create_backup_archive(&mut archive, &options, &AbortCheck::new(), |_| {})
+            .await
+            .unwrap();
+
+        let expected = [
+            "Assets/",
+            "Assets/LegacyFolder/",
+            "Assets/LegacyFolder/Script.cs",
+            "Packages/",
+            "Packages/com.anatawa12.package/",
+            "Packages/com.anatawa12.package/package.json",
+            "Packages/vpm-manifest.json",
+        ];
+
+        assert_eq!(read_entries(archive.into_inner()).await, expected);
+    })
+}
+
+#[test]
+fn backup_aborted() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("Assets/Scene.unity", "scene")
+            .build()
+            .await
+            .unwrap();
+
+        let abort = AbortCheck::new();
+        abort.abort();
+
+        let error = project
+            .create_backup_archive(
+                Cursor::new(Vec::new()),
+                &BackupOptions::new(),
+                &abort,
+                |_| {},
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::Interrupted);
+    })
+}
+
+async fn create_zip(entries: &[(&str, &str)]) -> Vec<u8> {
+    let mut writer = async_zip::base::write::ZipFileWriter::new(Cursor::new(Vec::new()));
+    for &(name, content) in entries {
+        let builder = async_zip::ZipEntryBuilder::new(name.into(), async_zip::Compression::Stored);
+        writer
+            .write_entry_whole(builder, content.as_bytes())
+            .await
+            .unwrap();
+    }
+    writer.close().await.unwrap().into_inner()
+}
+
+async fn write_file(io: &impl IoTrait, path: &str, content: &[u8]) {
+    let mut file = io.create_new(path.as_ref()).await.unwrap();
+    file.write_all(content).await.unwrap();
+    file.flush().await.unwrap();
+}
+
+#[test]
+fn restore_backup_round_trip() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("Assets/Scene.unity", "scene")
+            .add_file(
+                "ProjectSettings/ProjectVersion.txt",
+                "m_EditorVersion: 2022.3.22f1\n",
+            )
+            .build()
+            .await
+            .unwrap();
+
+        let mut archive = Cursor::new(Vec::new());
+        project
+            .create_backup_archive(
+                &mut archive,
+                &BackupOptions::new(),
+                &AbortCheck::new(),
+                |_| {},
+            )
+            .await
+            .unwrap();
+
+        let env = VirtualFileSystem::new();
+        write_file(&env, "backup.zip", &archive.into_inner()).await;
+
+        restore_project_backup(
+            &env,
+            "backup.zip".as_ref(),
+            "Projects/Restored".as_ref(),
+            &RestoreOptions::new(),
+            &AbortCheck::new(),
+            |_| {},
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            read_file(&env, "Projects/Restored/Assets/Scene.unity").await,
+            "scene"
+        );
+        assert_eq!(
+            read_file(&env, "Projects/Restored/ProjectSettings/ProjectVersion.txt").await,
+            "m_EditorVersion: 2022.3.22f1\n"
+        );
+
+        // the temporary folder should be renamed
+        let mut entries = env.read_dir("Projects".as_ref()).await.unwrap();
+        let mut names = vec![];
+        while let Some(entry) = futures::TryStreamExt::try_next(&mut entries).await.unwrap() {
+            names.push(vrc_get_vpm::io::DirEntry::file_name(&entry));
+        }
+        assert_eq!(names, ["Restored"]);
+    })
+}
+
+#[test]
+fn restore_backup_strips_top_level_folder() {
+    block_on(async {
+        let env = VirtualFileSystem::new();
+        let archive = create_zip(&[
+            ("MyProject/Assets/Scene.unity", "scene"),
+            (
+                "MyProject/ProjectSettings/ProjectVersion.txt",
+                "m_EditorVersion: 2019.4.31f1\n",
+            ),
+        ])
+        .await;
+        write_file(&env, "backup.zip", &archive).await;
+
+        restore_project_backup(
+            &env,
+            "backup.zip".as_ref(),
+            "Restored".as_ref(),
+            &RestoreOptions::new(),
+            &AbortCheck::new(),
+            |_| {},
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            read_file(&env, "Restored/Assets/Scene.unity").await,
+            "scene"
+        );
+    })
+}
+
+#[test]
+fn restore_backup_safety_checks() {
+    block_on(async {
+        let env = VirtualFileSystem::new();
+
+        let not_project = create_zip(&[("Assets/Scene.unity", "scene")]).await;
+        write_file(&env, "not-project.zip", &not_project).await;
+
+        let traversal = create_zip(&[
+            ("ProjectSettings/ProjectVersion.txt", ""),
+            ("../evil.txt", "evil"),
+        ])
+        .await;
+        write_file(&env, "traversal.zip", &traversal).await;
+
+        let valid = create_zip(&[("ProjectSettings/ProjectVersion.txt", "")]).await;
+        write_file(&env, "valid.zip", &valid).await;
+        env.add_file(Path::new("Existing/Assets/Old.txt"), b"old")
+            .await
+            .unwrap();
+
+        let restore =
+            |archive: &'static str, destination: &'static str, options: RestoreOptions| {
+                let env = &env;
+                async move {
+                    restore_project_backup(
+                        env,
+                        archive.as_ref(),
+                        destination.as_ref(),
+                        &options,
+                        &AbortCheck::new(),
+                        |_| {},
+                    )
+                    .await
+                    .map_err(|e| *e.into_inner().downcast::<io::Error>().unwrap())
+                }
+            };
+
+        let error = restore("not-project.zip", "Restored", RestoreOptions::new())
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        let error = restore("traversal.zip", "Restored", RestoreOptions::new())
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(!env.is_dir("Restored".as_ref()).await);
+
+        let error = restore("valid.zip", "Existing", RestoreOptions::new())
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(read_file(&env, "Existing/Assets/Old.txt").await, "old");
+
+        restore(
+            "valid.zip",
+            "Existing",
+            RestoreOptions::new().overwrite(true),
+        )
+        .await
+        .unwrap();
+        assert!(!env.is_file("Existing/Assets/Old.txt".as_ref()).await);
+        assert!(
+            env.is_file("Existing/ProjectSettings/ProjectVersion.txt".as_ref())
+                .await
+        );
+    })
+}
@@ -0,0 +1,318 @@
+#![cfg(feature = "experimental-project-management")]
+
+use common::*;
+use futures::executor::block_on;
+use futures::io::Cursor;
+use std::path::Path;
+use vrc_get_vpm::AbortCheck;
+use vrc_get_vpm::environment::{
+    EmbeddedTemplate, ProjectTemplate, Settings, list_project_templates,
+};
+use vrc_get_vpm::io::IoTrait;
+use vrc_get_vpm::unity_project::BackupOptions;
+use vrc_get_vpm::version::UnityVersion;
+use vrc_get_vpm::{ProjectType, UnityProject};
+
+mod common;
+
+#[test]
+fn avatars_skeleton() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        ProjectTemplate::Avatars
+            .instantiate(&VirtualFileSystem::new(), &fs, "Avatar Project")
+            .await
+            .unwrap();
+
+        assert!(fs.is_dir("Assets".as_ref()).await);
+
+        let project = UnityProject::load(fs).await.unwrap();
+        assert_eq!(
+            project.unity_version(),
+            Some(UnityVersion::new_f1(2022, 3, 22))
+        );
+        assert_eq!(project.unity_revision(), Some("887be4894c44"));
+
+        let dependencies = project.dependencies().collect::<Vec<_>>();
+        assert_eq!(dependencies, ["com.vrchat.avatars"]);
+        assert_eq!(
+            project.detect_project_type().await.unwrap(),
+            ProjectType::VpmStarter
+        );
+    })
+}
+
+#[test]
+fn blank_skeleton() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        ProjectTemplate::Blank
+            .instantiate(&VirtualFileSystem::new(), &fs, "Blank Project")
+            .await
+            .unwrap();
+
+        let project = UnityProject::load(fs).await.unwrap();
+        assert_eq!(project.dependencies().count(), 0);
+    })
+}
+
+#[test]
+fn worlds_without_udonsharp_skeleton() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        ProjectTemplate::WorldsWithoutUdonSharp
+            .instantiate(&VirtualFileSystem::new(), &fs, "World Project")
+            .await
+            .unwrap();
+
+        let manifest = read_file(&fs, "Packages/vpm-manifest.json").await;
+        let manifest = serde_json::from_str::<serde_json::Value>(&manifest).unwrap();
+        assert_eq!(
+            manifest["dependencies"],
+            serde_json::json!({ "com.vrchat.worlds": { "version": "3.3.x" } })
+        );
+
+        let project = UnityProject::load(fs).await.unwrap();
+        assert_eq!(
+            project.unity_version(),
+            Some(UnityVersion::new_f1(2019, 4, 31))
+        );
+    })
+}
+
+#[test]
+fn instantiate_embedded_template() {
+    block_on(async {
+        let files = vec![
+            (
+                "Packages/vpm-manifest.json".into(),
+                TEMPLATE_MANIFEST.as_bytes().into(),
+            ),
+            (
+                "ProjectSettings/ProjectSettings.asset".into(),
+                TEMPLATE_PROJECT_SETTINGS.as_bytes().into(),
+            ),
+            (
+                "ProjectSettings/ProjectVersion.txt".into(),
+                b"m_EditorVersion: 2022.3.22f1\n"[..].into(),
+            ),
+        ];
+        let template = ProjectTemplate::Embedded(EmbeddedTemplate::new(
+            "avatars-2022.3.22f1",
+            "Avatars 2022.3.22f1",
+            files,
+        ));
+        assert_eq!(template.name(), "Avatars 2022.3.22f1");
+
+        let fs = VirtualFileSystem::new();
+        template
+            .instantiate(&VirtualFileSystem::new(), &fs, "Embedded")
+            .await
+            .unwrap();
+
+        assert!(fs.is_dir("Assets".as_ref()).await);
+        let settings = read_file(&fs, "ProjectSettings/ProjectSettings.asset").await;
+        assert!(settings.contains("productName: \"Embedded\"\n"));
+        assert!(!settings.contains("0123456789abcdef0123456789abcdef"));
+
+        let project = UnityProject::load(fs).await.unwrap();
+        let dependencies = project.dependencies().collect::<Vec<_>>();
+        assert_eq!(dependencies, ["com.vrchat.avatars"]);
+    })
+}
+
+const TEMPLATE_MANIFEST: &str = r#"{
+  "dependencies": {
+    "com.vrchat.avatars": { "version": "3.x" }
+  },
+  "locked": {}
+}"#;
+
+const TEMPLATE_PROJECT_SETTINGS: &str = "%YAML 1.1\nPlayerSettings:\n  companyName: DefaultCompany\n  productName: Template Base\n  productGUID: 0123456789abcdef0123456789abcdef\n";
+
+async fn add_template_folder(fs: &VirtualFileSystem, folder: &str) {
+    let files = [
+        ("Assets/Shader.shader", "shader"),
+        ("Packages/vpm-manifest.json", TEMPLATE_MANIFEST),
+        (
+            "ProjectSettings/ProjectVersion.txt",
+            "m_EditorVersion: 2022.3.22f1\n",
+        ),
+        (
+            "ProjectSettings/ProjectSettings.asset",
+            TEMPLATE_PROJECT_SETTINGS,
+        ),
+        ("Library/ArtifactDB", "cache"),
+        ("temp/UnityLockfile", ""),
+        ("package.json", "{}"),
+    ];
+
+    for (path, content) in files {
+        let path = Path::new(folder).join(path);
+        fs.add_file(&path, content.as_bytes()).await.unwrap();
+    }
+}
+
+async fn find_template(env: &VirtualFileSystem, name: &str) -> ProjectTemplate {
+    let settings = Settings::load(env).await.unwrap();
+    list_project_templates(&settings, env)
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|x| x.name() == name)
+        .unwrap()
+}
+
+#[test]
+fn list_user_templates() {
+    block_on(async {
+        let env = VirtualFileSystem::new();
+        add_template_folder(&env, "Templates/Studio Base").await;
+        env.add_file(
+            "Templates/No Manifest/ProjectSettings/ProjectVersion.txt".as_ref(),
+            b"m_EditorVersion: 2022.3.22f1\n",
+        )
+        .await
+        .unwrap();
+        add_template_folder(&env, "Templates/Broken Manifest").await;
+        env.add_file(
+            "Templates/Broken Manifest/Packages/vpm-manifest.json".as_ref(),
+            b"{ broken",
+        )
+        .await
+        .unwrap();
+        env.add_file("Templates/Not A Zip.zip".as_ref(), b"not a zip")
+            .await
+            .unwrap();
+        env.add_file("Templates/README.txt".as_ref(), b"ignored")
+            .await
+            .unwrap();
+
+        let settings = Settings::load(&env).await.unwrap();
+        let templates = list_project_templates(&settings, &env).await.unwrap();
+
+        let listed = templates
+            .iter()
+            .map(|x| (x.name(), x.unusable_reason().is_some()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            listed,
+            [
+                ("Avatars", false),
+                ("Worlds", false),
+                ("Worlds without UdonSharp", false),
+                ("Blank", false),
+                ("Broken Manifest", true),
+                ("No Manifest", true),
+                ("Not A Zip", true),
+                ("Studio Base", false),
+            ]
+        );
+    })
+}
+
+#[test]
+fn no_templates_folder() {
+    block_on(async {
+        let env = VirtualFileSystem::new();
+        let settings = Settings::load(&env).await.unwrap();
+        let templates = list_project_templates(&settings, &env).await.unwrap();
+        assert_eq!(templates, ProjectTemplate::BUILTIN);
+    })
+}
+
+#[test]
+fn instantiate_folder_template() {
+    block_on(async {
+        let env = VirtualFileSystem::new();
+        add_template_folder(&env, "Templates/Studio Base").await;
+        let template = find_template(&env, "Studio Base").await;
+
+        let fs = VirtualFileSystem::new();
+        template
+            .instantiate(&env, &fs, "My \"First\" Avatar")
+            .await
+            .unwrap();
+
+        assert!(fs.is_file("Assets/Shader.shader".as_ref()).await);
+        assert!(!fs.is_dir("Library".as_ref()).await);
+        assert!(!fs.is_dir("temp".as_ref()).await);
+        assert!(!fs.is_file("package.json".as_ref()).await);
+
+        let project_settings = read_file(&fs, "ProjectSettings/ProjectSettings.asset").await;
+        assert!(project_settings.contains("  productName: \"My \\\"First\\\" Avatar\"\n"));
+        assert!(project_settings.contains("  companyName: DefaultCompany\n"));
+        assert!(!project_settings.contains("0123456789abcdef0123456789abcdef"));
+
+        let project = UnityProject::load(fs).await.unwrap();
+        let dependencies = project.dependencies().collect::<Vec<_>>();
+        assert_eq!(dependencies, ["com.vrchat.avatars"]);
+    })
+}
+
+#[test]
+fn instantiate_zip_template() {
+    block_on(async {
+        let template_project = VirtualProjectBuilder::new()
+            .add_dependency_range("com.vrchat.avatars", "3.x")
+            .add_file("Assets/Shader.shader", "shader")
+            .add_file(
+                "ProjectSettings/ProjectVersion.txt",
+                "m_EditorVersion: 2022.3.22f1\n",
+            )
+            .add_file(
+                "ProjectSettings/ProjectSettings.asset",
+                TEMPLATE_PROJECT_SETTINGS,
+            )
+            .build()
+            .await
+            .unwrap();
+        let mut archive = Cursor::new(Vec::new());
+        template_project
+            .create_backup_archive(
+                &mut archive,
+                &BackupOptions::new(),
+                &AbortCheck::new(),
+                |_| {},
+            )
+            .await
+            .unwrap();
+
+        let env = VirtualFileSystem::new();
+        env.add_file("Templates/Zipped.zip".as_ref(), &archive.into_inner())
+            .await
+            .unwrap();
+        let template = find_template(&env, "Zipped").await;
+        assert_eq!(template.unusable_reason(), None);
+
+        let fs = VirtualFileSystem::new();
+        template
+            .instantiate(&env, &fs, "Zipped Avatar")
+            .await
+            .unwrap();
+
+        assert!(fs.is_file("Assets/Shader.shader".as_ref()).await);
+        let project_settings = read_file(&fs, "ProjectSettings/ProjectSettings.asset").await;
+        assert!(project_settings.contains("  productName: \"Zipped Avatar\"\n"));
+
+        let project = UnityProject::load(fs).await.unwrap();
+        let dependencies = project.dependencies().collect::<Vec<_>>();
+        assert_eq!(dependencies, ["com.vrchat.avatars"]);
+    })
+}
+
+#[test]
+fn instantiate_unusable_template() {
+    block_on(async {
+        let env = VirtualFileSystem::new();
+        env.add_file("Templates/Empty/Assets/.keep".as_ref(), b"")
+            .await
+            .unwrap();
+        let template = find_template(&env, "Empty").await;
+        assert!(template.unusable_reason().is_some());
+
+        let fs = VirtualFileSystem::new();
+        let error = template.instantiate(&env, &fs, "Empty").await.unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    })
+}
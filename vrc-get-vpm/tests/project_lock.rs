@@ -0,0 +1,175 @@
+use futures::executor::block_on;
+use std::io;
+use std::time::{Duration, SystemTime};
+use vrc_get_vpm::io::memory::MemoryProjectIo;
+use vrc_get_vpm::io::{IoTrait, ProjectIo};
+use vrc_get_vpm::unity_project::{ProjectLockedError, ProjectModifiedError};
+use vrc_get_vpm::{ErrorCode, UnityProject};
+
+const LOCK_PATH: &str = ".vrc-get.lock";
+
+fn new_project_io() -> MemoryProjectIo {
+    MemoryProjectIo::new()
+        .with_vpm_manifest(r#"{"dependencies": {}, "locked": {}}"#)
+        .with_unity_version("2022.3.22f1", "887be4894c44")
+}
+
+fn lock_file(pid: u32, token: &str, created_at: SystemTime) -> String {
+    let created_at = (created_at.duration_since(SystemTime::UNIX_EPOCH))
+        .unwrap()
+        .as_secs();
+    format!(r#"{{"pid": {pid}, "token": "{token}", "createdAt": {created_at}}}"#)
+}
+
+fn other_process() -> u32 {
+    std::process::id().wrapping_add(1)
+}
+
+#[test]
+fn concurrent_operations() {
+    block_on(async {
+        let io = new_project_io();
+        let mut first = UnityProject::load(io.clone()).await.unwrap();
+        let second = UnityProject::load(io.clone()).await.unwrap();
+
+        // one waits for the other, and both complete
+        let (saved, written) = futures::join!(first.save(), second.write_default_gitignore(false));
+        saved.unwrap();
+        assert!(written.unwrap());
+
+        assert!(!io.is_file(LOCK_PATH.as_ref()).await);
+    })
+}
+
+#[test]
+fn waits_for_other_operation() {
+    block_on(async {
+        let io = new_project_io().with_file(
+            LOCK_PATH,
+            lock_file(other_process(), "other", SystemTime::now()),
+        );
+        let mut project = UnityProject::load(io.clone()).await.unwrap();
+
+        let release = async {
+            for _ in 0..5 {
+                io.sleep(Duration::from_millis(100)).await;
+            }
+            io.remove_file(LOCK_PATH.as_ref()).await.unwrap();
+        };
+        let (saved, ()) = futures::join!(project.save(), release);
+        saved.unwrap();
+
+        assert!(!io.is_file(LOCK_PATH.as_ref()).await);
+    })
+}
+
+#[test]
+fn fails_while_locked() {
+    block_on(async {
+        let io = new_project_io().with_file(
+            LOCK_PATH,
+            lock_file(other_process(), "other", SystemTime::now()),
+        );
+        let mut project = UnityProject::load(io.clone()).await.unwrap();
+        project.set_lock_timeout(Duration::ZERO);
+
+        let error = project.save().await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::ResourceBusy);
+        assert_eq!(error.code(), "project.operation-in-progress");
+        let inner = (error.get_ref())
+            .and_then(|x| x.downcast_ref::<ProjectLockedError>())
+            .unwrap();
+        assert_eq!(inner.pid(), Some(other_process()));
+
+        let error = project.write_default_gitignore(true).await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::ResourceBusy);
+        assert!(!io.is_file(".gitignore".as_ref()).await);
+
+        // the lock of the other process is kept
+        assert!(io.is_file(LOCK_PATH.as_ref()).await);
+    })
+}
+
+#[test]
+fn breaks_stale_locks() {
+    block_on(async {
+        let day_ago = SystemTime::now() - Duration::from_secs(24 * 60 * 60);
+        for content in [
+            // crashed long ago
+            lock_file(other_process(), "other", day_ago),
+            // left by the operation dropped in this process
+            lock_file(std::process::id(), "dropped", SystemTime::now()),
+        ] {
+            let io = new_project_io().with_file(LOCK_PATH, content);
+            let mut project = UnityProject::load(io.clone()).await.unwrap();
+            project.set_lock_timeout(Duration::ZERO);
+
+            project.save().await.unwrap();
+            assert!(!io.is_file(LOCK_PATH.as_ref()).await);
+        }
+    })
+}
+
+#[test]
+fn read_only_operations() {
+    block_on(async {
+        let io = new_project_io().with_file(
+            LOCK_PATH,
+            lock_file(other_process(), "other", SystemTime::now()),
+        );
+        let mut project = UnityProject::load(io.clone()).await.unwrap();
+        project.set_lock_timeout(Duration::ZERO);
+
+        project.remove_request(&[]).await.unwrap();
+        project.unity_lock_state().await.unwrap();
+        project.detect_project_type().await.unwrap();
+    })
+}
+
+#[test]
+fn guard_holds_lock() {
+    block_on(async {
+        let io = new_project_io();
+        let mut project = UnityProject::load(io.clone()).await.unwrap();
+        let mut other = UnityProject::load(io.clone()).await.unwrap();
+        other.set_lock_timeout(Duration::ZERO);
+
+        let guard = project.lock_project().await.unwrap();
+        // the operations share the lock, and the lock is released when the guard is dropped
+        project.save().await.unwrap();
+        assert!(io.is_file(LOCK_PATH.as_ref()).await);
+
+        let error = other.save().await.unwrap_err();
+        assert_eq!(error.code(), "project.operation-in-progress");
+
+        drop(guard);
+        assert!(!io.is_file(LOCK_PATH.as_ref()).await);
+        other.save().await.unwrap();
+    })
+}
+
+#[test]
+fn modified_by_other_process() {
+    block_on(async {
+        let io = new_project_io();
+        let mut project = UnityProject::load(io.clone()).await.unwrap();
+
+        io.write_sync(
+            "Packages/vpm-manifest.json".as_ref(),
+            br#"{"dependencies": {"com.vrchat.base": {"version": "3.7.0"}}, "locked": {}}"#,
+        )
+        .await
+        .unwrap();
+
+        let error = project.lock_project().await.unwrap_err();
+        assert_eq!(error.code(), "project.modified-externally");
+        assert!((error.get_ref()).is_some_and(|x| x.is::<ProjectModifiedError>()));
+        let error = project.save().await.unwrap_err();
+        assert_eq!(error.code(), "project.modified-externally");
+        assert!(!io.is_file(LOCK_PATH.as_ref()).await);
+
+        // the project loaded again can be modified
+        let project = UnityProject::load(io.clone()).await.unwrap();
+        drop(project.lock_project().await.unwrap());
+    })
+}
@@ -0,0 +1,86 @@
+use common::*;
+use futures::executor::block_on;
+use vrc_get_vpm::AbortCheck;
+use vrc_get_vpm::io::IoTrait;
+
+mod common;
+
+#[test]
+fn size_report() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("Assets/Scene.unity", "0123456789")
+            .add_file("Assets/Scripts/Script.cs", "01234")
+            .add_file("Library/ArtifactDB", "0123456789012345")
+            .add_file("Library/Artifacts/00/0000", "0123")
+            .add_file("Temp/temp.txt", "01")
+            .add_file("ProjectSettings/ProjectSettings.asset", "012")
+            .build()
+            .await
+            .unwrap();
+
+        let report = project.size_report(&AbortCheck::new()).await.unwrap();
+
+        assert_eq!(report.assets, 15);
+        assert_eq!(report.library, 20);
+        assert_eq!(report.temp, 2);
+        assert_eq!(report.other, 3);
+        assert!(report.packages > 0); // vpm-manifest.json
+        assert_eq!(report.file_count, 7);
+        assert_eq!(report.clearable(), 22);
+    })
+}
+
+#[test]
+fn size_report_aborted() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("Library/ArtifactDB", "0123456789")
+            .build()
+            .await
+            .unwrap();
+
+        let abort = AbortCheck::new();
+        abort.abort();
+        let err = project.size_report(&abort).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::Interrupted);
+    })
+}
+
+#[test]
+fn clear_library() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("Assets/Scene.unity", "0123456789")
+            .add_file("Library/ArtifactDB", "0123456789")
+            .add_file("Temp/temp.txt", "01")
+            .build()
+            .await
+            .unwrap();
+
+        let freed = project.clear_library(&AbortCheck::new()).await.unwrap();
+
+        assert_eq!(freed, 12);
+        assert!(!project.io().is_dir("Library".as_ref()).await);
+        assert!(!project.io().is_dir("Temp".as_ref()).await);
+        assert!(project.io().is_file("Assets/Scene.unity".as_ref()).await);
+    })
+}
+
+#[test]
+fn clear_library_open_in_unity() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("Library/ArtifactDB", "0123456789")
+            .add_file("Temp/UnityLockfile", "")
+            .build()
+            .await
+            .unwrap();
+        project.io().lock_exclusively("Temp/UnityLockfile".as_ref());
+
+        let err = project.clear_library(&AbortCheck::new()).await.unwrap_err();
+
+        assert_eq!(err.kind(), std::io::ErrorKind::ResourceBusy);
+        assert!(project.io().is_file("Library/ArtifactDB".as_ref()).await);
+    })
+}
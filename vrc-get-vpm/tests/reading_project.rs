@@ -1,5 +1,7 @@
-use crate::common::VirtualProjectBuilder;
+use crate::common::{VirtualFileSystem, VirtualProjectBuilder};
 use futures::executor::block_on;
+use vrc_get_vpm::UnityProject;
+use vrc_get_vpm::unity_project::ProjectDiagnostic;
 use vrc_get_vpm::version::{ReleaseType, UnityVersion, Version};
 
 mod common;
@@ -66,3 +68,132 @@ fn read_package_json_with_bad_url() {
         assert_eq!(package_json.name(), "com.anatawa12.package");
     })
 }
+
+#[test]
+fn diagnostics_for_broken_project() {
+    block_on(async {
+        let mut project = VirtualProjectBuilder::new()
+            .add_locked("com.anatawa12.package", Version::new(1, 0, 0), &[])
+            .add_file("Packages/vpm-manifest.json", "{ broken")
+            .add_file("Packages/com.anatawa12.package/README.md", "not a package")
+            .add_file(
+                "ProjectSettings/ProjectVersion.txt",
+                "m_EditorVersion: broken\n",
+            )
+            .build()
+            .await
+            .unwrap();
+
+        let diagnostics = project.diagnostics();
+        assert_eq!(diagnostics.len(), 2);
+        assert!(matches!(
+            &diagnostics[0],
+            ProjectDiagnostic::VpmManifestInvalid { .. }
+        ));
+        assert!(matches!(
+            &diagnostics[1],
+            ProjectDiagnostic::ProjectVersionInvalid { .. }
+        ));
+
+        // the broken manifest is treated as empty, so the folder is not locked
+        assert_eq!(project.locked_packages().count(), 0);
+        assert_eq!(project.unlocked_packages().len(), 1);
+
+        // saving unchanged project is fine
+        project.save().await.unwrap();
+
+        let json = serde_json::to_value(&diagnostics[0]).unwrap();
+        assert_eq!(json["kind"], "vpmManifestInvalid");
+        assert!(json["message"].is_string());
+    })
+}
+
+#[test]
+fn diagnostics_for_non_vpm_package() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_locked("com.anatawa12.package", Version::new(1, 0, 0), &[])
+            .add_file("Packages/com.anatawa12.package/README.md", "not a package")
+            .add_file(
+                "ProjectSettings/ProjectVersion.txt",
+                "m_EditorVersion: 2022.3.22f1\n",
+            )
+            .build()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            project.diagnostics(),
+            [ProjectDiagnostic::NonVpmPackage {
+                name: "com.anatawa12.package".into()
+            }]
+        );
+    })
+}
+
+#[test]
+fn diagnostics_for_invalid_package_names() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("../../evil", Version::new(1, 0, 0))
+            .add_locked(
+                "com.anatawa12.package",
+                Version::new(1, 0, 0),
+                &[("con", "1.0.0")],
+            )
+            .add_file(
+                "ProjectSettings/ProjectVersion.txt",
+                "m_EditorVersion: 2022.3.22f1\n",
+            )
+            .build()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            project.diagnostics(),
+            [
+                ProjectDiagnostic::InvalidPackageName {
+                    name: "../../evil".into()
+                },
+                ProjectDiagnostic::InvalidPackageName { name: "con".into() },
+            ]
+        );
+    })
+}
+
+#[test]
+fn diagnostics_for_missing_files() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        fs.add_file("Assets/Scene.unity".as_ref(), b"scene")
+            .await
+            .unwrap();
+        let project = UnityProject::load(fs).await.unwrap();
+
+        assert_eq!(
+            project.diagnostics(),
+            [
+                ProjectDiagnostic::PackagesFolderMissing,
+                ProjectDiagnostic::ProjectVersionMissing,
+            ]
+        );
+    })
+}
+
+#[test]
+fn packages_is_file() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        fs.add_file("Packages".as_ref(), b"").await.unwrap();
+        fs.add_file(
+            "ProjectSettings/ProjectVersion.txt".as_ref(),
+            b"m_EditorVersion: 2022.3.22f1\n",
+        )
+        .await
+        .unwrap();
+        let project = UnityProject::load(fs).await.unwrap();
+
+        assert_eq!(project.diagnostics(), [ProjectDiagnostic::PackagesIsFile]);
+        assert_eq!(project.dependencies().count(), 0);
+    })
+}
@@ -0,0 +1,171 @@
+use serde_json::json;
+use vrc_get_vpm::repository::RemoteRepository;
+use vrc_get_vpm::version::Version;
+
+#[test]
+fn lenient_versions() {
+    let repository = json!({
+        "name": "Test Repository",
+        "id": "com.anatawa12.test",
+        "url": "https://example.com/vpm.json",
+        "packages": {
+            "com.anatawa12.package": {
+                "versions": {
+                    "1.0.0": {
+                        "name": "com.anatawa12.package",
+                        "version": "1.0.0",
+                    },
+                    "v1.1.0": {
+                        "name": "com.anatawa12.package",
+                        "version": "v1.1.0",
+                    },
+                    "1.2": {
+                        "name": "com.anatawa12.package",
+                        "version": "1.2",
+                    },
+                    "1.3.0.4": {
+                        "name": "com.anatawa12.package",
+                        "version": "1.3.0.4",
+                    },
+                    "not a version": {
+                        "name": "com.anatawa12.package",
+                        "version": "not a version",
+                    },
+                },
+            },
+        },
+    });
+    let serde_json::Value::Object(repository) = repository else {
+        unreachable!()
+    };
+
+    let repository = RemoteRepository::parse(repository).unwrap();
+    let package = repository.get_package("com.anatawa12.package").unwrap();
+    assert_eq!(package.all_versions().count(), 4);
+
+    let strict = package.get_version(&Version::new(1, 0, 0)).unwrap();
+    assert_eq!(strict.original_version(), None);
+
+    let v_prefix = package.get_version(&Version::new(1, 1, 0)).unwrap();
+    assert_eq!(v_prefix.version(), &Version::new(1, 1, 0));
+    assert_eq!(v_prefix.original_version(), Some("v1.1.0"));
+
+    let zero_filled = package.get_version(&Version::new(1, 2, 0)).unwrap();
+    assert_eq!(zero_filled.original_version(), Some("1.2"));
+
+    let truncated = package.get_version(&Version::new(1, 3, 0)).unwrap();
+    assert_eq!(truncated.original_version(), Some("1.3.0.4"));
+}
+
+#[test]
+fn shared_strings() {
+    const VERSIONS: usize = 100;
+    let description = "The description of the package, repeated for all versions.";
+
+    let versions = (0..VERSIONS)
+        .map(|minor| {
+            let version = format!("1.{minor}.0");
+            let manifest = json!({
+                "name": "com.anatawa12.package",
+                "version": version,
+                "displayName": "Package",
+                "description": description,
+                "author": { "name": "anatawa12", "email": "anatawa12@example.com" },
+            });
+            (version, manifest)
+        })
+        .collect::<serde_json::Map<_, _>>();
+    let repository = json!({
+        "name": "Test Repository",
+        "id": "com.anatawa12.test",
+        "url": "https://example.com/vpm.json",
+        "packages": {
+            "com.anatawa12.package": { "versions": versions },
+        },
+    });
+    let serde_json::Value::Object(repository) = repository else {
+        unreachable!()
+    };
+
+    let parsed = RemoteRepository::parse(repository.clone()).unwrap();
+    let manifests = parsed
+        .get_versions_of("com.anatawa12.package")
+        .collect::<Vec<_>>();
+    assert_eq!(manifests.len(), VERSIONS);
+
+    // the bytes of the strings, counting the shared allocation once
+    let strings = manifests.iter().flat_map(|manifest| {
+        let author = manifest.author().unwrap();
+        [
+            Some(manifest.name()),
+            manifest.display_name(),
+            manifest.description(),
+            author.name(),
+            author.email(),
+        ]
+        .into_iter()
+        .flatten()
+    });
+    let total_bytes = strings.clone().map(str::len).sum::<usize>();
+    let allocated_bytes = strings
+        .map(|x| (x.as_ptr(), x.len()))
+        .collect::<std::collections::HashSet<_>>()
+        .into_iter()
+        .map(|(_, len)| len)
+        .sum::<usize>();
+    let per_version = total_bytes / VERSIONS;
+    assert_eq!(allocated_bytes, per_version);
+
+    // the values are the same as without sharing
+    let first = manifests[0];
+    assert_eq!(first.name(), "com.anatawa12.package");
+    assert_eq!(first.description(), Some(description));
+    assert_eq!(
+        first.author().unwrap().email(),
+        Some("anatawa12@example.com")
+    );
+
+    // the cache is serialized as it is loaded
+    assert_eq!(
+        serde_json::to_value(&parsed).unwrap(),
+        serde_json::Value::Object(repository)
+    );
+}
+
+#[test]
+fn invalid_package_names() {
+    let repository = json!({
+        "name": "Test Repository",
+        "id": "com.anatawa12.test",
+        "url": "https://example.com/vpm.json",
+        "packages": {
+            "../../evil": {
+                "versions": {
+                    "1.0.0": { "name": "../../evil", "version": "1.0.0" },
+                },
+            },
+            "nul": {
+                "versions": {
+                    "1.0.0": { "name": "nul", "version": "1.0.0" },
+                },
+            },
+            "com.anatawa12.package": {
+                "versions": {
+                    "1.0.0": { "name": "com.anatawa12.package", "version": "1.0.0" },
+                    "1.1.0": { "name": "com.foo/..\\bar", "version": "1.1.0" },
+                },
+            },
+        },
+    });
+    let serde_json::Value::Object(repository) = repository else {
+        unreachable!()
+    };
+
+    let repository = RemoteRepository::parse(repository).unwrap();
+    assert!(repository.get_package("../../evil").is_none());
+    assert!(repository.get_package("nul").is_none());
+
+    let package = repository.get_package("com.anatawa12.package").unwrap();
+    assert_eq!(package.all_versions().count(), 1);
+    assert!(package.get_version(&Version::new(1, 1, 0)).is_none());
+}
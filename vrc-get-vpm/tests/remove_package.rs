@@ -1,5 +1,6 @@
 use crate::common::*;
 use futures::executor::block_on;
+use vrc_get_vpm::unity_project::RemovePackageErr;
 use vrc_get_vpm::unity_project::pending_project_changes::RemoveReason;
 use vrc_get_vpm::version::Version;
 
@@ -138,3 +139,29 @@ fn remove_referenced_legacy_package() {
         );
     })
 }
+
+#[test]
+fn remove_not_installed() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new().build().await.unwrap();
+
+        let err = project
+            .remove_request(&["com.anatawa12.gists"])
+            .await
+            .expect_err("should fail");
+
+        assert!(matches!(err, vrc_get_vpm::Error::PackageResolution(_)));
+        assert_eq!(err.exit_code(), 5);
+        match err.downcast_ref::<RemovePackageErr>().unwrap() {
+            RemovePackageErr::NotInstalled(names) => {
+                assert_eq!(names.len(), 1);
+                assert_eq!(names[0].as_ref(), "com.anatawa12.gists");
+            }
+            err => panic!("unexpected error: {:?}", err),
+        }
+        assert_eq!(
+            err.to_string(),
+            "the following packages are not installed: com.anatawa12.gists"
+        );
+    })
+}
@@ -0,0 +1,95 @@
+use common::*;
+use futures::executor::block_on;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use vrc_get_vpm::environment::{Settings, cleanup_repos_folder};
+use vrc_get_vpm::io::IoTrait;
+
+mod common;
+
+async fn build_environment() -> VirtualFileSystem {
+    let fs = VirtualFileSystem::new();
+    let files: &[(&str, &[u8])] = &[
+        (
+            "settings.json",
+            br#"{"userRepos":[{"localPath":"Repos/com.anatawa12.repo.json","url":"https://example.com/vpm.json"}]}"#,
+        ),
+        ("Repos/vrc-official.json", b"{}"),
+        ("Repos/vrc-curated.json", b"{}"),
+        ("Repos/package-cache.json", b"{}"),
+        ("Repos/com.anatawa12.repo.json", b"{}"),
+        ("Repos/com.anatawa12.removed.json", b"removed"),
+        ("Repos/3f2fd7e0-interrupted.json.tmp", b"tmp"),
+        ("Repos/in-use.json", b"in use"),
+        (
+            "Repos/com.anatawa12.a/vrc-get-com.anatawa12.a-1.0.0.zip",
+            b"zip",
+        ),
+    ];
+    for (path, content) in files {
+        fs.add_file(path.as_ref(), content).await.unwrap();
+    }
+
+    let old = SystemTime::now() - Duration::from_secs(7 * 24 * 60 * 60);
+    for path in [
+        "Repos/com.anatawa12.removed.json",
+        "Repos/3f2fd7e0-interrupted.json.tmp",
+    ] {
+        fs.set_modified(path.as_ref(), old).await.unwrap();
+    }
+    fs.set_modified("Repos/in-use.json".as_ref(), SystemTime::now())
+        .await
+        .unwrap();
+
+    fs
+}
+
+#[test]
+fn cleanup_orphaned_files() {
+    block_on(async {
+        let env = build_environment().await;
+        let settings = Settings::load(&env).await.unwrap();
+
+        let dry_run = cleanup_repos_folder(&settings, &env, true).await.unwrap();
+        assert_eq!(dry_run.removed().len(), 2);
+        assert!(
+            env.is_file("Repos/com.anatawa12.removed.json".as_ref())
+                .await
+        );
+
+        let result = cleanup_repos_folder(&settings, &env, false).await.unwrap();
+        let removed = result
+            .removed()
+            .iter()
+            .map(|x| (x.path.as_path(), x.size))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            removed,
+            [
+                (Path::new("Repos/com.anatawa12.removed.json"), 7),
+                (Path::new("Repos/3f2fd7e0-interrupted.json.tmp"), 3),
+            ]
+        );
+        assert_eq!(result.total_size(), 10);
+        assert_eq!(result.skipped(), [Path::new("Repos/in-use.json")]);
+
+        assert!(
+            !env.is_file("Repos/com.anatawa12.removed.json".as_ref())
+                .await
+        );
+        assert!(
+            !env.is_file("Repos/3f2fd7e0-interrupted.json.tmp".as_ref())
+                .await
+        );
+        for kept in [
+            "Repos/vrc-official.json",
+            "Repos/vrc-curated.json",
+            "Repos/package-cache.json",
+            "Repos/com.anatawa12.repo.json",
+            "Repos/in-use.json",
+            "Repos/com.anatawa12.a/vrc-get-com.anatawa12.a-1.0.0.zip",
+        ] {
+            assert!(env.is_file(kept.as_ref()).await, "{kept} is removed");
+        }
+    })
+}
@@ -0,0 +1,101 @@
+use serde_json::json;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use vrc_get_vpm::repository::RemoteRepository;
+
+/// Counts the bytes allocated by the current thread to measure the resident size of the values.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATED: Cell<isize> = const { Cell::new(0) };
+}
+
+fn count(bytes: isize) {
+    let _ = ALLOCATED.try_with(|allocated| allocated.set(allocated.get() + bytes));
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count(layout.size() as isize);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        count(-(layout.size() as isize));
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count(new_size as isize - layout.size() as isize);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Returns the value created by `f` and the bytes kept allocated by it.
+fn resident<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATED.get();
+    let value = f();
+    let after = ALLOCATED.get();
+    (value, (after - before) as usize)
+}
+
+fn large_repository() -> serde_json::Map<String, serde_json::Value> {
+    let packages = (0..50)
+        .map(|package| {
+            let name = format!("com.anatawa12.package{package}");
+            let versions = (0..40)
+                .map(|minor| {
+                    let version = format!("1.{minor}.0");
+                    let manifest = json!({
+                        "name": name,
+                        "version": version,
+                        "displayName": format!("Package {package}"),
+                        "description": "The description of the package, repeated for all versions.",
+                        "unity": "2022.3",
+                        "author": { "name": "anatawa12", "email": "anatawa12@example.com" },
+                        "url": format!("https://example.com/{name}-{version}.zip"),
+                        "zipSHA256": format!("{:064x}", package * 100 + minor),
+                        "vpmDependencies": { "com.vrchat.base": ">=3.7.0" },
+                        "legacyFolders": { "Assets\\Package": null },
+                    });
+                    (version, manifest)
+                })
+                .collect::<serde_json::Map<_, _>>();
+            (name, json!({ "versions": versions }))
+        })
+        .collect::<serde_json::Map<_, _>>();
+
+    let repository = json!({
+        "name": "Test Repository",
+        "id": "com.anatawa12.test",
+        "url": "https://example.com/vpm.json",
+        "packages": packages,
+    });
+    let serde_json::Value::Object(repository) = repository else {
+        unreachable!()
+    };
+    repository
+}
+
+#[test]
+fn resident_size() {
+    let repository = large_repository();
+    let text_size = serde_json::to_string(&repository).unwrap().len();
+
+    let (raw, raw_size) = resident(|| repository.clone());
+    let (parsed, parsed_size) = resident(|| RemoteRepository::parse(repository).unwrap());
+
+    // the repository used to keep the raw json map instead of the json text
+    let previous_size = parsed_size - text_size + raw_size;
+    println!("resident size: {previous_size} bytes before, {parsed_size} bytes after");
+    assert!(parsed_size < previous_size);
+
+    // the cache is still serialized as it is loaded
+    assert_eq!(
+        serde_json::to_value(&parsed).unwrap(),
+        serde_json::Value::Object(raw)
+    );
+}
@@ -0,0 +1,165 @@
+#![cfg(all(feature = "unity", unix))]
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use vrc_get_vpm::AbortCheck;
+use vrc_get_vpm::event::{Event, EventObserver};
+use vrc_get_vpm::unity::{UnityMigrationResult, run_unity_migration_with_path};
+
+struct FakeUnity(PathBuf);
+
+impl FakeUnity {
+    fn new(script: &str) -> Self {
+        let path =
+            std::env::temp_dir().join(format!("vrc-get-fake-unity-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&path, format!("#!/bin/sh\n{script}")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        Self(path)
+    }
+}
+
+impl Drop for FakeUnity {
+    fn drop(&mut self) {
+        std::fs::remove_file(&self.0).ok();
+    }
+}
+
+#[derive(Default)]
+struct LogObserver {
+    lines: Mutex<Vec<String>>,
+}
+
+impl EventObserver for LogObserver {
+    fn on_event(&self, event: &Event<'_>) {
+        if let Event::UnityMigrationLog { line } = *event {
+            self.lines.lock().unwrap().push(line.to_owned());
+        }
+    }
+}
+
+fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+}
+
+fn run(script: &str, timeout: Option<Duration>) -> (UnityMigrationResult, Vec<String>) {
+    let unity = FakeUnity::new(script);
+    let observer = LogObserver::default();
+    let result = runtime()
+        .block_on(run_unity_migration_with_path(
+            &unity.0,
+            Path::new("project"),
+            timeout,
+            &observer,
+            &AbortCheck::new(),
+        ))
+        .unwrap();
+    (result, observer.lines.into_inner().unwrap())
+}
+
+#[test]
+fn success() {
+    let (result, lines) = run(
+        "echo \"args: $*\"\necho 'Exiting batchmode successfully now!'\nexit 0\n",
+        None,
+    );
+    assert_eq!(result, UnityMigrationResult::Success);
+    assert_eq!(
+        lines,
+        [
+            "args: -batchmode -quit -projectPath project -logFile -",
+            "Exiting batchmode successfully now!",
+        ]
+    );
+}
+
+#[test]
+fn compile_error() {
+    let (result, _) = run(
+        "echo 'Assets/Script.cs(1,1): error CS1002: ; expected'\n\
+        echo 'Scripts have compiler errors.'\n\
+        exit 1\n",
+        None,
+    );
+    assert_eq!(result, UnityMigrationResult::CompileError);
+}
+
+#[test]
+fn failed() {
+    let (result, _) = run("exit 3\n", None);
+    assert_eq!(result, UnityMigrationResult::Failed { exit_code: 3 });
+}
+
+#[test]
+fn crashed() {
+    let (result, _) = run("kill -9 $$\n", None);
+    assert_eq!(result, UnityMigrationResult::Crashed { exit_code: None });
+}
+
+#[test]
+fn timed_out() {
+    let (result, _) = run("exec sleep 10\n", Some(Duration::from_millis(200)));
+    assert_eq!(result, UnityMigrationResult::TimedOut);
+}
+
+#[test]
+fn aborted() {
+    let unity = FakeUnity::new("echo started\nexec sleep 10\n");
+    let observer = LogObserver::default();
+    let abort = AbortCheck::new();
+    let started = std::time::Instant::now();
+
+    let result = runtime().block_on(async {
+        let run =
+            run_unity_migration_with_path(&unity.0, Path::new("project"), None, &observer, &abort);
+        let cancel = async {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            abort.abort();
+        };
+        futures::join!(run, cancel).0
+    });
+
+    assert!(matches!(result, Err(vrc_get_vpm::Error::Cancelled(_))));
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[cfg(feature = "experimental-unity-management")]
+#[test]
+fn registered_installation() {
+    use vrc_get_vpm::environment::VccDatabaseConnection;
+    use vrc_get_vpm::io::DefaultEnvironmentIo;
+    use vrc_get_vpm::unity::run_unity_migration;
+    use vrc_get_vpm::version::UnityVersion;
+
+    let unity = FakeUnity::new("echo 'Exiting batchmode successfully now!'\nexit 0\n");
+    let env = std::env::temp_dir().join(format!("vrc-get-env-{}", uuid::Uuid::new_v4()));
+    let io = DefaultEnvironmentIo::new(env.clone().into_boxed_path());
+    let observer = LogObserver::default();
+
+    let result = runtime().block_on(async {
+        let mut connection = VccDatabaseConnection::connect(&io).await.unwrap();
+        let version = UnityVersion::parse("2022.3.22f1").unwrap();
+        (connection.add_unity_installation(unity.0.to_str().unwrap(), version)).unwrap();
+        let installation = connection.get_unity_installations().pop().unwrap();
+
+        run_unity_migration(
+            &installation,
+            Path::new("project"),
+            None,
+            &observer,
+            &AbortCheck::new(),
+        )
+        .await
+    });
+    std::fs::remove_dir_all(&env).ok();
+
+    assert_eq!(result.unwrap(), UnityMigrationResult::Success);
+    assert_eq!(
+        observer.lines.into_inner().unwrap(),
+        ["Exiting batchmode successfully now!"]
+    );
+}
@@ -0,0 +1,127 @@
+use common::MockHttpClient;
+use futures::executor::block_on;
+use std::path::Path;
+use vrc_get_vpm::environment::{PackageCollection, Settings};
+use vrc_get_vpm::io::IoTrait;
+use vrc_get_vpm::io::memory::MemoryEnvironmentIo;
+
+mod common;
+
+const OFFICIAL_URL: &str = "https://packages.vrchat.com/official?download";
+const OFFICIAL_CACHE: &str = "Repos/vrc-official.json";
+
+#[test]
+fn save_without_changes() {
+    block_on(async {
+        let env = MemoryEnvironmentIo::new();
+
+        let mut settings = Settings::load(&env).await.unwrap();
+        assert!(!settings.is_changed());
+
+        settings.save(&env).await.unwrap();
+        assert_eq!(env.write_count(), 0);
+        assert!(!env.is_file("settings.json".as_ref()).await);
+    })
+}
+
+#[test]
+fn save_only_after_changes() {
+    block_on(async {
+        let env = MemoryEnvironmentIo::new();
+
+        let mut settings = Settings::load(&env).await.unwrap();
+        settings.set_show_prerelease_packages(true);
+        settings.add_local_repo(Path::new("/repos/local.json"), None);
+        assert!(settings.is_changed());
+        // changing the settings never writes
+        assert_eq!(env.write_count(), 0);
+
+        settings.save(&env).await.unwrap();
+        assert_eq!(env.write_count(), 1);
+        assert!(!settings.is_changed());
+
+        // nothing is changed since the last save
+        settings.save(&env).await.unwrap();
+        assert_eq!(env.write_count(), 1);
+
+        let mut settings = Settings::load(&env).await.unwrap();
+        assert!(settings.show_prerelease_packages());
+        assert_eq!(settings.get_user_repos().len(), 1);
+        settings.save(&env).await.unwrap();
+        assert_eq!(env.write_count(), 1);
+    })
+}
+
+#[test]
+fn save_vrc_get_settings() {
+    block_on(async {
+        let env = MemoryEnvironmentIo::new();
+
+        let mut settings = Settings::load(&env).await.unwrap();
+        settings.set_use_package_store(true);
+        settings.set_use_hard_links(true);
+        assert!(settings.is_changed());
+
+        settings.save(&env).await.unwrap();
+        assert_eq!(env.write_count(), 1);
+        assert!(env.is_file("vrc-get/settings.json".as_ref()).await);
+        assert!(!env.is_file("settings.json".as_ref()).await);
+
+        let settings = Settings::load(&env).await.unwrap();
+        assert!(settings.use_package_store());
+        assert!(settings.use_hard_links());
+    })
+}
+
+#[test]
+fn no_op_changes() {
+    block_on(async {
+        let env = MemoryEnvironmentIo::new();
+
+        let mut settings = Settings::load(&env).await.unwrap();
+        assert!(settings.remove_repo(|_| true).is_empty());
+        assert!(settings.remove_id_duplication().is_empty());
+        settings.remove_user_package(Path::new("/packages/not-added"));
+        assert!(!settings.is_changed());
+
+        settings.save(&env).await.unwrap();
+        assert_eq!(env.write_count(), 0);
+    })
+}
+
+#[test]
+fn repository_caches_saved_explicitly() {
+    block_on(async {
+        let env = MemoryEnvironmentIo::new();
+        let http = MockHttpClient::new().with_response(
+            OFFICIAL_URL,
+            200,
+            r#"{"id": "com.vrchat.repos.official", "packages": {}}"#,
+        );
+
+        let settings = Settings::load(&env).await.unwrap();
+        let mut collection = PackageCollection::load(&settings, &env, Some(&http))
+            .await
+            .unwrap();
+        assert_eq!(collection.get_remote().count(), 1);
+        // loading downloads the repository but never writes the cache
+        assert!(collection.is_changed());
+        assert!(!env.is_file(OFFICIAL_CACHE.as_ref()).await);
+
+        let before = env.write_count();
+        collection.save(&env).await.unwrap();
+        assert!(!collection.is_changed());
+        assert!(env.is_file(OFFICIAL_CACHE.as_ref()).await);
+        assert_eq!(env.write_count(), before + 1);
+
+        // nothing is downloaded since the last save
+        collection.save(&env).await.unwrap();
+        assert_eq!(env.write_count(), before + 1);
+
+        let collection = PackageCollection::load(&settings, &env, None::<&MockHttpClient>)
+            .await
+            .unwrap();
+        assert!(!collection.is_changed());
+        assert_eq!(collection.get_remote().count(), 1);
+    })
+}
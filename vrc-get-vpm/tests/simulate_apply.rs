@@ -0,0 +1,293 @@
+use async_zip::base::write::ZipFileWriter;
+use async_zip::{Compression, ZipEntryBuilder};
+use common::*;
+use futures::executor::block_on;
+use futures::prelude::*;
+use indexmap::IndexMap;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::convert::Infallible;
+use std::path::{Path, PathBuf};
+use url::Url;
+use vrc_get_vpm::environment::PackageInstaller;
+use vrc_get_vpm::io::{DirEntry, FileOperation, IoTrait, RecordingIo};
+use vrc_get_vpm::repository::{LocalCachedRepository, RemoteRepository};
+use vrc_get_vpm::unity_project::AddPackageOperation;
+use vrc_get_vpm::version::Version;
+use vrc_get_vpm::{PackageInfo, PackageManifest};
+
+mod common;
+
+const PACKAGE_NAME: &str = "com.anatawa12.package";
+const PACKAGE_JSON: &str = r#"{"name":"com.anatawa12.package","version":"1.0.0"}"#;
+
+fn package_dir() -> PathBuf {
+    PathBuf::from(format!("Packages/{PACKAGE_NAME}"))
+}
+
+fn position(operations: &[FileOperation], f: impl Fn(&FileOperation) -> bool) -> usize {
+    (operations.iter().position(f))
+        .unwrap_or_else(|| panic!("operation not found in {operations:#?}"))
+}
+
+fn write_file(file: impl AsRef<Path>) -> impl Fn(&FileOperation) -> bool {
+    move |x| matches!(x, FileOperation::WriteFile { path, .. } if path == file.as_ref())
+}
+
+fn remove_dir_all(dir: impl AsRef<Path>) -> impl Fn(&FileOperation) -> bool {
+    move |x| matches!(x, FileOperation::RemoveDir { path, recursive: true } if path == dir.as_ref())
+}
+
+#[test]
+fn recording_io() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        fs.add_file("dir/file.txt".as_ref(), b"content")
+            .await
+            .unwrap();
+
+        let io = RecordingIo::new(&fs);
+        io.create_dir_all("new/dir".as_ref()).await.unwrap();
+        io.rename("dir".as_ref(), "moved".as_ref()).await.unwrap();
+        io.write("moved/written.txt".as_ref(), b"written")
+            .await
+            .unwrap();
+
+        // the operations are visible through the recording io
+        assert!(io.is_dir("new/dir".as_ref()).await);
+        assert!(!io.is_dir("dir".as_ref()).await);
+        assert!(io.is_file("moved/file.txt".as_ref()).await);
+        let mut written = String::new();
+        (io.open("moved/written.txt".as_ref()).await.unwrap())
+            .read_to_string(&mut written)
+            .await
+            .unwrap();
+        assert_eq!(written, "written");
+
+        let mut names = io
+            .read_dir("moved".as_ref())
+            .await
+            .unwrap()
+            .map_ok(|x| x.file_name())
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        names.sort();
+        assert_eq!(names, ["file.txt", "written.txt"]);
+
+        assert!(io.remove_file("dir/file.txt".as_ref()).await.is_err());
+        assert!(io.remove_dir("moved".as_ref()).await.is_err());
+
+        assert_eq!(
+            io.operations(),
+            [
+                FileOperation::CreateDir {
+                    path: "new/dir".into()
+                },
+                FileOperation::Rename {
+                    from: "dir".into(),
+                    to: "moved".into()
+                },
+                FileOperation::WriteFile {
+                    path: "moved/written.txt".into(),
+                    size: Some(7)
+                },
+            ]
+        );
+
+        // nothing is changed actually
+        assert_eq!(fs.write_count(), 0);
+        assert!(fs.is_file("dir/file.txt".as_ref()).await);
+        assert!(!fs.is_dir("new".as_ref()).await);
+    })
+}
+
+#[test]
+fn simulate_remove() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency(PACKAGE_NAME, Version::new(1, 0, 0))
+            .add_locked(PACKAGE_NAME, Version::new(1, 0, 0), &[])
+            .add_package_json(PACKAGE_NAME, PACKAGE_JSON)
+            .build()
+            .await
+            .unwrap();
+        let env = VirtualEnvironment::new(VirtualFileSystem::new());
+        let write_count = project.io().write_count();
+
+        let changes = project.remove_request(&[PACKAGE_NAME]).await.unwrap();
+        let operations = project
+            .simulate_pending_changes(&env, changes)
+            .await
+            .unwrap();
+
+        let package_json = package_dir().join("package.json");
+        let moved = position(
+            &operations,
+            |x| matches!(x, FileOperation::Rename { from, .. } if from == &package_json),
+        );
+        let removed = position(&operations, remove_dir_all(package_dir()));
+        let manifest = position(&operations, write_file("Packages/vpm-manifest.json"));
+        let temp = position(&operations, remove_dir_all("Temp/vrc-get"));
+        assert!(moved < removed);
+        assert!(removed < manifest);
+        assert!(manifest < temp);
+
+        // the project is not changed
+        assert_eq!(project.io().write_count(), write_count);
+        assert!(project.io().is_file(&package_json).await);
+        assert!(!project.io().is_dir("Temp".as_ref()).await);
+        assert!(project.is_locked(PACKAGE_NAME));
+    })
+}
+
+async fn create_zip(files: &[(&str, &str)]) -> Vec<u8> {
+    let mut writer = ZipFileWriter::new(Vec::new());
+    for (name, content) in files {
+        let entry = ZipEntryBuilder::new(name.to_string().into(), Compression::Stored);
+        writer
+            .write_entry_whole(entry, content.as_bytes())
+            .await
+            .unwrap();
+    }
+    writer.close().await.unwrap()
+}
+
+fn remote_repository() -> LocalCachedRepository {
+    let json = json!({
+        "packages": {
+            PACKAGE_NAME: {
+                "versions": {
+                    "1.0.0": {
+                        "name": PACKAGE_NAME,
+                        "version": "1.0.0",
+                        "url": "https://example.com/package.zip",
+                    }
+                }
+            }
+        }
+    });
+    let serde_json::Value::Object(json) = json else {
+        unreachable!()
+    };
+    LocalCachedRepository::new(RemoteRepository::parse(json).unwrap(), IndexMap::new())
+}
+
+async fn simulate_install(env: &VirtualFileSystem) -> Vec<FileOperation> {
+    let repository = remote_repository();
+    let manifest = repository
+        .repo()
+        .get_package_version(PACKAGE_NAME, &Version::new(1, 0, 0))
+        .unwrap();
+
+    let project = VirtualProjectBuilder::new().build().await.unwrap();
+    let collection = PackageCollectionBuilder::new().build();
+    let installer = PackageInstaller::new(env, None::<&Infallible>);
+
+    let changes = project
+        .add_package_request(
+            &collection,
+            &[PackageInfo::remote(manifest, &repository)],
+            AddPackageOperation::InstallToDependencies,
+            false,
+        )
+        .await
+        .unwrap();
+    let write_count = project.io().write_count();
+
+    let operations = project
+        .simulate_pending_changes(&installer, changes)
+        .await
+        .unwrap();
+
+    assert_eq!(project.io().write_count(), write_count);
+    assert!(!project.io().is_dir(&package_dir()).await);
+
+    operations
+}
+
+#[test]
+fn simulate_install_cached() {
+    block_on(async {
+        let env = VirtualFileSystem::new();
+        let zip = create_zip(&[
+            ("package.json", PACKAGE_JSON),
+            ("Runtime/Script.cs", "class Script {}"),
+        ])
+        .await;
+        let zip_name = format!("vrc-get-{PACKAGE_NAME}-1.0.0.zip");
+        let zip_path = Path::new("Repos").join(PACKAGE_NAME).join(&zip_name);
+        env.add_file(&zip_path, &zip).await.unwrap();
+        env.add_file(
+            &zip_path.with_extension("zip.sha256"),
+            format!("{} {zip_name}\n", hex::encode(Sha256::digest(&zip))).as_bytes(),
+        )
+        .await
+        .unwrap();
+
+        let operations = simulate_install(&env).await;
+
+        let manifest = position(&operations, write_file("Packages/vpm-manifest.json"));
+        let extracted = position(
+            &operations,
+            write_file(package_dir().join("Runtime/Script.cs")),
+        );
+        assert!(manifest < extracted);
+        assert!(
+            !(operations.iter()).any(|x| matches!(x, FileOperation::Download { .. })),
+            "{operations:#?}"
+        );
+    })
+}
+
+#[test]
+fn simulate_install_not_cached() {
+    block_on(async {
+        let env = VirtualFileSystem::new();
+
+        let operations = simulate_install(&env).await;
+
+        let manifest = position(&operations, write_file("Packages/vpm-manifest.json"));
+        let download = position(&operations, |x| {
+            *x == FileOperation::Download {
+                name: PACKAGE_NAME.into(),
+                version: Version::new(1, 0, 0),
+                url: Url::parse("https://example.com/package.zip").unwrap(),
+            }
+        });
+        assert!(manifest < download);
+
+        // nothing is downloaded to the cache
+        assert!(!env.is_dir("Repos".as_ref()).await);
+    })
+}
+
+#[test]
+fn simulate_unsupported_installer() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new().build().await.unwrap();
+        let collection = PackageCollectionBuilder::new()
+            .add(PackageManifest::new(PACKAGE_NAME, Version::new(1, 0, 0)))
+            .build();
+        let installer = VirtualEnvironment::new(VirtualFileSystem::new());
+
+        let changes = project
+            .add_package_request(
+                &collection,
+                &[collection.get_package(PACKAGE_NAME, Version::new(1, 0, 0))],
+                AddPackageOperation::InstallToDependencies,
+                false,
+            )
+            .await
+            .unwrap();
+        let write_count = project.io().write_count();
+
+        // the installer without simulation support must not be used to install the package
+        let error = project
+            .simulate_pending_changes(&installer, changes)
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::Unsupported);
+        assert_eq!(project.io().write_count(), write_count);
+    })
+}
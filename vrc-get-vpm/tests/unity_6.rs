@@ -0,0 +1,210 @@
+//! Unity 6 reports the versions like `6000.0.23f1`, which are not year releases.
+
+use common::*;
+use futures::executor::block_on;
+use vrc_get_vpm::unity_project::{MigrateUnity2022Error, MigrateUnityVersionError};
+use vrc_get_vpm::version::{UnityVersion, Version};
+
+mod common;
+
+const PROJECT_VERSION_2022: &str = "m_EditorVersion: 2022.3.22f1\n\
+    m_EditorVersionWithRevision: 2022.3.22f1 (887be4894c44)\n";
+const PROJECT_VERSION_6000: &str = "m_EditorVersion: 6000.0.23f1\n\
+    m_EditorVersionWithRevision: 6000.0.23f1 (1c4764c07fb4)\n";
+
+/// The SDK for Unity 2022 and the newer SDK for Unity 6.
+fn sdk_collection() -> PackageCollection {
+    PackageCollectionBuilder::new()
+        .add_sdk(Version::new(3, 7, 0), |x| x.with_unity(2022, 3))
+        .add_sdk(Version::new(3, 8, 0), |x| x.with_unity(6000, 0))
+        .build()
+}
+
+#[test]
+fn load_project() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("ProjectSettings/ProjectVersion.txt", PROJECT_VERSION_6000)
+            .build()
+            .await
+            .unwrap();
+
+        assert!(project.is_valid().await);
+        assert_eq!(
+            project.unity_version(),
+            Some(UnityVersion::new_f1(6000, 0, 23))
+        );
+        assert_eq!(project.unity_revision(), Some("1c4764c07fb4"));
+    })
+}
+
+#[test]
+fn upgrade_from_2022() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.vrchat.avatars", Version::new(3, 7, 0))
+            .add_locked(
+                "com.vrchat.avatars",
+                Version::new(3, 7, 0),
+                &[("com.vrchat.base", "3.7.0")],
+            )
+            .add_locked("com.vrchat.base", Version::new(3, 7, 0), &[])
+            .add_file("ProjectSettings/ProjectVersion.txt", PROJECT_VERSION_2022)
+            .build()
+            .await
+            .unwrap();
+        let collection = sdk_collection();
+
+        let target = UnityVersion::new_f1(6000, 0, 23);
+        let result = project
+            .migrate_unity_version_request(&collection, target)
+            .await
+            .unwrap();
+
+        let avatars = collection.get_package("com.vrchat.avatars", Version::new(3, 8, 0));
+        let base = collection.get_package("com.vrchat.base", Version::new(3, 8, 0));
+        assert_eq!(result.package_changes().len(), 2);
+        assert_installing_to_both(&result, &avatars);
+        assert_installing_to_both(&result, &base);
+        assert_eq!(result.unity_version(), Some(target));
+    })
+}
+
+#[test]
+fn apply_upgrade_from_2022() {
+    block_on(async {
+        let mut project = VirtualProjectBuilder::new()
+            .add_file("ProjectSettings/ProjectVersion.txt", PROJECT_VERSION_2022)
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new().build();
+        let target = UnityVersion::new_f1(6000, 0, 23);
+        let request = project
+            .migrate_unity_version_request(&collection, target)
+            .await
+            .unwrap();
+
+        let env = VirtualEnvironment::new(VirtualFileSystem::new());
+        project.apply_pending_changes(&env, request).await.unwrap();
+
+        assert_eq!(project.unity_version(), Some(target));
+        // the revision of the target Unity is not known, and keeping the 2022 revision would
+        // contradict m_EditorVersion, so the line is dropped until Unity opens the project
+        assert_eq!(
+            read_file(project.io(), "ProjectSettings/ProjectVersion.txt").await,
+            "m_EditorVersion: 6000.0.23f1\n"
+        );
+        assert_eq!(project.unity_revision(), None);
+    })
+}
+
+#[test]
+fn downgrade_to_2022_is_error() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("ProjectSettings/ProjectVersion.txt", PROJECT_VERSION_6000)
+            .build()
+            .await
+            .unwrap();
+
+        let err = project
+            .migrate_unity_version_request(&sdk_collection(), UnityVersion::new_f1(2022, 3, 22))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(MigrateUnityVersionError::DowngradingUnity { current, .. })
+                if *current == UnityVersion::new_f1(6000, 0, 23)
+        ));
+    })
+}
+
+#[test]
+fn migrate_unity_2022_refuses_unity_6() {
+    block_on(async {
+        let mut project = VirtualProjectBuilder::new()
+            .add_file("ProjectSettings/ProjectVersion.txt", PROJECT_VERSION_6000)
+            .build()
+            .await
+            .unwrap();
+
+        let env = VirtualEnvironment::new(VirtualFileSystem::new());
+        let err = project
+            .migrate_unity_2022(&sdk_collection(), &env)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(MigrateUnity2022Error::UnityVersionMismatch)
+        ));
+    })
+}
+
+#[cfg(feature = "experimental-unity-management")]
+#[test]
+fn find_unity_6_installation() {
+    use vrc_get_vpm::environment::VccDatabaseConnection;
+    use vrc_get_vpm::io::memory::MemoryEnvironmentIo;
+
+    fn editor_path(version: &str) -> String {
+        if cfg!(windows) {
+            format!("C:\\Program Files\\Unity\\Hub\\Editor\\{version}\\Editor\\Unity.exe")
+        } else {
+            format!("/opt/unity/{version}/Editor/Unity")
+        }
+    }
+
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("ProjectSettings/ProjectVersion.txt", PROJECT_VERSION_6000)
+            .build()
+            .await
+            .unwrap();
+        let expected = project.unity_version().unwrap();
+
+        let io = MemoryEnvironmentIo::new();
+        let mut connection = VccDatabaseConnection::connect(&io).await.unwrap();
+        let find = |connection: &VccDatabaseConnection, version: UnityVersion| {
+            (connection.find_most_suitable_unity(version))
+                .and_then(|x| x.version())
+                .map(|x| x.to_string())
+        };
+
+        for version in ["2022.3.22f1", "6000.1.2f1", "6000.2.0b1"] {
+            let parsed = UnityVersion::parse(version).unwrap();
+            (connection.add_unity_installation(&editor_path(version), parsed)).unwrap();
+        }
+
+        // the closest minor in the same major
+        assert_eq!(find(&connection, expected).as_deref(), Some("6000.1.2f1"));
+
+        for version in ["6000.0.19f1", "6000.0.30f1", "6000.0.35b1"] {
+            let parsed = UnityVersion::parse(version).unwrap();
+            (connection.add_unity_installation(&editor_path(version), parsed)).unwrap();
+        }
+
+        // the newest release in the same minor
+        assert_eq!(find(&connection, expected).as_deref(), Some("6000.0.30f1"));
+
+        let exact = UnityVersion::parse("6000.0.23f1").unwrap();
+        (connection.add_unity_installation(&editor_path("6000.0.23f1"), exact)).unwrap();
+        let found = connection.find_most_suitable_unity(expected).unwrap();
+        assert_eq!(found.version(), Some(expected));
+        assert_eq!(found.path(), Some(editor_path("6000.0.23f1").as_str()));
+
+        // Unity 6 is not used for 2022 projects, nor 2022 for Unity 6 projects
+        let unity_2022 = UnityVersion::new_f1(2022, 3, 6);
+        assert_eq!(
+            find(&connection, unity_2022).as_deref(),
+            Some("2022.3.22f1")
+        );
+        let unity_2021 = UnityVersion::new_f1(2021, 3, 0);
+        assert_eq!(find(&connection, unity_2021), None);
+        let unity_7 = UnityVersion::new_f1(7000, 0, 0);
+        assert_eq!(find(&connection, unity_7), None);
+    })
+}
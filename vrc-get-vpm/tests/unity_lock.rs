@@ -0,0 +1,130 @@
+use common::*;
+use futures::executor::block_on;
+use vrc_get_vpm::io::ProjectIo;
+use vrc_get_vpm::unity_project::{ProjectOpenInUnityError, UnityLockState};
+use vrc_get_vpm::version::UnityVersion;
+
+mod common;
+
+#[test]
+fn not_open() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("Temp/vrc-get/temp.txt", "")
+            .build()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            project.unity_lock_state().await.unwrap(),
+            UnityLockState::NotOpen
+        );
+        assert!(!project.is_open_in_unity().await.unwrap());
+        project.check_not_open_in_unity().await.unwrap();
+    })
+}
+
+#[test]
+fn lockfile_exists() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("Temp/UnityLockfile", "")
+            .build()
+            .await
+            .unwrap();
+
+        // the virtual file system cannot check the lock
+        assert_eq!(
+            project
+                .io()
+                .is_exclusively_locked("Temp/UnityLockfile".as_ref())
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            project.unity_lock_state().await.unwrap(),
+            UnityLockState::PossiblyStale
+        );
+        assert!(project.is_open_in_unity().await.unwrap());
+    })
+}
+
+#[test]
+fn apply_refused_while_open() {
+    block_on(async {
+        let mut project = VirtualProjectBuilder::new()
+            .add_file(
+                "ProjectSettings/ProjectVersion.txt",
+                "m_EditorVersion: 2022.3.6f1\n",
+            )
+            .add_file("Temp/UnityLockfile", "")
+            .build()
+            .await
+            .unwrap();
+        project.io().lock_exclusively("Temp/UnityLockfile".as_ref());
+        assert_eq!(
+            project.unity_lock_state().await.unwrap(),
+            UnityLockState::Open
+        );
+
+        let collection = PackageCollectionBuilder::new().build();
+        let env = VirtualEnvironment::new(VirtualFileSystem::new());
+        let target = UnityVersion::new_f1(2022, 3, 22);
+
+        let request = project
+            .migrate_unity_version_request(&collection, target)
+            .await
+            .unwrap();
+        let error = project
+            .apply_pending_changes(&env, request)
+            .await
+            .unwrap_err();
+        let error = error.downcast_ref::<std::io::Error>().unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::ResourceBusy);
+        let inner = error
+            .get_ref()
+            .and_then(|x| x.downcast_ref::<ProjectOpenInUnityError>())
+            .unwrap();
+        assert!(!inner.is_possibly_stale());
+        assert_eq!(
+            project.unity_version(),
+            Some(UnityVersion::new_f1(2022, 3, 6))
+        );
+
+        project.set_ignore_unity_lock(true);
+        let request = project
+            .migrate_unity_version_request(&collection, target)
+            .await
+            .unwrap();
+        project.apply_pending_changes(&env, request).await.unwrap();
+        assert_eq!(project.unity_version(), Some(target));
+    })
+}
+
+#[test]
+fn apply_with_stale_lockfile() {
+    block_on(async {
+        let mut project = VirtualProjectBuilder::new()
+            .add_file(
+                "ProjectSettings/ProjectVersion.txt",
+                "m_EditorVersion: 2022.3.6f1\n",
+            )
+            .add_file("Temp/UnityLockfile", "")
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new().build();
+        let env = VirtualEnvironment::new(VirtualFileSystem::new());
+        let target = UnityVersion::new_f1(2022, 3, 22);
+
+        // the lockfile not confirmed to be locked is only warned
+        let request = project
+            .migrate_unity_version_request(&collection, target)
+            .await
+            .unwrap();
+        project.apply_pending_changes(&env, request).await.unwrap();
+        assert_eq!(project.unity_version(), Some(target));
+    })
+}
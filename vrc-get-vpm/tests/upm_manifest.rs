@@ -0,0 +1,188 @@
+use common::*;
+use futures::executor::block_on;
+use futures::prelude::*;
+use serde_json::json;
+use vrc_get_vpm::io::IoTrait;
+use vrc_get_vpm::unity_project::AddPackageOperation;
+use vrc_get_vpm::unity_project::pending_project_changes::UpmChange;
+use vrc_get_vpm::version::Version;
+use vrc_get_vpm::{PackageManifest, ScopedRegistry};
+
+mod common;
+
+#[test]
+fn installing_package_requires_upm_changes() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new().build().await.unwrap();
+
+        let registry = ScopedRegistry::new(
+            "package.openupm.com",
+            "https://package.openupm.com",
+            ["com.cysharp.unitask"],
+        );
+        let collection = PackageCollectionBuilder::new()
+            .add(
+                PackageManifest::new("com.anatawa12.package", Version::new(1, 0, 0))
+                    .add_scoped_registry(registry.clone())
+                    .add_upm_dependency("com.cysharp.unitask", Version::new(2, 5, 0)),
+            )
+            .build();
+
+        let package = collection.get_package("com.anatawa12.package", Version::new(1, 0, 0));
+
+        let result = project
+            .add_package_request(
+                &collection,
+                &[package],
+                AddPackageOperation::InstallToDependencies,
+                false,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.upm_changes().len(), 2);
+        assert!(matches!(
+            &result.upm_changes()[0],
+            UpmChange::EnsureScopedRegistry { registry: added } if added == &registry
+        ));
+        assert!(matches!(
+            &result.upm_changes()[1],
+            UpmChange::EnsureDependency { name, version }
+                if name.as_ref() == "com.cysharp.unitask" && version == &Version::new(2, 5, 0)
+        ));
+    })
+}
+
+#[test]
+fn apply_upm_changes() {
+    block_on(async {
+        let mut project = VirtualProjectBuilder::new()
+            .add_dependency("com.anatawa12.gists", Version::new(1, 0, 0))
+            .add_locked("com.anatawa12.gists", Version::new(1, 0, 0), &[])
+            .add_file(
+                "Packages/manifest.json",
+                r#"{
+                    "dependencies": {
+                        "com.unity.textmeshpro": "3.0.6",
+                        "com.unity.xr.oculus.standalone": "2.0.0",
+                        "com.anatawa12.git": "https://example.com/package.git"
+                    },
+                    "scopedRegistries": [
+                        {
+                            "name": "package.openupm.com",
+                            "url": "https://package.openupm.com/",
+                            "scopes": ["com.cysharp.unitask"]
+                        }
+                    ],
+                    "testables": ["com.anatawa12.gists"]
+                }"#,
+            )
+            .build()
+            .await
+            .unwrap();
+
+        let env = VirtualEnvironment::new(VirtualFileSystem::new());
+
+        let mut changes = project
+            .remove_request(&["com.anatawa12.gists"])
+            .await
+            .unwrap();
+        changes
+            .ensure_upm_dependency("com.unity.textmeshpro".into(), Version::new(3, 0, 0))
+            .ensure_upm_dependency("com.unity.timeline".into(), Version::new(1, 2, 3))
+            .ensure_upm_dependency("com.anatawa12.git".into(), Version::new(1, 0, 0))
+            .remove_upm_dependency("com.unity.xr.oculus.standalone".into())
+            .ensure_scoped_registry(ScopedRegistry::new(
+                "OpenUPM",
+                "https://package.openupm.com",
+                ["com.cysharp.unitask", "com.neuecc.unirx"],
+            ))
+            .ensure_scoped_registry(ScopedRegistry::new(
+                "Example",
+                "https://example.com",
+                ["com.example"],
+            ));
+
+        project.apply_pending_changes(&env, changes).await.unwrap();
+
+        let mut manifest = String::new();
+        project
+            .io()
+            .open("Packages/manifest.json".as_ref())
+            .await
+            .unwrap()
+            .read_to_string(&mut manifest)
+            .await
+            .unwrap();
+        let manifest = serde_json::from_str::<serde_json::Value>(&manifest).unwrap();
+
+        assert_eq!(
+            manifest,
+            json!({
+                "dependencies": {
+                    "com.unity.textmeshpro": "3.0.6",
+                    "com.anatawa12.git": "https://example.com/package.git",
+                    "com.unity.timeline": "1.2.3"
+                },
+                "scopedRegistries": [
+                    {
+                        "name": "package.openupm.com",
+                        "url": "https://package.openupm.com/",
+                        "scopes": ["com.cysharp.unitask", "com.neuecc.unirx"]
+                    },
+                    {
+                        "name": "Example",
+                        "url": "https://example.com",
+                        "scopes": ["com.example"]
+                    }
+                ],
+                "testables": ["com.anatawa12.gists"]
+            })
+        );
+        // the order of keys are kept
+        assert_eq!(
+            manifest.as_object().unwrap().keys().collect::<Vec<_>>(),
+            ["dependencies", "scopedRegistries", "testables"]
+        );
+    })
+}
+
+#[test]
+fn malformed_scoped_registries() {
+    block_on(async {
+        let manifest =
+            r#"{"dependencies": {}, "scopedRegistries": {"url": "https://example.com"}}"#;
+        let mut project = VirtualProjectBuilder::new()
+            .add_file("Packages/manifest.json", manifest)
+            .build()
+            .await
+            .unwrap();
+
+        let env = VirtualEnvironment::new(VirtualFileSystem::new());
+
+        let mut changes = project.remove_request(&[]).await.unwrap();
+        changes.ensure_scoped_registry(ScopedRegistry::new(
+            "Example",
+            "https://example.com",
+            ["com.example"],
+        ));
+
+        let error = project
+            .apply_pending_changes(&env, changes)
+            .await
+            .unwrap_err();
+        let error = error.downcast_ref::<std::io::Error>().unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+        let mut saved = String::new();
+        project
+            .io()
+            .open("Packages/manifest.json".as_ref())
+            .await
+            .unwrap()
+            .read_to_string(&mut saved)
+            .await
+            .unwrap();
+        assert_eq!(saved, manifest);
+    })
+}
@@ -0,0 +1,185 @@
+//! The smoke test of the VCC database on the real file system,
+//! which runs on every target in the CI including ARM64 Windows.
+#![cfg(feature = "experimental-project-management")]
+
+use common::*;
+use futures::io::Cursor;
+use std::convert::Infallible;
+use std::fs;
+use std::path::{Path, PathBuf};
+use vrc_get_vpm::environment::{
+    CreateProjectOptions, PackageInstaller, ProjectTemplate, RestoreOptions, Settings,
+    VccDatabaseConnection,
+};
+use vrc_get_vpm::io::{DefaultEnvironmentIo, DefaultProjectIo};
+use vrc_get_vpm::unity_project::BackupOptions;
+use vrc_get_vpm::version::UnityVersion;
+use vrc_get_vpm::{AbortCheck, UnityProject};
+
+mod common;
+
+fn temp_dir() -> PathBuf {
+    let root = std::env::temp_dir().join(format!("vrc-get-database-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&root).unwrap();
+    root
+}
+
+fn create_project(root: &Path) -> PathBuf {
+    let project = root.join("project");
+    fs::create_dir_all(project.join("Packages")).unwrap();
+    fs::create_dir_all(project.join("ProjectSettings")).unwrap();
+    fs::write(
+        project.join("Packages/vpm-manifest.json"),
+        r#"{"dependencies":{},"locked":{}}"#,
+    )
+    .unwrap();
+    fs::write(
+        project.join("ProjectSettings/ProjectVersion.txt"),
+        "m_EditorVersion: 2022.3.22f1\n\
+        m_EditorVersionWithRevision: 2022.3.22f1 (887be4894c44)\n",
+    )
+    .unwrap();
+    project
+}
+
+#[test]
+fn open_write_read() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let root = temp_dir();
+        let io = DefaultEnvironmentIo::new(root.join("env").into_boxed_path());
+        let project_path = create_project(&root);
+        let project_path = project_path.to_str().unwrap();
+
+        let project = UnityProject::load(DefaultProjectIo::new(Path::new(project_path).into()))
+            .await
+            .unwrap();
+        let mut connection = VccDatabaseConnection::connect(&io).await.unwrap();
+        assert!(connection.get_projects().is_empty());
+        connection.add_project(&project).await.unwrap();
+        connection.save(&io).await.unwrap();
+        drop(connection);
+
+        let connection = VccDatabaseConnection::connect(&io).await.unwrap();
+        assert_eq!(connection.get_projects().len(), 1);
+        let found = connection.find_project(project_path).unwrap().unwrap();
+        assert_eq!(
+            found.unity_version(),
+            Some(UnityVersion::new_f1(2022, 3, 22))
+        );
+        assert_eq!(found.unity_revision(), Some("887be4894c44"));
+        drop(connection);
+
+        fs::remove_dir_all(root).ok();
+    })
+}
+
+#[test]
+fn restore_and_register() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let root = temp_dir();
+        let io = DefaultEnvironmentIo::new(root.join("env").into_boxed_path());
+        let project_path = create_project(&root);
+
+        let project = UnityProject::load(DefaultProjectIo::new(project_path.into()))
+            .await
+            .unwrap();
+        let mut archive = Cursor::new(Vec::new());
+        project
+            .create_backup_archive(
+                &mut archive,
+                &BackupOptions::new(),
+                &AbortCheck::new(),
+                |_| {},
+            )
+            .await
+            .unwrap();
+        fs::write(root.join("backup.zip"), archive.into_inner()).unwrap();
+
+        let mut connection = VccDatabaseConnection::connect(&io).await.unwrap();
+        let archive = root.join("backup.zip");
+        let restore = async |connection: &mut VccDatabaseConnection, name: &str, register: bool| {
+            connection
+                .restore_project_backup(
+                    &io,
+                    &archive,
+                    &root.join(name),
+                    &RestoreOptions::new().register(register),
+                    &AbortCheck::new(),
+                    |_| {},
+                )
+                .await
+                .unwrap()
+        };
+
+        restore(&mut connection, "not-registered", false).await;
+        assert!(connection.get_projects().is_empty());
+
+        let restored = restore(&mut connection, "registered", true).await;
+        assert!(
+            restored
+                .join("ProjectSettings/ProjectVersion.txt")
+                .is_file()
+        );
+        let found = connection
+            .find_project(restored.to_str().unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            found.unity_version(),
+            Some(UnityVersion::new_f1(2022, 3, 22))
+        );
+        drop(connection);
+
+        fs::remove_dir_all(root).ok();
+    })
+}
+
+#[test]
+fn create_project_writes_gitignore() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let root = temp_dir();
+        let io = DefaultEnvironmentIo::new(root.join("env").into_boxed_path());
+        let settings = Settings::load(&io).await.unwrap();
+        let collection = PackageCollectionBuilder::new().build();
+        let installer = PackageInstaller::new(&io, None::<&Infallible>);
+
+        let mut connection = VccDatabaseConnection::connect(&io).await.unwrap();
+        let project = connection
+            .create_project(
+                &settings,
+                &collection,
+                &installer,
+                &ProjectTemplate::Blank,
+                "Blank Project",
+                &CreateProjectOptions::new().location(&root.join("projects")),
+            )
+            .await
+            .unwrap();
+
+        let project_path = root.join("projects/Blank Project");
+        assert_eq!(project.project_dir(), project_path);
+        let gitignore = fs::read_to_string(project_path.join(".gitignore")).unwrap();
+        assert!(gitignore.lines().any(|line| line == "/[Ll]ibrary/"));
+        assert!(
+            connection
+                .find_project(project_path.to_str().unwrap())
+                .unwrap()
+                .is_some()
+        );
+        drop(connection);
+
+        fs::remove_dir_all(root).ok();
+    })
+}
@@ -0,0 +1,187 @@
+use common::*;
+use futures::executor::block_on;
+use vrc_get_vpm::PackageManifest;
+use vrc_get_vpm::version::Version;
+
+mod common;
+
+const RESOLVER: &str = "com.vrchat.core.vpm-resolver";
+const SETTINGS_PATH: &str = "Packages/vrc-get-settings.json";
+const PROJECT_VERSION_2022: &str = "m_EditorVersion: 2022.3.22f1\n\
+    m_EditorVersionWithRevision: 2022.3.22f1 (887be4894c44)\n";
+
+/// The SDK and the resolver.
+fn sdk_collection() -> PackageCollection {
+    PackageCollectionBuilder::new()
+        .add_sdk(Version::new(3, 7, 0), |x| x)
+        // 0.1.26 or older are only for Unity 2019
+        .add(PackageManifest::new(RESOLVER, Version::new(0, 1, 26)))
+        .add(PackageManifest::new(RESOLVER, Version::new(0, 1, 29)).with_unity(2022, 3))
+        .build()
+}
+
+fn vpm_project() -> VirtualProjectBuilder {
+    let mut builder = VirtualProjectBuilder::new();
+    builder
+        .add_dependency("com.vrchat.avatars", Version::new(3, 7, 0))
+        .add_locked(
+            "com.vrchat.avatars",
+            Version::new(3, 7, 0),
+            &[("com.vrchat.base", "3.7.0")],
+        )
+        .add_locked("com.vrchat.base", Version::new(3, 7, 0), &[])
+        .add_file("ProjectSettings/ProjectVersion.txt", PROJECT_VERSION_2022);
+    builder
+}
+
+fn legacy_project() -> VirtualProjectBuilder {
+    let mut builder = VirtualProjectBuilder::new();
+    builder
+        .add_file("Assets/VRCSDK/Plugins/VRCSDK3A.dll", "")
+        .add_file("ProjectSettings/ProjectVersion.txt", PROJECT_VERSION_2022);
+    builder
+}
+
+#[test]
+fn missing_resolver() {
+    block_on(async {
+        let project = vpm_project().build().await.unwrap();
+        let collection = sdk_collection();
+
+        assert!(project.is_vpm_resolver_missing());
+        let result = project
+            .vpm_resolver_request(&collection)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let resolver = collection.get_package(RESOLVER, Version::new(0, 1, 29));
+        assert_eq!(result.package_changes().len(), 1);
+        assert_installing_to_both(&result, &resolver);
+    })
+}
+
+#[test]
+fn installed_resolver() {
+    block_on(async {
+        let project = vpm_project()
+            .add_dependency(RESOLVER, Version::new(0, 1, 29))
+            .add_locked(RESOLVER, Version::new(0, 1, 29), &[])
+            .build()
+            .await
+            .unwrap();
+
+        assert!(!project.is_vpm_resolver_missing());
+        let result = project.vpm_resolver_request(&sdk_collection()).await;
+        assert!(result.unwrap().is_none());
+    })
+}
+
+#[test]
+fn non_vpm_project() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("ProjectSettings/ProjectVersion.txt", PROJECT_VERSION_2022)
+            .build()
+            .await
+            .unwrap();
+
+        assert!(!project.is_vpm_resolver_missing());
+        let result = project.vpm_resolver_request(&sdk_collection()).await;
+        assert!(result.unwrap().is_none());
+    })
+}
+
+#[test]
+fn legacy_migration() {
+    block_on(async {
+        let project = legacy_project().build().await.unwrap();
+        let collection = sdk_collection();
+
+        let result = project
+            .migrate_legacy_sdk_request(&collection)
+            .await
+            .unwrap();
+
+        let resolver = collection.get_package(RESOLVER, Version::new(0, 1, 29));
+        assert_eq!(result.package_changes().len(), 3);
+        assert_installing_to_both(&result, &resolver);
+    })
+}
+
+#[test]
+fn opted_out_project() {
+    block_on(async {
+        let collection = sdk_collection();
+
+        let project = vpm_project()
+            .add_file(SETTINGS_PATH, r#"{"excludeVpmResolver": true}"#)
+            .build()
+            .await
+            .unwrap();
+        assert!(project.is_vpm_resolver_excluded());
+        assert!(!project.is_vpm_resolver_missing());
+        let result = project.vpm_resolver_request(&collection).await;
+        assert!(result.unwrap().is_none());
+
+        let project = legacy_project()
+            .add_file(SETTINGS_PATH, r#"{"excludeVpmResolver": true}"#)
+            .build()
+            .await
+            .unwrap();
+        let result = project
+            .migrate_legacy_sdk_request(&collection)
+            .await
+            .unwrap();
+        assert_eq!(result.package_changes().len(), 2);
+        assert!(!result.package_changes().contains_key(RESOLVER));
+    })
+}
+
+#[test]
+fn save_opt_out() {
+    block_on(async {
+        let mut project = vpm_project().build().await.unwrap();
+
+        project.set_vpm_resolver_excluded(true);
+        project.save().await.unwrap();
+
+        let settings = read_file(project.io(), SETTINGS_PATH).await;
+        let settings = serde_json::from_str::<serde_json::Value>(&settings).unwrap();
+        assert_eq!(settings["excludeVpmResolver"], true);
+
+        project.set_vpm_resolver_excluded(false);
+        project.save().await.unwrap();
+
+        let settings = read_file(project.io(), SETTINGS_PATH).await;
+        let settings = serde_json::from_str::<serde_json::Value>(&settings).unwrap();
+        assert_eq!(settings.get("excludeVpmResolver"), None);
+    })
+}
+
+#[cfg(feature = "experimental-project-management")]
+#[test]
+fn template_project() {
+    use vrc_get_vpm::UnityProject;
+    use vrc_get_vpm::environment::ProjectTemplate;
+
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        ProjectTemplate::Avatars
+            .instantiate(&VirtualFileSystem::new(), &fs, "Avatar Project")
+            .await
+            .unwrap();
+        let project = UnityProject::load(fs).await.unwrap();
+        let collection = sdk_collection();
+
+        assert!(project.is_vpm_resolver_missing());
+        let result = project
+            .vpm_resolver_request(&collection)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let resolver = collection.get_package(RESOLVER, Version::new(0, 1, 29));
+        assert_installing_to_both(&result, &resolver);
+    })
+}
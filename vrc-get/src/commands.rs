@@ -2,12 +2,11 @@ use clap::{Args, Parser, Subcommand};
 use indexmap::IndexMap;
 use itertools::Itertools;
 
-use futures::future::join_all;
+use futures::future::{BoxFuture, join_all, ready};
 use log::warn;
 use reqwest::Url;
 use reqwest::header::{HeaderName, HeaderValue, InvalidHeaderName, InvalidHeaderValue};
 use serde::Serialize;
-use std::collections::HashMap;
 use std::env;
 use std::error::Error as StdError;
 use std::ffi::OsStr;
@@ -18,17 +17,23 @@ use std::process::exit;
 use std::str::FromStr;
 use tokio::fs::read_to_string;
 use vrc_get_vpm::environment::{
-    AddRepositoryErr, AddUserPackageResult, PackageCollection, PackageInstaller, Settings,
-    UserPackageCollection, add_remote_repo, cleanup_repos_folder, clear_package_cache,
+    AddRepositoryErr, AddUserPackageResult, AutoBackupOptions, PackageCollection, PackageInstaller,
+    PackageListingOptions, Settings, UserPackageCollection, add_remote_repo,
+    apply_pending_changes_with_backup, cleanup_repos_folder, clear_package_cache,
+    update_package_index,
 };
 use vrc_get_vpm::io::{DefaultEnvironmentIo, DefaultProjectIo, IoTrait};
 use vrc_get_vpm::repositories_file::RepositoriesFile;
 use vrc_get_vpm::repository::RemoteRepository;
-use vrc_get_vpm::unity_project::pending_project_changes::{PackageChange, RemoveReason};
-use vrc_get_vpm::unity_project::{AddPackageOperation, PendingProjectChanges};
+use vrc_get_vpm::unity_project::pending_project_changes::{PackageChange, RemoveReason, UpmChange};
+use vrc_get_vpm::unity_project::{
+    AddPackageOperation, ChangesReport, ConfirmationHandler, ConfirmationPolicy,
+    PendingProjectChanges, ProjectLockGuard,
+};
 use vrc_get_vpm::version::Version;
 use vrc_get_vpm::{
-    PackageCollection as _, PackageInfo, PackageManifest, UserRepoSetting, VersionSelector,
+    AbortCheck, PackageCollection as _, PackageInfo, PackageManifest, UserRepoSetting,
+    VersionSelector,
 };
 
 type UnityProject = vrc_get_vpm::UnityProject<DefaultProjectIo>;
@@ -102,11 +107,26 @@ async fn load_collection(
         let removed = settings.remove_id_duplication();
         collection.remove_repositories(&removed, io).await;
         settings.save(io).await.exit_context("saving settings");
+        (collection.save(io).await).exit_context("saving repository caches");
+        if let Err(e) = update_package_index(&settings, &collection, io).await {
+            warn!("failed to update package index: {e}");
+        }
     }
 
     collection
 }
 
+/// Creates the installer with the installation options in the settings.
+async fn load_installer<'a>(
+    io: &'a DefaultEnvironmentIo,
+    http: Option<&'a reqwest::Client>,
+) -> PackageInstaller<'a, reqwest::Client, DefaultEnvironmentIo> {
+    let settings = Settings::load(io).await.exit_context("loading settings");
+    PackageInstaller::new(io, http)
+        .use_package_store(settings.use_package_store())
+        .use_hard_links(settings.use_hard_links())
+}
+
 async fn load_unity(path: Option<Box<Path>>) -> UnityProject {
     let io = match path {
         None => {
@@ -121,6 +141,15 @@ async fn load_unity(path: Option<Box<Path>>) -> UnityProject {
         .exit_context("loading unity project")
 }
 
+/// Locks the project until the guard is dropped, so other processes cannot modify the project
+/// between loading and saving.
+async fn lock_unity(unity: &UnityProject) -> ProjectLockGuard {
+    unity
+        .lock_project()
+        .await
+        .exit_categorized("locking unity project")
+}
+
 fn absolute_path(path: impl AsRef<Path>) -> PathBuf {
     fn impl_(path: &Path) -> PathBuf {
         if path.is_absolute() {
@@ -137,7 +166,10 @@ fn absolute_path(path: impl AsRef<Path>) -> PathBuf {
 
 #[cfg(feature = "experimental-vcc")]
 async fn update_project_last_modified(io: &DefaultEnvironmentIo, project_dir: &Path) {
-    async fn inner(io: &DefaultEnvironmentIo, project_dir: &Path) -> Result<(), std::io::Error> {
+    async fn inner(
+        io: &DefaultEnvironmentIo,
+        project_dir: &Path,
+    ) -> Result<(), vrc_get_vpm::Error> {
         let mut connection = vrc_get_vpm::environment::VccDatabaseConnection::connect(io).await?;
         let project_dir = absolute_path(project_dir);
         connection.update_project_last_modified(&project_dir.to_string_lossy())?;
@@ -153,6 +185,27 @@ async fn update_project_last_modified(io: &DefaultEnvironmentIo, project_dir: &P
 #[cfg(not(feature = "experimental-vcc"))]
 async fn update_project_last_modified(_: &DefaultEnvironmentIo, _: &Path) {}
 
+/// Updates the project type in the VCC database after migrating the project.
+#[cfg(feature = "experimental-vcc")]
+async fn update_project_type(io: &DefaultEnvironmentIo, project: &UnityProject) {
+    async fn inner(
+        io: &DefaultEnvironmentIo,
+        project: &UnityProject,
+    ) -> Result<(), vrc_get_vpm::Error> {
+        let mut connection = vrc_get_vpm::environment::VccDatabaseConnection::connect(io).await?;
+        connection.update_project_type(project).await?;
+        connection.save(io).await?;
+        Ok(())
+    }
+
+    if let Err(err) = inner(io, project).await {
+        eprintln!("error updating project type on vcc: {err}");
+    }
+}
+
+#[cfg(not(feature = "experimental-vcc"))]
+async fn update_project_type(_: &DefaultEnvironmentIo, _: &UnityProject) {}
+
 fn get_package<'env>(
     env: &'env PackageCollection,
     name: &str,
@@ -244,6 +297,40 @@ fn print_prompt_install(changes: &PendingProjectChanges) {
         }
     }
 
+    if !changes.upm_changes().is_empty() {
+        println!("You're changing Packages/manifest.json:");
+        for change in changes.upm_changes() {
+            match change {
+                UpmChange::SetDependency { name, version } => {
+                    println!("- set {} to version {}", name, version);
+                }
+                UpmChange::RemoveDependency { name } => {
+                    println!("- remove {}", name);
+                }
+                UpmChange::EnsureDependency { name, version } => {
+                    println!("- add {} version {} or later", name, version);
+                }
+                UpmChange::EnsureScopedRegistry { registry } => {
+                    println!(
+                        "- add scoped registry {} ({}) for {}",
+                        registry.name(),
+                        registry.url(),
+                        registry.scopes().iter().join(", ")
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if !changes.protected_legacy_assets().is_empty() {
+        println!("**Those legacy assets are protected and will NOT be removed**");
+        println!("Please remove them manually if needed.");
+        for (x, package) in changes.protected_legacy_assets() {
+            println!("- {} (legacy asset of {})", x.display(), package);
+        }
+    }
+
     if !removed.is_empty() {
         println!("You're removing the following packages:");
         removed.sort_by_key(|(reason, _)| *reason);
@@ -309,12 +396,104 @@ fn print_prompt_install(changes: &PendingProjectChanges) {
     }
 }
 
-fn prompt_install(yes: bool) {
-    if yes {
+/// The confirmation with the prompt on the terminal.
+///
+/// The changes should be printed with [`print_prompt_install`] before asking.
+struct PromptConfirmation;
+
+impl ConfirmationHandler for PromptConfirmation {
+    fn confirm<'a>(&'a self, _: &'a ChangesReport) -> BoxFuture<'a, bool> {
+        Box::pin(ready(confirm_prompt("Do you want to apply those changes?")))
+    }
+}
+
+/// The destructive changes the user explicitly asked for with the command.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Requested {
+    Nothing,
+    Removals,
+    Downgrades,
+}
+
+/// Returns the policy to apply the changes.
+///
+/// The user is asked if the changes are destructive in a way not `requested` or `require_prompt` is true,
+/// unless `--yes` is set.
+fn confirmation_policy(
+    changes: &PendingProjectChanges,
+    unity: &UnityProject,
+    yes: bool,
+    require_prompt: bool,
+    requested: Requested,
+) -> ConfirmationPolicy<'static> {
+    let classification = changes.classify(unity);
+    let unrequested = (classification.removals && requested != Requested::Removals)
+        || classification.legacy_deletions
+        || (classification.downgrades && requested != Requested::Downgrades);
+
+    if !require_prompt && !unrequested {
+        ConfirmationPolicy::AlwaysProceed
+    } else if yes {
         println!("--yes is set. skipping confirm");
-    } else if !confirm_prompt("Do you want to apply those changes?") {
+        ConfirmationPolicy::AlwaysProceed
+    } else {
+        ConfirmationPolicy::RequireCallback(&PromptConfirmation)
+    }
+}
+
+async fn apply_changes<'env>(
+    io: &DefaultEnvironmentIo,
+    unity: &mut UnityProject,
+    installer: &'env impl vrc_get_vpm::PackageInstaller,
+    changes: PendingProjectChanges<'env>,
+    policy: ConfirmationPolicy<'_>,
+    context: &str,
+) {
+    if !policy.allows(unity, &changes).await {
         exit(1);
     }
+    apply_with_backup(io, unity, installer, changes, context).await;
+}
+
+/// Applies the changes with backing up the project before destructive changes if enabled in the settings.
+async fn apply_with_backup<'env>(
+    io: &DefaultEnvironmentIo,
+    unity: &mut UnityProject,
+    installer: &'env impl vrc_get_vpm::PackageInstaller,
+    changes: PendingProjectChanges<'env>,
+    context: &str,
+) {
+    let settings = Settings::load(io).await.exit_context("loading settings");
+    let backup = apply_pending_changes_with_backup(
+        &settings,
+        io,
+        unity,
+        installer,
+        changes,
+        &AutoBackupOptions::new(),
+        &AbortCheck::new(),
+    )
+    .await
+    .exit_categorized(context);
+    if let Some(backup) = backup {
+        println!("the project is backed up to {}", backup.path().display());
+    }
+}
+
+async fn simulate_changes<'env>(
+    unity: &UnityProject,
+    installer: &'env impl vrc_get_vpm::PackageInstaller,
+    changes: PendingProjectChanges<'env>,
+) {
+    let operations = unity
+        .simulate_pending_changes(installer, changes)
+        .await
+        .exit_categorized("simulating changes");
+
+    println!("--simulate is set. the following operations would be done:");
+    for operation in operations {
+        println!("{operation}");
+    }
 }
 
 fn require_prompt_for_install(
@@ -325,6 +504,8 @@ fn require_prompt_for_install(
     // dangerous changes
     if !changes.remove_legacy_folders().is_empty()
         || !changes.remove_legacy_files().is_empty()
+        || !changes.protected_legacy_assets().is_empty()
+        || !changes.upm_changes().is_empty()
         || !changes.conflicts().is_empty()
     {
         return true;
@@ -359,6 +540,11 @@ trait ResultExt<T, E>: Sized {
     fn exit_context(self, context: &str) -> T
     where
         E: Display;
+
+    /// Same as `exit_context` but exits with the exit code for the category of the error.
+    fn exit_categorized(self, context: &str) -> T
+    where
+        E: Into<vrc_get_vpm::Error>;
 }
 
 impl<T, E> ResultExt<T, E> for Result<T, E> {
@@ -371,6 +557,20 @@ impl<T, E> ResultExt<T, E> for Result<T, E> {
             Err(err) => exit_with!("error {context}: {err}"),
         }
     }
+
+    fn exit_categorized(self, context: &str) -> T
+    where
+        E: Into<vrc_get_vpm::Error>,
+    {
+        match self {
+            Ok(value) => value,
+            Err(err) => {
+                let err = err.into();
+                eprintln!("error {context}: {err}");
+                exit(err.exit_code())
+            }
+        }
+    }
 }
 
 mod info;
@@ -464,6 +664,12 @@ pub struct Install {
     /// skip confirm
     #[arg(short, long)]
     yes: bool,
+    /// Show the file operations to be done without changing the project
+    #[arg(long)]
+    simulate: bool,
+    /// Modify the project even if it's open in Unity
+    #[arg(long)]
+    force: bool,
 }
 
 impl Install {
@@ -473,6 +679,10 @@ impl Install {
             return Resolve {
                 project: self.project,
                 env_args: self.env_args,
+                simulate: self.simulate,
+                add_vpm_resolver: false,
+                exclude_vpm_resolver: false,
+                force: self.force,
             }
             .run()
             .await;
@@ -481,8 +691,10 @@ impl Install {
         let client = crate::create_client(self.env_args.offline);
         let io = DefaultEnvironmentIo::new_default();
         let collection = load_collection(&io, client.as_ref(), self.env_args.no_update).await;
-        let installer = PackageInstaller::new(&io, client.as_ref());
+        let installer = load_installer(&io, client.as_ref()).await;
         let mut unity = load_unity(self.project).await;
+        unity.set_ignore_unity_lock(self.force);
+        let _lock = lock_unity(&unity).await;
 
         let version_selector = match self.version {
             None => VersionSelector::latest_for(unity.unity_version(), self.prerelease),
@@ -523,18 +735,31 @@ impl Install {
                 self.prerelease,
             )
             .await
-            .exit_context("collecting packages to be installed");
+            .exit_categorized("collecting packages to be installed");
 
         print_prompt_install(&changes);
 
-        if require_prompt_for_install(&changes, name.as_str(), None) {
-            prompt_install(self.yes);
+        if self.simulate {
+            return simulate_changes(&unity, &installer, changes).await;
         }
 
-        unity
-            .apply_pending_changes(&installer, changes)
-            .await
-            .exit_context("adding package");
+        let require_prompt = require_prompt_for_install(&changes, name.as_str(), None);
+        // installing the specified version is an explicit downgrade
+        let requested = match self.version {
+            Some(_) => Requested::Downgrades,
+            None => Requested::Nothing,
+        };
+        let policy = confirmation_policy(&changes, &unity, self.yes, require_prompt, requested);
+
+        apply_changes(
+            &io,
+            &mut unity,
+            &installer,
+            changes,
+            policy,
+            "adding package",
+        )
+        .await;
 
         update_project_last_modified(&io, unity.project_dir()).await;
     }
@@ -552,6 +777,18 @@ pub struct Resolve {
     project: Option<Box<Path>>,
     #[command(flatten)]
     env_args: EnvArgs,
+    /// Show the file operations to be done without changing the project
+    #[arg(long)]
+    simulate: bool,
+    /// Add com.vrchat.core.vpm-resolver if it's missing in the project
+    #[arg(long, conflicts_with = "exclude_vpm_resolver")]
+    add_vpm_resolver: bool,
+    /// Do not suggest adding com.vrchat.core.vpm-resolver to the project anymore
+    #[arg(long)]
+    exclude_vpm_resolver: bool,
+    /// Modify the project even if it's open in Unity
+    #[arg(long)]
+    force: bool,
 }
 
 impl Resolve {
@@ -560,20 +797,62 @@ impl Resolve {
         let io = DefaultEnvironmentIo::new_default();
         let collection = load_collection(&io, client.as_ref(), self.env_args.no_update).await;
         let mut unity = load_unity(self.project).await;
+        unity.set_ignore_unity_lock(self.force);
+        let _lock = lock_unity(&unity).await;
 
-        let installer = PackageInstaller::new(&io, client.as_ref());
+        let installer = load_installer(&io, client.as_ref()).await;
 
         let changes = unity
             .resolve_request(&collection)
             .await
-            .exit_context("collecting packages to be installed");
+            .exit_categorized("collecting packages to be installed");
 
         print_prompt_install(&changes);
 
-        unity
-            .apply_pending_changes(&installer, changes)
+        if self.simulate {
+            simulate_changes(&unity, &installer, changes).await;
+        } else {
+            apply_with_backup(&io, &mut unity, &installer, changes, "installing packages").await;
+        }
+
+        if self.exclude_vpm_resolver {
+            if self.simulate {
+                println!("--simulate is set. the resolver would be excluded from the project");
+                return;
+            }
+            unity.set_vpm_resolver_excluded(true);
+            unity.save().await.exit_context("saving project");
+            return;
+        }
+
+        if !self.add_vpm_resolver {
+            if unity.is_vpm_resolver_missing() {
+                warn!(
+                    "com.vrchat.core.vpm-resolver is not installed. \
+                    use --add-vpm-resolver to add it or --exclude-vpm-resolver to hide this warning"
+                );
+            }
+            return;
+        }
+
+        let Some(changes) = unity
+            .vpm_resolver_request(&collection)
             .await
-            .exit_context("installing packages");
+            .exit_categorized("collecting the resolver to be installed")
+        else {
+            println!("nothing to add: the resolver is installed, excluded, or not found");
+            return;
+        };
+
+        print_prompt_install(&changes);
+
+        if self.simulate {
+            return simulate_changes(&unity, &installer, changes).await;
+        }
+
+        apply_with_backup(&io, &mut unity, &installer, changes, "adding the resolver").await;
+
+        update_project_last_modified(&io, unity.project_dir()).await;
     }
 }
 
@@ -594,32 +873,52 @@ pub struct Remove {
     /// skip confirm
     #[arg(short, long)]
     yes: bool,
+    /// Show the file operations to be done without changing the project
+    #[arg(long)]
+    simulate: bool,
+    /// Modify the project even if it's open in Unity
+    #[arg(long)]
+    force: bool,
 }
 
 impl Remove {
     pub async fn run(self) {
         let io = DefaultEnvironmentIo::new_default();
         let mut unity = load_unity(self.project).await;
+        unity.set_ignore_unity_lock(self.force);
+        let _lock = lock_unity(&unity).await;
 
         let changes = unity
             .remove_request(&self.names.iter().map(String::as_ref).collect::<Vec<_>>())
             .await
-            .exit_context("collecting packages to be removed");
-        let installer = PackageInstaller::new(&io, None::<&reqwest::Client>);
+            .exit_categorized("collecting packages to be removed");
+        let installer = load_installer(&io, None).await;
 
         print_prompt_install(&changes);
 
-        let confirm =
-            changes.package_changes().len() >= self.names.len() || !changes.conflicts().is_empty();
-
-        if confirm {
-            prompt_install(self.yes);
+        if self.simulate {
+            return simulate_changes(&unity, &installer, changes).await;
         }
 
-        unity
-            .apply_pending_changes(&installer, changes)
-            .await
-            .exit_context("removing packages");
+        let require_prompt =
+            changes.package_changes().len() >= self.names.len() || !changes.conflicts().is_empty();
+        let policy = confirmation_policy(
+            &changes,
+            &unity,
+            self.yes,
+            require_prompt,
+            Requested::Removals,
+        );
+
+        apply_changes(
+            &io,
+            &mut unity,
+            &installer,
+            changes,
+            policy,
+            "removing packages",
+        )
+        .await;
 
         update_project_last_modified(&io, unity.project_dir()).await;
     }
@@ -642,6 +941,9 @@ pub struct Reinstall {
     /// skip confirm
     #[arg(short, long)]
     yes: bool,
+    /// Show the file operations to be done without changing the project
+    #[arg(long)]
+    simulate: bool,
 }
 
 impl Reinstall {
@@ -649,30 +951,43 @@ impl Reinstall {
         let io = DefaultEnvironmentIo::new_default();
         let client = crate::create_client(self.env_args.offline);
         let collection = load_collection(&io, client.as_ref(), self.env_args.no_update).await;
-        let installer = PackageInstaller::new(&io, client.as_ref());
+        let installer = load_installer(&io, client.as_ref()).await;
 
         let mut unity = load_unity(self.project).await;
+        let _lock = lock_unity(&unity).await;
 
         let names = self.names.iter().map(String::as_ref).collect::<Vec<_>>();
 
         let changes = unity
             .reinstall_request(&collection, &names)
             .await
-            .exit_context("collecting packages to be removed");
+            .exit_categorized("collecting packages to be removed");
 
         print_prompt_install(&changes);
 
-        let confirm =
-            changes.package_changes().len() >= self.names.len() || !changes.conflicts().is_empty();
-
-        if confirm {
-            prompt_install(self.yes);
+        if self.simulate {
+            return simulate_changes(&unity, &installer, changes).await;
         }
 
-        unity
-            .apply_pending_changes(&installer, changes)
-            .await
-            .exit_context("removing packages");
+        let require_prompt =
+            changes.package_changes().len() >= self.names.len() || !changes.conflicts().is_empty();
+        let policy = confirmation_policy(
+            &changes,
+            &unity,
+            self.yes,
+            require_prompt,
+            Requested::Nothing,
+        );
+
+        apply_changes(
+            &io,
+            &mut unity,
+            &installer,
+            changes,
+            policy,
+            "removing packages",
+        )
+        .await;
 
         update_project_last_modified(&io, unity.project_dir()).await;
     }
@@ -717,39 +1032,21 @@ impl Outdated {
         let collection = load_collection(&io, client.as_ref(), self.env_args.no_update).await;
         let unity = load_unity(self.project).await;
 
-        let mut outdated_packages = HashMap::new();
-
-        let selector = VersionSelector::latest_for(unity.unity_version(), self.prerelease);
-
-        for locked in unity.locked_packages() {
-            match collection.find_package_by_name(locked.name(), selector) {
-                None => log::error!("latest version for package {} not found.", locked.name()),
-                // if found version is newer: add to outdated
-                Some(pkg) if locked.version() < pkg.version() => {
-                    outdated_packages.insert(pkg.name(), (pkg, locked.version()));
-                }
-                Some(_) => (),
-            }
-        }
+        let report = unity.outdated_report(&collection, self.prerelease);
 
-        for locked in unity.all_packages() {
-            for (name, range) in locked.dependencies() {
-                if let Some((outdated, _)) = outdated_packages.get(name.as_ref()) {
-                    if !range.matches(outdated.version()) {
-                        outdated_packages.remove(name.as_ref());
-                    }
-                }
+        for package in report.packages() {
+            if package.source_unavailable {
+                log::error!("latest version for package {} not found.", package.name);
             }
         }
 
         match self.json_format.map(|x| x.get()).unwrap_or(0) {
             0 => {
-                for (name, (found, installed)) in &outdated_packages {
+                for package in report.outdated() {
+                    let found = package.upgradable.as_ref().unwrap();
                     println!(
                         "{}: installed: {}, found: {}",
-                        name,
-                        installed,
-                        &found.version()
+                        package.name, package.locked, found
                     );
                 }
             }
@@ -760,16 +1057,17 @@ impl Outdated {
                     installed_version: &'a Version,
                     newer_version: &'a Version,
                 }
-                let info = outdated_packages
-                    .into_iter()
-                    .map(|(package_name, (found, installed))| OutdatedInfo {
-                        package_name,
-                        installed_version: installed,
-                        newer_version: found.version(),
+                let info = report
+                    .outdated()
+                    .map(|package| OutdatedInfo {
+                        package_name: &package.name,
+                        installed_version: &package.locked,
+                        newer_version: package.upgradable.as_ref().unwrap(),
                     })
                     .collect::<Vec<_>>();
                 println!("{}", serde_json::to_string(&info).unwrap());
             }
+            2 => println!("{}", serde_json::to_string(&report).unwrap()),
             v => exit_with!("unsupported json version: {v}"),
         }
     }
@@ -791,6 +1089,9 @@ pub struct Upgrade {
     /// Include prerelease
     #[arg(long = "prerelease")]
     prerelease: bool,
+    /// Upgrade VRChat SDK even if it requires newer Unity than the project uses
+    #[arg(long)]
+    allow_incompatible_sdk: bool,
 
     /// Path to project dir. by default CWD or parents of CWD will be used
     #[arg(short = 'p', long = "project")]
@@ -801,6 +1102,12 @@ pub struct Upgrade {
     /// skip confirm
     #[arg(short, long)]
     yes: bool,
+    /// Show the file operations to be done without changing the project
+    #[arg(long)]
+    simulate: bool,
+    /// Modify the project even if it's open in Unity
+    #[arg(long)]
+    force: bool,
 }
 
 impl Upgrade {
@@ -808,8 +1115,11 @@ impl Upgrade {
         let io = DefaultEnvironmentIo::new_default();
         let client = crate::create_client(self.env_args.offline);
         let collection = load_collection(&io, client.as_ref(), self.env_args.no_update).await;
-        let installer = PackageInstaller::new(&io, client.as_ref());
+        let installer = load_installer(&io, client.as_ref()).await;
         let mut unity = load_unity(self.project).await;
+        unity.set_ignore_unity_lock(self.force);
+        let _lock = lock_unity(&unity).await;
+        unity.set_allow_incompatible_sdk(self.allow_incompatible_sdk);
 
         let updates = if let Some(name) = &self.name {
             let version_selector = match self.version {
@@ -820,6 +1130,8 @@ impl Upgrade {
 
             vec![package]
         } else {
+            // the resolver selects the versions, so upgrading a package can upgrade
+            // the packages held back by the dependency ranges of the old version
             let version_selector =
                 VersionSelector::latest_for(unity.unity_version(), self.prerelease);
 
@@ -837,19 +1149,27 @@ impl Upgrade {
                 self.prerelease,
             )
             .await
-            .exit_context("collecting packages to be upgraded");
+            .exit_categorized("collecting packages to be upgraded");
 
         print_prompt_install(&changes);
 
+        if self.simulate {
+            return simulate_changes(&unity, &installer, changes).await;
+        }
+
         let require_prompt = if let Some(name) = &self.name {
             require_prompt_for_install(&changes, name.as_str(), None)
         } else {
             true
         };
 
-        if require_prompt {
-            prompt_install(self.yes)
-        }
+        let policy = confirmation_policy(
+            &changes,
+            &unity,
+            self.yes,
+            require_prompt,
+            Requested::Nothing,
+        );
 
         let updates = (changes.package_changes().iter())
             .filter_map(|(_, x)| x.as_install())
@@ -857,10 +1177,15 @@ impl Upgrade {
             .map(|x| (x.name().to_owned(), x.version().clone()))
             .collect::<Vec<_>>();
 
-        unity
-            .apply_pending_changes(&installer, changes)
-            .await
-            .exit_context("upgrading packages");
+        apply_changes(
+            &io,
+            &mut unity,
+            &installer,
+            changes,
+            policy,
+            "upgrading packages",
+        )
+        .await;
 
         for (name, version) in updates {
             println!("upgraded {} to {}", name, version);
@@ -896,6 +1221,9 @@ pub struct Downgrade {
     /// skip confirm
     #[arg(short, long)]
     yes: bool,
+    /// Show the file operations to be done without changing the project
+    #[arg(long)]
+    simulate: bool,
 }
 
 impl Downgrade {
@@ -903,8 +1231,9 @@ impl Downgrade {
         let client = crate::create_client(self.env_args.offline);
         let io = DefaultEnvironmentIo::new_default();
         let collection = load_collection(&io, client.as_ref(), self.env_args.no_update).await;
-        let installer = PackageInstaller::new(&io, client.as_ref());
+        let installer = load_installer(&io, client.as_ref()).await;
         let mut unity = load_unity(self.project).await;
+        let _lock = lock_unity(&unity).await;
 
         let updates = [get_package(
             &collection,
@@ -920,24 +1249,38 @@ impl Downgrade {
                 self.prerelease,
             )
             .await
-            .exit_context("collecting packages to be upgraded");
+            .exit_categorized("collecting packages to be upgraded");
 
         print_prompt_install(&changes);
 
-        if require_prompt_for_install(&changes, self.name.as_str(), None) {
-            prompt_install(self.yes)
+        if self.simulate {
+            return simulate_changes(&unity, &installer, changes).await;
         }
 
+        let require_prompt = require_prompt_for_install(&changes, self.name.as_str(), None);
+        let policy = confirmation_policy(
+            &changes,
+            &unity,
+            self.yes,
+            require_prompt,
+            Requested::Downgrades,
+        );
+
         let downgrades = (changes.package_changes().iter())
             .filter_map(|(_, x)| x.as_install())
             .filter_map(|x| x.install_package())
             .map(|x| (x.name().to_owned(), x.version().clone()))
             .collect::<Vec<_>>();
 
-        unity
-            .apply_pending_changes(&installer, changes)
-            .await
-            .exit_context("upgrading packages");
+        apply_changes(
+            &io,
+            &mut unity,
+            &installer,
+            changes,
+            policy,
+            "downgrading packages",
+        )
+        .await;
 
         for (name, version) in downgrades {
             println!("downgraded {} to {}", name, version);
@@ -957,6 +1300,14 @@ pub struct Search {
     #[arg(required = true, name = "QUERY")]
     queries: Vec<String>,
 
+    /// Include deprecated packages
+    #[arg(long)]
+    include_deprecated: bool,
+
+    /// Only show packages by the author
+    #[arg(long)]
+    author: Option<String>,
+
     #[command(flatten)]
     env_args: EnvArgs,
 }
@@ -982,15 +1333,22 @@ impl Search {
             sources
         }
 
-        let found_packages =
-            collection.find_whole_all_packages(VersionSelector::latest_for(None, true), |pkg| {
+        let mut options = PackageListingOptions::new().include_deprecated(self.include_deprecated);
+        if let Some(author) = &self.author {
+            options = options.author(Some(author));
+        }
+        let found_packages = collection.find_whole_all_packages_with(
+            VersionSelector::latest_for(None, true),
+            &options,
+            |pkg| {
                 // filtering
                 let search_targets = search_targets(pkg);
 
                 queries
                     .iter()
                     .all(|query| search_targets.iter().any(|x| x.contains(query)))
-            });
+            },
+        );
 
         if found_packages.is_empty() {
             println!("No matching package found!")
@@ -1005,6 +1363,12 @@ impl Search {
                 if let Some(description) = x.package_json().description() {
                     println!("{}", description);
                 }
+                if x.is_deprecated() {
+                    match x.deprecation_message() {
+                        Some(message) => println!("deprecated: {message}"),
+                        None => println!("deprecated"),
+                    }
+                }
                 println!();
             }
         }
@@ -1262,6 +1626,10 @@ impl RepoRemove {
 #[derive(Parser)]
 #[command(author, version)]
 pub struct RepoCleanup {
+    /// Show the files would be removed without removing them
+    #[arg(long)]
+    dry_run: bool,
+
     #[command(flatten)]
     env_args: EnvArgs,
 }
@@ -1270,9 +1638,26 @@ impl RepoCleanup {
     pub async fn run(self) {
         let io = DefaultEnvironmentIo::new_default();
         let settings = Settings::load(&io).await.exit_context("loading settings");
-        cleanup_repos_folder(&settings, &io)
+        let result = cleanup_repos_folder(&settings, &io, self.dry_run)
             .await
             .exit_context("cleaning up Repos directory");
+
+        let verb = if self.dry_run {
+            "would remove"
+        } else {
+            "removed"
+        };
+        for file in result.removed() {
+            println!("{verb} {} ({} bytes)", file.path.display(), file.size);
+        }
+        for path in result.skipped() {
+            println!("skipped recently modified {}", path.display());
+        }
+        println!(
+            "{verb} {} files, {} bytes in total",
+            result.removed().len(),
+            result.total_size()
+        );
     }
 }
 
@@ -1304,11 +1689,17 @@ impl RepoPackages {
                     let mut versions = versions.all_versions().collect::<Vec<_>>();
                     versions.sort_by_key(|pkg| pkg.version());
                     for pkg in &versions {
-                        println!(
-                            "{}: {}",
-                            pkg.version(),
-                            pkg.url().map(Url::as_str).unwrap_or("<no url>")
-                        );
+                        let url = pkg.url().map(Url::as_str).unwrap_or("<no url>");
+                        if let Some(original) = pkg.original_version() {
+                            println!(
+                                "{}: {} (non-semver version '{}' in repository)",
+                                pkg.version(),
+                                url,
+                                original
+                            );
+                        } else {
+                            println!("{}: {}", pkg.version(), url);
+                        }
                     }
                     println!();
                 }
@@ -1403,7 +1794,7 @@ impl RepoImport {
             .await
             {
                 Ok(()) => {}
-                Err(AddRepositoryErr::AlreadyAdded) => {
+                Err(err) if matches!(err.downcast_ref(), Some(AddRepositoryErr::AlreadyAdded)) => {
                     warn!(
                         "{} is already added so skipping that repository",
                         repository.url()
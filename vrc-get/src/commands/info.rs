@@ -1,4 +1,4 @@
-use super::{UnityProject, load_collection};
+use super::{ResultExt, UnityProject, load_collection};
 use crate::commands::load_unity;
 use clap::{Parser, Subcommand};
 use itertools::Itertools;
@@ -7,6 +7,7 @@ use std::collections::HashSet;
 use std::num::NonZeroU32;
 use std::path::Path;
 use vrc_get_vpm::PackageCollection;
+use vrc_get_vpm::environment::quick_package_index;
 use vrc_get_vpm::io::DefaultEnvironmentIo;
 use vrc_get_vpm::version::{UnityVersion, Version, VersionRange};
 
@@ -16,9 +17,10 @@ use vrc_get_vpm::version::{UnityVersion, Version, VersionRange};
 pub enum Info {
     Project(Project),
     Package(Package),
+    PackageNames(PackageNames),
 }
 
-multi_command!(Info is Project, Package);
+multi_command!(Info is Project, Package, PackageNames);
 
 /// Show project information
 ///
@@ -91,6 +93,15 @@ impl Project {
                 );
             }
         }
+
+        if unity.is_vpm_resolver_missing() {
+            eprintln!();
+            eprintln!(
+                "com.vrchat.core.vpm-resolver is not installed. \
+                Run `vrc-get resolve --add-vpm-resolver` to add it \
+                or `vrc-get resolve --exclude-vpm-resolver` to hide this notice."
+            );
+        }
     }
 
     pub async fn version1(unity: &UnityProject) {
@@ -98,6 +109,7 @@ impl Project {
         struct Project<'a> {
             unity_version: Option<UnityVersion>,
             packages: &'a [PackageInfo<'a>],
+            vpm_resolver_missing: bool,
         }
 
         #[derive(Serialize)]
@@ -159,6 +171,7 @@ impl Project {
         let project = Project {
             unity_version: unity.unity_version(),
             packages: packages.as_slice(),
+            vpm_resolver_missing: unity.is_vpm_resolver_missing(),
         };
 
         println!("{}", serde_json::to_string(&project).unwrap());
@@ -223,3 +236,24 @@ impl Package {
         println!("{}", serde_json::to_string(&package_info).unwrap());
     }
 }
+
+/// List the names of all packages, one per line
+///
+/// This reads the package index instead of loading the repositories to be fast enough for
+/// shell completions, so the result may lag a refresh of the repositories by one invocation.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct PackageNames {}
+
+impl PackageNames {
+    pub async fn run(self) {
+        let io = DefaultEnvironmentIo::new_default();
+        let index = quick_package_index(&io)
+            .await
+            .exit_context("loading package index");
+
+        for package in index.packages() {
+            println!("{}", package.name);
+        }
+    }
+}
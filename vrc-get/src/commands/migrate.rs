@@ -1,13 +1,16 @@
 use crate::commands::{
-    EnvArgs, ResultExt, confirm_prompt, load_collection, load_unity, update_project_last_modified,
+    EnvArgs, Requested, ResultExt, apply_changes, confirm_prompt, confirmation_policy,
+    load_collection, load_installer, load_unity, lock_unity, print_prompt_install,
+    update_project_last_modified, update_project_type,
 };
 use clap::{Parser, Subcommand};
 use log::info;
 use std::path::{Path, PathBuf};
 use std::process::exit;
-use tokio::process::Command;
-use vrc_get_vpm::environment::PackageInstaller;
+use vrc_get_vpm::AbortCheck;
+use vrc_get_vpm::event::{Event, EventObserver};
 use vrc_get_vpm::io::DefaultEnvironmentIo;
+use vrc_get_vpm::unity::{UnityMigrationResult, run_unity_migration_with_path};
 
 /// Migrate Unity Project
 #[derive(Subcommand)]
@@ -15,9 +18,10 @@ use vrc_get_vpm::io::DefaultEnvironmentIo;
 pub enum Migrate {
     Unity2022(Unity2022),
     Vpm(Vpm),
+    LegacySdk(LegacySdk),
 }
 
-multi_command!(Migrate is Unity2022, Vpm);
+multi_command!(Migrate is Unity2022, Vpm, LegacySdk);
 
 /// Migrate your project to Unity 2022
 #[derive(Parser)]
@@ -35,6 +39,9 @@ pub struct Unity2022 {
     unity: Option<PathBuf>,
     #[command(flatten)]
     env_args: EnvArgs,
+    /// Modify the project even if it's open in Unity
+    #[arg(long)]
+    force: bool,
 }
 
 impl Unity2022 {
@@ -47,11 +54,13 @@ impl Unity2022 {
         }
 
         let mut project = load_unity(self.project).await;
+        project.set_ignore_unity_lock(self.force);
+        let lock = lock_unity(&project).await;
 
         let client = crate::create_client(self.env_args.offline);
         let io = DefaultEnvironmentIo::new_default();
         let collection = load_collection(&io, client.as_ref(), self.env_args.no_update).await;
-        let installer = PackageInstaller::new(&io, client.as_ref());
+        let installer = load_installer(&io, client.as_ref()).await;
 
         #[cfg(feature = "experimental-vcc")]
         let connection = vrc_get_vpm::environment::VccDatabaseConnection::connect(&io)
@@ -62,6 +71,8 @@ impl Unity2022 {
             .migrate_unity_2022(&collection, &installer)
             .await
             .exit_context("migrating unity project");
+        // Unity may take long to finalize the migration
+        drop(lock);
 
         info!(
             "Updating manifest file finished successfully. Launching Unity to finalize migration..."
@@ -95,19 +106,27 @@ impl Unity2022 {
             }
         };
 
-        let status = Command::new(&unity)
-            .args([
-                "-quit".as_ref(),
-                "-batchmode".as_ref(),
-                "-projectPath".as_ref(),
-                project.project_dir().as_os_str(),
-            ])
-            .status()
-            .await
-            .exit_context("launching unity to finalize migration");
-
-        if !status.success() {
-            exit_with!("Unity exited with status {}", status);
+        let result = run_unity_migration_with_path(
+            &unity,
+            project.project_dir(),
+            None,
+            &PrintUnityLog,
+            &AbortCheck::new(),
+        )
+        .await
+        .exit_categorized("launching unity to finalize migration");
+
+        match result {
+            UnityMigrationResult::Success => {}
+            UnityMigrationResult::CompileError => {
+                exit_with!("Unity reported compile errors. Please fix them in Unity.")
+            }
+            UnityMigrationResult::Crashed { .. } => exit_with!("Unity crashed."),
+            UnityMigrationResult::Failed { exit_code } => {
+                exit_with!("Unity exited with status {}", exit_code)
+            }
+            UnityMigrationResult::TimedOut => exit_with!("Unity timed out."),
+            _ => exit_with!("Unity failed to finalize migration."),
         }
 
         info!("Unity exited successfully. Migration finished.");
@@ -116,6 +135,17 @@ impl Unity2022 {
     }
 }
 
+/// Prints the log of Unity launched to finalize the migration.
+struct PrintUnityLog;
+
+impl EventObserver for PrintUnityLog {
+    fn on_event(&self, event: &Event<'_>) {
+        if let Event::UnityMigrationLog { line } = event {
+            println!("{line}")
+        }
+    }
+}
+
 /// Migrate your legacy (unitypackage) VRCSDK project to VPM project
 #[derive(Parser)]
 pub struct Vpm {
@@ -124,6 +154,9 @@ pub struct Vpm {
     project: Option<Box<Path>>,
     #[command(flatten)]
     env_args: EnvArgs,
+    /// Modify the project even if it's open in Unity
+    #[arg(long)]
+    force: bool,
 }
 
 impl Vpm {
@@ -136,11 +169,13 @@ impl Vpm {
         }
 
         let mut project = load_unity(self.project).await;
+        project.set_ignore_unity_lock(self.force);
+        let _lock = lock_unity(&project).await;
 
         let client = crate::create_client(self.env_args.offline);
         let io = DefaultEnvironmentIo::new_default();
         let collection = load_collection(&io, client.as_ref(), self.env_args.no_update).await;
-        let installer = PackageInstaller::new(&io, client.as_ref());
+        let installer = load_installer(&io, client.as_ref()).await;
 
         project
             .migrate_vpm(&collection, &installer, false)
@@ -150,5 +185,60 @@ impl Vpm {
         info!("Migration finished.");
 
         update_project_last_modified(&io, project.project_dir()).await;
+        update_project_type(&io, &project).await;
+    }
+}
+
+/// Migrate your legacy (unitypackage) VRCSDK project to VPM SDK packages
+///
+/// The legacy SDK folders are removed only if they match the GUIDs of the VPM SDK.
+#[derive(Parser)]
+pub struct LegacySdk {
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+    #[command(flatten)]
+    env_args: EnvArgs,
+    /// skip confirm
+    #[arg(short, long)]
+    yes: bool,
+    /// Modify the project even if it's open in Unity
+    #[arg(long)]
+    force: bool,
+}
+
+impl LegacySdk {
+    pub async fn run(self) {
+        let mut project = load_unity(self.project).await;
+        project.set_ignore_unity_lock(self.force);
+        let _lock = lock_unity(&project).await;
+
+        let client = crate::create_client(self.env_args.offline);
+        let io = DefaultEnvironmentIo::new_default();
+        let collection = load_collection(&io, client.as_ref(), self.env_args.no_update).await;
+        let installer = load_installer(&io, client.as_ref()).await;
+
+        let changes = project
+            .migrate_legacy_sdk_request(&collection)
+            .await
+            .exit_categorized("collecting changes to migrate the project");
+
+        print_prompt_install(&changes);
+
+        let policy = confirmation_policy(&changes, &project, self.yes, true, Requested::Nothing);
+        apply_changes(
+            &io,
+            &mut project,
+            &installer,
+            changes,
+            policy,
+            "migrating unity project",
+        )
+        .await;
+
+        info!("Migration finished.");
+
+        update_project_last_modified(&io, project.project_dir()).await;
+        update_project_type(&io, &project).await;
     }
 }
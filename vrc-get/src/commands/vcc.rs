@@ -6,7 +6,7 @@ use std::fmt::{Display, Formatter};
 use std::path::Path;
 use vrc_get_vpm::environment::{Settings, VccDatabaseConnection, find_unity_hub};
 use vrc_get_vpm::io::{DefaultEnvironmentIo, DefaultProjectIo};
-use vrc_get_vpm::{UnityProject, unity_hub};
+use vrc_get_vpm::{AbortCheck, UnityProject, unity_hub};
 
 /// Experimental VCC commands
 #[derive(Subcommand)]
@@ -341,8 +341,15 @@ impl UnityUpdate {
         let mut connection = VccDatabaseConnection::connect(&io)
             .await
             .exit_context("connecting to database");
+        let legacy = connection
+            .import_legacy_unity_settings(&settings, &io, false)
+            .await
+            .exit_context("importing legacy unity settings");
+        if let Some(version) = legacy.unity_version {
+            println!("imported Unity {version} from legacy VCC settings");
+        }
         connection
-            .update_unity_from_unity_hub_and_fs(&unity_list, &io)
+            .update_unity_from_unity_hub_and_fs(&unity_list, &io, &AbortCheck::new())
             .await
             .exit_context("updating unity from unity hub");
 